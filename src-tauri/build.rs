@@ -1,3 +1,69 @@
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// `YYYY-MM-DD` (UTC) for a Unix timestamp.
+fn civil_date(secs: u64) -> String {
+    // Howard Hinnant's days-to-civil algorithm.
+    let z = (secs / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Short commit hash; CI can pin it with `WM_GIT_COMMIT`.
+fn git_commit() -> String {
+    if let Ok(commit) = std::env::var("WM_GIT_COMMIT") {
+        return commit;
+    }
+    Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|commit| commit.trim().to_string())
+        .filter(|commit| !commit.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Build date, honoring `SOURCE_DATE_EPOCH` for reproducible builds.
+fn build_date() -> String {
+    let secs = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0)
+        });
+    civil_date(secs)
+}
+
 fn main() {
+    println!("cargo:rerun-if-env-changed=WM_GIT_COMMIT");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    let head = Path::new("../.git/HEAD");
+    if head.exists() {
+        println!("cargo:rerun-if-changed=../.git/HEAD");
+        if let Some(reference) = std::fs::read_to_string(head)
+            .ok()
+            .and_then(|contents| contents.strip_prefix("ref: ").map(|r| r.trim().to_string()))
+        {
+            if Path::new("../.git").join(&reference).exists() {
+                println!("cargo:rerun-if-changed=../.git/{reference}");
+            }
+        }
+    }
+    println!("cargo:rustc-env=WM_GIT_COMMIT={}", git_commit());
+    println!("cargo:rustc-env=WM_BUILD_DATE={}", build_date());
+
     tauri_build::build()
 }
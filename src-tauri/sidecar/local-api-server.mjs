@@ -105,6 +105,8 @@ const ALLOWED_ENV_KEYS = new Set([
   'VITE_OPENSKY_RELAY_URL', 'OPENSKY_CLIENT_ID', 'OPENSKY_CLIENT_SECRET',
   'AISSTREAM_API_KEY', 'VITE_WS_RELAY_URL', 'FINNHUB_API_KEY', 'NASA_FIRMS_API_KEY',
   'OLLAMA_API_URL', 'OLLAMA_MODEL', 'WORLDMONITOR_API_KEY', 'WTO_API_KEY',
+  'FAA_NOTAM_CLIENT_ID', 'FAA_NOTAM_CLIENT_SECRET', 'RELAY_SHARED_SECRET', 'MQTT_PASSWORD',
+  'ALERT_WEBHOOK_SECRET',
 ]);
 
 const CHROME_UA = 'Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36';
@@ -220,11 +222,15 @@ function appendVary(existing, token) {
   return parts.join(', ');
 }
 
+// On battery the desktop app switches to low-power mode, which skips Brotli:
+// its default quality costs far more CPU than gzip for a loopback response.
+let lowPowerMode = false;
+
 async function maybeCompressResponseBody(body, headers, acceptEncoding = '') {
   if (!canCompress(headers, body)) return body;
   headers['vary'] = appendVary(headers['vary'], 'Accept-Encoding');
 
-  if (acceptEncoding.includes('br')) {
+  if (acceptEncoding.includes('br') && !lowPowerMode) {
     headers['content-encoding'] = 'br';
     return brotliCompressAsync(body);
   }
@@ -409,6 +415,30 @@ function saveVerboseState() {
   try { writeFileSync(_verboseStatePath, JSON.stringify({ verboseMode })); } catch { /* ignore */ }
 }
 
+const LOG_LEVELS = ['error', 'warn', 'info', 'debug', 'trace'];
+let logLevel = 'info';
+
+function normalizeLogLevel(value) {
+  const level = String(value ?? '').trim().toLowerCase();
+  return LOG_LEVELS.includes(level) ? level : null;
+}
+
+function levelEnabled(level) {
+  return LOG_LEVELS.indexOf(level) <= LOG_LEVELS.indexOf(logLevel);
+}
+
+// Drop messages below the current level (`log` counts as info). The desktop
+// app sets the initial level via LOCAL_API_LOG_LEVEL and changes it at
+// runtime through /api/local-log-level.
+function createLeveledLogger(base) {
+  return {
+    error: (...args) => { if (levelEnabled('error')) base.error(...args); },
+    warn: (...args) => { if (levelEnabled('warn')) base.warn(...args); },
+    log: (...args) => { if (levelEnabled('info')) base.log(...args); },
+    debug: (...args) => { if (levelEnabled('debug')) (base.debug ?? base.log).apply(base, args); },
+  };
+}
+
 function recordTraffic(entry) {
   trafficLog.push(entry);
   if (trafficLog.length > TRAFFIC_LOG_MAX) trafficLog.shift();
@@ -462,7 +492,9 @@ function resolveConfig(options = {}) {
   const dataDir = String(options.dataDir ?? process.env.LOCAL_API_DATA_DIR ?? resourceDir);
   const mode = String(options.mode ?? process.env.LOCAL_API_MODE ?? 'desktop-sidecar');
   const cloudFallback = String(options.cloudFallback ?? process.env.LOCAL_API_CLOUD_FALLBACK ?? '') === 'true';
-  const logger = options.logger ?? console;
+  const logger = createLeveledLogger(options.logger ?? console);
+  const initialLogLevel = normalizeLogLevel(options.logLevel ?? process.env.LOCAL_API_LOG_LEVEL) ?? 'info';
+  const initialLowPower = String(options.lowPower ?? process.env.LOCAL_API_LOW_POWER ?? '') === 'true';
 
   return {
     port,
@@ -473,6 +505,8 @@ function resolveConfig(options = {}) {
     mode,
     cloudFallback,
     logger,
+    initialLogLevel,
+    initialLowPower,
   };
 }
 
@@ -944,6 +978,7 @@ async function dispatch(requestUrl, req, routes, context) {
       remoteBase: context.remoteBase,
       cloudFallback: context.cloudFallback,
       routes: routes.length,
+      lowPower: lowPowerMode,
     });
   }
   if (requestUrl.pathname === '/api/local-traffic-log') {
@@ -967,6 +1002,32 @@ async function dispatch(requestUrl, req, routes, context) {
     }
     return json({ verboseMode });
   }
+  if (requestUrl.pathname === '/api/local-log-level') {
+    if (req.method === 'POST') {
+      const body = await readBody(req);
+      let level = null;
+      try { level = normalizeLogLevel(JSON.parse(body?.toString() || '{}').level); } catch { /* invalid JSON */ }
+      if (!level) {
+        return json({ error: `level must be one of ${LOG_LEVELS.join(', ')}` }, 400);
+      }
+      logLevel = level;
+      context.logger.log(`[local-api] log level set to ${level}`);
+    }
+    return json({ level: logLevel });
+  }
+  if (requestUrl.pathname === '/api/local-power-mode') {
+    if (req.method === 'POST') {
+      const body = await readBody(req);
+      let lowPower = null;
+      try { lowPower = JSON.parse(body?.toString() || '{}').lowPower; } catch { /* invalid JSON */ }
+      if (typeof lowPower !== 'boolean') {
+        return json({ error: 'lowPower must be a boolean' }, 400);
+      }
+      lowPowerMode = lowPower;
+      context.logger.log(`[local-api] low-power mode ${lowPower ? 'ON' : 'OFF'}`);
+    }
+    return json({ lowPower: lowPowerMode });
+  }
   // Registration — call Convex directly (desktop frontend bypasses sidecar for this endpoint;
   // this handler only runs when CONVEX_URL is available, e.g. self-hosted deployments)
   if (requestUrl.pathname === '/api/register-interest' && req.method === 'POST') {
@@ -1155,6 +1216,8 @@ async function dispatch(requestUrl, req, routes, context) {
 export async function createLocalApiServer(options = {}) {
   const context = resolveConfig(options);
   loadVerboseState(context.dataDir);
+  logLevel = context.initialLogLevel;
+  lowPowerMode = context.initialLowPower;
   const routes = await buildRouteTable(context.apiDir);
 
   const server = createServer(async (req, res) => {
@@ -1170,6 +1233,8 @@ export async function createLocalApiServer(options = {}) {
     const skipRecord = req.method === 'OPTIONS'
       || requestUrl.pathname === '/api/local-traffic-log'
       || requestUrl.pathname === '/api/local-debug-toggle'
+      || requestUrl.pathname === '/api/local-log-level'
+      || requestUrl.pathname === '/api/local-power-mode'
       || requestUrl.pathname === '/api/local-env-update'
       || requestUrl.pathname === '/api/local-validate-secret';
 
@@ -713,6 +713,104 @@ test('rejects unknown key via /api/local-env-update', async () => {
   }
 });
 
+test('changes log level at runtime via /api/local-log-level', async () => {
+  const localApi = await setupApiDir({});
+  const logged = [];
+
+  const app = await createLocalApiServer({
+    port: 0,
+    apiDir: localApi.apiDir,
+    logLevel: 'info',
+    logger: { log(msg) { logged.push(msg); }, warn() {}, error() {} },
+  });
+  const { port } = await app.start();
+
+  try {
+    const rejected = await fetch(`http://127.0.0.1:${port}/api/local-log-level`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ level: 'loud' }),
+    });
+    assert.equal(rejected.status, 400);
+
+    logged.length = 0;
+    const response = await fetch(`http://127.0.0.1:${port}/api/local-log-level`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ level: 'ERROR' }),
+    });
+    assert.equal(response.status, 200);
+    assert.deepEqual(await response.json(), { level: 'error' });
+    // The confirmation is info-level, so it is already suppressed.
+    assert.deepEqual(logged, []);
+
+    await fetch(`http://127.0.0.1:${port}/api/local-log-level`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ level: 'debug' }),
+    });
+    assert.deepEqual(logged, ['[local-api] log level set to debug']);
+  } finally {
+    await app.close();
+    await localApi.cleanup();
+  }
+});
+
+test('skips Brotli in low-power mode set via /api/local-power-mode', async () => {
+  const localApi = await setupApiDir({
+    'compression-check.js': `
+      export default async function handler() {
+        const payload = { value: 'x'.repeat(3000) };
+        return new Response(JSON.stringify(payload), {
+          status: 200,
+          headers: { 'content-type': 'application/json' }
+        });
+      }
+    `,
+  });
+
+  const app = await createLocalApiServer({
+    port: 0,
+    apiDir: localApi.apiDir,
+    logger: { log() {}, warn() {}, error() {} },
+  });
+  const { port } = await app.start();
+
+  try {
+    const rejected = await fetch(`http://127.0.0.1:${port}/api/local-power-mode`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ lowPower: 'yes' }),
+    });
+    assert.equal(rejected.status, 400);
+
+    const response = await fetch(`http://127.0.0.1:${port}/api/local-power-mode`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ lowPower: true }),
+    });
+    assert.deepEqual(await response.json(), { lowPower: true });
+
+    const compressed = await fetch(`http://127.0.0.1:${port}/api/compression-check`, {
+      headers: { 'Accept-Encoding': 'gzip, br' },
+    });
+    assert.equal(compressed.headers.get('content-encoding'), 'gzip');
+    const body = JSON.parse(gunzipSync(Buffer.from(await compressed.arrayBuffer())).toString('utf8'));
+    assert.equal(body.value.length, 3000);
+
+    await fetch(`http://127.0.0.1:${port}/api/local-power-mode`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ lowPower: false }),
+    });
+    const status = await fetch(`http://127.0.0.1:${port}/api/local-status`);
+    assert.equal((await status.json()).lowPower, false);
+  } finally {
+    await app.close();
+    await localApi.cleanup();
+  }
+});
+
 test('validates OLLAMA_API_URL via /api/local-validate-secret (reachable endpoint)', async () => {
   // Stand up a mock Ollama server that responds to /v1/models
   const mockOllama = createServer((req, res) => {
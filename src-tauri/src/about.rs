@@ -0,0 +1,65 @@
+//! Build metadata for Help → About and `get_about_info`. The commit hash and
+//! build date are stamped by `build.rs`; the bundled Node.js runtime that
+//! hosts the local API sidecar is asked for its version on demand.
+
+use serde::Serialize;
+use tauri::menu::AboutMetadata;
+use tauri::{AppHandle, Webview};
+
+pub const GIT_COMMIT: &str = env!("WM_GIT_COMMIT");
+pub const BUILD_DATE: &str = env!("WM_BUILD_DATE");
+pub const LICENSE: &str = "AGPL-3.0-only";
+const COPYRIGHT: &str = "\u{00a9} 2024-2026 Elie Habib";
+const WEBSITE: &str = "https://worldmonitor.app";
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AboutInfo {
+    pub name: String,
+    pub version: &'static str,
+    pub git_commit: &'static str,
+    pub build_date: &'static str,
+    /// Version of the Node.js runtime the sidecar runs on; `None` if it
+    /// could not be found or run.
+    pub sidecar_node_version: Option<String>,
+    pub license: &'static str,
+    pub copyright: &'static str,
+    pub os: &'static str,
+    pub arch: &'static str,
+}
+
+/// Metadata for the native About dialog.
+pub fn metadata(app: &AppHandle) -> AboutMetadata<'static> {
+    AboutMetadata {
+        name: Some(app.package_info().name.clone()),
+        version: Some(format!("{} ({GIT_COMMIT})", env!("CARGO_PKG_VERSION"))),
+        short_version: Some(env!("CARGO_PKG_VERSION").into()),
+        comments: Some(format!("Built {BUILD_DATE}\nLicensed under {LICENSE}")),
+        copyright: Some(COPYRIGHT.into()),
+        license: Some(LICENSE.into()),
+        website: Some(WEBSITE.into()),
+        website_label: Some("worldmonitor.app".into()),
+        ..Default::default()
+    }
+}
+
+#[tauri::command]
+pub async fn get_about_info(webview: Webview, app: AppHandle) -> Result<AboutInfo, String> {
+    crate::metrics::of(&webview)
+        .observe_async("get_about_info", async move {
+            crate::require_trusted_window(webview.label())?;
+            Ok(AboutInfo {
+                name: app.package_info().name.clone(),
+                version: env!("CARGO_PKG_VERSION"),
+                git_commit: GIT_COMMIT,
+                build_date: BUILD_DATE,
+                sidecar_node_version: crate::resolve_node_binary(&app)
+                    .as_deref()
+                    .and_then(crate::diagnostics::node_version),
+                license: LICENSE,
+                copyright: COPYRIGHT,
+                os: std::env::consts::OS,
+                arch: std::env::consts::ARCH,
+            })
+        })
+        .await
+}
@@ -0,0 +1,248 @@
+//! The OS accent color, so the dashboard's highlights can match the desktop.
+//! A background thread re-reads it and broadcasts `app://accent-color` when
+//! it changes. Sources, all best effort:
+//!
+//! - Windows: `UISettings`' accent color.
+//! - macOS: the `AppleAccentColor` preference, mapped to its named color
+//!   (Multicolor reads as blue).
+//! - Linux: KDE's `AccentColor` (or selection color) from `kdeglobals` under
+//!   Plasma, otherwise GNOME's named `accent-color` (GNOME 47 and later).
+//!
+//! `accent` is null where none of these is available.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+pub const CHANGED_EVENT: &str = "app://accent-color";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct AccentColor {
+    /// `#rrggbb`.
+    pub accent: Option<String>,
+}
+
+#[derive(Default)]
+pub struct AccentColorState {
+    current: Mutex<Option<AccentColor>>,
+}
+
+fn hex(r: u8, g: u8, b: u8) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+/// `AppleAccentColor` from `defaults read -g`; `None` is Multicolor.
+#[cfg_attr(not(any(target_os = "macos", test)), allow(dead_code))]
+fn macos_accent(value: Option<&str>) -> Option<String> {
+    let color = match value.map(str::trim) {
+        None => "#007aff",
+        Some("-1") => "#989898",
+        Some("0") => "#e0383e",
+        Some("1") => "#f7821b",
+        Some("2") => "#fcb827",
+        Some("3") => "#62ba46",
+        Some("4") => "#007aff",
+        Some("5") => "#953d96",
+        Some("6") => "#f74f9e",
+        Some(_) => return None,
+    };
+    Some(color.to_string())
+}
+
+/// GNOME's `accent-color` name (as printed by `gsettings`, quoted) to
+/// libadwaita's color for it.
+#[cfg_attr(not(any(target_os = "linux", test)), allow(dead_code))]
+fn gnome_accent(value: &str) -> Option<String> {
+    let color = match value.trim().trim_matches('\'') {
+        "blue" => "#3584e4",
+        "teal" => "#2190a4",
+        "green" => "#3a944a",
+        "yellow" => "#c88800",
+        "orange" => "#ed5b00",
+        "red" => "#e62d42",
+        "pink" => "#d56199",
+        "purple" => "#9141ac",
+        "slate" => "#6f8396",
+        _ => return None,
+    };
+    Some(color.to_string())
+}
+
+/// `AccentColor` in `[General]`, else the selection background, from a
+/// `kdeglobals` file. Colors are `r,g,b`.
+#[cfg_attr(not(any(target_os = "linux", test)), allow(dead_code))]
+fn kde_accent(kdeglobals: &str) -> Option<String> {
+    let mut section = "";
+    let mut accent = None;
+    let mut selection = None;
+    for line in kdeglobals.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name;
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match (section, key.trim()) {
+            ("General", "AccentColor") => accent = Some(value),
+            ("Colors:Selection", "BackgroundNormal") => selection = Some(value),
+            _ => {}
+        }
+    }
+    let rgb: Vec<u8> = accent.or(selection)?.split(',').map(|c| c.trim().parse().ok()).collect::<Option<_>>()?;
+    match rgb[..] {
+        [r, g, b] | [r, g, b, _] => Some(hex(r, g, b)),
+        _ => None,
+    }
+}
+
+fn read() -> AccentColor {
+    AccentColor {
+        accent: platform::read(),
+    }
+}
+
+/// The current accent color, as last read by the monitor thread.
+pub fn current(app: &AppHandle) -> AccentColor {
+    app.try_state::<AccentColorState>()
+        .and_then(|state| state.current.lock().unwrap_or_else(|e| e.into_inner()).clone())
+        .unwrap_or_else(read)
+}
+
+fn run(app: AppHandle) {
+    let state = app.state::<AccentColorState>();
+    loop {
+        let color = read();
+        let previous = state
+            .current
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .replace(color.clone());
+        if previous.is_some_and(|previous| previous != color) {
+            tracing::info!(target: "app", accent = color.accent.as_deref().unwrap_or("none"), "accent color changed");
+            let _ = app.emit(CHANGED_EVENT, color);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Start the accent color monitor thread.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    if let Err(e) = std::thread::Builder::new()
+        .name("accent-color-monitor".into())
+        .spawn(move || run(app))
+    {
+        tracing::warn!(target: "app", "failed to start accent color monitor: {e}");
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use windows::UI::ViewManagement::{UIColorType, UISettings};
+
+    pub fn read() -> Option<String> {
+        let color = UISettings::new().ok()?.GetColorValue(UIColorType::Accent).ok()?;
+        Some(super::hex(color.R, color.G, color.B))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::process::Command;
+
+    pub fn read() -> Option<String> {
+        let output = Command::new("defaults")
+            .args(["read", "-g", "AppleAccentColor"])
+            .output()
+            .ok()?;
+        // The key is absent for Multicolor, the default.
+        let value = output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned());
+        super::macos_accent(value.as_deref())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    fn kdeglobals() -> Option<PathBuf> {
+        let config = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(config.join("kdeglobals"))
+    }
+
+    fn gnome() -> Option<String> {
+        let output = Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.interface", "accent-color"])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        super::gnome_accent(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    fn kde() -> Option<String> {
+        super::kde_accent(&std::fs::read_to_string(kdeglobals()?).ok()?)
+    }
+
+    pub fn read() -> Option<String> {
+        let plasma = std::env::var("XDG_CURRENT_DESKTOP").is_ok_and(|desktop| desktop.contains("KDE"));
+        if plasma {
+            kde().or_else(gnome)
+        } else {
+            gnome().or_else(kde)
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    pub fn read() -> Option<String> {
+        None
+    }
+}
+
+#[tauri::command]
+pub fn get_accent_color(webview: Webview, app: AppHandle) -> Result<AccentColor, String> {
+    crate::metrics::of(&webview).observe("get_accent_color", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(current(&app))
+    })
+}
+
+#[cfg(test)]
+mod accent_color_tests {
+    use super::*;
+
+    #[test]
+    fn maps_macos_accent_colors() {
+        assert_eq!(macos_accent(None).as_deref(), Some("#007aff"));
+        assert_eq!(macos_accent(Some("3\n")).as_deref(), Some("#62ba46"));
+        assert_eq!(macos_accent(Some("-1")).as_deref(), Some("#989898"));
+        assert_eq!(macos_accent(Some("9")), None);
+    }
+
+    #[test]
+    fn maps_gnome_accent_names() {
+        assert_eq!(gnome_accent("'purple'\n").as_deref(), Some("#9141ac"));
+        assert_eq!(gnome_accent("'unknown'"), None);
+    }
+
+    #[test]
+    fn reads_kdeglobals() {
+        let accent = "[General]\nAccentColor=61,174,233\n\n[Colors:Selection]\nBackgroundNormal=1,2,3\n";
+        assert_eq!(kde_accent(accent).as_deref(), Some("#3daee9"));
+        let selection = "[Colors:Selection]\nBackgroundNormal=61,174,233,255\n";
+        assert_eq!(kde_accent(selection).as_deref(), Some("#3daee9"));
+        assert_eq!(kde_accent("[General]\nAccentColor=oops\n"), None);
+        assert_eq!(kde_accent(""), None);
+    }
+}
@@ -0,0 +1,374 @@
+//! ACLED conflict events synced into the local `store`, so the
+//! conflict panel works offline. With `ACLED_ACCESS_TOKEN` stored, events are
+//! synced at startup and then hourly, or right away with
+//! `sync_acled_events`.
+//!
+//! Syncs are incremental by event date: the first covers the last
+//! `INITIAL_DAYS`, later ones start `OVERLAP_DAYS` before the newest event
+//! date already stored, since ACLED keeps adding events for past days (and
+//! at most `RETENTION_DAYS` back after a long time offline). Pages
+//! of `PAGE_SIZE` are requested one after another; a 429 waits as long as
+//! ACLED asks before retrying the same page. The cursor only advances once a
+//! sync completes, so an interrupted one starts over from the same date.
+//! Events older than `RETENTION_DAYS` are pruned.
+//!
+//! `query_acled_events` reads the store, filtered by bounding box, date
+//! range and event type, returning events shaped like the conflict
+//! service's `AcledConflictEvent`.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+use tokio::sync::watch;
+
+use crate::store::{with_store, EventQuery, StoredEvent};
+
+pub const SYNCED_EVENT: &str = "acled://synced";
+
+const SOURCE: &str = "acled";
+const API_URL: &str = "https://acleddata.com/api/acled/read";
+const TOKEN_SECRET: &str = "ACLED_ACCESS_TOKEN";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+const SYNC_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Pause between pages, to stay well inside ACLED's rate limit.
+const PAGE_DELAY: Duration = Duration::from_secs(1);
+const PAGE_SIZE: usize = 5_000;
+const MAX_PAGES: u32 = 100;
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+const INITIAL_DAYS: u64 = 30;
+const OVERLAP_DAYS: u64 = 7;
+const RETENTION_DAYS: u64 = 365;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct AcledSyncStatus {
+    pub syncing: bool,
+    /// Unix seconds of the last completed sync.
+    pub last_sync: Option<u64>,
+    /// Newest event date synced, `YYYY-MM-DD`.
+    pub cursor: Option<String>,
+    pub stored_events: usize,
+    /// New events in the last completed sync.
+    pub added: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct AcledState {
+    /// Bumped to request a sync now.
+    trigger: Mutex<Option<watch::Sender<u64>>>,
+    status: Mutex<AcledSyncStatus>,
+}
+
+fn update_status(app: &AppHandle, f: impl FnOnce(&mut AcledSyncStatus)) -> AcledSyncStatus {
+    let state = app.state::<AcledState>();
+    let mut status = state.status.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut status);
+    status.clone()
+}
+
+/// The fields of ACLED's `read` response used here; all strings.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct RawEvent {
+    event_id_cnty: String,
+    event_date: String,
+    event_type: String,
+    country: String,
+    admin1: String,
+    latitude: String,
+    longitude: String,
+    fatalities: String,
+    actor1: String,
+    actor2: String,
+    source: String,
+}
+
+#[derive(Deserialize)]
+struct Page {
+    #[serde(default)]
+    data: Vec<RawEvent>,
+    message: Option<String>,
+    error: Option<Value>,
+}
+
+/// A raw event as stored, or `None` without an id, a date or a valid position.
+fn to_stored(raw: RawEvent) -> Option<StoredEvent> {
+    let date = NaiveDate::parse_from_str(&raw.event_date, "%Y-%m-%d").ok()?;
+    let lat: f64 = raw.latitude.trim().parse().ok()?;
+    let lon: f64 = raw.longitude.trim().parse().ok()?;
+    if raw.event_id_cnty.is_empty() || !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return None;
+    }
+    let occurred_at = date.and_hms_opt(0, 0, 0)?.and_utc().timestamp_millis();
+    let actors: Vec<&str> = [raw.actor1.as_str(), raw.actor2.as_str()].into_iter().filter(|a| !a.is_empty()).collect();
+    let id = format!("acled-{}", raw.event_id_cnty);
+    Some(StoredEvent {
+        data: json!({
+            "id": id,
+            "eventType": raw.event_type,
+            "country": raw.country,
+            "location": { "latitude": lat, "longitude": lon },
+            "occurredAt": occurred_at,
+            "fatalities": raw.fatalities.trim().parse::<u64>().unwrap_or(0),
+            "actors": actors,
+            "source": raw.source,
+            "admin1": raw.admin1,
+        }),
+        id,
+        event_date: raw.event_date,
+        event_type: raw.event_type,
+        lat,
+        lon,
+    })
+}
+
+/// First event date to request, given the stored cursor.
+fn sync_start(cursor: Option<&str>, today: NaiveDate) -> NaiveDate {
+    let oldest = today - Days::new(RETENTION_DAYS);
+    cursor
+        .and_then(|c| NaiveDate::parse_from_str(c, "%Y-%m-%d").ok())
+        .map(|c| (c - Days::new(OVERLAP_DAYS)).clamp(oldest, today))
+        .unwrap_or(today - Days::new(INITIAL_DAYS))
+}
+
+/// One page, retried on 429s.
+async fn fetch_page(client: &reqwest::Client, token: &str, start: &str, end: &str, page: u32) -> Result<Vec<RawEvent>, String> {
+    let mut retries = 0;
+    loop {
+        let response = client
+            .get(API_URL)
+            .bearer_auth(token)
+            .header("Accept", "application/json")
+            .query(&[
+                ("event_date", format!("{start}|{end}")),
+                ("event_date_where", "BETWEEN".to_string()),
+                ("limit", PAGE_SIZE.to_string()),
+                ("page", page.to_string()),
+                ("_format", "json".to_string()),
+            ])
+            .send()
+            .await
+            .map_err(|e| format!("request failed: {e}"))?;
+        match response.status() {
+            reqwest::StatusCode::TOO_MANY_REQUESTS if retries < MAX_RATE_LIMIT_RETRIES => {
+                let wait = response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok()?.trim().parse().ok())
+                    .map(Duration::from_secs)
+                    .unwrap_or(DEFAULT_RETRY_AFTER);
+                tracing::warn!(target: "app", page, wait_secs = wait.as_secs(), "ACLED rate limited");
+                tokio::time::sleep(wait).await;
+                retries += 1;
+                continue;
+            }
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+                return Err("ACLED rejected the access token".to_string());
+            }
+            status if !status.is_success() => return Err(format!("HTTP {status}")),
+            _ => {}
+        }
+        let body: Page = response.json().await.map_err(|e| format!("invalid response: {e}"))?;
+        if let Some(error) = body.error.filter(|e| !e.is_null()).map(|e| e.to_string()).or(body.message) {
+            return Err(format!("ACLED: {error}"));
+        }
+        return Ok(body.data);
+    }
+}
+
+/// One complete sync. Returns how many events were new.
+async fn sync(app: &AppHandle, client: &reqwest::Client, token: &str) -> Result<usize, String> {
+    let cursor = with_store(app, |store| store.cursor(SOURCE)).await?;
+    let today = Utc::now().date_naive();
+    let start = sync_start(cursor.as_deref(), today).format("%Y-%m-%d").to_string();
+    let end = today.format("%Y-%m-%d").to_string();
+    tracing::info!(target: "app", %start, %end, "syncing ACLED events");
+
+    let mut added = 0;
+    let mut newest = cursor;
+    for page in 1..=MAX_PAGES {
+        let raw = fetch_page(client, token, &start, &end, page).await?;
+        let full = raw.len() >= PAGE_SIZE;
+        let events: Vec<StoredEvent> = raw.into_iter().filter_map(to_stored).collect();
+        if let Some(date) = events.iter().map(|e| &e.event_date).max() {
+            if newest.as_ref().is_none_or(|n| date > n) {
+                newest = Some(date.clone());
+            }
+        }
+        added += with_store(app, move |store| store.upsert(SOURCE, &events)).await?.len();
+        if !full {
+            break;
+        }
+        tokio::time::sleep(PAGE_DELAY).await;
+    }
+
+    let cutoff = (today - Days::new(RETENTION_DAYS)).format("%Y-%m-%d").to_string();
+    let (newest, stored) = with_store(app, move |store| {
+        if let Some(newest) = &newest {
+            store.set_cursor(SOURCE, newest)?;
+        }
+        store.prune(SOURCE, &cutoff)?;
+        Ok((newest, store.count(SOURCE)?))
+    })
+    .await?;
+    update_status(app, |status| {
+        status.cursor = newest;
+        status.stored_events = stored;
+    });
+    Ok(added)
+}
+
+struct Poller {
+    app: AppHandle,
+    client: reqwest::Client,
+}
+
+impl crate::poll::Poller for Poller {
+    async fn round(&mut self) -> Duration {
+        let app = &self.app;
+        match app.state::<crate::SecretsCache>().get(TOKEN_SECRET) {
+            Some(token) => {
+                update_status(app, |status| status.syncing = true);
+                let result = sync(app, &self.client, &token).await;
+                let status = update_status(app, |status| {
+                    status.syncing = false;
+                    match result {
+                        Ok(added) => {
+                            status.last_sync = Some(crate::unix_timestamp_secs());
+                            status.added = added;
+                            status.error = None;
+                        }
+                        Err(err) => status.error = Some(err),
+                    }
+                });
+                match &status.error {
+                    Some(err) => tracing::warn!(target: "app", "ACLED sync failed: {err}"),
+                    None => tracing::info!(target: "app", added = status.added, stored = status.stored_events, "ACLED sync complete"),
+                }
+                let _ = app.emit(SYNCED_EVENT, status);
+            }
+            None => {
+                update_status(app, |status| status.error = Some(format!("{TOKEN_SECRET} is not set")));
+            }
+        }
+        SYNC_INTERVAL
+    }
+}
+
+async fn run(app: AppHandle, trigger: watch::Receiver<u64>) {
+    let client = match reqwest::Client::builder().use_native_tls().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!(target: "app", "ACLED sync unavailable: {err}");
+            return;
+        }
+    };
+    if let Ok((cursor, stored)) = with_store(&app, |store| Ok((store.cursor(SOURCE)?, store.count(SOURCE)?))).await {
+        update_status(&app, |status| {
+            status.cursor = cursor;
+            status.stored_events = stored;
+        });
+    }
+    let poller = Poller {
+        app: app.clone(),
+        client,
+    };
+    crate::poll::run_triggered(&app, trigger, poller).await;
+}
+
+/// Start the sync task; it syncs right away if a token is stored.
+pub fn start(app: &AppHandle) {
+    let (sender, receiver) = watch::channel(0);
+    *app.state::<AcledState>().trigger.lock().unwrap_or_else(|e| e.into_inner()) = Some(sender);
+    tauri::async_runtime::spawn(run(app.clone(), receiver));
+}
+
+/// Sync now, e.g. right after the token was added. Returns at once.
+#[tauri::command]
+pub fn sync_acled_events(webview: Webview, state: tauri::State<'_, AcledState>) -> Result<AcledSyncStatus, String> {
+    crate::metrics::of(&webview).observe("sync_acled_events", || {
+        crate::require_trusted_window(webview.label())?;
+        let status = state.status.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        if !status.syncing {
+            if let Some(trigger) = state.trigger.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+                trigger.send_modify(|n| *n += 1);
+            }
+        }
+        Ok(status)
+    })
+}
+
+#[tauri::command]
+pub fn get_acled_sync_status(webview: Webview, state: tauri::State<'_, AcledState>) -> Result<AcledSyncStatus, String> {
+    crate::metrics::of(&webview).observe("get_acled_sync_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.status.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+/// Stored ACLED events, newest first.
+#[tauri::command]
+pub async fn query_acled_events(webview: Webview, app: AppHandle, query: Option<EventQuery>) -> Result<Vec<Value>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("query_acled_events", async move {
+            crate::require_trusted_window(webview.label())?;
+            let query = query.unwrap_or_default();
+            with_store(&app, move |store| store.query(SOURCE, &query)).await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod acled_tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn maps_raw_events_to_the_conflict_shape() {
+        let raw: RawEvent = serde_json::from_value(json!({
+            "event_id_cnty": "SDN12345",
+            "event_date": "2026-03-04",
+            "event_type": "Battles",
+            "country": "Sudan",
+            "admin1": "Khartoum",
+            "latitude": "15.5007",
+            "longitude": "32.5599",
+            "fatalities": "3",
+            "actor1": "RSF",
+            "actor2": "",
+            "source": "Radio Dabanga",
+            "notes": "ignored",
+        }))
+        .unwrap();
+        let event = to_stored(raw).unwrap();
+        assert_eq!((event.id.as_str(), event.event_date.as_str()), ("acled-SDN12345", "2026-03-04"));
+        assert_eq!(event.data["location"], json!({ "latitude": 15.5007, "longitude": 32.5599 }));
+        assert_eq!(event.data["occurredAt"], date("2026-03-04").and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp_millis());
+        assert_eq!(event.data["fatalities"], 3);
+        assert_eq!(event.data["actors"], json!(["RSF"]));
+
+        let missing_position = RawEvent { event_id_cnty: "X1".to_string(), event_date: "2026-03-04".to_string(), ..RawEvent::default() };
+        assert!(to_stored(missing_position).is_none());
+    }
+
+    #[test]
+    fn resumes_before_the_cursor() {
+        let today = date("2026-03-31");
+        assert_eq!(sync_start(None, today), date("2026-03-01"));
+        assert_eq!(sync_start(Some("2026-03-30"), today), date("2026-03-23"));
+        // After a long time offline the gap is filled, back to the retention limit.
+        assert_eq!(sync_start(Some("2026-01-10"), today), date("2026-01-03"));
+        assert_eq!(sync_start(Some("2024-01-01"), today), date("2025-03-31"));
+        assert_eq!(sync_start(Some("garbage"), today), date("2026-03-01"));
+    }
+}
@@ -0,0 +1,593 @@
+//! A local ADS-B receiver (dump1090, readsb, or a Wingbits feeder running
+//! one) as a flight source. With `adsb.enabled` set, the app connects to its
+//! TCP output at `adsb.host` and `adsb.port` and decodes it here:
+//!
+//! - `sbs`: BaseStation CSV (port 30003), already decoded by the receiver.
+//! - `beast`: raw Mode S frames (port 30005). Extended squitters (DF17/18)
+//!   give identification, airborne position (globally CPR-decoded from an
+//!   even/odd pair) and velocity; everything else is skipped.
+//!
+//! Aircraft seen in the last minute with a position are published every two
+//! seconds as OpenSky state vectors through `opensky`, so they reach the
+//! dashboard in the same snapshot and diffs as network sources. Dropped
+//! connections are retried every 10 seconds, and `set_adsb_receiver`
+//! applies a new configuration right away.
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Webview};
+use tokio::io::AsyncReadExt;
+use tokio::net::TcpStream;
+use tokio::sync::watch;
+
+use crate::config::{AdsbConfig, AdsbProtocol, DesktopConfigState};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const RETRY_INTERVAL: Duration = Duration::from_secs(10);
+const PUBLISH_INTERVAL: Duration = Duration::from_secs(2);
+/// Aircraft not heard from for this long leave the published set.
+const STALE_SECS: u64 = 60;
+/// Even and odd CPR frames further apart than this do not pair.
+const CPR_MAX_GAP_SECS: u64 = 10;
+/// Longest SBS line or undecoded Beast buffer kept while waiting for more.
+const MAX_BUFFER: usize = 64 * 1024;
+
+const FEET_TO_METERS: f64 = 0.3048;
+const KNOTS_TO_MPS: f64 = 0.514_444;
+const FPM_TO_MPS: f64 = 0.005_08;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct AdsbStatus {
+    pub enabled: bool,
+    pub connected: bool,
+    /// Aircraft currently published.
+    pub aircraft: usize,
+    /// Frames or lines decoded since connecting.
+    pub messages: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct AdsbState {
+    config: Mutex<Option<watch::Sender<AdsbConfig>>>,
+    status: Mutex<AdsbStatus>,
+}
+
+fn update_status(app: &AppHandle, f: impl FnOnce(&mut AdsbStatus)) {
+    let state = app.state::<AdsbState>();
+    f(&mut state.status.lock().unwrap_or_else(|e| e.into_inner()));
+}
+
+/// One CPR-encoded airborne position.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Cpr {
+    lat: u32,
+    lon: u32,
+    at: u64,
+}
+
+/// What one decoded message says about one aircraft.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Update {
+    icao: u32,
+    callsign: Option<String>,
+    altitude_ft: Option<f64>,
+    ground_speed_kt: Option<f64>,
+    track: Option<f64>,
+    vertical_rate_fpm: Option<f64>,
+    squawk: Option<String>,
+    on_ground: Option<bool>,
+    position: Option<(f64, f64)>,
+    /// `(odd, lat, lon)`.
+    cpr: Option<(bool, u32, u32)>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Aircraft {
+    callsign: Option<String>,
+    altitude_ft: Option<f64>,
+    ground_speed_kt: Option<f64>,
+    track: Option<f64>,
+    vertical_rate_fpm: Option<f64>,
+    squawk: Option<String>,
+    on_ground: bool,
+    position: Option<(f64, f64)>,
+    position_at: u64,
+    last_seen: u64,
+    even: Option<Cpr>,
+    odd: Option<Cpr>,
+}
+
+impl Aircraft {
+    fn apply(&mut self, update: Update, now: u64) {
+        self.last_seen = now;
+        if let Some(callsign) = update.callsign.filter(|c| !c.is_empty()) {
+            self.callsign = Some(callsign);
+        }
+        self.altitude_ft = update.altitude_ft.or(self.altitude_ft);
+        self.ground_speed_kt = update.ground_speed_kt.or(self.ground_speed_kt);
+        self.track = update.track.or(self.track);
+        self.vertical_rate_fpm = update.vertical_rate_fpm.or(self.vertical_rate_fpm);
+        self.squawk = update.squawk.or(self.squawk.take());
+        self.on_ground = update.on_ground.unwrap_or(self.on_ground);
+        if let Some(position) = update.position {
+            self.position = Some(position);
+            self.position_at = now;
+        }
+        if let Some((odd, lat, lon)) = update.cpr {
+            let frame = Some(Cpr { lat, lon, at: now });
+            if odd {
+                self.odd = frame;
+            } else {
+                self.even = frame;
+            }
+            if let (Some(even), Some(odd)) = (self.even, self.odd) {
+                if even.at.abs_diff(odd.at) <= CPR_MAX_GAP_SECS {
+                    if let Some(position) = cpr_global(even, odd) {
+                        self.position = Some(position);
+                        self.position_at = now;
+                    }
+                }
+            }
+        }
+    }
+
+    /// An OpenSky state vector row, or `None` without a recent position.
+    fn state_row(&self, icao: u32, now: u64) -> Option<Value> {
+        let (lat, lon) = self.position?;
+        if now.saturating_sub(self.last_seen) > STALE_SECS {
+            return None;
+        }
+        Some(json!([
+            format!("{icao:06x}"),
+            self.callsign.as_deref().unwrap_or(""),
+            "",
+            self.position_at,
+            self.last_seen,
+            lon,
+            lat,
+            self.altitude_ft.map(|ft| ft * FEET_TO_METERS),
+            self.on_ground,
+            self.ground_speed_kt.map(|kt| kt * KNOTS_TO_MPS),
+            self.track,
+            self.vertical_rate_fpm.map(|fpm| fpm * FPM_TO_MPS),
+            Value::Null,
+            Value::Null,
+            self.squawk,
+            false,
+            0,
+        ]))
+    }
+}
+
+fn number(field: Option<&str>) -> Option<f64> {
+    field.map(str::trim).filter(|f| !f.is_empty())?.parse().ok().filter(|v: &f64| v.is_finite())
+}
+
+/// One BaseStation `MSG` line.
+fn parse_sbs(line: &str) -> Option<Update> {
+    let fields: Vec<&str> = line.trim().split(',').collect();
+    if fields.first() != Some(&"MSG") || fields.len() < 11 {
+        return None;
+    }
+    let field = |i: usize| fields.get(i).copied();
+    let text = |i: usize| field(i).map(str::trim).filter(|f| !f.is_empty()).map(str::to_string);
+    let position = number(field(14)).zip(number(field(15)));
+    Some(Update {
+        icao: u32::from_str_radix(field(4)?.trim(), 16).ok()?,
+        callsign: text(10),
+        altitude_ft: number(field(11)),
+        ground_speed_kt: number(field(12)),
+        track: number(field(13)),
+        vertical_rate_fpm: number(field(16)),
+        squawk: text(17),
+        on_ground: text(21).map(|flag| flag == "-1" || flag == "1"),
+        position: position.filter(|(lat, lon)| lat.abs() <= 90.0 && lon.abs() <= 180.0),
+        cpr: None,
+    })
+}
+
+/// Pull complete Beast frames out of `buffer`, returning their Mode S
+/// messages (without the timestamp and signal bytes). Consumed bytes are
+/// removed; a partial frame at the end is left for the next read.
+fn beast_frames(buffer: &mut Vec<u8>) -> Vec<Vec<u8>> {
+    const ESCAPE: u8 = 0x1a;
+    let mut messages = Vec::new();
+    let mut start = 0;
+    'frames: while let Some(offset) = buffer[start..].iter().position(|&b| b == ESCAPE) {
+        let at = start + offset;
+        let Some(&kind) = buffer.get(at + 1) else {
+            start = at;
+            break;
+        };
+        let length = match kind {
+            b'1' => 2,
+            b'2' => 7,
+            b'3' => 14,
+            b'4' => 14,
+            // An escaped data byte or noise: resynchronize after it.
+            _ => {
+                start = at + if kind == ESCAPE { 2 } else { 1 };
+                continue;
+            }
+        };
+        // 6 timestamp bytes, 1 signal byte, then the message, unescaped.
+        let mut frame = Vec::with_capacity(7 + length);
+        let mut i = at + 2;
+        while frame.len() < 7 + length {
+            let Some(&byte) = buffer.get(i) else {
+                start = at;
+                break 'frames;
+            };
+            if byte == ESCAPE {
+                match buffer.get(i + 1) {
+                    Some(&ESCAPE) => i += 1,
+                    Some(_) => {
+                        // A new frame began mid-frame; drop this one.
+                        start = i;
+                        continue 'frames;
+                    }
+                    None => {
+                        start = at;
+                        break 'frames;
+                    }
+                }
+            }
+            frame.push(byte);
+            i += 1;
+        }
+        if kind == b'2' || kind == b'3' {
+            messages.push(frame.split_off(7));
+        }
+        start = i;
+    }
+    if start == 0 && buffer.len() > MAX_BUFFER {
+        start = buffer.len();
+    }
+    buffer.drain(..start.min(buffer.len()));
+    messages
+}
+
+const CALLSIGN_CHARS: &[u8; 64] = b"#ABCDEFGHIJKLMNOPQRSTUVWXYZ##### ###############0123456789######";
+
+/// Bits `from..from + count` of `bytes`, most significant first.
+fn bits(bytes: &[u8], from: usize, count: usize) -> u32 {
+    (from..from + count).fold(0, |value, bit| (value << 1) | u32::from((bytes[bit / 8] >> (7 - bit % 8)) & 1))
+}
+
+/// An extended squitter (DF17/18), long Mode S message.
+fn parse_mode_s(message: &[u8]) -> Option<Update> {
+    if message.len() != 14 {
+        return None;
+    }
+    let df = message[0] >> 3;
+    if df != 17 && df != 18 {
+        return None;
+    }
+    let me = &message[4..11];
+    let mut update = Update {
+        icao: bits(message, 8, 24),
+        ..Update::default()
+    };
+    match bits(me, 0, 5) {
+        1..=4 => {
+            let callsign: String = (0..8)
+                .map(|i| CALLSIGN_CHARS[bits(me, 8 + i * 6, 6) as usize] as char)
+                .filter(|&c| c != '#')
+                .collect();
+            update.callsign = Some(callsign.trim().to_string());
+        }
+        5..=8 => update.on_ground = Some(true),
+        9..=18 => {
+            update.on_ground = Some(false);
+            let altitude = bits(me, 8, 12);
+            // Only 25 ft increments (Q bit set); Gillham-coded altitudes are skipped.
+            if altitude & 0x10 != 0 {
+                let n = ((altitude & 0xfe0) >> 1) | (altitude & 0x0f);
+                update.altitude_ft = Some(f64::from(n) * 25.0 - 1000.0);
+            }
+            update.cpr = Some((bits(me, 21, 1) == 1, bits(me, 22, 17), bits(me, 39, 17)));
+        }
+        19 => {
+            let subtype = bits(me, 5, 3);
+            if subtype == 1 || subtype == 2 {
+                let scale = if subtype == 2 { 4.0 } else { 1.0 };
+                let (ew, ns) = (bits(me, 14, 10), bits(me, 25, 10));
+                if ew > 0 && ns > 0 {
+                    let east = f64::from(ew - 1) * scale * if bits(me, 13, 1) == 1 { -1.0 } else { 1.0 };
+                    let north = f64::from(ns - 1) * scale * if bits(me, 24, 1) == 1 { -1.0 } else { 1.0 };
+                    update.ground_speed_kt = Some(east.hypot(north));
+                    update.track = Some(east.atan2(north).to_degrees().rem_euclid(360.0));
+                }
+                let rate = bits(me, 37, 9);
+                if rate > 0 {
+                    let sign = if bits(me, 36, 1) == 1 { -1.0 } else { 1.0 };
+                    update.vertical_rate_fpm = Some(sign * f64::from(rate - 1) * 64.0);
+                }
+            }
+        }
+        _ => {}
+    }
+    Some(update)
+}
+
+/// Number of longitude zones at `lat`.
+fn nl(lat: f64) -> u32 {
+    let lat = lat.abs();
+    if lat >= 87.0 {
+        return if lat > 87.0 { 1 } else { 2 };
+    }
+    let a = 1.0 - (PI / 30.0).cos();
+    let b = (PI / 180.0 * lat).cos().powi(2);
+    (2.0 * PI / (1.0 - a / b).acos()).floor() as u32
+}
+
+/// Globally unambiguous airborne position from an even and an odd frame;
+/// the later one gives the result.
+fn cpr_global(even: Cpr, odd: Cpr) -> Option<(f64, f64)> {
+    const SCALE: f64 = 131_072.0;
+    let (lat_e, lon_e) = (f64::from(even.lat) / SCALE, f64::from(even.lon) / SCALE);
+    let (lat_o, lon_o) = (f64::from(odd.lat) / SCALE, f64::from(odd.lon) / SCALE);
+    let j = (59.0 * lat_e - 60.0 * lat_o + 0.5).floor();
+    let wrap = |lat: f64| if lat >= 270.0 { lat - 360.0 } else { lat };
+    let rlat_e = wrap(360.0 / 60.0 * (j.rem_euclid(60.0) + lat_e));
+    let rlat_o = wrap(360.0 / 59.0 * (j.rem_euclid(59.0) + lat_o));
+    if nl(rlat_e) != nl(rlat_o) {
+        return None;
+    }
+    let (lat, nl_lat, odd_latest) = if odd.at > even.at {
+        (rlat_o, nl(rlat_o), true)
+    } else {
+        (rlat_e, nl(rlat_e), false)
+    };
+    let zones = f64::from(nl_lat.saturating_sub(u32::from(odd_latest)).max(1));
+    let m = (lon_e * f64::from(nl_lat - 1) - lon_o * f64::from(nl_lat) + 0.5).floor();
+    let mut lon = 360.0 / zones * (m.rem_euclid(zones) + if odd_latest { lon_o } else { lon_e });
+    if lon >= 180.0 {
+        lon -= 360.0;
+    }
+    ((-90.0..=90.0).contains(&lat)).then_some((lat, lon))
+}
+
+/// Decoded aircraft from one connection.
+#[derive(Default)]
+struct Tracker {
+    aircraft: HashMap<u32, Aircraft>,
+}
+
+impl Tracker {
+    fn apply(&mut self, update: Update, now: u64) {
+        self.aircraft.entry(update.icao).or_default().apply(update, now);
+    }
+
+    fn rows(&mut self, now: u64) -> Vec<Value> {
+        self.aircraft.retain(|_, a| now.saturating_sub(a.last_seen) <= STALE_SECS * 5);
+        self.aircraft.iter().filter_map(|(icao, a)| a.state_row(*icao, now)).collect()
+    }
+}
+
+/// Read from one connection until it fails.
+async fn receive(app: &AppHandle, config: &AdsbConfig) -> Result<(), String> {
+    let port = config.port.unwrap_or_else(|| config.protocol.default_port());
+    let address = format!("{}:{port}", config.host);
+    let mut socket = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(&address))
+        .await
+        .map_err(|_| format!("connecting to {address} timed out"))?
+        .map_err(|e| format!("failed to connect to {address}: {e}"))?;
+    tracing::info!(target: "app", %address, protocol = ?config.protocol, "connected to ADS-B receiver");
+    update_status(app, |status| {
+        status.connected = true;
+        status.messages = 0;
+        status.error = None;
+    });
+
+    let mut tracker = Tracker::default();
+    let mut buffer = Vec::new();
+    let mut chunk = vec![0; 16 * 1024];
+    let mut publish = tokio::time::interval(PUBLISH_INTERVAL);
+    loop {
+        tokio::select! {
+            read = socket.read(&mut chunk) => {
+                let read = read.map_err(|e| format!("read failed: {e}"))?;
+                if read == 0 {
+                    return Err("receiver closed the connection".to_string());
+                }
+                buffer.extend_from_slice(&chunk[..read]);
+                let updates: Vec<Update> = match config.protocol {
+                    AdsbProtocol::Sbs => sbs_lines(&mut buffer).iter().filter_map(|line| parse_sbs(line)).collect(),
+                    AdsbProtocol::Beast => beast_frames(&mut buffer).iter().filter_map(|m| parse_mode_s(m)).collect(),
+                };
+                let now = crate::unix_timestamp_secs();
+                let count = updates.len() as u64;
+                for update in updates {
+                    tracker.apply(update, now);
+                }
+                update_status(app, |status| status.messages += count);
+            }
+            _ = publish.tick() => {
+                let now = crate::unix_timestamp_secs();
+                let rows = tracker.rows(now);
+                update_status(app, |status| status.aircraft = rows.len());
+                crate::opensky::set_local(app, now, rows);
+            }
+        }
+    }
+}
+
+/// Complete lines from `buffer`, leaving a partial one behind.
+fn sbs_lines(buffer: &mut Vec<u8>) -> Vec<String> {
+    let Some(end) = buffer.iter().rposition(|&b| b == b'\n') else {
+        if buffer.len() > MAX_BUFFER {
+            buffer.clear();
+        }
+        return Vec::new();
+    };
+    let lines = String::from_utf8_lossy(&buffer[..end]).lines().map(str::to_string).collect();
+    buffer.drain(..=end);
+    lines
+}
+
+fn disconnected(app: &AppHandle, error: Option<String>) {
+    update_status(app, |status| {
+        status.connected = false;
+        status.aircraft = 0;
+        status.error = error;
+    });
+    crate::opensky::set_local(app, crate::unix_timestamp_secs(), Vec::new());
+}
+
+async fn run(app: AppHandle, mut config: watch::Receiver<AdsbConfig>) {
+    loop {
+        let current = config.borrow_and_update().clone();
+        update_status(&app, |status| status.enabled = current.enabled);
+        if current.enabled {
+            tokio::select! {
+                result = receive(&app, &current) => {
+                    if let Err(err) = result {
+                        tracing::warn!(target: "app", "ADS-B receiver: {err}");
+                        disconnected(&app, Some(err));
+                    }
+                    tokio::select! {
+                        _ = tokio::time::sleep(RETRY_INTERVAL) => continue,
+                        changed = config.changed() => if changed.is_err() { return },
+                    }
+                }
+                changed = config.changed() => if changed.is_err() { return },
+            }
+            disconnected(&app, None);
+        } else if config.changed().await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Start the receiver task with the configured receiver. It stays idle
+/// while `adsb.enabled` is off.
+pub fn start(app: &AppHandle) {
+    let config = app.state::<DesktopConfigState>().snapshot().adsb;
+    let (sender, receiver) = watch::channel(config);
+    *app.state::<AdsbState>().config.lock().unwrap_or_else(|e| e.into_inner()) = Some(sender);
+    tauri::async_runtime::spawn(run(app.clone(), receiver));
+}
+
+#[tauri::command]
+pub fn get_adsb_receiver(webview: Webview, config: tauri::State<'_, DesktopConfigState>) -> Result<AdsbConfig, String> {
+    crate::metrics::of(&webview).observe("get_adsb_receiver", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(config.snapshot().adsb)
+    })
+}
+
+/// Save the receiver settings and reconnect with them.
+#[tauri::command]
+pub async fn set_adsb_receiver(webview: Webview, app: AppHandle, receiver: AdsbConfig) -> Result<AdsbConfig, String> {
+    crate::metrics::of(&webview)
+        .observe_async("set_adsb_receiver", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                let config = app.state::<DesktopConfigState>();
+                let state = app.state::<AdsbState>();
+                let host = receiver.host.trim();
+                if host.is_empty() || host.contains(char::is_whitespace) {
+                    return Err("Enter the receiver's host name or IP address".to_string());
+                }
+                if receiver.port == Some(0) {
+                    return Err("Port must be between 1 and 65535".to_string());
+                }
+                let receiver = AdsbConfig {
+                    host: host.to_string(),
+                    ..receiver
+                };
+                config.update(|c| c.adsb = receiver.clone())?;
+                tracing::info!(target: "app", enabled = receiver.enabled, host = %receiver.host, "ADS-B receiver changed");
+                if let Some(sender) = state.config.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+                    sender.send_replace(receiver.clone());
+                }
+                Ok(receiver)
+            })
+            .await
+        })
+        .await
+}
+
+#[tauri::command]
+pub fn get_adsb_status(webview: Webview, state: tauri::State<'_, AdsbState>) -> Result<AdsbStatus, String> {
+    crate::metrics::of(&webview).observe("get_adsb_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.status.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+#[cfg(test)]
+mod adsb_tests {
+    use super::*;
+
+    fn hex(message: &str) -> Vec<u8> {
+        (0..message.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&message[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn parses_sbs_messages() {
+        let line = "MSG,3,1,1,4CA2D6,1,2024/01/01,12:00:00.000,2024/01/01,12:00:00.000,RYR12AB ,37000,450,90.5,53.35,-6.26,-64,7700,0,0,0,0\r";
+        let update = parse_sbs(line).unwrap();
+        assert_eq!(update.icao, 0x4ca2d6);
+        assert_eq!(update.callsign.as_deref(), Some("RYR12AB"));
+        assert_eq!(update.altitude_ft, Some(37000.0));
+        assert_eq!(update.position, Some((53.35, -6.26)));
+        assert_eq!(update.squawk.as_deref(), Some("7700"));
+        assert_eq!(update.on_ground, Some(false));
+        assert!(parse_sbs("MSG,1,1,1,4CA2D6,1,,,,,,,,,,,,,,,,").unwrap().position.is_none());
+        assert!(parse_sbs("SEL,,333,2,4CA2D6").is_none());
+    }
+
+    #[test]
+    fn decodes_identification_and_velocity() {
+        let identification = parse_mode_s(&hex("8D4840D6202CC371C32CE0576098")).unwrap();
+        assert_eq!(identification.icao, 0x4840d6);
+        assert_eq!(identification.callsign.as_deref(), Some("KLM1023"));
+
+        let velocity = parse_mode_s(&hex("8D485020994409940838175B284F")).unwrap();
+        assert_eq!(velocity.ground_speed_kt.map(f64::round), Some(159.0));
+        assert_eq!(velocity.track.map(|t| (t * 100.0).round()), Some(18288.0));
+        assert_eq!(velocity.vertical_rate_fpm, Some(-832.0));
+    }
+
+    #[test]
+    fn decodes_airborne_position_from_an_even_odd_pair() {
+        let even = parse_mode_s(&hex("8D40621D58C382D690C8AC2863A7")).unwrap();
+        let odd = parse_mode_s(&hex("8D40621D58C386435CC412692AD6")).unwrap();
+        assert_eq!(even.altitude_ft, Some(38000.0));
+        let mut aircraft = Aircraft::default();
+        aircraft.apply(odd, 100);
+        assert_eq!(aircraft.position, None);
+        aircraft.apply(even, 101);
+        let (lat, lon) = aircraft.position.unwrap();
+        assert!((lat - 52.2572).abs() < 1e-3, "{lat}");
+        assert!((lon - 3.9194).abs() < 1e-3, "{lon}");
+        let row = aircraft.state_row(0x40621d, 101).unwrap();
+        assert_eq!(row[0], "40621d");
+        assert!(aircraft.state_row(0x40621d, 101 + STALE_SECS + 1).is_none());
+    }
+
+    #[test]
+    fn splits_beast_frames() {
+        let message = hex("8D4840D6202CC371C32CE0576098");
+        let mut frame = vec![0x1a, b'3', 0, 0, 0, 0x1a, 0x1a, 0, 0, 0x80];
+        frame.extend_from_slice(&message);
+        let mut buffer = frame.clone();
+        buffer.extend_from_slice(&frame[..10]);
+        assert_eq!(beast_frames(&mut buffer), vec![message.clone()]);
+        assert_eq!(buffer, frame[..10]);
+        buffer.extend_from_slice(&frame[10..]);
+        assert_eq!(beast_frames(&mut buffer), vec![message]);
+        assert!(buffer.is_empty());
+    }
+}
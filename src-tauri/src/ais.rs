@@ -0,0 +1,531 @@
+//! Live vessel positions straight from AISstream, instead of a WebSocket in
+//! the dashboard. `start_ais_stream` connects with the stored
+//! `AISSTREAM_API_KEY` and subscribes to position reports inside the given
+//! bounding boxes (the whole world by default); calling it again while
+//! connected only changes the subscription. `stop_ais_stream` disconnects.
+//!
+//! The socket is always read as fast as it delivers. Reports are decoded
+//! here and coalesced to the latest one per vessel, and the dashboard gets
+//! one `ais://positions` batch a second. Static data is only read for the
+//! vessel's IMO number, which later positions carry for watchlists. Past `MAX_PENDING_VESSELS` vessels
+//! in a batch, reports for new ones are dropped and counted, so a busy
+//! subscription cannot swamp the webview.
+//!
+//! Dropped connections are retried with backoff, from 5 seconds up to 5
+//! minutes; a connection that goes three minutes without data counts as
+//! dropped. `ais://status` is emitted whenever the connection comes up or
+//! goes down.
+//!
+//! While the built-in `relay` is serving, the stream is kept up for the
+//! whole world: it is started with the relay, and `stop_ais_stream` only
+//! widens the subscription back out.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+use tokio::sync::watch;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::aivdm::HEADING_UNAVAILABLE;
+use crate::alerts::{Sighting, Target};
+use crate::store::{EventKind, Observation};
+
+pub const POSITIONS_EVENT: &str = "ais://positions";
+pub const STATUS_EVENT: &str = "ais://status";
+
+const AISSTREAM_URL: &str = "wss://stream.aisstream.io/v0/stream";
+const API_KEY_SECRET: &str = "AISSTREAM_API_KEY";
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(20);
+const IDLE_TIMEOUT: Duration = Duration::from_secs(180);
+const RECONNECT_MIN: Duration = Duration::from_secs(5);
+const RECONNECT_MAX: Duration = Duration::from_secs(300);
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_PENDING_VESSELS: usize = 20_000;
+/// IMO numbers remembered per connection before starting over.
+const MAX_KNOWN_IMO: usize = 200_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BoundingBox {
+    pub south: f64,
+    pub west: f64,
+    pub north: f64,
+    pub east: f64,
+}
+
+impl BoundingBox {
+    const WORLD: BoundingBox = BoundingBox {
+        south: -90.0,
+        west: -180.0,
+        north: 90.0,
+        east: 180.0,
+    };
+
+    pub fn is_valid(&self) -> bool {
+        (-90.0..=90.0).contains(&self.south)
+            && (-90.0..=90.0).contains(&self.north)
+            && (-180.0..=180.0).contains(&self.west)
+            && (-180.0..=180.0).contains(&self.east)
+            && self.south <= self.north
+    }
+
+    /// Whether the point is inside; a box with `west > east` crosses the
+    /// antimeridian.
+    pub fn contains(&self, lat: f64, lon: f64) -> bool {
+        (self.south..=self.north).contains(&lat)
+            && if self.west <= self.east {
+                (self.west..=self.east).contains(&lon)
+            } else {
+                lon >= self.west || lon <= self.east
+            }
+    }
+
+    /// Whether the boxes overlap; either may cross the antimeridian.
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        let spans = |b: &BoundingBox| {
+            if b.west <= b.east {
+                [(b.west, b.east), (b.west, b.east)]
+            } else {
+                [(b.west, 180.0), (-180.0, b.east)]
+            }
+        };
+        self.south <= other.north
+            && other.south <= self.north
+            && spans(self).iter().any(|a| spans(other).iter().any(|b| a.0 <= b.1 && b.0 <= a.1))
+    }
+}
+
+/// Shaped like the dashboard's `AisPositionData`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AisPosition {
+    pub mmsi: String,
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub ship_type: Option<u32>,
+    pub heading: Option<f64>,
+    pub speed: Option<f64>,
+    pub course: Option<f64>,
+    /// Milliseconds since the epoch, when the report was received.
+    pub timestamp: u64,
+    /// From the vessel's static data, once it has sent some.
+    pub imo: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct AisStreamStatus {
+    pub running: bool,
+    pub connected: bool,
+    /// Position reports received since the stream was started.
+    pub messages: u64,
+    /// Reports dropped because a batch was full.
+    pub dropped: u64,
+}
+
+#[derive(Default)]
+pub struct AisState {
+    /// Dropping the sender stops the client task.
+    subscription: Mutex<Option<watch::Sender<Vec<BoundingBox>>>>,
+    status: Mutex<AisStreamStatus>,
+}
+
+fn update_status(app: &AppHandle, f: impl FnOnce(&mut AisStreamStatus)) -> AisStreamStatus {
+    let state = app.state::<AisState>();
+    let mut status = state.status.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut status);
+    *status
+}
+
+fn set_connected(app: &AppHandle, connected: bool) {
+    let state = app.state::<AisState>();
+    let mut status = state.status.lock().unwrap_or_else(|e| e.into_inner());
+    if status.connected != connected {
+        status.connected = connected;
+        let _ = app.emit(STATUS_EVENT, *status);
+    }
+}
+
+/// AISstream's subscription message. Boxes are `[[lat, lon], [lat, lon]]`.
+fn subscription(api_key: &str, boxes: &[BoundingBox]) -> String {
+    let boxes: Vec<Value> = boxes
+        .iter()
+        .map(|b| json!([[b.south, b.west], [b.north, b.east]]))
+        .collect();
+    json!({
+        "APIKey": api_key,
+        "BoundingBoxes": boxes,
+        "FilterMessageTypes": ["PositionReport", "ShipStaticData"],
+    })
+    .to_string()
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+        .unwrap_or(0)
+}
+
+fn finite(value: Option<&Value>) -> Option<f64> {
+    value.and_then(Value::as_f64).filter(|v| v.is_finite())
+}
+
+/// What one AISstream message carries.
+#[derive(Debug, PartialEq)]
+enum Report {
+    Position(AisPosition),
+    /// A vessel's IMO number, from its static data.
+    Imo(String, u32),
+}
+
+/// The position report or IMO number in one AISstream message, or `None`
+/// for any other message or one without a usable position or number.
+fn decode(raw: &[u8], timestamp: u64) -> Option<Report> {
+    let message: Value = serde_json::from_slice(raw).ok()?;
+    let kind = message.get("MessageType")?.as_str()?;
+    let meta = message.get("MetaData")?;
+    let mmsi = match meta.get("MMSI")? {
+        Value::Number(n) => n.to_string(),
+        Value::String(s) if !s.is_empty() => s.clone(),
+        _ => return None,
+    };
+    if kind == "ShipStaticData" {
+        let imo = message.get("Message")?.get("ShipStaticData")?.get("ImoNumber")?.as_u64()?;
+        return u32::try_from(imo).ok().filter(|imo| *imo != 0).map(|imo| Report::Imo(mmsi, imo));
+    }
+    if kind != "PositionReport" {
+        return None;
+    }
+    let report = message.get("Message")?.get("PositionReport")?;
+    let lat = finite(report.get("Latitude")).or_else(|| finite(meta.get("latitude")))?;
+    let lon = finite(report.get("Longitude")).or_else(|| finite(meta.get("longitude")))?;
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return None;
+    }
+    Some(Report::Position(AisPosition {
+        mmsi,
+        name: meta.get("ShipName").and_then(Value::as_str).unwrap_or("").trim().to_string(),
+        lat,
+        lon,
+        ship_type: meta.get("ShipType").and_then(Value::as_u64).and_then(|t| u32::try_from(t).ok()),
+        heading: finite(report.get("TrueHeading")).filter(|h| *h != HEADING_UNAVAILABLE),
+        speed: finite(report.get("Sog")),
+        course: finite(report.get("Cog")),
+        timestamp,
+        imo: None,
+    }))
+}
+
+/// Reports waiting for the next flush, latest per vessel.
+#[derive(Default)]
+struct Batch {
+    positions: HashMap<String, AisPosition>,
+    dropped: u64,
+    /// IMO numbers by MMSI, kept across flushes.
+    imo: HashMap<String, u32>,
+}
+
+impl Batch {
+    fn push(&mut self, mut position: AisPosition) {
+        if self.positions.len() >= MAX_PENDING_VESSELS && !self.positions.contains_key(&position.mmsi) {
+            self.dropped += 1;
+            return;
+        }
+        position.imo = self.imo.get(&position.mmsi).copied();
+        self.positions.insert(position.mmsi.clone(), position);
+    }
+
+    fn learn(&mut self, mmsi: String, imo: u32) {
+        if self.imo.len() >= MAX_KNOWN_IMO && !self.imo.contains_key(&mmsi) {
+            self.imo.clear();
+        }
+        self.imo.insert(mmsi, imo);
+    }
+
+    fn flush(&mut self, app: &AppHandle) {
+        let dropped = std::mem::take(&mut self.dropped);
+        if dropped > 0 {
+            update_status(app, |status| status.dropped += dropped);
+        }
+        if self.positions.is_empty() {
+            return;
+        }
+        let positions: Vec<AisPosition> = self.positions.drain().map(|(_, position)| position).collect();
+        let sightings: Vec<Sighting> = positions
+            .iter()
+            .map(|p| Sighting {
+                kind: Target::Vessel,
+                id: &p.mmsi,
+                label: &p.name,
+                imo: p.imo,
+                lat: p.lat,
+                lon: p.lon,
+            })
+            .collect();
+        crate::alerts::observe_positions(app, &sightings);
+        crate::watchlists::observe_positions(app, &sightings);
+        crate::geofences::observe_positions(app, &sightings);
+        let tracks = positions
+            .iter()
+            .map(|p| Observation {
+                kind: EventKind::Vessel,
+                source: "ais".to_string(),
+                id: p.mmsi.clone(),
+                time: p.timestamp as i64,
+                lat: Some(p.lat),
+                lon: Some(p.lon),
+                title: if p.name.is_empty() { &p.mmsi } else { &p.name }.clone(),
+                data: json!(p),
+            })
+            .collect();
+        crate::store::record_tracks(app, tracks);
+        crate::relay::publish(app, "ais", &positions);
+        let _ = app.emit(POSITIONS_EVENT, positions);
+    }
+}
+
+enum Ended {
+    Stopped,
+    Dropped(String),
+}
+
+/// One connection, until it drops or the stream is stopped.
+async fn connect(app: &AppHandle, api_key: &str, boxes: &mut watch::Receiver<Vec<BoundingBox>>) -> Result<Ended, String> {
+    let (socket, _) = tokio::time::timeout(CONNECT_TIMEOUT, tokio_tungstenite::connect_async(AISSTREAM_URL))
+        .await
+        .map_err(|_| "timed out".to_string())?
+        .map_err(|e| e.to_string())?;
+    let (mut sink, mut stream) = socket.split();
+    let first = subscription(api_key, &boxes.borrow_and_update());
+    sink.send(Message::text(first))
+        .await
+        .map_err(|e| format!("failed to subscribe: {e}"))?;
+
+    tracing::info!(target: "app", "connected to AISstream");
+    set_connected(app, true);
+
+    let mut batch = Batch::default();
+    let mut flush = tokio::time::interval(FLUSH_INTERVAL);
+    // AISstream answers a bad key or subscription by closing right away;
+    // only a connection that delivered data resets the backoff.
+    let mut received = false;
+    let dropped = |reason: String, received: bool| if received { Ok(Ended::Dropped(reason)) } else { Err(reason) };
+    loop {
+        tokio::select! {
+            next = tokio::time::timeout(IDLE_TIMEOUT, stream.next()) => {
+                let raw = match next {
+                    Err(_) => return dropped("no data for three minutes".to_string(), received),
+                    Ok(None) => return dropped("connection closed".to_string(), received),
+                    Ok(Some(Err(e))) => return dropped(e.to_string(), received),
+                    Ok(Some(Ok(Message::Close(frame)))) => {
+                        let reason = frame.map(|f| f.reason.to_string()).filter(|r| !r.is_empty());
+                        return dropped(reason.unwrap_or_else(|| "closed by server".to_string()), received);
+                    }
+                    Ok(Some(Ok(Message::Text(text)))) => text.as_bytes().to_vec(),
+                    Ok(Some(Ok(Message::Binary(bytes)))) => bytes.to_vec(),
+                    Ok(Some(Ok(_))) => continue,
+                };
+                received = true;
+                match decode(&raw, now_millis()) {
+                    Some(Report::Position(position)) => {
+                        update_status(app, |status| status.messages += 1);
+                        batch.push(position);
+                    }
+                    Some(Report::Imo(mmsi, imo)) => batch.learn(mmsi, imo),
+                    None => {}
+                }
+            }
+            _ = flush.tick() => batch.flush(app),
+            changed = boxes.changed() => {
+                if changed.is_err() {
+                    batch.flush(app);
+                    let _ = sink.close().await;
+                    return Ok(Ended::Stopped);
+                }
+                let update = subscription(api_key, &boxes.borrow_and_update());
+                sink.send(Message::text(update))
+                    .await
+                    .map_err(|e| format!("failed to update subscription: {e}"))?;
+                tracing::info!(target: "app", boxes = boxes.borrow().len(), "AISstream subscription updated");
+            }
+        }
+    }
+}
+
+async fn run(app: AppHandle, api_key: String, mut boxes: watch::Receiver<Vec<BoundingBox>>) {
+    let mut backoff = RECONNECT_MIN;
+    loop {
+        match connect(&app, &api_key, &mut boxes).await {
+            Ok(Ended::Stopped) => break,
+            Ok(Ended::Dropped(reason)) => {
+                tracing::warn!(target: "app", "AISstream connection dropped: {reason}");
+                backoff = RECONNECT_MIN;
+            }
+            Err(err) => tracing::warn!(target: "app", "AISstream connection failed: {err}"),
+        }
+        set_connected(&app, false);
+        tokio::select! {
+            _ = tokio::time::sleep(backoff) => {}
+            changed = boxes.changed() => if changed.is_err() { break },
+        }
+        backoff = (backoff * 2).min(RECONNECT_MAX);
+    }
+    set_connected(&app, false);
+    tracing::info!(target: "app", "AISstream client stopped");
+}
+
+/// Connect to AISstream (or change the subscription if already connected).
+/// `bounding_boxes` defaults to the whole world.
+#[tauri::command]
+pub fn start_ais_stream(
+    webview: Webview,
+    app: AppHandle,
+    state: tauri::State<'_, AisState>,
+    bounding_boxes: Option<Vec<BoundingBox>>,
+) -> Result<AisStreamStatus, String> {
+    crate::metrics::of(&webview).observe("start_ais_stream", || {
+        crate::require_trusted_window(webview.label())?;
+        let boxes = bounding_boxes.unwrap_or_else(|| vec![BoundingBox::WORLD]);
+        if boxes.is_empty() || !boxes.iter().all(BoundingBox::is_valid) {
+            return Err("Bounding boxes must be non-empty, in degrees, with south <= north".to_string());
+        }
+        subscribe(&app, &state, boxes)
+    })
+}
+
+fn subscribe(app: &AppHandle, state: &AisState, boxes: Vec<BoundingBox>) -> Result<AisStreamStatus, String> {
+    let mut subscription = state.subscription.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(sender) = subscription.as_ref().filter(|sender| !sender.is_closed()) {
+        sender.send_replace(boxes);
+        return Ok(*state.status.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+    let api_key = app
+        .state::<crate::SecretsCache>()
+        .get(API_KEY_SECRET)
+        .ok_or("AISSTREAM_API_KEY is not set")?;
+    let (sender, receiver) = watch::channel(boxes);
+    *subscription = Some(sender);
+    let status = update_status(app, |status| *status = AisStreamStatus { running: true, ..Default::default() });
+    tracing::info!(target: "app", "starting AISstream client");
+    tauri::async_runtime::spawn(run(app.clone(), api_key, receiver));
+    Ok(status)
+}
+
+/// Connect for the whole world unless already connected; for `relay`.
+pub fn ensure_running(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<AisState>();
+    if state.subscription.lock().unwrap_or_else(|e| e.into_inner()).as_ref().is_some_and(|s| !s.is_closed()) {
+        return Ok(());
+    }
+    subscribe(app, &state, vec![BoundingBox::WORLD]).map(|_| ())
+}
+
+#[tauri::command]
+pub fn stop_ais_stream(webview: Webview, app: AppHandle, state: tauri::State<'_, AisState>) -> Result<(), String> {
+    crate::metrics::of(&webview).observe("stop_ais_stream", || {
+        crate::require_trusted_window(webview.label())?;
+        if crate::relay::is_serving(&app) {
+            // Relay clients still want the feed; just widen it back out.
+            if let Some(sender) = state.subscription.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+                sender.send_replace(vec![BoundingBox::WORLD]);
+            }
+            return Ok(());
+        }
+        if state.subscription.lock().unwrap_or_else(|e| e.into_inner()).take().is_some() {
+            update_status(&app, |status| status.running = false);
+        }
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn get_ais_stream_status(webview: Webview, state: tauri::State<'_, AisState>) -> Result<AisStreamStatus, String> {
+    crate::metrics::of(&webview).observe("get_ais_stream_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(*state.status.lock().unwrap_or_else(|e| e.into_inner()))
+    })
+}
+
+#[cfg(test)]
+mod ais_tests {
+    use super::*;
+
+    #[test]
+    fn decodes_position_reports() {
+        let raw = br#"{"MessageType":"PositionReport","MetaData":{"MMSI":244660000,"ShipName":"EVER GIVEN   ","latitude":30.0,"longitude":32.5},
+            "Message":{"PositionReport":{"Latitude":30.01,"Longitude":32.55,"Sog":12.3,"Cog":181.0,"TrueHeading":511}}}"#;
+        let Some(Report::Position(position)) = decode(raw, 7) else {
+            panic!("not a position");
+        };
+        assert_eq!(position.mmsi, "244660000");
+        assert_eq!(position.name, "EVER GIVEN");
+        assert_eq!((position.lat, position.lon), (30.01, 32.55));
+        assert_eq!((position.speed, position.course, position.heading), (Some(12.3), Some(181.0), None));
+        assert_eq!(position.timestamp, 7);
+
+        let static_data = br#"{"MessageType":"ShipStaticData","MetaData":{"MMSI":244660000},"Message":{"ShipStaticData":{"ImoNumber":9811000}}}"#;
+        assert_eq!(decode(static_data, 0), Some(Report::Imo("244660000".to_string(), 9_811_000)));
+        assert!(decode(br#"{"MessageType":"ShipStaticData","MetaData":{"MMSI":1},"Message":{"ShipStaticData":{"ImoNumber":0}}}"#, 0).is_none());
+        assert!(decode(br#"{"MessageType":"StandardClassBPositionReport","MetaData":{"MMSI":1}}"#, 0).is_none());
+        assert!(decode(br#"{"MessageType":"PositionReport","MetaData":{"MMSI":1},"Message":{"PositionReport":{"Latitude":91,"Longitude":0}}}"#, 0).is_none());
+        assert!(decode(b"not json", 0).is_none());
+    }
+
+    #[test]
+    fn boxes_intersect_across_the_antimeridian() {
+        let pacific = BoundingBox { south: -10.0, west: 170.0, north: 10.0, east: -170.0 };
+        let fiji = BoundingBox { south: -20.0, west: 177.0, north: -5.0, east: 179.0 };
+        let kiribati = BoundingBox { south: -5.0, west: -175.0, north: 2.0, east: -170.0 };
+        let hawaii = BoundingBox { south: 18.0, west: -161.0, north: 23.0, east: -154.0 };
+        assert!(pacific.intersects(&fiji) && fiji.intersects(&pacific));
+        assert!(pacific.intersects(&kiribati));
+        assert!(!pacific.intersects(&hawaii));
+        assert!(!fiji.intersects(&kiribati));
+    }
+
+    #[test]
+    fn subscribes_with_lat_lon_corners() {
+        let message: Value = serde_json::from_str(&subscription("key", &[BoundingBox::WORLD])).unwrap();
+        assert_eq!(message["APIKey"], "key");
+        assert_eq!(message["BoundingBoxes"], json!([[[-90.0, -180.0], [90.0, 180.0]]]));
+        assert_eq!(message["FilterMessageTypes"], json!(["PositionReport", "ShipStaticData"]));
+        assert!(!BoundingBox { south: 10.0, west: 0.0, north: 5.0, east: 1.0 }.is_valid());
+    }
+
+    #[test]
+    fn batches_keep_the_latest_report_per_vessel() {
+        let position = |mmsi: usize, timestamp| AisPosition {
+            mmsi: mmsi.to_string(),
+            name: String::new(),
+            lat: 0.0,
+            lon: 0.0,
+            ship_type: None,
+            heading: None,
+            speed: None,
+            course: None,
+            timestamp,
+            imo: None,
+        };
+        let mut batch = Batch::default();
+        batch.push(position(1, 1));
+        batch.push(position(1, 2));
+        assert_eq!(batch.positions["1"].timestamp, 2);
+        for mmsi in 2..=MAX_PENDING_VESSELS + 1 {
+            batch.push(position(mmsi, 1));
+        }
+        assert_eq!(batch.positions.len(), MAX_PENDING_VESSELS);
+        assert_eq!(batch.dropped, 1);
+        batch.push(position(1, 3));
+        assert_eq!(batch.dropped, 1);
+
+        batch.learn("1".to_string(), 9_811_000);
+        batch.push(position(1, 4));
+        assert_eq!(batch.positions["1"].imo, Some(9_811_000));
+    }
+}
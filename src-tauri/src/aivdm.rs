@@ -0,0 +1,349 @@
+//! AIS NMEA (`!AIVDM`/`!AIVDO`) decoding, independent of where the sentences
+//! come from, for feeds that deliver raw NMEA instead of AISstream's JSON.
+//!
+//! A `Decoder` takes sentences one at a time, checks their checksums,
+//! reassembles multi-sentence messages and decodes the payload into a
+//! `VesselRecord`. Supported message types are position reports (1–3 and
+//! class B 18/19) and static data (5 and class B 24); anything else, and any
+//! malformed sentence, is skipped. "Not available" values come out as
+//! `None`, so records from different message types can be merged field by
+//! field. `decode_ais_sentences` exposes it to the dashboard.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use tauri::Webview;
+
+/// AIS's "not available" true heading.
+pub const HEADING_UNAVAILABLE: f64 = 511.0;
+
+/// Incomplete multi-sentence messages kept at once; more means a feed that
+/// never finishes them.
+const MAX_PARTIAL: usize = 64;
+
+/// One decoded AIS message, normalized across message types.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VesselRecord {
+    pub message_type: u8,
+    pub mmsi: u32,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    /// Knots over ground.
+    pub speed: Option<f64>,
+    /// Degrees over ground.
+    pub course: Option<f64>,
+    pub heading: Option<f64>,
+    /// Navigational status (0 under way using engine … 15 undefined), class A only.
+    pub nav_status: Option<u8>,
+    pub name: Option<String>,
+    pub call_sign: Option<String>,
+    pub imo: Option<u32>,
+    pub ship_type: Option<u32>,
+    pub destination: Option<String>,
+    /// Meters, bow to stern.
+    pub length: Option<u32>,
+    /// Meters, port to starboard.
+    pub beam: Option<u32>,
+    /// Meters.
+    pub draught: Option<f64>,
+}
+
+/// Unpacked payload bits.
+struct Bits(Vec<bool>);
+
+impl Bits {
+    /// Undo the payload's six-bit ASCII armoring, dropping `fill` pad bits.
+    fn unarmor(payload: &str, fill: usize) -> Option<Bits> {
+        let mut bits = Vec::with_capacity(payload.len() * 6);
+        for c in payload.bytes() {
+            if !(48..=119).contains(&c) || (88..=95).contains(&c) {
+                return None;
+            }
+            let mut value = c - 48;
+            if value > 40 {
+                value -= 8;
+            }
+            bits.extend((0..6).rev().map(|i| (value >> i) & 1 == 1));
+        }
+        bits.truncate(bits.len().checked_sub(fill)?);
+        Some(Bits(bits))
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn unsigned(&self, from: usize, count: usize) -> u32 {
+        self.0[from..from + count].iter().fold(0, |value, &bit| (value << 1) | u32::from(bit))
+    }
+
+    fn signed(&self, from: usize, count: usize) -> i32 {
+        let value = self.unsigned(from, count);
+        let shift = 32 - count as u32;
+        ((value << shift) as i32) >> shift
+    }
+
+    /// Six-bit text, with `@` padding and trailing spaces removed.
+    fn text(&self, from: usize, chars: usize) -> Option<String> {
+        let chars = chars.min(self.len().saturating_sub(from) / 6);
+        let text: String = (0..chars)
+            .map(|i| {
+                let value = self.unsigned(from + i * 6, 6) as u8;
+                char::from(if value < 32 { value + 64 } else { value })
+            })
+            .collect();
+        let text = text.split('@').next().unwrap_or("").trim_end();
+        (!text.is_empty()).then(|| text.to_string())
+    }
+}
+
+fn position(bits: &Bits, lon_at: usize, record: &mut VesselRecord) {
+    let lon = f64::from(bits.signed(lon_at, 28)) / 600_000.0;
+    let lat = f64::from(bits.signed(lon_at + 28, 27)) / 600_000.0;
+    if lon.abs() <= 180.0 && lat.abs() <= 90.0 {
+        record.lon = Some(lon);
+        record.lat = Some(lat);
+    }
+}
+
+fn speed(raw: u32) -> Option<f64> {
+    (raw != 1023).then(|| f64::from(raw) / 10.0)
+}
+
+fn course(raw: u32) -> Option<f64> {
+    (raw < 3600).then(|| f64::from(raw) / 10.0)
+}
+
+fn heading(raw: u32) -> Option<f64> {
+    Some(f64::from(raw)).filter(|h| *h < 360.0 && *h != HEADING_UNAVAILABLE)
+}
+
+fn nonzero(value: u32) -> Option<u32> {
+    (value != 0).then_some(value)
+}
+
+/// Bow + stern and port + starboard, from the dimension block at `at`.
+fn dimensions(bits: &Bits, at: usize, record: &mut VesselRecord) {
+    record.length = nonzero(bits.unsigned(at, 9) + bits.unsigned(at + 9, 9));
+    record.beam = nonzero(bits.unsigned(at + 18, 6) + bits.unsigned(at + 24, 6));
+}
+
+/// Minimum payload bits for each supported message type.
+fn min_bits(message_type: u8) -> Option<usize> {
+    match message_type {
+        1..=3 => Some(149),
+        5 => Some(420),
+        18 => Some(139),
+        19 => Some(301),
+        24 => Some(160),
+        _ => None,
+    }
+}
+
+fn decode_payload(bits: &Bits) -> Option<VesselRecord> {
+    if bits.len() < 38 {
+        return None;
+    }
+    let message_type = bits.unsigned(0, 6) as u8;
+    if bits.len() < min_bits(message_type)? {
+        return None;
+    }
+    let mut record = VesselRecord {
+        message_type,
+        mmsi: bits.unsigned(8, 30),
+        ..VesselRecord::default()
+    };
+    match message_type {
+        1..=3 => {
+            record.nav_status = Some(bits.unsigned(38, 4) as u8);
+            record.speed = speed(bits.unsigned(50, 10));
+            position(bits, 61, &mut record);
+            record.course = course(bits.unsigned(116, 12));
+            record.heading = heading(bits.unsigned(128, 9));
+        }
+        5 => {
+            record.imo = nonzero(bits.unsigned(40, 30));
+            record.call_sign = bits.text(70, 7);
+            record.name = bits.text(112, 20);
+            record.ship_type = nonzero(bits.unsigned(232, 8));
+            dimensions(bits, 240, &mut record);
+            record.draught = nonzero(bits.unsigned(294, 8)).map(|d| f64::from(d) / 10.0);
+            record.destination = bits.text(302, 20);
+        }
+        18 | 19 => {
+            record.speed = speed(bits.unsigned(46, 10));
+            position(bits, 57, &mut record);
+            record.course = course(bits.unsigned(112, 12));
+            record.heading = heading(bits.unsigned(124, 9));
+            if message_type == 19 {
+                record.name = bits.text(143, 20);
+                record.ship_type = nonzero(bits.unsigned(263, 8));
+                dimensions(bits, 271, &mut record);
+            }
+        }
+        24 => match bits.unsigned(38, 2) {
+            0 => record.name = bits.text(40, 20),
+            1 => {
+                record.ship_type = nonzero(bits.unsigned(40, 8));
+                record.call_sign = bits.text(90, 7);
+                // Auxiliary craft (MMSI 98xxxxxxx) give their mothership here instead.
+                if record.mmsi / 10_000_000 != 98 {
+                    dimensions(bits, 132, &mut record);
+                }
+            }
+            _ => return None,
+        },
+        _ => return None,
+    }
+    Some(record)
+}
+
+/// The fields of one sentence, if its checksum matches.
+fn fields(sentence: &str) -> Option<Vec<&str>> {
+    // Skip any NMEA 4.0 tag block or receiver prefix.
+    let sentence = &sentence[sentence.find('!')?..];
+    let (body, checksum) = sentence.trim_end().split_once('*')?;
+    let expected = u8::from_str_radix(checksum.get(..2)?, 16).ok()?;
+    if body.bytes().skip(1).fold(0, |sum, b| sum ^ b) != expected {
+        return None;
+    }
+    let fields: Vec<&str> = body.split(',').collect();
+    let kind = fields[0].get(3..)?;
+    (fields.len() == 7 && (kind == "VDM" || kind == "VDO")).then_some(fields)
+}
+
+/// Fragments received so far for one multi-sentence message.
+struct Partial {
+    total: u8,
+    payload: String,
+    next: u8,
+}
+
+/// Turns a stream of AIVDM/AIVDO sentences into vessel records. Keep one
+/// per feed, since multi-sentence messages are reassembled across calls.
+#[derive(Default)]
+pub struct Decoder {
+    /// By sequential message id and radio channel.
+    partial: HashMap<(String, String), Partial>,
+}
+
+impl Decoder {
+    /// The record completed by `sentence`, if any.
+    pub fn push(&mut self, sentence: &str) -> Option<VesselRecord> {
+        let fields = fields(sentence)?;
+        let total: u8 = fields[1].parse().ok()?;
+        let number: u8 = fields[2].parse().ok()?;
+        let fill: usize = fields[6].parse().ok()?;
+        if total == 0 || number == 0 || number > total || fill > 5 {
+            return None;
+        }
+        if total == 1 {
+            return decode_payload(&Bits::unarmor(fields[5], fill)?);
+        }
+
+        let key = (fields[3].to_string(), fields[4].to_string());
+        if number == 1 {
+            if self.partial.len() >= MAX_PARTIAL {
+                self.partial.clear();
+            }
+            self.partial.insert(key.clone(), Partial { total, payload: String::new(), next: 1 });
+        }
+        let partial = self.partial.get_mut(&key)?;
+        if partial.total != total || partial.next != number {
+            self.partial.remove(&key);
+            return None;
+        }
+        partial.payload.push_str(fields[5]);
+        partial.next += 1;
+        if number < total {
+            return None;
+        }
+        let partial = self.partial.remove(&key)?;
+        decode_payload(&Bits::unarmor(&partial.payload, fill)?)
+    }
+}
+
+/// Decode a batch of sentences, in order, into the records they complete.
+#[tauri::command]
+pub fn decode_ais_sentences(webview: Webview, sentences: Vec<String>) -> Result<Vec<VesselRecord>, String> {
+    crate::metrics::of(&webview).observe("decode_ais_sentences", || {
+        crate::require_trusted_window(webview.label())?;
+        let mut decoder = Decoder::default();
+        Ok(sentences.iter().filter_map(|sentence| decoder.push(sentence)).collect())
+    })
+}
+
+#[cfg(test)]
+mod aivdm_tests {
+    use super::*;
+
+    fn decode(sentences: &[&str]) -> Vec<VesselRecord> {
+        let mut decoder = Decoder::default();
+        sentences.iter().filter_map(|sentence| decoder.push(sentence)).collect()
+    }
+
+    fn close(value: Option<f64>, expected: f64) -> bool {
+        value.is_some_and(|v| (v - expected).abs() < 1e-4)
+    }
+
+    #[test]
+    fn decodes_class_a_position_reports() {
+        let records = decode(&["!AIVDM,1,1,,A,15RTgt0PAso;90TKcjM8h6g208CQ,0*4A"]);
+        let record = &records[0];
+        assert_eq!((record.message_type, record.mmsi), (1, 371_798_000));
+        assert_eq!(record.nav_status, Some(0));
+        assert_eq!((record.speed, record.course, record.heading), (Some(12.3), Some(224.0), Some(215.0)));
+        assert!(close(record.lat, 48.38163), "{:?}", record.lat);
+        assert!(close(record.lon, -123.39538), "{:?}", record.lon);
+    }
+
+    #[test]
+    fn reassembles_static_and_voyage_data() {
+        let records = decode(&[
+            "!AIVDM,2,1,1,A,55?MbV02;H;s<HtKR20EHE:0@T4@Dn2222222216L961O5Gf0NSQEp6ClRp8,0*1C",
+            "!AIVDM,2,2,1,A,88888888880,2*25",
+        ]);
+        let record = &records[0];
+        assert_eq!((record.message_type, record.mmsi), (5, 351_759_000));
+        assert_eq!(record.imo, Some(9_134_270));
+        assert_eq!(record.call_sign.as_deref(), Some("3FOF8"));
+        assert_eq!(record.name.as_deref(), Some("EVER DIADEM"));
+        assert_eq!(record.destination.as_deref(), Some("NEW YORK"));
+        assert_eq!((record.ship_type, record.length, record.beam), (Some(70), Some(295), Some(32)));
+        assert_eq!(record.draught, Some(12.2));
+
+        // A second fragment without its first, or out of order, yields nothing.
+        assert!(decode(&["!AIVDM,2,2,1,A,88888888880,2*25"]).is_empty());
+    }
+
+    #[test]
+    fn decodes_class_b_reports() {
+        let records = decode(&[
+            "!AIVDM,1,1,,B,C5N3SRgPEnJGEBT>NhWAwwo862PaLELTBJ:V00000000S0D:R220,0*0B",
+            "!AIVDM,1,1,,A,H42O55i18tMET00000000000000,2*6D",
+            "!AIVDM,1,1,,A,H42O55lti4hhhilD3nink000?050,0*40",
+        ]);
+        let extended = &records[0];
+        assert_eq!((extended.message_type, extended.mmsi), (19, 367_059_850));
+        assert_eq!((extended.speed, extended.course, extended.heading), (Some(8.7), Some(335.9), None));
+        assert!(close(extended.lat, 29.543695), "{:?}", extended.lat);
+        assert!(close(extended.lon, -88.810392), "{:?}", extended.lon);
+        assert_eq!(extended.name.as_deref(), Some("CAPT.J.RIMES"));
+        assert_eq!((extended.ship_type, extended.length, extended.beam), (Some(70), Some(26), Some(8)));
+
+        let (part_a, part_b) = (&records[1], &records[2]);
+        assert_eq!((part_a.message_type, part_a.mmsi), (24, 271_041_815));
+        assert_eq!(part_a.name.as_deref(), Some("PROGUY"));
+        assert_eq!(part_b.call_sign.as_deref(), Some("TC6163"));
+        assert_eq!((part_b.ship_type, part_b.length, part_b.beam), (Some(60), Some(15), Some(5)));
+    }
+
+    #[test]
+    fn skips_bad_checksums_and_other_sentences() {
+        assert!(decode(&["!AIVDM,1,1,,A,15RTgt0PAso;90TKcjM8h6g208CQ,0*4B"]).is_empty());
+        assert!(decode(&["$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47"]).is_empty());
+        // Tag blocks are ignored.
+        assert_eq!(decode(&["\\s:station,c:1700000000*00\\!AIVDM,1,1,,A,15RTgt0PAso;90TKcjM8h6g208CQ,0*4A"]).len(), 1);
+    }
+}
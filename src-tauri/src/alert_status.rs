@@ -0,0 +1,69 @@
+//! Live alert and headline counts. The dashboard owns the alert pipeline, so
+//! it pushes a summary through `update_alert_status` whenever the counts
+//! change; the shell keeps the latest copy and rebroadcasts it as
+//! `alerts://status` for desktop surfaces that live outside the dashboard,
+//! such as the ticker window. Badges are set separately through `badging`.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+pub const STATUS_EVENT: &str = "alerts://status";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlertStatus {
+    /// Cross-module alerts from the last 24 hours.
+    pub alerts: u32,
+    /// Of those, alerts with critical or high priority.
+    pub critical: u32,
+    /// Headlines currently loaded across all feeds.
+    pub headlines: u32,
+    /// Headlines flagged as breaking alerts.
+    pub breaking: u32,
+}
+
+#[derive(Default)]
+pub struct AlertStatusState(Mutex<AlertStatus>);
+
+pub fn current(app: &AppHandle) -> AlertStatus {
+    app.try_state::<AlertStatusState>()
+        .map(|state| *state.0.lock().unwrap_or_else(|e| e.into_inner()))
+        .unwrap_or_default()
+}
+
+/// Only the dashboard runs the alert pipeline; other windows just read.
+#[tauri::command]
+pub fn update_alert_status(
+    webview: Webview,
+    app: AppHandle,
+    state: tauri::State<'_, AlertStatusState>,
+    status: AlertStatus,
+) -> Result<(), String> {
+    crate::metrics::of(&webview).observe("update_alert_status", || {
+        crate::require_trusted_window(webview.label())?;
+        if webview.label() != "main" {
+            return Err("Alert status is reported by the main window".to_string());
+        }
+        {
+            let mut current = state.0.lock().unwrap_or_else(|e| e.into_inner());
+            if *current == status {
+                return Ok(());
+            }
+            *current = status;
+        }
+        let _ = app.emit(STATUS_EVENT, status);
+        crate::alerts::record_metric(&app, "alerts", f64::from(status.alerts));
+        crate::alerts::record_metric(&app, "alerts.critical", f64::from(status.critical));
+        crate::alerts::record_metric(&app, "headlines.breaking", f64::from(status.breaking));
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn get_alert_status(webview: Webview, app: AppHandle) -> Result<AlertStatus, String> {
+    crate::metrics::of(&webview).observe("get_alert_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(current(&app))
+    })
+}
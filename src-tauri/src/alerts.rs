@@ -0,0 +1,1055 @@
+//! User-defined alert rules, evaluated in the shell against the live streams
+//! as they arrive: aircraft from `opensky` (network and local receiver),
+//! vessels from `ais`, new headlines from `news`, hits on the saved
+//! `watchlists`, and metrics reported through `record_alert_metric`,
+//! derived from the dashboard's `alert_status` counts, or fed in by `eia`
+//! (`eia.<dataset>`) and `swpc`, which also reports geomagnetic storms and
+//! solar flares, unusual flights scored by `anomalies`, and earthquakes,
+//! volcano notices and tsunami bulletins from `usgs` and `hazards`. A rule that matches shows a desktop notification
+//! (its urgency decides sound and attention, and Focus mode still applies),
+//! emits `alerts://fired`, and optionally goes out to `webhooks`.
+//!
+//! Rules live in `alert-rules.json`. Geofence rules name a fence saved in
+//! `geofences` and fire on the entries, exits or dwells it reports;
+//! watchlist rules fire when a subject appears or reappears, not on every
+//! position report; threshold rules fire when a metric crosses into the
+//! breaching range. Every rule
+//! also has a per-subject cooldown, and the last fired alerts are kept in
+//! memory for `get_fired_alerts` and the `digest`, which can also hold back
+//! their notifications.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+use crate::anomalies::{AnomalyKind, FlightAnomaly};
+use crate::geofences::{GeofenceEvent, Transition};
+use crate::hazards::{HazardEvent, HazardKind, HazardLevel};
+use crate::notifications::NotificationUrgency;
+use crate::swpc::{SpaceWeatherChange, SpaceWeatherEvent};
+use crate::watchlists::{WatchKind, WatchlistHit};
+use crate::webhooks::WebhookTarget;
+
+pub const ALERT_RULES_FILE: &str = "alert-rules.json";
+pub const FIRED_EVENT: &str = "alerts://fired";
+
+/// A subject not seen matching for this long counts as having left, so it
+/// fires again when it comes back.
+const ABSENCE: Duration = Duration::from_secs(15 * 60);
+const MAX_FIRED: usize = 100;
+const MAX_NAME_LEN: usize = 120;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Target {
+    Aircraft,
+    Vessel,
+    #[default]
+    Any,
+}
+
+impl Target {
+    pub(crate) fn includes(self, kind: Target) -> bool {
+        self == Target::Any || self == kind
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Comparison {
+    Above,
+    Below,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Condition {
+    /// An aircraft or vessel enters, leaves or dwells in the geofence with
+    /// id `fence` (see `geofences`, which also decides what it targets).
+    /// Only `transitions` count; entries by default.
+    Geofence {
+        fence: String,
+        #[serde(default = "default_transitions")]
+        transitions: Vec<Transition>,
+    },
+    /// A new headline contains any of the keywords (case-insensitive),
+    /// optionally only from the named feeds.
+    Keyword {
+        keywords: Vec<String>,
+        #[serde(default)]
+        sources: Vec<String>,
+    },
+    /// A metric goes above or below `value`.
+    Threshold {
+        metric: String,
+        comparison: Comparison,
+        value: f64,
+    },
+    /// A listed aircraft (ICAO24 address or callsign) or vessel (MMSI or
+    /// name) shows up.
+    Watchlist {
+        #[serde(default)]
+        aircraft: Vec<String>,
+        #[serde(default)]
+        vessels: Vec<String>,
+    },
+    /// Something on the saved watchlists (see `watchlists`) shows up: a
+    /// listed aircraft or vessel, any aircraft or vessel in a listed region,
+    /// or a headline with a listed keyword. Only entries of `kinds` count;
+    /// all do when empty.
+    Watchlists {
+        #[serde(default)]
+        kinds: Vec<WatchKind>,
+    },
+    /// The geomagnetic storm level reaches `storm` on NOAA's G scale (1-5),
+    /// or a solar flare reaches class `flare` (e.g. `X1`), as `swpc`
+    /// reports them. Either may be null to ignore it.
+    SpaceWeather {
+        #[serde(default = "default_storm")]
+        storm: Option<u8>,
+        #[serde(default = "default_flare")]
+        flare: Option<String>,
+    },
+    /// `anomalies` flags an aircraft with at least `min_score` (0-1). Only
+    /// anomalies of `kinds` count; all do when empty.
+    Anomaly {
+        #[serde(default)]
+        kinds: Vec<AnomalyKind>,
+        #[serde(default = "default_min_score")]
+        min_score: f64,
+    },
+    /// An earthquake, volcano or tsunami hazard is new or raised to at
+    /// least `min_level`. Only hazards of `kinds` count; all do when empty.
+    Hazard {
+        #[serde(default)]
+        kinds: Vec<HazardKind>,
+        #[serde(default = "default_hazard_level")]
+        min_level: HazardLevel,
+    },
+}
+
+fn default_transitions() -> Vec<Transition> {
+    vec![Transition::Enter]
+}
+
+fn default_hazard_level() -> HazardLevel {
+    HazardLevel::Warning
+}
+
+fn default_min_score() -> f64 {
+    0.5
+}
+
+fn default_storm() -> Option<u8> {
+    Some(3)
+}
+
+fn default_flare() -> Option<String> {
+    Some("X1".to_string())
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Delivery {
+    pub notify: bool,
+    /// Low is silent; normal plays a sound; critical also requests attention
+    /// and breaks through Focus mode.
+    pub urgency: NotificationUrgency,
+    /// A target with the default payload, from before `webhooks`.
+    pub webhook: Option<String>,
+    pub webhooks: Vec<WebhookTarget>,
+}
+
+impl Delivery {
+    fn webhook_targets(&self) -> Vec<WebhookTarget> {
+        let legacy = self.webhook.iter().map(|url| WebhookTarget { url: url.clone(), template: None });
+        legacy.chain(self.webhooks.iter().cloned()).collect()
+    }
+}
+
+impl Default for Delivery {
+    fn default() -> Self {
+        Delivery {
+            notify: true,
+            urgency: NotificationUrgency::Normal,
+            webhook: None,
+            webhooks: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertRule {
+    /// Assigned by `save_alert_rule` when empty.
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub condition: Condition,
+    #[serde(default)]
+    pub delivery: Delivery,
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_cooldown_secs() -> u64 {
+    300
+}
+
+impl AlertRule {
+    fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() || self.name.len() > MAX_NAME_LEN {
+            return Err(format!("Rule name must be 1-{MAX_NAME_LEN} characters"));
+        }
+        match &self.condition {
+            Condition::Geofence { fence, transitions } => {
+                if fence.trim().is_empty() {
+                    return Err("A geofence rule needs a geofence id".to_string());
+                }
+                if transitions.is_empty() {
+                    return Err("A geofence rule needs at least one transition".to_string());
+                }
+            }
+            Condition::Keyword { keywords, .. } => {
+                if keywords.iter().all(|k| k.trim().is_empty()) {
+                    return Err("A keyword rule needs at least one keyword".to_string());
+                }
+            }
+            Condition::Threshold { metric, value, .. } => {
+                if metric.trim().is_empty() || !value.is_finite() {
+                    return Err("A threshold rule needs a metric and a finite value".to_string());
+                }
+            }
+            Condition::Watchlist { aircraft, vessels } => {
+                if aircraft.iter().chain(vessels).all(|s| s.trim().is_empty()) {
+                    return Err("A watchlist needs at least one aircraft or vessel".to_string());
+                }
+            }
+            Condition::Watchlists { .. } => {}
+            Condition::SpaceWeather { storm, flare } => {
+                if storm.is_none() && flare.is_none() {
+                    return Err("A space weather rule needs a storm level or a flare class".to_string());
+                }
+                if storm.is_some_and(|level| !(1..=5).contains(&level)) {
+                    return Err("Storm level must be G1-G5".to_string());
+                }
+                if flare.as_deref().is_some_and(|class| crate::swpc::flare_flux(class).is_none()) {
+                    return Err("Flare class must be like M5 or X1".to_string());
+                }
+            }
+            Condition::Anomaly { min_score, .. } => {
+                if !(0.0..=1.0).contains(min_score) {
+                    return Err("Minimum anomaly score must be 0-1".to_string());
+                }
+            }
+            Condition::Hazard { .. } => {}
+        }
+        if self.delivery.webhooks.len() + usize::from(self.delivery.webhook.is_some()) > crate::webhooks::MAX_TARGETS {
+            return Err(format!("A rule can have at most {} webhooks", crate::webhooks::MAX_TARGETS));
+        }
+        for target in self.delivery.webhook_targets() {
+            target.validate()?;
+        }
+        Ok(())
+    }
+
+    fn cooldown(&self) -> Duration {
+        Duration::from_secs(self.cooldown_secs)
+    }
+}
+
+/// An aircraft or vessel position from one of the streams.
+pub struct Sighting<'a> {
+    pub kind: Target,
+    /// ICAO24 address or MMSI.
+    pub id: &'a str,
+    /// Callsign or vessel name; may be empty.
+    pub label: &'a str,
+    /// A vessel's IMO number, once its static data has been received.
+    pub imo: Option<u32>,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl Sighting<'_> {
+    pub(crate) fn describe(&self) -> String {
+        let kind = if self.kind == Target::Vessel { "Vessel" } else { "Aircraft" };
+        if self.label.is_empty() {
+            format!("{kind} {}", self.id)
+        } else {
+            format!("{kind} {} ({})", self.label, self.id)
+        }
+    }
+}
+
+/// A new headline from a feed.
+pub struct Headline<'a> {
+    pub source: &'a str,
+    pub title: &'a str,
+    pub link: &'a str,
+}
+
+/// Payload of `alerts://fired`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FiredAlert {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub title: String,
+    pub body: String,
+    /// What matched: an aircraft or vessel id, a headline link, or a metric.
+    pub subject: String,
+    /// Seconds since the epoch.
+    pub fired_at: u64,
+}
+
+/// Per-rule tracking between evaluations.
+#[derive(Default)]
+struct Engine {
+    /// When each (rule, subject) last matched a watchlist.
+    present: HashMap<(String, String), Instant>,
+    /// Threshold rules currently past their value.
+    breaching: HashMap<String, bool>,
+    /// When each (rule, subject) last fired, while its cooldown runs.
+    last_fired: HashMap<(String, String), Instant>,
+}
+
+impl Engine {
+    /// Apply the rule's cooldown for `subject`; true when it may fire now.
+    fn take(&mut self, rule: &AlertRule, subject: &str, now: Instant) -> bool {
+        let key = (rule.id.clone(), subject.to_string());
+        if let Some(last) = self.last_fired.get(&key) {
+            if now.duration_since(*last) < rule.cooldown() {
+                return false;
+            }
+        }
+        self.last_fired.insert(key, now);
+        true
+    }
+
+    /// Record that `subject` matched; true when it was not already present.
+    fn arrive(&mut self, rule: &AlertRule, subject: &str, now: Instant) -> bool {
+        let previous = self.present.insert((rule.id.clone(), subject.to_string()), now);
+        previous.is_none_or(|seen| now.duration_since(seen) >= ABSENCE)
+    }
+
+    fn positions(&mut self, rules: &[AlertRule], sightings: &[Sighting], now: Instant) -> Vec<(usize, FiredAlert)> {
+        let mut fired = Vec::new();
+        for (index, rule) in rules.iter().enumerate().filter(|(_, rule)| rule.enabled) {
+            let Condition::Watchlist { aircraft, vessels } = &rule.condition else {
+                continue;
+            };
+            for sighting in sightings {
+                let list = if sighting.kind == Target::Vessel { vessels } else { aircraft };
+                let matched = list.iter().any(|entry| {
+                    let entry = entry.trim();
+                    !entry.is_empty()
+                        && (entry.eq_ignore_ascii_case(sighting.id) || entry.eq_ignore_ascii_case(sighting.label.trim()))
+                });
+                if !matched || !self.arrive(rule, sighting.id, now) || !self.take(rule, sighting.id, now) {
+                    continue;
+                }
+                let body = format!("{} sighted at {:.3}, {:.3}", sighting.describe(), sighting.lat, sighting.lon);
+                fired.push((index, firing(rule, body, sighting.id)));
+            }
+        }
+        self.present.retain(|_, seen| now.duration_since(*seen) < ABSENCE);
+        fired
+    }
+
+    fn geofence_events(&mut self, rules: &[AlertRule], events: &[GeofenceEvent], now: Instant) -> Vec<(usize, FiredAlert)> {
+        let mut fired = Vec::new();
+        for (index, rule) in rules.iter().enumerate().filter(|(_, rule)| rule.enabled) {
+            let Condition::Geofence { fence, transitions } = &rule.condition else {
+                continue;
+            };
+            for event in events.iter().filter(|e| e.fence_id == *fence && transitions.contains(&e.transition)) {
+                if !self.take(rule, &event.subject, now) {
+                    continue;
+                }
+                let sighting = Sighting {
+                    kind: event.target,
+                    id: &event.subject,
+                    label: &event.label,
+                    imo: None,
+                    lat: event.lat,
+                    lon: event.lon,
+                };
+                let minutes = event.inside_secs / 60;
+                let body = match event.transition {
+                    Transition::Enter => format!("{} entered {}", sighting.describe(), event.fence_name),
+                    Transition::Exit => format!("{} left {} after {minutes} min", sighting.describe(), event.fence_name),
+                    Transition::Dwell => format!("{} has been in {} for {minutes} min", sighting.describe(), event.fence_name),
+                };
+                fired.push((index, firing(rule, body, &event.subject)));
+            }
+        }
+        fired
+    }
+
+    fn headlines(&mut self, rules: &[AlertRule], headlines: &[Headline], now: Instant) -> Vec<(usize, FiredAlert)> {
+        let mut fired = Vec::new();
+        for (index, rule) in rules.iter().enumerate().filter(|(_, rule)| rule.enabled) {
+            let Condition::Keyword { keywords, sources } = &rule.condition else {
+                continue;
+            };
+            let keywords: Vec<String> = keywords
+                .iter()
+                .map(|k| k.trim().to_lowercase())
+                .filter(|k| !k.is_empty())
+                .collect();
+            let matches: Vec<&Headline> = headlines
+                .iter()
+                .filter(|h| sources.is_empty() || sources.iter().any(|s| s.eq_ignore_ascii_case(h.source)))
+                .filter(|h| {
+                    let title = h.title.to_lowercase();
+                    keywords.iter().any(|k| title.contains(k.as_str()))
+                })
+                .collect();
+            let Some(first) = matches.first() else {
+                continue;
+            };
+            // One alert per batch; the cooldown is per rule, not per headline.
+            if !self.take(rule, "", now) {
+                continue;
+            }
+            let body = match matches.len() {
+                1 => format!("{}: {}", first.source, first.title),
+                n => format!("{}: {} (+{} more)", first.source, first.title, n - 1),
+            };
+            fired.push((index, firing(rule, body, first.link)));
+        }
+        fired
+    }
+
+    fn metric(&mut self, rules: &[AlertRule], name: &str, value: f64, now: Instant) -> Vec<(usize, FiredAlert)> {
+        let mut fired = Vec::new();
+        for (index, rule) in rules.iter().enumerate().filter(|(_, rule)| rule.enabled) {
+            let Condition::Threshold { metric, comparison, value: limit } = &rule.condition else {
+                continue;
+            };
+            if metric != name {
+                continue;
+            }
+            let breaching = match comparison {
+                Comparison::Above => value > *limit,
+                Comparison::Below => value < *limit,
+            };
+            let was = self.breaching.insert(rule.id.clone(), breaching).unwrap_or(false);
+            if !breaching || was || !self.take(rule, name, now) {
+                continue;
+            }
+            let direction = if *comparison == Comparison::Above { "above" } else { "below" };
+            fired.push((index, firing(rule, format!("{name} is {value} ({direction} {limit})"), name)));
+        }
+        fired
+    }
+
+    fn watchlist_hits(&mut self, rules: &[AlertRule], hits: &[WatchlistHit], now: Instant) -> Vec<(usize, FiredAlert)> {
+        let mut fired = Vec::new();
+        for (index, rule) in rules.iter().enumerate().filter(|(_, rule)| rule.enabled) {
+            let Condition::Watchlists { kinds } = &rule.condition else {
+                continue;
+            };
+            for hit in hits.iter().filter(|hit| kinds.is_empty() || kinds.contains(&hit.kind)) {
+                // A vessel in two listed regions is two subjects.
+                let key = format!("{}:{}:{}", hit.kind.as_str(), hit.entry, hit.subject);
+                if !self.arrive(rule, &key, now) || !self.take(rule, &key, now) {
+                    continue;
+                }
+                fired.push((index, firing(rule, hit.description.clone(), &hit.subject)));
+            }
+        }
+        self.present.retain(|_, seen| now.duration_since(*seen) < ABSENCE);
+        fired
+    }
+
+    fn space_weather(&mut self, rules: &[AlertRule], events: &[SpaceWeatherEvent], now: Instant) -> Vec<(usize, FiredAlert)> {
+        let mut fired = Vec::new();
+        for (index, rule) in rules.iter().enumerate().filter(|(_, rule)| rule.enabled) {
+            let Condition::SpaceWeather { storm, flare } = &rule.condition else {
+                continue;
+            };
+            let flare = flare.as_deref().and_then(crate::swpc::flare_flux);
+            for event in events {
+                // Fire as the event crosses into the rule's range.
+                let crossed = match event.change {
+                    SpaceWeatherChange::Storm { level, previous } => {
+                        storm.is_some_and(|min| level >= min && previous < min)
+                    }
+                    SpaceWeatherChange::Flare { flux, previous } => {
+                        flare.is_some_and(|min| flux >= min && previous.is_none_or(|p| p < min))
+                    }
+                };
+                if crossed && self.take(rule, &event.subject, now) {
+                    fired.push((index, firing(rule, event.description.clone(), &event.subject)));
+                }
+            }
+        }
+        fired
+    }
+
+    fn anomalies(&mut self, rules: &[AlertRule], anomalies: &[FlightAnomaly], now: Instant) -> Vec<(usize, FiredAlert)> {
+        let mut fired = Vec::new();
+        for (index, rule) in rules.iter().enumerate().filter(|(_, rule)| rule.enabled) {
+            let Condition::Anomaly { kinds, min_score } = &rule.condition else {
+                continue;
+            };
+            let matching = anomalies
+                .iter()
+                .filter(|anomaly| anomaly.score >= *min_score)
+                .filter(|anomaly| kinds.is_empty() || kinds.contains(&anomaly.kind));
+            for anomaly in matching {
+                // A squawk and a jump on one aircraft are two subjects.
+                let key = format!("{}:{}", anomaly.kind.as_str(), anomaly.icao24);
+                if self.take(rule, &key, now) {
+                    let body = format!("{} (score {:.2})", anomaly.description, anomaly.score);
+                    fired.push((index, firing(rule, body, &anomaly.icao24)));
+                }
+            }
+        }
+        fired
+    }
+
+    fn hazards(&mut self, rules: &[AlertRule], events: &[HazardEvent], now: Instant) -> Vec<(usize, FiredAlert)> {
+        let mut fired = Vec::new();
+        for (index, rule) in rules.iter().enumerate().filter(|(_, rule)| rule.enabled) {
+            let Condition::Hazard { kinds, min_level } = &rule.condition else {
+                continue;
+            };
+            // Fire as the hazard crosses into the rule's range.
+            let matching = events
+                .iter()
+                .filter(|event| kinds.is_empty() || kinds.contains(&event.kind))
+                .filter(|event| event.level >= *min_level && event.previous.is_none_or(|p| p < *min_level));
+            for event in matching {
+                if self.take(rule, &event.subject, now) {
+                    fired.push((index, firing(rule, event.description.clone(), &event.subject)));
+                }
+            }
+        }
+        fired
+    }
+
+    /// Drop cooldowns that have run out, or whose rule is gone; they no
+    /// longer hold anything back.
+    fn prune(&mut self, rules: &[AlertRule], now: Instant) {
+        let cooldowns: HashMap<&str, Duration> = rules.iter().map(|rule| (rule.id.as_str(), rule.cooldown())).collect();
+        self.last_fired
+            .retain(|(id, _), last| cooldowns.get(id.as_str()).is_some_and(|cooldown| now.duration_since(*last) < *cooldown));
+    }
+
+    /// Drop tracking for a rule that changed or was deleted.
+    fn forget(&mut self, rule_id: &str) {
+        self.present.retain(|(id, _), _| id != rule_id);
+        self.last_fired.retain(|(id, _), _| id != rule_id);
+        self.breaching.remove(rule_id);
+    }
+}
+
+fn firing(rule: &AlertRule, body: String, subject: &str) -> FiredAlert {
+    FiredAlert {
+        rule_id: rule.id.clone(),
+        rule_name: rule.name.clone(),
+        title: rule.name.clone(),
+        body,
+        subject: subject.to_string(),
+        fired_at: crate::unix_timestamp_secs(),
+    }
+}
+
+/// Managed state: the rules, their tracking, and recent alerts.
+pub struct AlertsState {
+    path: PathBuf,
+    /// The file could not be read and could not be moved aside, so it is
+    /// not overwritten.
+    unquarantined: bool,
+    rules: Mutex<Vec<AlertRule>>,
+    engine: Mutex<Engine>,
+    fired: Mutex<VecDeque<FiredAlert>>,
+}
+
+impl AlertsState {
+    /// Load `alert-rules.json`; a missing file means no rules, and an
+    /// unreadable one is moved aside (see `load_json_file`).
+    pub fn load(path: PathBuf) -> Self {
+        let (rules, unquarantined): (Vec<AlertRule>, _) = crate::load_json_file(&path);
+        AlertsState {
+            path,
+            unquarantined,
+            rules: Mutex::new(rules),
+            engine: Mutex::default(),
+            fired: Mutex::default(),
+        }
+    }
+
+    fn persist(&self, rules: &[AlertRule]) -> Result<(), String> {
+        if self.unquarantined {
+            return Err(format!("Alert rules {} are unreadable and could not be moved aside", self.path.display()));
+        }
+        let serialized =
+            serde_json::to_string_pretty(rules).map_err(|e| format!("Failed to serialize alert rules: {e}"))?;
+        fs::write(&self.path, serialized)
+            .map_err(|e| format!("Failed to write alert rules {}: {e}", self.path.display()))
+    }
+}
+
+/// Run `evaluate` against the enabled rules and deliver whatever fired.
+fn evaluate(
+    app: &AppHandle,
+    evaluate: impl FnOnce(&mut Engine, &[AlertRule], Instant) -> Vec<(usize, FiredAlert)>,
+) {
+    let Some(state) = app.try_state::<AlertsState>() else {
+        return;
+    };
+    let fired: Vec<(Delivery, FiredAlert)> = {
+        let rules = state.rules.lock().unwrap_or_else(|e| e.into_inner());
+        if !rules.iter().any(|rule| rule.enabled) {
+            return;
+        }
+        let mut engine = state.engine.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        engine.prune(&rules, now);
+        evaluate(&mut engine, &rules, now)
+            .into_iter()
+            .map(|(index, alert)| (rules[index].delivery.clone(), alert))
+            .collect()
+    };
+    for (delivery, alert) in fired {
+        deliver(app, &state, delivery, alert);
+    }
+}
+
+fn deliver(app: &AppHandle, state: &AlertsState, delivery: Delivery, alert: FiredAlert) {
+    tracing::info!(target: "app", rule = %alert.rule_name, subject = %alert.subject, "alert fired");
+    if delivery.notify && !crate::digest::holds(app, delivery.urgency) {
+        let action = format!("alert:{}", alert.rule_id);
+        if let Err(err) =
+            crate::notifications::send(app, "main", &alert.title, &alert.body, delivery.urgency, Some(&action))
+        {
+            tracing::warn!(target: "app", "alert notification failed: {err}");
+        }
+    }
+    crate::speech::announce_alert(app, &alert, delivery.urgency);
+    crate::webhooks::dispatch(app, &alert, delivery.webhook_targets());
+    crate::mqtt::publish_alert(app, &alert);
+    let _ = app.emit(FIRED_EVENT, &alert);
+    let mut fired = state.fired.lock().unwrap_or_else(|e| e.into_inner());
+    if fired.len() == MAX_FIRED {
+        fired.pop_front();
+    }
+    fired.push_back(alert);
+}
+
+/// Alerts fired at or after `since` (seconds), oldest first.
+pub fn fired_since(app: &AppHandle, since: u64) -> Vec<FiredAlert> {
+    let Some(state) = app.try_state::<AlertsState>() else {
+        return Vec::new();
+    };
+    let fired = state.fired.lock().unwrap_or_else(|e| e.into_inner());
+    fired.iter().filter(|alert| alert.fired_at >= since).cloned().collect()
+}
+
+/// Check aircraft or vessel positions against watchlist rules.
+pub fn observe_positions(app: &AppHandle, sightings: &[Sighting]) {
+    if sightings.is_empty() {
+        return;
+    }
+    evaluate(app, |engine, rules, now| engine.positions(rules, sightings, now));
+}
+
+/// Check the transitions `geofences` reported against geofence rules.
+pub fn observe_geofence_events(app: &AppHandle, events: &[GeofenceEvent]) {
+    if events.is_empty() {
+        return;
+    }
+    evaluate(app, |engine, rules, now| engine.geofence_events(rules, events, now));
+}
+
+/// Check new headlines against keyword rules.
+pub fn observe_headlines(app: &AppHandle, headlines: &[Headline]) {
+    if headlines.is_empty() {
+        return;
+    }
+    evaluate(app, |engine, rules, now| engine.headlines(rules, headlines, now));
+}
+
+/// Check hits on the saved watchlists against `Watchlists` rules.
+pub fn observe_watchlist_hits(app: &AppHandle, hits: &[WatchlistHit]) {
+    if hits.is_empty() {
+        return;
+    }
+    evaluate(app, |engine, rules, now| engine.watchlist_hits(rules, hits, now));
+}
+
+/// Check storm and flare changes against `SpaceWeather` rules.
+pub fn observe_space_weather(app: &AppHandle, events: &[SpaceWeatherEvent]) {
+    if events.is_empty() {
+        return;
+    }
+    evaluate(app, |engine, rules, now| engine.space_weather(rules, events, now));
+}
+
+/// Check flight anomalies against `Anomaly` rules.
+pub fn observe_anomalies(app: &AppHandle, anomalies: &[FlightAnomaly]) {
+    if anomalies.is_empty() {
+        return;
+    }
+    evaluate(app, |engine, rules, now| engine.anomalies(rules, anomalies, now));
+}
+
+/// Check new or raised hazards against `Hazard` rules.
+pub fn observe_hazards(app: &AppHandle, events: &[HazardEvent]) {
+    if events.is_empty() {
+        return;
+    }
+    evaluate(app, |engine, rules, now| engine.hazards(rules, events, now));
+}
+
+/// Check a metric's new value against threshold rules.
+pub fn record_metric(app: &AppHandle, name: &str, value: f64) {
+    crate::mqtt::publish_metric(app, name, value);
+    evaluate(app, |engine, rules, now| engine.metric(rules, name, value, now));
+}
+
+#[tauri::command]
+pub fn list_alert_rules(webview: Webview, state: tauri::State<'_, AlertsState>) -> Result<Vec<AlertRule>, String> {
+    crate::metrics::of(&webview).observe("list_alert_rules", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.rules.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+/// Add a rule, or replace the one with the same id.
+#[tauri::command]
+pub async fn save_alert_rule(webview: Webview, app: AppHandle, mut rule: AlertRule) -> Result<AlertRule, String> {
+    crate::metrics::of(&webview)
+        .observe_async("save_alert_rule", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                let state = app.state::<AlertsState>();
+                rule.validate()?;
+                if rule.id.is_empty() {
+                    let mut buf = [0u8; 8];
+                    getrandom::getrandom(&mut buf).map_err(|e| format!("Failed to generate rule id: {e}"))?;
+                    rule.id = buf.iter().map(|b| format!("{b:02x}")).collect();
+                }
+                let mut rules = state.rules.lock().unwrap_or_else(|e| e.into_inner());
+                let mut updated = rules.clone();
+                match updated.iter_mut().find(|existing| existing.id == rule.id) {
+                    Some(existing) => *existing = rule.clone(),
+                    None => updated.push(rule.clone()),
+                }
+                state.persist(&updated)?;
+                *rules = updated;
+                state.engine.lock().unwrap_or_else(|e| e.into_inner()).forget(&rule.id);
+                Ok(rule)
+            })
+            .await
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn delete_alert_rule(webview: Webview, app: AppHandle, id: String) -> Result<bool, String> {
+    crate::metrics::of(&webview)
+        .observe_async("delete_alert_rule", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                let state = app.state::<AlertsState>();
+                let mut rules = state.rules.lock().unwrap_or_else(|e| e.into_inner());
+                let updated: Vec<AlertRule> = rules.iter().filter(|rule| rule.id != id).cloned().collect();
+                if updated.len() == rules.len() {
+                    return Ok(false);
+                }
+                state.persist(&updated)?;
+                *rules = updated;
+                state.engine.lock().unwrap_or_else(|e| e.into_inner()).forget(&id);
+                Ok(true)
+            })
+            .await
+        })
+        .await
+}
+
+/// Report a metric for threshold rules, e.g. a market price the dashboard
+/// tracks. `alerts`, `alerts.critical` and `headlines.breaking` are reported
+/// from `update_alert_status`.
+#[tauri::command]
+pub fn record_alert_metric(webview: Webview, app: AppHandle, metric: String, value: f64) -> Result<(), String> {
+    crate::metrics::of(&webview).observe("record_alert_metric", || {
+        crate::require_trusted_window(webview.label())?;
+        if !value.is_finite() {
+            return Err("Metric value must be finite".to_string());
+        }
+        record_metric(&app, &metric, value);
+        Ok(())
+    })
+}
+
+/// The most recent alerts, oldest first.
+#[tauri::command]
+pub fn get_fired_alerts(webview: Webview, state: tauri::State<'_, AlertsState>) -> Result<Vec<FiredAlert>, String> {
+    crate::metrics::of(&webview).observe("get_fired_alerts", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.fired.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect())
+    })
+}
+
+#[cfg(test)]
+mod alerts_tests {
+    use super::*;
+
+    fn rule(id: &str, condition: Condition) -> AlertRule {
+        AlertRule {
+            id: id.to_string(),
+            name: format!("rule {id}"),
+            enabled: true,
+            condition,
+            delivery: Delivery::default(),
+            cooldown_secs: 300,
+        }
+    }
+
+    fn fence_event(fence: &str, transition: Transition, subject: &str) -> GeofenceEvent {
+        GeofenceEvent {
+            fence_id: fence.to_string(),
+            fence_name: "Hormuz".to_string(),
+            transition,
+            target: Target::Vessel,
+            subject: subject.to_string(),
+            label: String::new(),
+            lat: 26.0,
+            lon: 56.0,
+            inside_secs: 1_200,
+            at: 0,
+        }
+    }
+
+    #[test]
+    fn geofence_rules_follow_fence_transitions() {
+        let condition = |transitions| Condition::Geofence { fence: "f1".to_string(), transitions };
+        let rules = [rule("enter", condition(default_transitions())), rule("exit", condition(vec![Transition::Exit]))];
+        let mut engine = Engine::default();
+        let start = Instant::now();
+
+        let fired = engine.geofence_events(&rules, &[fence_event("f1", Transition::Enter, "1")], start);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].1.body, "Vessel 1 entered Hormuz");
+        // Other fences and unwanted transitions don't fire.
+        assert!(engine.geofence_events(&rules, &[fence_event("f2", Transition::Enter, "2")], start).is_empty());
+        assert!(engine.geofence_events(&rules, &[fence_event("f1", Transition::Dwell, "1")], start).is_empty());
+        let fired = engine.geofence_events(&rules, &[fence_event("f1", Transition::Exit, "1")], start);
+        assert_eq!(fired[0].1.body, "Vessel 1 left Hormuz after 20 min");
+        // Entering again within the cooldown is held back.
+        let later = start + Duration::from_secs(60);
+        assert!(engine.geofence_events(&rules, &[fence_event("f1", Transition::Enter, "1")], later).is_empty());
+    }
+
+    #[test]
+    fn watchlist_matches_id_or_label() {
+        let rules = [rule(
+            "w",
+            Condition::Watchlist { aircraft: vec!["RCH123".to_string()], vessels: vec![] },
+        )];
+        let mut engine = Engine::default();
+        let now = Instant::now();
+        let plane = Sighting { kind: Target::Aircraft, id: "ae1234", label: "rch123 ", imo: None, lat: 1.0, lon: 2.0 };
+        let fired = engine.positions(&rules, &[plane], now);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].1.subject, "ae1234");
+        let again = Sighting { kind: Target::Aircraft, id: "ae1234", label: "RCH123", imo: None, lat: 1.1, lon: 2.0 };
+        assert!(engine.positions(&rules, &[again], now + Duration::from_secs(60)).is_empty());
+    }
+
+    #[test]
+    fn keyword_rule_batches_matches_and_cools_down() {
+        let rules = [rule(
+            "k",
+            Condition::Keyword { keywords: vec!["Strait".to_string()], sources: vec![] },
+        )];
+        let mut engine = Engine::default();
+        let now = Instant::now();
+        let headlines = [
+            Headline { source: "Reuters", title: "Ships turn back from strait", link: "a" },
+            Headline { source: "BBC", title: "Weather", link: "b" },
+            Headline { source: "AP", title: "STRAIT closed", link: "c" },
+        ];
+        let fired = engine.headlines(&rules, &headlines, now);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].1.body, "Reuters: Ships turn back from strait (+1 more)");
+        assert!(engine.headlines(&rules, &headlines, now + Duration::from_secs(10)).is_empty());
+        assert_eq!(engine.headlines(&rules, &headlines, now + Duration::from_secs(301)).len(), 1);
+    }
+
+    #[test]
+    fn saved_watchlist_hits_fire_per_kind_and_subject() {
+        let rules = [rule("s", Condition::Watchlists { kinds: vec![WatchKind::Region] })];
+        let mut engine = Engine::default();
+        let now = Instant::now();
+        let hit = |kind, subject: &str| WatchlistHit {
+            kind,
+            entry: "Hormuz".to_string(),
+            subject: subject.to_string(),
+            description: format!("{subject} entered Hormuz"),
+        };
+        let hits = [hit(WatchKind::Region, "1"), hit(WatchKind::Region, "2"), hit(WatchKind::Vessel, "1")];
+        let fired = engine.watchlist_hits(&rules, &hits, now);
+        assert_eq!(fired.len(), 2);
+        assert_eq!(fired[1].1.body, "2 entered Hormuz");
+        // Still there a minute later: no repeat.
+        assert!(engine.watchlist_hits(&rules, &hits, now + Duration::from_secs(60)).is_empty());
+    }
+
+    #[test]
+    fn space_weather_fires_as_storms_and_flares_cross_the_level() {
+        let json = r#"{"name":"Space weather","condition":{"kind":"spaceWeather"}}"#;
+        let parsed: AlertRule = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.condition, Condition::SpaceWeather { storm: Some(3), flare: Some("X1".to_string()) });
+        let rules = [rule("w", parsed.condition)];
+        let mut engine = Engine::default();
+        let now = Instant::now();
+        let event = |subject: &str, change| SpaceWeatherEvent {
+            subject: subject.to_string(),
+            change,
+            description: subject.to_string(),
+        };
+        let events = [
+            event("storm:1", SpaceWeatherChange::Storm { level: 2, previous: 0 }),
+            event("storm:2", SpaceWeatherChange::Storm { level: 4, previous: 2 }),
+            event("storm:3", SpaceWeatherChange::Storm { level: 5, previous: 4 }),
+            event("flare:1", SpaceWeatherChange::Flare { flux: 5e-5, previous: None }),
+            event("flare:2", SpaceWeatherChange::Flare { flux: 2e-4, previous: Some(5e-5) }),
+            event("flare:3", SpaceWeatherChange::Flare { flux: 3e-4, previous: Some(2e-4) }),
+        ];
+        let fired = engine.space_weather(&rules, &events, now);
+        let subjects: Vec<&str> = fired.iter().map(|(_, alert)| alert.subject.as_str()).collect();
+        assert_eq!(subjects, ["storm:2", "flare:2"]);
+
+        let mut bad = rule("b", Condition::SpaceWeather { storm: Some(6), flare: None });
+        assert!(bad.validate().is_err());
+        bad.condition = Condition::SpaceWeather { storm: None, flare: Some("Q1".to_string()) };
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn anomalies_fire_by_kind_and_score() {
+        let json = r#"{"name":"Emergencies","condition":{"kind":"anomaly","kinds":["squawk","altitudeDrop"]}}"#;
+        let parsed: AlertRule = serde_json::from_str(json).unwrap();
+        let rules = [rule("a", parsed.condition)];
+        let mut engine = Engine::default();
+        let now = Instant::now();
+        let anomaly = |kind, icao24: &str, score| FlightAnomaly {
+            kind,
+            icao24: icao24.to_string(),
+            callsign: String::new(),
+            score,
+            lat: 0.0,
+            lon: 0.0,
+            time: 0,
+            description: format!("Aircraft {icao24}"),
+        };
+        let anomalies = [
+            anomaly(AnomalyKind::Squawk, "a", 0.9),
+            anomaly(AnomalyKind::AltitudeDrop, "a", 0.6),
+            anomaly(AnomalyKind::AltitudeDrop, "b", 0.4),
+            anomaly(AnomalyKind::PositionJump, "c", 1.0),
+        ];
+        let fired = engine.anomalies(&rules, &anomalies, now);
+        assert_eq!(fired.len(), 2);
+        assert_eq!(fired[0].1.body, "Aircraft a (score 0.90)");
+        assert_eq!(fired[1].1.subject, "a");
+        assert!(engine.anomalies(&rules, &anomalies, now + Duration::from_secs(60)).is_empty());
+
+        let bad = rule("b", Condition::Anomaly { kinds: vec![], min_score: 1.5 });
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn hazards_fire_as_they_reach_the_level() {
+        let json = r#"{"name":"Volcanoes","condition":{"kind":"hazard","kinds":["volcano","earthquake"],"min_level":"watch"}}"#;
+        let parsed: AlertRule = serde_json::from_str(json).unwrap();
+        let rules = [rule("h", parsed.condition)];
+        let mut engine = Engine::default();
+        let now = Instant::now();
+        let event = |kind, subject: &str, level, previous| HazardEvent {
+            kind,
+            level,
+            previous,
+            subject: subject.to_string(),
+            description: subject.to_string(),
+        };
+        let events = [
+            event(HazardKind::Volcano, "volcano:1", HazardLevel::Advisory, None),
+            event(HazardKind::Volcano, "volcano:2", HazardLevel::Watch, Some(HazardLevel::Advisory)),
+            event(HazardKind::Volcano, "volcano:3", HazardLevel::Warning, Some(HazardLevel::Watch)),
+            event(HazardKind::Tsunami, "tsunami:a", HazardLevel::Warning, None),
+            event(HazardKind::Earthquake, "quake:b", HazardLevel::Watch, None),
+        ];
+        let fired = engine.hazards(&rules, &events, now);
+        let subjects: Vec<&str> = fired.iter().map(|(_, alert)| alert.subject.as_str()).collect();
+        assert_eq!(subjects, ["volcano:2", "quake:b"]);
+    }
+
+    #[test]
+    fn threshold_fires_on_crossing() {
+        let mut rules = [rule(
+            "t",
+            Condition::Threshold { metric: "alerts.critical".to_string(), comparison: Comparison::Above, value: 3.0 },
+        )];
+        rules[0].cooldown_secs = 0;
+        let mut engine = Engine::default();
+        let now = Instant::now();
+        assert!(engine.metric(&rules, "alerts.critical", 2.0, now).is_empty());
+        assert_eq!(engine.metric(&rules, "alerts.critical", 5.0, now).len(), 1);
+        assert!(engine.metric(&rules, "alerts.critical", 6.0, now).is_empty());
+        assert!(engine.metric(&rules, "alerts.critical", 1.0, now).is_empty());
+        assert_eq!(engine.metric(&rules, "alerts.critical", 4.0, now).len(), 1);
+        assert!(engine.metric(&rules, "other", 10.0, now).is_empty());
+    }
+
+    #[test]
+    fn prunes_cooldowns_that_ran_out() {
+        let rules = [rule(
+            "t",
+            Condition::Threshold { metric: "alerts.critical".to_string(), comparison: Comparison::Above, value: 3.0 },
+        )];
+        let mut engine = Engine::default();
+        let now = Instant::now();
+        assert_eq!(engine.metric(&rules, "alerts.critical", 5.0, now).len(), 1);
+        engine.prune(&rules, now + Duration::from_secs(60));
+        assert_eq!(engine.last_fired.len(), 1);
+        engine.prune(&rules, now + Duration::from_secs(300));
+        assert!(engine.last_fired.is_empty());
+    }
+
+    #[test]
+    fn rules_round_trip_and_validate() {
+        let json = r#"{"name":"Hormuz","condition":{"kind":"geofence","fence":"f1"},"delivery":{"urgency":"critical","webhook":"https://example.com/hook"}}"#;
+        let parsed: AlertRule = serde_json::from_str(json).unwrap();
+        assert!(parsed.enabled);
+        assert_eq!(parsed.cooldown_secs, 300);
+        assert!(parsed.delivery.notify);
+        assert_eq!(parsed.delivery.urgency, NotificationUrgency::Critical);
+        assert_eq!(parsed.condition, Condition::Geofence { fence: "f1".to_string(), transitions: vec![Transition::Enter] });
+        assert!(parsed.validate().is_ok());
+
+        let mut bad = parsed.clone();
+        bad.condition = Condition::Geofence { fence: "f1".to_string(), transitions: vec![] };
+        assert!(bad.validate().is_err());
+        let mut bad = parsed;
+        bad.delivery.webhook = Some("file:///etc/passwd".to_string());
+        assert!(bad.validate().is_err());
+    }
+}
@@ -0,0 +1,448 @@
+//! Unusual aircraft behaviour, flagged in the shell as `opensky` publishes
+//! positions (network and local receiver). Each batch is handed to a
+//! background worker that keeps a short history per aircraft and looks for:
+//!
+//! - emergency squawks: 7500 (unlawful interference), 7700 (general
+//!   emergency) and 7600 (radio failure), when an aircraft starts squawking
+//!   one;
+//! - sudden altitude drops: an airborne descent faster than
+//!   `anomalies.descent_rate` metres per second between two reports;
+//! - circling: turning through `anomalies.circling_turns` full turns within
+//!   `anomalies.circling_window_secs` inside a watchlist region;
+//! - position jumps: an implied ground speed above `anomalies.max_speed`,
+//!   the signature of GPS jamming or spoofing. Several aircraft jumping near
+//!   each other within the window score higher.
+//!
+//! Every anomaly has a score from 0 to 1; those reaching
+//! `anomalies.min_score` go to the alert engine, where `anomaly` rules pick
+//! kinds and a minimum score, and out as `anomalies://detected`. The last
+//! ones are kept for `get_flight_anomalies`.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use geo::{Distance, Haversine, Point};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, Webview};
+use tokio::sync::mpsc;
+
+use crate::ais::BoundingBox;
+use crate::config::{AnomaliesConfig, DesktopConfigState};
+
+pub const DETECTED_EVENT: &str = "anomalies://detected";
+
+const MAX_RECENT: usize = 200;
+/// Aircraft not reported for this long are forgotten.
+const HISTORY_SECS: u64 = 30 * 60;
+/// Reports further apart than this are not compared for rates or turns.
+const MAX_GAP_SECS: u64 = 300;
+/// The same anomaly on the same aircraft is flagged again after this long.
+const REFLAG_SECS: u64 = 10 * 60;
+/// Descents shorter than this are noise, whatever the rate.
+const MIN_DROP_M: f64 = 300.0;
+/// Jumps shorter than this are left to the speed check alone.
+const MIN_JUMP_M: f64 = 5_000.0;
+/// Other jumps within this distance and `circling_window_secs` count
+/// towards a jamming cluster.
+const CLUSTER_RADIUS_M: f64 = 150_000.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AnomalyKind {
+    Squawk,
+    AltitudeDrop,
+    Circling,
+    PositionJump,
+}
+
+impl AnomalyKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AnomalyKind::Squawk => "squawk",
+            AnomalyKind::AltitudeDrop => "altitudeDrop",
+            AnomalyKind::Circling => "circling",
+            AnomalyKind::PositionJump => "positionJump",
+        }
+    }
+}
+
+/// Payload of `anomalies://detected`, one per anomaly.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightAnomaly {
+    pub kind: AnomalyKind,
+    pub icao24: String,
+    /// May be empty.
+    pub callsign: String,
+    /// 0 to 1.
+    pub score: f64,
+    pub lat: f64,
+    pub lon: f64,
+    /// Seconds since the epoch, of the report that showed it.
+    pub time: u64,
+    pub description: String,
+}
+
+/// The parts of a state vector row the detectors use.
+#[derive(Clone, Debug, PartialEq)]
+struct Report {
+    icao24: String,
+    callsign: String,
+    time: u64,
+    lat: f64,
+    lon: f64,
+    /// Barometric, else geometric, in metres.
+    altitude: Option<f64>,
+    on_ground: bool,
+    /// Degrees clockwise from north.
+    track: Option<f64>,
+    squawk: Option<String>,
+}
+
+impl Report {
+    /// Read an OpenSky state vector row; `time` stands in for a missing
+    /// `time_position`.
+    fn parse(row: &Value, time: u64) -> Option<Self> {
+        let field = |i: usize| row.get(i).filter(|v| !v.is_null());
+        Some(Report {
+            icao24: field(0)?.as_str()?.to_string(),
+            callsign: field(1).and_then(Value::as_str).unwrap_or("").trim().to_string(),
+            time: field(3).and_then(Value::as_u64).unwrap_or(time),
+            lon: field(5)?.as_f64()?,
+            lat: field(6)?.as_f64()?,
+            altitude: field(7).or(field(13)).and_then(Value::as_f64),
+            on_ground: field(8).and_then(Value::as_bool).unwrap_or(false),
+            track: field(10).and_then(Value::as_f64),
+            squawk: field(14).and_then(Value::as_str).map(str::to_string),
+        })
+    }
+
+    fn describe(&self) -> String {
+        if self.callsign.is_empty() {
+            format!("Aircraft {}", self.icao24)
+        } else {
+            format!("Aircraft {} ({})", self.callsign, self.icao24)
+        }
+    }
+
+    fn anomaly(&self, kind: AnomalyKind, score: f64, description: String) -> FlightAnomaly {
+        FlightAnomaly {
+            kind,
+            icao24: self.icao24.clone(),
+            callsign: self.callsign.clone(),
+            score: score.clamp(0.0, 1.0),
+            lat: self.lat,
+            lon: self.lon,
+            time: self.time,
+            description,
+        }
+    }
+
+    /// An anomaly when the squawk is an emergency code.
+    fn squawking(&self) -> Option<FlightAnomaly> {
+        let squawk = self.squawk.as_deref()?;
+        let (meaning, score) = emergency(squawk)?;
+        Some(self.anomaly(AnomalyKind::Squawk, score, format!("{} squawking {squawk} ({meaning})", self.describe())))
+    }
+}
+
+/// What an emergency squawk means and how much it scores.
+fn emergency(squawk: &str) -> Option<(&'static str, f64)> {
+    match squawk {
+        "7500" => Some(("unlawful interference", 1.0)),
+        "7700" => Some(("general emergency", 0.9)),
+        "7600" => Some(("radio failure", 0.6)),
+        _ => None,
+    }
+}
+
+/// Signed change from heading `from` to `to`, in -180..180.
+fn turn(from: f64, to: f64) -> f64 {
+    (to - from + 540.0).rem_euclid(360.0) - 180.0
+}
+
+struct Track {
+    last: Report,
+    /// Signed heading changes in degrees, with when they ended.
+    turns: VecDeque<(u64, f64)>,
+    flagged: HashMap<AnomalyKind, u64>,
+}
+
+impl Track {
+    /// True when `kind` may be flagged again at `time`, recording it.
+    fn flag(&mut self, kind: AnomalyKind, time: u64) -> bool {
+        if self.flagged.get(&kind).is_some_and(|last| time.saturating_sub(*last) < REFLAG_SECS) {
+            return false;
+        }
+        self.flagged.insert(kind, time);
+        true
+    }
+}
+
+/// Per-aircraft history and the detectors.
+struct Detector {
+    config: AnomaliesConfig,
+    tracks: HashMap<String, Track>,
+    /// Recent position jumps as (time, lat, lon).
+    jumps: VecDeque<(u64, f64, f64)>,
+}
+
+impl Detector {
+    fn new(config: AnomaliesConfig) -> Self {
+        Detector {
+            config,
+            tracks: HashMap::new(),
+            jumps: VecDeque::new(),
+        }
+    }
+
+    /// Compare each report with the aircraft's last one; `regions` are the
+    /// named areas where circling counts.
+    fn detect(&mut self, time: u64, reports: Vec<Report>, regions: &[(String, BoundingBox)]) -> Vec<FlightAnomaly> {
+        let mut found = Vec::new();
+        let window = self.config.circling_window_secs;
+        for report in reports {
+            let Some(track) = self.tracks.get_mut(&report.icao24) else {
+                found.extend(report.squawking());
+                let track = Track { last: report.clone(), turns: VecDeque::new(), flagged: HashMap::new() };
+                self.tracks.insert(report.icao24, track);
+                continue;
+            };
+            if report.time <= track.last.time {
+                continue;
+            }
+            let previous = std::mem::replace(&mut track.last, report.clone());
+
+            if report.squawk != previous.squawk {
+                found.extend(report.squawking());
+            }
+
+            let elapsed = report.time - previous.time;
+            if elapsed > MAX_GAP_SECS {
+                track.turns.clear();
+                continue;
+            }
+            let seconds = elapsed as f64;
+
+            let distance = Haversine.distance(Point::new(previous.lon, previous.lat), Point::new(report.lon, report.lat));
+            if distance >= MIN_JUMP_M && distance / seconds > self.config.max_speed {
+                let nearby = self
+                    .jumps
+                    .iter()
+                    .filter(|(_, lat, lon)| {
+                        Haversine.distance(Point::new(*lon, *lat), Point::new(report.lon, report.lat)) <= CLUSTER_RADIUS_M
+                    })
+                    .count();
+                self.jumps.push_back((report.time, report.lat, report.lon));
+                if track.flag(AnomalyKind::PositionJump, report.time) {
+                    let cluster = match nearby {
+                        0 => String::new(),
+                        n => format!(", {n} more nearby"),
+                    };
+                    found.push(report.anomaly(
+                        AnomalyKind::PositionJump,
+                        0.4 + 0.2 * nearby as f64,
+                        format!(
+                            "{} jumped {:.0} km in {elapsed} s{cluster}, possible GPS interference",
+                            report.describe(),
+                            distance / 1000.0
+                        ),
+                    ));
+                }
+                // The jump says nothing about how the aircraft turned.
+                track.turns.clear();
+                continue;
+            }
+
+            if let (false, false, Some(from), Some(to)) =
+                (previous.on_ground, report.on_ground, previous.altitude, report.altitude)
+            {
+                let rate = (from - to) / seconds;
+                if from - to >= MIN_DROP_M
+                    && rate >= self.config.descent_rate
+                    && track.flag(AnomalyKind::AltitudeDrop, report.time)
+                {
+                    found.push(report.anomaly(
+                        AnomalyKind::AltitudeDrop,
+                        0.5 * rate / self.config.descent_rate,
+                        format!(
+                            "{} dropped {:.0} m in {elapsed} s ({:.0} ft/min)",
+                            report.describe(),
+                            from - to,
+                            rate * 196.85
+                        ),
+                    ));
+                }
+            }
+
+            match (report.on_ground, previous.track, report.track) {
+                (false, Some(from), Some(to)) => track.turns.push_back((report.time, turn(from, to))),
+                _ => track.turns.clear(),
+            }
+            while track.turns.front().is_some_and(|(t, _)| report.time.saturating_sub(*t) > window) {
+                track.turns.pop_front();
+            }
+            let turned: f64 = track.turns.iter().map(|(_, degrees)| degrees).sum();
+            let needed = 360.0 * self.config.circling_turns;
+            if turned.abs() >= needed {
+                let region = regions.iter().find(|(_, bounds)| bounds.contains(report.lat, report.lon));
+                if let Some((name, _)) = region {
+                    if track.flag(AnomalyKind::Circling, report.time) {
+                        found.push(report.anomaly(
+                            AnomalyKind::Circling,
+                            0.5 * turned.abs() / needed,
+                            format!(
+                                "{} circled {:.1} times over {name} in {} min",
+                                report.describe(),
+                                turned.abs() / 360.0,
+                                window / 60
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+        self.tracks.retain(|_, track| time.saturating_sub(track.last.time) < HISTORY_SECS);
+        while self.jumps.front().is_some_and(|(t, ..)| time.saturating_sub(*t) > window) {
+            self.jumps.pop_front();
+        }
+        found
+    }
+}
+
+/// A published batch: its time and state vector rows.
+type Batch = (u64, Vec<Value>);
+
+#[derive(Default)]
+pub struct AnomaliesState {
+    /// Batches of state vector rows for the worker.
+    sender: Mutex<Option<mpsc::UnboundedSender<Batch>>>,
+    recent: Mutex<VecDeque<FlightAnomaly>>,
+}
+
+/// Hand a published batch of state vector rows to the worker. Returns at
+/// once; does nothing while detection is disabled.
+pub fn observe_aircraft(app: &AppHandle, time: u64, rows: &[Value]) {
+    let Some(state) = app.try_state::<AnomaliesState>() else {
+        return;
+    };
+    let sender = state.sender.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(sender) = sender {
+        let _ = sender.send((time, rows.to_vec()));
+    }
+}
+
+fn report(app: &AppHandle, anomalies: &[FlightAnomaly]) {
+    if anomalies.is_empty() {
+        return;
+    }
+    for anomaly in anomalies {
+        tracing::info!(target: "app", icao24 = %anomaly.icao24, kind = anomaly.kind.as_str(), score = anomaly.score, "flight anomaly");
+        let _ = app.emit(DETECTED_EVENT, anomaly);
+    }
+    crate::alerts::observe_anomalies(app, anomalies);
+    let state = app.state::<AnomaliesState>();
+    let mut recent = state.recent.lock().unwrap_or_else(|e| e.into_inner());
+    for anomaly in anomalies {
+        if recent.len() == MAX_RECENT {
+            recent.pop_front();
+        }
+        recent.push_back(anomaly.clone());
+    }
+}
+
+async fn run(app: AppHandle, config: AnomaliesConfig, mut batches: mpsc::UnboundedReceiver<Batch>) {
+    let min_score = config.min_score;
+    let mut detector = Detector::new(config);
+    while let Some((time, rows)) = batches.recv().await {
+        let reports = rows.iter().filter_map(|row| Report::parse(row, time)).collect();
+        let regions = crate::watchlists::regions(&app);
+        let found: Vec<FlightAnomaly> = detector
+            .detect(time, reports, &regions)
+            .into_iter()
+            .filter(|anomaly| anomaly.score >= min_score)
+            .collect();
+        report(&app, &found);
+    }
+}
+
+pub fn start(app: &AppHandle) {
+    let config = app.state::<DesktopConfigState>().snapshot().anomalies;
+    if !config.enabled {
+        return;
+    }
+    let (sender, receiver) = mpsc::unbounded_channel();
+    *app.state::<AnomaliesState>().sender.lock().unwrap_or_else(|e| e.into_inner()) = Some(sender);
+    tauri::async_runtime::spawn(run(app.clone(), config, receiver));
+}
+
+/// The most recent anomalies, oldest first.
+#[tauri::command]
+pub fn get_flight_anomalies(webview: Webview, state: tauri::State<'_, AnomaliesState>) -> Result<Vec<FlightAnomaly>, String> {
+    crate::metrics::of(&webview).observe("get_flight_anomalies", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.recent.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect())
+    })
+}
+
+#[cfg(test)]
+mod anomalies_tests {
+    use super::*;
+    use serde_json::json;
+
+    fn row(time: u64, lon: f64, lat: f64, altitude: f64, track: f64, squawk: Option<&str>) -> Value {
+        json!(["abc123", "TEST1 ", "X", time, time, lon, lat, altitude, false, 200.0, track, 0.0, null, altitude, squawk])
+    }
+
+    fn detect(detector: &mut Detector, time: u64, row: Value) -> Vec<FlightAnomaly> {
+        let regions = [("Box".to_string(), BoundingBox { south: 0.0, west: 0.0, north: 10.0, east: 10.0 })];
+        detector.detect(time, Report::parse(&row, time).into_iter().collect(), &regions)
+    }
+
+    #[test]
+    fn flags_squawks_drops_and_jumps() {
+        let mut detector = Detector::new(AnomaliesConfig::default());
+        assert!(detect(&mut detector, 100, row(100, 20.0, 20.0, 10_000.0, 90.0, Some("1000"))).is_empty());
+
+        let squawk = detect(&mut detector, 110, row(110, 20.01, 20.0, 10_000.0, 90.0, Some("7700")));
+        assert_eq!(squawk.len(), 1);
+        assert_eq!(squawk[0].kind, AnomalyKind::Squawk);
+        assert_eq!(squawk[0].description, "Aircraft TEST1 (abc123) squawking 7700 (general emergency)");
+        // Still squawking: not a new anomaly.
+        assert!(detect(&mut detector, 120, row(120, 20.02, 20.0, 10_000.0, 90.0, Some("7700"))).is_empty());
+
+        // 1200 m in 20 s is 60 m/s, twice the default rate.
+        let drop = detect(&mut detector, 140, row(140, 20.04, 20.0, 8_800.0, 90.0, Some("7700")));
+        assert_eq!(drop.len(), 1);
+        assert_eq!(drop[0].kind, AnomalyKind::AltitudeDrop);
+        assert_eq!(drop[0].score, 1.0);
+
+        // A degree of longitude in 10 s.
+        let jump = detect(&mut detector, 150, row(150, 21.04, 20.0, 8_800.0, 90.0, Some("7700")));
+        assert_eq!(jump.len(), 1);
+        assert_eq!(jump[0].kind, AnomalyKind::PositionJump);
+        assert_eq!(jump[0].score, 0.4);
+    }
+
+    #[test]
+    fn flags_circling_inside_regions_only() {
+        let config = AnomaliesConfig { circling_turns: 1.0, ..AnomaliesConfig::default() };
+        let circle = |detector: &mut Detector, lon: f64| {
+            let mut found = Vec::new();
+            for step in 0..=12 {
+                let time = 1_000 + step * 10;
+                found.extend(detect(detector, time, row(time, lon, 5.0, 3_000.0, (step * 30 % 360) as f64, None)));
+            }
+            found
+        };
+        let outside = circle(&mut Detector::new(config.clone()), 20.0);
+        assert!(outside.is_empty());
+        let inside = circle(&mut Detector::new(config), 5.0);
+        assert_eq!(inside.len(), 1);
+        assert_eq!(inside[0].kind, AnomalyKind::Circling);
+        assert_eq!(inside[0].description, "Aircraft TEST1 (abc123) circled 1.0 times over Box in 10 min");
+        assert_eq!(turn(350.0, 10.0), 20.0);
+        assert_eq!(turn(10.0, 350.0), -20.0);
+    }
+}
@@ -0,0 +1,67 @@
+//! Optional see-through main window (`AppearanceConfig`), for running the
+//! dashboard as a desktop overlay widget. The window is created transparent
+//! with the configured native backdrop, and the page is told to drop its
+//! opaque background through the `window-transparent` class on `<html>`.
+//! Both are fixed when the window is built, so changes apply on next launch.
+
+use tauri::window::{Color, Effect, EffectState, EffectsBuilder};
+use tauri::{Runtime, WebviewWindowBuilder};
+
+use crate::config::{AppearanceConfig, WindowEffect};
+
+/// Runs before the page's own scripts, which may find `<html>` not yet parsed.
+const TRANSPARENT_CLASS_SCRIPT: &str = "(function(){var apply=function(){document.documentElement.classList.add('window-transparent')};if(document.documentElement){apply()}else{document.addEventListener('readystatechange',apply,{once:true})}})();";
+
+/// The platform's closest native effect for `effect` on `os`
+/// (`std::env::consts::OS`).
+fn native_effect(effect: WindowEffect, os: &str) -> Option<Effect> {
+    match (os, effect) {
+        (_, WindowEffect::None) => None,
+        ("macos", WindowEffect::Vibrancy) => Some(Effect::UnderWindowBackground),
+        ("macos", WindowEffect::Acrylic) => Some(Effect::HudWindow),
+        ("macos", WindowEffect::Mica) => Some(Effect::Sidebar),
+        ("windows", WindowEffect::Vibrancy | WindowEffect::Acrylic) => Some(Effect::Acrylic),
+        ("windows", WindowEffect::Mica) => Some(Effect::Mica),
+        _ => None,
+    }
+}
+
+/// Configure a window builder for `appearance`; a no-op unless transparency
+/// is enabled.
+pub fn apply<'a, R: Runtime, M: tauri::Manager<R>>(
+    builder: WebviewWindowBuilder<'a, R, M>,
+    appearance: &AppearanceConfig,
+) -> WebviewWindowBuilder<'a, R, M> {
+    if !appearance.transparent {
+        return builder;
+    }
+    let builder = builder
+        .transparent(true)
+        .background_color(Color(0, 0, 0, 0))
+        .initialization_script(TRANSPARENT_CLASS_SCRIPT);
+    match native_effect(appearance.effect, std::env::consts::OS) {
+        Some(effect) => builder.effects(
+            EffectsBuilder::new()
+                .effect(effect)
+                .state(EffectState::FollowsWindowActiveState)
+                .build(),
+        ),
+        None => builder,
+    }
+}
+
+#[cfg(test)]
+mod appearance_tests {
+    use super::native_effect;
+    use crate::config::WindowEffect;
+    use tauri::window::Effect;
+
+    #[test]
+    fn maps_effects_to_each_platform() {
+        assert_eq!(native_effect(WindowEffect::Vibrancy, "macos"), Some(Effect::UnderWindowBackground));
+        assert_eq!(native_effect(WindowEffect::Vibrancy, "windows"), Some(Effect::Acrylic));
+        assert_eq!(native_effect(WindowEffect::Mica, "windows"), Some(Effect::Mica));
+        assert_eq!(native_effect(WindowEffect::Acrylic, "linux"), None);
+        assert_eq!(native_effect(WindowEffect::None, "macos"), None);
+    }
+}
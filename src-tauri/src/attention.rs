@@ -0,0 +1,55 @@
+//! Drawing the user's attention to new alerts from outside the dashboard.
+//! `notify_attention` is called when a high-priority alert fires and maps
+//! that onto what each platform has:
+//!
+//! - Windows: a flashing taskbar button.
+//! - macOS: a dock bounce.
+//! - Linux: the window manager's urgency hint.
+//!
+//! Nothing flashes while the dashboard is focused; the user is already there.
+//! The standing alert count and severity are badged by `badging`.
+
+use tauri::{AppHandle, Manager, UserAttentionType, Webview};
+
+use crate::badging::AlertSeverity;
+
+pub fn notify(app: &AppHandle, severity: Option<AlertSeverity>, flash: bool) -> Result<(), String> {
+    if !flash {
+        return Ok(());
+    }
+    request(app, severity == Some(AlertSeverity::Critical))
+}
+
+/// Flash the taskbar button / bounce the dock icon, unless the dashboard is
+/// focused. `critical` keeps it going until the user looks.
+pub fn request(app: &AppHandle, critical: bool) -> Result<(), String> {
+    let Some(window) = app.get_webview_window("main") else {
+        return Ok(());
+    };
+    if window.is_focused().unwrap_or(false) {
+        return Ok(());
+    }
+    let kind = if critical {
+        UserAttentionType::Critical
+    } else {
+        UserAttentionType::Informational
+    };
+    window
+        .request_user_attention(Some(kind))
+        .map_err(|e| format!("Failed to request attention: {e}"))
+}
+
+/// `flash` asks for attention because a high-priority alert fired; a
+/// `critical` severity keeps it going until the user looks.
+#[tauri::command]
+pub fn notify_attention(
+    webview: Webview,
+    app: AppHandle,
+    severity: Option<AlertSeverity>,
+    flash: bool,
+) -> Result<(), String> {
+    crate::metrics::of(&webview).observe("notify_attention", || {
+        crate::require_trusted_window(webview.label())?;
+        notify(&app, severity, flash)
+    })
+}
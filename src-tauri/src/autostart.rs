@@ -0,0 +1,55 @@
+//! Launch at login, so monitoring resumes after a reboot. Registration is
+//! the OS's own (a Launch Agent on macOS, the `Run` registry key on Windows,
+//! an XDG autostart entry on Linux) and is queried rather than mirrored in
+//! `desktop-config.json`, so it stays right if the user removes it from the
+//! system settings. What opens at login follows `LaunchConfig`.
+
+use tauri::{AppHandle, Webview, Wry};
+use tauri_plugin_autostart::ManagerExt;
+
+pub fn plugin() -> tauri::plugin::TauriPlugin<Wry> {
+    // No extra arguments: the launch setting, not the login item, decides
+    // whether the dashboard opens. macOS defaults to a Launch Agent.
+    tauri_plugin_autostart::Builder::new().build()
+}
+
+pub fn is_enabled(app: &AppHandle) -> Result<bool, String> {
+    app.autolaunch()
+        .is_enabled()
+        .map_err(|e| format!("Failed to read launch at login: {e}"))
+}
+
+pub fn set_enabled(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    let autolaunch = app.autolaunch();
+    let result = if enabled {
+        autolaunch.enable()
+    } else {
+        autolaunch.disable()
+    };
+    result.map_err(|e| format!("Failed to update launch at login: {e}"))?;
+    tracing::info!(target: "app", enabled, "launch at login changed");
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_launch_at_login(webview: Webview, app: AppHandle) -> Result<bool, String> {
+    crate::metrics::of(&webview).observe("get_launch_at_login", || {
+        crate::require_trusted_window(webview.label())?;
+        is_enabled(&app)
+    })
+}
+
+/// Returns the state actually registered afterwards.
+#[tauri::command]
+pub async fn set_launch_at_login(webview: Webview, app: AppHandle, enabled: bool) -> Result<bool, String> {
+    crate::metrics::of(&webview)
+        .observe_async("set_launch_at_login", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                set_enabled(&app, enabled)?;
+                is_enabled(&app)
+            })
+            .await
+        })
+        .await
+}
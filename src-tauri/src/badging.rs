@@ -0,0 +1,190 @@
+//! Alert badges on the app's OS surfaces. `apply` (the `set_badge` command)
+//! takes the active alert count and the highest active severity and maps
+//! them onto what each platform has, so callers never branch on the OS:
+//!
+//! - macOS and Linux: the count on the dock / launcher icon (Linux needs a
+//!   desktop that supports the Unity launcher API).
+//! - Windows: a colored dot over the taskbar button for the severity; taskbar
+//!   buttons cannot show a count.
+//! - Everywhere with a tray: the same dot over the tray icon.
+//!
+//! A zero count or no severity clears the corresponding badge.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::image::Image;
+use tauri::{AppHandle, Manager, Webview};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertSeverity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl AlertSeverity {
+    /// Badge dot color, matching the dashboard's priority colors.
+    fn color(self) -> [u8; 3] {
+        match self {
+            AlertSeverity::Low => [0x33, 0x88, 0xff],
+            AlertSeverity::Medium => [0xff, 0xaa, 0x00],
+            AlertSeverity::High => [0xff, 0x88, 0x00],
+            AlertSeverity::Critical => [0xff, 0x44, 0x44],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Badge {
+    count: u32,
+    severity: Option<AlertSeverity>,
+}
+
+/// The badge last applied, so repeated reports don't redraw icons.
+#[derive(Default)]
+pub struct BadgeState(Mutex<Option<Badge>>);
+
+/// Overlay icons are drawn at this size; Windows scales them to 16x16 DIPs.
+#[cfg(windows)]
+const OVERLAY_SIZE: u32 = 32;
+
+/// RGBA pixels of a filled, anti-aliased circle in `color` with a dark rim so
+/// it stays visible on light taskbars.
+fn dot_pixels(color: [u8; 3], size: u32) -> Vec<u8> {
+    let center = size as f32 / 2.0;
+    let radius = center - 1.0;
+    let rim = radius - 2.0;
+    let mut pixels = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let dx = x as f32 + 0.5 - center;
+            let dy = y as f32 + 0.5 - center;
+            let distance = (dx * dx + dy * dy).sqrt();
+            let coverage = (radius - distance + 0.5).clamp(0.0, 1.0);
+            let [r, g, b] = if distance > rim { [0x1a, 0x1c, 0x1e] } else { color };
+            pixels.extend_from_slice(&[r, g, b, (coverage * 255.0).round() as u8]);
+        }
+    }
+    pixels
+}
+
+/// `icon` (RGBA, `width` wide) with a severity dot over its bottom-right
+/// corner, covering half the icon.
+fn badged_pixels(icon: &[u8], width: u32, height: u32, severity: AlertSeverity) -> Vec<u8> {
+    let size = (width.min(height) / 2).max(1);
+    let dot = dot_pixels(severity.color(), size);
+    let (left, top) = (width - size, height - size);
+    let mut pixels = icon.to_vec();
+    for y in 0..size {
+        for x in 0..size {
+            let src = &dot[((y * size + x) * 4) as usize..][..4];
+            let dst = &mut pixels[(((top + y) * width + left + x) * 4) as usize..][..4];
+            let alpha = src[3] as f32 / 255.0;
+            for channel in 0..3 {
+                dst[channel] = (src[channel] as f32 * alpha + dst[channel] as f32 * (1.0 - alpha)).round() as u8;
+            }
+            dst[3] = (src[3] as f32 + dst[3] as f32 * (1.0 - alpha)).round() as u8;
+        }
+    }
+    pixels
+}
+
+/// Dock / launcher count. Windows has none; the overlay stands in.
+#[cfg(not(windows))]
+fn set_count(window: &tauri::WebviewWindow, badge: Badge) -> Result<(), String> {
+    window
+        .set_badge_count((badge.count > 0).then_some(i64::from(badge.count)))
+        .map_err(|e| format!("Failed to set badge count: {e}"))
+}
+
+#[cfg(windows)]
+fn set_count(window: &tauri::WebviewWindow, badge: Badge) -> Result<(), String> {
+    let pixels = badge
+        .severity
+        .filter(|_| badge.count > 0)
+        .map(|severity| dot_pixels(severity.color(), OVERLAY_SIZE));
+    let icon = pixels.as_deref().map(|pixels| Image::new(pixels, OVERLAY_SIZE, OVERLAY_SIZE));
+    window
+        .set_overlay_icon(icon)
+        .map_err(|e| format!("Failed to set taskbar overlay: {e}"))
+}
+
+fn set_tray_icon(app: &AppHandle, badge: Badge) -> Result<(), String> {
+    let (Some(tray), Some(icon)) = (crate::tray::icon(app), app.default_window_icon()) else {
+        return Ok(());
+    };
+    let icon = match badge.severity.filter(|_| badge.count > 0) {
+        Some(severity) => Image::new_owned(
+            badged_pixels(icon.rgba(), icon.width(), icon.height(), severity),
+            icon.width(),
+            icon.height(),
+        ),
+        None => icon.clone().to_owned(),
+    };
+    tray.set_icon(Some(icon)).map_err(|e| format!("Failed to set tray icon: {e}"))
+}
+
+/// Badge `count` active alerts at `severity` on every surface this platform
+/// has. A zero count clears the badges.
+pub fn apply(app: &AppHandle, count: u32, severity: Option<AlertSeverity>) -> Result<(), String> {
+    let badge = Badge { count, severity };
+    if let Some(state) = app.try_state::<BadgeState>() {
+        if state.0.lock().unwrap_or_else(|e| e.into_inner()).replace(badge) == Some(badge) {
+            return Ok(());
+        }
+    }
+    if let Some(window) = app.get_webview_window("main") {
+        set_count(&window, badge)?;
+    }
+    set_tray_icon(app, badge)
+}
+
+/// The severity last badged, for callers that only change the count.
+pub fn current_severity(app: &AppHandle) -> Option<AlertSeverity> {
+    app.try_state::<BadgeState>()
+        .and_then(|state| *state.0.lock().unwrap_or_else(|e| e.into_inner()))
+        .and_then(|badge| badge.severity)
+}
+
+#[tauri::command]
+pub fn set_badge(
+    webview: Webview,
+    app: AppHandle,
+    count: u32,
+    severity: Option<AlertSeverity>,
+) -> Result<(), String> {
+    crate::metrics::of(&webview).observe("set_badge", || {
+        crate::require_trusted_window(webview.label())?;
+        apply(&app, count, severity)
+    })
+}
+
+#[cfg(test)]
+mod badging_tests {
+    use super::*;
+
+    #[test]
+    fn dot_is_opaque_with_transparent_corners() {
+        let size = 32;
+        let pixels = dot_pixels(AlertSeverity::Critical.color(), size);
+        assert_eq!(pixels.len(), (size * size * 4) as usize);
+        let at = |x: u32, y: u32| &pixels[((y * size + x) * 4) as usize..][..4];
+        assert_eq!(at(16, 16), &[0xff, 0x44, 0x44, 0xff]);
+        assert_eq!(at(0, 0)[3], 0);
+        assert_eq!(at(31, 31)[3], 0);
+    }
+
+    #[test]
+    fn badges_the_bottom_right_corner() {
+        let (width, height) = (16, 16);
+        let icon = [0x10, 0x20, 0x30, 0xff].repeat((width * height) as usize);
+        let pixels = badged_pixels(&icon, width, height, AlertSeverity::Low);
+        let at = |x: u32, y: u32| &pixels[((y * width + x) * 4) as usize..][..4];
+        assert_eq!(at(2, 2), &[0x10, 0x20, 0x30, 0xff]);
+        assert_eq!(at(12, 12), &[0x33, 0x88, 0xff, 0xff]);
+        assert_eq!(at(15, 15)[3], 0xff);
+    }
+}
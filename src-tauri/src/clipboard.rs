@@ -0,0 +1,141 @@
+//! Native clipboard access. Webview clipboard APIs need a user gesture, a
+//! focused document and, for images, `ClipboardItem` support that WebKitGTK
+//! lacks; these commands go through the OS clipboard instead. Text,
+//! formatted coordinates and PNG images (map snapshots) can be copied, and
+//! `read_clipboard_text` feeds the dashboard's jump-to-coordinates shortcut.
+
+use serde::Deserialize;
+use tauri::image::Image;
+use tauri::ipc::{InvokeBody, Request};
+use tauri::{AppHandle, Webview};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// PNGs larger than this are refused rather than decoded.
+const MAX_IMAGE_BYTES: usize = 32 * 1024 * 1024;
+/// Clipboard text longer than this is not a coordinate worth returning.
+const MAX_READ_CHARS: usize = 64 * 1024;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CoordinateFormat {
+    /// `48.85660, 2.35220`
+    #[default]
+    Decimal,
+    /// `48°51'23.8"N 2°21'07.9"E`
+    Dms,
+}
+
+fn dms(value: f64, positive: char, negative: char) -> String {
+    let hemisphere = if value < 0.0 { negative } else { positive };
+    // Round once, in tenths of a second, so 59.95" carries into the minute.
+    let tenths = (value.abs() * 36_000.0).round() as u64;
+    let (degrees, rest) = (tenths / 36_000, tenths % 36_000);
+    let (minutes, tenths) = (rest / 600, rest % 600);
+    format!("{degrees}°{minutes:02}'{:02}.{}\"{hemisphere}", tenths / 10, tenths % 10)
+}
+
+pub fn format_coordinates(lat: f64, lon: f64, format: CoordinateFormat) -> Result<String, String> {
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return Err(format!("Coordinates out of range: {lat}, {lon}"));
+    }
+    Ok(match format {
+        CoordinateFormat::Decimal => format!("{lat:.5}, {lon:.5}"),
+        CoordinateFormat::Dms => format!("{} {}", dms(lat, 'N', 'S'), dms(lon, 'E', 'W')),
+    })
+}
+
+fn write_text(app: &AppHandle, text: &str) -> Result<(), String> {
+    app.clipboard()
+        .write_text(text)
+        .map_err(|e| format!("Failed to copy to clipboard: {e}"))
+}
+
+#[tauri::command]
+pub fn copy_to_clipboard(webview: Webview, app: AppHandle, text: String) -> Result<(), String> {
+    crate::metrics::of(&webview).observe("copy_to_clipboard", || {
+        crate::require_trusted_window(webview.label())?;
+        write_text(&app, &text)
+    })
+}
+
+/// Returns the text that was copied.
+#[tauri::command]
+pub fn copy_coordinates_to_clipboard(
+    webview: Webview,
+    app: AppHandle,
+    lat: f64,
+    lon: f64,
+    format: Option<CoordinateFormat>,
+) -> Result<String, String> {
+    crate::metrics::of(&webview).observe("copy_coordinates_to_clipboard", || {
+        crate::require_trusted_window(webview.label())?;
+        let text = format_coordinates(lat, lon, format.unwrap_or_default())?;
+        write_text(&app, &text)?;
+        Ok(text)
+    })
+}
+
+/// Takes the PNG as the raw invoke body.
+#[tauri::command]
+pub fn copy_image_to_clipboard(webview: Webview, app: AppHandle, request: Request<'_>) -> Result<(), String> {
+    crate::metrics::of(&webview).observe("copy_image_to_clipboard", || {
+        crate::require_trusted_window(webview.label())?;
+        let InvokeBody::Raw(png) = request.body() else {
+            return Err("Expected PNG bytes as the request body".to_string());
+        };
+        if png.len() > MAX_IMAGE_BYTES {
+            return Err(format!("Image too large to copy ({} bytes)", png.len()));
+        }
+        let image = Image::from_bytes(png).map_err(|e| format!("Failed to decode PNG: {e}"))?;
+        app.clipboard()
+            .write_image(&image)
+            .map_err(|e| format!("Failed to copy image to clipboard: {e}"))
+    })
+}
+
+/// Clipboard text, or null when the clipboard holds no text.
+#[tauri::command]
+pub fn read_clipboard_text(webview: Webview, app: AppHandle) -> Result<Option<String>, String> {
+    crate::metrics::of(&webview).observe("read_clipboard_text", || {
+        crate::require_trusted_window(webview.label())?;
+        // Empty or non-text clipboards are reported as errors by some backends.
+        Ok(app
+            .clipboard()
+            .read_text()
+            .ok()
+            .filter(|text| !text.is_empty() && text.chars().count() <= MAX_READ_CHARS))
+    })
+}
+
+#[cfg(test)]
+mod clipboard_tests {
+    use super::*;
+
+    #[test]
+    fn formats_decimal_coordinates() {
+        assert_eq!(
+            format_coordinates(48.8566, 2.3522, CoordinateFormat::Decimal).unwrap(),
+            "48.85660, 2.35220"
+        );
+    }
+
+    #[test]
+    fn formats_dms_coordinates() {
+        assert_eq!(
+            format_coordinates(48.8566, 2.3522, CoordinateFormat::Dms).unwrap(),
+            "48°51'23.8\"N 2°21'07.9\"E"
+        );
+        assert_eq!(
+            format_coordinates(-33.8688, -70.0, CoordinateFormat::Dms).unwrap(),
+            "33°52'07.7\"S 70°00'00.0\"W"
+        );
+        // 0.99999° is 59'59.96", which rounds up to a whole degree.
+        assert_eq!(dms(0.99999, 'N', 'S'), "1°00'00.0\"N");
+    }
+
+    #[test]
+    fn rejects_out_of_range_coordinates() {
+        assert!(format_coordinates(91.0, 0.0, CoordinateFormat::Decimal).is_err());
+        assert!(format_coordinates(0.0, f64::NAN, CoordinateFormat::Decimal).is_err());
+    }
+}
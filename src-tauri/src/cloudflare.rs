@@ -0,0 +1,534 @@
+//! Cloudflare Radar internet outages synced into the local `store`, so the
+//! outage layer and the internet-health panels work offline. With
+//! `CLOUDFLARE_API_TOKEN` stored, the outage annotations and traffic
+//! anomalies of the last `WINDOW` are fetched at startup and then every
+//! `SYNC_INTERVAL`, or right away with `sync_internet_outages` — globally,
+//! or one request per country in `cloudflare.countries`.
+//!
+//! Both are stored under the `cloudflare` source, placed at their country's
+//! centroid and shaped like the infrastructure service's `InternetOutage`,
+//! which `query_internet_outages` returns. Ongoing national-scale outages
+//! new to the store (nationwide annotations and country-level traffic
+//! anomalies) go out as one `cloudflare://outages` batch, and their count
+//! is the `cloudflare.national` metric for threshold rules. Events older
+//! than `RETENTION_DAYS` are pruned.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+use tokio::sync::watch;
+
+use crate::config::DesktopConfigState;
+use crate::store::{with_store, EventQuery, StoredEvent};
+
+pub const OUTAGES_EVENT: &str = "cloudflare://outages";
+
+const SOURCE: &str = "cloudflare";
+const API_URL: &str = "https://api.cloudflare.com/client/v4/radar";
+const OUTAGE_CENTER_URL: &str = "https://radar.cloudflare.com/outage-center";
+const TOKEN_SECRET: &str = "CLOUDFLARE_API_TOKEN";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Radar is rate limited; the hosted API caches for 5 minutes.
+const SYNC_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const WINDOW: &str = "7d";
+const PAGE_SIZE: usize = 100;
+const RETENTION_DAYS: u64 = 90;
+
+/// Country centroids, `(ISO code, lat, lon)`, matching the hosted API's.
+const CENTROIDS: &[(&str, f64, f64)] = &[
+    ("AF", 33.94, 67.71), ("AL", 41.15, 20.17), ("DZ", 28.03, 1.66), ("AO", -11.20, 17.87), ("AR", -38.42, -63.62),
+    ("AM", 40.07, 45.04), ("AU", -25.27, 133.78), ("AT", 47.52, 14.55), ("AZ", 40.14, 47.58), ("BH", 26.07, 50.56),
+    ("BD", 23.69, 90.36), ("BY", 53.71, 27.95), ("BE", 50.50, 4.47), ("BJ", 9.31, 2.32), ("BO", -16.29, -63.59),
+    ("BA", 43.92, 17.68), ("BW", -22.33, 24.68), ("BR", -14.24, -51.93), ("BG", 42.73, 25.49), ("BF", 12.24, -1.56),
+    ("BI", -3.37, 29.92), ("KH", 12.57, 104.99), ("CM", 7.37, 12.35), ("CA", 56.13, -106.35), ("CF", 6.61, 20.94),
+    ("TD", 15.45, 18.73), ("CL", -35.68, -71.54), ("CN", 35.86, 104.20), ("CO", 4.57, -74.30), ("CG", -0.23, 15.83),
+    ("CD", -4.04, 21.76), ("CR", 9.75, -83.75), ("HR", 45.10, 15.20), ("CU", 21.52, -77.78), ("CY", 35.13, 33.43),
+    ("CZ", 49.82, 15.47), ("DK", 56.26, 9.50), ("DJ", 11.83, 42.59), ("EC", -1.83, -78.18), ("EG", 26.82, 30.80),
+    ("SV", 13.79, -88.90), ("ER", 15.18, 39.78), ("EE", 58.60, 25.01), ("ET", 9.15, 40.49), ("FI", 61.92, 25.75),
+    ("FR", 46.23, 2.21), ("GA", -0.80, 11.61), ("GM", 13.44, -15.31), ("GE", 42.32, 43.36), ("DE", 51.17, 10.45),
+    ("GH", 7.95, -1.02), ("GR", 39.07, 21.82), ("GT", 15.78, -90.23), ("GN", 9.95, -9.70), ("HT", 18.97, -72.29),
+    ("HN", 15.20, -86.24), ("HK", 22.32, 114.17), ("HU", 47.16, 19.50), ("IN", 20.59, 78.96), ("ID", -0.79, 113.92),
+    ("IR", 32.43, 53.69), ("IQ", 33.22, 43.68), ("IE", 53.14, -7.69), ("IL", 31.05, 34.85), ("IT", 41.87, 12.57),
+    ("CI", 7.54, -5.55), ("JP", 36.20, 138.25), ("JO", 30.59, 36.24), ("KZ", 48.02, 66.92), ("KE", -0.02, 37.91),
+    ("KW", 29.31, 47.48), ("KG", 41.20, 74.77), ("LA", 19.86, 102.50), ("LV", 56.88, 24.60), ("LB", 33.85, 35.86),
+    ("LY", 26.34, 17.23), ("LT", 55.17, 23.88), ("LU", 49.82, 6.13), ("MG", -18.77, 46.87), ("MW", -13.25, 34.30),
+    ("MY", 4.21, 101.98), ("ML", 17.57, -4.00), ("MR", 21.01, -10.94), ("MX", 23.63, -102.55), ("MD", 47.41, 28.37),
+    ("MN", 46.86, 103.85), ("MA", 31.79, -7.09), ("MZ", -18.67, 35.53), ("MM", 21.92, 95.96), ("NA", -22.96, 18.49),
+    ("NP", 28.39, 84.12), ("NL", 52.13, 5.29), ("NZ", -40.90, 174.89), ("NI", 12.87, -85.21), ("NE", 17.61, 8.08),
+    ("NG", 9.08, 8.68), ("KP", 40.34, 127.51), ("NO", 60.47, 8.47), ("OM", 21.47, 55.98), ("PK", 30.38, 69.35),
+    ("PS", 31.95, 35.23), ("PA", 8.54, -80.78), ("PG", -6.32, 143.96), ("PY", -23.44, -58.44),
+    ("PE", -9.19, -75.02), ("PH", 12.88, 121.77), ("PL", 51.92, 19.15), ("PT", 39.40, -8.22), ("QA", 25.35, 51.18),
+    ("RO", 45.94, 24.97), ("RU", 61.52, 105.32), ("RW", -1.94, 29.87), ("SA", 23.89, 45.08), ("SN", 14.50, -14.45),
+    ("RS", 44.02, 21.01), ("SL", 8.46, -11.78), ("SG", 1.35, 103.82), ("SK", 48.67, 19.70), ("SI", 46.15, 14.99),
+    ("SO", 5.15, 46.20), ("ZA", -30.56, 22.94), ("KR", 35.91, 127.77), ("SS", 6.88, 31.31), ("ES", 40.46, -3.75),
+    ("LK", 7.87, 80.77), ("SD", 12.86, 30.22), ("SE", 60.13, 18.64), ("CH", 46.82, 8.23), ("SY", 34.80, 38.997),
+    ("TW", 23.70, 120.96), ("TJ", 38.86, 71.28), ("TZ", -6.37, 34.89), ("TH", 15.87, 100.99), ("TG", 8.62, 0.82),
+    ("TT", 10.69, -61.22), ("TN", 33.89, 9.54), ("TR", 38.96, 35.24), ("TM", 38.97, 59.56), ("UG", 1.37, 32.29),
+    ("UA", 48.38, 31.17), ("AE", 23.42, 53.85), ("GB", 55.38, -3.44), ("US", 37.09, -95.71), ("UY", -32.52, -55.77),
+    ("UZ", 41.38, 64.59), ("VE", 6.42, -66.59), ("VN", 14.06, 108.28), ("YE", 15.55, 48.52), ("ZM", -13.13, 27.85),
+    ("ZW", -19.02, 29.15),
+];
+
+pub(crate) fn centroid(code: &str) -> Option<(f64, f64)> {
+    CENTROIDS
+        .iter()
+        .find(|(c, _, _)| c.eq_ignore_ascii_case(code))
+        .map(|(_, lat, lon)| (*lat, *lon))
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutageSyncStatus {
+    pub syncing: bool,
+    /// Unix seconds of the last completed sync.
+    pub last_sync: Option<u64>,
+    pub stored_events: usize,
+    /// New outages and anomalies in the last completed sync.
+    pub added: usize,
+    /// Ongoing national-scale outages.
+    pub national: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct CloudflareState {
+    /// Bumped to request a sync now.
+    trigger: Mutex<Option<watch::Sender<u64>>>,
+    status: Mutex<OutageSyncStatus>,
+}
+
+fn update_status(app: &AppHandle, f: impl FnOnce(&mut OutageSyncStatus)) -> OutageSyncStatus {
+    let state = app.state::<CloudflareState>();
+    let mut status = state.status.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut status);
+    status.clone()
+}
+
+#[derive(Deserialize)]
+struct Response<T> {
+    #[serde(default)]
+    success: bool,
+    #[serde(default)]
+    errors: Vec<ApiError>,
+    result: Option<T>,
+}
+
+#[derive(Deserialize)]
+struct ApiError {
+    message: String,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Annotations {
+    annotations: Vec<Annotation>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Annotation {
+    id: String,
+    description: String,
+    scope: Option<String>,
+    start_date: String,
+    end_date: Option<String>,
+    locations: Vec<String>,
+    linked_url: Option<String>,
+    locations_details: Vec<Place>,
+    asns_details: Vec<AsnDetails>,
+    outage: OutageDetails,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct OutageDetails {
+    outage_cause: String,
+    outage_type: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+struct Place {
+    code: String,
+    name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct AsnDetails {
+    asn: Value,
+    name: String,
+    location: Option<Place>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Anomalies {
+    traffic_anomalies: Vec<Anomaly>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Anomaly {
+    uuid: String,
+    /// `LOCATION` for a whole country, `AS` for one network.
+    #[serde(rename = "type")]
+    kind: String,
+    status: String,
+    start_date: String,
+    end_date: Option<String>,
+    location_details: Option<Place>,
+    asn_details: Option<AsnDetails>,
+}
+
+/// Milliseconds since the epoch of an RFC 3339 time; 0 when missing.
+fn millis(value: Option<&str>) -> i64 {
+    value
+        .and_then(|v| chrono::DateTime::parse_from_rfc3339(v).ok())
+        .map_or(0, |t| t.timestamp_millis())
+}
+
+fn event_date(start: &str) -> Option<String> {
+    let date = start.get(..10)?;
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    Some(date.to_string())
+}
+
+/// Whether a stored outage is national in scale and still ongoing.
+fn national(event: &StoredEvent) -> bool {
+    event.data["national"].as_bool().unwrap_or(false) && event.data["endedAt"].as_i64() == Some(0)
+}
+
+fn from_annotation(raw: Annotation) -> Option<StoredEvent> {
+    let (code, (lat, lon)) = raw.locations.iter().find_map(|code| Some((code, centroid(code)?)))?;
+    let date = event_date(&raw.start_date)?;
+    let country = raw
+        .locations_details
+        .iter()
+        .find(|p| p.code.eq_ignore_ascii_case(code))
+        .map_or_else(|| code.clone(), |p| p.name.clone());
+    let outage = &raw.outage;
+    let mut categories = vec!["Cloudflare Radar".to_string()];
+    if !outage.outage_cause.is_empty() {
+        categories.push(outage.outage_cause.replace('_', " "));
+    }
+    if !outage.outage_type.is_empty() {
+        categories.push(outage.outage_type.clone());
+    }
+    categories.extend(raw.asns_details.iter().take(2).filter(|a| !a.name.is_empty()).map(|a| a.name.clone()));
+    let severity = match outage.outage_type.as_str() {
+        "NATIONWIDE" => "OUTAGE_SEVERITY_TOTAL",
+        "REGIONAL" => "OUTAGE_SEVERITY_MAJOR",
+        _ => "OUTAGE_SEVERITY_PARTIAL",
+    };
+    let title = match &raw.scope {
+        Some(scope) if !scope.is_empty() => format!("{scope} outage in {country}"),
+        _ => format!("Internet disruption in {country}"),
+    };
+    let id = format!("cf-{}", raw.id);
+    Some(StoredEvent {
+        data: json!({
+            "id": id,
+            "title": title,
+            "link": raw.linked_url.filter(|l| !l.is_empty()).unwrap_or_else(|| OUTAGE_CENTER_URL.to_string()),
+            "description": raw.description,
+            "detectedAt": millis(Some(&raw.start_date)),
+            "country": country,
+            "region": "",
+            "location": { "latitude": lat, "longitude": lon },
+            "severity": severity,
+            "categories": categories,
+            "cause": outage.outage_cause,
+            "outageType": outage.outage_type,
+            "endedAt": millis(raw.end_date.as_deref()),
+            "national": outage.outage_type == "NATIONWIDE",
+        }),
+        id,
+        event_date: date,
+        event_type: "outage".to_string(),
+        lat,
+        lon,
+    })
+}
+
+fn from_anomaly(raw: Anomaly) -> Option<StoredEvent> {
+    let asn = raw.asn_details.as_ref();
+    let place = raw.location_details.clone().or_else(|| asn?.location.clone())?;
+    let (lat, lon) = centroid(&place.code)?;
+    let date = event_date(&raw.start_date)?;
+    let country = if place.name.is_empty() { place.code.clone() } else { place.name.clone() };
+    let whole_country = raw.kind == "LOCATION";
+    let mut categories = vec!["Cloudflare Radar".to_string(), "Traffic anomaly".to_string()];
+    if !raw.status.is_empty() {
+        categories.push(raw.status.to_lowercase());
+    }
+    let title = match asn.filter(|_| !whole_country) {
+        Some(asn) => {
+            let number = asn.asn.as_str().map_or_else(|| asn.asn.to_string(), str::to_string);
+            format!("Traffic anomaly on AS{number} {} in {country}", asn.name).replace("  ", " ")
+        }
+        None => format!("Traffic anomaly in {country}"),
+    };
+    if let Some(asn) = asn.filter(|a| !a.name.is_empty()) {
+        categories.push(asn.name.clone());
+    }
+    let id = format!("cf-anomaly-{}", raw.uuid);
+    Some(StoredEvent {
+        data: json!({
+            "id": id,
+            "title": title,
+            "link": OUTAGE_CENTER_URL,
+            "description": "",
+            "detectedAt": millis(Some(&raw.start_date)),
+            "country": country,
+            "region": "",
+            "location": { "latitude": lat, "longitude": lon },
+            "severity": if whole_country { "OUTAGE_SEVERITY_MAJOR" } else { "OUTAGE_SEVERITY_PARTIAL" },
+            "categories": categories,
+            "cause": "",
+            "outageType": "",
+            "endedAt": millis(raw.end_date.as_deref()),
+            "national": whole_country,
+        }),
+        id,
+        event_date: date,
+        event_type: "anomaly".to_string(),
+        lat,
+        lon,
+    })
+}
+
+async fn fetch<T: serde::de::DeserializeOwned>(
+    client: &reqwest::Client,
+    token: &str,
+    path: &str,
+    country: Option<&str>,
+) -> Result<T, String> {
+    let mut query = vec![("dateRange", WINDOW.to_string()), ("limit", PAGE_SIZE.to_string()), ("format", "json".to_string())];
+    if let Some(country) = country {
+        query.push(("location", country.to_string()));
+    }
+    let response = client
+        .get(format!("{API_URL}/{path}"))
+        .bearer_auth(token)
+        .query(&query)
+        .send()
+        .await
+        .map_err(|e| format!("request failed: {e}"))?;
+    match response.status() {
+        reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+            return Err("Cloudflare rejected the API token".to_string());
+        }
+        status if !status.is_success() => return Err(format!("{path}: HTTP {status}")),
+        _ => {}
+    }
+    let body: Response<T> = response.json().await.map_err(|e| format!("{path}: invalid response: {e}"))?;
+    if !body.success || !body.errors.is_empty() {
+        let messages: Vec<String> = body.errors.into_iter().map(|e| e.message).collect();
+        return Err(format!("{path}: {}", messages.join("; ")));
+    }
+    body.result.ok_or_else(|| format!("{path}: empty response"))
+}
+
+/// One complete sync. Returns how many events were new, and the ongoing
+/// national-scale ones among them.
+async fn sync(app: &AppHandle, client: &reqwest::Client, token: &str) -> Result<(usize, Vec<Value>), String> {
+    let countries = app.state::<DesktopConfigState>().snapshot().cloudflare.countries;
+    let scopes: Vec<Option<&str>> =
+        if countries.is_empty() { vec![None] } else { countries.iter().map(|c| Some(c.as_str())).collect() };
+    let mut events = Vec::new();
+    for country in scopes {
+        let annotations: Annotations = fetch(client, token, "annotations/outages", country).await?;
+        events.extend(annotations.annotations.into_iter().filter_map(from_annotation));
+        let anomalies: Anomalies = fetch(client, token, "traffic_anomalies", country).await?;
+        events.extend(anomalies.traffic_anomalies.into_iter().filter_map(from_anomaly));
+    }
+    // An outage spanning several requested countries comes back for each.
+    events.sort_by(|a, b| a.id.cmp(&b.id));
+    events.dedup_by(|a, b| a.id == b.id);
+
+    let ongoing = events.iter().filter(|e| national(e)).count();
+    let cutoff = (Utc::now().date_naive() - Days::new(RETENTION_DAYS)).format("%Y-%m-%d").to_string();
+    let (added, stored) = with_store(app, move |store| {
+        let added = store.upsert(SOURCE, &events)?;
+        store.prune(SOURCE, &cutoff)?;
+        let added: Vec<StoredEvent> = events.into_iter().filter(|e| added.contains(&e.id)).collect();
+        Ok((added, store.count(SOURCE)?))
+    })
+    .await?;
+    update_status(app, |status| {
+        status.stored_events = stored;
+        status.national = ongoing;
+    });
+    crate::alerts::record_metric(app, "cloudflare.national", ongoing as f64);
+    let count = added.len();
+    Ok((count, added.into_iter().filter(national).map(|e| e.data).collect()))
+}
+
+struct Poller {
+    app: AppHandle,
+    client: reqwest::Client,
+}
+
+impl crate::poll::Poller for Poller {
+    async fn round(&mut self) -> Duration {
+        let app = &self.app;
+        match app.state::<crate::SecretsCache>().get(TOKEN_SECRET) {
+            Some(token) => {
+                update_status(app, |status| status.syncing = true);
+                let result = sync(app, &self.client, &token).await;
+                let mut national = Vec::new();
+                let status = update_status(app, |status| {
+                    status.syncing = false;
+                    match result {
+                        Ok((added, new_national)) => {
+                            status.last_sync = Some(crate::unix_timestamp_secs());
+                            status.added = added;
+                            status.error = None;
+                            national = new_national;
+                        }
+                        Err(err) => status.error = Some(err),
+                    }
+                });
+                match &status.error {
+                    Some(err) => tracing::warn!(target: "app", "Cloudflare Radar sync failed: {err}"),
+                    None => tracing::debug!(target: "app", added = status.added, national = national.len(), "Cloudflare Radar sync complete"),
+                }
+                if !national.is_empty() {
+                    tracing::info!(target: "app", count = national.len(), "new national-scale internet outages");
+                    let _ = app.emit(OUTAGES_EVENT, national);
+                }
+            }
+            None => {
+                update_status(app, |status| status.error = Some(format!("{TOKEN_SECRET} is not set")));
+            }
+        }
+        SYNC_INTERVAL
+    }
+}
+
+async fn run(app: AppHandle, trigger: watch::Receiver<u64>) {
+    let client = match reqwest::Client::builder().use_native_tls().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!(target: "app", "Cloudflare Radar sync unavailable: {err}");
+            return;
+        }
+    };
+    if let Ok(stored) = with_store(&app, |store| store.count(SOURCE)).await {
+        update_status(&app, |status| status.stored_events = stored);
+    }
+    let poller = Poller {
+        app: app.clone(),
+        client,
+    };
+    crate::poll::run_triggered(&app, trigger, poller).await;
+}
+
+/// Start the sync task; it syncs right away if a token is stored.
+pub fn start(app: &AppHandle) {
+    let (sender, receiver) = watch::channel(0);
+    *app.state::<CloudflareState>().trigger.lock().unwrap_or_else(|e| e.into_inner()) = Some(sender);
+    tauri::async_runtime::spawn(run(app.clone(), receiver));
+}
+
+/// Sync now, e.g. right after the token was added. Returns at once.
+#[tauri::command]
+pub fn sync_internet_outages(webview: Webview, state: tauri::State<'_, CloudflareState>) -> Result<OutageSyncStatus, String> {
+    crate::metrics::of(&webview).observe("sync_internet_outages", || {
+        crate::require_trusted_window(webview.label())?;
+        let status = state.status.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        if !status.syncing {
+            if let Some(trigger) = state.trigger.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+                trigger.send_modify(|n| *n += 1);
+            }
+        }
+        Ok(status)
+    })
+}
+
+#[tauri::command]
+pub fn get_outage_sync_status(webview: Webview, state: tauri::State<'_, CloudflareState>) -> Result<OutageSyncStatus, String> {
+    crate::metrics::of(&webview).observe("get_outage_sync_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.status.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+/// Stored outages and anomalies (event types `outage` and `anomaly`),
+/// newest first.
+#[tauri::command]
+pub async fn query_internet_outages(webview: Webview, app: AppHandle, query: Option<EventQuery>) -> Result<Vec<Value>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("query_internet_outages", async move {
+            crate::require_trusted_window(webview.label())?;
+            let query = query.unwrap_or_default();
+            with_store(&app, move |store| store.query(SOURCE, &query)).await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod cloudflare_tests {
+    use super::*;
+
+    #[test]
+    fn shapes_annotations_like_the_outage_service() {
+        let body = r#"{"success":true,"errors":[],"result":{"annotations":[
+            {"id":"1234","dataSource":"ALL","description":"Government-directed shutdown","scope":"Nationwide",
+             "startDate":"2026-05-10T06:00:00Z","endDate":null,"locations":["IR"],"asns":[],
+             "eventType":"OUTAGE","linkedUrl":"https://x.com/CloudflareRadar/status/1",
+             "locationsDetails":[{"name":"Iran","code":"IR"}],"asnsDetails":[],
+             "outage":{"outageCause":"GOVERNMENT_DIRECTED","outageType":"NATIONWIDE"}},
+            {"id":"1235","description":"Cable cut","startDate":"2026-05-09T00:00:00Z","endDate":"2026-05-09T12:00:00Z",
+             "locations":["XX","GM"],"locationsDetails":[{"name":"Gambia","code":"GM"}],
+             "asnsDetails":[{"asn":"37309","name":"QCell","location":{"code":"GM","name":"Gambia"}}],
+             "outage":{"outageCause":"CABLE_CUT","outageType":"REGIONAL"}},
+            {"id":"1236","startDate":"2026-05-09T00:00:00Z","locations":["ZZ"],"outage":{}}
+        ]}}"#;
+        let response: Response<Annotations> = serde_json::from_str(body).unwrap();
+        let events: Vec<StoredEvent> = response.result.unwrap().annotations.into_iter().filter_map(from_annotation).collect();
+        assert_eq!(events.len(), 2);
+        let iran = &events[0];
+        assert_eq!((iran.id.as_str(), iran.event_date.as_str(), iran.lat), ("cf-1234", "2026-05-10", 32.43));
+        assert_eq!(iran.data["title"], "Nationwide outage in Iran");
+        assert_eq!(iran.data["severity"], "OUTAGE_SEVERITY_TOTAL");
+        assert_eq!(iran.data["categories"], json!(["Cloudflare Radar", "GOVERNMENT DIRECTED", "NATIONWIDE"]));
+        assert_eq!(iran.data["detectedAt"], 1_778_392_800_000i64);
+        assert!(national(iran));
+        let gambia = &events[1];
+        assert_eq!(gambia.data["country"], "Gambia");
+        assert_eq!(gambia.data["link"], OUTAGE_CENTER_URL);
+        assert_eq!(gambia.data["categories"][3], "QCell");
+        assert!(!national(gambia));
+    }
+
+    #[test]
+    fn places_traffic_anomalies_by_country_or_network() {
+        let body = r#"{"success":true,"result":{"trafficAnomalies":[
+            {"uuid":"a1","type":"LOCATION","status":"VERIFIED","startDate":"2026-05-10T07:15:00Z",
+             "locationDetails":{"code":"PK","name":"Pakistan"}},
+            {"uuid":"a2","type":"AS","status":"UNVERIFIED","startDate":"2026-05-10T08:00:00Z","endDate":"2026-05-10T09:00:00Z",
+             "asnDetails":{"asn":"17557","name":"PKTELECOM-AS-PK","location":{"code":"PK","name":"Pakistan"}}},
+            {"uuid":"a3","type":"AS","startDate":"2026-05-10T08:00:00Z","asnDetails":{"asn":"1","name":"x"}}
+        ]}}"#;
+        let response: Response<Anomalies> = serde_json::from_str(body).unwrap();
+        let events: Vec<StoredEvent> = response.result.unwrap().traffic_anomalies.into_iter().filter_map(from_anomaly).collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!((events[0].id.as_str(), events[0].event_type.as_str()), ("cf-anomaly-a1", "anomaly"));
+        assert_eq!(events[0].data["title"], "Traffic anomaly in Pakistan");
+        assert!(national(&events[0]));
+        assert_eq!(events[1].data["title"], "Traffic anomaly on AS17557 PKTELECOM-AS-PK in Pakistan");
+        assert_eq!(events[1].data["severity"], "OUTAGE_SEVERITY_PARTIAL");
+        assert!(!national(&events[1]));
+
+        let failed: Response<Anomalies> =
+            serde_json::from_str(r#"{"success":false,"errors":[{"code":10000,"message":"Authentication error"}]}"#).unwrap();
+        assert_eq!(failed.errors[0].message, "Authentication error");
+    }
+}
@@ -0,0 +1,1115 @@
+//! Desktop-only preferences persisted as `desktop-config.json` in the app data
+//! dir. These are settings the Rust shell needs before any webview exists
+//! (such as the log rotation policy), so they cannot live in the frontend's
+//! localStorage. Every field has a default, and unknown or missing keys fall
+//! back to it so older files keep loading after upgrades.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ais::BoundingBox;
+use crate::badging::AlertSeverity;
+use crate::hazards::HazardLevel;
+
+pub const DESKTOP_CONFIG_FILE: &str = "desktop-config.json";
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DesktopConfig {
+    pub log: LogConfig,
+    pub watchdog: WatchdogConfig,
+    pub error_reporting: ErrorReportingConfig,
+    pub tray: TrayConfig,
+    pub shortcuts: ShortcutsConfig,
+    pub ticker: TickerConfig,
+    pub developer: DeveloperConfig,
+    pub appearance: AppearanceConfig,
+    pub launch: LaunchConfig,
+    pub pdf: PdfConfig,
+    pub links: LinksConfig,
+    pub idle: IdleConfig,
+    pub power: PowerConfig,
+    pub focus_mode: FocusModeConfig,
+    pub adsb: AdsbConfig,
+    pub gdelt: GdeltConfig,
+    pub tiles: TileCacheConfig,
+    pub usgs: UsgsConfig,
+    pub fred: FredConfig,
+    pub eia: EiaConfig,
+    pub digest: DigestConfig,
+    pub faa: FaaConfig,
+    pub orbits: OrbitsConfig,
+    pub swpc: SwpcConfig,
+    pub cloudflare: CloudflareConfig,
+    pub relay: RelayConfig,
+    pub mqtt: MqttConfig,
+    pub cyber: CyberConfig,
+    pub markets: MarketsConfig,
+    pub maritime: MaritimeConfig,
+    pub anomalies: AnomaliesConfig,
+    pub exports: ExportsConfig,
+    pub sounds: SoundsConfig,
+    pub speech: SpeechConfig,
+    pub hazards: HazardsConfig,
+}
+
+/// Ordered from most to least severe, so `a <= b` means `a` passes a `b` filter.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// `[epoch][LEVEL][target] message key=value`
+    #[default]
+    Human,
+    /// One JSON object per line with an RFC 3339 `timestamp`, for jq/Loki.
+    Json,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogConfig {
+    /// Default level for the desktop log and the sidecar. `WORLD_MONITOR_LOG`
+    /// still takes precedence at startup when set.
+    pub level: LogLevel,
+    /// Line format of `desktop.log` (stderr always uses the human format).
+    /// Takes effect on next launch.
+    pub format: LogFormat,
+    /// Rotate `desktop.log` once it would grow past this many bytes.
+    pub max_file_bytes: u64,
+    /// Rotated generations kept alongside the live file (`desktop.log.1` ..).
+    pub max_generations: usize,
+    /// Rotated generations older than this are deleted at startup.
+    pub retention_days: u64,
+    /// Also mirror events to the platform log (journald on Linux, the Windows
+    /// Event Log, unified logging on macOS). Takes effect on next launch.
+    pub native_backend: bool,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        LogConfig {
+            level: LogLevel::default(),
+            format: LogFormat::default(),
+            max_file_bytes: 5 * 1024 * 1024,
+            max_generations: 5,
+            retention_days: 14,
+            native_backend: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchdogConfig {
+    pub enabled: bool,
+    /// Seconds between pings to the main window.
+    pub interval_secs: u64,
+    /// Seconds without an ack before the main window counts as frozen.
+    pub unresponsive_after_secs: u64,
+    /// Reload the main window once it is found frozen.
+    pub auto_reload: bool,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        WatchdogConfig {
+            enabled: true,
+            interval_secs: 10,
+            unresponsive_after_secs: 30,
+            auto_reload: false,
+        }
+    }
+}
+
+/// Opt-in error reporting; see `error_reporting`. Both reporters are off
+/// unless the user enables them.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ErrorReportingConfig {
+    /// Append reports to `error-reports.jsonl` in the logs dir.
+    pub write_to_file: bool,
+    /// POST reports as JSON to this URL (https, or http on localhost).
+    pub endpoint: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrayConfig {
+    /// Hide the main window to the tray when it is closed (Windows/Linux;
+    /// macOS always hides to the dock).
+    pub close_to_tray: bool,
+    /// Hide the main window to the tray when it is minimized.
+    pub minimize_to_tray: bool,
+    /// Set once the "still running in the tray" notification has been shown.
+    pub hide_notice_shown: bool,
+}
+
+impl Default for TrayConfig {
+    fn default() -> Self {
+        TrayConfig {
+            close_to_tray: true,
+            minimize_to_tray: false,
+            hide_notice_shown: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShortcutsConfig {
+    /// Global accelerator that shows/hides the main window; `None` disables it.
+    pub toggle_window: Option<String>,
+}
+
+impl Default for ShortcutsConfig {
+    fn default() -> Self {
+        ShortcutsConfig {
+            toggle_window: Some("CommandOrControl+Shift+W".to_string()),
+        }
+    }
+}
+
+/// Screen corner the ticker window snaps to, within the monitor's work area.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TickerCorner {
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TickerConfig {
+    /// Show the always-on-top ticker strip (restored on next launch).
+    pub visible: bool,
+    /// Let clicks pass through the ticker to the windows underneath.
+    pub click_through: bool,
+    pub corner: TickerCorner,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeveloperConfig {
+    /// Enable View → Reload / Force Reload in release builds (debug builds
+    /// always have them), e.g. so kiosk machines can recover a wedged page.
+    /// Toggle Developer Tools still needs the `devtools` Cargo feature.
+    /// Takes effect on next launch.
+    pub reload_menu: bool,
+}
+
+/// Native backdrop behind a transparent main window. Each platform uses its
+/// closest equivalent; Linux has none and shows only the transparency.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowEffect {
+    #[default]
+    None,
+    /// macOS vibrancy (Windows: acrylic).
+    Vibrancy,
+    /// Windows acrylic blur (macOS: HUD vibrancy).
+    Acrylic,
+    /// Windows 11 Mica (macOS: sidebar vibrancy).
+    Mica,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppearanceConfig {
+    /// Create the main window with a see-through background, e.g. to use the
+    /// dashboard as a desktop overlay. Takes effect on next launch.
+    pub transparent: bool,
+    /// Backdrop shown through the transparent window.
+    pub effect: WindowEffect,
+}
+
+/// What the app opens at launch; see `launch`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LaunchWindow {
+    #[default]
+    Dashboard,
+    /// Start hidden with only the tray icon; the dashboard still loads.
+    Tray,
+    /// Open settings first; the dashboard appears once settings is closed.
+    Settings,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchConfig {
+    pub window: LaunchWindow,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PageSize {
+    #[default]
+    A4,
+    A3,
+    Letter,
+    Legal,
+}
+
+/// Page setup for File → Print / Export PDF.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PdfConfig {
+    pub page_size: PageSize,
+    /// The dashboard is wide, so this defaults to on.
+    pub landscape: bool,
+}
+
+impl Default for PdfConfig {
+    fn default() -> Self {
+        PdfConfig {
+            page_size: PageSize::default(),
+            landscape: true,
+        }
+    }
+}
+
+/// Sites `open_external_url` opens without asking. Entries match the host
+/// and its subdomains; a leading `*.` is accepted.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LinksConfig {
+    pub allowed_hosts: Vec<String>,
+    /// Ask before opening other sites; when off they are refused.
+    pub confirm_unlisted: bool,
+}
+
+impl Default for LinksConfig {
+    fn default() -> Self {
+        LinksConfig {
+            allowed_hosts: vec!["worldmonitor.app".to_string(), "github.com".to_string()],
+            confirm_unlisted: true,
+        }
+    }
+}
+
+/// System idle detection; see `idle`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdleConfig {
+    pub enabled: bool,
+    /// Minutes without keyboard or mouse input before refreshes slow down.
+    pub threshold_mins: u64,
+}
+
+impl Default for IdleConfig {
+    fn default() -> Self {
+        IdleConfig {
+            enabled: true,
+            threshold_mins: 15,
+        }
+    }
+}
+
+/// Battery-aware polling; see `power`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PowerConfig {
+    /// How many times slower to refresh on battery; 1 turns this off.
+    pub battery_refresh_multiplier: u32,
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        PowerConfig {
+            battery_refresh_multiplier: 2,
+        }
+    }
+}
+
+/// Do Not Disturb handling; see `focus_mode`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FocusModeConfig {
+    /// Critical alerts still notify and play sounds during Do Not Disturb.
+    pub critical_breaks_through: bool,
+}
+
+impl Default for FocusModeConfig {
+    fn default() -> Self {
+        FocusModeConfig {
+            critical_breaks_through: true,
+        }
+    }
+}
+
+/// Feed format of a local ADS-B receiver; see `adsb`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AdsbProtocol {
+    /// BaseStation CSV, dump1090's port 30003.
+    #[default]
+    Sbs,
+    /// Beast binary, dump1090's port 30005.
+    Beast,
+}
+
+impl AdsbProtocol {
+    pub fn default_port(self) -> u16 {
+        match self {
+            AdsbProtocol::Sbs => 30003,
+            AdsbProtocol::Beast => 30005,
+        }
+    }
+}
+
+/// Local ADS-B receiver (dump1090, readsb); see `adsb`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdsbConfig {
+    pub enabled: bool,
+    pub host: String,
+    /// The protocol's usual port when unset.
+    pub port: Option<u16>,
+    pub protocol: AdsbProtocol,
+}
+
+impl Default for AdsbConfig {
+    fn default() -> Self {
+        AdsbConfig {
+            enabled: false,
+            host: "127.0.0.1".to_string(),
+            port: None,
+            protocol: AdsbProtocol::Sbs,
+        }
+    }
+}
+
+/// GDELT 2.0 event ingestion; see `gdelt`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GdeltConfig {
+    pub enabled: bool,
+    /// Theme names (`protest`, `conflict`, ...) or CAMEO event code
+    /// prefixes; every event when empty.
+    pub themes: Vec<String>,
+    /// FIPS country codes of the event location; anywhere when empty.
+    pub countries: Vec<String>,
+    /// Areas the event location must fall in; anywhere when empty.
+    pub bounding_boxes: Vec<BoundingBox>,
+}
+
+impl Default for GdeltConfig {
+    fn default() -> Self {
+        GdeltConfig {
+            enabled: true,
+            themes: vec!["protest".to_string(), "conflict".to_string()],
+            countries: Vec::new(),
+            bounding_boxes: Vec::new(),
+        }
+    }
+}
+
+/// Offline map tile cache; see `tile_cache`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TileCacheConfig {
+    /// Tile URL template with `{z}`, `{x}`, `{y}` and optionally `{s}`.
+    pub url: String,
+    /// Substituted for `{s}`, spreading requests across hosts.
+    pub subdomains: Vec<String>,
+    pub max_bytes: u64,
+    /// Cached tiles older than this are refetched when online.
+    pub max_age_days: u64,
+}
+
+impl Default for TileCacheConfig {
+    fn default() -> Self {
+        TileCacheConfig {
+            url: "https://tiles-{s}.basemaps.cartocdn.com/vectortiles/carto.streets/v1/{z}/{x}/{y}.mvt".to_string(),
+            subdomains: ["a", "b", "c", "d"].map(String::from).to_vec(),
+            max_bytes: 512 * 1024 * 1024,
+            max_age_days: 30,
+        }
+    }
+}
+
+/// Which USGS summary feed `usgs` polls.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UsgsFeed {
+    /// Quakes USGS rates significant, whatever their magnitude.
+    #[serde(rename = "significant")]
+    Significant,
+    /// M4.5 and above.
+    #[default]
+    #[serde(rename = "4.5")]
+    M45,
+    /// Every event, down to microquakes.
+    #[serde(rename = "all")]
+    All,
+}
+
+impl UsgsFeed {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UsgsFeed::Significant => "significant",
+            UsgsFeed::M45 => "4.5",
+            UsgsFeed::All => "all",
+        }
+    }
+}
+
+/// USGS earthquake feed polling; see `usgs`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsgsConfig {
+    pub enabled: bool,
+    pub feed: UsgsFeed,
+    /// Quakes below this magnitude are not stored.
+    pub min_magnitude: Option<f64>,
+    /// Areas the epicentre must fall in; anywhere when empty.
+    pub bounding_boxes: Vec<BoundingBox>,
+    /// New quakes of at least this magnitude raise a notification; none
+    /// when unset.
+    pub notify_magnitude: Option<f64>,
+}
+
+impl Default for UsgsConfig {
+    fn default() -> Self {
+        UsgsConfig {
+            enabled: true,
+            feed: UsgsFeed::M45,
+            min_magnitude: None,
+            bounding_boxes: Vec::new(),
+            notify_magnitude: Some(6.0),
+        }
+    }
+}
+
+/// FRED economic series fetching; see `fred`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FredConfig {
+    /// FRED series ids.
+    pub series: Vec<String>,
+    /// How many of the latest observations each fetch asks for.
+    pub observations: usize,
+}
+
+impl Default for FredConfig {
+    fn default() -> Self {
+        FredConfig {
+            series: ["WALCL", "FEDFUNDS", "T10Y2Y", "UNRATE", "CPIAUCSL", "DGS10", "VIXCLS"].map(String::from).to_vec(),
+            observations: 120,
+        }
+    }
+}
+
+/// One EIA API v2 dataset; see `eia`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EiaDataset {
+    /// Also names the alert metrics `eia.<id>` and `eia.<id>.change`.
+    pub id: String,
+    pub name: String,
+    /// Route under `/v2/`, such as `petroleum/stoc/wstk`.
+    pub route: String,
+    /// `hourly`, `daily`, `weekly`, `monthly` or `annual`.
+    pub frequency: String,
+    /// Facet filters, such as `series: ["WCESTUS1"]`. Rows matching the same
+    /// period are summed.
+    #[serde(default)]
+    pub facets: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    pub unit: String,
+}
+
+impl EiaDataset {
+    fn new(id: &str, name: &str, route: &str, frequency: &str, facets: &[(&str, &str)], unit: &str) -> Self {
+        EiaDataset {
+            id: id.to_string(),
+            name: name.to_string(),
+            route: route.to_string(),
+            frequency: frequency.to_string(),
+            facets: facets.iter().map(|(k, v)| (k.to_string(), vec![v.to_string()])).collect(),
+            unit: unit.to_string(),
+        }
+    }
+}
+
+/// EIA energy data fetching; see `eia`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EiaConfig {
+    pub datasets: Vec<EiaDataset>,
+    /// Periods fetched the first time a dataset is seen.
+    pub history: usize,
+}
+
+impl Default for EiaConfig {
+    fn default() -> Self {
+        EiaConfig {
+            datasets: vec![
+                EiaDataset::new(
+                    "crude_stocks",
+                    "U.S. crude oil stocks (excl. SPR)",
+                    "petroleum/stoc/wstk",
+                    "weekly",
+                    &[("series", "WCESTUS1")],
+                    "thousand barrels",
+                ),
+                EiaDataset::new(
+                    "gas_storage",
+                    "Lower 48 working gas in storage",
+                    "natural-gas/stor/wkly",
+                    "weekly",
+                    &[("series", "NW2_EPG0_SWO_R48_BCF")],
+                    "Bcf",
+                ),
+                EiaDataset::new(
+                    "henry_hub",
+                    "Henry Hub natural gas spot price",
+                    "natural-gas/pri/fut",
+                    "daily",
+                    &[("series", "RNGWHHD")],
+                    "$/MMBtu",
+                ),
+                EiaDataset::new("wti", "WTI crude oil spot price", "petroleum/pri/spt", "daily", &[("series", "RWTC")], "$/barrel"),
+                EiaDataset::new(
+                    "us48_demand",
+                    "Lower 48 electricity demand",
+                    "electricity/rto/region-data",
+                    "hourly",
+                    &[("respondent", "US48"), ("type", "D")],
+                    "MWh",
+                ),
+            ],
+            history: 104,
+        }
+    }
+}
+
+/// Scheduled notification digests; see `digest`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DigestConfig {
+    pub enabled: bool,
+    /// Local times of day to deliver a digest at, `HH:MM`.
+    pub times: Vec<String>,
+    /// How far back each digest looks.
+    pub window_hours: u32,
+    /// Most alerts, quakes and headlines listed in each section.
+    pub max_items: usize,
+    /// Smaller quakes are left out.
+    pub min_magnitude: f64,
+    /// While digests are on, non-critical alert and earthquake
+    /// notifications wait for the next digest instead of showing at once.
+    pub hold_notifications: bool,
+    /// Have the configured LLM (Ollama, Groq or OpenRouter) write the
+    /// digest's text.
+    pub summarize: bool,
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        DigestConfig {
+            enabled: false,
+            times: vec!["08:00".to_string(), "18:00".to_string()],
+            window_hours: 12,
+            max_items: 3,
+            min_magnitude: 5.0,
+            hold_notifications: true,
+            summarize: false,
+        }
+    }
+}
+
+/// FAA airspace notices; see `faa`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FaaConfig {
+    /// Poll the active TFRs.
+    pub enabled: bool,
+    /// ICAO locations (airports, ARTCCs) to fetch NOTAMs for, e.g. `KJFK`
+    /// or `KZDC`. Needs the FAA NOTAM API credentials.
+    pub notam_locations: Vec<String>,
+    /// Circles to fetch NOTAMs in, as well.
+    pub notam_areas: Vec<NotamArea>,
+}
+
+impl Default for FaaConfig {
+    fn default() -> Self {
+        FaaConfig {
+            enabled: true,
+            notam_locations: Vec::new(),
+            notam_areas: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotamArea {
+    pub lat: f64,
+    pub lon: f64,
+    /// Nautical miles, at most 100.
+    pub radius_nm: f64,
+}
+
+/// Satellite tracking; see `orbits`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OrbitsConfig {
+    pub enabled: bool,
+    /// Celestrak groups to track, e.g. `stations`, `visual`, `starlink`.
+    pub groups: Vec<String>,
+    /// Seconds between position updates.
+    pub interval_secs: u64,
+    /// Satellites beyond this many (in group order) are not tracked.
+    pub max_satellites: usize,
+    /// Where to predict passes for; none turns the hourly prediction off.
+    pub observer: Option<Observer>,
+    /// Degrees above the horizon a pass has to reach.
+    pub min_elevation: f64,
+    /// NORAD catalogue numbers to predict passes of.
+    pub pass_satellites: Vec<u32>,
+    /// How far ahead to predict.
+    pub pass_hours: u32,
+}
+
+impl Default for OrbitsConfig {
+    fn default() -> Self {
+        OrbitsConfig {
+            enabled: false,
+            groups: vec!["stations".to_string(), "visual".to_string()],
+            interval_secs: 10,
+            max_satellites: 5000,
+            observer: None,
+            min_elevation: 10.0,
+            pass_satellites: vec![25544],
+            pass_hours: 24,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Observer {
+    pub lat: f64,
+    pub lon: f64,
+    /// Metres above the ellipsoid.
+    #[serde(default)]
+    pub alt_m: f64,
+}
+
+/// NOAA space weather products; see `swpc`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SwpcConfig {
+    pub enabled: bool,
+}
+
+impl Default for SwpcConfig {
+    fn default() -> Self {
+        SwpcConfig { enabled: true }
+    }
+}
+
+/// Cloudflare Radar outages; see `cloudflare`. Synced whenever
+/// `CLOUDFLARE_API_TOKEN` is stored.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CloudflareConfig {
+    /// ISO country codes to fetch one by one; worldwide when empty.
+    pub countries: Vec<String>,
+}
+
+/// The built-in WebSocket relay; see `relay`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RelayConfig {
+    pub enabled: bool,
+    /// Listen on every interface rather than loopback only. Clients from
+    /// other machines still need `RELAY_SHARED_SECRET`.
+    pub lan: bool,
+    pub port: u16,
+    pub max_clients: usize,
+}
+
+impl Default for RelayConfig {
+    fn default() -> Self {
+        RelayConfig {
+            enabled: false,
+            lan: false,
+            port: 3004,
+            max_clients: 10,
+        }
+    }
+}
+
+/// Publishing to an MQTT broker; see `mqtt`. Topic templates take
+/// `{rule}`, `{kind}`, `{entry}` and `{name}`; an empty one publishes
+/// nothing of its kind.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttConfig {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub tls: bool,
+    pub client_id: String,
+    /// Sent with the `MQTT_PASSWORD` secret, if any.
+    pub username: Option<String>,
+    /// 0 or 1.
+    pub qos: u8,
+    pub keep_alive_secs: u16,
+    pub alert_topic: String,
+    pub watchlist_topic: String,
+    pub metric_topic: String,
+    /// Metric names to publish; a trailing `*` matches a prefix.
+    pub metrics: Vec<String>,
+    /// Retain metric messages, so new subscribers get the last value.
+    pub retain_metrics: bool,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        MqttConfig {
+            enabled: false,
+            host: String::new(),
+            port: 8883,
+            tls: true,
+            client_id: "world-monitor".to_string(),
+            username: None,
+            qos: 1,
+            keep_alive_secs: 60,
+            alert_topic: "worldmonitor/alerts/{rule}".to_string(),
+            watchlist_topic: "worldmonitor/watchlists/{kind}/{entry}".to_string(),
+            metric_topic: "worldmonitor/metrics/{name}".to_string(),
+            metrics: Vec::new(),
+            retain_metrics: true,
+        }
+    }
+}
+
+/// Cyber threat feeds; see `cyber`. `urlhaus` and `otx` are fetched only
+/// with `URLHAUS_AUTH_KEY` and `OTX_API_KEY` stored.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CyberConfig {
+    pub enabled: bool,
+    /// Any of `kev`, `feodo`, `urlhaus` and `otx`.
+    pub feeds: Vec<String>,
+    /// Indicators not seen for this many days are dropped.
+    pub days: u32,
+}
+
+impl Default for CyberConfig {
+    fn default() -> Self {
+        CyberConfig {
+            enabled: true,
+            feeds: ["kev", "feodo", "urlhaus", "otx"].map(String::from).to_vec(),
+            days: 14,
+        }
+    }
+}
+
+/// Market quotes polled into the store; see `markets`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarketsConfig {
+    pub enabled: bool,
+    pub interval_secs: u64,
+    pub symbols: Vec<MarketSymbol>,
+    /// Ticks older than this many days are dropped.
+    pub retention_days: u32,
+}
+
+impl Default for MarketsConfig {
+    fn default() -> Self {
+        let symbol = |symbol: &str, name: &str, kind| MarketSymbol {
+            symbol: symbol.to_string(),
+            name: name.to_string(),
+            kind,
+            provider: MarketProvider::Yahoo,
+        };
+        MarketsConfig {
+            enabled: true,
+            interval_secs: 300,
+            symbols: vec![
+                symbol("^GSPC", "S&P 500", MarketKind::Index),
+                symbol("^DJI", "Dow Jones", MarketKind::Index),
+                symbol("^IXIC", "Nasdaq", MarketKind::Index),
+                symbol("^FTSE", "FTSE 100", MarketKind::Index),
+                symbol("^N225", "Nikkei 225", MarketKind::Index),
+                symbol("BZ=F", "Brent crude", MarketKind::Commodity),
+                symbol("CL=F", "WTI crude", MarketKind::Commodity),
+                symbol("NG=F", "Natural gas", MarketKind::Commodity),
+                symbol("GC=F", "Gold", MarketKind::Commodity),
+                symbol("EURUSD=X", "EUR/USD", MarketKind::Fx),
+                symbol("GBPUSD=X", "GBP/USD", MarketKind::Fx),
+                symbol("JPY=X", "USD/JPY", MarketKind::Fx),
+                symbol("CNY=X", "USD/CNY", MarketKind::Fx),
+            ],
+            retention_days: 7,
+        }
+    }
+}
+
+/// One polled instrument, by the provider's own symbol.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarketSymbol {
+    pub symbol: String,
+    /// Shown instead of the symbol; the symbol when empty.
+    pub name: String,
+    pub kind: MarketKind,
+    pub provider: MarketProvider,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarketKind {
+    #[default]
+    Index,
+    Commodity,
+    Fx,
+}
+
+/// Where quotes come from: Yahoo Finance's chart API (intraday ticks, no
+/// key) or Finnhub's quote API (one tick per poll, `FINNHUB_API_KEY`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarketProvider {
+    #[default]
+    Yahoo,
+    Finnhub,
+}
+
+/// Maritime zone boundaries; see `maritime`. The URLs are only used when
+/// neither the cache nor the bundle has a layer; an empty one skips it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MaritimeConfig {
+    pub enabled: bool,
+    pub eez_url: String,
+    pub territorial_url: String,
+    /// Douglas-Peucker tolerance in degrees for downloaded boundaries.
+    pub tolerance: f64,
+}
+
+impl Default for MaritimeConfig {
+    fn default() -> Self {
+        let layer = |name: &str| {
+            format!(
+                "https://geo.vliz.be/geoserver/MarineRegions/wfs?service=WFS&version=1.0.0&request=GetFeature\
+                 &typeName=MarineRegions:{name}&outputFormat=application/json"
+            )
+        };
+        MaritimeConfig {
+            enabled: true,
+            eez_url: layer("eez"),
+            territorial_url: layer("eez_12nm"),
+            tolerance: 0.01,
+        }
+    }
+}
+
+/// Flight anomaly detection; see `anomalies`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnomaliesConfig {
+    pub enabled: bool,
+    /// Anomalies scoring below this (0-1) are dropped.
+    pub min_score: f64,
+    /// Metres per second of descent that count as a sudden drop.
+    pub descent_rate: f64,
+    /// Full turns inside a watchlist region that count as circling.
+    pub circling_turns: f64,
+    pub circling_window_secs: u64,
+    /// Metres per second between two reports beyond which the position
+    /// jumped.
+    pub max_speed: f64,
+}
+
+impl Default for AnomaliesConfig {
+    fn default() -> Self {
+        AnomaliesConfig {
+            enabled: true,
+            min_score: 0.3,
+            descent_rate: 30.0,
+            circling_turns: 2.0,
+            circling_window_secs: 600,
+            max_speed: 400.0,
+        }
+    }
+}
+
+/// Scheduled export jobs; see `exports`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportsConfig {
+    pub enabled: bool,
+    /// Where files are written, relative to the app's data directory; an
+    /// `exports` folder there when empty.
+    pub directory: String,
+}
+
+impl Default for ExportsConfig {
+    fn default() -> Self {
+        ExportsConfig {
+            enabled: true,
+            directory: String::new(),
+        }
+    }
+}
+
+/// Native alert sounds; see `sounds`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundsConfig {
+    pub enabled: bool,
+    /// From 0 (silent) to 1.
+    pub volume: f64,
+    /// Local time of day, `HH:MM`, from which alert sounds stay silent
+    /// until `quiet_until`; no quiet hours when either is empty.
+    pub quiet_from: String,
+    pub quiet_until: String,
+    /// WAV files played instead of the built-in tones, by severity.
+    pub files: BTreeMap<AlertSeverity, String>,
+}
+
+impl Default for SoundsConfig {
+    fn default() -> Self {
+        SoundsConfig {
+            enabled: true,
+            volume: 0.6,
+            quiet_from: String::new(),
+            quiet_until: String::new(),
+            files: BTreeMap::new(),
+        }
+    }
+}
+
+/// Text-to-speech briefings; see `speech`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpeechConfig {
+    pub enabled: bool,
+    /// The platform voice to use; the system default when empty.
+    pub voice: String,
+    /// Speaking rate relative to normal, from 0.5 to 2.
+    pub rate: f64,
+    /// Read out critical alerts as they fire.
+    pub announce_alerts: bool,
+    /// Read out each digest as it is delivered.
+    pub announce_digests: bool,
+}
+
+impl Default for SpeechConfig {
+    fn default() -> Self {
+        SpeechConfig {
+            enabled: true,
+            voice: String::new(),
+            rate: 1.0,
+            announce_alerts: false,
+            announce_digests: false,
+        }
+    }
+}
+
+/// Volcano notices and tsunami bulletins; see `hazards`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HazardsConfig {
+    pub enabled: bool,
+    /// Poll USGS volcano notices and the Smithsonian weekly reports.
+    pub volcanoes: bool,
+    /// Poll the NOAA tsunami warning centers.
+    pub tsunamis: bool,
+    /// New or raised hazards at this level or above raise a notification;
+    /// none when unset.
+    pub notify_level: Option<HazardLevel>,
+}
+
+impl Default for HazardsConfig {
+    fn default() -> Self {
+        HazardsConfig {
+            enabled: true,
+            volcanoes: true,
+            tsunamis: true,
+            notify_level: Some(HazardLevel::Watch),
+        }
+    }
+}
+
+/// Managed state wrapping the loaded config and where it is persisted.
+pub struct DesktopConfigState {
+    path: PathBuf,
+    config: Mutex<DesktopConfig>,
+}
+
+impl DesktopConfigState {
+    pub fn new(path: PathBuf, config: DesktopConfig) -> Self {
+        DesktopConfigState {
+            path,
+            config: Mutex::new(config),
+        }
+    }
+
+    pub fn snapshot(&self) -> DesktopConfig {
+        self.config.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Apply `update` and persist. Like the secrets vault, the in-memory copy
+    /// only changes once the write has succeeded.
+    pub fn update(&self, update: impl FnOnce(&mut DesktopConfig)) -> Result<DesktopConfig, String> {
+        let mut config = self.config.lock().unwrap_or_else(|e| e.into_inner());
+        let mut proposed = config.clone();
+        update(&mut proposed);
+        save(&self.path, &proposed)?;
+        *config = proposed.clone();
+        Ok(proposed)
+    }
+}
+
+/// Load the config. A missing file yields defaults; an unreadable or invalid
+/// file yields `Err` so the caller can log it and continue with defaults.
+pub fn load(path: &Path) -> Result<DesktopConfig, String> {
+    if !path.exists() {
+        return Ok(DesktopConfig::default());
+    }
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read desktop config {}: {e}", path.display()))?;
+    serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid desktop config {}: {e}", path.display()))
+}
+
+pub fn save(path: &Path, config: &DesktopConfig) -> Result<(), String> {
+    let serialized = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize desktop config: {e}"))?;
+    fs::write(path, serialized)
+        .map_err(|e| format!("Failed to write desktop config {}: {e}", path.display()))
+}
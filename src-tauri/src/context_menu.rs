@@ -0,0 +1,178 @@
+//! Native context menus for the web UI. `show_context_menu` builds a popup
+//! from a JSON description and opens it at the cursor in the calling window;
+//! the chosen item comes back to that window as `context-menu://selected`
+//! with the request number the command returned. Dismissing the menu sends
+//! nothing.
+//!
+//! Item ids are namespaced as `ctx:<request>:<id>` so the app menu handler
+//! can tell popup clicks from its own items and stale popups are ignored.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem};
+use tauri::{AppHandle, Emitter, Manager, Webview, Wry};
+
+pub const SELECTED_EVENT: &str = "context-menu://selected";
+const ID_PREFIX: &str = "ctx:";
+const MAX_ITEMS: usize = 64;
+const MAX_ID_LEN: usize = 64;
+const MAX_LABEL_LEN: usize = 200;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ContextMenuItem {
+    Item {
+        id: String,
+        label: String,
+        #[serde(default = "enabled_by_default")]
+        enabled: bool,
+        /// Renders a check item when set.
+        #[serde(default)]
+        checked: Option<bool>,
+    },
+    Separator,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Selection {
+    request: u64,
+    id: String,
+}
+
+/// Managed state: the most recent popup and the window that asked for it.
+#[derive(Default)]
+pub struct ContextMenuState(Mutex<(u64, Option<String>)>);
+
+fn validate(items: &[ContextMenuItem]) -> Result<(), String> {
+    if items.is_empty() || items.len() > MAX_ITEMS {
+        return Err(format!("Context menu needs 1-{MAX_ITEMS} items"));
+    }
+    for item in items {
+        if let ContextMenuItem::Item { id, label, .. } = item {
+            if id.is_empty() || id.len() > MAX_ID_LEN {
+                return Err(format!("Invalid context menu item id: {id}"));
+            }
+            if label.trim().is_empty() || label.len() > MAX_LABEL_LEN {
+                return Err(format!("Invalid label for context menu item {id}"));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `(request, item id)` for a popup menu event id.
+fn parse_event_id(event_id: &str) -> Option<(u64, &str)> {
+    let (request, id) = event_id.strip_prefix(ID_PREFIX)?.split_once(':')?;
+    Some((request.parse().ok()?, id))
+}
+
+pub fn owns(event_id: &str) -> bool {
+    event_id.starts_with(ID_PREFIX)
+}
+
+fn build(app: &AppHandle, request: u64, items: &[ContextMenuItem]) -> Result<Menu<Wry>, String> {
+    let map_err = |e: tauri::Error| format!("Failed to build context menu: {e}");
+    let mut built: Vec<Box<dyn IsMenuItem<Wry>>> = Vec::with_capacity(items.len());
+    for item in items {
+        match item {
+            ContextMenuItem::Separator => built.push(Box::new(PredefinedMenuItem::separator(app).map_err(map_err)?)),
+            ContextMenuItem::Item { id, label, enabled, checked } => {
+                let id = format!("{ID_PREFIX}{request}:{id}");
+                match checked {
+                    Some(checked) => built.push(Box::new(
+                        CheckMenuItem::with_id(app, id, label, *enabled, *checked, None::<&str>).map_err(map_err)?,
+                    )),
+                    None => built.push(Box::new(
+                        MenuItem::with_id(app, id, label, *enabled, None::<&str>).map_err(map_err)?,
+                    )),
+                }
+            }
+        }
+    }
+    let refs: Vec<&dyn IsMenuItem<Wry>> = built.iter().map(|item| item.as_ref()).collect();
+    Menu::with_items(app, &refs).map_err(map_err)
+}
+
+/// Forward a popup click to the window that opened the popup.
+pub fn on_selected(app: &AppHandle, event_id: &str) {
+    let Some((request, id)) = parse_event_id(event_id) else {
+        return;
+    };
+    let Some(state) = app.try_state::<ContextMenuState>() else {
+        return;
+    };
+    let target = {
+        let current = state.0.lock().unwrap_or_else(|e| e.into_inner());
+        if current.0 != request {
+            return;
+        }
+        current.1.clone()
+    };
+    if let Some(label) = target {
+        let _ = app.emit_to(label.as_str(), SELECTED_EVENT, Selection { request, id: id.to_string() });
+    }
+}
+
+/// Show a native popup at the cursor. Returns the request number that
+/// `context-menu://selected` will carry.
+#[tauri::command]
+pub fn show_context_menu(
+    webview: Webview,
+    app: AppHandle,
+    state: tauri::State<'_, ContextMenuState>,
+    items: Vec<ContextMenuItem>,
+) -> Result<u64, String> {
+    crate::metrics::of(&webview).observe("show_context_menu", || {
+        crate::require_trusted_window(webview.label())?;
+        validate(&items)?;
+        let request = {
+            let mut current = state.0.lock().unwrap_or_else(|e| e.into_inner());
+            current.0 += 1;
+            current.1 = Some(webview.label().to_string());
+            current.0
+        };
+        let menu = build(&app, request, &items)?;
+        webview
+            .window()
+            .popup_menu(&menu)
+            .map_err(|e| format!("Failed to show context menu: {e}"))?;
+        Ok(request)
+    })
+}
+
+#[cfg(test)]
+mod context_menu_tests {
+    use super::{parse_event_id, validate, ContextMenuItem};
+
+    fn item(id: &str, label: &str) -> ContextMenuItem {
+        ContextMenuItem::Item {
+            id: id.to_string(),
+            label: label.to_string(),
+            enabled: true,
+            checked: None,
+        }
+    }
+
+    #[test]
+    fn parses_namespaced_event_ids() {
+        assert_eq!(parse_event_id("ctx:7:copy-coords"), Some((7, "copy-coords")));
+        assert_eq!(parse_event_id("ctx:7:a:b"), Some((7, "a:b")));
+        assert_eq!(parse_event_id("file.quit"), None);
+        assert_eq!(parse_event_id("ctx:x:copy"), None);
+    }
+
+    #[test]
+    fn validates_menu_description() {
+        assert!(validate(&[item("copy", "Copy"), ContextMenuItem::Separator]).is_ok());
+        assert!(validate(&[]).is_err());
+        assert!(validate(&[item("", "Copy")]).is_err());
+        assert!(validate(&[item("copy", "  ")]).is_err());
+        let too_many: Vec<_> = (0..65).map(|i| item(&i.to_string(), "x")).collect();
+        assert!(validate(&too_many).is_err());
+    }
+}
@@ -0,0 +1,203 @@
+//! Crash reporting. A panic hook (and the fatal-error path around Tauri
+//! startup) writes `crash-<epoch>.json` into the logs dir with the panic
+//! message, backtrace, a tail of `desktop.log`, and basic system info. On the
+//! next launch the UI asks `get_last_crash_report` and, once the user has
+//! seen it, calls `acknowledge_crash_report` so it is not offered again.
+
+use std::backtrace::Backtrace;
+use std::fs;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use sysinfo::System;
+use tauri::{AppHandle, Webview};
+
+const CRASH_PREFIX: &str = "crash-";
+const CRASH_SUFFIX: &str = ".json";
+const ACKNOWLEDGED_SUFFIX: &str = ".seen.json";
+const CRASH_LOG_TAIL_LINES: usize = 200;
+
+/// Logs dir recorded once it is known (Tauri `setup`); panics earlier than
+/// that only reach the default hook on stderr.
+static CRASH_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub id: String,
+    pub timestamp: u64,
+    pub kind: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    pub backtrace: String,
+    pub app_version: String,
+    pub os: String,
+    pub os_version: Option<String>,
+    pub arch: String,
+    pub recent_log: Vec<String>,
+}
+
+impl CrashReport {
+    fn capture(kind: &str, message: String, location: Option<String>) -> Self {
+        let timestamp = crate::unix_timestamp_secs();
+        let recent_log = CRASH_DIR
+            .get()
+            .and_then(|dir| {
+                crate::log_files::read_tail_lines(&dir.join(crate::DESKTOP_LOG_FILE), CRASH_LOG_TAIL_LINES).ok()
+            })
+            .map(|(lines, _)| lines)
+            .unwrap_or_default();
+        CrashReport {
+            id: format!("{CRASH_PREFIX}{timestamp}"),
+            timestamp,
+            kind: kind.to_string(),
+            message,
+            location,
+            thread: std::thread::current().name().map(str::to_string),
+            backtrace: Backtrace::force_capture().to_string(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            os_version: System::long_os_version(),
+            arch: std::env::consts::ARCH.to_string(),
+            recent_log,
+        }
+    }
+
+    fn write(&self) -> Option<PathBuf> {
+        let path = CRASH_DIR.get()?.join(format!("{}{CRASH_SUFFIX}", self.id));
+        let json = serde_json::to_string_pretty(self).ok()?;
+        fs::write(&path, json).ok()?;
+        Some(path)
+    }
+}
+
+fn panic_message(info: &PanicHookInfo<'_>) -> String {
+    if let Some(message) = info.payload().downcast_ref::<&str>() {
+        (*message).to_string()
+    } else if let Some(message) = info.payload().downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "panic with non-string payload".to_string()
+    }
+}
+
+/// Chain a crash-report writer in front of the default panic hook. Call at
+/// the very start of `main`.
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let location = info.location().map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+        let report = CrashReport::capture("panic", panic_message(info), location);
+        tracing::error!(
+            target: "app",
+            location = report.location.as_deref(),
+            "panic: {}",
+            report.message
+        );
+        if let Some(path) = report.write() {
+            eprintln!("[tauri] crash report written to {}", path.display());
+        }
+        crate::error_reporting::report(
+            crate::error_reporting::ErrorSource::Panic,
+            report.message.clone(),
+            report.location.clone(),
+        );
+        default_hook(info);
+    }));
+}
+
+/// Enable report writing once the logs dir is known.
+pub fn set_crash_dir(dir: PathBuf) {
+    let _ = CRASH_DIR.set(dir);
+}
+
+/// Record a non-panic fatal error (e.g. Tauri failing to build) before exit.
+pub fn record_fatal_error(message: String) {
+    tracing::error!(target: "app", "fatal: {message}");
+    let report = CrashReport::capture("fatal-error", message, None);
+    if let Some(path) = report.write() {
+        eprintln!("[tauri] crash report written to {}", path.display());
+    }
+    crate::error_reporting::report(crate::error_reporting::ErrorSource::Fatal, report.message, None);
+}
+
+/// Newest unacknowledged `crash-<epoch>.json` in `dir`.
+fn latest_unacknowledged(dir: &Path) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let timestamp = name
+                .strip_prefix(CRASH_PREFIX)?
+                .strip_suffix(CRASH_SUFFIX)?
+                .parse::<u64>()
+                .ok()?;
+            Some((timestamp, entry.path()))
+        })
+        .max_by_key(|(timestamp, _)| *timestamp)
+        .map(|(_, path)| path)
+}
+
+#[tauri::command]
+pub async fn get_last_crash_report(webview: Webview, app: AppHandle) -> Result<Option<CrashReport>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("get_last_crash_report", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                let Some(path) = latest_unacknowledged(&crate::logs_dir_path(&app)?) else {
+                    return Ok(None);
+                };
+                let contents = fs::read_to_string(&path)
+                    .map_err(|e| format!("Failed to read crash report {}: {e}", path.display()))?;
+                serde_json::from_str(&contents)
+                    .map(Some)
+                    .map_err(|e| format!("Invalid crash report {}: {e}", path.display()))
+            })
+            .await
+        })
+        .await
+}
+
+/// Mark a report as seen (renamed to `crash-<epoch>.seen.json`, kept on disk
+/// for diagnostics bundles).
+#[tauri::command]
+pub async fn acknowledge_crash_report(webview: Webview, app: AppHandle, id: String) -> Result<(), String> {
+    crate::metrics::of(&webview)
+        .observe_async("acknowledge_crash_report", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                let timestamp = id
+                    .strip_prefix(CRASH_PREFIX)
+                    .and_then(|t| t.parse::<u64>().ok())
+                    .ok_or_else(|| format!("Invalid crash report id: {id}"))?;
+                let dir = crate::logs_dir_path(&app)?;
+                let from = dir.join(format!("{CRASH_PREFIX}{timestamp}{CRASH_SUFFIX}"));
+                let to = dir.join(format!("{CRASH_PREFIX}{timestamp}{ACKNOWLEDGED_SUFFIX}"));
+                fs::rename(&from, &to).map_err(|e| format!("Failed to acknowledge crash report {id}: {e}"))
+            })
+            .await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod crash_report_tests {
+    use super::latest_unacknowledged;
+    use std::fs;
+
+    #[test]
+    fn picks_newest_unacknowledged_report() {
+        let dir = std::env::temp_dir().join(format!("wm-crash-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        assert!(latest_unacknowledged(&dir).is_none());
+
+        for name in ["crash-100.json", "crash-300.seen.json", "crash-200.json", "desktop.log"] {
+            fs::write(dir.join(name), "{}").unwrap();
+        }
+        assert_eq!(latest_unacknowledged(&dir), Some(dir.join("crash-200.json")));
+    }
+}
@@ -0,0 +1,883 @@
+//! Cyber threat feeds synced into the local `store`, so the cyber layer
+//! works offline and keyword rules can watch it. The feeds named in
+//! `cyber.feeds` are fetched at startup and then every `SYNC_INTERVAL`, or
+//! right away with `sync_cyber_threats`:
+//!
+//! - `kev`: CISA's Known Exploited Vulnerabilities catalog, one row per
+//!   CVE, re-imported only when its version changes;
+//! - `feodo`: abuse.ch Feodo Tracker botnet C2 servers;
+//! - `urlhaus`: abuse.ch URLhaus malware URLs, with `URLHAUS_AUTH_KEY`;
+//! - `otx`: AlienVault OTX IPv4 indicators, with `OTX_API_KEY`.
+//!
+//! Indicators are shaped like the cyber service's `CyberThreat`, placed at
+//! their country's centroid when the feed gives no coordinates, and stored
+//! once per indicator however many feeds report it: `sources`, tags and
+//! the seen times are merged and the highest severity wins. Indicators not
+//! seen for `cyber.days` are pruned.
+//!
+//! Vulnerabilities and indicators new to the store go through the keyword
+//! rules and watchlists like headlines do; new vulnerabilities also go out
+//! as one `cyber://vulnerabilities` batch. The first import of each is
+//! stored quietly.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+use tokio::sync::watch;
+
+use crate::alerts::Headline;
+use crate::config::DesktopConfigState;
+use crate::store::{with_store, IndicatorQuery, IndicatorRow, VulnerabilityQuery, VulnerabilityRow};
+
+pub const VULNERABILITIES_EVENT: &str = "cyber://vulnerabilities";
+
+const KEV_URL: &str = "https://www.cisa.gov/sites/default/files/feeds/known_exploited_vulnerabilities.json";
+const FEODO_URL: &str = "https://feodotracker.abuse.ch/downloads/ipblocklist.json";
+const URLHAUS_URL: &str = "https://urlhaus-api.abuse.ch/v1/urls/recent/limit/1000/";
+const OTX_URL: &str = "https://otx.alienvault.com/api/v1/indicators/export";
+const NVD_URL: &str = "https://nvd.nist.gov/vuln/detail";
+const URLHAUS_SECRET: &str = "URLHAUS_AUTH_KEY";
+const OTX_SECRET: &str = "OTX_API_KEY";
+/// `sync_state` source holding the last imported catalog version.
+const KEV_CURSOR: &str = "cisa-kev";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+/// The catalog changes a few times a week and the blocklists hourly.
+const SYNC_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// The export pages through every indicator modified in the window.
+const OTX_MAX_PAGES: usize = 5;
+const MAX_TAGS: usize = 8;
+/// Spread, in degrees, of indicators placed at a country centroid.
+const CENTROID_JITTER: f64 = 1.0;
+
+const SEVERITIES: [&str; 4] =
+    ["CRITICALITY_LEVEL_LOW", "CRITICALITY_LEVEL_MEDIUM", "CRITICALITY_LEVEL_HIGH", "CRITICALITY_LEVEL_CRITICAL"];
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedStatus {
+    pub feed: String,
+    /// Records fetched in the last sync.
+    pub fetched: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CyberSyncStatus {
+    pub syncing: bool,
+    /// Unix seconds of the last completed sync.
+    pub last_sync: Option<u64>,
+    pub stored_indicators: usize,
+    pub stored_vulnerabilities: usize,
+    /// New indicators and vulnerabilities in the last completed sync.
+    pub added: usize,
+    pub feeds: Vec<FeedStatus>,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct CyberState {
+    /// Bumped to request a sync now.
+    trigger: Mutex<Option<watch::Sender<u64>>>,
+    status: Mutex<CyberSyncStatus>,
+}
+
+fn update_status(app: &AppHandle, f: impl FnOnce(&mut CyberSyncStatus)) -> CyberSyncStatus {
+    let state = app.state::<CyberState>();
+    let mut status = state.status.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut status);
+    status.clone()
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Catalog {
+    catalog_version: String,
+    vulnerabilities: Vec<KnownExploited>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct KnownExploited {
+    #[serde(rename = "cveID")]
+    cve_id: String,
+    vendor_project: String,
+    product: String,
+    vulnerability_name: String,
+    date_added: String,
+    short_description: String,
+    required_action: String,
+    due_date: String,
+    known_ransomware_campaign_use: String,
+    notes: String,
+    cwes: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct FeodoRecord {
+    ip_address: String,
+    status: String,
+    country: Option<String>,
+    first_seen: Option<String>,
+    last_online: Option<String>,
+    malware: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct UrlhausResponse {
+    query_status: String,
+    urls: Vec<UrlhausRecord>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct UrlhausRecord {
+    url: String,
+    url_status: String,
+    host: String,
+    #[serde(alias = "dateadded")]
+    date_added: Option<String>,
+    last_online: Option<String>,
+    threat: Option<String>,
+    tags: Value,
+    country: Option<String>,
+    urlhaus_reference: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct OtxPage {
+    results: Vec<OtxRecord>,
+    next: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct OtxRecord {
+    indicator: String,
+    title: Option<String>,
+    description: Option<String>,
+    tags: Value,
+    created: Option<String>,
+    modified: Option<String>,
+}
+
+/// An indicator as one feed reports it.
+#[derive(Clone, Debug, PartialEq)]
+struct Threat {
+    /// `c2_server`, `malware_host`, `phishing` or `malicious_url`.
+    kind: &'static str,
+    source: &'static str,
+    indicator: String,
+    /// `ip`, `domain` or `url`.
+    indicator_type: &'static str,
+    country: String,
+    /// 1 (low) to 4 (critical).
+    severity: u8,
+    malware_family: String,
+    tags: Vec<String>,
+    first_seen: i64,
+    last_seen: i64,
+    link: String,
+}
+
+impl Threat {
+    fn title(&self) -> String {
+        let kind = match self.kind {
+            "c2_server" => "C2 server",
+            "malware_host" => "malware host",
+            "phishing" => "phishing host",
+            _ => "malicious URL",
+        };
+        match self.malware_family.as_str() {
+            "" => format!("{kind} {}", self.indicator),
+            family => format!("{family} {kind} {}", self.indicator),
+        }
+    }
+
+    fn into_row(self, now: i64) -> IndicatorRow {
+        let place = place(&self.indicator, &self.country);
+        let last_seen = self.last_seen.max(self.first_seen);
+        let last_seen = if last_seen > 0 { last_seen } else { now };
+        IndicatorRow {
+            data: json!({
+                "id": format!("{}:{}", self.indicator_type, self.indicator),
+                "type": format!("CYBER_THREAT_TYPE_{}", self.kind.to_uppercase()),
+                "source": format!("CYBER_THREAT_SOURCE_{}", self.source.to_uppercase()),
+                "sources": [self.source],
+                "indicator": self.indicator,
+                "indicatorType": format!("CYBER_THREAT_INDICATOR_TYPE_{}", self.indicator_type.to_uppercase()),
+                "location": place.map(|(lat, lon)| json!({ "latitude": lat, "longitude": lon })),
+                "country": self.country,
+                "severity": SEVERITIES[usize::from(self.severity.clamp(1, 4)) - 1],
+                "malwareFamily": self.malware_family,
+                "tags": self.tags,
+                "firstSeenAt": self.first_seen,
+                "lastSeenAt": last_seen,
+            }),
+            indicator_type: self.indicator_type.to_string(),
+            indicator: self.indicator,
+            severity: self.severity,
+            last_seen,
+            lat: place.map(|(lat, _)| lat),
+            lon: place.map(|(_, lon)| lon),
+        }
+    }
+}
+
+/// Collapse whitespace and cut to `max` characters.
+fn clean(value: &str, max: usize) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ").chars().take(max).collect()
+}
+
+fn is_ip(value: &str) -> bool {
+    value.parse::<IpAddr>().is_ok()
+}
+
+/// Lowercase, deduplicated tags from an array or a `;`, `,` or `|`
+/// separated string.
+fn tags(values: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in values.into_iter().flat_map(|v| v.split([';', ',', '|']).map(|t| clean(t, 40).to_lowercase()).collect::<Vec<_>>()) {
+        if !tag.is_empty() && !tags.contains(&tag) && tags.len() < MAX_TAGS {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+fn tag_values(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) => items.iter().filter_map(|t| t.as_str().map(str::to_string)).collect(),
+        Value::String(s) => vec![s.clone()],
+        _ => Vec::new(),
+    }
+}
+
+fn country(value: Option<&str>) -> String {
+    let value = clean(value.unwrap_or_default(), 64);
+    if value.len() == 2 { value.to_uppercase() } else { value }
+}
+
+/// Milliseconds since the epoch of a feed time (RFC 3339, `YYYY-MM-DD
+/// HH:MM:SS` in UTC, or a date); 0 when missing or unparseable.
+fn millis(value: Option<&str>) -> i64 {
+    let Some(value) = value.map(|v| v.trim().trim_end_matches(" UTC")).filter(|v| !v.is_empty()) else {
+        return 0;
+    };
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return time.timestamp_millis();
+    }
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S%.f"] {
+        if let Ok(time) = NaiveDateTime::parse_from_str(value, format) {
+            return time.and_utc().timestamp_millis();
+        }
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map_or(0, |t| t.and_utc().timestamp_millis())
+}
+
+/// Where to draw an indicator: its country's centroid, spread by a
+/// per-indicator offset so one country's indicators don't stack.
+fn place(indicator: &str, country: &str) -> Option<(f64, f64)> {
+    let (lat, lon) = crate::cloudflare::centroid(country)?;
+    // FNV-1a, so the offset is the same on every sync.
+    let hash = indicator.bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| (h ^ u64::from(b)).wrapping_mul(0x100_0000_01b3));
+    let offset = |bits: u64| ((bits & 0xffff) as f64 / f64::from(0xffff) - 0.5) * 2.0 * CENTROID_JITTER;
+    Some((lat + offset(hash), lon + offset(hash >> 16)))
+}
+
+fn from_feodo(raw: FeodoRecord, cutoff: i64) -> Option<Threat> {
+    let ip = clean(&raw.ip_address, 80).to_lowercase();
+    let status = raw.status.trim().to_lowercase();
+    if !is_ip(&ip) || !matches!(status.as_str(), "" | "online" | "offline") {
+        return None;
+    }
+    let first_seen = millis(raw.first_seen.as_deref());
+    let last_seen = millis(raw.last_online.as_deref().or(raw.first_seen.as_deref()));
+    let activity = if last_seen > 0 { last_seen } else { first_seen };
+    if activity > 0 && activity < cutoff {
+        return None;
+    }
+    let family = clean(raw.malware.as_deref().unwrap_or_default(), 80);
+    let lower = family.to_lowercase();
+    let severity = if status != "online" {
+        2
+    } else if ["emotet", "qakbot", "trickbot", "dridex", "ransom"].iter().any(|f| lower.contains(f)) {
+        4
+    } else {
+        3
+    };
+    Some(Threat {
+        kind: "c2_server",
+        source: "feodo",
+        link: format!("https://feodotracker.abuse.ch/browse/host/{ip}/"),
+        indicator: ip,
+        indicator_type: "ip",
+        country: country(raw.country.as_deref()),
+        severity,
+        malware_family: family,
+        tags: tags(["botnet".to_string(), "c2".to_string()]),
+        first_seen,
+        last_seen,
+    })
+}
+
+fn from_urlhaus(raw: UrlhausRecord, cutoff: i64) -> Option<Threat> {
+    if !matches!(raw.url_status.trim().to_lowercase().as_str(), "" | "online") {
+        return None;
+    }
+    let url = clean(&raw.url, 1024);
+    let hostname = reqwest::Url::parse(&url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.trim_matches(['[', ']']).to_lowercase()))
+        .unwrap_or_default();
+    let host = clean(&raw.host, 80).to_lowercase();
+    let (indicator_type, indicator) = if is_ip(&host) {
+        ("ip", host)
+    } else if is_ip(&hostname) {
+        ("ip", hostname)
+    } else if !hostname.is_empty() {
+        ("domain", hostname)
+    } else {
+        ("url", url)
+    };
+    if indicator.is_empty() {
+        return None;
+    }
+    let first_seen = millis(raw.date_added.as_deref());
+    let last_seen = millis(raw.last_online.as_deref().or(raw.date_added.as_deref()));
+    let activity = if last_seen > 0 { last_seen } else { first_seen };
+    if activity > 0 && activity < cutoff {
+        return None;
+    }
+    let tags = tags(tag_values(&raw.tags));
+    let threat = raw.threat.as_deref().unwrap_or_default().to_lowercase();
+    let has_tag = |needle: &str| tags.iter().any(|t| t.contains(needle));
+    let kind = if threat.contains("phish") || has_tag("phish") {
+        "phishing"
+    } else if threat.contains("malware") || threat.contains("payload") || has_tag("malware") {
+        "malware_host"
+    } else {
+        "malicious_url"
+    };
+    let severity = match kind {
+        "phishing" => 2,
+        _ if tags.iter().any(|t| t == "ransomware" || t == "botnet") => 4,
+        "malware_host" => 3,
+        _ => 2,
+    };
+    Some(Threat {
+        kind,
+        source: "urlhaus",
+        indicator,
+        indicator_type,
+        country: country(raw.country.as_deref()),
+        severity,
+        malware_family: clean(raw.threat.as_deref().unwrap_or_default(), 80),
+        tags,
+        first_seen,
+        last_seen,
+        link: raw.urlhaus_reference.filter(|l| !l.is_empty()).unwrap_or_else(|| "https://urlhaus.abuse.ch/".to_string()),
+    })
+}
+
+fn from_otx(raw: OtxRecord) -> Option<Threat> {
+    let ip = clean(&raw.indicator, 80).to_lowercase();
+    if !is_ip(&ip) {
+        return None;
+    }
+    let tags = tags(tag_values(&raw.tags));
+    let matches = |needles: &[&str]| tags.iter().any(|t| needles.iter().any(|n| t.contains(n)));
+    let title = raw.title.filter(|t| !t.trim().is_empty()).or(raw.description).unwrap_or_default();
+    Some(Threat {
+        kind: if matches(&["c2", "botnet"]) { "c2_server" } else { "malware_host" },
+        source: "otx",
+        link: format!("https://otx.alienvault.com/indicator/ip/{ip}"),
+        indicator: ip,
+        indicator_type: "ip",
+        country: String::new(),
+        severity: if matches(&["ransomware", "apt", "c2", "botnet"]) { 3 } else { 2 },
+        malware_family: clean(&title, 200),
+        first_seen: millis(raw.created.as_deref()),
+        last_seen: millis(raw.modified.as_deref().or(raw.created.as_deref())),
+        tags,
+    })
+}
+
+fn from_kev(raw: KnownExploited) -> Option<VulnerabilityRow> {
+    let cve = raw.cve_id.trim().to_uppercase();
+    NaiveDate::parse_from_str(&raw.date_added, "%Y-%m-%d").ok()?;
+    if !cve.starts_with("CVE-") {
+        return None;
+    }
+    let ransomware = raw.known_ransomware_campaign_use.eq_ignore_ascii_case("known");
+    Some(VulnerabilityRow {
+        data: json!({
+            "id": cve,
+            "cve": cve,
+            "vendor": raw.vendor_project,
+            "product": raw.product,
+            "name": raw.vulnerability_name,
+            "description": raw.short_description,
+            "requiredAction": raw.required_action,
+            "dateAdded": raw.date_added,
+            "dueDate": raw.due_date,
+            "ransomware": ransomware,
+            "cwes": raw.cwes,
+            "notes": raw.notes,
+            "link": format!("{NVD_URL}/{cve}"),
+        }),
+        cve,
+        added: raw.date_added,
+        ransomware,
+    })
+}
+
+/// Fold a newly reported indicator into the stored one.
+fn merge(row: &IndicatorRow, stored: &Value) -> IndicatorRow {
+    let mut merged = row.clone();
+    let data = &mut merged.data;
+    let stored_severity = SEVERITIES.iter().position(|s| stored["severity"] == *s).map_or(0, |i| i as u8 + 1);
+    if stored_severity > row.severity {
+        merged.severity = stored_severity;
+        data["severity"] = stored["severity"].clone();
+        data["type"] = stored["type"].clone();
+    }
+    data["source"] = stored["source"].clone();
+    let strings = |value: &Value| tag_values(value).into_iter();
+    let mut sources: Vec<String> = strings(&stored["sources"]).collect();
+    sources.extend(strings(&row.data["sources"]).filter(|s| !strings(&stored["sources"]).any(|t| t == *s)));
+    data["sources"] = json!(sources);
+    data["tags"] = json!(tags(strings(&stored["tags"]).chain(strings(&row.data["tags"]))));
+    let first_seen = |v: &Value| v["firstSeenAt"].as_i64().filter(|&t| t > 0);
+    data["firstSeenAt"] = json!(first_seen(stored).into_iter().chain(first_seen(&row.data)).min().unwrap_or(0));
+    merged.last_seen = row.last_seen.max(stored["lastSeenAt"].as_i64().unwrap_or(0));
+    data["lastSeenAt"] = json!(merged.last_seen);
+    for key in ["malwareFamily", "country"] {
+        if data[key].as_str().unwrap_or_default().is_empty() {
+            data[key] = stored[key].clone();
+        }
+    }
+    if row.lat.is_none() {
+        if let (Some(lat), Some(lon)) = (stored["location"]["latitude"].as_f64(), stored["location"]["longitude"].as_f64()) {
+            (merged.lat, merged.lon) = (Some(lat), Some(lon));
+            data["location"] = stored["location"].clone();
+        }
+    }
+    merged
+}
+
+async fn fetch<T: serde::de::DeserializeOwned>(request: reqwest::RequestBuilder, feed: &str) -> Result<T, String> {
+    let response = request.send().await.map_err(|e| format!("request failed: {e}"))?;
+    match response.status() {
+        reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => Err(format!("{feed} rejected the API key")),
+        status if !status.is_success() => Err(format!("HTTP {status}")),
+        _ => response.json().await.map_err(|e| format!("invalid response: {e}")),
+    }
+}
+
+/// The catalog if its version differs from `imported`.
+async fn fetch_kev(client: &reqwest::Client, imported: Option<&str>) -> Result<Option<Catalog>, String> {
+    let catalog: Catalog = fetch(client.get(KEV_URL), "CISA").await?;
+    Ok((imported != Some(catalog.catalog_version.as_str())).then_some(catalog))
+}
+
+async fn fetch_feodo(client: &reqwest::Client, cutoff: i64) -> Result<(usize, Vec<Threat>), String> {
+    let records: Vec<FeodoRecord> = fetch(client.get(FEODO_URL), "Feodo Tracker").await?;
+    Ok((records.len(), records.into_iter().filter_map(|r| from_feodo(r, cutoff)).collect()))
+}
+
+async fn fetch_urlhaus(client: &reqwest::Client, key: &str, cutoff: i64) -> Result<(usize, Vec<Threat>), String> {
+    let body: UrlhausResponse = fetch(client.get(URLHAUS_URL).header("Auth-Key", key), "URLhaus").await?;
+    if !matches!(body.query_status.as_str(), "ok" | "no_results") {
+        return Err(format!("URLhaus: {}", body.query_status));
+    }
+    Ok((body.urls.len(), body.urls.into_iter().filter_map(|r| from_urlhaus(r, cutoff)).collect()))
+}
+
+async fn fetch_otx(client: &reqwest::Client, key: &str, since: &str) -> Result<(usize, Vec<Threat>), String> {
+    let mut url = reqwest::Url::parse(OTX_URL).map_err(|e| e.to_string())?;
+    url.query_pairs_mut().append_pair("type", "IPv4").append_pair("modified_since", since);
+    let (mut fetched, mut threats) = (0, Vec::new());
+    for _ in 0..OTX_MAX_PAGES {
+        let page: OtxPage = fetch(client.get(url.clone()).header("X-OTX-API-KEY", key), "AlienVault OTX").await?;
+        fetched += page.results.len();
+        threats.extend(page.results.into_iter().filter_map(from_otx));
+        match page.next.as_deref().and_then(|n| reqwest::Url::parse(n).ok()) {
+            Some(next) => url = next,
+            None => break,
+        }
+    }
+    Ok((fetched, threats))
+}
+
+/// New vulnerabilities and indicators, as the headlines rules and
+/// watchlists match.
+struct Sightings {
+    vulnerabilities: Vec<Value>,
+    threats: Vec<Threat>,
+    /// Whether each table was empty before this sync.
+    first_kev: bool,
+    first_indicators: bool,
+}
+
+/// One complete sync. Feed failures are recorded per feed; only a store
+/// failure fails the sync.
+async fn sync(app: &AppHandle, client: &reqwest::Client) -> Result<Sightings, String> {
+    let config = app.state::<DesktopConfigState>().snapshot().cyber;
+    let now = Utc::now();
+    let cutoff = now.timestamp_millis() - i64::from(config.days.max(1)) * 24 * 60 * 60 * 1000;
+    let secrets = app.state::<crate::SecretsCache>();
+    let mut feeds = Vec::new();
+    let mut threats = Vec::new();
+    let mut sightings = Sightings { vulnerabilities: Vec::new(), threats: Vec::new(), first_kev: false, first_indicators: false };
+
+    for feed in &config.feeds {
+        let result = match feed.as_str() {
+            "kev" => {
+                let imported = with_store(app, |store| store.cursor(KEV_CURSOR)).await?;
+                match fetch_kev(client, imported.as_deref()).await {
+                    Ok(Some(catalog)) => {
+                        let fetched = catalog.vulnerabilities.len();
+                        let rows: Vec<VulnerabilityRow> = catalog.vulnerabilities.into_iter().filter_map(from_kev).collect();
+                        let (first, added) = with_store(app, move |store| {
+                            let first = store.count_vulnerabilities()? == 0;
+                            let added = store.upsert_vulnerabilities(&rows)?;
+                            store.set_cursor(KEV_CURSOR, &catalog.catalog_version)?;
+                            Ok((first, added.into_iter().map(|i| rows[i].data.clone()).collect::<Vec<_>>()))
+                        })
+                        .await?;
+                        sightings.first_kev = first;
+                        sightings.vulnerabilities = added;
+                        Ok(fetched)
+                    }
+                    Ok(None) => Ok(0),
+                    Err(err) => Err(err),
+                }
+            }
+            "feodo" => fetch_feodo(client, cutoff).await.map(|(n, t)| {
+                threats.extend(t);
+                n
+            }),
+            "urlhaus" => match secrets.get(URLHAUS_SECRET) {
+                Some(key) => fetch_urlhaus(client, &key, cutoff).await.map(|(n, t)| {
+                    threats.extend(t);
+                    n
+                }),
+                None => Err(format!("{URLHAUS_SECRET} is not set")),
+            },
+            "otx" => match secrets.get(OTX_SECRET) {
+                Some(key) => {
+                    let since = DateTime::from_timestamp_millis(cutoff).unwrap_or(now).format("%Y-%m-%d").to_string();
+                    fetch_otx(client, &key, &since).await.map(|(n, t)| {
+                        threats.extend(t);
+                        n
+                    })
+                }
+                None => Err(format!("{OTX_SECRET} is not set")),
+            },
+            other => Err(format!("unknown feed {other}")),
+        };
+        feeds.push(match result {
+            Ok(fetched) => FeedStatus { feed: feed.clone(), fetched, error: None },
+            Err(error) => FeedStatus { feed: feed.clone(), fetched: 0, error: Some(error) },
+        });
+    }
+
+    // The same indicator from two feeds, or twice from one, is one row.
+    let mut unique: HashMap<(&'static str, String), usize> = HashMap::new();
+    let mut rows: Vec<IndicatorRow> = Vec::new();
+    let mut reported: Vec<Threat> = Vec::new();
+    let now_millis = now.timestamp_millis();
+    for threat in threats {
+        let row = threat.clone().into_row(now_millis);
+        match unique.get(&(threat.indicator_type, threat.indicator.clone())) {
+            Some(&i) => rows[i] = merge(&row, &rows[i].data),
+            None => {
+                unique.insert((threat.indicator_type, threat.indicator.clone()), rows.len());
+                rows.push(row);
+                reported.push(threat);
+            }
+        }
+    }
+    let (first, added, indicators, vulnerabilities) = with_store(app, move |store| {
+        let first = store.count_indicators()? == 0;
+        let added = store.upsert_indicators(&rows, merge)?;
+        store.prune_indicators(cutoff)?;
+        Ok((first, added, store.count_indicators()?, store.count_vulnerabilities()?))
+    })
+    .await?;
+    sightings.first_indicators = first;
+    sightings.threats = added.into_iter().map(|i| reported[i].clone()).collect();
+    update_status(app, |status| {
+        status.stored_indicators = indicators;
+        status.stored_vulnerabilities = vulnerabilities;
+        status.feeds = feeds;
+    });
+    Ok(sightings)
+}
+
+/// Pass new vulnerabilities and indicators to keyword rules and watchlists.
+fn report(app: &AppHandle, sightings: &Sightings) {
+    let mut items: Vec<(&str, String, String)> = Vec::new();
+    if !sightings.first_kev {
+        items.extend(sightings.vulnerabilities.iter().map(|v| {
+            let title = format!("{}: {} ({} {})", v["cve"].as_str().unwrap_or_default(), v["name"].as_str().unwrap_or_default(),
+                v["vendor"].as_str().unwrap_or_default(), v["product"].as_str().unwrap_or_default());
+            ("CISA KEV", title, v["link"].as_str().unwrap_or_default().to_string())
+        }));
+        if !sightings.vulnerabilities.is_empty() {
+            let _ = app.emit(VULNERABILITIES_EVENT, &sightings.vulnerabilities);
+        }
+    }
+    if !sightings.first_indicators {
+        items.extend(sightings.threats.iter().map(|t| {
+            let source = match t.source {
+                "feodo" => "Feodo Tracker",
+                "urlhaus" => "URLhaus",
+                _ => "AlienVault OTX",
+            };
+            (source, t.title(), t.link.clone())
+        }));
+    }
+    let headlines: Vec<Headline> =
+        items.iter().map(|(source, title, link)| Headline { source, title, link }).collect();
+    crate::alerts::observe_headlines(app, &headlines);
+    crate::watchlists::observe_headlines(app, &headlines);
+}
+
+struct Poller {
+    app: AppHandle,
+    client: reqwest::Client,
+}
+
+impl crate::poll::Poller for Poller {
+    async fn round(&mut self) -> Duration {
+        let app = &self.app;
+        update_status(app, |status| status.syncing = true);
+        let result = sync(app, &self.client).await;
+        let status = update_status(app, |status| {
+            status.syncing = false;
+            match &result {
+                Ok(sightings) => {
+                    status.last_sync = Some(crate::unix_timestamp_secs());
+                    status.added = sightings.vulnerabilities.len() + sightings.threats.len();
+                    status.error = None;
+                }
+                Err(err) => status.error = Some(err.clone()),
+            }
+        });
+        match result {
+            Ok(sightings) => {
+                for feed in status.feeds.iter().filter(|f| f.error.is_some()) {
+                    tracing::debug!(target: "app", feed = %feed.feed, "cyber feed skipped: {}", feed.error.as_deref().unwrap_or_default());
+                }
+                tracing::debug!(target: "app", added = status.added, "cyber threat sync complete");
+                report(app, &sightings);
+            }
+            Err(err) => tracing::warn!(target: "app", "Cyber threat sync failed: {err}"),
+        }
+        SYNC_INTERVAL
+    }
+}
+
+async fn run(app: AppHandle, trigger: watch::Receiver<u64>) {
+    let client = match reqwest::Client::builder().use_native_tls().user_agent(crate::USER_AGENT).timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!(target: "app", "Cyber threat sync unavailable: {err}");
+            return;
+        }
+    };
+    if let Ok((indicators, vulnerabilities)) =
+        with_store(&app, |store| Ok((store.count_indicators()?, store.count_vulnerabilities()?))).await
+    {
+        update_status(&app, |status| {
+            status.stored_indicators = indicators;
+            status.stored_vulnerabilities = vulnerabilities;
+        });
+    }
+    let poller = Poller {
+        app: app.clone(),
+        client,
+    };
+    crate::poll::run_triggered(&app, trigger, poller).await;
+}
+
+/// Start the sync task unless `cyber.enabled` is off; it syncs right away.
+pub fn start(app: &AppHandle) {
+    if !app.state::<DesktopConfigState>().snapshot().cyber.enabled {
+        return;
+    }
+    let (sender, receiver) = watch::channel(0);
+    *app.state::<CyberState>().trigger.lock().unwrap_or_else(|e| e.into_inner()) = Some(sender);
+    tauri::async_runtime::spawn(run(app.clone(), receiver));
+}
+
+/// Sync now, e.g. right after a feed key was added. Returns at once.
+#[tauri::command]
+pub fn sync_cyber_threats(webview: Webview, state: tauri::State<'_, CyberState>) -> Result<CyberSyncStatus, String> {
+    crate::metrics::of(&webview).observe("sync_cyber_threats", || {
+        crate::require_trusted_window(webview.label())?;
+        let status = state.status.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        if !status.syncing {
+            match state.trigger.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+                Some(trigger) => trigger.send_modify(|n| *n += 1),
+                None => return Err("Cyber threat feeds are disabled".to_string()),
+            }
+        }
+        Ok(status)
+    })
+}
+
+#[tauri::command]
+pub fn get_cyber_sync_status(webview: Webview, state: tauri::State<'_, CyberState>) -> Result<CyberSyncStatus, String> {
+    crate::metrics::of(&webview).observe("get_cyber_sync_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.status.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+/// Stored indicators, shaped like `CyberThreat` plus `sources`, most
+/// recently seen first.
+#[tauri::command]
+pub async fn query_threat_indicators(webview: Webview, app: AppHandle, query: Option<IndicatorQuery>) -> Result<Vec<Value>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("query_threat_indicators", async move {
+            crate::require_trusted_window(webview.label())?;
+            let query = query.unwrap_or_default();
+            with_store(&app, move |store| store.indicators(&query)).await
+        })
+        .await
+}
+
+/// Stored known exploited vulnerabilities, most recently added first.
+#[tauri::command]
+pub async fn query_exploited_vulnerabilities(
+    webview: Webview,
+    app: AppHandle,
+    query: Option<VulnerabilityQuery>,
+) -> Result<Vec<Value>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("query_exploited_vulnerabilities", async move {
+            crate::require_trusted_window(webview.label())?;
+            let query = query.unwrap_or_default();
+            with_store(&app, move |store| store.vulnerabilities(&query)).await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod cyber_tests {
+    use super::*;
+
+    #[test]
+    fn shapes_feed_records_like_the_cyber_service() {
+        let feodo: Vec<FeodoRecord> = serde_json::from_str(
+            r#"[{"ip_address":"51.75.1.2","port":443,"status":"online","country":"fr","first_seen":"2026-05-01 10:00:00",
+                 "last_online":"2026-05-10","malware":"QakBot"},
+                {"ip_address":"not-an-ip","status":"online"},
+                {"ip_address":"10.0.0.1","status":"online","first_seen":"2020-01-01 00:00:00","last_online":"2020-01-02"}]"#,
+        )
+        .unwrap();
+        let cutoff = millis(Some("2026-04-01"));
+        let threats: Vec<Threat> = feodo.into_iter().filter_map(|r| from_feodo(r, cutoff)).collect();
+        assert_eq!(threats.len(), 1);
+        assert_eq!((threats[0].severity, threats[0].country.as_str()), (4, "FR"));
+        assert_eq!(threats[0].title(), "QakBot C2 server 51.75.1.2");
+        let row = threats[0].clone().into_row(0);
+        assert_eq!(row.data["id"], "ip:51.75.1.2");
+        assert_eq!(row.data["type"], "CYBER_THREAT_TYPE_C2_SERVER");
+        assert_eq!(row.data["severity"], "CRITICALITY_LEVEL_CRITICAL");
+        assert_eq!(row.data["firstSeenAt"], 1_777_629_600_000i64);
+        assert_eq!(row.last_seen, millis(Some("2026-05-10")));
+        let (lat, lon) = (row.lat.unwrap(), row.lon.unwrap());
+        assert!((lat - 46.23).abs() <= CENTROID_JITTER && (lon - 2.21).abs() <= CENTROID_JITTER);
+        assert_eq!(place("51.75.1.2", "FR"), Some((lat, lon)));
+
+        let urlhaus: UrlhausResponse = serde_json::from_str(
+            r#"{"query_status":"ok","urls":[
+                {"url":"http://evil.example.com/x.exe","url_status":"online","host":"evil.example.com",
+                 "date_added":"2026-05-10 08:00:00 UTC","threat":"malware_download","tags":["Ransomware","exe"],
+                 "urlhaus_reference":"https://urlhaus.abuse.ch/url/1/"},
+                {"url":"http://1.2.3.4/i","url_status":"offline","host":"1.2.3.4","date_added":"2026-05-10 08:00:00 UTC"}]}"#,
+        )
+        .unwrap();
+        let threats: Vec<Threat> = urlhaus.urls.into_iter().filter_map(|r| from_urlhaus(r, cutoff)).collect();
+        assert_eq!(threats.len(), 1);
+        assert_eq!((threats[0].indicator_type, threats[0].indicator.as_str()), ("domain", "evil.example.com"));
+        assert_eq!((threats[0].kind, threats[0].severity), ("malware_host", 4));
+        assert_eq!(threats[0].tags, ["ransomware", "exe"]);
+
+        let otx: OtxPage = serde_json::from_str(
+            r#"{"results":[{"indicator":"5.6.7.8","type":"IPv4","title":"Cobalt Strike beacons","tags":["c2"],
+                "created":"2026-05-09T12:00:00"},{"indicator":"example.org","type":"domain"}],"next":null}"#,
+        )
+        .unwrap();
+        let threats: Vec<Threat> = otx.results.into_iter().filter_map(from_otx).collect();
+        assert_eq!(threats.len(), 1);
+        assert_eq!((threats[0].kind, threats[0].severity), ("c2_server", 3));
+        assert_eq!(threats[0].last_seen, millis(Some("2026-05-09 12:00:00")));
+
+        let catalog: Catalog = serde_json::from_str(
+            r#"{"catalogVersion":"2026.05.10","vulnerabilities":[
+                {"cveID":"CVE-2026-1234","vendorProject":"Fortinet","product":"FortiOS","vulnerabilityName":"FortiOS Heap Overflow",
+                 "dateAdded":"2026-05-09","knownRansomwareCampaignUse":"Known","cwes":["CWE-122"]},
+                {"cveID":"CVE-2026-9","dateAdded":"not a date"}]}"#,
+        )
+        .unwrap();
+        let rows: Vec<VulnerabilityRow> = catalog.vulnerabilities.into_iter().filter_map(from_kev).collect();
+        assert_eq!(rows.len(), 1);
+        assert!(rows[0].ransomware);
+        assert_eq!(rows[0].data["link"], "https://nvd.nist.gov/vuln/detail/CVE-2026-1234");
+    }
+
+    #[test]
+    fn merges_an_indicator_reported_by_several_feeds() {
+        let feodo = Threat {
+            kind: "c2_server",
+            source: "feodo",
+            indicator: "51.75.1.2".to_string(),
+            indicator_type: "ip",
+            country: "FR".to_string(),
+            severity: 4,
+            malware_family: "QakBot".to_string(),
+            tags: vec!["botnet".to_string(), "c2".to_string()],
+            first_seen: 1_000,
+            last_seen: 5_000,
+            link: String::new(),
+        };
+        let otx = Threat {
+            kind: "malware_host",
+            source: "otx",
+            country: String::new(),
+            severity: 2,
+            malware_family: String::new(),
+            tags: vec!["c2".to_string(), "apt".to_string()],
+            first_seen: 500,
+            last_seen: 9_000,
+            ..feodo.clone()
+        };
+        let stored = feodo.into_row(0);
+        let merged = merge(&otx.into_row(0), &stored.data);
+        assert_eq!((merged.severity, merged.last_seen, merged.lat), (4, 9_000, stored.lat));
+        assert_eq!(merged.data["type"], "CYBER_THREAT_TYPE_C2_SERVER");
+        assert_eq!(merged.data["source"], "CYBER_THREAT_SOURCE_FEODO");
+        assert_eq!(merged.data["sources"], json!(["feodo", "otx"]));
+        assert_eq!(merged.data["tags"], json!(["botnet", "c2", "apt"]));
+        assert_eq!((merged.data["firstSeenAt"].clone(), merged.data["country"].clone()), (json!(500), json!("FR")));
+        assert_eq!(merged.data["malwareFamily"], "QakBot");
+    }
+}
@@ -0,0 +1,199 @@
+//! `worldmonitor://` deep links. Links arrive from the OS through the
+//! deep-link plugin (on Windows and Linux via the single-instance plugin,
+//! since each click launches the app again). Supported forms:
+//!
+//! - `worldmonitor://map?lat=48.85&lon=2.35&zoom=6` centers the dashboard map.
+//! - `worldmonitor://panel/<id>` focuses a panel, in its own window if it is
+//!   detached, otherwise on the dashboard.
+//! - `worldmonitor://settings[/<section>]` opens the settings window, on a
+//!   given tab such as `api-keys`.
+//!
+//! Map and panel links are emitted to the target window as `app://navigate`.
+//! Links that launched the app are queued until the dashboard asks for them
+//! with `take_pending_navigation`, since it is not listening yet.
+
+use std::sync::Mutex;
+
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+pub const SCHEME: &str = "worldmonitor";
+pub const NAVIGATE_EVENT: &str = "app://navigate";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum DeepLink {
+    Map { lat: f64, lon: f64, zoom: Option<f64> },
+    Panel { id: String },
+    Settings { section: Option<String> },
+}
+
+/// Startup links waiting for the main window's frontend.
+#[derive(Default)]
+pub struct PendingNavigation(Mutex<Vec<DeepLink>>);
+
+fn coordinate(url: &Url, name: &str, range: std::ops::RangeInclusive<f64>) -> Result<Option<f64>, String> {
+    let Some((_, value)) = url.query_pairs().find(|(key, _)| key == name) else {
+        return Ok(None);
+    };
+    match value.parse::<f64>() {
+        Ok(parsed) if range.contains(&parsed) => Ok(Some(parsed)),
+        _ => Err(format!("Invalid {name} in deep link: {value}")),
+    }
+}
+
+fn parse(url: &Url) -> Result<DeepLink, String> {
+    if url.scheme() != SCHEME {
+        return Err(format!("Unsupported deep link scheme: {}", url.scheme()));
+    }
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    match (url.host_str().unwrap_or(""), segments.as_slice()) {
+        ("map", []) => {
+            let lat = coordinate(url, "lat", -90.0..=90.0)?;
+            let lon = coordinate(url, "lon", -180.0..=180.0)?;
+            let zoom = coordinate(url, "zoom", 0.0..=22.0)?;
+            match (lat, lon) {
+                (Some(lat), Some(lon)) => Ok(DeepLink::Map { lat, lon, zoom }),
+                _ => Err("Map deep link needs lat and lon".to_string()),
+            }
+        }
+        ("panel", [id]) => {
+            crate::panel_windows::validate_panel_id(id)?;
+            Ok(DeepLink::Panel { id: id.to_string() })
+        }
+        ("settings", []) => Ok(DeepLink::Settings { section: None }),
+        ("settings", [section]) => {
+            crate::validate_settings_section(section)?;
+            Ok(DeepLink::Settings { section: Some(section.to_string()) })
+        }
+        _ => Err(format!("Unknown deep link: {url}")),
+    }
+}
+
+/// Focus or create the window a link targets and hand it the link.
+fn route(app: &AppHandle, link: DeepLink, queue: bool) -> Result<(), String> {
+    match &link {
+        DeepLink::Settings { section } => return crate::open_settings_window(app, section.as_deref()),
+        DeepLink::Panel { id } => {
+            let label = format!("panel-{id}");
+            if app.get_webview_window(&label).is_some() {
+                crate::panel_windows::open(app, id)?;
+                return app
+                    .emit_to(label.as_str(), NAVIGATE_EVENT, &link)
+                    .map_err(|e| format!("Failed to send deep link: {e}"));
+            }
+        }
+        DeepLink::Map { .. } => {}
+    }
+    crate::tray::show_dashboard(app);
+    if queue {
+        if let Some(pending) = app.try_state::<PendingNavigation>() {
+            pending.0.lock().unwrap_or_else(|e| e.into_inner()).push(link);
+        }
+        return Ok(());
+    }
+    app.emit_to("main", NAVIGATE_EVENT, &link)
+        .map_err(|e| format!("Failed to send deep link: {e}"))
+}
+
+fn handle(app: &AppHandle, urls: Vec<Url>, queue: bool) {
+    for url in urls {
+        tracing::info!(target: "app", url = %url, "deep link received");
+        if let Err(err) = parse(&url).and_then(|link| route(app, link, queue)) {
+            tracing::warn!(target: "app", "{err}");
+        }
+    }
+}
+
+/// Register the scheme where that happens at runtime, route links that
+/// launched the app, and subscribe to later ones.
+pub fn init(app: &AppHandle) {
+    app.manage(PendingNavigation::default());
+    let deep_link = app.deep_link();
+    // Installers register the scheme on Windows; an AppImage or a dev build
+    // has nothing else to do it.
+    #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+    if let Err(err) = deep_link.register_all() {
+        tracing::warn!(target: "app", "failed to register {SCHEME}:// handler: {err}");
+    }
+    // A restart relaunches with the same arguments; its links were handled.
+    match deep_link.get_current().map(|urls| urls.filter(|_| !crate::restart::is_resumed(app))) {
+        Ok(Some(urls)) => handle(app, urls, true),
+        Ok(None) => {}
+        Err(err) => tracing::warn!(target: "app", "failed to read launch deep link: {err}"),
+    }
+    let handle_app = app.clone();
+    deep_link.on_open_url(move |event| handle(&handle_app, event.urls(), false));
+}
+
+/// Links still waiting for the dashboard, to carry across a restart.
+pub fn pending(app: &AppHandle) -> Vec<DeepLink> {
+    app.try_state::<PendingNavigation>()
+        .map(|pending| pending.0.lock().unwrap_or_else(|e| e.into_inner()).clone())
+        .unwrap_or_default()
+}
+
+/// Queue links carried across a restart.
+pub fn restore_pending(app: &AppHandle, links: Vec<DeepLink>) {
+    if let Some(pending) = app.try_state::<PendingNavigation>() {
+        pending.0.lock().unwrap_or_else(|e| e.into_inner()).extend(links);
+    }
+}
+
+/// Drain the links that arrived before the dashboard was listening.
+#[tauri::command]
+pub fn take_pending_navigation(
+    webview: Webview,
+    pending: tauri::State<'_, PendingNavigation>,
+) -> Result<Vec<DeepLink>, String> {
+    crate::metrics::of(&webview).observe("take_pending_navigation", || {
+        crate::require_trusted_window(webview.label())?;
+        if webview.label() != "main" {
+            return Ok(Vec::new());
+        }
+        Ok(std::mem::take(&mut *pending.0.lock().unwrap_or_else(|e| e.into_inner())))
+    })
+}
+
+#[cfg(test)]
+mod deep_links_tests {
+    use super::{parse, DeepLink};
+    use reqwest::Url;
+
+    fn link(url: &str) -> Result<DeepLink, String> {
+        parse(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn parses_supported_links() {
+        assert_eq!(
+            link("worldmonitor://map?lat=48.85&lon=2.35&zoom=6"),
+            Ok(DeepLink::Map { lat: 48.85, lon: 2.35, zoom: Some(6.0) })
+        );
+        assert_eq!(
+            link("worldmonitor://panel/live-news/"),
+            Ok(DeepLink::Panel { id: "live-news".to_string() })
+        );
+        assert_eq!(link("worldmonitor://settings"), Ok(DeepLink::Settings { section: None }));
+        assert_eq!(
+            link("worldmonitor://settings/api-keys"),
+            Ok(DeepLink::Settings { section: Some("api-keys".to_string()) })
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_links() {
+        assert!(link("worldmonitor://map?lat=91&lon=0").is_err());
+        assert!(link("worldmonitor://map?lat=10").is_err());
+        assert!(link("worldmonitor://panel/..%2Fsettings").is_err());
+        assert!(link("worldmonitor://panel").is_err());
+        assert!(link("worldmonitor://unknown").is_err());
+        assert!(link("worldmonitor://settings/appearance").is_err());
+        assert!(link("https://map?lat=1&lon=1").is_err());
+    }
+}
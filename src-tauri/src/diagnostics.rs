@@ -0,0 +1,299 @@
+//! Support tooling: environment reporting for the settings "About" tab and
+//! the one-click diagnostics bundle handed to maintainers when a user reports
+//! a problem. Both share `SystemInfo` so the data always matches.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde::Serialize;
+use sysinfo::{CpuRefreshKind, MemoryRefreshKind, RefreshKind, System};
+use tauri::{AppHandle, Manager, Webview};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::config::DesktopConfig;
+use crate::log_files::LogFileKind;
+use crate::{CacheRecoveryReport, LocalApiState, PersistentCache, SecretsCache};
+
+/// Bundle exports currently running, so `clear_logs` can refuse to truncate
+/// files that are being zipped.
+#[derive(Default)]
+pub struct DiagnosticsExportState {
+    in_flight: AtomicUsize,
+}
+
+impl DiagnosticsExportState {
+    pub fn is_busy(&self) -> bool {
+        self.in_flight.load(Ordering::SeqCst) > 0
+    }
+}
+
+/// Marks an export in flight until dropped (including on panic).
+struct ExportGuard<'a>(&'a AtomicUsize);
+
+impl<'a> ExportGuard<'a> {
+    fn enter(state: &'a DiagnosticsExportState) -> Self {
+        state.in_flight.fetch_add(1, Ordering::SeqCst);
+        ExportGuard(&state.in_flight)
+    }
+}
+
+impl Drop for ExportGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct SystemInfo {
+    os_name: Option<String>,
+    os_version: Option<String>,
+    kernel_version: Option<String>,
+    arch: &'static str,
+    cpu_model: Option<String>,
+    cpu_count: usize,
+    total_memory_bytes: u64,
+    available_memory_bytes: u64,
+    app_version: &'static str,
+    git_commit: &'static str,
+    build_date: &'static str,
+    tauri_version: &'static str,
+    webview_version: Option<String>,
+    node_binary: Option<String>,
+    node_version: Option<String>,
+}
+
+#[derive(Serialize)]
+struct EnvironmentInfo {
+    generated_at: u64,
+    system: SystemInfo,
+    local_api_port: Option<u16>,
+    sidecar_script: String,
+    resource_root: String,
+}
+
+#[derive(Serialize)]
+struct CacheStats {
+    entries: usize,
+    file_bytes: Option<u64>,
+    namespaces: BTreeMap<String, usize>,
+    recovery: Option<CacheRecoveryReport>,
+}
+
+/// Config with every secret value replaced by its presence only.
+#[derive(Serialize)]
+struct RedactedConfig {
+    desktop: DesktopConfig,
+    secrets_configured: Vec<String>,
+}
+
+pub(crate) fn node_version(node: &Path) -> Option<String> {
+    let mut cmd = Command::new(node);
+    cmd.arg("--version");
+    #[cfg(windows)]
+    cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    let output = cmd.output().ok().filter(|o| o.status.success())?;
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!version.is_empty()).then_some(version)
+}
+
+/// Gather environment details. Spawns `node --version`, so call it off the
+/// main thread.
+pub fn system_info(app: &AppHandle) -> SystemInfo {
+    let sys = System::new_with_specifics(
+        RefreshKind::nothing()
+            .with_memory(MemoryRefreshKind::nothing().with_ram())
+            .with_cpu(CpuRefreshKind::nothing()),
+    );
+    let node_binary = crate::resolve_node_binary(app);
+    SystemInfo {
+        os_name: System::name(),
+        os_version: System::long_os_version(),
+        kernel_version: System::kernel_version(),
+        arch: std::env::consts::ARCH,
+        cpu_model: sys
+            .cpus()
+            .first()
+            .map(|cpu| cpu.brand().trim().to_string())
+            .filter(|brand| !brand.is_empty()),
+        cpu_count: sys.cpus().len(),
+        total_memory_bytes: sys.total_memory(),
+        available_memory_bytes: sys.available_memory(),
+        app_version: env!("CARGO_PKG_VERSION"),
+        git_commit: crate::about::GIT_COMMIT,
+        build_date: crate::about::BUILD_DATE,
+        tauri_version: tauri::VERSION,
+        webview_version: tauri::webview_version().ok(),
+        node_version: node_binary.as_deref().and_then(node_version),
+        node_binary: node_binary.map(|p| p.display().to_string()),
+    }
+}
+
+fn environment_info(app: &AppHandle) -> EnvironmentInfo {
+    let (sidecar_script, resource_root) = crate::local_api_paths(app);
+    let local_api_port = app
+        .try_state::<LocalApiState>()
+        .and_then(|state| state.port.lock().ok().and_then(|g| *g));
+    EnvironmentInfo {
+        generated_at: crate::unix_timestamp_secs(),
+        system: system_info(app),
+        local_api_port,
+        sidecar_script: sidecar_script.display().to_string(),
+        resource_root: resource_root.display().to_string(),
+    }
+}
+
+fn cache_stats(app: &AppHandle) -> CacheStats {
+    let file_bytes = crate::cache_file_path(app)
+        .ok()
+        .and_then(|p| fs::metadata(p).ok())
+        .map(|m| m.len());
+    let Some(cache) = app.try_state::<PersistentCache>() else {
+        return CacheStats {
+            entries: 0,
+            file_bytes,
+            namespaces: BTreeMap::new(),
+            recovery: None,
+        };
+    };
+    let data = cache.data.lock().unwrap_or_else(|e| e.into_inner());
+    let mut namespaces = BTreeMap::new();
+    for key in data.keys() {
+        let namespace = crate::cache_namespace(key).unwrap_or_else(|| "(none)".to_string());
+        *namespaces.entry(namespace).or_insert(0) += 1;
+    }
+    CacheStats {
+        entries: data.len(),
+        file_bytes,
+        namespaces,
+        recovery: cache.recovery.clone(),
+    }
+}
+
+fn redacted_config(app: &AppHandle) -> RedactedConfig {
+    let mut desktop: DesktopConfig = crate::desktop_config_path(app)
+        .and_then(|p| crate::config::load(&p))
+        .unwrap_or_default();
+    // Report endpoints can carry ingest keys in the URL.
+    if desktop.error_reporting.endpoint.is_some() {
+        desktop.error_reporting.endpoint = Some("<redacted>".to_string());
+    }
+    let mut secrets_configured: Vec<String> = app
+        .try_state::<SecretsCache>()
+        .map(|cache| {
+            cache
+                .secrets
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .keys()
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+    secrets_configured.sort();
+    RedactedConfig {
+        desktop,
+        secrets_configured,
+    }
+}
+
+fn add_json<W: Write + io::Seek, T: Serialize>(zip: &mut ZipWriter<W>, name: &str, value: &T) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(value).map_err(|e| format!("Failed to serialize {name}: {e}"))?;
+    zip.start_file(name, SimpleFileOptions::default())
+        .map_err(|e| format!("Failed to add {name}: {e}"))?;
+    zip.write_all(&json).map_err(|e| format!("Failed to add {name}: {e}"))
+}
+
+fn add_file<W: Write + io::Seek>(zip: &mut ZipWriter<W>, name: &str, path: &Path) -> Result<(), String> {
+    let Ok(mut source) = File::open(path) else {
+        // Missing logs (e.g. sidecar never started) are expected; skip them.
+        return Ok(());
+    };
+    zip.start_file(name, SimpleFileOptions::default())
+        .map_err(|e| format!("Failed to add {name}: {e}"))?;
+    io::copy(&mut source, zip).map_err(|e| format!("Failed to add {name}: {e}"))?;
+    Ok(())
+}
+
+fn write_bundle(app: &AppHandle, target: &Path) -> Result<(), String> {
+    let file = File::create(target)
+        .map_err(|e| format!("Failed to create diagnostics bundle {}: {e}", target.display()))?;
+    let mut zip = ZipWriter::new(file);
+
+    add_json(&mut zip, "environment.json", &environment_info(app))?;
+    add_json(&mut zip, "config.redacted.json", &redacted_config(app))?;
+    add_json(&mut zip, "cache-stats.json", &cache_stats(app))?;
+    for kind in [LogFileKind::Desktop, LogFileKind::LocalApi] {
+        let path = kind.path(app)?;
+        let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        add_file(&mut zip, &format!("logs/{name}"), &path)?;
+    }
+    // Include the previous desktop.log generation so issues right before a rotation are kept.
+    let desktop_log = LogFileKind::Desktop.path(app)?;
+    add_file(&mut zip, "logs/desktop.log.1", &crate::logging::generation_path(&desktop_log, 1))?;
+    add_file(
+        &mut zip,
+        &format!("logs/{}", crate::error_reporting::ERROR_REPORTS_FILE),
+        &crate::logs_dir_path(app)?.join(crate::error_reporting::ERROR_REPORTS_FILE),
+    )?;
+    if let Ok(entries) = fs::read_dir(crate::logs_dir_path(app)?) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with("crash-") && name.ends_with(".json") {
+                add_file(&mut zip, &format!("crashes/{name}"), &entry.path())?;
+            } else if name.starts_with("freeze-") && name.ends_with(".json") {
+                add_file(&mut zip, &format!("freezes/{name}"), &entry.path())?;
+            }
+        }
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finalize diagnostics bundle: {e}"))?;
+    Ok(())
+}
+
+fn bundle_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    match app.path().download_dir() {
+        Ok(dir) if dir.is_dir() => Ok(dir),
+        _ => crate::logs_dir_path(app),
+    }
+}
+
+/// Zip logs, redacted config, cache stats, and environment/sidecar details
+/// into Downloads (falling back to the logs dir) and return the bundle path.
+#[tauri::command]
+pub async fn export_diagnostics_bundle(webview: Webview, app: AppHandle) -> Result<String, String> {
+    crate::metrics::of(&webview)
+        .observe_async("export_diagnostics_bundle", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                let exports = app.state::<DiagnosticsExportState>();
+                let _guard = ExportGuard::enter(&exports);
+                let target = bundle_dir(&app)?.join(format!(
+                    "world-monitor-diagnostics-{}.zip",
+                    crate::unix_timestamp_secs()
+                ));
+                write_bundle(&app, &target)?;
+                tracing::info!(target: "app", path = %target.display(), "diagnostics bundle exported");
+                Ok(target.display().to_string())
+            })
+            .await
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn get_system_info(webview: Webview, app: AppHandle) -> Result<SystemInfo, String> {
+    crate::metrics::of(&webview)
+        .observe_async("get_system_info", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || Ok(system_info(&app))).await
+        })
+        .await
+}
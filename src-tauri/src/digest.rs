@@ -0,0 +1,422 @@
+//! Scheduled notification digests: at each of `digest.times` (local time)
+//! the last `digest.window_hours` of fired alerts, earthquakes from `usgs`
+//! and headlines from `news` are compiled into one summary notification,
+//! also emitted as `digest://delivered`. Headlines are ranked by how many
+//! other feeds ran the same story. With `digest.summarize` the text is
+//! written by the configured LLM (Ollama, then Groq, then OpenRouter; see
+//! `llm`), falling back to the compiled list if none answers.
+//!
+//! While digests are on and `digest.hold_notifications` is set, alert
+//! rules and `usgs` hold back their non-critical notifications (`holds`),
+//! so the digest replaces the stream instead of adding to it.
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveTime, TimeZone};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+use crate::alerts::FiredAlert;
+use crate::config::{DesktopConfigState, DigestConfig};
+use crate::notifications::NotificationUrgency;
+use crate::store::{EventStore, NewsQuery, QuakeQuery};
+
+pub const DELIVERED_EVENT: &str = "digest://delivered";
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// Most headlines and quakes read from the store per digest.
+const MAX_CANDIDATES: usize = 2_000;
+/// Headlines sharing this many significant words tell the same story.
+const SAME_STORY_WORDS: usize = 3;
+const MAX_SUMMARY_CHARS: usize = 600;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestItem {
+    /// Milliseconds since the epoch.
+    pub time: i64,
+    pub title: String,
+    pub detail: String,
+}
+
+/// Payload of `digest://delivered`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Digest {
+    /// The window covered, in milliseconds since the epoch.
+    pub since: i64,
+    pub until: i64,
+    pub alert_count: usize,
+    pub quake_count: usize,
+    pub headline_count: usize,
+    /// The top `digest.max_items` of each.
+    pub alerts: Vec<DigestItem>,
+    pub quakes: Vec<DigestItem>,
+    pub headlines: Vec<DigestItem>,
+    pub title: String,
+    pub body: String,
+    /// Whether `body` was written by an LLM.
+    pub summarized: bool,
+}
+
+#[derive(Default)]
+pub struct DigestState {
+    last: Mutex<Option<Digest>>,
+}
+
+/// Whether a notification of `urgency` should wait for the next digest.
+pub fn holds(app: &AppHandle, urgency: NotificationUrgency) -> bool {
+    let config = app.state::<DesktopConfigState>().snapshot().digest;
+    config.enabled && config.hold_notifications && urgency != NotificationUrgency::Critical
+}
+
+fn schedule(times: &[String]) -> Vec<NaiveTime> {
+    times.iter().filter_map(|t| NaiveTime::parse_from_str(t.trim(), "%H:%M").ok()).collect()
+}
+
+/// Whether one of `times` falls after `after` and no later than `now`.
+/// After a long sleep only the last day is considered.
+fn is_due<Tz: TimeZone>(times: &[NaiveTime], after: &DateTime<Tz>, now: &DateTime<Tz>) -> bool {
+    let tz = now.timezone();
+    let last = now.date_naive();
+    let mut date = after.date_naive().max(last.pred_opt().unwrap_or(last));
+    while date <= last {
+        for time in times {
+            let Some(at) = tz.from_local_datetime(&date.and_time(*time)).earliest() else {
+                continue;
+            };
+            if &at > after && &at <= now {
+                return true;
+            }
+        }
+        let Some(next) = date.succ_opt() else { break };
+        date = next;
+    }
+    false
+}
+
+fn significant_words(title: &str) -> HashSet<String> {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 5)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Up to `max` headlines, most widely covered first (then newest), with at
+/// most one per story.
+fn top_headlines(headlines: &[Value], max: usize) -> Vec<DigestItem> {
+    let words: Vec<HashSet<String>> =
+        headlines.iter().map(|h| significant_words(h["title"].as_str().unwrap_or_default())).collect();
+    let same_story = |a: usize, b: usize| words[a].intersection(&words[b]).count() >= SAME_STORY_WORDS;
+    let coverage: Vec<usize> = (0..headlines.len())
+        .map(|i| {
+            let sources: HashSet<&str> = (0..headlines.len())
+                .filter(|&j| j != i && same_story(i, j))
+                .filter_map(|j| headlines[j]["source"].as_str())
+                .filter(|&source| Some(source) != headlines[i]["source"].as_str())
+                .collect();
+            sources.len()
+        })
+        .collect();
+    let mut order: Vec<usize> = (0..headlines.len()).collect();
+    // The store hands headlines over newest first; the sort is stable.
+    order.sort_by_key(|&i| std::cmp::Reverse(coverage[i]));
+    let mut picked: Vec<usize> = Vec::new();
+    for i in order {
+        if picked.len() == max {
+            break;
+        }
+        if !picked.iter().any(|&p| same_story(p, i)) {
+            picked.push(i);
+        }
+    }
+    picked
+        .into_iter()
+        .map(|i| {
+            let h = &headlines[i];
+            let source = h["source"].as_str().unwrap_or_default();
+            DigestItem {
+                time: DateTime::parse_from_rfc3339(h["pubDate"].as_str().unwrap_or_default())
+                    .map(|d| d.timestamp_millis())
+                    .unwrap_or_default(),
+                title: h["title"].as_str().unwrap_or_default().to_string(),
+                detail: match coverage[i] {
+                    0 => source.to_string(),
+                    n => format!("{source} and {n} more"),
+                },
+            }
+        })
+        .collect()
+}
+
+fn quake_items(quakes: &[Value], max: usize) -> Vec<DigestItem> {
+    let mut quakes: Vec<&Value> = quakes.iter().collect();
+    quakes.sort_by(|a, b| b["magnitude"].as_f64().unwrap_or(0.0).total_cmp(&a["magnitude"].as_f64().unwrap_or(0.0)));
+    quakes
+        .into_iter()
+        .take(max)
+        .map(|q| DigestItem {
+            time: q["occurredAt"].as_i64().unwrap_or_default(),
+            title: format!("M{:.1} {}", q["magnitude"].as_f64().unwrap_or(0.0), q["place"].as_str().unwrap_or_default()),
+            detail: if q["tsunami"].as_bool().unwrap_or(false) { "tsunami possible".to_string() } else { String::new() },
+        })
+        .collect()
+}
+
+/// `3 quakes ≥ M5.0: M6.4 Chile; ...` for `noun` `quake` and `qualifier`
+/// ` ≥ M5.0`.
+fn line(count: usize, noun: &str, qualifier: &str, items: &[DigestItem]) -> Option<String> {
+    if count == 0 {
+        return None;
+    }
+    let plural = if count == 1 { "" } else { "s" };
+    let titles: Vec<&str> = items.iter().map(|i| i.title.as_str()).collect();
+    Some(format!("{count} {noun}{plural}{qualifier}: {}", titles.join("; ")))
+}
+
+/// The digest of `alerts`, `quakes` and `headlines` over `since..until`.
+fn compile(
+    config: &DigestConfig,
+    since: i64,
+    until: i64,
+    alerts: Vec<FiredAlert>,
+    quakes: Vec<Value>,
+    headlines: Vec<Value>,
+) -> Digest {
+    let alert_items: Vec<DigestItem> = alerts
+        .iter()
+        .rev()
+        .take(config.max_items)
+        .map(|a| DigestItem {
+            time: (a.fired_at * 1000) as i64,
+            title: a.title.clone(),
+            detail: a.body.clone(),
+        })
+        .collect();
+    let mut digest = Digest {
+        since,
+        until,
+        alert_count: alerts.len(),
+        quake_count: quakes.len(),
+        headline_count: headlines.len(),
+        alerts: alert_items,
+        quakes: quake_items(&quakes, config.max_items),
+        headlines: top_headlines(&headlines, config.max_items),
+        title: "World Monitor digest".to_string(),
+        body: String::new(),
+        summarized: false,
+    };
+    let lines: Vec<String> = [
+        line(digest.alert_count, "alert", "", &digest.alerts),
+        line(digest.quake_count, "quake", &format!(" ≥ M{:.1}", config.min_magnitude), &digest.quakes),
+        line(digest.headline_count, "headline", "", &digest.headlines),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    digest.body = if lines.is_empty() {
+        "Nothing new.".to_string()
+    } else {
+        lines.join("\n")
+    };
+    digest
+}
+
+async fn build(app: &AppHandle, config: &DigestConfig, window_hours: u32) -> Result<Digest, String> {
+    let until = Local::now().timestamp_millis();
+    let since = until - i64::from(window_hours.max(1)) * 60 * 60 * 1000;
+    let alerts = crate::alerts::fired_since(app, (since / 1000) as u64);
+    let min_magnitude = config.min_magnitude;
+    let handle = app.clone();
+    let (quakes, headlines) = crate::runtime::blocking(move || {
+        let store = handle.state::<EventStore>();
+        let quakes = store.quakes(&QuakeQuery {
+            since: Some(since),
+            min_magnitude: Some(min_magnitude),
+            limit: Some(MAX_CANDIDATES),
+            ..QuakeQuery::default()
+        })?;
+        let headlines = store.news(&NewsQuery {
+            since: Some(since),
+            limit: Some(MAX_CANDIDATES),
+            ..NewsQuery::default()
+        })?;
+        Ok::<_, String>((quakes, headlines))
+    })
+    .await?;
+    Ok(compile(config, since, until, alerts, quakes, headlines))
+}
+
+fn prompt(digest: &Digest) -> String {
+    let mut text = String::new();
+    for (name, count, items) in [
+        ("Alerts", digest.alert_count, &digest.alerts),
+        ("Earthquakes", digest.quake_count, &digest.quakes),
+        ("Top headlines", digest.headline_count, &digest.headlines),
+    ] {
+        text.push_str(&format!("{name} ({count} in total):\n"));
+        for item in items {
+            match item.detail.as_str() {
+                "" => text.push_str(&format!("- {}\n", item.title)),
+                detail => text.push_str(&format!("- {} ({detail})\n", item.title)),
+            }
+        }
+    }
+    text
+}
+
+async fn summarize(app: &AppHandle, digest: &Digest) -> Result<String, String> {
+    let system = "You write the text of a desktop notification summarizing world events for an analyst. \
+                  Use at most three short plain sentences, lead with what matters most, and add nothing \
+                  that is not in the list.";
+    let text = crate::llm::complete(app, system, &prompt(digest), 200).await?;
+    Ok(text.chars().take(MAX_SUMMARY_CHARS).collect())
+}
+
+/// Compile, optionally summarize, and deliver a digest.
+async fn deliver(app: &AppHandle, config: &DigestConfig) -> Result<Digest, String> {
+    let mut digest = build(app, config, config.window_hours).await?;
+    if config.summarize {
+        match summarize(app, &digest).await {
+            Ok(text) => {
+                digest.body = text;
+                digest.summarized = true;
+            }
+            Err(err) => tracing::warn!(target: "app", "digest summary unavailable: {err}"),
+        }
+    }
+    crate::notifications::send(app, "main", &digest.title, &digest.body, NotificationUrgency::Normal, Some("digest"))?;
+    tracing::info!(target: "app", alerts = digest.alert_count, quakes = digest.quake_count, "digest delivered");
+    let _ = app.emit(DELIVERED_EVENT, &digest);
+    crate::speech::announce_digest(app, &digest);
+    *app.state::<DigestState>().last.lock().unwrap_or_else(|e| e.into_inner()) = Some(digest.clone());
+    Ok(digest)
+}
+
+/// The last digest delivered, or else what one would list now.
+pub async fn latest(app: &AppHandle) -> Result<Digest, String> {
+    let last = app.state::<DigestState>().last.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(digest) = last {
+        return Ok(digest);
+    }
+    let config = app.state::<DesktopConfigState>().snapshot().digest;
+    build(app, &config, config.window_hours).await
+}
+
+/// Deliver a digest at each scheduled time from now on.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut checked = Local::now();
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let now = Local::now();
+            let config = app.state::<DesktopConfigState>().snapshot().digest;
+            if config.enabled && is_due(&schedule(&config.times), &checked, &now) {
+                if let Err(err) = deliver(&app, &config).await {
+                    tracing::warn!(target: "app", "digest failed: {err}");
+                }
+            }
+            checked = now;
+        }
+    });
+}
+
+/// What a digest over the last `window_hours` (default `digest.window_hours`)
+/// would list, without notifying.
+#[tauri::command]
+pub async fn preview_digest(webview: Webview, app: AppHandle, window_hours: Option<u32>) -> Result<Digest, String> {
+    crate::metrics::of(&webview)
+        .observe_async("preview_digest", async move {
+            crate::require_trusted_window(webview.label())?;
+            let config = app.state::<DesktopConfigState>().snapshot().digest;
+            build(&app, &config, window_hours.unwrap_or(config.window_hours)).await
+        })
+        .await
+}
+
+/// Deliver a digest now, whether or not digests are scheduled.
+#[tauri::command]
+pub async fn send_digest(webview: Webview, app: AppHandle) -> Result<Digest, String> {
+    crate::metrics::of(&webview)
+        .observe_async("send_digest", async move {
+            crate::require_trusted_window(webview.label())?;
+            let config = app.state::<DesktopConfigState>().snapshot().digest;
+            deliver(&app, &config).await
+        })
+        .await
+}
+
+#[tauri::command]
+pub fn get_last_digest(webview: Webview, state: tauri::State<'_, DigestState>) -> Result<Option<Digest>, String> {
+    crate::metrics::of(&webview).observe("get_last_digest", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.last.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+#[cfg(test)]
+mod digest_tests {
+    use super::*;
+    use chrono::{FixedOffset, NaiveDate};
+    use serde_json::json;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<FixedOffset> {
+        let offset = FixedOffset::east_opt(2 * 3600).unwrap();
+        let time = NaiveDate::from_ymd_opt(2026, 3, day).unwrap().and_hms_opt(hour, minute, 0).unwrap();
+        offset.from_local_datetime(&time).unwrap()
+    }
+
+    #[test]
+    fn fires_once_per_scheduled_time() {
+        let times = schedule(&["08:00".to_string(), " 18:30 ".to_string(), "noon".to_string()]);
+        assert_eq!(times.len(), 2);
+        assert!(is_due(&times, &at(13, 7, 59), &at(13, 8, 0)));
+        assert!(!is_due(&times, &at(13, 8, 0), &at(13, 8, 1)));
+        assert!(!is_due(&times, &at(13, 8, 1), &at(13, 18, 29)));
+        // Asleep across midnight and a morning.
+        assert!(is_due(&times, &at(13, 19, 0), &at(14, 9, 0)));
+        assert!(!is_due(&times, &at(13, 19, 0), &at(14, 7, 0)));
+    }
+
+    #[test]
+    fn ranks_headlines_by_coverage() {
+        let headline = |title: &str, source: &str| json!({ "title": title, "source": source, "pubDate": "2026-03-13T08:00:00Z" });
+        let headlines = [
+            headline("Markets steady ahead of central bank meeting", "FT"),
+            headline("Earthquake strikes off coast of northern Chile", "BBC"),
+            headline("Powerful earthquake strikes northern Chile coast", "Reuters"),
+            headline("Chile earthquake: strikes felt along northern coast", "AP"),
+            headline("Election results delayed in several provinces", "AP"),
+        ];
+        let top = top_headlines(&headlines, 2);
+        assert_eq!(top[0].title, "Earthquake strikes off coast of northern Chile");
+        assert_eq!(top[0].detail, "BBC and 2 more");
+        assert_eq!(top[1].title, "Markets steady ahead of central bank meeting");
+    }
+
+    #[test]
+    fn compiles_a_body_per_section() {
+        let alert = FiredAlert {
+            rule_id: "r".to_string(),
+            rule_name: "Hormuz".to_string(),
+            title: "Tanker entered Hormuz".to_string(),
+            body: "MMSI 1".to_string(),
+            subject: "1".to_string(),
+            fired_at: 1_773_360_000,
+        };
+        let quakes = vec![
+            json!({ "place": "Fiji", "magnitude": 5.2, "occurredAt": 1 }),
+            json!({ "place": "Chile", "magnitude": 6.4, "occurredAt": 2, "tsunami": true }),
+        ];
+        let config = DigestConfig { max_items: 1, ..DigestConfig::default() };
+        let digest = compile(&config, 0, 1, vec![alert], quakes, Vec::new());
+        assert_eq!(digest.quakes[0].title, "M6.4 Chile");
+        assert_eq!(digest.body, "1 alert: Tanker entered Hormuz\n2 quakes ≥ M5.0: M6.4 Chile");
+        assert_eq!(compile(&config, 0, 1, Vec::new(), Vec::new(), Vec::new()).body, "Nothing new.");
+    }
+}
@@ -0,0 +1,157 @@
+//! Dock (and launcher) integration. The app icon's alert badge is drawn by
+//! `badging`; `set_badge_count` remains for callers that only know a count.
+//!
+//! On macOS the dock icon's menu also offers Open Settings, Pause/Resume
+//! Monitoring, and Quit. AppKit asks the application delegate for that menu
+//! (`applicationDockMenu:`), so `init` adds the method to the delegate class
+//! tao installs.
+
+use tauri::{AppHandle, Webview};
+
+/// Show `count` on the app icon, keeping the current severity; zero or
+/// `None` clears the badge.
+#[tauri::command]
+pub fn set_badge_count(webview: Webview, app: AppHandle, count: Option<u32>) -> Result<(), String> {
+    crate::metrics::of(&webview).observe("set_badge_count", || {
+        crate::require_trusted_window(webview.label())?;
+        crate::badging::apply(&app, count.unwrap_or(0), crate::badging::current_severity(&app))
+    })
+}
+
+#[cfg(target_os = "macos")]
+const DOCK_SETTINGS_TAG: isize = 1;
+#[cfg(target_os = "macos")]
+const DOCK_PAUSE_TAG: isize = 2;
+#[cfg(target_os = "macos")]
+const DOCK_QUIT_TAG: isize = 3;
+
+/// Dock menu entries as `(tag, title)`, rebuilt each time the menu opens so
+/// the pause entry reflects the current state.
+#[cfg(target_os = "macos")]
+fn dock_menu_items(app: &AppHandle) -> [(isize, &'static str); 3] {
+    let pause = if crate::monitoring::is_paused(app) {
+        "Resume Monitoring"
+    } else {
+        "Pause Monitoring"
+    };
+    [
+        (DOCK_SETTINGS_TAG, "Open Settings"),
+        (DOCK_PAUSE_TAG, pause),
+        (DOCK_QUIT_TAG, "Quit"),
+    ]
+}
+
+#[cfg(target_os = "macos")]
+fn on_dock_menu_selected(app: &AppHandle, tag: isize) {
+    match tag {
+        DOCK_SETTINGS_TAG => {
+            if let Err(err) = crate::open_settings_window(app, None) {
+                tracing::error!(target: "menu", "dock settings failed: {err}");
+            }
+        }
+        DOCK_PAUSE_TAG => crate::monitoring::set_paused(app, !crate::monitoring::is_paused(app)),
+        DOCK_QUIT_TAG => app.exit(0),
+        _ => {}
+    }
+}
+
+/// Install the dock menu. Call from `setup`, on the main thread.
+#[cfg(target_os = "macos")]
+pub fn init(app: &AppHandle) {
+    if let Err(err) = macos::install(app) {
+        tracing::warn!(target: "app", "dock menu unavailable: {err}");
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn init(_app: &AppHandle) {}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::sync::OnceLock;
+
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyClass, AnyObject, Imp, Sel};
+    use objc2::{define_class, msg_send, sel, MainThreadMarker, MainThreadOnly};
+    use objc2_app_kit::{NSApplication, NSMenu, NSMenuItem};
+    use objc2_foundation::{NSObject, NSString};
+    use tauri::AppHandle;
+
+    static APP: OnceLock<AppHandle> = OnceLock::new();
+
+    // Target for dock menu items; forwards the item's tag to Rust.
+    define_class!(
+        #[unsafe(super(NSObject))]
+        #[thread_kind = MainThreadOnly]
+        #[name = "WorldMonitorDockMenuTarget"]
+        struct DockMenuTarget;
+
+        impl DockMenuTarget {
+            #[unsafe(method(dockMenuItemSelected:))]
+            fn item_selected(&self, item: &NSMenuItem) {
+                if let Some(app) = APP.get() {
+                    super::on_dock_menu_selected(app, item.tag());
+                }
+            }
+        }
+    );
+
+    impl DockMenuTarget {
+        fn new(mtm: MainThreadMarker) -> Retained<Self> {
+            unsafe { msg_send![Self::alloc(mtm), init] }
+        }
+    }
+
+    thread_local! {
+        // Menu items hold their target weakly, so keep it alive here.
+        static TARGET: Option<Retained<DockMenuTarget>> = MainThreadMarker::new().map(DockMenuTarget::new);
+    }
+
+    /// `-[NSApplicationDelegate applicationDockMenu:]`
+    unsafe extern "C-unwind" fn application_dock_menu(_this: &AnyObject, _cmd: Sel, _sender: &AnyObject) -> *mut NSMenu {
+        let (Some(mtm), Some(app)) = (MainThreadMarker::new(), APP.get()) else {
+            return std::ptr::null_mut();
+        };
+        let menu = NSMenu::new(mtm);
+        TARGET.with(|target| {
+            let target: Option<&AnyObject> = target.as_deref().map(|target| &***target);
+            for (tag, title) in super::dock_menu_items(app) {
+                let item = unsafe {
+                    NSMenuItem::initWithTitle_action_keyEquivalent(
+                        NSMenuItem::alloc(mtm),
+                        &NSString::from_str(title),
+                        Some(sel!(dockMenuItemSelected:)),
+                        &NSString::new(),
+                    )
+                };
+                unsafe { item.setTarget(target) };
+                item.setTag(tag);
+                menu.addItem(&item);
+            }
+        });
+        Retained::autorelease_return(menu)
+    }
+
+    pub fn install(app: &AppHandle) -> Result<(), String> {
+        let mtm = MainThreadMarker::new().ok_or("dock menu must be installed on the main thread")?;
+        if APP.set(app.clone()).is_err() {
+            return Ok(());
+        }
+        let delegate = NSApplication::sharedApplication(mtm)
+            .delegate()
+            .ok_or("application has no delegate")?;
+        let delegate: &AnyObject = delegate.as_ref();
+        let class = delegate.class() as *const AnyClass as *mut AnyClass;
+        let imp: Imp = unsafe {
+            std::mem::transmute::<unsafe extern "C-unwind" fn(&AnyObject, Sel, &AnyObject) -> *mut NSMenu, Imp>(
+                application_dock_menu,
+            )
+        };
+        let added = unsafe { objc2::ffi::class_addMethod(class, sel!(applicationDockMenu:), imp, c"@@:@".as_ptr()) };
+        if added.as_bool() {
+            Ok(())
+        } else {
+            Err("delegate already provides a dock menu".to_string())
+        }
+    }
+}
@@ -0,0 +1,411 @@
+//! EIA energy data (API v2) fetched into the local `store` with the stored
+//! `EIA_API_KEY`. The datasets in `eia.datasets` (by default U.S. crude
+//! stocks, natural gas storage, Henry Hub and WTI spot prices, and Lower 48
+//! electricity demand) are updated at startup and then every
+//! `SYNC_INTERVAL`, or right away with `sync_eia_data`.
+//!
+//! Updates are incremental: the first fetch of a dataset takes the latest
+//! `eia.history` periods, later ones start from the newest stored period,
+//! which EIA sometimes revises. Datasets with new or revised periods go out
+//! together as `eia://updated`, shaped like `get_eia_data` returns them.
+//! After every sync each dataset's latest value and its change from the
+//! period before are reported to the alert engine as `eia.<id>` and
+//! `eia.<id>.change`, so threshold rules can watch them.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, Webview};
+use tokio::sync::watch;
+
+use crate::config::{DesktopConfigState, EiaDataset};
+use crate::store::with_store;
+
+pub const UPDATED_EVENT: &str = "eia://updated";
+
+const API_URL: &str = "https://api.eia.gov/v2";
+const KEY_SECRET: &str = "EIA_API_KEY";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const SYNC_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// The most rows EIA returns per request.
+const MAX_ROWS: usize = 5_000;
+const DEFAULT_LIMIT: usize = 104;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct EiaSyncStatus {
+    pub syncing: bool,
+    /// Unix seconds of the last completed sync.
+    pub last_sync: Option<u64>,
+    /// Datasets that changed in the last completed sync.
+    pub changed: Vec<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct EiaState {
+    /// Bumped to request a sync now.
+    trigger: Mutex<Option<watch::Sender<u64>>>,
+    status: Mutex<EiaSyncStatus>,
+}
+
+fn update_status(app: &AppHandle, f: impl FnOnce(&mut EiaSyncStatus)) -> EiaSyncStatus {
+    let state = app.state::<EiaState>();
+    let mut status = state.status.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut status);
+    status.clone()
+}
+
+/// A stored dataset, ready to plot.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EiaSeriesData {
+    pub id: String,
+    pub name: String,
+    pub unit: String,
+    pub frequency: String,
+    /// EIA's period strings (`2026`, `2026-03`, `2026-03-13`, `2026-03-13T05`).
+    pub periods: Vec<String>,
+    /// The start of each period in milliseconds since the epoch (UTC).
+    pub timestamps: Vec<i64>,
+    pub values: Vec<f64>,
+    pub latest: Option<f64>,
+    pub previous: Option<f64>,
+    pub change: Option<f64>,
+    pub change_percent: Option<f64>,
+}
+
+/// The start of an EIA period, in milliseconds since the epoch.
+fn period_timestamp(period: &str) -> Option<i64> {
+    let time = match period.len() {
+        4 => NaiveDate::from_ymd_opt(period.parse().ok()?, 1, 1)?.and_time(NaiveTime::MIN),
+        7 => NaiveDate::parse_from_str(&format!("{period}-01"), "%Y-%m-%d").ok()?.and_time(NaiveTime::MIN),
+        10 => NaiveDate::parse_from_str(period, "%Y-%m-%d").ok()?.and_time(NaiveTime::MIN),
+        13 => NaiveDateTime::parse_from_str(&format!("{period}:00"), "%Y-%m-%dT%H:%M").ok()?,
+        _ => return None,
+    };
+    Some(time.and_utc().timestamp_millis())
+}
+
+fn series_data(dataset: &EiaDataset, observations: Vec<(String, f64)>) -> EiaSeriesData {
+    let latest = observations.last().map(|(_, v)| *v);
+    let previous = observations.len().checked_sub(2).map(|i| observations[i].1);
+    let change = latest.zip(previous).map(|(l, p)| l - p);
+    let change_percent = change.zip(previous).filter(|(_, p)| *p != 0.0).map(|(c, p)| c / p.abs() * 100.0);
+    let (periods, (timestamps, values)): (Vec<String>, (Vec<i64>, Vec<f64>)) = observations
+        .into_iter()
+        .filter_map(|(period, value)| Some((period_timestamp(&period)?, period, value)))
+        .map(|(time, period, value)| (period, (time, value)))
+        .unzip();
+    EiaSeriesData {
+        id: dataset.id.clone(),
+        name: dataset.name.clone(),
+        unit: dataset.unit.clone(),
+        frequency: dataset.frequency.clone(),
+        periods,
+        timestamps,
+        values,
+        latest,
+        previous,
+        change,
+        change_percent,
+    }
+}
+
+#[derive(Deserialize)]
+struct DataResponse {
+    response: DataBody,
+}
+
+#[derive(Deserialize)]
+struct DataBody {
+    data: Vec<Row>,
+}
+
+#[derive(Deserialize)]
+struct Row {
+    period: String,
+    /// A number or a numeric string; null where EIA has no value.
+    #[serde(default)]
+    value: Value,
+}
+
+/// `(period, value)` pairs, oldest first, with rows of the same period
+/// summed.
+fn parse_rows(text: &str) -> Result<Vec<(String, f64)>, String> {
+    let response: DataResponse = serde_json::from_str(text).map_err(|e| format!("invalid response: {e}"))?;
+    let mut periods: BTreeMap<String, f64> = BTreeMap::new();
+    for row in response.response.data {
+        let value = match &row.value {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        };
+        if let Some(value) = value.filter(|v: &f64| v.is_finite()) {
+            *periods.entry(row.period).or_default() += value;
+        }
+    }
+    Ok(periods.into_iter().collect())
+}
+
+fn query(dataset: &EiaDataset, key: &str, start: Option<&str>, length: usize) -> Vec<(String, String)> {
+    let mut params = vec![
+        ("api_key".to_string(), key.to_string()),
+        ("frequency".to_string(), dataset.frequency.clone()),
+        ("data[0]".to_string(), "value".to_string()),
+        ("sort[0][column]".to_string(), "period".to_string()),
+        ("sort[0][direction]".to_string(), "desc".to_string()),
+        ("length".to_string(), length.to_string()),
+    ];
+    for (facet, values) in &dataset.facets {
+        params.extend(values.iter().map(|v| (format!("facets[{facet}][]"), v.clone())));
+    }
+    if let Some(start) = start {
+        params.push(("start".to_string(), start.to_string()));
+    }
+    params
+}
+
+/// Fetch what is new in one dataset. Returns its data if anything changed.
+async fn sync_dataset(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    key: &str,
+    dataset: &EiaDataset,
+    history: usize,
+) -> Result<Option<EiaSeriesData>, String> {
+    let newest = with_store(app, {
+        let id = dataset.id.clone();
+        move |store| store.eia_observations(&id, 1)
+    })
+    .await?
+    .pop()
+    .map(|(period, _)| period);
+    let length = if newest.is_some() { MAX_ROWS } else { history.clamp(1, MAX_ROWS) };
+    let response = client
+        .get(format!("{API_URL}/{}/data/", dataset.route.trim_matches('/')))
+        .query(&query(dataset, key, newest.as_deref(), length))
+        .send()
+        .await
+        // The key is in the URL; keep it out of error messages.
+        .map_err(|e| format!("request failed: {}", e.without_url()))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("HTTP {status}"));
+    }
+    let text = response.text().await.map_err(|e| format!("request failed: {}", e.without_url()))?;
+    let observations = parse_rows(&text)?;
+    let dataset = dataset.clone();
+    with_store(app, move |store| {
+        if store.store_eia_observations(&dataset.id, &observations)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(series_data(&dataset, store.eia_observations(&dataset.id, DEFAULT_LIMIT)?)))
+    })
+    .await
+}
+
+/// Update every configured dataset and report the latest values to the
+/// alert engine. Returns the changed datasets.
+async fn sync(app: &AppHandle, client: &reqwest::Client, key: &str) -> Result<Vec<EiaSeriesData>, String> {
+    let config = app.state::<DesktopConfigState>().snapshot().eia;
+    let mut changed = Vec::new();
+    let mut errors = Vec::new();
+    for dataset in &config.datasets {
+        match sync_dataset(app, client, key, dataset, config.history).await {
+            Ok(Some(series)) => changed.push(series),
+            Ok(None) => {}
+            Err(err) => errors.push(format!("{}: {err}", dataset.id)),
+        }
+    }
+    let datasets = config.datasets.clone();
+    let latest = with_store(app, move |store| {
+        datasets
+            .iter()
+            .map(|dataset| Ok(series_data(dataset, store.eia_observations(&dataset.id, 2)?)))
+            .collect::<Result<Vec<_>, String>>()
+    })
+    .await?;
+    for series in latest {
+        if let Some(value) = series.latest {
+            crate::alerts::record_metric(app, &format!("eia.{}", series.id), value);
+        }
+        if let Some(change) = series.change {
+            crate::alerts::record_metric(app, &format!("eia.{}.change", series.id), change);
+        }
+    }
+    if !changed.is_empty() {
+        let _ = app.emit(UPDATED_EVENT, &changed);
+    }
+    if errors.is_empty() {
+        Ok(changed)
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+struct Poller {
+    app: AppHandle,
+    client: reqwest::Client,
+}
+
+impl crate::poll::Poller for Poller {
+    async fn round(&mut self) -> Duration {
+        let app = &self.app;
+        match app.state::<crate::SecretsCache>().get(KEY_SECRET) {
+            Some(key) => {
+                update_status(app, |status| status.syncing = true);
+                let result = sync(app, &self.client, &key).await;
+                let status = update_status(app, |status| {
+                    status.syncing = false;
+                    status.last_sync = Some(crate::unix_timestamp_secs());
+                    match result {
+                        Ok(changed) => {
+                            status.changed = changed.into_iter().map(|s| s.id).collect();
+                            status.error = None;
+                        }
+                        Err(err) => status.error = Some(err),
+                    }
+                });
+                match &status.error {
+                    Some(err) => tracing::warn!(target: "app", "EIA sync failed: {err}"),
+                    None => tracing::debug!(target: "app", changed = ?status.changed, "EIA sync complete"),
+                }
+            }
+            None => {
+                update_status(app, |status| status.error = Some(format!("{KEY_SECRET} is not set")));
+            }
+        }
+        SYNC_INTERVAL
+    }
+}
+
+async fn run(app: AppHandle, trigger: watch::Receiver<u64>) {
+    let client = match reqwest::Client::builder().use_native_tls().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!(target: "app", "EIA sync unavailable: {err}");
+            return;
+        }
+    };
+    let poller = Poller {
+        app: app.clone(),
+        client,
+    };
+    crate::poll::run_triggered(&app, trigger, poller).await;
+}
+
+/// Start the sync task; it syncs right away if a key is stored.
+pub fn start(app: &AppHandle) {
+    let (sender, receiver) = watch::channel(0);
+    *app.state::<EiaState>().trigger.lock().unwrap_or_else(|e| e.into_inner()) = Some(sender);
+    tauri::async_runtime::spawn(run(app.clone(), receiver));
+}
+
+/// Sync now, e.g. right after the key was added. Returns at once.
+#[tauri::command]
+pub fn sync_eia_data(webview: Webview, state: tauri::State<'_, EiaState>) -> Result<EiaSyncStatus, String> {
+    crate::metrics::of(&webview).observe("sync_eia_data", || {
+        crate::require_trusted_window(webview.label())?;
+        let status = state.status.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        if !status.syncing {
+            if let Some(trigger) = state.trigger.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+                trigger.send_modify(|n| *n += 1);
+            }
+        }
+        Ok(status)
+    })
+}
+
+#[tauri::command]
+pub fn get_eia_sync_status(webview: Webview, state: tauri::State<'_, EiaState>) -> Result<EiaSyncStatus, String> {
+    crate::metrics::of(&webview).observe("get_eia_sync_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.status.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+/// Configured datasets (all when `ids` is empty), each with its latest
+/// `limit` periods.
+#[tauri::command]
+pub async fn get_eia_data(
+    webview: Webview,
+    app: AppHandle,
+    ids: Option<Vec<String>>,
+    limit: Option<usize>,
+) -> Result<Vec<EiaSeriesData>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("get_eia_data", async move {
+            crate::require_trusted_window(webview.label())?;
+            let ids = ids.unwrap_or_default();
+            let datasets: Vec<EiaDataset> = app
+                .state::<DesktopConfigState>()
+                .snapshot()
+                .eia
+                .datasets
+                .into_iter()
+                .filter(|d| ids.is_empty() || ids.contains(&d.id))
+                .collect();
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_ROWS);
+            with_store(&app, move |store| {
+                datasets
+                    .iter()
+                    .map(|dataset| Ok(series_data(dataset, store.eia_observations(&dataset.id, limit)?)))
+                    .collect()
+            })
+            .await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod eia_tests {
+    use super::*;
+    use crate::config::EiaConfig;
+
+    #[test]
+    fn parses_and_sums_rows() {
+        let text = r#"{"response": {"total": 4, "frequency": "weekly", "data": [
+            {"period": "2026-03-13", "series": "A", "value": "425000"},
+            {"period": "2026-03-13", "series": "B", "value": 1000},
+            {"period": "2026-03-06", "series": "A", "value": "428400"},
+            {"period": "2026-02-27", "series": "A", "value": null}
+        ]}}"#;
+        assert_eq!(
+            parse_rows(text).unwrap(),
+            [("2026-03-06".to_string(), 428400.0), ("2026-03-13".to_string(), 426000.0)]
+        );
+        assert!(parse_rows(r#"{"error": "invalid api_key"}"#).is_err());
+    }
+
+    #[test]
+    fn reads_every_period_format() {
+        assert_eq!(period_timestamp("2026"), Some(1767225600000));
+        assert_eq!(period_timestamp("2026-03"), Some(1772323200000));
+        assert_eq!(period_timestamp("2026-03-13"), Some(1773360000000));
+        assert_eq!(period_timestamp("2026-03-13T05"), Some(1773378000000));
+        assert_eq!(period_timestamp("March"), None);
+    }
+
+    #[test]
+    fn builds_incremental_queries_and_series() {
+        let dataset = &EiaConfig::default().datasets[4];
+        let params = query(dataset, "KEY", Some("2026-03-13T05"), MAX_ROWS);
+        assert!(params.contains(&("facets[respondent][]".to_string(), "US48".to_string())));
+        assert!(params.contains(&("facets[type][]".to_string(), "D".to_string())));
+        assert!(params.contains(&("start".to_string(), "2026-03-13T05".to_string())));
+
+        let series = series_data(
+            dataset,
+            vec![("2026-03-13T04".to_string(), 400_000.0), ("2026-03-13T05".to_string(), 380_000.0)],
+        );
+        assert_eq!(series.periods, ["2026-03-13T04", "2026-03-13T05"]);
+        assert_eq!(series.timestamps, [1773374400000, 1773378000000]);
+        assert_eq!((series.latest, series.change), (Some(380_000.0), Some(-20_000.0)));
+        assert_eq!(series.change_percent, Some(-5.0));
+    }
+}
@@ -0,0 +1,299 @@
+//! Opt-in error reporting. Panics, fatal startup errors, failed commands, and
+//! unexpected sidecar exits are turned into an `ErrorReport` and handed to
+//! every configured `ErrorReporter`. Two reporters are built in, both off by
+//! default and enabled through `DesktopConfig::error_reporting`:
+//!
+//! - `FileReporter` appends one JSON object per line to
+//!   `error-reports.jsonl` in the logs dir.
+//! - `HttpReporter` POSTs the report as JSON to a user-configured endpoint.
+//!
+//! Reporters live in a process-wide registry (the panic hook has no
+//! `AppHandle`), swapped wholesale by `configure`. Identical reports are
+//! suppressed for `REPEAT_WINDOW` so a polling command that keeps failing
+//! does not flood the endpoint.
+
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Webview};
+
+use crate::config::{DesktopConfigState, ErrorReportingConfig};
+
+pub const ERROR_REPORTS_FILE: &str = "error-reports.jsonl";
+const REPEAT_WINDOW: Duration = Duration::from_secs(60);
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+static REPORTERS: RwLock<Vec<Box<dyn ErrorReporter>>> = RwLock::new(Vec::new());
+static RECENT: Mutex<Option<HashMap<String, Instant>>> = Mutex::new(None);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorSource {
+    Panic,
+    Fatal,
+    Command,
+    Sidecar,
+}
+
+impl ErrorSource {
+    /// Whether the process may exit right after reporting, so reporters
+    /// should finish synchronously.
+    fn is_terminal(self) -> bool {
+        matches!(self, ErrorSource::Panic | ErrorSource::Fatal)
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ErrorReport {
+    pub timestamp: u64,
+    pub source: ErrorSource,
+    pub message: String,
+    /// Command name, panic location, or exit status depending on `source`.
+    pub context: Option<String>,
+    pub app_version: &'static str,
+    pub os: &'static str,
+    pub arch: &'static str,
+}
+
+impl ErrorReport {
+    fn new(source: ErrorSource, message: String, context: Option<String>) -> Self {
+        ErrorReport {
+            timestamp: crate::unix_timestamp_secs(),
+            source,
+            message,
+            context,
+            app_version: env!("CARGO_PKG_VERSION"),
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+        }
+    }
+}
+
+/// A destination for error reports. Implementations must not call `report`
+/// themselves and should avoid panicking: they also run inside the panic hook.
+pub trait ErrorReporter: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn report(&self, report: &ErrorReport) -> Result<(), String>;
+}
+
+pub struct FileReporter {
+    path: PathBuf,
+}
+
+impl FileReporter {
+    pub fn new(path: PathBuf) -> Self {
+        FileReporter { path }
+    }
+}
+
+impl ErrorReporter for FileReporter {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn report(&self, report: &ErrorReport) -> Result<(), String> {
+        let mut line = serde_json::to_vec(report).map_err(|e| format!("Failed to serialize error report: {e}"))?;
+        line.push(b'\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(&line))
+            .map_err(|e| format!("Failed to write error report to {}: {e}", self.path.display()))
+    }
+}
+
+pub struct HttpReporter {
+    endpoint: reqwest::Url,
+    client: reqwest::Client,
+}
+
+impl HttpReporter {
+    pub fn new(endpoint: &str) -> Result<Self, String> {
+        Ok(HttpReporter {
+            endpoint: validate_endpoint(endpoint)?,
+            client: reqwest::Client::new(),
+        })
+    }
+}
+
+impl ErrorReporter for HttpReporter {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    /// Sends from a dedicated thread so callers on the async runtime (or in
+    /// the panic hook) never block on a runtime they are already inside.
+    /// Terminal reports wait up to `HTTP_TIMEOUT` for delivery.
+    fn report(&self, report: &ErrorReport) -> Result<(), String> {
+        let request = self.client.post(self.endpoint.clone()).json(report).timeout(HTTP_TIMEOUT);
+        let endpoint = self.endpoint.clone();
+        let (done_tx, done_rx) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("error-report-post".into())
+            .spawn(move || {
+                let result = tauri::async_runtime::block_on(async move { request.send().await?.error_for_status() });
+                if let Err(e) = result {
+                    tracing::warn!(target: "app", endpoint = %endpoint, "failed to deliver error report: {e}");
+                }
+                let _ = done_tx.send(());
+            })
+            .map_err(|e| format!("Failed to start error report upload: {e}"))?;
+        if report.source.is_terminal() {
+            let _ = done_rx.recv_timeout(HTTP_TIMEOUT);
+        }
+        Ok(())
+    }
+}
+
+/// https anywhere, plain http only to loopback.
+pub fn validate_endpoint(endpoint: &str) -> Result<reqwest::Url, String> {
+    let parsed = reqwest::Url::parse(endpoint.trim()).map_err(|_| format!("Invalid error report endpoint: {endpoint}"))?;
+    match parsed.scheme() {
+        "https" => Ok(parsed),
+        "http" if matches!(parsed.host_str(), Some("localhost") | Some("127.0.0.1")) => Ok(parsed),
+        _ => Err("Error report endpoint must be https:// (http:// only for localhost)".to_string()),
+    }
+}
+
+fn build_reporters(config: &ErrorReportingConfig, logs_dir: &Path) -> Result<Vec<Box<dyn ErrorReporter>>, String> {
+    let mut reporters: Vec<Box<dyn ErrorReporter>> = Vec::new();
+    if config.write_to_file {
+        reporters.push(Box::new(FileReporter::new(logs_dir.join(ERROR_REPORTS_FILE))));
+    }
+    if let Some(endpoint) = config.endpoint.as_deref().filter(|e| !e.trim().is_empty()) {
+        reporters.push(Box::new(HttpReporter::new(endpoint)?));
+    }
+    Ok(reporters)
+}
+
+/// Replace the active reporters to match `config`.
+pub fn configure(config: &ErrorReportingConfig, logs_dir: &Path) -> Result<(), String> {
+    let reporters = build_reporters(config, logs_dir)?;
+    *REPORTERS.write().unwrap_or_else(|e| e.into_inner()) = reporters;
+    Ok(())
+}
+
+/// True if the same report was already sent within `REPEAT_WINDOW`.
+fn is_repeat(recent: &mut HashMap<String, Instant>, key: String, now: Instant) -> bool {
+    recent.retain(|_, at| now.duration_since(*at) < REPEAT_WINDOW);
+    if recent.contains_key(&key) {
+        return true;
+    }
+    recent.insert(key, now);
+    false
+}
+
+/// Hand an error to every configured reporter. A no-op unless reporting has
+/// been enabled in the desktop config.
+pub fn report(source: ErrorSource, message: impl Into<String>, context: Option<String>) {
+    let reporters = REPORTERS.read().unwrap_or_else(|e| e.into_inner());
+    if reporters.is_empty() {
+        return;
+    }
+    let report = ErrorReport::new(source, message.into(), context);
+    let key = format!("{:?}|{}|{}", report.source, report.context.as_deref().unwrap_or(""), report.message);
+    {
+        let mut recent = RECENT.lock().unwrap_or_else(|e| e.into_inner());
+        if is_repeat(recent.get_or_insert_with(HashMap::new), key, Instant::now()) {
+            return;
+        }
+    }
+    for reporter in reporters.iter() {
+        if let Err(err) = reporter.report(&report) {
+            tracing::warn!(target: "app", reporter = reporter.name(), "{err}");
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_error_reporting(
+    webview: Webview,
+    config: tauri::State<'_, DesktopConfigState>,
+) -> Result<ErrorReportingConfig, String> {
+    crate::metrics::of(&webview).observe("get_error_reporting", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(config.snapshot().error_reporting)
+    })
+}
+
+/// Validate, apply, and persist the error-reporting settings.
+#[tauri::command]
+pub async fn set_error_reporting(
+    webview: Webview,
+    app: AppHandle,
+    settings: ErrorReportingConfig,
+) -> Result<(), String> {
+    crate::metrics::of(&webview)
+        .observe_async("set_error_reporting", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                let config = app.state::<DesktopConfigState>();
+                let mut settings = settings.clone();
+                settings.endpoint = settings
+                    .endpoint
+                    .map(|e| e.trim().to_string())
+                    .filter(|e| !e.is_empty());
+                configure(&settings, &crate::logs_dir_path(&app)?)?;
+                config.update(|c| c.error_reporting = settings.clone())?;
+                tracing::info!(
+                    target: "app",
+                    write_to_file = settings.write_to_file,
+                    endpoint = settings.endpoint.is_some(),
+                    "error reporting settings changed"
+                );
+                Ok(())
+            })
+            .await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod error_reporting_tests {
+    use super::{is_repeat, validate_endpoint, ErrorReport, ErrorReporter, ErrorSource, FileReporter, REPEAT_WINDOW};
+    use std::collections::HashMap;
+    use std::fs;
+    use std::time::Instant;
+
+    #[test]
+    fn endpoint_requires_https_except_loopback() {
+        assert!(validate_endpoint("https://errors.example.com/ingest").is_ok());
+        assert!(validate_endpoint("http://127.0.0.1:9000/report").is_ok());
+        assert!(validate_endpoint("http://errors.example.com").is_err());
+        assert!(validate_endpoint("file:///tmp/x").is_err());
+        assert!(validate_endpoint("not a url").is_err());
+    }
+
+    #[test]
+    fn file_reporter_appends_json_lines() {
+        let dir = std::env::temp_dir().join(format!("wm-errors-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let reporter = FileReporter::new(dir.join("errors.jsonl"));
+        for message in ["first", "second"] {
+            let report = ErrorReport::new(ErrorSource::Command, message.to_string(), Some("get_secret".into()));
+            reporter.report(&report).unwrap();
+        }
+        let contents = fs::read_to_string(dir.join("errors.jsonl")).unwrap();
+        let lines: Vec<serde_json::Value> = contents.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["source"], "command");
+        assert_eq!(lines[1]["message"], "second");
+    }
+
+    #[test]
+    fn suppresses_repeats_within_window() {
+        let mut recent = HashMap::new();
+        let now = Instant::now();
+        assert!(!is_repeat(&mut recent, "a".into(), now));
+        assert!(is_repeat(&mut recent, "a".into(), now));
+        assert!(!is_repeat(&mut recent, "b".into(), now));
+        assert!(!is_repeat(&mut recent, "a".into(), now + REPEAT_WINDOW));
+    }
+}
@@ -0,0 +1,484 @@
+//! Scheduled exports for downstream analysis pipelines. Jobs saved with
+//! `save_export_job` write a dataset as CSV or JSON to `exports.directory`
+//! (relative to the app's data directory, and an `exports` folder there
+//! when empty) every
+//! `intervalMins`, or right away with `run_export_job`. A job exports one
+//! of:
+//!
+//! - `alerts`: alerts fired by the alert rules (the last 100 are kept);
+//! - `events`: timeline events, optionally of some kinds and within an
+//!   area, the newest 10,000 per run;
+//! - `watchlistActivity`: hits on the saved watchlists, as `watchlists`
+//!   logs them.
+//!
+//! Each run covers the time since the job's last successful one (one
+//! interval back for its first), so consecutive files don't overlap, and a
+//! run with nothing new writes no file. Files are named after the job and
+//! the run's UTC time and written under a temporary name first, so a
+//! pipeline watching the folder never picks up half a file. Jobs and their
+//! last run live in `export-jobs.json`.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Webview};
+
+use crate::ais::BoundingBox;
+use crate::config::DesktopConfigState;
+use crate::store::{EventKind, EventStore, TimelineQuery};
+use crate::watchlists::WatchKind;
+
+pub const EXPORT_JOBS_FILE: &str = "export-jobs.json";
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const MAX_JOBS: usize = 100;
+const MAX_NAME_LEN: usize = 120;
+/// A week.
+const MAX_INTERVAL_MINS: u32 = 7 * 24 * 60;
+const MAX_EVENTS: usize = 10_000;
+
+const ALERT_COLUMNS: &[&str] = &["firedAt", "ruleId", "ruleName", "title", "body", "subject"];
+const EVENT_COLUMNS: &[&str] = &["time", "kind", "source", "id", "lat", "lon", "title", "data"];
+const ACTIVITY_COLUMNS: &[&str] = &["time", "kind", "entry", "subject", "description"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataFormat {
+    Csv,
+    Json,
+}
+
+impl DataFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            DataFormat::Csv => "csv",
+            DataFormat::Json => "json",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ExportDataset {
+    Alerts,
+    /// Timeline events of `kinds` (all when empty), within `area` if set.
+    Events {
+        #[serde(default)]
+        kinds: Vec<EventKind>,
+        #[serde(default)]
+        area: Option<BoundingBox>,
+    },
+    /// Watchlist hits on entries of `kinds` (all when empty).
+    WatchlistActivity {
+        #[serde(default)]
+        kinds: Vec<WatchKind>,
+    },
+}
+
+/// The outcome of a job's last run.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRun {
+    /// Seconds since the epoch.
+    pub at: u64,
+    pub rows: usize,
+    /// The file written; none when there was nothing new or the run failed.
+    pub file: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportJob {
+    /// Assigned by `save_export_job` when empty.
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub dataset: ExportDataset,
+    pub format: DataFormat,
+    #[serde(default = "default_interval_mins")]
+    pub interval_mins: u32,
+    /// Kept by the scheduler; ignored when saving.
+    #[serde(default)]
+    pub last_run: Option<ExportRun>,
+    /// Seconds since the epoch up to which data has been exported.
+    #[serde(default)]
+    pub exported_until: Option<u64>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_interval_mins() -> u32 {
+    60
+}
+
+impl ExportJob {
+    fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() || self.name.len() > MAX_NAME_LEN {
+            return Err(format!("Job name must be 1-{MAX_NAME_LEN} characters"));
+        }
+        if !(1..=MAX_INTERVAL_MINS).contains(&self.interval_mins) {
+            return Err(format!("Interval must be 1-{MAX_INTERVAL_MINS} minutes"));
+        }
+        if let ExportDataset::Events { area: Some(area), .. } = &self.dataset {
+            if !area.is_valid() {
+                return Err("Export area must be a valid bounding box".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    fn interval_secs(&self) -> u64 {
+        u64::from(self.interval_mins) * 60
+    }
+
+    fn is_due(&self, now: u64) -> bool {
+        self.enabled && self.last_run.as_ref().is_none_or(|run| now >= run.at + self.interval_secs())
+    }
+
+    /// `<name>-<UTC time>.<ext>`, with the name reduced to lowercase
+    /// letters, digits and dashes.
+    fn file_name(&self, at: u64) -> String {
+        let mut slug = String::new();
+        for c in self.name.chars() {
+            if c.is_ascii_alphanumeric() {
+                slug.push(c.to_ascii_lowercase());
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+        let slug = match slug.trim_end_matches('-') {
+            "" => self.id.as_str(),
+            slug => slug,
+        };
+        let time = DateTime::from_timestamp(at as i64, 0).unwrap_or_default().format("%Y%m%dT%H%M%SZ");
+        format!("{slug}-{time}.{}", self.format.extension())
+    }
+}
+
+/// Managed state: the jobs, and which are running.
+pub struct ExportsState {
+    path: PathBuf,
+    /// The file could not be read and could not be moved aside, so it is
+    /// not overwritten.
+    unquarantined: bool,
+    jobs: Mutex<Vec<ExportJob>>,
+    running: Mutex<HashSet<String>>,
+}
+
+impl ExportsState {
+    /// Load `export-jobs.json`; a missing file means no jobs, and an
+    /// unreadable one is moved aside (see `load_json_file`).
+    pub fn load(path: PathBuf) -> Self {
+        let (jobs, unquarantined): (Vec<ExportJob>, _) = crate::load_json_file(&path);
+        ExportsState {
+            path,
+            unquarantined,
+            jobs: Mutex::new(jobs),
+            running: Mutex::default(),
+        }
+    }
+
+    fn persist(&self, jobs: &[ExportJob]) -> Result<(), String> {
+        if self.unquarantined {
+            return Err(format!("Export jobs {} are unreadable and could not be moved aside", self.path.display()));
+        }
+        let serialized = serde_json::to_string_pretty(jobs).map_err(|e| format!("Failed to serialize export jobs: {e}"))?;
+        fs::write(&self.path, serialized).map_err(|e| format!("Failed to write export jobs {}: {e}", self.path.display()))
+    }
+}
+
+/// One CSV field, quoted when it has to be. Nested values are written as
+/// JSON.
+fn csv_field(value: &Value) -> String {
+    let text = match value {
+        Value::Null => return String::new(),
+        Value::String(s) => s.clone(),
+        _ => value.to_string(),
+    };
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+/// `rows` (JSON objects) as `format`; CSV has a header of `columns`.
+fn render(format: DataFormat, columns: &[&str], rows: &[Value]) -> Result<String, String> {
+    match format {
+        DataFormat::Json => serde_json::to_string_pretty(rows).map_err(|e| format!("Failed to serialize export: {e}")),
+        DataFormat::Csv => {
+            let mut csv = columns.join(",");
+            csv.push_str("\r\n");
+            for row in rows {
+                let fields: Vec<String> = columns.iter().map(|c| csv_field(row.get(*c).unwrap_or(&Value::Null))).collect();
+                csv.push_str(&fields.join(","));
+                csv.push_str("\r\n");
+            }
+            Ok(csv)
+        }
+    }
+}
+
+fn to_rows<T: Serialize>(items: &[T]) -> Result<Vec<Value>, String> {
+    items
+        .iter()
+        .map(|item| serde_json::to_value(item).map_err(|e| format!("Failed to serialize export: {e}")))
+        .collect()
+}
+
+/// What `dataset` recorded in `[since, until)` seconds, oldest first.
+fn collect(app: &AppHandle, dataset: &ExportDataset, since: u64, until: u64) -> Result<(&'static [&'static str], Vec<Value>), String> {
+    match dataset {
+        ExportDataset::Alerts => {
+            let mut alerts = crate::alerts::fired_since(app, since);
+            alerts.retain(|alert| alert.fired_at < until);
+            Ok((ALERT_COLUMNS, to_rows(&alerts)?))
+        }
+        ExportDataset::Events { kinds, area } => {
+            let mut events = app.state::<EventStore>().events(&TimelineQuery {
+                kinds: kinds.clone(),
+                bounding_box: *area,
+                since: Some((since * 1000) as i64),
+                until: Some((until * 1000) as i64 - 1),
+                limit: Some(MAX_EVENTS),
+            })?;
+            events.reverse();
+            Ok((EVENT_COLUMNS, to_rows(&events)?))
+        }
+        ExportDataset::WatchlistActivity { kinds } => {
+            let mut activity = crate::watchlists::activity_since(app, since);
+            activity.retain(|hit| hit.time < until && (kinds.is_empty() || kinds.contains(&hit.kind)));
+            Ok((ACTIVITY_COLUMNS, to_rows(&activity)?))
+        }
+    }
+}
+
+fn directory(app: &AppHandle) -> Result<PathBuf, String> {
+    let configured = app.state::<DesktopConfigState>().snapshot().exports.directory;
+    let data = app.path().app_data_dir().map_err(|e| format!("Failed to resolve app data dir: {e}"))?;
+    // A relative directory is taken from the data directory, not from
+    // wherever the app happened to be started.
+    Ok(match configured.trim() {
+        "" => data.join("exports"),
+        configured => data.join(configured),
+    })
+}
+
+/// Write `contents` to `dir/file` by way of a hidden temporary file.
+fn write_file(dir: &Path, file: &str, contents: &str) -> Result<PathBuf, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let path = dir.join(file);
+    let partial = dir.join(format!(".{file}.part"));
+    fs::write(&partial, contents).map_err(|e| format!("Failed to write {}: {e}", partial.display()))?;
+    fs::rename(&partial, &path).map_err(|e| format!("Failed to move {} into place: {e}", path.display()))?;
+    Ok(path)
+}
+
+/// Export what `job` has not exported yet, up to `at`. Blocking.
+fn export(app: &AppHandle, job: &ExportJob, at: u64) -> Result<(usize, Option<PathBuf>), String> {
+    let since = job.exported_until.unwrap_or(at.saturating_sub(job.interval_secs()));
+    let (columns, rows) = collect(app, &job.dataset, since, at)?;
+    if rows.is_empty() {
+        return Ok((0, None));
+    }
+    let contents = render(job.format, columns, &rows)?;
+    let path = write_file(&directory(app)?, &job.file_name(at), &contents)?;
+    Ok((rows.len(), Some(path)))
+}
+
+/// Run `job` now and record the outcome on it.
+async fn execute(app: &AppHandle, job: ExportJob) -> Result<ExportRun, String> {
+    let state = app.state::<ExportsState>();
+    if !state.running.lock().unwrap_or_else(|e| e.into_inner()).insert(job.id.clone()) {
+        return Err(format!("Export job {} is already running", job.name));
+    }
+    let at = crate::unix_timestamp_secs();
+    let id = job.id.clone();
+    let handle = app.clone();
+    let result = crate::runtime::blocking(move || export(&handle, &job, at)).await;
+    let run = match result {
+        Ok((rows, file)) => {
+            if let Some(file) = &file {
+                tracing::info!(target: "app", job = %id, rows, path = %file.display(), "export written");
+            }
+            ExportRun { at, rows, file: file.map(|path| path.display().to_string()), error: None }
+        }
+        Err(err) => {
+            tracing::warn!(target: "app", job = %id, "export failed: {err}");
+            ExportRun { at, rows: 0, file: None, error: Some(err) }
+        }
+    };
+    {
+        let mut jobs = state.jobs.lock().unwrap_or_else(|e| e.into_inner());
+        // Deleted while running: nothing to record.
+        if let Some(job) = jobs.iter_mut().find(|job| job.id == id) {
+            if run.error.is_none() {
+                job.exported_until = Some(at);
+            }
+            job.last_run = Some(run.clone());
+            if let Err(err) = state.persist(&jobs) {
+                tracing::warn!(target: "app", "{err}");
+            }
+        }
+    }
+    state.running.lock().unwrap_or_else(|e| e.into_inner()).remove(&id);
+    Ok(run)
+}
+
+async fn run(app: AppHandle) {
+    loop {
+        let now = crate::unix_timestamp_secs();
+        let due: Vec<ExportJob> = {
+            let jobs = app.state::<ExportsState>().jobs.lock().unwrap_or_else(|e| e.into_inner()).clone();
+            jobs.into_iter().filter(|job| job.is_due(now)).collect()
+        };
+        for job in due {
+            let _ = execute(&app, job).await;
+        }
+        tokio::time::sleep(CHECK_INTERVAL).await;
+    }
+}
+
+pub fn start(app: &AppHandle) {
+    if !app.state::<DesktopConfigState>().snapshot().exports.enabled {
+        return;
+    }
+    tauri::async_runtime::spawn(run(app.clone()));
+}
+
+#[tauri::command]
+pub fn list_export_jobs(webview: Webview, state: tauri::State<'_, ExportsState>) -> Result<Vec<ExportJob>, String> {
+    crate::metrics::of(&webview).observe("list_export_jobs", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.jobs.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+/// Add a job, or replace the one with the same id, keeping its last run.
+#[tauri::command]
+pub async fn save_export_job(webview: Webview, app: AppHandle, mut job: ExportJob) -> Result<ExportJob, String> {
+    crate::metrics::of(&webview)
+        .observe_async("save_export_job", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                let state = app.state::<ExportsState>();
+                job.validate()?;
+                if job.id.is_empty() {
+                    let mut buf = [0u8; 8];
+                    getrandom::getrandom(&mut buf).map_err(|e| format!("Failed to generate job id: {e}"))?;
+                    job.id = buf.iter().map(|b| format!("{b:02x}")).collect();
+                }
+                let mut jobs = state.jobs.lock().unwrap_or_else(|e| e.into_inner());
+                let mut updated = jobs.clone();
+                match updated.iter_mut().find(|existing| existing.id == job.id) {
+                    Some(existing) => {
+                        job.last_run = existing.last_run.take();
+                        job.exported_until = existing.exported_until;
+                        *existing = job.clone();
+                    }
+                    None => {
+                        job.last_run = None;
+                        job.exported_until = None;
+                        updated.push(job.clone());
+                    }
+                }
+                if updated.len() > MAX_JOBS {
+                    return Err(format!("At most {MAX_JOBS} export jobs can be saved"));
+                }
+                state.persist(&updated)?;
+                *jobs = updated;
+                Ok(job)
+            })
+            .await
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn delete_export_job(webview: Webview, app: AppHandle, id: String) -> Result<bool, String> {
+    crate::metrics::of(&webview)
+        .observe_async("delete_export_job", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                let state = app.state::<ExportsState>();
+                let mut jobs = state.jobs.lock().unwrap_or_else(|e| e.into_inner());
+                let updated: Vec<ExportJob> = jobs.iter().filter(|job| job.id != id).cloned().collect();
+                if updated.len() == jobs.len() {
+                    return Ok(false);
+                }
+                state.persist(&updated)?;
+                *jobs = updated;
+                Ok(true)
+            })
+            .await
+        })
+        .await
+}
+
+/// Run a job now, whether or not it is due or enabled, and return the run.
+#[tauri::command]
+pub async fn run_export_job(webview: Webview, app: AppHandle, id: String) -> Result<ExportRun, String> {
+    crate::metrics::of(&webview)
+        .observe_async("run_export_job", async move {
+            crate::require_trusted_window(webview.label())?;
+            let job = {
+                let jobs = app.state::<ExportsState>().jobs.lock().unwrap_or_else(|e| e.into_inner()).clone();
+                jobs.into_iter().find(|job| job.id == id).ok_or_else(|| format!("No export job {id}"))?
+            };
+            execute(&app, job).await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod exports_tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn renders_csv_and_json() {
+        let rows = [
+            json!({ "time": 1, "title": "Strike, \"port\"", "lat": 12.5, "data": { "a": 1 } }),
+            json!({ "time": 2, "title": "Calm" }),
+        ];
+        let columns = ["time", "title", "lat", "data"];
+        let csv = render(DataFormat::Csv, &columns, &rows).unwrap();
+        assert_eq!(csv, "time,title,lat,data\r\n1,\"Strike, \"\"port\"\"\",12.5,\"{\"\"a\"\":1}\"\r\n2,Calm,,\r\n");
+        let parsed: Value = serde_json::from_str(&render(DataFormat::Json, &columns, &rows).unwrap()).unwrap();
+        assert_eq!(parsed, json!(rows));
+    }
+
+    #[test]
+    fn reads_validates_and_schedules_jobs() {
+        let json = r#"{"name":"Hormuz vessels / hourly","dataset":{"type":"events","kinds":["vessel"],"area":{"south":25,"west":55,"north":27,"east":58}},"format":"csv"}"#;
+        let mut job: ExportJob = serde_json::from_str(json).unwrap();
+        assert!(job.validate().is_ok());
+        assert_eq!(job.interval_mins, 60);
+        assert!(matches!(job.dataset, ExportDataset::Events { area: Some(_), .. }));
+        assert_eq!(job.file_name(1_773_360_000), "hormuz-vessels-hourly-20260313T000000Z.csv");
+
+        assert!(job.is_due(1_000));
+        job.last_run = Some(ExportRun { at: 1_000, rows: 0, file: None, error: None });
+        assert!(!job.is_due(4_599));
+        assert!(job.is_due(4_600));
+        job.enabled = false;
+        assert!(!job.is_due(10_000));
+
+        job.interval_mins = 0;
+        assert!(job.validate().is_err());
+        let activity: ExportJob =
+            serde_json::from_str(r#"{"name":"Hits","dataset":{"type":"watchlistActivity","kinds":["region"]},"format":"json"}"#).unwrap();
+        assert_eq!(activity.dataset, ExportDataset::WatchlistActivity { kinds: vec![WatchKind::Region] });
+    }
+}
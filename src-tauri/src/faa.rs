@@ -0,0 +1,570 @@
+//! FAA airspace notices polled into the local `store` as map overlays. With
+//! `faa.enabled` set (the default) the worker fetches the active Temporary
+//! Flight Restrictions from the FAA's TFR map service every
+//! `UPDATE_INTERVAL`; with the FAA NOTAM API credentials stored
+//! (`FAA_NOTAM_CLIENT_ID`, `FAA_NOTAM_CLIENT_SECRET`) it also fetches the
+//! NOTAMs for `faa.notam_locations` and within `faa.notam_areas`.
+//!
+//! Each poll replaces the stored set of its source, so lapsed notices drop
+//! out; every notice is a GeoJSON feature (a NOTAM without a geometry of
+//! its own gets its reference point). New notices go out in one
+//! `faa://notices` batch and are matched against the `watchlists` regions,
+//! so `watchlists` alert rules fire for restrictions in an area being
+//! watched; the number of active TFRs is reported as the `faa.tfrs` metric.
+//! `get_airspace_notices` returns the stored set as a feature collection.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDateTime};
+use geo::BoundingRect;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+use crate::ais::BoundingBox;
+use crate::config::{DesktopConfigState, FaaConfig};
+use crate::store::{EventStore, NoticeQuery, NoticeRow};
+use crate::watchlists::Coverage;
+
+pub const NOTICES_EVENT: &str = "faa://notices";
+
+const TFR_URL: &str = "https://tfr.faa.gov/geoserver/TFR/ows";
+const NOTAM_URL: &str = "https://external-api.faa.gov/notamapi/v1/notams";
+const CLIENT_ID_SECRET: &str = "FAA_NOTAM_CLIENT_ID";
+const CLIENT_SECRET_SECRET: &str = "FAA_NOTAM_CLIENT_SECRET";
+
+const UPDATE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const PAGE_SIZE: usize = 1_000;
+/// Pages fetched per NOTAM query at most.
+const MAX_PAGES: u32 = 10;
+const MAX_RADIUS_NM: f64 = 100.0;
+const METRES_PER_NM: f64 = 1_852.0;
+const METRES_PER_DEGREE: f64 = 111_320.0;
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FaaStatus {
+    pub enabled: bool,
+    /// Whether the NOTAM API credentials are stored and something is
+    /// configured to fetch.
+    pub notams: bool,
+    /// When notices were last fetched, in milliseconds since the epoch.
+    pub last_update: Option<i64>,
+    pub active_tfrs: usize,
+    pub active_notams: usize,
+    /// New notices from the last poll.
+    pub added: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct FaaState {
+    status: Mutex<FaaStatus>,
+}
+
+fn update_status(app: &AppHandle, f: impl FnOnce(&mut FaaStatus)) {
+    let state = app.state::<FaaState>();
+    f(&mut state.status.lock().unwrap_or_else(|e| e.into_inner()));
+}
+
+/// The box around a GeoJSON geometry, widened by `radius` metres for a
+/// point with a radius.
+fn bounds(geometry: &Value, radius: f64) -> Option<BoundingBox> {
+    let geometry = geojson::Geometry::from_json_value(geometry.clone()).ok()?;
+    let rect = geo::Geometry::<f64>::try_from(geometry).ok()?.bounding_rect()?;
+    let (min, max) = (rect.min(), rect.max());
+    let dlat = radius / METRES_PER_DEGREE;
+    let dlon = dlat / min.y.to_radians().cos().max(0.01);
+    let b = BoundingBox {
+        south: (min.y - dlat).max(-90.0),
+        west: (min.x - dlon).max(-180.0),
+        north: (max.y + dlat).min(90.0),
+        east: (max.x + dlon).min(180.0),
+    };
+    b.is_valid().then_some(b)
+}
+
+/// The part of a TFR's regulation most people know it by.
+fn tfr_type(legal: &str) -> &'static str {
+    match legal.trim() {
+        "91.137" => "Hazards",
+        "91.138" => "Hazards (Hawaii)",
+        "91.139" => "Emergency air traffic rules",
+        "91.141" => "VIP",
+        "91.143" => "Space operations",
+        "91.144" => "High barometric pressure",
+        "91.145" => "Aerial demonstrations or sports",
+        "99.7" => "Security",
+        _ => "Restriction",
+    }
+}
+
+/// The active TFRs in a WFS GeoJSON response, one row per area.
+fn parse_tfrs(text: &str) -> Result<Vec<NoticeRow>, String> {
+    let collection: Value = serde_json::from_str(text).map_err(|e| format!("invalid TFR list: {e}"))?;
+    let features = collection["features"].as_array().ok_or("invalid TFR list: no features")?;
+    Ok(features
+        .iter()
+        .filter_map(|feature| {
+            let p = &feature["properties"];
+            let key = p["NOTAM_KEY"].as_str().filter(|k| !k.is_empty())?;
+            let geometry = &feature["geometry"];
+            let bounds = bounds(geometry, 0.0)?;
+            let number = key.split('-').next().unwrap_or(key);
+            let text = |name: &str| p[name].as_str().unwrap_or_default().trim().to_string();
+            let updated = NaiveDateTime::parse_from_str(&text("LAST_MODIFICATION_DATETIME"), "%Y%m%d%H%M")
+                .ok()
+                .map(|t| t.and_utc().timestamp_millis());
+            let id = format!("tfr:{key}");
+            Some(NoticeRow {
+                data: json!({
+                    "type": "Feature",
+                    "id": id,
+                    "geometry": geometry,
+                    "properties": {
+                        "source": "tfr",
+                        "number": number,
+                        "title": text("TITLE"),
+                        "type": tfr_type(&text("LEGAL")),
+                        "state": text("STATE"),
+                        "facility": text("CNS_LOCATION_ID"),
+                        "updatedAt": updated,
+                        "url": format!("https://tfr.faa.gov/tfr3/?page=detail_{}", number.replace('/', "_")),
+                    },
+                }),
+                id,
+                source: "tfr".to_string(),
+                bounds,
+                ends: None,
+            })
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NotamPage {
+    #[serde(default)]
+    items: Vec<NotamItem>,
+    #[serde(default)]
+    total_pages: u32,
+}
+
+#[derive(Deserialize)]
+struct NotamItem {
+    properties: NotamProperties,
+    #[serde(default)]
+    geometry: Value,
+}
+
+#[derive(Deserialize)]
+struct NotamProperties {
+    #[serde(rename = "coreNOTAMData")]
+    core_notam_data: CoreNotamData,
+}
+
+#[derive(Deserialize)]
+struct CoreNotamData {
+    notam: Notam,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Notam {
+    id: String,
+    number: String,
+    location: String,
+    icao_location: String,
+    classification: String,
+    effective_start: String,
+    effective_end: String,
+    text: String,
+    /// `DDMM[SS]{N,S}DDDMM[SS]{E,W}`.
+    coordinates: String,
+    /// Nautical miles, e.g. `005`.
+    radius: String,
+    #[serde(rename = "minimumFL")]
+    minimum_fl: String,
+    #[serde(rename = "maximumFL")]
+    maximum_fl: String,
+}
+
+/// Milliseconds since the epoch of a NOTAM time; `PERM` and unparseable
+/// times are open-ended.
+fn notam_time(time: &str) -> Option<i64> {
+    let time = time.trim().trim_end_matches("EST");
+    DateTime::parse_from_rfc3339(time).ok().map(|t| t.timestamp_millis())
+}
+
+/// `[lon, lat]` of a NOTAM's `coordinates`, e.g. `3851N07702W`.
+fn notam_point(coordinates: &str) -> Option<[f64; 2]> {
+    let split = coordinates.find(['N', 'S'])?;
+    let (lat, rest) = coordinates.split_at(split);
+    let (hemisphere, rest) = rest.split_at(1);
+    let (lon, meridian) = rest.split_at(rest.len().checked_sub(1)?);
+    let angle = |digits: &str, degrees: usize| -> Option<f64> {
+        if !digits.chars().all(|c| c.is_ascii_digit()) || !matches!(digits.len() - degrees, 2 | 4) {
+            return None;
+        }
+        let part = |range: std::ops::Range<usize>| digits.get(range).map_or(Some(0.0), |d| d.parse::<f64>().ok());
+        Some(part(0..degrees)? + part(degrees..degrees + 2)? / 60.0 + part(degrees + 2..degrees + 4)? / 3600.0)
+    };
+    let lat = angle(lat, 2)? * if hemisphere == "S" { -1.0 } else { 1.0 };
+    let lon = angle(lon, 3)?
+        * match meridian {
+            "W" => -1.0,
+            "E" => 1.0,
+            _ => return None,
+        };
+    ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon)).then_some([lon, lat])
+}
+
+fn parse_notams(text: &str) -> Result<(Vec<NoticeRow>, u32), String> {
+    let page: NotamPage = serde_json::from_str(text).map_err(|e| format!("invalid NOTAM response: {e}"))?;
+    let rows = page
+        .items
+        .into_iter()
+        .filter_map(|item| {
+            let n = item.properties.core_notam_data.notam;
+            if n.id.is_empty() {
+                return None;
+            }
+            let radius_nm = n.radius.trim().parse::<f64>().ok().filter(|r| *r > 0.0 && *r < 999.0);
+            let geometry = match item.geometry {
+                Value::Object(_) => item.geometry,
+                _ => json!({ "type": "Point", "coordinates": notam_point(&n.coordinates)? }),
+            };
+            let point_radius = if geometry["type"] == "Point" { radius_nm.unwrap_or(0.0) * METRES_PER_NM } else { 0.0 };
+            let bounds = bounds(&geometry, point_radius)?;
+            let ends = notam_time(&n.effective_end);
+            let location = if n.icao_location.is_empty() { n.location } else { n.icao_location };
+            let id = format!("notam:{}", n.id);
+            Some(NoticeRow {
+                data: json!({
+                    "type": "Feature",
+                    "id": id,
+                    "geometry": geometry,
+                    "properties": {
+                        "source": "notam",
+                        "number": n.number,
+                        "title": format!("{} {}", location, n.number).trim(),
+                        "location": location,
+                        "classification": n.classification,
+                        "text": n.text,
+                        "startsAt": notam_time(&n.effective_start),
+                        "endsAt": ends,
+                        "radiusNm": radius_nm,
+                        "minimumFl": n.minimum_fl,
+                        "maximumFl": n.maximum_fl,
+                    },
+                }),
+                id,
+                source: "notam".to_string(),
+                bounds,
+                ends,
+            })
+        })
+        .collect();
+    Ok((rows, page.total_pages))
+}
+
+/// The NOTAM API queries for the configured locations and areas.
+fn notam_queries(config: &FaaConfig) -> Vec<Vec<(&'static str, String)>> {
+    let locations = config
+        .notam_locations
+        .iter()
+        .map(|l| l.trim().to_uppercase())
+        .filter(|l| !l.is_empty())
+        .map(|location| vec![("icaoLocation", location)]);
+    let areas = config.notam_areas.iter().map(|area| {
+        vec![
+            ("locationLatitude", area.lat.to_string()),
+            ("locationLongitude", area.lon.to_string()),
+            ("locationRadius", area.radius_nm.clamp(1.0, MAX_RADIUS_NM).to_string()),
+        ]
+    });
+    locations.chain(areas).collect()
+}
+
+async fn get(request: reqwest::RequestBuilder) -> Result<String, String> {
+    request
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("request failed: {e}"))?
+        .text()
+        .await
+        .map_err(|e| format!("request failed: {e}"))
+}
+
+async fn fetch_tfrs(client: &reqwest::Client) -> Result<Vec<NoticeRow>, String> {
+    let query = [
+        ("service", "WFS"),
+        ("version", "1.0.0"),
+        ("request", "GetFeature"),
+        ("typeName", "TFR:V_TFR_LOC"),
+        ("outputFormat", "application/json"),
+    ];
+    parse_tfrs(&get(client.get(TFR_URL).query(&query)).await?)
+}
+
+async fn fetch_notams(client: &reqwest::Client, config: &FaaConfig, credentials: &(String, String)) -> Result<Vec<NoticeRow>, String> {
+    let mut notices = BTreeMap::new();
+    for query in notam_queries(config) {
+        let mut page = 1;
+        loop {
+            let request = client
+                .get(NOTAM_URL)
+                .header("client_id", &credentials.0)
+                .header("client_secret", &credentials.1)
+                .query(&query)
+                .query(&[("responseFormat", "geoJson".to_string()), ("pageSize", PAGE_SIZE.to_string()), ("pageNum", page.to_string())]);
+            let (rows, pages) = parse_notams(&get(request).await?)?;
+            // Overlapping queries return the same NOTAM more than once.
+            notices.extend(rows.into_iter().map(|row| (row.id.clone(), row)));
+            if page >= pages.min(MAX_PAGES) {
+                break;
+            }
+            page += 1;
+        }
+    }
+    Ok(notices.into_values().collect())
+}
+
+/// Store the active set of a source, returning the new notices and how many
+/// are active.
+async fn store(app: &AppHandle, source: &'static str, notices: Vec<NoticeRow>) -> Result<(Vec<NoticeRow>, usize), String> {
+    let handle = app.clone();
+    crate::runtime::blocking(move || {
+        let added = handle.state::<EventStore>().replace_notices(source, &notices)?;
+        let active = notices.len();
+        let mut notices: Vec<Option<NoticeRow>> = notices.into_iter().map(Some).collect();
+        Ok((added.into_iter().filter_map(|i| notices[i].take()).collect(), active))
+    })
+    .await
+}
+
+fn credentials(app: &AppHandle) -> Option<(String, String)> {
+    let secrets = app.state::<crate::SecretsCache>();
+    Some((secrets.get(CLIENT_ID_SECRET)?, secrets.get(CLIENT_SECRET_SECRET)?))
+}
+
+/// One poll of every configured source. A source that fails keeps its
+/// stored notices.
+async fn update(app: &AppHandle, client: &reqwest::Client, config: &FaaConfig) -> Result<(), String> {
+    let mut added = Vec::new();
+    let mut errors = Vec::new();
+    if config.enabled {
+        match fetch_tfrs(client).await {
+            Ok(tfrs) => {
+                let (new, active) = store(app, "tfr", tfrs).await?;
+                added.extend(new);
+                update_status(app, |status| status.active_tfrs = active);
+                crate::alerts::record_metric(app, "faa.tfrs", active as f64);
+            }
+            Err(err) => errors.push(format!("TFRs: {err}")),
+        }
+    }
+    let credentials = credentials(app).filter(|_| !notam_queries(config).is_empty());
+    update_status(app, |status| status.notams = credentials.is_some());
+    if let Some(credentials) = credentials {
+        match fetch_notams(client, config, &credentials).await {
+            Ok(notams) => {
+                let (new, active) = store(app, "notam", notams).await?;
+                added.extend(new);
+                update_status(app, |status| status.active_notams = active);
+            }
+            Err(err) => errors.push(format!("NOTAMs: {err}")),
+        }
+    }
+
+    tracing::debug!(target: "app", added = added.len(), "FAA notices polled");
+    update_status(app, |status| {
+        status.last_update = Some(chrono::Utc::now().timestamp_millis());
+        status.added = added.len();
+    });
+    if !added.is_empty() {
+        let descriptions: Vec<String> = added
+            .iter()
+            .map(|n| {
+                let p = &n.data["properties"];
+                let number = p["number"].as_str().unwrap_or_default();
+                match p["source"].as_str() {
+                    Some("tfr") => format!("TFR {number} ({})", p["type"].as_str().unwrap_or_default()),
+                    _ => format!("NOTAM {}", p["title"].as_str().unwrap_or(number)),
+                }
+            })
+            .collect();
+        let coverage: Vec<Coverage> = added
+            .iter()
+            .zip(&descriptions)
+            .map(|(n, description)| Coverage { id: &n.id, bounds: n.bounds, description })
+            .collect();
+        crate::watchlists::observe_coverage(app, &coverage);
+        let _ = app.emit(NOTICES_EVENT, added.into_iter().map(|n| n.data).collect::<Vec<Value>>());
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+struct Poller {
+    app: AppHandle,
+    client: reqwest::Client,
+    config: FaaConfig,
+}
+
+impl crate::poll::Poller for Poller {
+    async fn round(&mut self) -> Duration {
+        let result = update(&self.app, &self.client, &self.config).await;
+        if let Err(err) = &result {
+            tracing::warn!(target: "app", "FAA update failed: {err}");
+        }
+        update_status(&self.app, |status| status.error = result.err());
+        UPDATE_INTERVAL
+    }
+}
+
+async fn run(app: AppHandle, config: FaaConfig) {
+    let client = match reqwest::Client::builder().use_native_tls().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!(target: "app", "FAA polling unavailable: {err}");
+            return;
+        }
+    };
+    let poller = Poller {
+        app: app.clone(),
+        client,
+        config,
+    };
+    crate::poll::run(&app, poller).await;
+}
+
+/// Start polling if `faa.enabled` is set or NOTAMs are configured.
+pub fn start(app: &AppHandle) {
+    let config = app.state::<DesktopConfigState>().snapshot().faa;
+    update_status(app, |status| status.enabled = config.enabled);
+    if config.enabled || !notam_queries(&config).is_empty() {
+        tauri::async_runtime::spawn(run(app.clone(), config));
+    }
+}
+
+#[tauri::command]
+pub fn get_faa_status(webview: Webview, state: tauri::State<'_, FaaState>) -> Result<FaaStatus, String> {
+    crate::metrics::of(&webview).observe("get_faa_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.status.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+/// Active TFRs and NOTAMs as a GeoJSON feature collection.
+#[tauri::command]
+pub async fn get_airspace_notices(webview: Webview, app: AppHandle, query: Option<NoticeQuery>) -> Result<Value, String> {
+    crate::metrics::of(&webview)
+        .observe_async("get_airspace_notices", async move {
+            crate::require_trusted_window(webview.label())?;
+            let query = query.unwrap_or_default();
+            let now = chrono::Utc::now().timestamp_millis();
+            let features = crate::runtime::blocking(move || app.state::<EventStore>().notices(&query, now)).await?;
+            Ok(json!({ "type": "FeatureCollection", "features": features }))
+        })
+        .await
+}
+
+#[cfg(test)]
+mod faa_tests {
+    use super::*;
+    use crate::config::NotamArea;
+
+    const TFRS: &str = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {
+                "type": "Feature",
+                "id": "V_TFR_LOC.1",
+                "geometry": { "type": "Polygon", "coordinates": [[[-77.2, 38.7], [-76.8, 38.7], [-76.8, 39.1], [-77.2, 39.1], [-77.2, 38.7]]] },
+                "properties": {
+                    "NOTAM_KEY": "6/4321-1-FDC-F", "TITLE": "WASHINGTON, DC, VIP Movement", "LEGAL": "91.141",
+                    "STATE": "DC", "CNS_LOCATION_ID": "ZDC", "LAST_MODIFICATION_DATETIME": "202610141530"
+                }
+            },
+            { "type": "Feature", "geometry": null, "properties": { "NOTAM_KEY": "6/1111-1-FDC-F" } }
+        ]
+    }"#;
+
+    const NOTAMS: &str = r#"{
+        "pageSize": 2, "pageNum": 1, "totalCount": 2, "totalPages": 1,
+        "items": [
+            {
+                "type": "Feature",
+                "properties": { "coreNOTAMData": { "notam": {
+                    "id": "NOTAM_1_12345", "number": "10/123", "location": "JFK", "icaoLocation": "KJFK",
+                    "classification": "DOM", "effectiveStart": "2026-10-15T12:00:00.000Z", "effectiveEnd": "2026-10-16T12:00:00.000Z",
+                    "text": "RWY 04L/22R CLSD", "coordinates": "4038N07347W", "radius": "005"
+                } } }
+            },
+            {
+                "type": "Feature",
+                "properties": { "coreNOTAMData": { "notam": {
+                    "id": "NOTAM_1_67890", "number": "10/456", "icaoLocation": "KZNY", "effectiveEnd": "PERM",
+                    "text": "AIRSPACE UAS WI AN AREA DEFINED AS"
+                } } },
+                "geometry": { "type": "GeometryCollection", "geometries": [
+                    { "type": "Polygon", "coordinates": [[[-74.1, 40.5], [-73.9, 40.5], [-73.9, 40.7], [-74.1, 40.5]]] }
+                ] }
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn parses_tfr_areas() {
+        let tfrs = parse_tfrs(TFRS).unwrap();
+        assert_eq!(tfrs.len(), 1);
+        let tfr = &tfrs[0];
+        assert_eq!(tfr.id, "tfr:6/4321-1-FDC-F");
+        assert_eq!(tfr.bounds, BoundingBox { south: 38.7, west: -77.2, north: 39.1, east: -76.8 });
+        let p = &tfr.data["properties"];
+        assert_eq!((p["number"].as_str(), p["type"].as_str()), (Some("6/4321"), Some("VIP")));
+        assert_eq!(p["updatedAt"], json!(1791991800000i64));
+        assert!(parse_tfrs("<html>").is_err());
+    }
+
+    #[test]
+    fn parses_notams_with_and_without_geometry() {
+        let (notams, pages) = parse_notams(NOTAMS).unwrap();
+        assert_eq!((notams.len(), pages), (2, 1));
+        let runway = &notams[0];
+        assert_eq!(runway.data["geometry"]["coordinates"], json!([-(73.0 + 47.0 / 60.0), 40.0 + 38.0 / 60.0]));
+        // The 5 NM radius widens the point's box.
+        assert!((runway.bounds.north - runway.bounds.south - 2.0 * 5.0 * METRES_PER_NM / METRES_PER_DEGREE).abs() < 1e-9);
+        assert_eq!(runway.ends, Some(1792152000000));
+        assert_eq!(runway.data["properties"]["title"], json!("KJFK 10/123"));
+        let uas = &notams[1];
+        assert_eq!(uas.ends, None);
+        assert_eq!((uas.bounds.west, uas.bounds.north), (-74.1, 40.7));
+    }
+
+    #[test]
+    fn reads_notam_coordinates_and_queries() {
+        assert_eq!(notam_point("3851N07702W"), Some([-(77.0 + 2.0 / 60.0), 38.0 + 51.0 / 60.0]));
+        assert_eq!(notam_point("335130S1511230E"), Some([151.0 + 12.0 / 60.0 + 30.0 / 3600.0, -(33.0 + 51.0 / 60.0 + 30.0 / 3600.0)]));
+        assert_eq!(notam_point(""), None);
+        assert_eq!(notam_point("9951N07702W"), None);
+
+        let config = FaaConfig {
+            notam_locations: vec![" kjfk".to_string(), String::new()],
+            notam_areas: vec![NotamArea { lat: 38.9, lon: -77.0, radius_nm: 250.0 }],
+            ..FaaConfig::default()
+        };
+        let queries = notam_queries(&config);
+        assert_eq!(queries.len(), 2);
+        assert_eq!(queries[0], [("icaoLocation", "KJFK".to_string())]);
+        assert_eq!(queries[1][2], ("locationRadius", "100".to_string()));
+    }
+}
@@ -0,0 +1,672 @@
+//! Files dropped onto the dashboard. Each one is validated here and routed
+//! by extension:
+//!
+//! - `.env`: supported API keys are merged into the secrets vault; other
+//!   variables are ignored and only their names are reported.
+//! - `.geojson` / `.kml`: parsed into a GeoJSON `FeatureCollection` (KML is
+//!   converted) for the dashboard to draw as a map overlay.
+//! - `.wmconfig`: a settings bundle, applied once the user confirms. Its
+//!   `desktop` section is merged into `desktop-config.json`, limited to
+//!   `IMPORTABLE_SECTIONS`; its `preferences` (dashboard localStorage keys)
+//!   are handed to the dashboard to apply.
+//! - Images (`.jpg`, `.png`, `.tif`, `.webp`, `.heic`): their metadata (see
+//!   `image_metadata`), so the map can jump to where a photo was taken.
+//!
+//! Every file produces one `file-imported` event, including the ones that
+//! were rejected, so the dashboard can report the outcome.
+//!
+//! `.wmconfig` is also registered as a file type, so bundles can be opened
+//! from the file manager: on macOS as an `Opened` event, elsewhere as a
+//! command-line argument to the first or a second instance. Those imports
+//! wait in `PendingImports` until the dashboard is listening.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult};
+
+use crate::config::{DesktopConfig, DesktopConfigState};
+use crate::image_metadata::ImageMetadata;
+
+pub const FILE_IMPORTED_EVENT: &str = "file-imported";
+
+/// Marker and version a `.wmconfig` bundle must carry.
+const BUNDLE_FORMAT_KEY: &str = "worldmonitor_config";
+const BUNDLE_FORMAT_VERSION: u64 = 1;
+/// Only the dashboard's own localStorage keys may be set from a bundle.
+const PREFERENCE_KEY_PREFIX: &str = "worldmonitor-";
+const IMPORT_LABEL: &str = "Import";
+/// `desktop` sections a bundle may set. The others point the app at hosts,
+/// endpoints or folders (MQTT broker, LAN relay, error reporting, exports,
+/// allowed link hosts, receivers), so a bundle from someone else could
+/// redirect data or credentials; those are only changed in Settings. For the
+/// same reason the tile server (`tiles.url`, `tiles.subdomains`) and
+/// `sounds.files` are refused within their sections.
+const IMPORTABLE_SECTIONS: [&str; 15] = [
+    "appearance",
+    "launch",
+    "tray",
+    "ticker",
+    "pdf",
+    "idle",
+    "power",
+    "focus_mode",
+    "shortcuts",
+    "tiles",
+    "sounds",
+    "speech",
+    "digest",
+    "hazards",
+    "usgs",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FileKind {
+    Env,
+    GeoJson,
+    Kml,
+    Config,
+    Image,
+}
+
+impl FileKind {
+    fn of(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        // `.env` has no extension as far as `Path` is concerned.
+        if name == ".env" || name.ends_with(".env") {
+            return Some(FileKind::Env);
+        }
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "geojson" => Some(FileKind::GeoJson),
+            "kml" => Some(FileKind::Kml),
+            "wmconfig" => Some(FileKind::Config),
+            "jpg" | "jpeg" | "png" | "tif" | "tiff" | "webp" | "heic" | "heif" => Some(FileKind::Image),
+            _ => None,
+        }
+    }
+
+    fn max_bytes(self) -> u64 {
+        match self {
+            FileKind::Env => 64 * 1024,
+            FileKind::Config => 1024 * 1024,
+            FileKind::GeoJson | FileKind::Kml => 20 * 1024 * 1024,
+            FileKind::Image => 50 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverlayFormat {
+    GeoJson,
+    Kml,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ImportOutcome {
+    Secrets {
+        imported: Vec<String>,
+        ignored: Vec<String>,
+    },
+    Overlay {
+        name: String,
+        format: OverlayFormat,
+        features: usize,
+        geojson: Value,
+    },
+    Config {
+        desktop: bool,
+        preferences: Map<String, Value>,
+    },
+    Image {
+        metadata: ImageMetadata,
+    },
+    Rejected {
+        error: String,
+    },
+}
+
+/// Payload of `file-imported`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FileImport {
+    pub file: String,
+    #[serde(flatten)]
+    pub outcome: ImportOutcome,
+}
+
+/// Opened-file imports waiting for the dashboard. Once it has drained the
+/// queue with `take_pending_imports`, later ones are emitted directly.
+#[derive(Default)]
+pub struct PendingImports(Mutex<PendingQueue>);
+
+#[derive(Default)]
+struct PendingQueue {
+    dashboard_ready: bool,
+    imports: Vec<FileImport>,
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn import_file(app: &AppHandle, path: &Path) -> FileImport {
+    let file = file_name(path);
+    let outcome = import(app, path).unwrap_or_else(|error| ImportOutcome::Rejected { error });
+    match &outcome {
+        ImportOutcome::Rejected { error } => tracing::warn!(target: "app", %file, "file import rejected: {error}"),
+        _ => tracing::info!(target: "app", %file, "file imported"),
+    }
+    FileImport { file, outcome }
+}
+
+fn emit(app: &AppHandle, import: FileImport) {
+    if let Err(err) = app.emit(FILE_IMPORTED_EVENT, import) {
+        tracing::warn!(target: "app", "failed to emit {FILE_IMPORTED_EVENT}: {err}");
+    }
+}
+
+fn spawn_import(name: &str, job: impl FnOnce() + Send + 'static) {
+    if let Err(err) = std::thread::Builder::new().name(name.into()).spawn(job) {
+        tracing::error!(target: "app", "failed to start file import: {err}");
+    }
+}
+
+/// Handle a drop onto `label`. Only the dashboard accepts files; reading and
+/// parsing happen off the event loop. Settings bundles are confirmed first,
+/// as when they are opened from the file manager.
+pub fn on_drop(app: &AppHandle, label: &str, paths: &[PathBuf]) {
+    if label != "main" || paths.is_empty() {
+        return;
+    }
+    let app = app.clone();
+    let paths = paths.to_vec();
+    spawn_import("file-import", move || {
+        for path in paths {
+            if FileKind::of(&path) == Some(FileKind::Config) && !confirm_bundle(&app, &file_name(&path)) {
+                tracing::info!(target: "app", file = %file_name(&path), "settings bundle import cancelled");
+                continue;
+            }
+            emit(&app, import_file(&app, &path));
+        }
+    });
+}
+
+/// The settings bundles among command-line arguments, resolved against the
+/// directory they were given in. Other arguments (flags, deep links) are
+/// left to their own handlers.
+pub fn bundle_paths(args: &[String], cwd: &Path) -> Vec<PathBuf> {
+    args.iter()
+        .map(PathBuf::from)
+        .filter(|path| FileKind::of(path) == Some(FileKind::Config))
+        .map(|path| if path.is_absolute() { path } else { cwd.join(path) })
+        .collect()
+}
+
+/// Ask before applying a dropped or opened bundle. Blocks until answered;
+/// call off the main thread.
+fn confirm_bundle(app: &AppHandle, file: &str) -> bool {
+    let mut dialog = app
+        .dialog()
+        .message(format!(
+            "Import settings from {file}?\n\nThis changes your World Monitor settings and reloads the dashboard."
+        ))
+        .title("Import Settings")
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::OkCancelCustom(IMPORT_LABEL.to_string(), "Cancel".to_string()));
+    if let Some(window) = app.get_webview_window("main") {
+        dialog = dialog.parent(&window);
+    }
+    match dialog.blocking_show_with_result() {
+        MessageDialogResult::Ok => true,
+        MessageDialogResult::Custom(label) => label == IMPORT_LABEL,
+        _ => false,
+    }
+}
+
+/// Import settings bundles opened with the app, after confirmation.
+pub fn on_open(app: &AppHandle, paths: Vec<PathBuf>) {
+    let paths: Vec<PathBuf> = paths
+        .into_iter()
+        .filter(|path| FileKind::of(path) == Some(FileKind::Config))
+        .collect();
+    if paths.is_empty() {
+        return;
+    }
+    crate::tray::show_dashboard(app);
+    let app = app.clone();
+    spawn_import("bundle-import", move || {
+        for path in paths {
+            let file = file_name(&path);
+            tracing::info!(target: "app", %file, "settings bundle opened");
+            if !confirm_bundle(&app, &file) {
+                tracing::info!(target: "app", %file, "settings bundle import cancelled");
+                continue;
+            }
+            let import = import_file(&app, &path);
+            let Some(pending) = app.try_state::<PendingImports>() else {
+                emit(&app, import);
+                continue;
+            };
+            let mut queue = pending.0.lock().unwrap_or_else(|e| e.into_inner());
+            if queue.dashboard_ready {
+                drop(queue);
+                emit(&app, import);
+            } else {
+                queue.imports.push(import);
+            }
+        }
+    });
+}
+
+/// Import bundles passed on the command line that launched the app. A
+/// restart relaunches with the same arguments, so it skips them.
+pub fn open_launch_files(app: &AppHandle) {
+    if crate::restart::is_resumed(app) {
+        return;
+    }
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let cwd = std::env::current_dir().unwrap_or_default();
+    on_open(app, bundle_paths(&args, &cwd));
+}
+
+/// Imports still waiting for the dashboard, to carry across a restart.
+pub fn pending(app: &AppHandle) -> Vec<FileImport> {
+    app.try_state::<PendingImports>()
+        .map(|pending| pending.0.lock().unwrap_or_else(|e| e.into_inner()).imports.clone())
+        .unwrap_or_default()
+}
+
+/// Queue imports carried across a restart.
+pub fn restore_pending(app: &AppHandle, imports: Vec<FileImport>) {
+    if let Some(pending) = app.try_state::<PendingImports>() {
+        pending.0.lock().unwrap_or_else(|e| e.into_inner()).imports.extend(imports);
+    }
+}
+
+/// Drain the imports that finished before the dashboard was listening.
+#[tauri::command]
+pub fn take_pending_imports(
+    webview: Webview,
+    pending: tauri::State<'_, PendingImports>,
+) -> Result<Vec<FileImport>, String> {
+    crate::metrics::of(&webview).observe("take_pending_imports", || {
+        crate::require_trusted_window(webview.label())?;
+        if webview.label() != "main" {
+            return Ok(Vec::new());
+        }
+        let mut queue = pending.0.lock().unwrap_or_else(|e| e.into_inner());
+        queue.dashboard_ready = true;
+        Ok(std::mem::take(&mut queue.imports))
+    })
+}
+
+fn import(app: &AppHandle, path: &Path) -> Result<ImportOutcome, String> {
+    let kind = FileKind::of(path)
+        .ok_or("Unsupported file type; drop a .env, .geojson, .kml or .wmconfig file, or an image")?;
+    let size = fs::metadata(path).map_err(|e| format!("Failed to read file: {e}"))?.len();
+    if size > kind.max_bytes() {
+        return Err(format!("File is too large ({size} bytes, limit {})", kind.max_bytes()));
+    }
+    let text = || fs::read_to_string(path).map_err(|e| format!("Failed to read file: {e}"));
+    match kind {
+        FileKind::Env => import_secrets(app, &text()?),
+        FileKind::GeoJson => overlay(path, OverlayFormat::GeoJson, geojson_collection(&text()?)?),
+        FileKind::Kml => overlay(path, OverlayFormat::Kml, kml_to_geojson(&text()?)?),
+        FileKind::Config => import_config(app, &text()?),
+        FileKind::Image => {
+            let bytes = fs::read(path).map_err(|e| format!("Failed to read file: {e}"))?;
+            let metadata = crate::image_metadata::read(&bytes)?;
+            Ok(ImportOutcome::Image { metadata })
+        }
+    }
+}
+
+fn import_secrets(app: &AppHandle, text: &str) -> Result<ImportOutcome, String> {
+    let (supported, ignored): (Vec<_>, Vec<_>) = parse_env(text)
+        .into_iter()
+        .partition(|(key, _)| crate::SUPPORTED_SECRET_KEYS.contains(&key.as_str()));
+    let entries: HashMap<String, String> = supported.into_iter().filter(|(_, value)| !value.is_empty()).collect();
+    if entries.is_empty() {
+        return Err("No supported API keys found in file".to_string());
+    }
+    app.state::<crate::SecretsCache>().import(&entries)?;
+    let mut imported: Vec<String> = entries.into_keys().collect();
+    imported.sort();
+    tracing::info!(target: "secrets", count = imported.len(), "imported secrets from .env file");
+    Ok(ImportOutcome::Secrets {
+        imported,
+        ignored: ignored.into_iter().map(|(key, _)| key).collect(),
+    })
+}
+
+/// `KEY=value` pairs of a dotenv file, in file order. Accepts `export`
+/// prefixes, quoted values, and `#` comments.
+fn parse_env(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let (key, value) = line.split_once('=')?;
+            let key = key.trim();
+            if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return None;
+            }
+            let value = value.trim();
+            let value = match value.chars().next() {
+                Some(quote @ ('"' | '\'')) => value[1..].split(quote).next().unwrap_or_default(),
+                _ => value.split(" #").next().unwrap_or_default().trim_end(),
+            };
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+fn overlay(path: &Path, format: OverlayFormat, geojson: Value) -> Result<ImportOutcome, String> {
+    let features = geojson["features"].as_array().map_or(0, Vec::len);
+    if features == 0 {
+        return Err("File contains no map features".to_string());
+    }
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    Ok(ImportOutcome::Overlay {
+        name,
+        format,
+        features,
+        geojson,
+    })
+}
+
+const GEOMETRY_TYPES: [&str; 7] = [
+    "Point",
+    "MultiPoint",
+    "LineString",
+    "MultiLineString",
+    "Polygon",
+    "MultiPolygon",
+    "GeometryCollection",
+];
+
+/// Parse GeoJSON and normalize it to a `FeatureCollection`.
+fn geojson_collection(text: &str) -> Result<Value, String> {
+    let value: Value = serde_json::from_str(text).map_err(|e| format!("Invalid GeoJSON: {e}"))?;
+    let features = match value["type"].as_str() {
+        Some("FeatureCollection") => value["features"]
+            .as_array()
+            .cloned()
+            .ok_or("Invalid GeoJSON: FeatureCollection without features")?,
+        Some("Feature") => vec![value],
+        Some(kind) if GEOMETRY_TYPES.contains(&kind) => {
+            vec![json!({ "type": "Feature", "properties": {}, "geometry": value })]
+        }
+        _ => return Err("Invalid GeoJSON: not a feature, collection or geometry".to_string()),
+    };
+    for feature in &features {
+        let geometry_ok = match &feature["geometry"] {
+            Value::Null => true,
+            geometry => geometry["type"].as_str().is_some_and(|kind| GEOMETRY_TYPES.contains(&kind)),
+        };
+        if feature["type"] != "Feature" || !geometry_ok {
+            return Err("Invalid GeoJSON: malformed feature".to_string());
+        }
+    }
+    Ok(json!({ "type": "FeatureCollection", "features": features }))
+}
+
+/// Convert a KML document's placemarks (points, lines, polygons, and multi
+/// geometries of those) into a GeoJSON `FeatureCollection`.
+fn kml_to_geojson(text: &str) -> Result<Value, String> {
+    let document = roxmltree::Document::parse(text).map_err(|e| format!("Invalid KML: {e}"))?;
+    if document.root_element().tag_name().name() != "kml" {
+        return Err("Invalid KML: missing <kml> root".to_string());
+    }
+    let features: Vec<Value> = document
+        .descendants()
+        .filter(|node| is_kml(node, "Placemark"))
+        .filter_map(|placemark| {
+            let geometry = placemark.children().find_map(|child| kml_geometry(&child))?;
+            let mut properties = Map::new();
+            for field in ["name", "description"] {
+                if let Some(text) = kml_child(&placemark, field).and_then(|node| node.text()) {
+                    properties.insert(field.to_string(), Value::String(text.trim().to_string()));
+                }
+            }
+            Some(json!({ "type": "Feature", "properties": properties, "geometry": geometry }))
+        })
+        .collect();
+    Ok(json!({ "type": "FeatureCollection", "features": features }))
+}
+
+fn is_kml(node: &roxmltree::Node, name: &str) -> bool {
+    node.is_element() && node.tag_name().name() == name
+}
+
+fn kml_child<'a, 'input>(node: &roxmltree::Node<'a, 'input>, name: &str) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|child| is_kml(child, name))
+}
+
+fn kml_coordinates(node: &roxmltree::Node) -> Option<Vec<Value>> {
+    let text = kml_child(node, "coordinates")?.text()?;
+    let positions: Option<Vec<Value>> = text
+        .split_whitespace()
+        .map(|tuple| {
+            let mut parts = tuple.split(',').map(|part| part.parse::<f64>().ok());
+            let lon = parts.next()??;
+            let lat = parts.next()??;
+            ((-180.0..=180.0).contains(&lon) && (-90.0..=90.0).contains(&lat)).then(|| json!([lon, lat]))
+        })
+        .collect();
+    positions.filter(|positions| !positions.is_empty())
+}
+
+fn kml_geometry(node: &roxmltree::Node) -> Option<Value> {
+    if !node.is_element() {
+        return None;
+    }
+    match node.tag_name().name() {
+        "Point" => {
+            let position = kml_coordinates(node)?.into_iter().next()?;
+            Some(json!({ "type": "Point", "coordinates": position }))
+        }
+        "LineString" => Some(json!({ "type": "LineString", "coordinates": kml_coordinates(node)? })),
+        "Polygon" => {
+            let ring = |boundary: roxmltree::Node| kml_child(&boundary, "LinearRing").and_then(|ring| kml_coordinates(&ring));
+            let mut rings = vec![ring(kml_child(node, "outerBoundaryIs")?)?];
+            rings.extend(
+                node.children()
+                    .filter(|child| is_kml(child, "innerBoundaryIs"))
+                    .filter_map(ring),
+            );
+            Some(json!({ "type": "Polygon", "coordinates": rings }))
+        }
+        "MultiGeometry" => {
+            let geometries: Vec<Value> = node.children().filter_map(|child| kml_geometry(&child)).collect();
+            (!geometries.is_empty()).then(|| json!({ "type": "GeometryCollection", "geometries": geometries }))
+        }
+        _ => None,
+    }
+}
+
+fn import_config(app: &AppHandle, text: &str) -> Result<ImportOutcome, String> {
+    let bundle: Value = serde_json::from_str(text).map_err(|e| format!("Invalid settings bundle: {e}"))?;
+    if bundle[BUNDLE_FORMAT_KEY].as_u64() != Some(BUNDLE_FORMAT_VERSION) {
+        return Err(format!(
+            "Not a World Monitor settings bundle (expected \"{BUNDLE_FORMAT_KEY}\": {BUNDLE_FORMAT_VERSION})"
+        ));
+    }
+    let preferences: Map<String, Value> = match &bundle["preferences"] {
+        Value::Null => Map::new(),
+        Value::Object(preferences) => preferences
+            .iter()
+            .filter(|(key, _)| key.starts_with(PREFERENCE_KEY_PREFIX))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect(),
+        _ => return Err("Invalid settings bundle: preferences must be an object".to_string()),
+    };
+    let desktop = match &bundle["desktop"] {
+        Value::Null => None,
+        Value::Object(_) => {
+            let config = app.state::<DesktopConfigState>();
+            Some(merge_desktop_config(&config.snapshot(), &bundle["desktop"])?)
+        }
+        _ => return Err("Invalid settings bundle: desktop must be an object".to_string()),
+    };
+    if desktop.is_none() && preferences.is_empty() {
+        return Err("Settings bundle contains no settings".to_string());
+    }
+    if let Some(desktop) = &desktop {
+        app.state::<DesktopConfigState>().update(|config| *config = desktop.clone())?;
+    }
+    Ok(ImportOutcome::Config {
+        desktop: desktop.is_some(),
+        preferences,
+    })
+}
+
+/// `current` with the bundle's `desktop` section laid over it, so a bundle
+/// only needs the settings it changes. Only `IMPORTABLE_SECTIONS` may be
+/// set, and each goes through the checks its settings command applies.
+fn merge_desktop_config(current: &DesktopConfig, overrides: &Value) -> Result<DesktopConfig, String> {
+    fn merge(base: &mut Value, overrides: &Value) {
+        match (base, overrides) {
+            (Value::Object(base), Value::Object(overrides)) => {
+                for (key, value) in overrides {
+                    merge(base.entry(key.clone()).or_insert(Value::Null), value);
+                }
+            }
+            (base, value) => *base = value.clone(),
+        }
+    }
+    if let Some(section) = overrides
+        .as_object()
+        .and_then(|sections| sections.keys().find(|key| !IMPORTABLE_SECTIONS.contains(&key.as_str())))
+    {
+        return Err(format!("Settings bundles cannot change \"{section}\"; change it in Settings instead"));
+    }
+    let mut merged = serde_json::to_value(current).map_err(|e| format!("Failed to read desktop config: {e}"))?;
+    merge(&mut merged, overrides);
+    let mut merged: DesktopConfig =
+        serde_json::from_value(merged).map_err(|e| format!("Invalid settings bundle: {e}"))?;
+    let invalid = |e: String| format!("Invalid settings bundle: {e}");
+    if merged.tiles.url != current.tiles.url || merged.tiles.subdomains != current.tiles.subdomains {
+        return Err(invalid("the tile server can only be chosen in Settings".to_string()));
+    }
+    if merged.tiles != current.tiles {
+        merged.tiles = crate::tile_cache::validate(merged.tiles).map_err(invalid)?;
+    }
+    if let Some(shortcut) = &merged.shortcuts.toggle_window {
+        crate::shortcuts::parse(shortcut).map_err(invalid)?;
+    }
+    if merged.sounds.files != current.sounds.files {
+        return Err(invalid("sound files can only be chosen in Settings".to_string()));
+    }
+    merged.sounds.volume = crate::sounds::clamp_volume(merged.sounds.volume).map_err(invalid)?;
+    merged.power.battery_refresh_multiplier = crate::power::clamp_multiplier(merged.power.battery_refresh_multiplier);
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod file_import_tests {
+    use std::path::{Path, PathBuf};
+
+    use super::{bundle_paths, geojson_collection, kml_to_geojson, merge_desktop_config, parse_env, FileKind};
+    use crate::config::{DesktopConfig, LaunchWindow};
+
+    #[test]
+    fn classifies_by_extension() {
+        assert_eq!(FileKind::of(Path::new("/tmp/.env")), Some(FileKind::Env));
+        assert_eq!(FileKind::of(Path::new("keys.production.env")), Some(FileKind::Env));
+        assert_eq!(FileKind::of(Path::new("Zones.GeoJSON")), Some(FileKind::GeoJson));
+        assert_eq!(FileKind::of(Path::new("route.kml")), Some(FileKind::Kml));
+        assert_eq!(FileKind::of(Path::new("desk.wmconfig")), Some(FileKind::Config));
+        assert_eq!(FileKind::of(Path::new("IMG_0042.JPG")), Some(FileKind::Image));
+        assert_eq!(FileKind::of(Path::new("notes.txt")), None);
+    }
+
+    #[test]
+    fn picks_bundles_out_of_arguments() {
+        let args = ["--hidden", "desk.wmconfig", "/tmp/Ops.WMCONFIG", "worldmonitor://map", "keys.env"].map(String::from);
+        assert_eq!(
+            bundle_paths(&args, Path::new("/home/me")),
+            vec![PathBuf::from("/home/me/desk.wmconfig"), PathBuf::from("/tmp/Ops.WMCONFIG")]
+        );
+    }
+
+    #[test]
+    fn parses_dotenv_lines() {
+        let env = "# keys\nexport GROQ_API_KEY=\"abc 123\"\nOPENROUTER_API_KEY=xyz # personal\nEMPTY=\nbad line\n";
+        assert_eq!(
+            parse_env(env),
+            vec![
+                ("GROQ_API_KEY".to_string(), "abc 123".to_string()),
+                ("OPENROUTER_API_KEY".to_string(), "xyz".to_string()),
+                ("EMPTY".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn normalizes_geojson_and_rejects_garbage() {
+        let point = geojson_collection(r#"{"type":"Point","coordinates":[2.35,48.85]}"#).unwrap();
+        assert_eq!(point["features"][0]["geometry"]["type"], "Point");
+        assert!(geojson_collection(r#"{"type":"FeatureCollection","features":[{"type":"Blob"}]}"#).is_err());
+        assert!(geojson_collection("[]").is_err());
+    }
+
+    #[test]
+    fn converts_kml_placemarks() {
+        let kml = r#"<?xml version="1.0"?>
+            <kml xmlns="http://www.opengis.net/kml/2.2"><Document>
+              <Placemark><name>Paris</name><Point><coordinates>2.35,48.85,0</coordinates></Point></Placemark>
+              <Placemark><Polygon><outerBoundaryIs><LinearRing>
+                <coordinates>0,0 1,0 1,1 0,0</coordinates>
+              </LinearRing></outerBoundaryIs></Polygon></Placemark>
+            </Document></kml>"#;
+        let collection = kml_to_geojson(kml).unwrap();
+        let features = collection["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2);
+        assert_eq!(features[0]["properties"]["name"], "Paris");
+        assert_eq!(features[0]["geometry"]["coordinates"], serde_json::json!([2.35, 48.85]));
+        assert_eq!(features[1]["geometry"]["coordinates"][0].as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn merges_partial_desktop_config() {
+        let merged = merge_desktop_config(
+            &DesktopConfig::default(),
+            &serde_json::json!({ "launch": { "window": "tray" } }),
+        )
+        .unwrap();
+        assert_eq!(merged.launch.window, LaunchWindow::Tray);
+        assert!(merge_desktop_config(&DesktopConfig::default(), &serde_json::json!({ "launch": { "window": 3 } })).is_err());
+    }
+
+    #[test]
+    fn keeps_bundles_to_safe_validated_sections() {
+        let current = DesktopConfig::default();
+        let merge = |overrides| merge_desktop_config(&current, &overrides);
+        assert!(merge(serde_json::json!({ "mqtt": { "host": "broker.example", "tls": false } })).is_err());
+        assert!(merge(serde_json::json!({ "relay": { "lan": true } })).is_err());
+        assert!(merge(serde_json::json!({ "error_reporting": { "endpoint": "https://collect.example" } })).is_err());
+        assert!(merge(serde_json::json!({ "tiles": { "url": "file:///etc/{z}/{x}/{y}" } })).is_err());
+        assert!(merge(serde_json::json!({ "tiles": { "url": "https://tiles.example/{z}/{x}/{y}.png" } })).is_err());
+        assert!(merge(serde_json::json!({ "tiles": { "subdomains": ["evil"] } })).is_err());
+        assert!(merge(serde_json::json!({ "tiles": { "max_age_days": 7 } })).is_ok());
+        assert!(merge(serde_json::json!({ "sounds": { "files": { "critical": "/tmp/a.wav" } } })).is_err());
+        let merged = merge(serde_json::json!({ "sounds": { "volume": 4.0 }, "power": { "battery_refresh_multiplier": 0 } })).unwrap();
+        assert_eq!(merged.sounds.volume, 1.0);
+        assert_eq!(merged.power.battery_refresh_multiplier, 1);
+    }
+}
@@ -0,0 +1,210 @@
+//! The OS Do Not Disturb / Focus state. While it is on, `notifications`
+//! holds back native notifications and the dashboard mutes its alert sounds;
+//! alert badges (see `badging`) still update, silently. Critical alerts break
+//! through unless `focus_mode.critical_breaks_through` is turned off.
+//!
+//! What each platform exposes:
+//!
+//! - macOS: manually enabled Focus modes, from the Do Not Disturb
+//!   assertions database. Scheduled Focus is not recorded there, and newer
+//!   releases may need Full Disk Access to read it.
+//! - Windows: the shell's notification state, which covers presentation
+//!   mode, full-screen apps and Focus assist's quiet hours.
+//! - Linux: GNOME's Do Not Disturb (`show-banners`).
+//!
+//! Where the state cannot be read, notifications go out as usual.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+use crate::config::DesktopConfigState;
+
+pub const FOCUS_MODE_EVENT: &str = "app://focus-mode";
+
+/// People toggle Do Not Disturb by hand and expect it to take effect.
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct FocusModeStatus {
+    /// Do Not Disturb or a Focus mode is on.
+    pub active: bool,
+    /// Critical alerts still notify and play sounds while it is on.
+    pub critical_breaks_through: bool,
+}
+
+#[derive(Default)]
+pub struct FocusModeState {
+    active: Mutex<Option<bool>>,
+}
+
+fn active(app: &AppHandle) -> bool {
+    app.try_state::<FocusModeState>()
+        .and_then(|state| *state.active.lock().unwrap_or_else(|e| e.into_inner()))
+        .or_else(platform::read)
+        .unwrap_or(false)
+}
+
+pub fn current(app: &AppHandle) -> FocusModeStatus {
+    FocusModeStatus {
+        active: active(app),
+        critical_breaks_through: app.state::<DesktopConfigState>().snapshot().focus_mode.critical_breaks_through,
+    }
+}
+
+/// Whether an alert may interrupt the user with a banner or sound.
+pub fn allows(app: &AppHandle, critical: bool) -> bool {
+    let status = current(app);
+    !status.active || (critical && status.critical_breaks_through)
+}
+
+fn run(app: AppHandle) {
+    let state = app.state::<FocusModeState>();
+    loop {
+        let active = platform::read();
+        let previous = std::mem::replace(&mut *state.active.lock().unwrap_or_else(|e| e.into_inner()), active);
+        if previous.is_some() && previous != active {
+            tracing::info!(target: "app", active = active.unwrap_or(false), "focus mode changed");
+            let _ = app.emit(FOCUS_MODE_EVENT, current(&app));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Start the focus mode monitor thread.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    if let Err(e) = std::thread::Builder::new()
+        .name("focus-mode-monitor".into())
+        .spawn(move || run(app))
+    {
+        tracing::warn!(target: "app", "failed to start focus mode monitor: {e}");
+    }
+}
+
+/// Focus is on when any store in `Assertions.json` holds an assertion.
+#[cfg_attr(not(any(target_os = "macos", test)), allow(dead_code))]
+fn parse_assertions(json: &str) -> Option<bool> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    let stores = value.get("data")?.as_array()?;
+    Some(stores.iter().any(|store| {
+        store
+            .get("storeAssertionRecords")
+            .and_then(|records| records.as_array())
+            .is_some_and(|records| !records.is_empty())
+    }))
+}
+
+/// Do Not Disturb is on when GNOME stops showing banners.
+#[cfg_attr(not(any(target_os = "linux", test)), allow(dead_code))]
+fn parse_show_banners(output: &str) -> Option<bool> {
+    match output.trim() {
+        "true" => Some(false),
+        "false" => Some(true),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::path::PathBuf;
+
+    const ASSERTIONS: &str = "Library/DoNotDisturb/DB/Assertions.json";
+
+    pub fn read() -> Option<bool> {
+        let path = PathBuf::from(std::env::var_os("HOME")?).join(ASSERTIONS);
+        super::parse_assertions(&std::fs::read_to_string(path).ok()?)
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use windows::Win32::UI::Shell::{
+        SHQueryUserNotificationState, QUNS_BUSY, QUNS_PRESENTATION_MODE, QUNS_QUIET_TIME, QUNS_RUNNING_D3D_FULL_SCREEN,
+    };
+
+    pub fn read() -> Option<bool> {
+        let state = unsafe { SHQueryUserNotificationState() }.ok()?;
+        Some(matches!(
+            state,
+            QUNS_BUSY | QUNS_PRESENTATION_MODE | QUNS_QUIET_TIME | QUNS_RUNNING_D3D_FULL_SCREEN
+        ))
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::process::Command;
+
+    pub fn read() -> Option<bool> {
+        let output = Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        super::parse_show_banners(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    pub fn read() -> Option<bool> {
+        None
+    }
+}
+
+#[tauri::command]
+pub fn get_focus_mode_status(webview: Webview, app: AppHandle) -> Result<FocusModeStatus, String> {
+    crate::metrics::of(&webview).observe("get_focus_mode_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(current(&app))
+    })
+}
+
+/// Whether critical alerts notify during Do Not Disturb. Returns the stored
+/// value.
+#[tauri::command]
+pub async fn set_critical_breaks_through(
+    webview: Webview,
+    app: AppHandle,
+    enabled: bool,
+) -> Result<bool, String> {
+    crate::metrics::of(&webview)
+        .observe_async("set_critical_breaks_through", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                let config = app.state::<DesktopConfigState>();
+                config.update(|c| c.focus_mode.critical_breaks_through = enabled)?;
+                tracing::info!(target: "app", enabled, "critical alerts during focus mode changed");
+                let _ = app.emit(FOCUS_MODE_EVENT, current(&app));
+                Ok(enabled)
+            })
+            .await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod focus_mode_tests {
+    use super::*;
+
+    #[test]
+    fn reads_macos_assertions() {
+        let on = r#"{"data":[{"storeAssertionRecords":[{"assertionDetails":{"assertionDetailsModeIdentifier":"com.apple.donotdisturb.mode.default"}}]}]}"#;
+        assert_eq!(parse_assertions(on), Some(true));
+        assert_eq!(parse_assertions(r#"{"data":[{}]}"#), Some(false));
+        assert_eq!(parse_assertions(r#"{"data":[{"storeAssertionRecords":[]}]}"#), Some(false));
+        assert_eq!(parse_assertions("not json"), None);
+    }
+
+    #[test]
+    fn reads_gnome_show_banners() {
+        assert_eq!(parse_show_banners("false\n"), Some(true));
+        assert_eq!(parse_show_banners("true\n"), Some(false));
+        assert_eq!(parse_show_banners(""), None);
+    }
+}
@@ -0,0 +1,379 @@
+//! FRED economic series fetched into the local `store` with the stored
+//! `FRED_API_KEY`, so the economic panel works offline and the key never
+//! leaves the shell. The series in `fred.series` are checked at startup and
+//! then every `SYNC_INTERVAL`, or right away with `sync_fred_series`.
+//!
+//! Each check asks for the series' metadata first and only fetches the
+//! latest `fred.observations` observations when FRED's `last_updated` has
+//! moved on. A series with new or revised observations counts as changed,
+//! and the changed series go out together as `fred://updated`, shaped like
+//! `get_fred_series` returns them: timestamps and values as parallel arrays,
+//! oldest first, plus the latest value and its change from the one before.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+use tokio::sync::watch;
+
+use crate::config::{DesktopConfigState, FredConfig};
+use crate::store::{with_store, FredMeta};
+
+pub const UPDATED_EVENT: &str = "fred://updated";
+
+const API_URL: &str = "https://api.stlouisfed.org/fred";
+const KEY_SECRET: &str = "FRED_API_KEY";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const SYNC_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const MAX_OBSERVATIONS: usize = 1_000;
+const DEFAULT_LIMIT: usize = 120;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct FredSyncStatus {
+    pub syncing: bool,
+    /// Unix seconds of the last completed sync.
+    pub last_sync: Option<u64>,
+    /// Series that changed in the last completed sync.
+    pub changed: Vec<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct FredState {
+    /// Bumped to request a sync now.
+    trigger: Mutex<Option<watch::Sender<u64>>>,
+    status: Mutex<FredSyncStatus>,
+}
+
+fn update_status(app: &AppHandle, f: impl FnOnce(&mut FredSyncStatus)) -> FredSyncStatus {
+    let state = app.state::<FredState>();
+    let mut status = state.status.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut status);
+    status.clone()
+}
+
+/// A stored series, ready to plot.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FredSeriesData {
+    #[serde(flatten)]
+    pub meta: FredMeta,
+    /// Observation dates as milliseconds since the epoch (UTC midnight).
+    pub timestamps: Vec<i64>,
+    pub values: Vec<f64>,
+    /// `YYYY-MM-DD` of the latest observation.
+    pub date: Option<String>,
+    pub latest: Option<f64>,
+    pub previous: Option<f64>,
+    pub change: Option<f64>,
+    pub change_percent: Option<f64>,
+}
+
+fn series_data(meta: FredMeta, observations: Vec<(String, f64)>) -> FredSeriesData {
+    let latest = observations.last().map(|(_, v)| *v);
+    let previous = observations.len().checked_sub(2).map(|i| observations[i].1);
+    let change = latest.zip(previous).map(|(l, p)| l - p);
+    let change_percent = change.zip(previous).filter(|(_, p)| *p != 0.0).map(|(c, p)| c / p.abs() * 100.0);
+    let (timestamps, values) = observations
+        .iter()
+        .filter_map(|(date, value)| {
+            let time = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?.and_time(chrono::NaiveTime::MIN);
+            Some((time.and_utc().timestamp_millis(), *value))
+        })
+        .unzip();
+    FredSeriesData {
+        date: observations.last().map(|(d, _)| d.clone()),
+        meta,
+        timestamps,
+        values,
+        latest,
+        previous,
+        change,
+        change_percent,
+    }
+}
+
+#[derive(Deserialize)]
+struct SeriesResponse {
+    seriess: Vec<SeriesInfo>,
+}
+
+#[derive(Deserialize)]
+struct SeriesInfo {
+    id: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    units: String,
+    #[serde(default)]
+    frequency: String,
+    #[serde(default)]
+    last_updated: String,
+}
+
+#[derive(Deserialize)]
+struct ObservationsResponse {
+    observations: Vec<Observation>,
+}
+
+#[derive(Deserialize)]
+struct Observation {
+    date: String,
+    /// A number, or `.` where there is no value.
+    value: String,
+}
+
+fn parse_meta(text: &str) -> Result<FredMeta, String> {
+    let response: SeriesResponse = serde_json::from_str(text).map_err(|e| format!("invalid series response: {e}"))?;
+    let info = response.seriess.into_iter().next().ok_or("no such series")?;
+    Ok(FredMeta {
+        id: info.id,
+        title: info.title,
+        units: info.units,
+        frequency: info.frequency,
+        last_updated: info.last_updated,
+    })
+}
+
+fn parse_observations(text: &str) -> Result<Vec<(String, f64)>, String> {
+    let response: ObservationsResponse =
+        serde_json::from_str(text).map_err(|e| format!("invalid observations response: {e}"))?;
+    Ok(response
+        .observations
+        .into_iter()
+        .filter_map(|o| Some((o.date, o.value.parse::<f64>().ok().filter(|v| v.is_finite())?)))
+        .collect())
+}
+
+async fn get(client: &reqwest::Client, path: &str, key: &str, params: &[(&str, String)]) -> Result<String, String> {
+    let response = client
+        .get(format!("{API_URL}/{path}"))
+        .query(&[("api_key", key), ("file_type", "json")])
+        .query(params)
+        .send()
+        .await
+        // The key is in the URL; keep it out of error messages.
+        .map_err(|e| format!("request failed: {}", e.without_url()))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("HTTP {status}"));
+    }
+    response.text().await.map_err(|e| format!("request failed: {}", e.without_url()))
+}
+
+/// Fetch one series if FRED has updated it. Returns its data if anything
+/// changed.
+async fn sync_series(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    key: &str,
+    id: &str,
+    config: &FredConfig,
+) -> Result<Option<FredSeriesData>, String> {
+    let meta = parse_meta(&get(client, "series", key, &[("series_id", id.to_string())]).await?)?;
+    let stored = with_store(app, {
+        let id = id.to_string();
+        move |store| store.fred_meta(&id)
+    })
+    .await?;
+    if stored.is_some_and(|stored| stored.last_updated == meta.last_updated) {
+        return Ok(None);
+    }
+    let limit = config.observations.clamp(1, MAX_OBSERVATIONS);
+    let text = get(
+        client,
+        "series/observations",
+        key,
+        &[("series_id", id.to_string()), ("sort_order", "desc".to_string()), ("limit", limit.to_string())],
+    )
+    .await?;
+    let observations = parse_observations(&text)?;
+    with_store(app, move |store| {
+        if !store.store_fred_series(&meta, &observations)? {
+            return Ok(None);
+        }
+        Ok(Some(series_data(meta.clone(), store.fred_observations(&meta.id, limit)?)))
+    })
+    .await
+}
+
+/// Check every configured series, returning the changed ones.
+async fn sync(app: &AppHandle, client: &reqwest::Client, key: &str) -> Result<Vec<FredSeriesData>, String> {
+    let config = app.state::<DesktopConfigState>().snapshot().fred;
+    let mut changed = Vec::new();
+    let mut errors = Vec::new();
+    for id in &config.series {
+        match sync_series(app, client, key, id, &config).await {
+            Ok(Some(series)) => changed.push(series),
+            Ok(None) => {}
+            Err(err) => errors.push(format!("{id}: {err}")),
+        }
+    }
+    if !changed.is_empty() {
+        let _ = app.emit(UPDATED_EVENT, &changed);
+    }
+    if errors.is_empty() {
+        Ok(changed)
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+struct Poller {
+    app: AppHandle,
+    client: reqwest::Client,
+}
+
+impl crate::poll::Poller for Poller {
+    async fn round(&mut self) -> Duration {
+        let app = &self.app;
+        match app.state::<crate::SecretsCache>().get(KEY_SECRET) {
+            Some(key) => {
+                update_status(app, |status| status.syncing = true);
+                let result = sync(app, &self.client, &key).await;
+                let status = update_status(app, |status| {
+                    status.syncing = false;
+                    status.last_sync = Some(crate::unix_timestamp_secs());
+                    match result {
+                        Ok(changed) => {
+                            status.changed = changed.into_iter().map(|s| s.meta.id).collect();
+                            status.error = None;
+                        }
+                        Err(err) => status.error = Some(err),
+                    }
+                });
+                match &status.error {
+                    Some(err) => tracing::warn!(target: "app", "FRED sync failed: {err}"),
+                    None => tracing::debug!(target: "app", changed = ?status.changed, "FRED sync complete"),
+                }
+            }
+            None => {
+                update_status(app, |status| status.error = Some(format!("{KEY_SECRET} is not set")));
+            }
+        }
+        SYNC_INTERVAL
+    }
+}
+
+async fn run(app: AppHandle, trigger: watch::Receiver<u64>) {
+    let client = match reqwest::Client::builder().use_native_tls().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!(target: "app", "FRED sync unavailable: {err}");
+            return;
+        }
+    };
+    let poller = Poller {
+        app: app.clone(),
+        client,
+    };
+    crate::poll::run_triggered(&app, trigger, poller).await;
+}
+
+/// Start the sync task; it syncs right away if a key is stored.
+pub fn start(app: &AppHandle) {
+    let (sender, receiver) = watch::channel(0);
+    *app.state::<FredState>().trigger.lock().unwrap_or_else(|e| e.into_inner()) = Some(sender);
+    tauri::async_runtime::spawn(run(app.clone(), receiver));
+}
+
+/// Sync now, e.g. right after the key was added. Returns at once.
+#[tauri::command]
+pub fn sync_fred_series(webview: Webview, state: tauri::State<'_, FredState>) -> Result<FredSyncStatus, String> {
+    crate::metrics::of(&webview).observe("sync_fred_series", || {
+        crate::require_trusted_window(webview.label())?;
+        let status = state.status.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        if !status.syncing {
+            if let Some(trigger) = state.trigger.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+                trigger.send_modify(|n| *n += 1);
+            }
+        }
+        Ok(status)
+    })
+}
+
+#[tauri::command]
+pub fn get_fred_sync_status(webview: Webview, state: tauri::State<'_, FredState>) -> Result<FredSyncStatus, String> {
+    crate::metrics::of(&webview).observe("get_fred_sync_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.status.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+/// Stored series (the configured ones when `ids` is empty), each with its
+/// latest `limit` observations; series never fetched are left out.
+#[tauri::command]
+pub async fn get_fred_series(
+    webview: Webview,
+    app: AppHandle,
+    ids: Option<Vec<String>>,
+    limit: Option<usize>,
+) -> Result<Vec<FredSeriesData>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("get_fred_series", async move {
+            crate::require_trusted_window(webview.label())?;
+            let ids = ids
+                .filter(|ids| !ids.is_empty())
+                .unwrap_or_else(|| app.state::<DesktopConfigState>().snapshot().fred.series);
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_OBSERVATIONS);
+            with_store(&app, move |store| {
+                let mut series = Vec::new();
+                for id in ids {
+                    if let Some(meta) = store.fred_meta(&id)? {
+                        series.push(series_data(meta, store.fred_observations(&id, limit)?));
+                    }
+                }
+                Ok(series)
+            })
+            .await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod fred_tests {
+    use super::*;
+
+    #[test]
+    fn parses_series_and_observations() {
+        let meta = parse_meta(
+            r#"{"seriess": [{"id": "DGS10", "title": "Market Yield on U.S. Treasury Securities at 10-Year Constant Maturity",
+                "units": "Percent", "frequency": "Daily", "last_updated": "2026-03-13 16:02:04-05"}]}"#,
+        )
+        .unwrap();
+        assert_eq!((meta.id.as_str(), meta.frequency.as_str()), ("DGS10", "Daily"));
+        assert!(parse_meta(r#"{"seriess": []}"#).is_err());
+
+        let observations = parse_observations(
+            r#"{"observations": [
+                {"realtime_start": "2026-03-14", "date": "2026-03-13", "value": "4.21"},
+                {"realtime_start": "2026-03-14", "date": "2026-03-12", "value": "."}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(observations, [("2026-03-13".to_string(), 4.21)]);
+    }
+
+    #[test]
+    fn shapes_series_for_plotting() {
+        let meta = FredMeta {
+            id: "FEDFUNDS".to_string(),
+            title: "Federal Funds Effective Rate".to_string(),
+            units: "Percent".to_string(),
+            frequency: "Monthly".to_string(),
+            last_updated: String::new(),
+        };
+        let data = series_data(meta.clone(), vec![("2026-01-01".to_string(), 4.0), ("2026-02-01".to_string(), 3.5)]);
+        assert_eq!(data.timestamps, [1767225600000, 1769904000000]);
+        assert_eq!(data.values, [4.0, 3.5]);
+        assert_eq!((data.latest, data.previous, data.change), (Some(3.5), Some(4.0), Some(-0.5)));
+        assert_eq!(data.change_percent, Some(-12.5));
+        assert_eq!(data.date.as_deref(), Some("2026-02-01"));
+
+        let single = series_data(meta, vec![("2026-01-01".to_string(), 4.0)]);
+        assert_eq!((single.previous, single.change, single.change_percent), (None, None, None));
+    }
+}
@@ -0,0 +1,428 @@
+//! GDELT 2.0 events ingested into the local `store`. GDELT publishes
+//! an events export every 15 minutes; with `gdelt.enabled` set (the
+//! default), the worker checks `lastupdate.txt` on that schedule, downloads
+//! each export it has not seen yet (catching up on at most `MAX_BACKFILL`
+//! after a gap) and keeps the root events matching the configured filters:
+//!
+//! - `themes`: names from `THEMES` (`protest`, `conflict`, ...), mapped to
+//!   CAMEO event code prefixes, or the prefixes themselves.
+//! - `countries`: FIPS codes of the event location.
+//! - `bounding_boxes`: areas the event location must fall in.
+//!
+//! GDELT records one real-world event once per source article, so events
+//! are stored under a signature of date, event code, actors and location,
+//! and the duplicates collapse into one row. Events that are new to the
+//! store go out in one `gdelt://events` batch per export, for the news and
+//! conflict panels; `query_gdelt_events` reads the store, whose event type
+//! is the matched theme. Events older than `RETENTION_DAYS` are pruned.
+
+use std::io::{Cursor, Read};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+use crate::config::{DesktopConfigState, GdeltConfig};
+use crate::store::{EventQuery, EventStore, StoredEvent};
+
+pub const EVENTS_EVENT: &str = "gdelt://events";
+
+const SOURCE: &str = "gdelt";
+const LAST_UPDATE_URL: &str = "http://data.gdeltproject.org/gdeltv2/lastupdate.txt";
+const EXPORT_SUFFIX: &str = ".export.CSV.zip";
+
+const UPDATE_INTERVAL: Duration = Duration::from_secs(15 * 60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+const ERROR_RETRY: Duration = Duration::from_secs(5 * 60);
+/// Exports fetched at most per check, newest kept, after the app was closed.
+const MAX_BACKFILL: usize = 8;
+const RETENTION_DAYS: u64 = 7;
+
+/// Export columns (tab-separated, no header).
+const COLUMNS: usize = 61;
+const COL_ID: usize = 0;
+const COL_DATE: usize = 1;
+const COL_ACTOR1_CODE: usize = 5;
+const COL_ACTOR1_NAME: usize = 6;
+const COL_ACTOR2_CODE: usize = 15;
+const COL_ACTOR2_NAME: usize = 16;
+const COL_IS_ROOT: usize = 25;
+const COL_EVENT_CODE: usize = 26;
+const COL_QUAD_CLASS: usize = 29;
+const COL_GOLDSTEIN: usize = 30;
+const COL_MENTIONS: usize = 31;
+const COL_SOURCES: usize = 32;
+const COL_TONE: usize = 34;
+const COL_PLACE: usize = 52;
+const COL_COUNTRY: usize = 53;
+const COL_LAT: usize = 56;
+const COL_LON: usize = 57;
+const COL_FEATURE: usize = 58;
+const COL_URL: usize = 60;
+
+/// Theme names and the CAMEO event code prefixes they cover.
+const THEMES: &[(&str, &[&str])] = &[
+    ("protest", &["14"]),
+    ("conflict", &["18", "19", "20"]),
+    ("coercion", &["17"]),
+    ("threat", &["13"]),
+    ("military", &["15"]),
+    ("sanctions", &["163"]),
+];
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct GdeltStatus {
+    pub enabled: bool,
+    /// Timestamp (`YYYYMMDDHHMMSS`) of the last export ingested.
+    pub last_export: Option<String>,
+    pub stored_events: usize,
+    /// New events from the last export.
+    pub added: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct GdeltState {
+    status: Mutex<GdeltStatus>,
+}
+
+fn update_status(app: &AppHandle, f: impl FnOnce(&mut GdeltStatus)) {
+    let state = app.state::<GdeltState>();
+    f(&mut state.status.lock().unwrap_or_else(|e| e.into_inner()));
+}
+
+/// The configured themes as `(event type, code prefix)` pairs.
+fn prefixes(themes: &[String]) -> Vec<(String, String)> {
+    themes
+        .iter()
+        .flat_map(|theme| {
+            let theme = theme.trim().to_lowercase();
+            match THEMES.iter().find(|(name, _)| *name == theme) {
+                Some((name, codes)) => codes.iter().map(|code| (name.to_string(), code.to_string())).collect(),
+                None if !theme.is_empty() && theme.bytes().all(|b| b.is_ascii_digit()) => vec![(theme.clone(), theme)],
+                None => {
+                    tracing::warn!(target: "app", %theme, "unknown GDELT theme");
+                    Vec::new()
+                }
+            }
+        })
+        .collect()
+}
+
+/// Compiled filters.
+struct Filter {
+    prefixes: Vec<(String, String)>,
+    config: GdeltConfig,
+}
+
+impl Filter {
+    fn new(config: GdeltConfig) -> Self {
+        Filter {
+            prefixes: prefixes(&config.themes),
+            config,
+        }
+    }
+
+    /// The event type to store a matching event under.
+    fn event_type(&self, event_code: &str, country: &str, lat: f64, lon: f64) -> Option<String> {
+        if !self.config.countries.is_empty() && !self.config.countries.iter().any(|c| c.eq_ignore_ascii_case(country)) {
+            return None;
+        }
+        if !self.config.bounding_boxes.is_empty() && !self.config.bounding_boxes.iter().any(|b| b.contains(lat, lon)) {
+            return None;
+        }
+        if self.config.themes.is_empty() {
+            return Some(event_code.get(..2).unwrap_or(event_code).to_string());
+        }
+        self.prefixes
+            .iter()
+            .find(|(_, prefix)| event_code.starts_with(prefix.as_str()))
+            .map(|(theme, _)| theme.clone())
+    }
+}
+
+/// One export row as stored, or `None` for a non-root event, one without a
+/// location, or one the filter rejects.
+fn parse_row(line: &str, filter: &Filter) -> Option<StoredEvent> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    if fields.len() < COLUMNS || fields[COL_IS_ROOT] != "1" {
+        return None;
+    }
+    let lat: f64 = fields[COL_LAT].parse().ok()?;
+    let lon: f64 = fields[COL_LON].parse().ok()?;
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return None;
+    }
+    let event_code = fields[COL_EVENT_CODE];
+    let country = fields[COL_COUNTRY];
+    let event_type = filter.event_type(event_code, country, lat, lon)?;
+    let date = NaiveDate::parse_from_str(fields[COL_DATE], "%Y%m%d").ok()?;
+    let number = |i: usize| fields[i].parse::<f64>().ok().filter(|v| v.is_finite());
+    let place = if fields[COL_FEATURE].is_empty() {
+        format!("{lat:.2},{lon:.2}")
+    } else {
+        fields[COL_FEATURE].to_string()
+    };
+    let actors: Vec<&str> = [fields[COL_ACTOR1_NAME], fields[COL_ACTOR2_NAME]].into_iter().filter(|a| !a.is_empty()).collect();
+    let id = format!(
+        "{}|{event_code}|{}|{}|{place}",
+        fields[COL_DATE], fields[COL_ACTOR1_CODE], fields[COL_ACTOR2_CODE]
+    );
+    Some(StoredEvent {
+        data: json!({
+            "id": id,
+            "globalEventId": fields[COL_ID],
+            "eventType": event_type,
+            "eventCode": event_code,
+            "quadClass": number(COL_QUAD_CLASS),
+            "goldstein": number(COL_GOLDSTEIN),
+            "mentions": number(COL_MENTIONS),
+            "sources": number(COL_SOURCES),
+            "tone": number(COL_TONE),
+            "actors": actors,
+            "country": country,
+            "place": fields[COL_PLACE],
+            "location": { "latitude": lat, "longitude": lon },
+            "occurredAt": date.and_hms_opt(0, 0, 0)?.and_utc().timestamp_millis(),
+            "url": fields[COL_URL],
+        }),
+        id,
+        event_date: date.format("%Y-%m-%d").to_string(),
+        event_type,
+        lat,
+        lon,
+    })
+}
+
+/// The events export URL and its timestamp from `lastupdate.txt`.
+fn latest_export(last_update: &str) -> Option<(String, String)> {
+    let url = last_update
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(2))
+        .find(|url| url.ends_with(EXPORT_SUFFIX))?;
+    let timestamp = url.rsplit('/').next()?.strip_suffix(EXPORT_SUFFIX)?;
+    Some((url.to_string(), timestamp.to_string()))
+}
+
+/// Export timestamps after `cursor` up to and including `latest`, at most
+/// `MAX_BACKFILL` of them, oldest first.
+fn pending_exports(cursor: Option<&str>, latest: &str) -> Vec<String> {
+    let parse = |t: &str| NaiveDateTime::parse_from_str(t, "%Y%m%d%H%M%S").ok();
+    let (Some(cursor), Some(end)) = (cursor.and_then(parse), parse(latest)) else {
+        return vec![latest.to_string()];
+    };
+    let mut exports = Vec::new();
+    let mut at = end;
+    while at > cursor && exports.len() < MAX_BACKFILL {
+        exports.push(at.format("%Y%m%d%H%M%S").to_string());
+        at -= chrono::Duration::minutes(15);
+    }
+    exports.reverse();
+    exports
+}
+
+fn unzip(bytes: &[u8]) -> Result<String, String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(|e| format!("invalid export archive: {e}"))?;
+    let mut file = archive.by_index(0).map_err(|e| format!("invalid export archive: {e}"))?;
+    let mut text = String::new();
+    file.read_to_string(&mut text).map_err(|e| format!("invalid export: {e}"))?;
+    Ok(text)
+}
+
+/// Download and store one export. Returns the events new to the store.
+async fn ingest(app: &AppHandle, client: &reqwest::Client, url: &str, filter: &Filter) -> Result<Vec<Value>, String> {
+    let response = client.get(url).send().await.map_err(|e| format!("request failed: {e}"))?;
+    match response.status() {
+        // Exports are occasionally skipped; nothing to ingest.
+        reqwest::StatusCode::NOT_FOUND => return Ok(Vec::new()),
+        status if !status.is_success() => return Err(format!("HTTP {status}")),
+        _ => {}
+    }
+    let bytes = response.bytes().await.map_err(|e| format!("download failed: {e}"))?;
+    let text = unzip(&bytes)?;
+    let events: Vec<StoredEvent> = text.lines().filter_map(|line| parse_row(line, filter)).collect();
+    let app = app.clone();
+    crate::runtime::blocking(move || {
+        let store = app.state::<EventStore>();
+        let added = store.upsert(SOURCE, &events)?;
+        let added: std::collections::HashSet<String> = added.into_iter().collect();
+        Ok(events.into_iter().filter(|e| added.contains(&e.id)).map(|e| e.data).collect())
+    })
+    .await
+}
+
+/// One check of `lastupdate.txt`, ingesting whatever is new.
+async fn update(app: &AppHandle, client: &reqwest::Client, filter: &Filter) -> Result<(), String> {
+    let last_update = client
+        .get(LAST_UPDATE_URL)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("request failed: {e}"))?
+        .text()
+        .await
+        .map_err(|e| format!("request failed: {e}"))?;
+    let (latest_url, latest) = latest_export(&last_update).ok_or("no events export in lastupdate.txt")?;
+    let base = latest_url.trim_end_matches(&format!("{latest}{EXPORT_SUFFIX}")).to_string();
+
+    let store = app.state::<EventStore>();
+    let cursor = store.cursor(SOURCE)?;
+    for timestamp in pending_exports(cursor.as_deref(), &latest) {
+        let added = ingest(app, client, &format!("{base}{timestamp}{EXPORT_SUFFIX}"), filter).await?;
+        store.set_cursor(SOURCE, &timestamp)?;
+        tracing::debug!(target: "app", export = %timestamp, added = added.len(), "GDELT export ingested");
+        update_status(app, |status| {
+            status.last_export = Some(timestamp.clone());
+            status.added = added.len();
+        });
+        if !added.is_empty() {
+            let _ = app.emit(EVENTS_EVENT, added);
+        }
+    }
+
+    let cutoff = (Utc::now().date_naive() - chrono::Days::new(RETENTION_DAYS)).format("%Y-%m-%d").to_string();
+    store.prune(SOURCE, &cutoff)?;
+    let stored = store.count(SOURCE)?;
+    update_status(app, |status| status.stored_events = stored);
+    Ok(())
+}
+
+struct Poller {
+    app: AppHandle,
+    client: reqwest::Client,
+    filter: Filter,
+}
+
+impl crate::poll::Poller for Poller {
+    async fn round(&mut self) -> Duration {
+        match update(&self.app, &self.client, &self.filter).await {
+            Ok(()) => {
+                update_status(&self.app, |status| status.error = None);
+                UPDATE_INTERVAL
+            }
+            Err(err) => {
+                tracing::warn!(target: "app", "GDELT update failed: {err}");
+                update_status(&self.app, |status| status.error = Some(err));
+                ERROR_RETRY
+            }
+        }
+    }
+}
+
+async fn run(app: AppHandle, config: GdeltConfig) {
+    let client = match reqwest::Client::builder().use_native_tls().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!(target: "app", "GDELT ingestion unavailable: {err}");
+            return;
+        }
+    };
+    let poller = Poller {
+        app: app.clone(),
+        client,
+        filter: Filter::new(config),
+    };
+    crate::poll::run(&app, poller).await;
+}
+
+/// Start ingesting if `gdelt.enabled` is set.
+pub fn start(app: &AppHandle) {
+    let config = app.state::<DesktopConfigState>().snapshot().gdelt;
+    update_status(app, |status| status.enabled = config.enabled);
+    if config.enabled {
+        tauri::async_runtime::spawn(run(app.clone(), config));
+    }
+}
+
+#[tauri::command]
+pub fn get_gdelt_status(webview: Webview, state: tauri::State<'_, GdeltState>) -> Result<GdeltStatus, String> {
+    crate::metrics::of(&webview).observe("get_gdelt_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.status.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+/// Stored GDELT events, newest first. `eventTypes` are theme names.
+#[tauri::command]
+pub async fn query_gdelt_events(webview: Webview, app: AppHandle, query: Option<EventQuery>) -> Result<Vec<Value>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("query_gdelt_events", async move {
+            crate::require_trusted_window(webview.label())?;
+            let query = query.unwrap_or_default();
+            crate::runtime::blocking(move || app.state::<EventStore>().query(SOURCE, &query)).await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod gdelt_tests {
+    use super::*;
+    use crate::ais::BoundingBox;
+
+    fn row(event_code: &str, is_root: &str, country: &str, lat: &str, lon: &str) -> String {
+        let mut fields = vec![""; COLUMNS];
+        fields[COL_ID] = "1234567";
+        fields[COL_DATE] = "20260314";
+        fields[COL_ACTOR1_CODE] = "USA";
+        fields[COL_ACTOR1_NAME] = "UNITED STATES";
+        fields[COL_IS_ROOT] = is_root;
+        fields[COL_EVENT_CODE] = event_code;
+        fields[COL_MENTIONS] = "10";
+        fields[COL_COUNTRY] = country;
+        fields[COL_LAT] = lat;
+        fields[COL_LON] = lon;
+        fields[COL_FEATURE] = "-1456928";
+        fields[COL_URL] = "https://example.com/story";
+        fields.join("\t")
+    }
+
+    #[test]
+    fn filters_by_theme_and_region() {
+        let filter = Filter::new(GdeltConfig {
+            themes: vec!["protest".to_string(), "190".to_string()],
+            countries: vec!["sy".to_string()],
+            ..GdeltConfig::default()
+        });
+        let event = parse_row(&row("145", "1", "SY", "36.2", "37.15"), &filter).unwrap();
+        assert_eq!((event.event_type.as_str(), event.event_date.as_str()), ("protest", "2026-03-14"));
+        assert_eq!(event.id, "20260314|145|USA||-1456928");
+        assert_eq!(event.data["actors"], json!(["UNITED STATES"]));
+        assert_eq!(event.data["mentions"], json!(10.0));
+        assert_eq!(parse_row(&row("190", "1", "SY", "36.2", "37.15"), &filter).unwrap().event_type, "190");
+
+        assert!(parse_row(&row("193", "1", "SY", "36.2", "37.15"), &filter).is_none());
+        assert!(parse_row(&row("145", "0", "SY", "36.2", "37.15"), &filter).is_none());
+        assert!(parse_row(&row("145", "1", "IZ", "33.3", "44.4"), &filter).is_none());
+        assert!(parse_row(&row("145", "1", "SY", "", ""), &filter).is_none());
+
+        let boxed = Filter::new(GdeltConfig {
+            themes: Vec::new(),
+            bounding_boxes: vec![BoundingBox { south: 30.0, west: 30.0, north: 40.0, east: 40.0 }],
+            ..GdeltConfig::default()
+        });
+        assert_eq!(parse_row(&row("042", "1", "SY", "36.2", "37.15"), &boxed).unwrap().event_type, "04");
+        assert!(parse_row(&row("042", "1", "IZ", "33.3", "44.4"), &boxed).is_none());
+    }
+
+    #[test]
+    fn finds_the_latest_export_and_backfills() {
+        let last_update = "150383 297a16b493de7cf6ca809a7cc31d0b93 http://data.gdeltproject.org/gdeltv2/20260314120000.export.CSV.zip\n\
+            318084 bb27f78ba45f69a17ea6ed7755e9f8ff http://data.gdeltproject.org/gdeltv2/20260314120000.mentions.CSV.zip\n";
+        let (url, timestamp) = latest_export(last_update).unwrap();
+        assert_eq!(url, "http://data.gdeltproject.org/gdeltv2/20260314120000.export.CSV.zip");
+        assert_eq!(timestamp, "20260314120000");
+
+        assert_eq!(pending_exports(None, "20260314120000"), ["20260314120000"]);
+        assert!(pending_exports(Some("20260314120000"), "20260314120000").is_empty());
+        assert_eq!(
+            pending_exports(Some("20260314113000"), "20260314120000"),
+            ["20260314114500", "20260314120000"]
+        );
+        let after_a_day = pending_exports(Some("20260313120000"), "20260314120000");
+        assert_eq!(after_a_day.len(), MAX_BACKFILL);
+        assert_eq!(after_a_day.last().map(String::as_str), Some("20260314120000"));
+    }
+}
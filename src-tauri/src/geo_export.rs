@@ -0,0 +1,333 @@
+//! Export of stored positions for GIS tools: one aircraft's or vessel's
+//! trail from the timeline (as `get_track` returns it), or a time-boxed set
+//! of timeline events (as `query_events` selects them), written as KML for
+//! Google Earth, GPX, or GeoJSON for QGIS to a file picked in a native save
+//! dialog. Tracks become a line with their start and end time, events one
+//! point each; events without a position are left out.
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, SecondsFormat};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Webview};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::store::{EventKind, EventStore, Observation, TimelineQuery, TrackPoint};
+
+const GENERATOR: &str = concat!("WorldMonitor ", env!("CARGO_PKG_VERSION"));
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Kml,
+    Gpx,
+    GeoJson,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Kml => "kml",
+            ExportFormat::Gpx => "gpx",
+            ExportFormat::GeoJson => "geojson",
+        }
+    }
+
+    fn filter_name(self) -> &'static str {
+        match self {
+            ExportFormat::Kml => "KML",
+            ExportFormat::Gpx => "GPX",
+            ExportFormat::GeoJson => "GeoJSON",
+        }
+    }
+}
+
+/// What to export.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ExportSelection {
+    /// The trail of aircraft (ICAO24) or vessel (MMSI) `id`, between `from`
+    /// and `to` in milliseconds.
+    Track {
+        id: String,
+        #[serde(default)]
+        kind: Option<EventKind>,
+        #[serde(default)]
+        from: Option<i64>,
+        #[serde(default)]
+        to: Option<i64>,
+    },
+    /// Timeline events matching `query`, at most 10,000.
+    Events {
+        #[serde(default)]
+        query: TimelineQuery,
+    },
+}
+
+fn iso_time(millis: i64) -> String {
+    DateTime::from_timestamp_millis(millis)
+        .map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .unwrap_or_default()
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than whitespace are not valid XML.
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn kml(name: &str, placemarks: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<kml xmlns=\"http://www.opengis.net/kml/2.2\">\n<Document>\n<name>{}</name>\n{placemarks}</Document>\n</kml>\n",
+        escape(name)
+    )
+}
+
+fn gpx(name: &str, body: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<gpx version=\"1.1\" creator=\"{GENERATOR}\" xmlns=\"http://www.topografix.com/GPX/1/1\">\n<metadata><name>{}</name></metadata>\n{body}</gpx>\n",
+        escape(name)
+    )
+}
+
+fn track_file(name: &str, points: &[TrackPoint], format: ExportFormat) -> String {
+    let (start, end) = match (points.first(), points.last()) {
+        (Some(first), Some(last)) => (iso_time(first.time), iso_time(last.time)),
+        _ => Default::default(),
+    };
+    match format {
+        ExportFormat::Kml => {
+            let coordinates: Vec<String> = points.iter().map(|p| format!("{},{}", p.lon, p.lat)).collect();
+            kml(
+                name,
+                &format!(
+                    "<Placemark>\n<name>{}</name>\n<TimeSpan><begin>{start}</begin><end>{end}</end></TimeSpan>\n<LineString><tessellate>1</tessellate><coordinates>{}</coordinates></LineString>\n</Placemark>\n",
+                    escape(name),
+                    coordinates.join(" ")
+                ),
+            )
+        }
+        ExportFormat::Gpx => {
+            let trkpts: String = points
+                .iter()
+                .map(|p| format!("<trkpt lat=\"{}\" lon=\"{}\"><time>{}</time></trkpt>\n", p.lat, p.lon, iso_time(p.time)))
+                .collect();
+            gpx(name, &format!("<trk>\n<name>{}</name>\n<trkseg>\n{trkpts}</trkseg>\n</trk>\n", escape(name)))
+        }
+        ExportFormat::GeoJson => {
+            let coordinates: Vec<[f64; 2]> = points.iter().map(|p| [p.lon, p.lat]).collect();
+            let times: Vec<String> = points.iter().map(|p| iso_time(p.time)).collect();
+            json!({
+                "type": "FeatureCollection",
+                "features": [{
+                    "type": "Feature",
+                    "geometry": { "type": "LineString", "coordinates": coordinates },
+                    "properties": { "name": name, "start": start, "end": end, "times": times },
+                }],
+            })
+            .to_string()
+        }
+    }
+}
+
+fn events_file(name: &str, events: &[Observation], format: ExportFormat) -> String {
+    let located = events.iter().filter_map(|o| Some((o, o.lat?, o.lon?)));
+    match format {
+        ExportFormat::Kml => {
+            let placemarks: String = located
+                .map(|(o, lat, lon)| {
+                    format!(
+                        "<Placemark>\n<name>{}</name>\n<description>{} from {}, {}</description>\n<TimeStamp><when>{}</when></TimeStamp>\n<Point><coordinates>{lon},{lat}</coordinates></Point>\n</Placemark>\n",
+                        escape(&o.title),
+                        o.kind.as_str(),
+                        escape(&o.source),
+                        escape(&o.id),
+                        iso_time(o.time)
+                    )
+                })
+                .collect();
+            kml(name, &placemarks)
+        }
+        ExportFormat::Gpx => {
+            let waypoints: String = located
+                .map(|(o, lat, lon)| {
+                    format!(
+                        "<wpt lat=\"{lat}\" lon=\"{lon}\"><time>{}</time><name>{}</name><src>{}</src><type>{}</type></wpt>\n",
+                        iso_time(o.time),
+                        escape(&o.title),
+                        escape(&o.source),
+                        o.kind.as_str()
+                    )
+                })
+                .collect();
+            gpx(name, &waypoints)
+        }
+        ExportFormat::GeoJson => {
+            let features: Vec<Value> = located
+                .map(|(o, lat, lon)| {
+                    json!({
+                        "type": "Feature",
+                        "geometry": { "type": "Point", "coordinates": [lon, lat] },
+                        "properties": {
+                            "kind": o.kind,
+                            "source": o.source,
+                            "id": o.id,
+                            "time": iso_time(o.time),
+                            "title": o.title,
+                            "data": o.data,
+                        },
+                    })
+                })
+                .collect();
+            json!({ "type": "FeatureCollection", "name": name, "features": features }).to_string()
+        }
+    }
+}
+
+/// The file's contents and suggested name, read from the store.
+fn render(store: &EventStore, selection: ExportSelection, format: ExportFormat) -> Result<(String, String), String> {
+    match selection {
+        ExportSelection::Track { id, kind, from, to } => {
+            if kind.is_some_and(|k| !matches!(k, EventKind::Flight | EventKind::Vessel)) {
+                return Err("Tracks are kept for flights and vessels".to_string());
+            }
+            let points = store.track(kind, &id, from.unwrap_or(i64::MIN), to.unwrap_or(i64::MAX))?;
+            if points.is_empty() {
+                return Err(format!("No positions recorded for {id}"));
+            }
+            let name = format!("Track {id}");
+            let file = format!("world-monitor-track-{id}.{}", format.extension());
+            Ok((track_file(&name, &points, format), file))
+        }
+        ExportSelection::Events { mut query } => {
+            query.limit.get_or_insert(usize::MAX);
+            let events = store.events(&query)?;
+            if !events.iter().any(|o| o.lat.is_some() && o.lon.is_some()) {
+                return Err("No events with a position match".to_string());
+            }
+            let name = "World Monitor events".to_string();
+            let file = format!("world-monitor-events-{}.{}", crate::unix_timestamp_secs(), format.extension());
+            Ok((events_file(&name, &events, format), file))
+        }
+    }
+}
+
+/// Ask where to save `file`. Blocks until answered; call off the main thread.
+fn pick_path(app: &AppHandle, file: &str, format: ExportFormat) -> Option<PathBuf> {
+    let mut dialog = app
+        .dialog()
+        .file()
+        .set_title("Export")
+        .set_file_name(file)
+        .add_filter(format.filter_name(), &[format.extension()]);
+    if let Ok(dir) = app.path().download_dir() {
+        dialog = dialog.set_directory(dir);
+    }
+    if let Some(window) = app.get_webview_window("main") {
+        dialog = dialog.set_parent(&window);
+    }
+    dialog.blocking_save_file()?.into_path().ok()
+}
+
+/// Write `selection` as `format` to a file picked in a save dialog. Returns
+/// its path, or `None` if the dialog was cancelled.
+#[tauri::command]
+pub async fn export_geodata(
+    webview: Webview,
+    app: AppHandle,
+    selection: ExportSelection,
+    format: ExportFormat,
+) -> Result<Option<String>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("export_geodata", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                let (contents, file) = render(&app.state::<EventStore>(), selection, format)?;
+                let Some(path) = pick_path(&app, &file, format) else {
+                    return Ok(None);
+                };
+                std::fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+                tracing::info!(target: "app", path = %path.display(), "geodata exported");
+                Ok(Some(path.display().to_string()))
+            })
+            .await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod geo_export_tests {
+    use super::*;
+
+    fn points() -> Vec<TrackPoint> {
+        vec![
+            TrackPoint { time: 1_773_360_000_000, lat: 50.0, lon: 8.0 },
+            TrackPoint { time: 1_773_360_300_000, lat: 50.5, lon: 8.2 },
+        ]
+    }
+
+    #[test]
+    fn writes_tracks_in_every_format() {
+        let kml = track_file("Track 3c6444", &points(), ExportFormat::Kml);
+        assert!(kml.contains("<coordinates>8,50 8.2,50.5</coordinates>"));
+        assert!(kml.contains("<begin>2026-03-13T00:00:00Z</begin><end>2026-03-13T00:05:00Z</end>"));
+        roxmltree::Document::parse(&kml).unwrap();
+
+        let gpx = track_file("Track 3c6444", &points(), ExportFormat::Gpx);
+        assert!(gpx.contains("<trkpt lat=\"50.5\" lon=\"8.2\"><time>2026-03-13T00:05:00Z</time></trkpt>"));
+        roxmltree::Document::parse(&gpx).unwrap();
+
+        let geojson: Value = serde_json::from_str(&track_file("Track 3c6444", &points(), ExportFormat::GeoJson)).unwrap();
+        assert_eq!(geojson["features"][0]["geometry"]["coordinates"], json!([[8.0, 50.0], [8.2, 50.5]]));
+        assert_eq!(geojson["features"][0]["properties"]["end"], "2026-03-13T00:05:00Z");
+    }
+
+    #[test]
+    fn writes_located_events_and_escapes_text() {
+        let event = |id: &str, position: Option<(f64, f64)>| Observation {
+            kind: EventKind::Headline,
+            source: "BBC & AP".to_string(),
+            id: id.to_string(),
+            time: 1_773_360_000_000,
+            lat: position.map(|p| p.0),
+            lon: position.map(|p| p.1),
+            title: "<Strike> near \"port\"".to_string(),
+            data: json!({}),
+        };
+        let events = [event("a", Some((12.5, 43.1))), event("b", None)];
+
+        let kml = events_file("Events", &events, ExportFormat::Kml);
+        let document = roxmltree::Document::parse(&kml).unwrap();
+        let names: Vec<&str> = document.descendants().filter(|n| n.has_tag_name("name")).filter_map(|n| n.text()).collect();
+        assert_eq!(names, ["Events", "<Strike> near \"port\""]);
+
+        let gpx = events_file("Events", &events, ExportFormat::Gpx);
+        assert_eq!(roxmltree::Document::parse(&gpx).unwrap().descendants().filter(|n| n.has_tag_name("wpt")).count(), 1);
+
+        let geojson: Value = serde_json::from_str(&events_file("Events", &events, ExportFormat::GeoJson)).unwrap();
+        assert_eq!(geojson["features"].as_array().unwrap().len(), 1);
+        assert_eq!(geojson["features"][0]["properties"]["kind"], "headline");
+    }
+
+    #[test]
+    fn reads_selections() {
+        let track: ExportSelection = serde_json::from_value(json!({ "type": "track", "id": "3c6444", "kind": "flight" })).unwrap();
+        assert!(matches!(track, ExportSelection::Track { kind: Some(EventKind::Flight), .. }));
+        let events: ExportSelection =
+            serde_json::from_value(json!({ "type": "events", "query": { "since": 0, "until": 1000 } })).unwrap();
+        assert!(matches!(events, ExportSelection::Events { query } if query.until == Some(1000)));
+        assert_eq!(serde_json::from_value::<ExportFormat>(json!("geojson")).unwrap(), ExportFormat::GeoJson);
+    }
+}
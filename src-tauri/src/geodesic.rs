@@ -0,0 +1,171 @@
+//! Batch geodesic calculations on the WGS84 ellipsoid: distance and bearing
+//! between pairs of points, destination points, and routes densified along
+//! the geodesic for drawing flight paths. Coordinates are `[lon, lat]` in
+//! degrees, distances metres and bearings degrees clockwise from north.
+
+use geo::{Bearing, Destination, Distance, Geodesic, InterpolatePoint, Point};
+use serde::{Deserialize, Serialize};
+use tauri::Webview;
+
+use crate::runtime::blocking;
+
+const MAX_BATCH: usize = 100_000;
+const MAX_ROUTE_POINTS: usize = 100_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Leg {
+    pub distance: f64,
+    /// Bearing on leaving the start point, 0..360.
+    pub bearing: f64,
+    /// Bearing on arriving at the end point, 0..360.
+    pub final_bearing: f64,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct Travel {
+    pub from: [f64; 2],
+    pub bearing: f64,
+    pub distance: f64,
+}
+
+fn point(&[lon, lat]: &[f64; 2]) -> Result<Point<f64>, String> {
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return Err(format!("Coordinates out of range: [{lon}, {lat}]"));
+    }
+    Ok(Point::new(lon, lat))
+}
+
+fn batch_size(len: usize) -> Result<(), String> {
+    if len > MAX_BATCH {
+        return Err(format!("At most {MAX_BATCH} items per call"));
+    }
+    Ok(())
+}
+
+fn leg(from: Point<f64>, to: Point<f64>) -> Leg {
+    Leg {
+        distance: Geodesic.distance(from, to),
+        bearing: Geodesic.bearing(from, to),
+        final_bearing: (Geodesic.bearing(to, from) + 180.0) % 360.0,
+    }
+}
+
+fn destination(travel: &Travel) -> Result<[f64; 2], String> {
+    if !travel.bearing.is_finite() || !travel.distance.is_finite() {
+        return Err("Bearing and distance must be finite".to_string());
+    }
+    let to = Geodesic.destination(point(&travel.from)?, travel.bearing, travel.distance);
+    Ok([to.x(), to.y()])
+}
+
+/// `waypoints` with points added along each geodesic leg so that none is
+/// more than `spacing` metres from the next. Longitudes are unwrapped past
+/// ±180° where the route crosses the antimeridian, so the line draws without
+/// jumping across the map.
+fn densify(waypoints: &[[f64; 2]], spacing: f64) -> Result<Vec<[f64; 2]>, String> {
+    let points = waypoints.iter().map(point).collect::<Result<Vec<_>, _>>()?;
+    let mut route: Vec<[f64; 2]> = Vec::new();
+    let mut push = |p: Point<f64>| {
+        let lon = match route.last() {
+            Some(&[previous, _]) => previous + (p.x() - previous + 180.0).rem_euclid(360.0) - 180.0,
+            None => p.x(),
+        };
+        // Intermediate points can land on the leg's end.
+        if route.last() != Some(&[lon, p.y()]) {
+            route.push([lon, p.y()]);
+        }
+    };
+    if let Some(&first) = points.first() {
+        push(first);
+    }
+    for pair in points.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        let added = (Geodesic.distance(from, to) / spacing).ceil();
+        if added > MAX_ROUTE_POINTS as f64 {
+            return Err(format!("Route needs more than {MAX_ROUTE_POINTS} points; increase the spacing"));
+        }
+        Geodesic.points_along_line(from, to, spacing, false).for_each(&mut push);
+        push(to);
+    }
+    if route.len() > MAX_ROUTE_POINTS {
+        return Err(format!("Route needs more than {MAX_ROUTE_POINTS} points; increase the spacing"));
+    }
+    Ok(route)
+}
+
+/// Distance and bearings for each `[from, to]` pair.
+#[tauri::command]
+pub async fn geodesic_legs(webview: Webview, pairs: Vec<[[f64; 2]; 2]>) -> Result<Vec<Leg>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("geodesic_legs", async move {
+            crate::require_trusted_window(webview.label())?;
+            batch_size(pairs.len())?;
+            blocking(move || pairs.iter().map(|[from, to]| Ok(leg(point(from)?, point(to)?))).collect()).await
+        })
+        .await
+}
+
+/// Where each journey ends.
+#[tauri::command]
+pub async fn geodesic_destinations(webview: Webview, journeys: Vec<Travel>) -> Result<Vec<[f64; 2]>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("geodesic_destinations", async move {
+            crate::require_trusted_window(webview.label())?;
+            batch_size(journeys.len())?;
+            blocking(move || journeys.iter().map(destination).collect()).await
+        })
+        .await
+}
+
+/// Each route densified to at most `spacing` metres between points.
+#[tauri::command]
+pub async fn geodesic_routes(
+    webview: Webview,
+    routes: Vec<Vec<[f64; 2]>>,
+    spacing: f64,
+) -> Result<Vec<Vec<[f64; 2]>>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("geodesic_routes", async move {
+            crate::require_trusted_window(webview.label())?;
+            batch_size(routes.len())?;
+            if !spacing.is_finite() || spacing < 1_000.0 {
+                return Err("Spacing must be at least 1000 metres".to_string());
+            }
+            blocking(move || routes.iter().map(|r| densify(r, spacing)).collect()).await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod geodesic_tests {
+    use super::*;
+
+    const JFK: [f64; 2] = [-73.7781, 40.6413];
+    const LHR: [f64; 2] = [-0.4543, 51.47];
+
+    #[test]
+    fn measures_legs() {
+        let leg = leg(point(&JFK).unwrap(), point(&LHR).unwrap());
+        assert!((leg.distance - 5_555_000.0).abs() < 10_000.0, "distance {}", leg.distance);
+        assert!((leg.bearing - 51.4).abs() < 0.5, "bearing {}", leg.bearing);
+        assert!((leg.final_bearing - 108.4).abs() < 0.5, "final bearing {}", leg.final_bearing);
+        assert!(point(&[0.0, 91.0]).is_err());
+    }
+
+    #[test]
+    fn finds_destinations() {
+        let forward = leg(point(&JFK).unwrap(), point(&LHR).unwrap());
+        let [lon, lat] = destination(&Travel { from: JFK, bearing: forward.bearing, distance: forward.distance }).unwrap();
+        assert!((lon - LHR[0]).abs() < 1e-6 && (lat - LHR[1]).abs() < 1e-6);
+    }
+
+    #[test]
+    fn densifies_routes_across_the_antimeridian() {
+        let route = densify(&[[170.0, 0.0], [-170.0, 0.0]], 100_000.0).unwrap();
+        assert_eq!(route.first(), Some(&[170.0, 0.0]));
+        assert!((route.last().unwrap()[0] - 190.0).abs() < 1e-9);
+        assert!(route.windows(2).all(|w| w[1][0] > w[0][0] && w[1][0] - w[0][0] < 1.0));
+        assert!(densify(&[JFK, LHR], 1.0).is_err());
+    }
+}
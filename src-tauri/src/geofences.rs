@@ -0,0 +1,619 @@
+//! Geofences: polygons, circles and maritime zones saved with
+//! `save_geofence`, checked in the shell against every aircraft position
+//! from `opensky` (network and local receiver) and vessel position from
+//! `ais`. Fences are indexed by bounding box in an r-tree, so a batch of
+//! thousands of positions costs one tree lookup each rather than a test
+//! against every fence. A zone fence names an EEZ or territorial sea from
+//! `maritime` and is checked once the zones have loaded.
+//!
+//! Each batch emits `geofence://event` with the transitions it caused: an
+//! object entering a fence, leaving it (seen outside), or dwelling in it
+//! for the fence's `dwellSecs`. The same transitions go to the `alerts`
+//! engine's geofence rules. An object that stops reporting is forgotten
+//! after 15 minutes without an exit, and enters again if it comes back.
+//! Fences live in `geofences.json`; the last events are kept in memory for
+//! `get_geofence_events`.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use geo::{Distance, Haversine, Intersects, LineString, MultiPolygon, Point, Polygon};
+use rstar::primitives::{GeomWithData, Rectangle};
+use rstar::RTree;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+use crate::alerts::{Sighting, Target};
+use crate::maritime::ZoneIndex;
+
+pub const GEOFENCES_FILE: &str = "geofences.json";
+pub const GEOFENCE_EVENT: &str = "geofence://event";
+
+const ABSENCE: Duration = Duration::from_secs(15 * 60);
+const MAX_FENCES: usize = 1_000;
+const MAX_POINTS: usize = 10_000;
+const MAX_EVENTS: usize = 200;
+const MAX_NAME_LEN: usize = 120;
+const MAX_RADIUS_M: f64 = 5_000_000.0;
+const METRES_PER_DEGREE: f64 = 111_320.0;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Shape {
+    /// A ring of `[lon, lat]` points; closing it is optional.
+    Polygon { coordinates: Vec<[f64; 2]> },
+    /// `center` is `[lon, lat]`; `radius` is in metres.
+    Circle { center: [f64; 2], radius: f64 },
+    /// A maritime zone by id, e.g. `eez:8442`.
+    Zone { zone: String },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Geofence {
+    /// Assigned by `save_geofence` when empty.
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub shape: Shape,
+    #[serde(default)]
+    pub target: Target,
+    /// Report objects that stay inside this long; 0 for no dwell events.
+    #[serde(default)]
+    pub dwell_secs: u64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl Geofence {
+    fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() || self.name.len() > MAX_NAME_LEN {
+            return Err(format!("Geofence name must be 1-{MAX_NAME_LEN} characters"));
+        }
+        let valid = |[lon, lat]: &[f64; 2]| (-180.0..=180.0).contains(lon) && (-90.0..=90.0).contains(lat);
+        match &self.shape {
+            Shape::Polygon { coordinates } => {
+                if coordinates.len() < 3 || coordinates.len() > MAX_POINTS {
+                    return Err(format!("A geofence polygon needs 3-{MAX_POINTS} points"));
+                }
+                if !coordinates.iter().all(valid) {
+                    return Err("Geofence points must be [lon, lat] within range".to_string());
+                }
+            }
+            Shape::Circle { center, radius } => {
+                if !valid(center) {
+                    return Err("Geofence center must be [lon, lat] within range".to_string());
+                }
+                if !(*radius > 0.0 && *radius <= MAX_RADIUS_M) {
+                    return Err(format!("Geofence radius must be between 0 and {MAX_RADIUS_M} metres"));
+                }
+            }
+            Shape::Zone { zone } => {
+                if !crate::maritime::is_zone_id(zone) {
+                    return Err("Geofence zone must be a maritime zone id like eez:8442".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Transition {
+    Enter,
+    Exit,
+    Dwell,
+}
+
+/// Payload of `geofence://event` (a batch of these).
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeofenceEvent {
+    pub fence_id: String,
+    pub fence_name: String,
+    pub transition: Transition,
+    pub target: Target,
+    /// ICAO24 address or MMSI.
+    pub subject: String,
+    /// Callsign or vessel name; may be empty.
+    pub label: String,
+    pub lat: f64,
+    pub lon: f64,
+    /// Seconds inside the fence, for exits and dwells.
+    pub inside_secs: u64,
+    /// Seconds since the epoch.
+    pub at: u64,
+}
+
+/// An object inside a fence, for `get_geofence_occupants`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Occupant {
+    pub fence_id: String,
+    pub target: Target,
+    pub subject: String,
+    pub label: String,
+    pub lat: f64,
+    pub lon: f64,
+    /// Seconds since the epoch.
+    pub entered_at: u64,
+}
+
+/// A fence's shape, ready to test points against.
+enum Area {
+    Polygon(Polygon<f64>),
+    Circle(Point<f64>, f64),
+    Zone(Arc<MultiPolygon<f64>>),
+}
+
+impl Area {
+    /// The area and the boxes to index it by; `None` for a zone that isn't
+    /// loaded (yet).
+    fn new(shape: &Shape, zones: Option<&ZoneIndex>) -> Option<(Self, Vec<Rectangle<[f64; 2]>>)> {
+        Some(match shape {
+            Shape::Polygon { coordinates } => {
+                let polygon = Polygon::new(LineString::from(coordinates.clone()), Vec::new());
+                let (mut low, mut high) = ([f64::MAX; 2], [f64::MIN; 2]);
+                for [lon, lat] in coordinates {
+                    low = [low[0].min(*lon), low[1].min(*lat)];
+                    high = [high[0].max(*lon), high[1].max(*lat)];
+                }
+                (Area::Polygon(polygon), vec![Rectangle::from_corners(low, high)])
+            }
+            Shape::Circle { center: [lon, lat], radius } => {
+                let dlat = radius / METRES_PER_DEGREE;
+                let (south, north) = (lat - dlat, lat + dlat);
+                // Near a pole or across the antimeridian the box spans every
+                // longitude; the exact test below still applies.
+                let dlon = dlat / lat.to_radians().cos().max(f64::EPSILON);
+                let (west, east) = if south <= -90.0 || north >= 90.0 || lon - dlon < -180.0 || lon + dlon > 180.0 {
+                    (-180.0, 180.0)
+                } else {
+                    (lon - dlon, lon + dlon)
+                };
+                let bounds = Rectangle::from_corners([west, south.max(-90.0)], [east, north.min(90.0)]);
+                (Area::Circle(Point::new(*lon, *lat), *radius), vec![bounds])
+            }
+            Shape::Zone { zone } => {
+                let shape = zones?.shape(zone)?;
+                let boxes = crate::maritime::polygon_boxes(&shape);
+                (Area::Zone(shape), boxes)
+            }
+        })
+    }
+
+    fn contains(&self, point: Point<f64>) -> bool {
+        match self {
+            Area::Polygon(polygon) => polygon.intersects(&point),
+            Area::Circle(center, radius) => Haversine.distance(*center, point) <= *radius,
+            Area::Zone(shape) => shape.intersects(&point),
+        }
+    }
+}
+
+struct Presence {
+    label: String,
+    lat: f64,
+    lon: f64,
+    entered: Instant,
+    entered_at: u64,
+    seen: Instant,
+    dwelled: bool,
+}
+
+/// The indexed fences and who is inside them.
+#[derive(Default)]
+struct Engine {
+    tree: RTree<GeomWithData<Rectangle<[f64; 2]>, usize>>,
+    /// Enabled fences, by the index stored in the tree.
+    areas: Vec<(Geofence, Area)>,
+    /// Per object, the fences it is inside, by fence id.
+    presence: HashMap<(Target, String), HashMap<String, Presence>>,
+    /// Maritime zones for zone fences, once loaded.
+    zones: Option<Arc<ZoneIndex>>,
+}
+
+impl Engine {
+    fn new(fences: &[Geofence]) -> Self {
+        let mut engine = Engine::default();
+        engine.rebuild(fences);
+        engine
+    }
+
+    /// Re-index `fences`, forgetting objects in fences that were removed.
+    fn rebuild(&mut self, fences: &[Geofence]) {
+        let mut areas = Vec::new();
+        let mut boxes = Vec::new();
+        for fence in fences.iter().filter(|fence| fence.enabled) {
+            if let Some((area, bounds)) = Area::new(&fence.shape, self.zones.as_deref()) {
+                boxes.extend(bounds.into_iter().map(|b| GeomWithData::new(b, areas.len())));
+                areas.push((fence.clone(), area));
+            }
+        }
+        self.tree = RTree::bulk_load(boxes);
+        self.areas = areas;
+        let ids: Vec<&str> = self.areas.iter().map(|(fence, _)| fence.id.as_str()).collect();
+        for inside in self.presence.values_mut() {
+            inside.retain(|id, _| ids.contains(&id.as_str()));
+        }
+        self.presence.retain(|_, inside| !inside.is_empty());
+    }
+
+    /// Drop tracking for a fence whose shape or target changed.
+    fn forget(&mut self, fence_id: &str) {
+        for inside in self.presence.values_mut() {
+            inside.remove(fence_id);
+        }
+        self.presence.retain(|_, inside| !inside.is_empty());
+    }
+
+    fn observe(&mut self, sightings: &[Sighting], now: Instant, at: u64) -> Vec<GeofenceEvent> {
+        let mut events = Vec::new();
+        for s in sightings {
+            let point = Point::new(s.lon, s.lat);
+            let mut within: Vec<usize> = self
+                .tree
+                .locate_all_at_point(&[s.lon, s.lat])
+                .map(|entry| entry.data)
+                .filter(|&index| {
+                    let (fence, area) = &self.areas[index];
+                    fence.target.includes(s.kind) && area.contains(point)
+                })
+                .collect();
+            within.sort_unstable();
+            within.dedup();
+            let key = (s.kind, s.id.to_string());
+            let inside = self.presence.entry(key).or_default();
+            let event = |fence: &Geofence, transition, inside_secs| GeofenceEvent {
+                fence_id: fence.id.clone(),
+                fence_name: fence.name.clone(),
+                transition,
+                target: s.kind,
+                subject: s.id.to_string(),
+                label: s.label.trim().to_string(),
+                lat: s.lat,
+                lon: s.lon,
+                inside_secs,
+                at,
+            };
+            let mut left: Vec<String> = inside.keys().cloned().collect();
+            for index in within {
+                let fence = &self.areas[index].0;
+                left.retain(|id| *id != fence.id);
+                if inside.get(&fence.id).is_none_or(|p| now.duration_since(p.seen) >= ABSENCE) {
+                    events.push(event(fence, Transition::Enter, 0));
+                    let presence = Presence {
+                        label: String::new(),
+                        lat: s.lat,
+                        lon: s.lon,
+                        entered: now,
+                        entered_at: at,
+                        seen: now,
+                        dwelled: false,
+                    };
+                    inside.insert(fence.id.clone(), presence);
+                }
+                let Some(presence) = inside.get_mut(&fence.id) else {
+                    continue;
+                };
+                presence.label = s.label.trim().to_string();
+                (presence.lat, presence.lon, presence.seen) = (s.lat, s.lon, now);
+                let stayed = now.duration_since(presence.entered);
+                if fence.dwell_secs > 0 && !presence.dwelled && stayed.as_secs() >= fence.dwell_secs {
+                    presence.dwelled = true;
+                    events.push(event(fence, Transition::Dwell, stayed.as_secs()));
+                }
+            }
+            for id in left {
+                let Some(presence) = inside.remove(&id) else {
+                    continue;
+                };
+                if now.duration_since(presence.seen) >= ABSENCE {
+                    continue;
+                }
+                if let Some((fence, _)) = self.areas.iter().find(|(fence, _)| fence.id == id) {
+                    events.push(event(fence, Transition::Exit, now.duration_since(presence.entered).as_secs()));
+                }
+            }
+        }
+        for inside in self.presence.values_mut() {
+            inside.retain(|_, presence| now.duration_since(presence.seen) < ABSENCE);
+        }
+        self.presence.retain(|_, inside| !inside.is_empty());
+        events
+    }
+
+    fn occupants(&self, fence_id: Option<&str>) -> Vec<Occupant> {
+        let mut occupants: Vec<Occupant> = self
+            .presence
+            .iter()
+            .flat_map(|((target, subject), inside)| {
+                inside
+                    .iter()
+                    .filter(move |(id, _)| fence_id.is_none_or(|wanted| wanted == id.as_str()))
+                    .map(move |(id, presence)| Occupant {
+                        fence_id: id.clone(),
+                        target: *target,
+                        subject: subject.clone(),
+                        label: presence.label.clone(),
+                        lat: presence.lat,
+                        lon: presence.lon,
+                        entered_at: presence.entered_at,
+                    })
+            })
+            .collect();
+        occupants.sort_by(|a, b| (&a.fence_id, a.entered_at, &a.subject).cmp(&(&b.fence_id, b.entered_at, &b.subject)));
+        occupants
+    }
+}
+
+/// Managed state: the fences, their index and occupants, and recent events.
+pub struct GeofencesState {
+    path: PathBuf,
+    /// The file could not be read and could not be moved aside, so it is
+    /// not overwritten.
+    unquarantined: bool,
+    fences: Mutex<Vec<Geofence>>,
+    engine: Mutex<Engine>,
+    events: Mutex<VecDeque<GeofenceEvent>>,
+}
+
+impl GeofencesState {
+    /// Load `geofences.json`; a missing file means no fences, and an
+    /// unreadable one is moved aside (see `load_json_file`).
+    pub fn load(path: PathBuf) -> Self {
+        let (fences, unquarantined): (Vec<Geofence>, _) = crate::load_json_file(&path);
+        GeofencesState {
+            path,
+            unquarantined,
+            engine: Mutex::new(Engine::new(&fences)),
+            fences: Mutex::new(fences),
+            events: Mutex::default(),
+        }
+    }
+
+    fn persist(&self, fences: &[Geofence]) -> Result<(), String> {
+        if self.unquarantined {
+            return Err(format!("Geofences {} are unreadable and could not be moved aside", self.path.display()));
+        }
+        let serialized =
+            serde_json::to_string_pretty(fences).map_err(|e| format!("Failed to serialize geofences: {e}"))?;
+        fs::write(&self.path, serialized)
+            .map_err(|e| format!("Failed to write geofences {}: {e}", self.path.display()))
+    }
+}
+
+/// Take the loaded maritime zones and start checking zone fences.
+pub fn set_zones(app: &AppHandle, zones: Arc<ZoneIndex>) {
+    let Some(state) = app.try_state::<GeofencesState>() else {
+        return;
+    };
+    let fences = state.fences.lock().unwrap_or_else(|e| e.into_inner());
+    let mut engine = state.engine.lock().unwrap_or_else(|e| e.into_inner());
+    engine.zones = Some(zones);
+    engine.rebuild(&fences);
+}
+
+/// Check aircraft or vessel positions against the fences and emit the
+/// transitions.
+pub fn observe_positions(app: &AppHandle, sightings: &[Sighting]) {
+    let Some(state) = app.try_state::<GeofencesState>() else {
+        return;
+    };
+    let events = {
+        let mut engine = state.engine.lock().unwrap_or_else(|e| e.into_inner());
+        if engine.areas.is_empty() {
+            return;
+        }
+        engine.observe(sightings, Instant::now(), crate::unix_timestamp_secs())
+    };
+    if events.is_empty() {
+        return;
+    }
+    {
+        let mut recent = state.events.lock().unwrap_or_else(|e| e.into_inner());
+        recent.extend(events.iter().cloned());
+        let excess = recent.len().saturating_sub(MAX_EVENTS);
+        recent.drain(..excess);
+    }
+    tracing::debug!(target: "app", count = events.len(), "geofence transitions");
+    crate::alerts::observe_geofence_events(app, &events);
+    let _ = app.emit(GEOFENCE_EVENT, &events);
+}
+
+#[tauri::command]
+pub fn list_geofences(webview: Webview, state: tauri::State<'_, GeofencesState>) -> Result<Vec<Geofence>, String> {
+    crate::metrics::of(&webview).observe("list_geofences", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.fences.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+/// Add a fence, or replace the one with the same id.
+#[tauri::command]
+pub async fn save_geofence(webview: Webview, app: AppHandle, mut fence: Geofence) -> Result<Geofence, String> {
+    crate::metrics::of(&webview)
+        .observe_async("save_geofence", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                let state = app.state::<GeofencesState>();
+                fence.validate()?;
+                if let Shape::Zone { zone } = &fence.shape {
+                    let zones = state.engine.lock().unwrap_or_else(|e| e.into_inner()).zones.clone();
+                    if zones.is_some_and(|zones| zones.shape(zone).is_none()) {
+                        return Err(format!("Unknown maritime zone {zone}"));
+                    }
+                }
+                if fence.id.is_empty() {
+                    let mut buf = [0u8; 8];
+                    getrandom::getrandom(&mut buf).map_err(|e| format!("Failed to generate geofence id: {e}"))?;
+                    fence.id = buf.iter().map(|b| format!("{b:02x}")).collect();
+                }
+                let mut fences = state.fences.lock().unwrap_or_else(|e| e.into_inner());
+                let mut updated = fences.clone();
+                match updated.iter_mut().find(|existing| existing.id == fence.id) {
+                    Some(existing) => *existing = fence.clone(),
+                    None => updated.push(fence.clone()),
+                }
+                if updated.len() > MAX_FENCES {
+                    return Err(format!("At most {MAX_FENCES} geofences can be saved"));
+                }
+                state.persist(&updated)?;
+                *fences = updated;
+                let mut engine = state.engine.lock().unwrap_or_else(|e| e.into_inner());
+                engine.forget(&fence.id);
+                engine.rebuild(&fences);
+                Ok(fence)
+            })
+            .await
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn delete_geofence(webview: Webview, app: AppHandle, id: String) -> Result<bool, String> {
+    crate::metrics::of(&webview)
+        .observe_async("delete_geofence", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                let state = app.state::<GeofencesState>();
+                let mut fences = state.fences.lock().unwrap_or_else(|e| e.into_inner());
+                let updated: Vec<Geofence> = fences.iter().filter(|fence| fence.id != id).cloned().collect();
+                if updated.len() == fences.len() {
+                    return Ok(false);
+                }
+                state.persist(&updated)?;
+                *fences = updated;
+                state.engine.lock().unwrap_or_else(|e| e.into_inner()).rebuild(&fences);
+                Ok(true)
+            })
+            .await
+        })
+        .await
+}
+
+/// Objects currently inside a fence, or inside any fence without `id`.
+#[tauri::command]
+pub fn get_geofence_occupants(
+    webview: Webview,
+    state: tauri::State<'_, GeofencesState>,
+    id: Option<String>,
+) -> Result<Vec<Occupant>, String> {
+    crate::metrics::of(&webview).observe("get_geofence_occupants", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.engine.lock().unwrap_or_else(|e| e.into_inner()).occupants(id.as_deref()))
+    })
+}
+
+/// The most recent transitions, oldest first.
+#[tauri::command]
+pub fn get_geofence_events(
+    webview: Webview,
+    state: tauri::State<'_, GeofencesState>,
+) -> Result<Vec<GeofenceEvent>, String> {
+    crate::metrics::of(&webview).observe("get_geofence_events", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.events.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect())
+    })
+}
+
+#[cfg(test)]
+mod geofences_tests {
+    use super::*;
+
+    fn fence(id: &str, shape: Shape, dwell_secs: u64) -> Geofence {
+        Geofence { id: id.to_string(), name: format!("fence {id}"), enabled: true, shape, target: Target::Any, dwell_secs }
+    }
+
+    fn vessel(id: &str, lat: f64, lon: f64) -> Sighting<'_> {
+        Sighting { kind: Target::Vessel, id, label: "", imo: None, lat, lon }
+    }
+
+    fn transitions(events: &[GeofenceEvent]) -> Vec<(&str, Transition)> {
+        events.iter().map(|e| (e.fence_id.as_str(), e.transition)).collect()
+    }
+
+    #[test]
+    fn reports_enter_dwell_and_exit() {
+        let square = Shape::Polygon { coordinates: vec![[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]] };
+        let circle = Shape::Circle { center: [5.0, 5.0], radius: 50_000.0 };
+        let mut engine = Engine::new(&[fence("square", square, 0), fence("port", circle, 600)]);
+        let start = Instant::now();
+
+        assert!(engine.observe(&[vessel("1", 20.0, 5.0)], start, 0).is_empty());
+        let entered = engine.observe(&[vessel("1", 5.0, 5.0)], start, 0);
+        assert_eq!(transitions(&entered), [("square", Transition::Enter), ("port", Transition::Enter)]);
+        assert!(engine.observe(&[vessel("1", 5.1, 5.0)], start + Duration::from_secs(60), 0).is_empty());
+        let dwelled = engine.observe(&[vessel("1", 5.1, 5.0)], start + Duration::from_secs(600), 0);
+        assert_eq!(transitions(&dwelled), [("port", Transition::Dwell)]);
+        assert_eq!(engine.occupants(Some("port")).len(), 1);
+
+        // Out of the port (about 110 km away) but still in the square.
+        let left = engine.observe(&[vessel("1", 6.0, 5.0)], start + Duration::from_secs(660), 0);
+        assert_eq!(transitions(&left), [("port", Transition::Exit)]);
+        assert_eq!(left[0].inside_secs, 660);
+        assert_eq!(engine.occupants(None).len(), 1);
+
+        // Gone silent, then back: a new entry rather than an exit.
+        let later = start + Duration::from_secs(660) + ABSENCE;
+        assert_eq!(transitions(&engine.observe(&[vessel("1", 6.0, 5.0)], later, 0)), [("square", Transition::Enter)]);
+    }
+
+    #[test]
+    fn filters_by_target_and_rebuilds() {
+        let mut only_aircraft = fence("air", Shape::Circle { center: [0.0, 0.0], radius: 10_000.0 }, 0);
+        only_aircraft.target = Target::Aircraft;
+        let mut engine = Engine::new(&[only_aircraft.clone()]);
+        let now = Instant::now();
+        assert!(engine.observe(&[vessel("1", 0.0, 0.0)], now, 0).is_empty());
+        let plane = || Sighting { kind: Target::Aircraft, id: "abc123", label: "RCH1", imo: None, lat: 0.05, lon: 0.0 };
+        assert_eq!(engine.observe(&[plane()], now, 0).len(), 1);
+
+        only_aircraft.enabled = false;
+        engine.rebuild(&[only_aircraft]);
+        assert!(engine.occupants(None).is_empty());
+        assert!(engine.observe(&[plane()], now, 0).is_empty());
+    }
+
+    #[test]
+    fn circles_across_the_antimeridian_are_found() {
+        let mut engine = Engine::new(&[fence("dateline", Shape::Circle { center: [179.9, 0.0], radius: 50_000.0 }, 0)]);
+        let events = engine.observe(&[vessel("1", 0.0, -179.9)], Instant::now(), 0);
+        assert_eq!(transitions(&events), [("dateline", Transition::Enter)]);
+    }
+
+    #[test]
+    fn reports_vessels_entering_a_maritime_zone() {
+        let fences = [fence("fiji", Shape::Zone { zone: "eez:8325".to_string() }, 0)];
+        let mut engine = Engine::new(&fences);
+        assert!(engine.areas.is_empty());
+        engine.zones = Some(Arc::new(crate::maritime::maritime_tests::index()));
+        engine.rebuild(&fences);
+        let now = Instant::now();
+        assert!(engine.observe(&[vessel("1", -17.0, 170.0)], now, 0).is_empty());
+        // Across the antimeridian, into the zone's other half.
+        let entered = engine.observe(&[vessel("1", -17.0, -179.0)], now, 0);
+        assert_eq!(transitions(&entered), [("fiji", Transition::Enter)]);
+        assert!(engine.observe(&[vessel("1", -17.0, 179.0)], now, 0).is_empty());
+        assert!(fence("x", Shape::Zone { zone: "eez".to_string() }, 0).validate().is_err());
+    }
+
+    #[test]
+    fn validates_shapes() {
+        assert!(fence("a", Shape::Polygon { coordinates: vec![[0.0, 0.0], [1.0, 1.0]] }, 0).validate().is_err());
+        assert!(fence("b", Shape::Circle { center: [0.0, 95.0], radius: 10.0 }, 0).validate().is_err());
+        assert!(fence("c", Shape::Circle { center: [0.0, 0.0], radius: 0.0 }, 0).validate().is_err());
+        assert!(fence("d", Shape::Circle { center: [0.0, 0.0], radius: 100.0 }, 0).validate().is_ok());
+        let json = r#"{"name":"Port","shape":{"type":"circle","center":[4.4,51.9],"radius":5000},"dwellSecs":600}"#;
+        let parsed: Geofence = serde_json::from_str(json).unwrap();
+        assert!(parsed.enabled && parsed.target == Target::Any && parsed.dwell_secs == 600);
+    }
+}
@@ -0,0 +1,299 @@
+//! GeoJSON processing moved off the webview's main thread: Douglas-Peucker
+//! simplification, bounding boxes, clipping to a bounding box and
+//! point-in-polygon lookups. Every command accepts any GeoJSON object (a
+//! geometry, a feature or a feature collection), runs on a blocking thread,
+//! and leaves feature ids and properties as they were. Coordinates are
+//! `[lon, lat]` as in GeoJSON; bounding boxes with `west > east` cross the
+//! antimeridian.
+
+use geo::{
+    BooleanOps, BoundingRect, Geometry, GeometryCollection, Intersects, LineString, MultiLineString, MultiPoint,
+    MultiPolygon, Point, Rect, Simplify,
+};
+use geojson::{Feature, GeoJson};
+use serde_json::Value;
+use tauri::Webview;
+
+use crate::ais::BoundingBox;
+use crate::runtime::blocking;
+
+fn parse(value: Value) -> Result<GeoJson, String> {
+    GeoJson::from_json_value(value).map_err(|e| format!("Invalid GeoJSON: {e}"))
+}
+
+fn to_geo(geometry: geojson::Geometry) -> Result<Geometry<f64>, String> {
+    geometry.try_into().map_err(|e: geojson::Error| format!("Invalid geometry: {e}"))
+}
+
+fn from_geo(geometry: &Geometry<f64>) -> geojson::Geometry {
+    geojson::Geometry::new(geojson::Value::from(geometry))
+}
+
+/// Apply `f` to every geometry. Features whose geometry `f` drops are left
+/// out of collections, and keep a null geometry otherwise.
+fn map_geometries(geojson: GeoJson, f: &impl Fn(Geometry<f64>) -> Option<Geometry<f64>>) -> Result<GeoJson, String> {
+    let map_feature = |mut feature: Feature| -> Result<(Feature, bool), String> {
+        let Some(geometry) = feature.geometry.take() else {
+            return Ok((feature, true));
+        };
+        feature.geometry = f(to_geo(geometry)?).map(|g| from_geo(&g));
+        let kept = feature.geometry.is_some();
+        Ok((feature, kept))
+    };
+    Ok(match geojson {
+        GeoJson::Geometry(geometry) => {
+            let mapped = f(to_geo(geometry)?).unwrap_or(Geometry::GeometryCollection(GeometryCollection::default()));
+            GeoJson::Geometry(from_geo(&mapped))
+        }
+        GeoJson::Feature(feature) => GeoJson::Feature(map_feature(feature)?.0),
+        GeoJson::FeatureCollection(mut collection) => {
+            let mut features = Vec::with_capacity(collection.features.len());
+            for feature in collection.features {
+                let (feature, kept) = map_feature(feature)?;
+                if kept {
+                    features.push(feature);
+                }
+            }
+            collection.features = features;
+            collection.bbox = None;
+            GeoJson::FeatureCollection(collection)
+        }
+    })
+}
+
+fn simplify(geometry: Geometry<f64>, tolerance: f64) -> Geometry<f64> {
+    match geometry {
+        Geometry::LineString(line) => Geometry::LineString(line.simplify(tolerance)),
+        Geometry::MultiLineString(lines) => Geometry::MultiLineString(lines.simplify(tolerance)),
+        Geometry::Polygon(polygon) => Geometry::Polygon(polygon.simplify(tolerance)),
+        Geometry::MultiPolygon(polygons) => Geometry::MultiPolygon(polygons.simplify(tolerance)),
+        Geometry::GeometryCollection(collection) => Geometry::GeometryCollection(
+            collection.into_iter().map(|g| simplify(g, tolerance)).collect(),
+        ),
+        other => other,
+    }
+}
+
+/// The box as polygons: one, or two when it crosses the antimeridian.
+fn region(b: &BoundingBox) -> MultiPolygon<f64> {
+    let rect = |west: f64, east: f64| Rect::new((west, b.south), (east, b.north)).to_polygon();
+    if b.west <= b.east {
+        MultiPolygon::new(vec![rect(b.west, b.east)])
+    } else {
+        MultiPolygon::new(vec![rect(b.west, 180.0), rect(-180.0, b.east)])
+    }
+}
+
+fn clip(geometry: Geometry<f64>, region: &MultiPolygon<f64>) -> Option<Geometry<f64>> {
+    let lines = |lines: MultiLineString<f64>| {
+        let mut clipped = region.clip(&lines, false);
+        match clipped.0.len() {
+            0 => None,
+            1 => clipped.0.pop().map(Geometry::LineString),
+            _ => Some(Geometry::MultiLineString(clipped)),
+        }
+    };
+    let areas = |polygons: MultiPolygon<f64>| {
+        let mut clipped = polygons.intersection(region);
+        match clipped.0.len() {
+            0 => None,
+            1 => clipped.0.pop().map(Geometry::Polygon),
+            _ => Some(Geometry::MultiPolygon(clipped)),
+        }
+    };
+    match geometry {
+        Geometry::Point(point) => region.intersects(&point).then_some(Geometry::Point(point)),
+        Geometry::MultiPoint(points) => {
+            let inside: Vec<Point<f64>> = points.into_iter().filter(|p| region.intersects(p)).collect();
+            (!inside.is_empty()).then(|| Geometry::MultiPoint(MultiPoint::new(inside)))
+        }
+        Geometry::Line(line) => lines(MultiLineString::new(vec![LineString::from(line)])),
+        Geometry::LineString(line) => lines(MultiLineString::new(vec![line])),
+        Geometry::MultiLineString(multi) => lines(multi),
+        Geometry::Polygon(polygon) => areas(MultiPolygon::new(vec![polygon])),
+        Geometry::MultiPolygon(multi) => areas(multi),
+        Geometry::Rect(rect) => areas(MultiPolygon::new(vec![rect.to_polygon()])),
+        Geometry::Triangle(triangle) => areas(MultiPolygon::new(vec![triangle.to_polygon()])),
+        Geometry::GeometryCollection(collection) => {
+            let clipped: Vec<Geometry<f64>> = collection.into_iter().filter_map(|g| clip(g, region)).collect();
+            (!clipped.is_empty()).then(|| Geometry::GeometryCollection(GeometryCollection::new_from(clipped)))
+        }
+    }
+}
+
+fn bounds(geojson: &GeoJson) -> Result<Option<BoundingBox>, String> {
+    let collection: GeometryCollection<f64> =
+        geojson.try_into().map_err(|e: geojson::Error| format!("Invalid geometry: {e}"))?;
+    Ok(collection.bounding_rect().map(|rect| BoundingBox {
+        south: rect.min().y,
+        west: rect.min().x,
+        north: rect.max().y,
+        east: rect.max().x,
+    }))
+}
+
+/// For each `[lon, lat]` point, the index of the first feature (0 for a lone
+/// geometry or feature) whose geometry covers it.
+fn locate(points: &[[f64; 2]], geojson: GeoJson) -> Result<Vec<Option<usize>>, String> {
+    let geometries: Vec<Option<geojson::Geometry>> = match geojson {
+        GeoJson::Geometry(geometry) => vec![Some(geometry)],
+        GeoJson::Feature(feature) => vec![feature.geometry],
+        GeoJson::FeatureCollection(collection) => collection.features.into_iter().map(|f| f.geometry).collect(),
+    };
+    let mut shapes: Vec<(usize, Rect<f64>, Geometry<f64>)> = Vec::new();
+    for (index, geometry) in geometries.into_iter().enumerate() {
+        let Some(geometry) = geometry else {
+            continue;
+        };
+        let geometry = to_geo(geometry)?;
+        if let Some(rect) = geometry.bounding_rect() {
+            shapes.push((index, rect, geometry));
+        }
+    }
+    Ok(points
+        .iter()
+        .map(|&[lon, lat]| {
+            let point = Point::new(lon, lat);
+            shapes
+                .iter()
+                .find(|(_, rect, geometry)| rect.intersects(&point) && geometry.intersects(&point))
+                .map(|(index, _, _)| *index)
+        })
+        .collect())
+}
+
+fn to_json(geojson: &GeoJson) -> Result<Value, String> {
+    serde_json::to_value(geojson).map_err(|e| format!("Failed to serialize GeoJSON: {e}"))
+}
+
+/// Simplify lines and polygons with Douglas-Peucker; `tolerance` is in
+/// degrees.
+#[tauri::command]
+pub async fn simplify_geojson(webview: Webview, geojson: Value, tolerance: f64) -> Result<Value, String> {
+    crate::metrics::of(&webview)
+        .observe_async("simplify_geojson", async move {
+            crate::require_trusted_window(webview.label())?;
+            if !tolerance.is_finite() || tolerance < 0.0 {
+                return Err("Tolerance must be zero or more".to_string());
+            }
+            blocking(move || to_json(&map_geometries(parse(geojson)?, &|g| Some(simplify(g, tolerance)))?)).await
+        })
+        .await
+}
+
+/// The bounding box of everything in `geojson`; `None` when it is empty.
+#[tauri::command]
+pub async fn geojson_bounds(webview: Webview, geojson: Value) -> Result<Option<BoundingBox>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("geojson_bounds", async move {
+            crate::require_trusted_window(webview.label())?;
+            blocking(move || bounds(&parse(geojson)?)).await
+        })
+        .await
+}
+
+/// Clip to `bounding_box`. Features entirely outside it are dropped from
+/// collections.
+#[tauri::command]
+pub async fn clip_geojson(webview: Webview, geojson: Value, bounding_box: BoundingBox) -> Result<Value, String> {
+    crate::metrics::of(&webview)
+        .observe_async("clip_geojson", async move {
+            crate::require_trusted_window(webview.label())?;
+            if !bounding_box.is_valid() {
+                return Err("Invalid bounding box".to_string());
+            }
+            let region = region(&bounding_box);
+            blocking(move || to_json(&map_geometries(parse(geojson)?, &|g| clip(g, &region))?)).await
+        })
+        .await
+}
+
+/// Which feature of `geojson` each `[lon, lat]` point falls in, if any.
+#[tauri::command]
+pub async fn points_in_polygons(
+    webview: Webview,
+    points: Vec<[f64; 2]>,
+    geojson: Value,
+) -> Result<Vec<Option<usize>>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("points_in_polygons", async move {
+            crate::require_trusted_window(webview.label())?;
+            blocking(move || locate(&points, parse(geojson)?)).await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod geometry_tests {
+    use super::*;
+    use geo::Polygon;
+    use serde_json::json;
+
+    fn collection() -> GeoJson {
+        parse(json!({
+            "type": "FeatureCollection",
+            "features": [
+                {
+                    "type": "Feature",
+                    "properties": { "name": "square" },
+                    "geometry": { "type": "Polygon", "coordinates": [[[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]]] }
+                },
+                {
+                    "type": "Feature",
+                    "properties": { "name": "route" },
+                    "geometry": { "type": "LineString", "coordinates": [[20, 0], [20.001, 5], [20, 10], [30, 10]] }
+                },
+                {
+                    "type": "Feature",
+                    "properties": { "name": "port" },
+                    "geometry": { "type": "Point", "coordinates": [-5, -5] }
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn features(geojson: &GeoJson) -> Vec<Value> {
+        let GeoJson::FeatureCollection(collection) = geojson else {
+            panic!("not a collection");
+        };
+        collection.features.iter().map(|f| json!(f)).collect()
+    }
+
+    #[test]
+    fn simplifies_lines_and_keeps_properties() {
+        let simplified = map_geometries(collection(), &|g| Some(simplify(g, 0.01))).unwrap();
+        let features = features(&simplified);
+        assert_eq!(features[1]["geometry"]["coordinates"], json!([[20.0, 0.0], [20.0, 10.0], [30.0, 10.0]]));
+        assert_eq!(features[1]["properties"]["name"], "route");
+        assert_eq!(features[2]["geometry"]["coordinates"], json!([-5.0, -5.0]));
+    }
+
+    #[test]
+    fn computes_bounds() {
+        let b = bounds(&collection()).unwrap().unwrap();
+        assert_eq!((b.south, b.west, b.north, b.east), (-5.0, -5.0, 10.0, 30.0));
+        let empty = parse(json!({ "type": "FeatureCollection", "features": [] })).unwrap();
+        assert!(bounds(&empty).unwrap().is_none());
+    }
+
+    #[test]
+    fn clips_to_a_box() {
+        let b = BoundingBox { south: 0.0, west: 5.0, north: 20.0, east: 25.0 };
+        let clipped = map_geometries(collection(), &|g| clip(g, &region(&b))).unwrap();
+        let features = features(&clipped);
+        assert_eq!(features.len(), 2);
+        let square = Polygon::<f64>::try_from(geojson::Geometry::from_json_value(features[0]["geometry"].clone()).unwrap()).unwrap();
+        let rect = square.bounding_rect().unwrap();
+        assert_eq!((rect.min().x, rect.max().x), (5.0, 10.0));
+        assert_eq!(features[1]["properties"]["name"], "route");
+        let route = &features[1]["geometry"]["coordinates"];
+        assert!(route.as_array().unwrap().iter().all(|c| c[0].as_f64().unwrap() <= 25.0));
+    }
+
+    #[test]
+    fn locates_points() {
+        let points = [[5.0, 5.0], [-5.0, -5.0], [50.0, 50.0], [20.0, 5.0]];
+        assert_eq!(locate(&points, collection()).unwrap(), [Some(0), Some(2), None, None]);
+    }
+}
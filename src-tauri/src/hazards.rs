@@ -0,0 +1,717 @@
+//! Volcano and tsunami hazards polled into the local `store`, next to the
+//! `usgs` earthquakes. With `hazards.enabled` set (the default), the worker
+//! fetches the NOAA tsunami warning centers' bulletins (NTWC and PTWC)
+//! every `UPDATE_INTERVAL`, and the USGS elevated volcano notices and the
+//! Smithsonian weekly volcanic activity report every `VOLCANO_INTERVAL`.
+//! One feed failing does not hold back the others.
+//!
+//! Each notice is normalized to a kind and an alert level: a volcano's
+//! ground alert level or aviation color code, whichever is higher, and a
+//! bulletin's category. Volcanoes keep one row each, replaced as notices
+//! come in; bulletins keep one row per bulletin. Hazards that are new or
+//! whose level changed go out in one `hazards://events` batch, and those
+//! that are new or raised within `ALERT_WITHIN` go to the alert engine
+//! (`Hazard` rules, which new `usgs` quakes reach too) and, from
+//! `hazards.notify_level`, raise a notification (critical for warnings).
+//! `query_hazards` reads the store. Hazards older than `RETENTION_DAYS`
+//! are pruned.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+use crate::config::{DesktopConfigState, HazardsConfig};
+use crate::notifications::NotificationUrgency;
+use crate::store::{EventStore, HazardQuery, HazardRow, QuakeRow};
+
+pub const HAZARDS_EVENT: &str = "hazards://events";
+
+const VOLCANO_NOTICES_URL: &str = "https://volcanoes.usgs.gov/hans-public/api/volcano/getElevatedVolcanoes";
+const VOLCANO_REPORTS_URL: &str = "https://volcano.si.edu/news/WeeklyVolcanoRSS.xml";
+/// The National and Pacific Tsunami Warning Centers' Atom feeds.
+const TSUNAMI_FEEDS: [(&str, &str); 2] = [
+    ("ntwc", "https://www.tsunami.gov/events/xml/PAAQAtom.xml"),
+    ("ptwc", "https://www.tsunami.gov/events/xml/PHEBAtom.xml"),
+];
+const GEORSS_NS: &str = "http://www.georss.org/georss";
+
+const UPDATE_INTERVAL: Duration = Duration::from_secs(2 * 60);
+const VOLCANO_INTERVAL: Duration = Duration::from_secs(30 * 60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const ERROR_RETRY: Duration = Duration::from_secs(5 * 60);
+/// New or raised hazards older than this are stored without alerting.
+const ALERT_WITHIN: Duration = Duration::from_secs(6 * 60 * 60);
+const RETENTION_DAYS: u64 = 30;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HazardKind {
+    /// Only reaches the alert engine; quakes are stored by `usgs`.
+    Earthquake,
+    Volcano,
+    Tsunami,
+}
+
+impl HazardKind {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            HazardKind::Earthquake => "earthquake",
+            HazardKind::Volcano => "volcano",
+            HazardKind::Tsunami => "tsunami",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            HazardKind::Earthquake => "Earthquake",
+            HazardKind::Volcano => "Volcano",
+            HazardKind::Tsunami => "Tsunami",
+        }
+    }
+}
+
+/// Ordered from least to most severe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HazardLevel {
+    Information,
+    Advisory,
+    Watch,
+    Warning,
+}
+
+const LEVELS: [HazardLevel; 4] =
+    [HazardLevel::Information, HazardLevel::Advisory, HazardLevel::Watch, HazardLevel::Warning];
+
+impl HazardLevel {
+    /// The stored form, increasing with severity.
+    pub(crate) fn rank(self) -> i64 {
+        self as i64
+    }
+
+    pub(crate) fn from_rank(rank: i64) -> Option<Self> {
+        usize::try_from(rank).ok().and_then(|rank| LEVELS.get(rank)).copied()
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            HazardLevel::Information => "information",
+            HazardLevel::Advisory => "advisory",
+            HazardLevel::Watch => "watch",
+            HazardLevel::Warning => "warning",
+        }
+    }
+
+    /// A volcano alert level, aviation color code or bulletin category.
+    fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "warning" | "threat" | "red" => Some(HazardLevel::Warning),
+            "watch" | "orange" => Some(HazardLevel::Watch),
+            "advisory" | "yellow" => Some(HazardLevel::Advisory),
+            "information" | "normal" | "green" => Some(HazardLevel::Information),
+            _ => None,
+        }
+    }
+}
+
+/// The level a quake counts as for `Hazard` rules: a warning from M7 or
+/// with a tsunami flag, a watch from M6 and an advisory from M4.5.
+pub fn quake_level(magnitude: f64, tsunami: bool) -> HazardLevel {
+    if magnitude >= 7.0 || tsunami {
+        HazardLevel::Warning
+    } else if magnitude >= 6.0 {
+        HazardLevel::Watch
+    } else if magnitude >= 4.5 {
+        HazardLevel::Advisory
+    } else {
+        HazardLevel::Information
+    }
+}
+
+/// A hazard that is new or whose level rose, as `Hazard` rules see it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HazardEvent {
+    pub kind: HazardKind,
+    pub level: HazardLevel,
+    /// The level before; `None` when the hazard is new.
+    pub previous: Option<HazardLevel>,
+    pub subject: String,
+    pub description: String,
+}
+
+impl HazardEvent {
+    /// A newly stored quake.
+    pub fn quake(quake: &QuakeRow) -> Self {
+        let tsunami = quake.data["tsunami"].as_bool().unwrap_or(false);
+        let mut description =
+            format!("M{:.1} earthquake {}", quake.magnitude, quake.data["place"].as_str().unwrap_or_default());
+        if tsunami {
+            description.push_str(" — tsunami possible");
+        }
+        HazardEvent {
+            kind: HazardKind::Earthquake,
+            level: quake_level(quake.magnitude, tsunami),
+            previous: None,
+            subject: format!("quake:{}", quake.id),
+            description,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HazardsStatus {
+    pub enabled: bool,
+    /// When the feeds were last fetched, in milliseconds since the epoch.
+    pub last_update: Option<i64>,
+    pub stored_hazards: usize,
+    /// Hazards new or changed in the last poll.
+    pub changed: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct HazardsState {
+    status: Mutex<HazardsStatus>,
+}
+
+fn update_status(app: &AppHandle, f: impl FnOnce(&mut HazardsStatus)) {
+    let state = app.state::<HazardsState>();
+    f(&mut state.status.lock().unwrap_or_else(|e| e.into_inner()));
+}
+
+fn valid_position(lat: Option<f64>, lon: Option<f64>) -> Option<(f64, f64)> {
+    let (lat, lon) = (lat?, lon?);
+    ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon)).then_some((lat, lon))
+}
+
+/// A volcano on the USGS elevated list.
+#[derive(Deserialize)]
+struct VolcanoNotice {
+    volcano_name: String,
+    vnum: Option<Value>,
+    obs_abbr: Option<String>,
+    obs_fullname: Option<String>,
+    alert_level: Option<String>,
+    color_code: Option<String>,
+    /// `2026-03-14 20:10:11`.
+    sent_utc: Option<String>,
+    notice_url: Option<String>,
+    #[serde(alias = "lat")]
+    latitude: Option<f64>,
+    #[serde(alias = "long", alias = "lon")]
+    longitude: Option<f64>,
+}
+
+fn parse_volcano_notices(text: &str) -> Result<Vec<HazardRow>, String> {
+    let notices: Vec<VolcanoNotice> = serde_json::from_str(text).map_err(|e| format!("invalid notices: {e}"))?;
+    Ok(notices
+        .into_iter()
+        .filter_map(|notice| {
+            let time = chrono::NaiveDateTime::parse_from_str(notice.sent_utc.as_deref()?.trim(), "%Y-%m-%d %H:%M:%S")
+                .ok()?
+                .and_utc()
+                .timestamp_millis();
+            let ground = notice.alert_level.as_deref().and_then(HazardLevel::parse);
+            let aviation = notice.color_code.as_deref().and_then(HazardLevel::parse);
+            let level = ground.max(aviation)?;
+            let number = match notice.vnum {
+                Some(Value::String(vnum)) => vnum,
+                Some(Value::Number(vnum)) => vnum.to_string(),
+                _ => notice.volcano_name.to_lowercase(),
+            };
+            let id = format!("volcano:{number}");
+            let position = valid_position(notice.latitude, notice.longitude);
+            let observatory = notice.obs_fullname.or(notice.obs_abbr.map(|abbr| abbr.to_uppercase()));
+            let mut description = format!(
+                "Alert level {}, aviation color code {}",
+                notice.alert_level.as_deref().unwrap_or("unassigned"),
+                notice.color_code.as_deref().unwrap_or("unassigned"),
+            );
+            if let Some(observatory) = &observatory {
+                description.push_str(&format!(" ({observatory})"));
+            }
+            Some(HazardRow {
+                data: json!({
+                    "id": id,
+                    "kind": HazardKind::Volcano,
+                    "level": level,
+                    "title": notice.volcano_name,
+                    "description": description,
+                    "time": time,
+                    "lat": position.map(|(lat, _)| lat),
+                    "lon": position.map(|(_, lon)| lon),
+                    "alertLevel": notice.alert_level,
+                    "colorCode": notice.color_code,
+                    "observatory": observatory,
+                    "sourceName": "USGS",
+                    "sourceUrl": notice.notice_url,
+                }),
+                id,
+                kind: HazardKind::Volcano,
+                level,
+                source: "usgs-volcanoes".to_string(),
+                title: format!("{} ({})", notice.volcano_name, level.as_str()),
+                time,
+                lat: position.map(|(lat, _)| lat),
+                lon: position.map(|(_, lon)| lon),
+            })
+        })
+        .collect())
+}
+
+fn local_name<'a>(node: &roxmltree::Node<'a, '_>) -> &'a str {
+    node.tag_name().name()
+}
+
+fn child<'a, 'input>(node: &roxmltree::Node<'a, 'input>, name: &str) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|n| n.is_element() && local_name(n) == name)
+}
+
+/// The text nodes under `node`, trimmed, skipping empty ones.
+fn texts<'a>(node: &roxmltree::Node<'a, '_>) -> Vec<&'a str> {
+    node.descendants().filter(|n| n.is_text()).filter_map(|n| n.text()).map(str::trim).filter(|t| !t.is_empty()).collect()
+}
+
+fn child_text(node: &roxmltree::Node, name: &str) -> Option<String> {
+    child(node, name).map(|n| texts(&n).join(" ")).filter(|t| !t.is_empty())
+}
+
+fn parse_date(date: &str) -> Option<i64> {
+    let date = date.trim();
+    DateTime::parse_from_rfc2822(date)
+        .or_else(|_| DateTime::parse_from_rfc3339(date))
+        .ok()
+        .map(|d| d.timestamp_millis())
+}
+
+fn parse_xml(text: &str) -> Result<roxmltree::Document<'_>, String> {
+    let options = roxmltree::ParsingOptions { allow_dtd: true, ..Default::default() };
+    roxmltree::Document::parse_with_options(text, options).map_err(|e| format!("invalid feed: {e}"))
+}
+
+/// Volcanoes in the Smithsonian's weekly report, one row each; those under
+/// "new activity/unrest" count as advisories.
+fn parse_volcano_reports(text: &str) -> Result<Vec<HazardRow>, String> {
+    let document = parse_xml(text)?;
+    Ok(document
+        .descendants()
+        .filter(|n| n.is_element() && local_name(n) == "item")
+        .filter_map(|item| {
+            let title = child_text(&item, "title")?;
+            let time = parse_date(&child_text(&item, "pubDate")?)?;
+            // "Etna (Italy) - Report for 1 October-7 October 2025".
+            let name = title.split(" - ").next().unwrap_or(&title).trim().to_string();
+            let description = child_text(&item, "description").unwrap_or_default();
+            let unrest = [&title, &description].iter().any(|t| t.to_lowercase().contains("new activity"));
+            let level = if unrest { HazardLevel::Advisory } else { HazardLevel::Information };
+            let point = item
+                .children()
+                .find(|n| n.is_element() && local_name(n) == "point" && n.tag_name().namespace() == Some(GEORSS_NS))
+                .map(|n| texts(&n).join(" "));
+            let mut coordinates = point.iter().flat_map(|p| p.split_whitespace()).map(str::parse::<f64>);
+            let position = match (coordinates.next(), coordinates.next()) {
+                (Some(Ok(lat)), Some(Ok(lon))) => valid_position(Some(lat), Some(lon)),
+                _ => None,
+            };
+            let link = child_text(&item, "link");
+            let id = format!("gvp:{}", name.to_lowercase());
+            Some(HazardRow {
+                data: json!({
+                    "id": id,
+                    "kind": HazardKind::Volcano,
+                    "level": level,
+                    "title": name,
+                    "description": description,
+                    "time": time,
+                    "lat": position.map(|(lat, _)| lat),
+                    "lon": position.map(|(_, lon)| lon),
+                    "sourceName": "Smithsonian GVP",
+                    "sourceUrl": link,
+                }),
+                id,
+                kind: HazardKind::Volcano,
+                level,
+                source: "gvp".to_string(),
+                title,
+                time,
+                lat: position.map(|(lat, _)| lat),
+                lon: position.map(|(_, lon)| lon),
+            })
+        })
+        .collect())
+}
+
+/// The text after a `<strong>Label:</strong>` in a bulletin summary.
+fn summary_field<'a>(fields: &[&'a str], label: &str) -> Option<&'a str> {
+    let at = fields.iter().position(|f| f.trim_end_matches(':').trim() == label)?;
+    fields.get(at + 1).copied()
+}
+
+/// Bulletins from a tsunami warning center's Atom feed.
+fn parse_tsunami_feed(text: &str, center: &str) -> Result<Vec<HazardRow>, String> {
+    let document = parse_xml(text)?;
+    Ok(document
+        .root_element()
+        .children()
+        .filter(|n| n.is_element() && local_name(n) == "entry")
+        .filter_map(|entry| {
+            let entry_id = child_text(&entry, "id")?;
+            let region = child_text(&entry, "title")?;
+            let time = parse_date(&child_text(&entry, "updated")?)?;
+            let summary = child(&entry, "summary").map(|n| texts(&n)).unwrap_or_default();
+            let category = summary_field(&summary, "Category")?;
+            let level = HazardLevel::parse(category)?;
+            // "6.5(Mwp)".
+            let magnitude = summary_field(&summary, "Preliminary Magnitude").and_then(|m| {
+                let end = m.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(m.len());
+                m[..end].parse::<f64>().ok()
+            });
+            let coordinate = |name| child_text(&entry, name).and_then(|t| t.parse::<f64>().ok());
+            let position = valid_position(coordinate("lat"), coordinate("long"));
+            let link = entry
+                .children()
+                .filter(|n| n.is_element() && local_name(n) == "link")
+                .find_map(|n| n.attribute("href"))
+                .map(str::to_string);
+            let mut description = format!("Tsunami {} for {region}", category.to_lowercase());
+            if let Some(magnitude) = magnitude {
+                description.push_str(&format!(" after an M{magnitude:.1} earthquake"));
+            }
+            let id = format!("tsunami:{entry_id}");
+            Some(HazardRow {
+                data: json!({
+                    "id": id,
+                    "kind": HazardKind::Tsunami,
+                    "level": level,
+                    "title": region,
+                    "description": description,
+                    "time": time,
+                    "lat": position.map(|(lat, _)| lat),
+                    "lon": position.map(|(_, lon)| lon),
+                    "category": category,
+                    "magnitude": magnitude,
+                    "center": center.to_uppercase(),
+                    "sourceName": "NOAA Tsunami Warning Center",
+                    "sourceUrl": link,
+                }),
+                id,
+                kind: HazardKind::Tsunami,
+                level,
+                source: center.to_string(),
+                title: format!("Tsunami {}: {region}", category.to_lowercase()),
+                time,
+                lat: position.map(|(lat, _)| lat),
+                lon: position.map(|(_, lon)| lon),
+            })
+        })
+        .collect())
+}
+
+/// The stored hazards that are new or raised, reported within
+/// `ALERT_WITHIN` of `now`.
+fn changes(hazards: &[HazardRow], previous: &[Option<HazardLevel>], now: i64) -> Vec<HazardEvent> {
+    hazards
+        .iter()
+        .zip(previous)
+        .filter(|(hazard, previous)| previous.is_none_or(|p| p < hazard.level))
+        .filter(|(hazard, _)| now - hazard.time <= ALERT_WITHIN.as_millis() as i64)
+        .map(|(hazard, previous)| HazardEvent {
+            kind: hazard.kind,
+            level: hazard.level,
+            previous: *previous,
+            subject: hazard.id.clone(),
+            description: hazard.data["description"].as_str().unwrap_or(&hazard.title).to_string(),
+        })
+        .collect()
+}
+
+/// Whether a new or raised hazard is worth a notification, and how urgent
+/// it is.
+fn notification(event: &HazardEvent, config: &HazardsConfig) -> Option<NotificationUrgency> {
+    if config.notify_level.is_none_or(|min| event.level < min) {
+        return None;
+    }
+    Some(if event.level == HazardLevel::Warning {
+        NotificationUrgency::Critical
+    } else {
+        NotificationUrgency::Normal
+    })
+}
+
+fn notify(app: &AppHandle, event: &HazardEvent, urgency: NotificationUrgency) {
+    if crate::digest::holds(app, urgency) {
+        return;
+    }
+    let title = format!("{} {}", event.kind.label(), event.level.as_str());
+    let action = format!("hazard:{}", event.subject);
+    if let Err(err) = crate::notifications::send(app, "main", &title, &event.description, urgency, Some(&action)) {
+        tracing::warn!(target: "app", "hazard notification failed: {err}");
+    }
+}
+
+async fn get(client: &reqwest::Client, url: &str) -> Result<String, String> {
+    client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("{url}: request failed: {e}"))?
+        .text()
+        .await
+        .map_err(|e| format!("{url}: request failed: {e}"))
+}
+
+/// One poll of the tsunami feeds, and of the volcano feeds too when
+/// `volcanoes` is set. Feeds that fail are reported together after the
+/// others are stored.
+async fn update(app: &AppHandle, client: &reqwest::Client, config: &HazardsConfig, volcanoes: bool) -> Result<(), String> {
+    let mut hazards = Vec::new();
+    let mut errors = Vec::new();
+    let mut collect = |result: Result<Vec<HazardRow>, String>| match result {
+        Ok(rows) => hazards.extend(rows),
+        Err(err) => errors.push(err),
+    };
+    if config.tsunamis {
+        for (center, url) in TSUNAMI_FEEDS {
+            collect(get(client, url).await.and_then(|text| parse_tsunami_feed(&text, center)));
+        }
+    }
+    if volcanoes {
+        collect(get(client, VOLCANO_NOTICES_URL).await.and_then(|text| parse_volcano_notices(&text)));
+        collect(get(client, VOLCANO_REPORTS_URL).await.and_then(|text| parse_volcano_reports(&text)));
+    }
+
+    let handle = app.clone();
+    let (hazards, previous, stored) = crate::runtime::blocking(move || {
+        let store = handle.state::<EventStore>();
+        let previous = store.upsert_hazards(&hazards)?;
+        let cutoff = crate::unix_timestamp_secs().saturating_sub(RETENTION_DAYS * 24 * 60 * 60) as i64 * 1000;
+        store.prune_hazards(cutoff)?;
+        Ok::<_, String>((hazards, previous, store.count_hazards()?))
+    })
+    .await?;
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let changed: Vec<Value> = hazards
+        .iter()
+        .zip(&previous)
+        .filter(|(hazard, previous)| **previous != Some(hazard.level))
+        .map(|(hazard, _)| hazard.data.clone())
+        .collect();
+    let events = changes(&hazards, &previous, now);
+    tracing::debug!(target: "app", changed = changed.len(), raised = events.len(), "hazard feeds polled");
+    update_status(app, |status| {
+        status.last_update = Some(now);
+        status.changed = changed.len();
+        status.stored_hazards = stored;
+    });
+    for event in &events {
+        if let Some(urgency) = notification(event, config) {
+            notify(app, event, urgency);
+        }
+    }
+    crate::alerts::observe_hazards(app, &events);
+    if !changed.is_empty() {
+        let _ = app.emit(HAZARDS_EVENT, changed);
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+struct Poller {
+    app: AppHandle,
+    client: reqwest::Client,
+    config: HazardsConfig,
+    volcanoes_polled: Option<Instant>,
+}
+
+impl crate::poll::Poller for Poller {
+    async fn round(&mut self) -> Duration {
+        let volcanoes =
+            self.config.volcanoes && self.volcanoes_polled.is_none_or(|at| at.elapsed() >= VOLCANO_INTERVAL);
+        if volcanoes {
+            self.volcanoes_polled = Some(Instant::now());
+        }
+        match update(&self.app, &self.client, &self.config, volcanoes).await {
+            Ok(()) => {
+                update_status(&self.app, |status| status.error = None);
+                UPDATE_INTERVAL
+            }
+            Err(err) => {
+                tracing::warn!(target: "app", "hazard update failed: {err}");
+                update_status(&self.app, |status| status.error = Some(err));
+                ERROR_RETRY
+            }
+        }
+    }
+}
+
+async fn run(app: AppHandle, config: HazardsConfig) {
+    let client = match reqwest::Client::builder().use_native_tls().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!(target: "app", "hazard polling unavailable: {err}");
+            return;
+        }
+    };
+    let poller = Poller {
+        app: app.clone(),
+        client,
+        config,
+        volcanoes_polled: None,
+    };
+    crate::poll::run(&app, poller).await;
+}
+
+/// Start polling if `hazards.enabled` is set and any feed is on.
+pub fn start(app: &AppHandle) {
+    let config = app.state::<DesktopConfigState>().snapshot().hazards;
+    let enabled = config.enabled && (config.volcanoes || config.tsunamis);
+    update_status(app, |status| status.enabled = enabled);
+    if enabled {
+        tauri::async_runtime::spawn(run(app.clone(), config));
+    }
+}
+
+#[tauri::command]
+pub fn get_hazards_status(webview: Webview, state: tauri::State<'_, HazardsState>) -> Result<HazardsStatus, String> {
+    crate::metrics::of(&webview).observe("get_hazards_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.status.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+/// Stored volcano notices and tsunami bulletins, newest first.
+#[tauri::command]
+pub async fn query_hazards(webview: Webview, app: AppHandle, query: Option<HazardQuery>) -> Result<Vec<Value>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("query_hazards", async move {
+            crate::require_trusted_window(webview.label())?;
+            let query = query.unwrap_or_default();
+            crate::runtime::blocking(move || app.state::<EventStore>().hazards(&query)).await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod hazards_tests {
+    use super::*;
+
+    const NOTICES: &str = r#"[
+        {
+            "obs_fullname": "Alaska Volcano Observatory", "obs_abbr": "avo", "volcano_name": "Great Sitkin",
+            "vnum": "311120", "notice_type_cd": "DU", "sent_utc": "2026-03-14 20:10:11",
+            "color_code": "ORANGE", "alert_level": "ADVISORY",
+            "notice_url": "https://volcanoes.usgs.gov/hans-public/notice/DOI-USGS-AVO-2026-03-14T20:10:11+00:00",
+            "latitude": 52.076, "longitude": -176.13
+        },
+        { "volcano_name": "Kilauea", "vnum": 332010, "sent_utc": "2026-03-13 08:00:00", "alert_level": "WATCH" },
+        { "volcano_name": "Unknown", "sent_utc": "2026-03-13 08:00:00", "color_code": "UNASSIGNED" }
+    ]"#;
+
+    const REPORTS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <rss version="2.0" xmlns:georss="http://www.georss.org/georss">
+          <channel>
+            <title>Weekly Volcanic Activity Report</title>
+            <item>
+              <title>Etna (Italy) - Report for 11 March-17 March 2026 - New Activity/Unrest</title>
+              <link>https://volcano.si.edu/showreport.cfm?wvar=GVP.WVAR20260311-211060</link>
+              <description>Strombolian activity at the summit craters.</description>
+              <pubDate>Wed, 18 Mar 2026 00:00:00 GMT</pubDate>
+              <georss:point>37.748 14.999</georss:point>
+            </item>
+            <item>
+              <title>Sakurajima (Japan) - Report for 11 March-17 March 2026</title>
+              <description>Ongoing eruptive activity.</description>
+              <pubDate>Wed, 18 Mar 2026 00:00:00 GMT</pubDate>
+            </item>
+          </channel>
+        </rss>"#;
+
+    const BULLETINS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <feed xmlns="http://www.w3.org/2005/Atom" xmlns:geo="http://www.w3.org/2003/01/geo/wgs84_pos#">
+          <title>PTWC Tsunami Messages</title>
+          <entry>
+            <id>urn:uuid:1b7e8a3c-0000-4000-8000-000000000001</id>
+            <title>Tonga Islands</title>
+            <updated>2026-03-14T10:05:00Z</updated>
+            <geo:lat>-20.100</geo:lat>
+            <geo:long>-174.200</geo:long>
+            <summary type="xhtml"><div xmlns="http://www.w3.org/1999/xhtml">
+              <strong>Category:</strong> Threat<br/>
+              <strong>Bulletin Issue Time: </strong> 2026.03.14 10:05:00 UTC<br/>
+              <strong>Preliminary Magnitude: </strong>7.6(Mwp)<br/>
+            </div></summary>
+            <link rel="related" title="Bulletin" type="text/plain" href="https://tsunami.gov/events/PHEB/2026/03/14/tonga.txt"/>
+          </entry>
+          <entry>
+            <id>urn:uuid:1b7e8a3c-0000-4000-8000-000000000002</id>
+            <title>Off Coast of Northern Chile</title>
+            <updated>2026-03-12T02:00:00Z</updated>
+            <summary type="xhtml"><div xmlns="http://www.w3.org/1999/xhtml">
+              <strong>Category:</strong> Information<br/>
+            </div></summary>
+          </entry>
+        </feed>"#;
+
+    #[test]
+    fn parses_volcano_notices_and_reports() {
+        let notices = parse_volcano_notices(NOTICES).unwrap();
+        assert_eq!(notices.len(), 2);
+        let sitkin = &notices[0];
+        // The aviation color code outranks the ground alert level.
+        assert_eq!((sitkin.id.as_str(), sitkin.level), ("volcano:311120", HazardLevel::Watch));
+        assert_eq!((sitkin.lat, sitkin.lon), (Some(52.076), Some(-176.13)));
+        assert_eq!(sitkin.data["observatory"], json!("Alaska Volcano Observatory"));
+        assert_eq!(sitkin.time, 1773519011000);
+        assert_eq!((notices[1].id.as_str(), notices[1].lat), ("volcano:332010", None));
+        assert!(parse_volcano_notices("<html>").is_err());
+
+        let reports = parse_volcano_reports(REPORTS).unwrap();
+        assert_eq!(reports.len(), 2);
+        assert_eq!((reports[0].id.as_str(), reports[0].level), ("gvp:etna (italy)", HazardLevel::Advisory));
+        assert_eq!((reports[0].lat, reports[0].lon), (Some(37.748), Some(14.999)));
+        assert_eq!((reports[1].level, reports[1].lat), (HazardLevel::Information, None));
+    }
+
+    #[test]
+    fn parses_tsunami_bulletins() {
+        let bulletins = parse_tsunami_feed(BULLETINS, "ptwc").unwrap();
+        assert_eq!(bulletins.len(), 2);
+        let tonga = &bulletins[0];
+        assert_eq!(tonga.id, "tsunami:urn:uuid:1b7e8a3c-0000-4000-8000-000000000001");
+        assert_eq!((tonga.level, tonga.lat, tonga.lon), (HazardLevel::Warning, Some(-20.1), Some(-174.2)));
+        assert_eq!(tonga.data["magnitude"], json!(7.6));
+        assert_eq!(tonga.data["description"], json!("Tsunami threat for Tonga Islands after an M7.6 earthquake"));
+        assert_eq!(tonga.data["sourceUrl"], json!("https://tsunami.gov/events/PHEB/2026/03/14/tonga.txt"));
+        assert_eq!(bulletins[1].level, HazardLevel::Information);
+        assert!(parse_tsunami_feed("not xml", "ntwc").is_err());
+    }
+
+    #[test]
+    fn alerts_on_new_and_raised_recent_hazards() {
+        let bulletins = parse_tsunami_feed(BULLETINS, "ptwc").unwrap();
+        let now = 1773482700000 + 60_000;
+        let events = changes(&bulletins, &[None, None], now);
+        // The Chilean statement is two days old.
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].kind, events[0].level), (HazardKind::Tsunami, HazardLevel::Warning));
+        assert!(changes(&bulletins, &[Some(HazardLevel::Warning), None], now).is_empty());
+        assert_eq!(changes(&bulletins, &[Some(HazardLevel::Watch), None], now)[0].previous, Some(HazardLevel::Watch));
+
+        let config = HazardsConfig::default();
+        assert_eq!(notification(&events[0], &config), Some(NotificationUrgency::Critical));
+        let advisory = HazardEvent { level: HazardLevel::Advisory, ..events[0].clone() };
+        assert_eq!(notification(&advisory, &config), None);
+        assert_eq!(quake_level(6.4, true), HazardLevel::Warning);
+        assert_eq!(quake_level(6.4, false), HazardLevel::Watch);
+    }
+}
@@ -0,0 +1,221 @@
+//! System idle detection. A background thread reads how long it has been
+//! since the last keyboard or mouse input anywhere on the machine (not just
+//! in our windows) and, once that passes `idle.threshold_mins`, broadcasts
+//! `app://idle` with `true`; the dashboard stretches its refresh intervals
+//! until input resumes and `app://idle` arrives with `false`. Saves
+//! bandwidth on machines left on overnight.
+//!
+//! Idle time comes from `GetLastInputInfo` on Windows, the HID system event
+//! source on macOS, and GNOME's Mutter IdleMonitor or the freedesktop
+//! ScreenSaver service on Linux. Where none is available nothing is emitted.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+use crate::config::IdleConfig;
+
+pub const IDLE_EVENT: &str = "app://idle";
+
+/// Short enough that returning to the keyboard restores full cadence within
+/// seconds.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+pub struct IdleState {
+    idle: AtomicBool,
+}
+
+pub fn is_idle(app: &AppHandle) -> bool {
+    app.try_state::<IdleState>()
+        .is_some_and(|state| state.idle.load(Ordering::SeqCst))
+}
+
+fn set_idle(app: &AppHandle, idle: bool, idle_for: Duration) {
+    let Some(state) = app.try_state::<IdleState>() else {
+        return;
+    };
+    if state.idle.swap(idle, Ordering::SeqCst) == idle {
+        return;
+    }
+    tracing::info!(target: "app", idle, idle_for_secs = idle_for.as_secs(), "system idle state changed");
+    let _ = app.emit(IDLE_EVENT, idle);
+}
+
+fn threshold(config: &IdleConfig) -> Duration {
+    Duration::from_secs(config.threshold_mins.max(1) * 60)
+}
+
+fn run(app: AppHandle, config: IdleConfig) {
+    let threshold = threshold(&config);
+    let clock = platform::IdleClock::new();
+    let mut unavailable_logged = false;
+    loop {
+        match clock.idle_time() {
+            Some(idle_for) => set_idle(&app, idle_for >= threshold, idle_for),
+            None if !unavailable_logged => {
+                unavailable_logged = true;
+                tracing::info!(target: "app", "system idle time unavailable; refreshes stay at full rate");
+            }
+            None => {}
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Start the idle monitor thread if enabled in the desktop config.
+pub fn start(app: &AppHandle, config: IdleConfig) {
+    if !config.enabled {
+        return;
+    }
+    let app = app.clone();
+    if let Err(e) = std::thread::Builder::new()
+        .name("idle-monitor".into())
+        .spawn(move || run(app, config))
+    {
+        tracing::warn!(target: "app", "failed to start idle monitor: {e}");
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::time::Duration;
+
+    use windows::Win32::System::SystemInformation::GetTickCount;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    pub struct IdleClock;
+
+    impl IdleClock {
+        pub fn new() -> Self {
+            IdleClock
+        }
+
+        pub fn idle_time(&self) -> Option<Duration> {
+            let mut info = LASTINPUTINFO {
+                cbSize: size_of::<LASTINPUTINFO>() as u32,
+                dwTime: 0,
+            };
+            if !unsafe { GetLastInputInfo(&mut info) }.as_bool() {
+                return None;
+            }
+            // Both are 32-bit tick counts, which wrap every 49.7 days.
+            let elapsed = unsafe { GetTickCount() }.wrapping_sub(info.dwTime);
+            Some(Duration::from_millis(elapsed.into()))
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::time::Duration;
+
+    const HID_SYSTEM_STATE: i32 = 1;
+    const ANY_INPUT_EVENT: u32 = !0;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(source_state: i32, event_type: u32) -> f64;
+    }
+
+    pub struct IdleClock;
+
+    impl IdleClock {
+        pub fn new() -> Self {
+            IdleClock
+        }
+
+        pub fn idle_time(&self) -> Option<Duration> {
+            let secs = unsafe { CGEventSourceSecondsSinceLastEventType(HID_SYSTEM_STATE, ANY_INPUT_EVENT) };
+            Duration::try_from_secs_f64(secs).ok()
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::time::Duration;
+
+    use zbus::blocking::Connection;
+
+    pub struct IdleClock {
+        session: Option<Connection>,
+    }
+
+    impl IdleClock {
+        pub fn new() -> Self {
+            IdleClock {
+                session: Connection::session().ok(),
+            }
+        }
+
+        pub fn idle_time(&self) -> Option<Duration> {
+            let session = self.session.as_ref()?;
+            // GNOME, on X11 and Wayland alike; milliseconds.
+            if let Ok(reply) = session.call_method(
+                Some("org.gnome.Mutter.IdleMonitor"),
+                "/org/gnome/Mutter/IdleMonitor/Core",
+                Some("org.gnome.Mutter.IdleMonitor"),
+                "GetIdletime",
+                &(),
+            ) {
+                if let Ok(ms) = reply.body().deserialize::<u64>() {
+                    return Some(Duration::from_millis(ms));
+                }
+            }
+            // KDE and other freedesktop screen savers; seconds.
+            let reply = session
+                .call_method(
+                    Some("org.freedesktop.ScreenSaver"),
+                    "/org/freedesktop/ScreenSaver",
+                    Some("org.freedesktop.ScreenSaver"),
+                    "GetSessionIdleTime",
+                    &(),
+                )
+                .ok()?;
+            let secs = reply.body().deserialize::<u32>().ok()?;
+            Some(Duration::from_secs(secs.into()))
+        }
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+mod platform {
+    use std::time::Duration;
+
+    pub struct IdleClock;
+
+    impl IdleClock {
+        pub fn new() -> Self {
+            IdleClock
+        }
+
+        pub fn idle_time(&self) -> Option<Duration> {
+            None
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_system_idle(webview: Webview, app: AppHandle) -> Result<bool, String> {
+    crate::metrics::of(&webview).observe("get_system_idle", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(is_idle(&app))
+    })
+}
+
+#[cfg(test)]
+mod idle_tests {
+    use super::*;
+
+    #[test]
+    fn threshold_is_at_least_a_minute() {
+        let config = |threshold_mins| IdleConfig {
+            enabled: true,
+            threshold_mins,
+        };
+        assert_eq!(threshold(&config(0)), Duration::from_secs(60));
+        assert_eq!(threshold(&config(15)), Duration::from_secs(900));
+    }
+}
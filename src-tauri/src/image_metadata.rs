@@ -0,0 +1,404 @@
+//! Metadata of images dropped onto the dashboard or picked for inspection,
+//! read locally: when the photo was taken, the camera and lens, and where,
+//! so the map can jump to GPS-tagged photos. JPEG, PNG, TIFF, WebP and HEIF
+//! files are read; every tag of the primary image is listed as well.
+//!
+//! On request a clean copy is written next to the original (`photo.jpg`
+//! becomes `photo-clean.jpg`), without the EXIF, XMP, IPTC and comment
+//! segments of a JPEG or the `eXIf` and text chunks of a PNG. The image
+//! data itself is copied untouched, and the original is never modified.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use exif::{Exif, In, Reader, Tag};
+use serde::{Deserialize, Serialize};
+use tauri::Webview;
+
+const MAX_BYTES: u64 = 50 * 1024 * 1024;
+const MAX_VALUE_CHARS: usize = 200;
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+    Tiff,
+    Webp,
+    Heif,
+}
+
+impl ImageFormat {
+    fn sniff(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0xFF, 0xD8]) {
+            Some(ImageFormat::Jpeg)
+        } else if bytes.starts_with(PNG_SIGNATURE) {
+            Some(ImageFormat::Png)
+        } else if bytes.starts_with(b"II*\0") || bytes.starts_with(b"MM\0*") {
+            Some(ImageFormat::Tiff)
+        } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+            Some(ImageFormat::Webp)
+        } else if bytes.get(4..8) == Some(b"ftyp") {
+            Some(ImageFormat::Heif)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageMetadata {
+    pub format: Option<ImageFormat>,
+    /// When the photo was taken, `YYYY-MM-DDTHH:MM:SS` plus the UTC offset
+    /// if the camera recorded one.
+    pub taken: Option<String>,
+    pub camera: Option<String>,
+    pub lens: Option<String>,
+    pub software: Option<String>,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    /// Meters above sea level.
+    pub altitude: Option<f64>,
+    /// Degrees the camera faced.
+    pub direction: Option<f64>,
+    /// Every tag of the primary image, by name.
+    pub fields: BTreeMap<String, String>,
+    /// Where the clean copy was written, when one was asked for.
+    pub stripped: Option<String>,
+}
+
+fn text(exif: &Exif, tag: Tag) -> Option<String> {
+    match &exif.get_field(tag, In::PRIMARY)?.value {
+        exif::Value::Ascii(values) => {
+            let value = String::from_utf8_lossy(values.first()?).trim().to_string();
+            (!value.is_empty()).then_some(value)
+        }
+        _ => None,
+    }
+}
+
+fn rational(exif: &Exif, tag: Tag) -> Option<f64> {
+    match &exif.get_field(tag, In::PRIMARY)?.value {
+        exif::Value::Rational(values) => Some(values.first()?.to_f64()).filter(|value| value.is_finite()),
+        _ => None,
+    }
+}
+
+/// Degrees, minutes and seconds in `tag`, negative where `reference` is
+/// `S` or `W`.
+fn coordinate(exif: &Exif, tag: Tag, reference: Tag, max: f64) -> Option<f64> {
+    let exif::Value::Rational(parts) = &exif.get_field(tag, In::PRIMARY)?.value else {
+        return None;
+    };
+    if parts.is_empty() {
+        return None;
+    }
+    let degrees: f64 = parts.iter().zip([1.0, 60.0, 3600.0]).map(|(part, scale)| part.to_f64() / scale).sum();
+    let sign = match text(exif, reference).as_deref() {
+        Some("S" | "W") => -1.0,
+        _ => 1.0,
+    };
+    (degrees.is_finite() && degrees <= max).then_some(sign * degrees)
+}
+
+fn taken(exif: &Exif) -> Option<String> {
+    let field = exif.get_field(Tag::DateTimeOriginal, In::PRIMARY).or_else(|| exif.get_field(Tag::DateTime, In::PRIMARY))?;
+    let exif::Value::Ascii(values) = &field.value else {
+        return None;
+    };
+    let mut time = exif::DateTime::from_ascii(values.first()?).ok()?;
+    if let Some(exif::Value::Ascii(offset)) = exif.get_field(Tag::OffsetTimeOriginal, In::PRIMARY).map(|f| &f.value) {
+        let _ = offset.first().map(|offset| time.parse_offset(offset));
+    }
+    let mut taken = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        time.year, time.month, time.day, time.hour, time.minute, time.second
+    );
+    if let Some(offset) = time.offset {
+        let sign = if offset < 0 { '-' } else { '+' };
+        taken.push_str(&format!("{sign}{:02}:{:02}", offset.abs() / 60, offset.abs() % 60));
+    }
+    Some(taken)
+}
+
+fn camera(exif: &Exif) -> Option<String> {
+    let model = text(exif, Tag::Model);
+    match (text(exif, Tag::Make), model) {
+        (Some(make), Some(model)) if model.to_lowercase().starts_with(&make.to_lowercase()) => Some(model),
+        (Some(make), Some(model)) => Some(format!("{make} {model}")),
+        (make, model) => model.or(make),
+    }
+}
+
+fn metadata(exif: &Exif) -> ImageMetadata {
+    let fields = exif
+        .fields()
+        .filter(|field| field.ifd_num == In::PRIMARY && field.tag != Tag::MakerNote)
+        .map(|field| {
+            let value = field.display_value().with_unit(exif).to_string();
+            (field.tag.to_string(), value.chars().take(MAX_VALUE_CHARS).collect())
+        })
+        .collect();
+    let altitude = rational(exif, Tag::GPSAltitude).map(|altitude| {
+        match exif.get_field(Tag::GPSAltitudeRef, In::PRIMARY).and_then(|f| f.value.get_uint(0)) {
+            Some(1) => -altitude,
+            _ => altitude,
+        }
+    });
+    ImageMetadata {
+        taken: taken(exif),
+        camera: camera(exif),
+        lens: text(exif, Tag::LensModel),
+        software: text(exif, Tag::Software),
+        lat: coordinate(exif, Tag::GPSLatitude, Tag::GPSLatitudeRef, 90.0),
+        lon: coordinate(exif, Tag::GPSLongitude, Tag::GPSLongitudeRef, 180.0),
+        altitude,
+        direction: rational(exif, Tag::GPSImgDirection),
+        fields,
+        ..ImageMetadata::default()
+    }
+}
+
+/// The metadata of an image file's contents. An image without EXIF data
+/// has none, which is not an error.
+pub fn read(bytes: &[u8]) -> Result<ImageMetadata, String> {
+    let format = ImageFormat::sniff(bytes).ok_or("Not a JPEG, PNG, TIFF, WebP or HEIF image")?;
+    let mut metadata = match Reader::new().read_from_container(&mut Cursor::new(bytes)) {
+        Ok(exif) => metadata(&exif),
+        Err(exif::Error::NotFound(_)) => ImageMetadata::default(),
+        Err(err) => return Err(format!("Failed to read image metadata: {err}")),
+    };
+    metadata.format = Some(format);
+    Ok(metadata)
+}
+
+/// A JPEG without its APP1 (EXIF, XMP), APP2 multi-picture (MPF), APP13
+/// (IPTC) and comment segments, cut at its first end of image: whatever
+/// follows, like the previews a multi-picture file appends with their own
+/// EXIF, is dropped.
+fn strip_jpeg(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let malformed = || "Malformed JPEG".to_string();
+    let mut clean = bytes[..2].to_vec();
+    let mut at = 2;
+    loop {
+        match bytes.get(at..at + 2).ok_or_else(malformed)? {
+            [0xFF, 0xFF] => at += 1,
+            [0xFF, 0xD9] => {
+                clean.extend_from_slice(&bytes[at..at + 2]);
+                return Ok(clean);
+            }
+            [0xFF, 0x01 | 0xD0..=0xD7] => {
+                clean.extend_from_slice(&bytes[at..at + 2]);
+                at += 2;
+            }
+            [0xFF, marker] => {
+                let length = bytes.get(at + 2..at + 4).ok_or_else(malformed)?;
+                let end = at + 2 + usize::from(u16::from_be_bytes([length[0], length[1]]));
+                if end < at + 4 || end > bytes.len() {
+                    return Err(malformed());
+                }
+                let mpf = *marker == 0xE2 && bytes[at + 4..end].starts_with(b"MPF\0");
+                if !matches!(marker, 0xE1 | 0xED | 0xFE) && !mpf {
+                    clean.extend_from_slice(&bytes[at..end]);
+                }
+                at = end;
+                if *marker == 0xDA {
+                    // Scan data runs to the next marker; a 0xFF in it is
+                    // followed by a stuffed zero or a restart marker.
+                    let start = at;
+                    loop {
+                        match bytes.get(at..at + 2).ok_or_else(malformed)? {
+                            [0xFF, 0x00 | 0xD0..=0xD7] => at += 2,
+                            [0xFF, _] => break,
+                            _ => at += 1,
+                        }
+                    }
+                    clean.extend_from_slice(&bytes[start..at]);
+                }
+            }
+            _ => return Err(malformed()),
+        }
+    }
+}
+
+/// A PNG without its `eXIf`, text and timestamp chunks.
+fn strip_png(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let malformed = || "Malformed PNG".to_string();
+    let mut clean = PNG_SIGNATURE.to_vec();
+    let mut at = PNG_SIGNATURE.len();
+    while at < bytes.len() {
+        let header = bytes.get(at..at + 8).ok_or_else(malformed)?;
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let end = at.checked_add(12 + length).filter(|end| *end <= bytes.len()).ok_or_else(malformed)?;
+        let kind = &header[4..8];
+        if !matches!(kind, b"eXIf" | b"tEXt" | b"zTXt" | b"iTXt" | b"tIME") {
+            clean.extend_from_slice(&bytes[at..end]);
+        }
+        at = end;
+        if kind == b"IEND" {
+            break;
+        }
+    }
+    Ok(clean)
+}
+
+/// The image without its metadata.
+pub fn strip(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    match ImageFormat::sniff(bytes) {
+        Some(ImageFormat::Jpeg) => strip_jpeg(bytes),
+        Some(ImageFormat::Png) => strip_png(bytes),
+        _ => Err("Only JPEG and PNG images can be stripped".to_string()),
+    }
+}
+
+/// `photo-clean.jpg` next to `photo.jpg`, numbered if that is taken.
+fn clean_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    let extension = path.extension().map(|ext| format!(".{}", ext.to_string_lossy())).unwrap_or_default();
+    (1..)
+        .map(|n| match n {
+            1 => path.with_file_name(format!("{stem}-clean{extension}")),
+            n => path.with_file_name(format!("{stem}-clean-{n}{extension}")),
+        })
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_default()
+}
+
+/// Read an image file, and write a clean copy of it when `strip` is set.
+pub fn inspect(path: &Path, strip_copy: bool) -> Result<ImageMetadata, String> {
+    let size = fs::metadata(path).map_err(|e| format!("Failed to read image: {e}"))?.len();
+    if size > MAX_BYTES {
+        return Err(format!("Image is too large ({size} bytes, limit {MAX_BYTES})"));
+    }
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read image: {e}"))?;
+    let mut metadata = read(&bytes)?;
+    if strip_copy {
+        let clean = strip(&bytes)?;
+        let target = clean_path(path);
+        fs::write(&target, clean).map_err(|e| format!("Failed to write {}: {e}", target.display()))?;
+        tracing::info!(target: "app", path = %target.display(), "wrote image without metadata");
+        metadata.stripped = Some(target.to_string_lossy().into_owned());
+    }
+    Ok(metadata)
+}
+
+/// Timestamp, camera, GPS position and all tags of the image at `path`.
+/// With `strip` a copy without metadata is also written next to it.
+#[tauri::command]
+pub async fn read_image_metadata(webview: Webview, path: String, strip: Option<bool>) -> Result<ImageMetadata, String> {
+    crate::metrics::of(&webview)
+        .observe_async("read_image_metadata", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || inspect(Path::new(&path), strip.unwrap_or(false))).await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod image_metadata_tests {
+    use super::*;
+    use exif::experimental::Writer;
+    use exif::{Field, Rational, Value};
+
+    fn ascii(tag: Tag, text: &str) -> Field {
+        Field {
+            tag,
+            ifd_num: In::PRIMARY,
+            value: Value::Ascii(vec![text.as_bytes().to_vec()]),
+        }
+    }
+
+    fn rationals(tag: Tag, values: &[(u32, u32)]) -> Field {
+        let values = values.iter().map(|&(num, denom)| Rational { num, denom }).collect();
+        Field {
+            tag,
+            ifd_num: In::PRIMARY,
+            value: Value::Rational(values),
+        }
+    }
+
+    /// A JPEG header carrying these EXIF fields, a comment and a JFIF
+    /// segment, followed by scan data.
+    fn jpeg(fields: &[Field]) -> Vec<u8> {
+        let mut writer = Writer::new();
+        for field in fields {
+            writer.push_field(field);
+        }
+        let mut tiff = Cursor::new(Vec::new());
+        writer.write(&mut tiff, false).unwrap();
+        let segment = |marker: u8, payload: &[u8]| {
+            let mut bytes = vec![0xFF, marker];
+            bytes.extend_from_slice(&((payload.len() + 2) as u16).to_be_bytes());
+            bytes.extend_from_slice(payload);
+            bytes
+        };
+        let mut bytes = vec![0xFF, 0xD8];
+        bytes.extend(segment(0xE0, b"JFIF\0\x01\x01"));
+        bytes.extend(segment(0xE1, &[b"Exif\0\0".as_slice(), tiff.get_ref()].concat()));
+        bytes.extend(segment(0xFE, b"shot by ops"));
+        bytes.extend([0xFF, 0xDA, 0x00, 0x02, 0x12, 0x34, 0xFF, 0xD9]);
+        bytes
+    }
+
+    #[test]
+    fn reads_time_camera_and_position() {
+        let bytes = jpeg(&[
+            ascii(Tag::Make, "Canon"),
+            ascii(Tag::Model, "Canon EOS R5"),
+            ascii(Tag::DateTimeOriginal, "2024:03:01 14:05:09"),
+            ascii(Tag::OffsetTimeOriginal, "+02:00"),
+            ascii(Tag::GPSLatitudeRef, "S"),
+            rationals(Tag::GPSLatitude, &[(33, 1), (52, 1), (3600, 100)]),
+            ascii(Tag::GPSLongitudeRef, "E"),
+            rationals(Tag::GPSLongitude, &[(151, 1), (12, 1), (0, 1)]),
+        ]);
+        let metadata = read(&bytes).unwrap();
+        assert_eq!(metadata.format, Some(ImageFormat::Jpeg));
+        assert_eq!(metadata.camera.as_deref(), Some("Canon EOS R5"));
+        assert_eq!(metadata.taken.as_deref(), Some("2024-03-01T14:05:09+02:00"));
+        assert!((metadata.lat.unwrap() + 33.876_666).abs() < 1e-5);
+        assert!((metadata.lon.unwrap() - 151.2).abs() < 1e-9);
+        assert_eq!(metadata.fields["Make"], "\"Canon\"");
+        assert!(read(b"GIF89a").is_err());
+    }
+
+    #[test]
+    fn strips_metadata_segments_and_chunks() {
+        let bytes = jpeg(&[ascii(Tag::Make, "Canon")]);
+        let clean = strip(&bytes).unwrap();
+        assert!(clean.windows(4).any(|w| w == b"JFIF"));
+        assert!(!clean.windows(4).any(|w| w == b"Exif" || w == b"shot"));
+        assert!(clean.ends_with(&[0xFF, 0xDA, 0x00, 0x02, 0x12, 0x34, 0xFF, 0xD9]));
+        assert_eq!(read(&clean).unwrap().fields.len(), 0);
+
+        let chunk = |kind: &[u8], data: &[u8]| {
+            let mut bytes = (data.len() as u32).to_be_bytes().to_vec();
+            bytes.extend_from_slice(kind);
+            bytes.extend_from_slice(data);
+            bytes.extend_from_slice(&[0; 4]);
+            bytes
+        };
+        let png = [PNG_SIGNATURE.to_vec(), chunk(b"IHDR", &[0; 13]), chunk(b"tEXt", b"Author\0ops"), chunk(b"IEND", &[])].concat();
+        assert_eq!(strip(&png).unwrap(), [PNG_SIGNATURE.to_vec(), chunk(b"IHDR", &[0; 13]), chunk(b"IEND", &[])].concat());
+        assert!(strip(&png[..20]).is_err());
+    }
+
+    #[test]
+    fn keeps_only_the_primary_image() {
+        // A multi-picture file: the primary image with its MPF index and a
+        // stuffed byte and restart marker in the scan, then a preview that
+        // carries EXIF.
+        let primary = [
+            0xFF, 0xD8, 0xFF, 0xE2, 0x00, 0x08, b'M', b'P', b'F', 0x00, b'I', b'I', 0xFF, 0xDA, 0x00, 0x02, 0x12, 0xFF,
+            0x00, 0xFF, 0xD0, 0x34, 0xFF, 0xD9,
+        ];
+        let bytes = [primary.as_slice(), &jpeg(&[ascii(Tag::Make, "Canon")])].concat();
+        let clean = strip(&bytes).unwrap();
+        assert_eq!(clean, [&primary[..2], &primary[12..]].concat());
+        assert!(strip(&primary[..20]).is_err());
+    }
+}
@@ -0,0 +1,108 @@
+//! Keep the machine awake while monitoring, like `caffeinate -d`: a power
+//! assertion (IOKit on macOS, `SetThreadExecutionState` on Windows, a
+//! ScreenSaver/logind inhibitor on Linux) stops the display from blanking
+//! and the system from idle-sleeping, so feeds keep arriving on a wall or
+//! briefing screen. Toggled with `set_keep_awake` or the tray's "Keep Awake"
+//! item. Not persisted, and released on quit.
+//!
+//! Acquire and release on the main thread: Windows ties the execution state
+//! to the thread that set it. Sync commands, menu events and `RunEvent::Exit`
+//! all run there.
+
+use std::sync::Mutex;
+
+use tauri::menu::CheckMenuItem;
+use tauri::{AppHandle, Manager, Webview, Wry};
+
+const MENU_ID: &str = "tray.keep-awake";
+
+#[derive(Default)]
+pub struct KeepAwakeState {
+    assertion: Mutex<Option<keepawake::KeepAwake>>,
+}
+
+struct KeepAwakeMenu {
+    item: CheckMenuItem<Wry>,
+}
+
+pub fn is_enabled(app: &AppHandle) -> bool {
+    app.try_state::<KeepAwakeState>().is_some_and(|state| {
+        state
+            .assertion
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_some()
+    })
+}
+
+/// Acquire or release the power assertion. Returns whether one is held
+/// afterwards.
+pub fn set_enabled(app: &AppHandle, enabled: bool) -> Result<bool, String> {
+    let state = app.state::<KeepAwakeState>();
+    let mut assertion = state.assertion.lock().unwrap_or_else(|e| e.into_inner());
+    if assertion.is_some() != enabled {
+        *assertion = if enabled {
+            let acquired = keepawake::Builder::default()
+                .display(true)
+                .idle(true)
+                .reason("Monitoring live feeds")
+                .app_name("World Monitor")
+                .app_reverse_domain(app.config().identifier.as_str())
+                .create()
+                .map_err(|e| format!("Failed to keep the system awake: {e}"))?;
+            Some(acquired)
+        } else {
+            None
+        };
+        tracing::info!(target: "app", enabled, "keep awake changed");
+    }
+    drop(assertion);
+    if let Some(menu) = app.try_state::<KeepAwakeMenu>() {
+        let _ = menu.item.set_checked(enabled);
+    }
+    Ok(enabled)
+}
+
+/// Let the system sleep again. Call on exit.
+pub fn release(app: &AppHandle) {
+    if let Some(state) = app.try_state::<KeepAwakeState>() {
+        state.assertion.lock().unwrap_or_else(|e| e.into_inner()).take();
+    }
+}
+
+/// The tray's "Keep Awake" check item.
+pub fn tray_item(app: &AppHandle) -> tauri::Result<CheckMenuItem<Wry>> {
+    let item = CheckMenuItem::with_id(app, MENU_ID, "Keep Awake", true, is_enabled(app), None::<&str>)?;
+    app.manage(KeepAwakeMenu { item: item.clone() });
+    Ok(item)
+}
+
+pub fn owns_menu_id(id: &str) -> bool {
+    id == MENU_ID
+}
+
+pub fn handle_menu_event(app: &AppHandle) {
+    let enabled = !is_enabled(app);
+    if let Err(err) = set_enabled(app, enabled) {
+        tracing::error!(target: "menu", "{err}");
+        if let Some(menu) = app.try_state::<KeepAwakeMenu>() {
+            let _ = menu.item.set_checked(is_enabled(app));
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_keep_awake(webview: Webview, app: AppHandle) -> Result<bool, String> {
+    crate::metrics::of(&webview).observe("get_keep_awake", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(is_enabled(&app))
+    })
+}
+
+#[tauri::command]
+pub fn set_keep_awake(webview: Webview, app: AppHandle, enabled: bool) -> Result<bool, String> {
+    crate::metrics::of(&webview).observe("set_keep_awake", || {
+        crate::require_trusted_window(webview.label())?;
+        set_enabled(&app, enabled)
+    })
+}
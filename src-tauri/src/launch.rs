@@ -0,0 +1,115 @@
+//! What the app opens at launch (`LaunchConfig`): the dashboard, nothing but
+//! the tray icon, or the settings window. `--hidden` on the command line
+//! (as a login item would pass) starts in the tray regardless. After
+//! `restart_app`, whatever was showing before the restart wins over both.
+//!
+//! The main window is always created so feeds, alerts, and the ticker keep
+//! running; it just stays hidden. Starting in the tray falls back to the
+//! dashboard when there is no tray icon to reopen it from (macOS always has
+//! the dock). When starting in settings, closing settings shows the
+//! dashboard.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::{AppHandle, Manager, Webview};
+
+use crate::config::{DesktopConfigState, LaunchWindow};
+
+pub const START_HIDDEN_ARG: &str = "--hidden";
+
+/// Set while the dashboard waits for the launch settings window to close.
+static DASHBOARD_DEFERRED: AtomicBool = AtomicBool::new(false);
+
+fn choose(configured: LaunchWindow, start_hidden: bool, reachable_when_hidden: bool) -> LaunchWindow {
+    let requested = if start_hidden { LaunchWindow::Tray } else { configured };
+    if requested == LaunchWindow::Tray && !reachable_when_hidden {
+        LaunchWindow::Dashboard
+    } else {
+        requested
+    }
+}
+
+/// What this launch asked for, before checking that the tray exists.
+pub fn requested(app: &AppHandle) -> LaunchWindow {
+    if let Some(window) = crate::restart::launch_window(app) {
+        return window;
+    }
+    let configured = app
+        .try_state::<DesktopConfigState>()
+        .map(|config| config.snapshot().launch.window)
+        .unwrap_or_default();
+    choose(configured, std::env::args().any(|arg| arg == START_HIDDEN_ARG), true)
+}
+
+/// What to open now that the tray has (or has not) been created.
+pub fn resolve(app: &AppHandle) -> LaunchWindow {
+    let requested = requested(app);
+    let reachable = cfg!(target_os = "macos") || crate::tray::is_available(app);
+    let window = choose(requested, false, reachable);
+    if window != requested {
+        tracing::warn!(target: "app", "no tray icon to start hidden in; opening the dashboard");
+    }
+    window
+}
+
+/// Open the non-dashboard launch window, if any. Call once the main window
+/// exists (hidden unless `window` is the dashboard).
+pub fn open(app: &AppHandle, window: LaunchWindow) {
+    match window {
+        LaunchWindow::Dashboard => {}
+        LaunchWindow::Tray => tracing::info!(target: "app", "started hidden in the tray"),
+        LaunchWindow::Settings => {
+            DASHBOARD_DEFERRED.store(true, Ordering::SeqCst);
+            if let Err(err) = crate::open_settings_window(app, None) {
+                tracing::error!(target: "app", "failed to open settings at launch: {err}");
+                DASHBOARD_DEFERRED.store(false, Ordering::SeqCst);
+                crate::tray::show_dashboard(app);
+            }
+        }
+    }
+}
+
+/// Closing the launch settings window brings up the dashboard.
+pub fn on_close_requested(app: &AppHandle, label: &str) {
+    if label == "settings" && DASHBOARD_DEFERRED.swap(false, Ordering::SeqCst) {
+        crate::tray::show_dashboard(app);
+    }
+}
+
+#[tauri::command]
+pub fn get_launch_window(webview: Webview, config: tauri::State<'_, DesktopConfigState>) -> Result<LaunchWindow, String> {
+    crate::metrics::of(&webview).observe("get_launch_window", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(config.snapshot().launch.window)
+    })
+}
+
+#[tauri::command]
+pub async fn set_launch_window(webview: Webview, app: AppHandle, window: LaunchWindow) -> Result<(), String> {
+    crate::metrics::of(&webview)
+        .observe_async("set_launch_window", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                let config = app.state::<DesktopConfigState>();
+                config.update(|c| c.launch.window = window)?;
+                tracing::info!(target: "app", ?window, "launch window changed");
+                Ok(())
+            })
+            .await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod launch_tests {
+    use super::choose;
+    use crate::config::LaunchWindow;
+
+    #[test]
+    fn hidden_flag_and_missing_tray() {
+        assert_eq!(choose(LaunchWindow::Settings, false, true), LaunchWindow::Settings);
+        assert_eq!(choose(LaunchWindow::Dashboard, true, true), LaunchWindow::Tray);
+        assert_eq!(choose(LaunchWindow::Tray, false, false), LaunchWindow::Dashboard);
+        assert_eq!(choose(LaunchWindow::Settings, true, false), LaunchWindow::Dashboard);
+    }
+}
@@ -0,0 +1,132 @@
+//! Opening links in the default browser. Panels link to arbitrary news
+//! sites, so `open_external_url` only takes http(s) URLs and opens hosts on
+//! `links.allowed_hosts` directly; anything else waits for a native confirm
+//! dialog, which can also add the host to the allowlist.
+
+use tauri::{Manager, Url, Webview};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult};
+
+use crate::config::DesktopConfigState;
+
+const OPEN_LABEL: &str = "Open";
+const CANCEL_LABEL: &str = "Cancel";
+
+/// Whether `host` is an allowlisted host or one of its subdomains.
+fn host_allowed(host: &str, allowed: &[String]) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    allowed.iter().any(|entry| {
+        let entry = entry.trim().trim_start_matches("*.").trim_start_matches('.').to_ascii_lowercase();
+        !entry.is_empty()
+            && (host == entry || host.strip_suffix(entry.as_str()).is_some_and(|rest| rest.ends_with('.')))
+    })
+}
+
+fn parse(url: &str) -> Result<(Url, String), String> {
+    let parsed = Url::parse(url).map_err(|_| "Invalid URL".to_string())?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("Only http:// and https:// links can be opened".to_string());
+    }
+    let host = parsed
+        .host_str()
+        .filter(|host| !host.is_empty())
+        .ok_or_else(|| "Link has no host".to_string())?
+        .to_string();
+    Ok((parsed, host))
+}
+
+enum Choice {
+    Open,
+    AlwaysOpen,
+    Cancel,
+}
+
+/// Ask before opening an unlisted host. Blocks until answered; call off the
+/// main thread.
+fn confirm(webview: &Webview, url: &Url, host: &str) -> Choice {
+    let always = format!("Always Open {host}");
+    let dialog = webview
+        .app_handle()
+        .dialog()
+        .message(format!(
+            "{host} is not on your list of trusted sites.\n\n{url}\n\nOpen it in your browser?"
+        ))
+        .title("Open External Link")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::YesNoCancelCustom(
+            OPEN_LABEL.to_string(),
+            always.clone(),
+            CANCEL_LABEL.to_string(),
+        ))
+        .parent(&webview.window());
+    match dialog.blocking_show_with_result() {
+        MessageDialogResult::Yes => Choice::Open,
+        MessageDialogResult::No => Choice::AlwaysOpen,
+        MessageDialogResult::Custom(label) if label == OPEN_LABEL => Choice::Open,
+        MessageDialogResult::Custom(label) if label == always => Choice::AlwaysOpen,
+        _ => Choice::Cancel,
+    }
+}
+
+/// Open `url` in the browser, asking first when its host is not allowlisted.
+/// Returns whether it was opened.
+pub fn open(webview: &Webview, url: &str) -> Result<bool, String> {
+    let app = webview.app_handle();
+    let (parsed, host) = parse(url)?;
+    let links = app.state::<DesktopConfigState>().snapshot().links;
+    if !host_allowed(&host, &links.allowed_hosts) {
+        if !links.confirm_unlisted {
+            return Err(format!("{host} is not on the list of trusted sites"));
+        }
+        match confirm(webview, &parsed, &host) {
+            Choice::Open => {}
+            Choice::AlwaysOpen => {
+                let saved = app
+                    .state::<DesktopConfigState>()
+                    .update(|c| c.links.allowed_hosts.push(host.clone()));
+                match saved {
+                    Ok(_) => tracing::info!(target: "app", host, "added trusted link host"),
+                    Err(err) => tracing::warn!(target: "app", "{err}"),
+                }
+            }
+            Choice::Cancel => return Ok(false),
+        }
+    }
+    crate::open_in_shell(parsed.as_str())?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub async fn open_external_url(webview: Webview, url: String) -> Result<bool, String> {
+    crate::metrics::of(&webview)
+        .observe_async("open_external_url", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || open(&webview, &url)).await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod links_tests {
+    use super::*;
+
+    #[test]
+    fn allowlist_matches_hosts_and_subdomains() {
+        let allowed = vec!["github.com".to_string(), "*.bbc.co.uk".to_string()];
+        assert!(host_allowed("github.com", &allowed));
+        assert!(host_allowed("gist.GitHub.com", &allowed));
+        assert!(host_allowed("www.bbc.co.uk", &allowed));
+        assert!(host_allowed("bbc.co.uk", &allowed));
+        assert!(!host_allowed("evilgithub.com", &allowed));
+        assert!(!host_allowed("github.com.evil.net", &allowed));
+        assert!(!host_allowed("example.com", &[String::new()]));
+    }
+
+    #[test]
+    fn only_web_links_are_opened() {
+        assert_eq!(parse("https://www.reuters.com/world").unwrap().1, "www.reuters.com");
+        assert!(parse("http://example.com").is_ok());
+        assert!(parse("file:///etc/passwd").is_err());
+        assert!(parse("javascript:alert(1)").is_err());
+        assert!(parse("not a url").is_err());
+    }
+}
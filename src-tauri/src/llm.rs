@@ -0,0 +1,601 @@
+//! LLM calls made natively, so `GROQ_API_KEY` and `OPENROUTER_API_KEY`
+//! never reach the webview. Providers are tried in order: a local Ollama
+//! (`OLLAMA_API_URL`, optionally `OLLAMA_MODEL`), Groq, then OpenRouter,
+//! moving on when one is not configured, fails, or is rate limited. A 429
+//! also benches the provider for as long as its `Retry-After` says (a
+//! minute when it doesn't).
+//!
+//! `summarize` builds its prompt from local data (the headlines the caller
+//! passes, or else the newest ones in the `store`, plus the alerts fired in
+//! the same window and any context the caller adds) and streams the reply
+//! over a channel as it arrives. If a provider fails mid-reply the channel
+//! gets `reset` and the next one starts over. `digest` writes its summaries
+//! through `complete`.
+//!
+//! `translate_text` translates headlines in numbered batches and caches each
+//! translation in the `store`, so a headline is only sent once per language.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Manager, Webview};
+
+use crate::alerts::FiredAlert;
+use crate::store::{EventStore, NewsQuery};
+
+const OLLAMA_DEFAULT_MODEL: &str = "llama3.1:8b";
+const GROQ_URL: &str = "https://api.groq.com/openai/v1/chat/completions";
+const GROQ_MODEL: &str = "llama-3.1-8b-instant";
+const OPENROUTER_URL: &str = "https://openrouter.ai/api/v1/chat/completions";
+const OPENROUTER_MODEL: &str = "openrouter/free";
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+/// Longest wait for the next piece of a streamed reply.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+const REPLY_TIMEOUT: Duration = Duration::from_secs(120);
+const DEFAULT_RATE_LIMIT: Duration = Duration::from_secs(60);
+const MAX_RATE_LIMIT: Duration = Duration::from_secs(60 * 60);
+const SUMMARY_MAX_TOKENS: u32 = 200;
+const DEFAULT_HOURS: u32 = 6;
+const MAX_HEADLINES: usize = 30;
+const MAX_ALERTS: usize = 10;
+const MAX_CONTEXT_CHARS: usize = 4_000;
+const MAX_HEADLINE_CHARS: usize = 300;
+const MAX_TRANSLATE_TEXTS: usize = 100;
+const TRANSLATE_BATCH: usize = 20;
+const MAX_TRANSLATE_CHARS: usize = 500;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    Ollama,
+    Groq,
+    OpenRouter,
+}
+
+impl Provider {
+    fn as_str(self) -> &'static str {
+        match self {
+            Provider::Ollama => "ollama",
+            Provider::Groq => "groq",
+            Provider::OpenRouter => "openrouter",
+        }
+    }
+}
+
+/// A configured provider's chat completions endpoint.
+struct Endpoint {
+    provider: Provider,
+    url: String,
+    model: String,
+    key: Option<String>,
+}
+
+/// The configured providers, in the order they are tried.
+fn endpoints(app: &AppHandle) -> Vec<Endpoint> {
+    let secrets = app.state::<crate::SecretsCache>();
+    let mut endpoints = Vec::new();
+    if let Some(base) = secrets.get("OLLAMA_API_URL") {
+        endpoints.push(Endpoint {
+            provider: Provider::Ollama,
+            url: format!("{}/v1/chat/completions", base.trim_end_matches('/')),
+            model: secrets.get("OLLAMA_MODEL").unwrap_or_else(|| OLLAMA_DEFAULT_MODEL.to_string()),
+            key: None,
+        });
+    }
+    if let Some(key) = secrets.get("GROQ_API_KEY") {
+        endpoints.push(Endpoint {
+            provider: Provider::Groq,
+            url: GROQ_URL.to_string(),
+            model: GROQ_MODEL.to_string(),
+            key: Some(key),
+        });
+    }
+    if let Some(key) = secrets.get("OPENROUTER_API_KEY") {
+        endpoints.push(Endpoint {
+            provider: Provider::OpenRouter,
+            url: OPENROUTER_URL.to_string(),
+            model: OPENROUTER_MODEL.to_string(),
+            key: Some(key),
+        });
+    }
+    endpoints
+}
+
+/// Providers benched after a 429, until when.
+#[derive(Default)]
+pub struct LlmState {
+    benched: Mutex<HashMap<Provider, Instant>>,
+}
+
+impl LlmState {
+    fn is_benched(&self, provider: Provider, now: Instant) -> bool {
+        let mut benched = self.benched.lock().unwrap_or_else(|e| e.into_inner());
+        benched.retain(|_, until| *until > now);
+        benched.contains_key(&provider)
+    }
+
+    fn bench(&self, provider: Provider, wait: Duration) {
+        let until = Instant::now() + wait.min(MAX_RATE_LIMIT);
+        self.benched.lock().unwrap_or_else(|e| e.into_inner()).insert(provider, until);
+    }
+}
+
+/// Why a provider gave no complete reply.
+enum Failure {
+    RateLimited(Duration),
+    Failed(String),
+}
+
+/// One line of a server-sent event stream of chat completion chunks.
+#[derive(Debug, PartialEq)]
+enum StreamLine {
+    Token(String),
+    Done,
+    Error(String),
+    Skip,
+}
+
+fn parse_line(line: &str) -> StreamLine {
+    let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+        return StreamLine::Skip;
+    };
+    if data == "[DONE]" {
+        return StreamLine::Done;
+    }
+    let Ok(chunk) = serde_json::from_str::<Value>(data) else {
+        return StreamLine::Skip;
+    };
+    if let Some(error) = chunk.get("error") {
+        let message = error["message"].as_str().map(str::to_string).unwrap_or_else(|| error.to_string());
+        return StreamLine::Error(message);
+    }
+    match chunk["choices"][0]["delta"]["content"].as_str() {
+        Some(text) if !text.is_empty() => StreamLine::Token(text.to_string()),
+        _ => StreamLine::Skip,
+    }
+}
+
+fn retry_after(response: &reqwest::Response) -> Duration {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_RATE_LIMIT)
+}
+
+/// Stream one reply from `endpoint`, handing each piece to `on_token`.
+async fn stream(
+    client: &reqwest::Client,
+    endpoint: &Endpoint,
+    messages: &Value,
+    max_tokens: u32,
+    on_token: &mut impl FnMut(&str),
+) -> Result<String, Failure> {
+    let mut request = client
+        .post(&endpoint.url)
+        .header("HTTP-Referer", "https://worldmonitor.app")
+        .header("X-Title", "WorldMonitor")
+        .json(&json!({
+            "model": endpoint.model,
+            "temperature": 0.3,
+            "max_tokens": max_tokens,
+            "stream": true,
+            "messages": messages,
+        }));
+    if let Some(key) = &endpoint.key {
+        request = request.bearer_auth(key);
+    }
+    let mut response = request.send().await.map_err(|e| Failure::Failed(e.without_url().to_string()))?;
+    if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(Failure::RateLimited(retry_after(&response)));
+    }
+    if !response.status().is_success() {
+        return Err(Failure::Failed(format!("HTTP {}", response.status())));
+    }
+    let mut text = String::new();
+    let mut pending: Vec<u8> = Vec::new();
+    loop {
+        let chunk = tokio::time::timeout(IDLE_TIMEOUT, response.chunk())
+            .await
+            .map_err(|_| Failure::Failed("stream stalled".to_string()))?
+            .map_err(|e| Failure::Failed(e.without_url().to_string()))?;
+        let Some(chunk) = chunk else {
+            break;
+        };
+        pending.extend_from_slice(&chunk);
+        while let Some(end) = pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=end).collect();
+            match parse_line(String::from_utf8_lossy(&line).trim()) {
+                StreamLine::Token(token) => {
+                    on_token(&token);
+                    text.push_str(&token);
+                }
+                StreamLine::Done => return Ok(text),
+                StreamLine::Error(message) => return Err(Failure::Failed(message)),
+                StreamLine::Skip => {}
+            }
+        }
+    }
+    Ok(text)
+}
+
+/// Ask each configured provider in turn for a reply to `messages`,
+/// streaming it to `on_event`. Returns the reply with who wrote it.
+async fn generate(
+    app: &AppHandle,
+    messages: Value,
+    max_tokens: u32,
+    mut on_event: impl FnMut(SummaryEvent),
+) -> Result<Summary, String> {
+    let endpoints = endpoints(app);
+    if endpoints.is_empty() {
+        return Err("No LLM provider is configured".to_string());
+    }
+    let client = reqwest::Client::builder()
+        .use_native_tls()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(REPLY_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+    let state = app.state::<LlmState>();
+    let mut failures = Vec::new();
+    for endpoint in &endpoints {
+        if state.is_benched(endpoint.provider, Instant::now()) {
+            failures.push(format!("{}: rate limited", endpoint.provider.as_str()));
+            continue;
+        }
+        on_event(SummaryEvent::Started { provider: endpoint.provider, model: endpoint.model.clone() });
+        let mut streamed = false;
+        let mut on_token = |token: &str| {
+            streamed = true;
+            on_event(SummaryEvent::Token { text: token.to_string() });
+        };
+        let result = stream(&client, endpoint, &messages, max_tokens, &mut on_token).await;
+        let failure = match result {
+            Ok(text) if !text.trim().is_empty() => {
+                return Ok(Summary { text: text.trim().to_string(), provider: endpoint.provider, model: endpoint.model.clone() });
+            }
+            Ok(_) => "empty reply".to_string(),
+            Err(Failure::RateLimited(wait)) => {
+                state.bench(endpoint.provider, wait);
+                format!("rate limited for {}s", wait.as_secs())
+            }
+            Err(Failure::Failed(err)) => err,
+        };
+        tracing::warn!(target: "app", provider = endpoint.provider.as_str(), "LLM request failed: {failure}");
+        if streamed {
+            on_event(SummaryEvent::Reset);
+        }
+        failures.push(format!("{}: {failure}", endpoint.provider.as_str()));
+    }
+    Err(format!("No LLM provider answered ({})", failures.join("; ")))
+}
+
+/// A one-off reply from the first provider that gives one, for native
+/// features such as the `digest`.
+pub async fn complete(app: &AppHandle, system: &str, user: &str, max_tokens: u32) -> Result<String, String> {
+    let messages = json!([
+        { "role": "system", "content": system },
+        { "role": "user", "content": user },
+    ]);
+    Ok(generate(app, messages, max_tokens, |_| {}).await?.text)
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryMode {
+    /// The most important story, in two sentences.
+    #[default]
+    Brief,
+    /// What the most significant development means.
+    Analysis,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SummaryRequest {
+    pub mode: SummaryMode,
+    /// Headlines to summarize; the newest stored ones when empty.
+    pub headlines: Vec<String>,
+    /// Feeds to read stored headlines from; all when empty.
+    pub sources: Vec<String>,
+    /// How far back stored headlines and alerts are read.
+    pub hours: Option<u32>,
+    /// Extra context, such as the dashboard's geographic signals.
+    pub context: String,
+    /// Language code of the reply; English when empty.
+    pub lang: String,
+}
+
+/// Messages on a `summarize` channel.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SummaryEvent {
+    /// A provider was asked.
+    Started { provider: Provider, model: String },
+    /// The next piece of the reply.
+    Token { text: String },
+    /// The provider failed mid-reply; drop what arrived, the next one
+    /// starts over.
+    Reset,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Summary {
+    pub text: String,
+    pub provider: Provider,
+    pub model: String,
+}
+
+fn clip(text: &str, max: usize) -> String {
+    text.trim().chars().take(max).collect()
+}
+
+/// System and user prompts for `request` over `headlines` and `alerts`.
+fn prompts(request: &SummaryRequest, headlines: &[String], alerts: &[FiredAlert], today: &str) -> (String, String) {
+    let rules = "Rules:\n\
+                 - Each numbered headline below is a SEPARATE, UNRELATED story\n\
+                 - NEVER combine or merge people, places, or facts from different headlines\n\
+                 - NEVER start with \"Breaking news\", \"Tonight\" or similar openings\n\
+                 - If alerts or context are given, use them only where they relate to the chosen story\n\
+                 - No bullet points, no meta-commentary";
+    let task = match request.mode {
+        SummaryMode::Brief => "Summarize the single most important headline in 2 concise sentences MAX (under 60 words \
+                               total). Lead with what happened and where.",
+        SummaryMode::Analysis => "Analyze the most significant development in 2 concise sentences MAX (under 60 words \
+                                  total). Lead with what is significant and why.",
+    };
+    let mut system = format!("Current date: {today}. Provide geopolitical context appropriate for the current date.\n\n{task}\n{rules}");
+    let lang = request.lang.trim();
+    if !lang.is_empty() && !lang.eq_ignore_ascii_case("en") {
+        system.push_str(&format!("\nIMPORTANT: Write the reply in {} language.", clip(lang, 10).to_uppercase()));
+    }
+
+    let question = match request.mode {
+        SummaryMode::Brief => "Pick the most important ONE and summarize only that story:",
+        SummaryMode::Analysis => "What's the key pattern or risk?",
+    };
+    let mut user = format!("Each headline below is a separate story. {question}\n");
+    for (i, headline) in headlines.iter().enumerate() {
+        user.push_str(&format!("{}. {headline}\n", i + 1));
+    }
+    if !alerts.is_empty() {
+        user.push_str("\nRecent alerts:\n");
+        for alert in alerts.iter().rev().take(MAX_ALERTS) {
+            user.push_str(&format!("- {}: {}\n", alert.title, alert.body));
+        }
+    }
+    let context = clip(&request.context, MAX_CONTEXT_CHARS);
+    if !context.is_empty() {
+        user.push_str(&format!("\n{context}\n"));
+    }
+    (system, user.trim_end().to_string())
+}
+
+/// The caller's headlines, or the newest stored ones, without repeats.
+fn headlines(app: &AppHandle, request: &SummaryRequest, since: i64) -> Result<Vec<String>, String> {
+    let titles = if request.headlines.is_empty() {
+        let items = app.state::<EventStore>().news(&NewsQuery {
+            sources: request.sources.clone(),
+            since: Some(since),
+            limit: Some(MAX_HEADLINES * 2),
+        })?;
+        items.iter().filter_map(|item| item["title"].as_str().map(str::to_string)).collect()
+    } else {
+        request.headlines.clone()
+    };
+    let mut seen = HashSet::new();
+    Ok(titles
+        .iter()
+        .map(|title| clip(title, MAX_HEADLINE_CHARS))
+        .filter(|title| !title.is_empty() && seen.insert(title.to_lowercase()))
+        .take(MAX_HEADLINES)
+        .collect())
+}
+
+/// Summarize headlines with the first LLM provider that answers, streaming
+/// the reply to `on_event`. Returns the whole reply once it is complete.
+#[tauri::command]
+pub async fn summarize(
+    webview: Webview,
+    app: AppHandle,
+    request: Option<SummaryRequest>,
+    on_event: Channel<SummaryEvent>,
+) -> Result<Summary, String> {
+    crate::metrics::of(&webview)
+        .observe_async("summarize", async move {
+            crate::require_trusted_window(webview.label())?;
+            let request = request.unwrap_or_default();
+            let now = chrono::Utc::now();
+            let since = now.timestamp_millis() - i64::from(request.hours.unwrap_or(DEFAULT_HOURS).max(1)) * 60 * 60 * 1000;
+            let handle = app.clone();
+            let selection = request.clone();
+            let headlines = crate::runtime::blocking(move || headlines(&handle, &selection, since)).await?;
+            if headlines.is_empty() {
+                return Err("No headlines to summarize".to_string());
+            }
+            let alerts = crate::alerts::fired_since(&app, (since / 1000) as u64);
+            let (system, user) = prompts(&request, &headlines, &alerts, &now.format("%Y-%m-%d").to_string());
+            let messages = json!([
+                { "role": "system", "content": system },
+                { "role": "user", "content": user },
+            ]);
+            generate(&app, messages, SUMMARY_MAX_TOKENS, |event| {
+                let _ = on_event.send(event);
+            })
+            .await
+        })
+        .await
+}
+
+/// Prompts asking for `texts` translated into `lang`, one numbered line each.
+fn translation_prompts(lang: &str, texts: &[String]) -> (String, String) {
+    let system = format!(
+        "Translate each numbered line into {} language. Reply with the translations only, one per line, \
+         keeping each line's number. Keep names and places as they are commonly written in that language. \
+         No notes or explanations.",
+        lang.to_uppercase()
+    );
+    let mut user = String::new();
+    for (i, text) in texts.iter().enumerate() {
+        user.push_str(&format!("{}. {}\n", i + 1, text.replace('\n', " ")));
+    }
+    (system, user.trim_end().to_string())
+}
+
+/// The `count` translations in a numbered reply, where each was found.
+fn parse_translations(reply: &str, count: usize) -> Vec<Option<String>> {
+    let mut translations = vec![None; count];
+    for line in reply.lines() {
+        let Some((number, text)) = line.trim().split_once(['.', ')']) else {
+            continue;
+        };
+        let text = text.trim();
+        match number.trim().parse::<usize>() {
+            Ok(n) if (1..=count).contains(&n) && !text.is_empty() => translations[n - 1] = Some(text.to_string()),
+            _ => {}
+        }
+    }
+    translations
+}
+
+/// Translate `texts` into language `target_lang` (a code such as `en`),
+/// returning them in the same order. Cached translations are reused and new
+/// ones cached.
+#[tauri::command]
+pub async fn translate_text(
+    webview: Webview,
+    app: AppHandle,
+    texts: Vec<String>,
+    target_lang: String,
+) -> Result<Vec<String>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("translate_text", async move {
+            crate::require_trusted_window(webview.label())?;
+            let lang = target_lang.trim().to_lowercase();
+            if lang.is_empty() || lang.len() > 10 || !lang.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                return Err(format!("Invalid language code: {target_lang}"));
+            }
+            if texts.len() > MAX_TRANSLATE_TEXTS {
+                return Err(format!("At most {MAX_TRANSLATE_TEXTS} texts can be translated at once"));
+            }
+            let texts: Vec<String> = texts.iter().map(|text| clip(text, MAX_TRANSLATE_CHARS)).collect();
+            let handle = app.clone();
+            let (selection, code) = (texts.clone(), lang.clone());
+            let mut cached =
+                crate::runtime::blocking(move || handle.state::<EventStore>().translations(&code, &selection)).await?;
+
+            let mut seen = HashSet::new();
+            let missing: Vec<String> = texts
+                .iter()
+                .filter(|text| !text.is_empty() && !cached.contains_key(*text) && seen.insert(text.to_string()))
+                .cloned()
+                .collect();
+            let mut translated = Vec::new();
+            for batch in missing.chunks(TRANSLATE_BATCH) {
+                let (system, user) = translation_prompts(&lang, batch);
+                let max_tokens = (batch.len() * 100) as u32;
+                let reply = complete(&app, &system, &user, max_tokens).await?;
+                for (text, translation) in batch.iter().zip(parse_translations(&reply, batch.len())) {
+                    let translation = match translation {
+                        Some(translation) => translation,
+                        // The model merged or dropped lines; ask for this one alone.
+                        None => {
+                            let (system, user) = translation_prompts(&lang, std::slice::from_ref(text));
+                            let reply = complete(&app, &system, &user, 100).await?;
+                            match parse_translations(&reply, 1).pop().flatten() {
+                                Some(translation) => translation,
+                                None => clip(&reply, MAX_TRANSLATE_CHARS),
+                            }
+                        }
+                    };
+                    translated.push((text.clone(), translation));
+                }
+            }
+
+            if !translated.is_empty() {
+                cached.extend(translated.iter().cloned());
+                let handle = app.clone();
+                let now = chrono::Utc::now().timestamp_millis();
+                let stored = crate::runtime::blocking(move || {
+                    handle.state::<EventStore>().store_translations(&lang, &translated, now)
+                })
+                .await;
+                if let Err(err) = stored {
+                    tracing::warn!(target: "app", "failed to cache translations: {err}");
+                }
+            }
+            Ok(texts.into_iter().map(|text| cached.get(&text).cloned().unwrap_or(text)).collect())
+        })
+        .await
+}
+
+#[cfg(test)]
+mod llm_tests {
+    use super::*;
+
+    #[test]
+    fn reads_streamed_chunks() {
+        assert_eq!(
+            parse_line(r#"data: {"choices":[{"delta":{"content":"Strait"}}]}"#),
+            StreamLine::Token("Strait".to_string())
+        );
+        assert_eq!(parse_line(r#"data: {"choices":[{"delta":{"role":"assistant"}}]}"#), StreamLine::Skip);
+        assert_eq!(parse_line(": OPENROUTER PROCESSING"), StreamLine::Skip);
+        assert_eq!(parse_line("data: [DONE]"), StreamLine::Done);
+        assert_eq!(
+            parse_line(r#"data: {"error":{"message":"Rate limit exceeded","code":429}}"#),
+            StreamLine::Error("Rate limit exceeded".to_string())
+        );
+    }
+
+    #[test]
+    fn builds_prompts_from_headlines_alerts_and_context() {
+        let request = SummaryRequest {
+            mode: SummaryMode::Analysis,
+            context: "Convergence: 3 signals near Hormuz".to_string(),
+            lang: "fr".to_string(),
+            ..SummaryRequest::default()
+        };
+        let alert = FiredAlert {
+            rule_id: "r".to_string(),
+            rule_name: "Hormuz".to_string(),
+            title: "Hormuz".to_string(),
+            body: "Vessel EVER GIVEN entered Hormuz".to_string(),
+            subject: "1".to_string(),
+            fired_at: 0,
+        };
+        let headlines = ["Tankers divert".to_string(), "Talks resume".to_string()];
+        let (system, user) = prompts(&request, &headlines, &[alert], "2026-03-13");
+        assert!(system.starts_with("Current date: 2026-03-13."));
+        assert!(system.contains("Analyze the most significant development"));
+        assert!(system.ends_with("Write the reply in FR language."));
+        assert_eq!(
+            user,
+            "Each headline below is a separate story. What's the key pattern or risk?\n\
+             1. Tankers divert\n2. Talks resume\n\n\
+             Recent alerts:\n- Hormuz: Vessel EVER GIVEN entered Hormuz\n\n\
+             Convergence: 3 signals near Hormuz"
+        );
+    }
+
+    #[test]
+    fn translates_in_numbered_batches() {
+        let texts = ["Hola mundo".to_string(), "Línea\ndividida".to_string()];
+        let (system, user) = translation_prompts("en", &texts);
+        assert!(system.starts_with("Translate each numbered line into EN language."));
+        assert_eq!(user, "1. Hola mundo\n2. Línea dividida");
+        assert_eq!(
+            parse_translations("1. Hello world\n\n2) Split line\n3. Extra", 2),
+            [Some("Hello world".to_string()), Some("Split line".to_string())]
+        );
+        assert_eq!(parse_translations("Hello world. Split line", 2), [None, None]);
+    }
+}
@@ -0,0 +1,152 @@
+//! The OS locale and preferred languages, so the frontend formats numbers
+//! and dates for the user's region and picks its UI language from the
+//! system rather than the webview's guess. A background thread re-reads
+//! them and broadcasts `app://locale-changed` when they change. Windows and
+//! macOS report changes live; on Linux the locale comes from the session
+//! environment, which only changes at the next login.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+pub const CHANGED_EVENT: &str = "app://locale-changed";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+const FALLBACK_LOCALE: &str = "en-US";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SystemLocale {
+    /// BCP 47 tag for formatting, e.g. `en-GB`.
+    pub locale: String,
+    /// ISO 3166 region (or UN M.49 code) from `locale`, if it has one.
+    pub region: Option<String>,
+    /// Preferred UI languages, most preferred first.
+    pub languages: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct LocaleState {
+    current: Mutex<Option<SystemLocale>>,
+}
+
+/// Normalize POSIX-style tags (`en_GB.UTF-8@euro`) to BCP 47 (`en-GB`).
+fn normalize(tag: &str) -> Option<String> {
+    let tag = tag.split(['.', '@']).next()?.replace('_', "-");
+    let tag = tag.trim();
+    (!tag.is_empty() && tag != "C" && tag != "POSIX").then(|| tag.to_string())
+}
+
+/// The region subtag: two letters or three digits, after the language.
+fn region_of(locale: &str) -> Option<String> {
+    locale
+        .split('-')
+        .skip(1)
+        .find(|subtag| {
+            (subtag.len() == 2 && subtag.chars().all(|c| c.is_ascii_alphabetic()))
+                || (subtag.len() == 3 && subtag.chars().all(|c| c.is_ascii_digit()))
+        })
+        .map(str::to_ascii_uppercase)
+}
+
+fn from_tags(primary: Option<String>, preferred: impl IntoIterator<Item = String>) -> SystemLocale {
+    let mut languages: Vec<String> = Vec::new();
+    for tag in preferred.into_iter().filter_map(|tag| normalize(&tag)) {
+        if !languages.contains(&tag) {
+            languages.push(tag);
+        }
+    }
+    let locale = primary
+        .and_then(|tag| normalize(&tag))
+        .or_else(|| languages.first().cloned())
+        .unwrap_or_else(|| FALLBACK_LOCALE.to_string());
+    if languages.is_empty() {
+        languages.push(locale.clone());
+    }
+    SystemLocale {
+        region: region_of(&locale),
+        locale,
+        languages,
+    }
+}
+
+fn read() -> SystemLocale {
+    from_tags(sys_locale::get_locale(), sys_locale::get_locales())
+}
+
+/// The current system locale, as last read by the monitor thread.
+pub fn current(app: &AppHandle) -> SystemLocale {
+    app.try_state::<LocaleState>()
+        .and_then(|state| state.current.lock().unwrap_or_else(|e| e.into_inner()).clone())
+        .unwrap_or_else(read)
+}
+
+fn run(app: AppHandle) {
+    let state = app.state::<LocaleState>();
+    loop {
+        let locale = read();
+        let mut current = state.current.lock().unwrap_or_else(|e| e.into_inner());
+        if current.as_ref() != Some(&locale) {
+            let changed = current.is_some();
+            *current = Some(locale.clone());
+            drop(current);
+            if changed {
+                tracing::info!(target: "app", locale = %locale.locale, "system locale changed");
+                let _ = app.emit(CHANGED_EVENT, locale);
+            }
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Start the locale monitor thread.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    if let Err(e) = std::thread::Builder::new()
+        .name("locale-monitor".into())
+        .spawn(move || run(app))
+    {
+        tracing::warn!(target: "app", "failed to start locale monitor: {e}");
+    }
+}
+
+#[tauri::command]
+pub fn get_locale(webview: Webview, app: AppHandle) -> Result<SystemLocale, String> {
+    crate::metrics::of(&webview).observe("get_locale", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(current(&app))
+    })
+}
+
+#[cfg(test)]
+mod locale_tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_posix_locales() {
+        assert_eq!(normalize("en_GB.UTF-8").as_deref(), Some("en-GB"));
+        assert_eq!(normalize("de_DE@euro").as_deref(), Some("de-DE"));
+        assert_eq!(normalize("C"), None);
+    }
+
+    #[test]
+    fn extracts_region() {
+        assert_eq!(region_of("en-GB").as_deref(), Some("GB"));
+        assert_eq!(region_of("zh-Hans-CN").as_deref(), Some("CN"));
+        assert_eq!(region_of("es-419").as_deref(), Some("419"));
+        assert_eq!(region_of("fr"), None);
+    }
+
+    #[test]
+    fn fills_in_missing_values() {
+        let locale = from_tags(None, ["pt_BR".to_string(), "pt-BR".to_string(), "en".to_string()]);
+        assert_eq!(locale.locale, "pt-BR");
+        assert_eq!(locale.region.as_deref(), Some("BR"));
+        assert_eq!(locale.languages, ["pt-BR", "en"]);
+
+        let fallback = from_tags(None, Vec::new());
+        assert_eq!(fallback.locale, FALLBACK_LOCALE);
+        assert_eq!(fallback.languages, [FALLBACK_LOCALE]);
+    }
+}
@@ -0,0 +1,478 @@
+//! Commands that read the on-disk log files (`desktop.log`, `local-api.log`)
+//! on behalf of the webview, so debug tooling (the settings Debug tab and the
+//! log viewer window) never needs filesystem access from JS.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Manager, Webview};
+
+use crate::config::LogLevel;
+use crate::diagnostics::DiagnosticsExportState;
+
+const TAIL_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const TAIL_DEFAULT_LINES: usize = 200;
+const TAIL_MAX_LINES: usize = 5000;
+/// How far back from EOF the initial backlog scan reads.
+const TAIL_BACKLOG_BYTES: u64 = 512 * 1024;
+const QUERY_DEFAULT_LIMIT: usize = 500;
+const QUERY_MAX_LIMIT: usize = 5000;
+/// How far back from EOF `query_logs` scans each file.
+const QUERY_SCAN_BYTES: u64 = 8 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum LogFileKind {
+    Desktop,
+    LocalApi,
+}
+
+impl LogFileKind {
+    const ALL: [LogFileKind; 2] = [LogFileKind::Desktop, LogFileKind::LocalApi];
+
+    pub fn path(self, app: &AppHandle) -> Result<PathBuf, String> {
+        match self {
+            LogFileKind::Desktop => crate::desktop_log_path(app),
+            LogFileKind::LocalApi => crate::sidecar_log_path(app),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LogTailEvent {
+    Lines { lines: Vec<String> },
+    /// The file shrank (rotation or truncation); following restarts at offset 0.
+    Reset,
+    Ended,
+}
+
+/// Live tails, keyed by id, with a cancellation flag each.
+#[derive(Default)]
+pub struct LogTailState {
+    next_id: AtomicU32,
+    active: Mutex<HashMap<u32, Arc<AtomicBool>>>,
+}
+
+impl LogTailState {
+    fn register(&self) -> (u32, Arc<AtomicBool>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.active
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id, cancelled.clone());
+        (id, cancelled)
+    }
+
+    fn finish(&self, id: u32) {
+        self.active.lock().unwrap_or_else(|e| e.into_inner()).remove(&id);
+    }
+}
+
+#[derive(Serialize)]
+pub struct ClearLogsReport {
+    reclaimed_bytes: u64,
+}
+
+/// Truncate a file in place, returning its previous size (0 if missing).
+///
+/// Truncating rather than deleting keeps other handles valid: the sidecar's
+/// stdout/stderr are opened in append mode, so its next write simply lands at
+/// the new end of file on every platform. A fresh write handle is used because
+/// append-only handles cannot be truncated on Windows.
+pub fn truncate_file(path: &Path) -> std::io::Result<u64> {
+    let file = match OpenOptions::new().write(true).open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    let len = file.metadata()?.len();
+    file.set_len(0)?;
+    Ok(len)
+}
+
+/// Keep the last `count` lines of `text`.
+fn last_lines(text: &str, count: usize) -> Vec<String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.len().saturating_sub(count);
+    lines[start..].iter().map(|line| line.to_string()).collect()
+}
+
+/// Append `chunk` to `pending` and drain every completed line, leaving a
+/// trailing partial line buffered until its newline arrives.
+fn drain_complete_lines(pending: &mut String, chunk: &str) -> Vec<String> {
+    pending.push_str(chunk);
+    let Some(last_newline) = pending.rfind('\n') else {
+        return Vec::new();
+    };
+    let rest = pending.split_off(last_newline + 1);
+    let complete = std::mem::replace(pending, rest);
+    complete
+        .lines()
+        .map(|line| line.trim_end_matches('\r').to_string())
+        .collect()
+}
+
+fn read_range(path: &Path, from: u64) -> std::io::Result<(String, u64)> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    file.seek(SeekFrom::Start(from))?;
+    let mut buf = Vec::with_capacity(len.saturating_sub(from) as usize);
+    file.take(len.saturating_sub(from)).read_to_end(&mut buf)?;
+    Ok((String::from_utf8_lossy(&buf).into_owned(), len))
+}
+
+/// Read at most `max_bytes` from the end of a file, starting on a line
+/// boundary. Returns the text and the EOF offset.
+fn read_last_bytes(path: &Path, max_bytes: u64) -> std::io::Result<(String, u64)> {
+    let len = std::fs::metadata(path)?.len();
+    let start = len.saturating_sub(max_bytes);
+    let (mut text, end) = read_range(path, start)?;
+    // The scan may start mid-line; drop that fragment unless we read from 0.
+    if start > 0 {
+        if let Some(idx) = text.find('\n') {
+            text.drain(..=idx);
+        }
+    }
+    Ok((text, end))
+}
+
+/// Read the last `count` lines of a file, scanning at most
+/// `TAIL_BACKLOG_BYTES` from the end. Returns the lines and the EOF offset.
+pub fn read_tail_lines(path: &Path, count: usize) -> std::io::Result<(Vec<String>, u64)> {
+    let (text, end) = read_last_bytes(path, TAIL_BACKLOG_BYTES)?;
+    Ok((last_lines(&text, count), end))
+}
+
+fn run_tail(path: PathBuf, lines: usize, follow: bool, cancelled: &AtomicBool, channel: &Channel<LogTailEvent>) {
+    let mut offset = match read_tail_lines(&path, lines) {
+        Ok((backlog, end)) => {
+            if !backlog.is_empty() && channel.send(LogTailEvent::Lines { lines: backlog }).is_err() {
+                return;
+            }
+            end
+        }
+        Err(_) => 0,
+    };
+
+    let mut pending = String::new();
+    while follow && !cancelled.load(Ordering::Relaxed) {
+        std::thread::sleep(TAIL_POLL_INTERVAL);
+        let current_len = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        if current_len < offset {
+            offset = 0;
+            pending.clear();
+            if channel.send(LogTailEvent::Reset).is_err() {
+                return;
+            }
+        }
+        if current_len == offset {
+            continue;
+        }
+        let Ok((chunk, end)) = read_range(&path, offset) else {
+            continue;
+        };
+        offset = end;
+        let lines = drain_complete_lines(&mut pending, &chunk);
+        if !lines.is_empty() && channel.send(LogTailEvent::Lines { lines }).is_err() {
+            // Receiving webview went away.
+            return;
+        }
+    }
+    let _ = channel.send(LogTailEvent::Ended);
+}
+
+/// Filters for `query_logs`. Omitted fields match everything; `file: None`
+/// searches both logs.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct LogQuery {
+    file: Option<LogFileKind>,
+    /// Least severe level to include. Lines without a recognizable level
+    /// (most of `local-api.log`) count as info.
+    level: Option<LogLevel>,
+    /// Case-insensitive substring match.
+    search: Option<String>,
+    /// Matches to skip, newest first.
+    offset: usize,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct LogLine {
+    file: LogFileKind,
+    /// 1-based within the scanned window of the file.
+    line: usize,
+    level: LogLevel,
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LogPage {
+    lines: Vec<LogLine>,
+    /// Matches across all requested files, for paging.
+    total: usize,
+    has_more: bool,
+}
+
+/// Level of a `[epoch][LEVEL][target] message` line, a JSON-format line
+/// (`"level":"LEVEL"`), or sidecar output that carries a bracketed level tag.
+fn line_level(line: &str) -> LogLevel {
+    let tag = if line.starts_with('{') {
+        line.split_once(r#""level":""#)
+            .and_then(|(_, rest)| rest.split_once('"'))
+            .map(|(level, _)| level)
+    } else {
+        line.strip_prefix('[')
+            .and_then(|rest| rest.split_once("]["))
+            .and_then(|(_, rest)| rest.split_once(']'))
+            .map(|(level, _)| level)
+    };
+    match tag {
+        Some("ERROR") => LogLevel::Error,
+        Some("WARN") => LogLevel::Warn,
+        Some("DEBUG") => LogLevel::Debug,
+        Some("TRACE") => LogLevel::Trace,
+        _ => LogLevel::Info,
+    }
+}
+
+/// Matching lines of one file's text, newest first.
+fn filter_lines(file: LogFileKind, text: &str, query: &LogQuery) -> Vec<LogLine> {
+    let needle = query
+        .search
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_lowercase);
+    let mut matches: Vec<LogLine> = text
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, text)| {
+            let level = line_level(text);
+            if query.level.is_some_and(|min| level > min) {
+                return None;
+            }
+            if needle.as_ref().is_some_and(|n| !text.to_lowercase().contains(n)) {
+                return None;
+            }
+            Some(LogLine {
+                file,
+                line: idx + 1,
+                level,
+                text: text.trim_end_matches('\r').to_string(),
+            })
+        })
+        .collect();
+    matches.reverse();
+    matches
+}
+
+fn paginate(matches: Vec<LogLine>, offset: usize, limit: usize) -> LogPage {
+    let total = matches.len();
+    let lines: Vec<LogLine> = matches.into_iter().skip(offset).take(limit).collect();
+    LogPage {
+        has_more: offset + lines.len() < total,
+        lines,
+        total,
+    }
+}
+
+fn run_query(app: &AppHandle, query: &LogQuery) -> Result<LogPage, String> {
+    let files = match query.file {
+        Some(file) => vec![file],
+        None => LogFileKind::ALL.to_vec(),
+    };
+    let mut matches = Vec::new();
+    for file in files {
+        let path = file.path(app)?;
+        let text = match read_last_bytes(&path, QUERY_SCAN_BYTES) {
+            Ok((text, _)) => text,
+            // The sidecar log does not exist until the sidecar first starts.
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(format!("Failed to read {}: {e}", path.display())),
+        };
+        matches.extend(filter_lines(file, &text, query));
+    }
+    let limit = query.limit.unwrap_or(QUERY_DEFAULT_LIMIT).min(QUERY_MAX_LIMIT);
+    Ok(paginate(matches, query.offset, limit))
+}
+
+/// Page through log lines, newest first, filtered by file, level, and text.
+/// Backs the log viewer window.
+#[tauri::command]
+pub async fn query_logs(webview: Webview, app: AppHandle, query: LogQuery) -> Result<LogPage, String> {
+    crate::metrics::of(&webview)
+        .observe_async("query_logs", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || run_query(&app, &query)).await
+        })
+        .await
+}
+
+/// Stream the last `lines` lines of a log file, then (if `follow`) every line
+/// appended afterwards. Returns a tail id for `stop_log_tail`.
+#[tauri::command]
+pub async fn tail_log(
+    webview: Webview,
+    app: AppHandle,
+    file: LogFileKind,
+    follow: bool,
+    lines: Option<usize>,
+    on_event: Channel<LogTailEvent>,
+) -> Result<u32, String> {
+    crate::metrics::of(&webview)
+        .observe_async("tail_log", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                let path = file.path(&app)?;
+                let lines = lines.unwrap_or(TAIL_DEFAULT_LINES).min(TAIL_MAX_LINES);
+                let (id, cancelled) = app.state::<LogTailState>().register();
+
+                let tail_app = app.clone();
+                std::thread::Builder::new()
+                    .name(format!("log-tail-{id}"))
+                    .spawn(move || {
+                        run_tail(path, lines, follow, &cancelled, &on_event);
+                        tail_app.state::<LogTailState>().finish(id);
+                    })
+                    .map_err(|e| format!("Failed to start log tail: {e}"))?;
+                tracing::debug!(target: "app", id, ?file, follow, "log tail started");
+                Ok(id)
+            })
+            .await
+        })
+        .await
+}
+
+#[tauri::command]
+pub fn stop_log_tail(webview: Webview, state: tauri::State<'_, LogTailState>, id: u32) -> Result<(), String> {
+    crate::metrics::of(&webview).observe("stop_log_tail", || {
+        crate::require_trusted_window(webview.label())?;
+        if let Some(cancelled) = state.active.lock().unwrap_or_else(|e| e.into_inner()).get(&id) {
+            cancelled.store(true, Ordering::Relaxed);
+        }
+        Ok(())
+    })
+}
+
+/// Empty both log files (and delete rotated `desktop.log` generations).
+/// Refused while a tail or diagnostics export is reading them.
+#[tauri::command]
+pub async fn clear_logs(webview: Webview, app: AppHandle) -> Result<ClearLogsReport, String> {
+    crate::metrics::of(&webview)
+        .observe_async("clear_logs", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                let tails = app.state::<LogTailState>();
+                // Hold the tail registry so no new tail starts mid-clear.
+                let active = tails.active.lock().unwrap_or_else(|e| e.into_inner());
+                if !active.is_empty() {
+                    return Err("Cannot clear logs while a log tail is running".to_string());
+                }
+                if app.state::<DiagnosticsExportState>().is_busy() {
+                    return Err("Cannot clear logs while a diagnostics export is running".to_string());
+                }
+
+                let desktop = LogFileKind::Desktop.path(&app)?;
+                let mut reclaimed_bytes = crate::logging::clear_desktop_log(&desktop)
+                    .map_err(|e| format!("Failed to clear {}: {e}", desktop.display()))?;
+                let local_api = LogFileKind::LocalApi.path(&app)?;
+                reclaimed_bytes += truncate_file(&local_api)
+                    .map_err(|e| format!("Failed to clear {}: {e}", local_api.display()))?;
+                drop(active);
+
+                tracing::info!(target: "app", reclaimed_bytes, "logs cleared");
+                Ok(ClearLogsReport { reclaimed_bytes })
+            })
+            .await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tail_tests {
+    use super::{
+        drain_complete_lines, filter_lines, last_lines, line_level, paginate, truncate_file, LogFileKind, LogQuery,
+    };
+    use crate::config::LogLevel;
+
+    #[test]
+    fn last_lines_keeps_tail() {
+        assert_eq!(last_lines("a\nb\nc\n", 2), vec!["b", "c"]);
+        assert_eq!(last_lines("a\n", 5), vec!["a"]);
+        assert!(last_lines("", 5).is_empty());
+    }
+
+    #[test]
+    fn partial_lines_are_buffered_until_newline() {
+        let mut pending = String::new();
+        assert!(drain_complete_lines(&mut pending, "[1][INFO] par").is_empty());
+        assert_eq!(
+            drain_complete_lines(&mut pending, "tial\r\n[2][WARN] next\n[3]"),
+            vec!["[1][INFO] partial", "[2][WARN] next"]
+        );
+        assert_eq!(pending, "[3]");
+    }
+
+    #[test]
+    fn parses_level_from_desktop_format() {
+        assert_eq!(line_level("[1700000000][WARN][cache] slow"), LogLevel::Warn);
+        assert_eq!(line_level("[1700000000][ERROR][sidecar] exited"), LogLevel::Error);
+        assert_eq!(line_level("[local-api] listening on 46123"), LogLevel::Info);
+        assert_eq!(
+            line_level(r#"{"timestamp":"2025-01-01T00:00:00.000000Z","level":"DEBUG","message":"x","target":"app"}"#),
+            LogLevel::Debug
+        );
+    }
+
+    #[test]
+    fn query_filters_by_level_and_search_newest_first() {
+        let text = "[1][INFO][app] started\n[2][WARN][cache] Slow write\n[3][ERROR][cache] write failed\n[4][DEBUG][cache] flushed\n";
+        let query = LogQuery {
+            level: Some(LogLevel::Warn),
+            search: Some("CACHE".to_string()),
+            ..LogQuery::default()
+        };
+        let lines: Vec<usize> = filter_lines(LogFileKind::Desktop, text, &query)
+            .iter()
+            .map(|l| l.line)
+            .collect();
+        assert_eq!(lines, vec![3, 2]);
+
+        let all = filter_lines(LogFileKind::Desktop, text, &LogQuery::default());
+        let page = paginate(all, 1, 2);
+        assert_eq!(page.total, 4);
+        assert!(page.has_more);
+        assert_eq!(page.lines.iter().map(|l| l.line).collect::<Vec<_>>(), vec![3, 2]);
+    }
+
+    #[test]
+    fn truncate_keeps_append_handles_valid() {
+        use std::io::Write;
+
+        let path = std::env::temp_dir().join(format!("wm-truncate-{}.log", std::process::id()));
+        let mut writer = std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&path)
+            .unwrap();
+        writer.write_all(b"old line\n").unwrap();
+        let mut appender = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+
+        assert_eq!(truncate_file(&path).unwrap(), 9);
+        appender.write_all(b"new\n").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new\n");
+        assert_eq!(truncate_file(&path.with_extension("missing")).unwrap(), 0);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,538 @@
+//! Desktop logging built on `tracing`.
+//!
+//! Every subsystem logs through the `tracing` macros with an explicit target
+//! (`sidecar`, `cache`, `secrets`, `menu`, `app`), and a single subscriber
+//! writes them to `desktop.log` as
+//! `[epoch][LEVEL][target] message key=value ...`, or as JSON lines with
+//! RFC 3339 timestamps when `LogConfig::format` is `json`.
+//! Filtering uses `EnvFilter` directives from `WORLD_MONITOR_LOG`
+//! (e.g. `WORLD_MONITOR_LOG=info,sidecar=debug`), falling back to the
+//! configured `LogConfig::level`. `set_log_level` swaps the filter at runtime.
+//!
+//! The file is size-rotated: once a write would push it past
+//! `LogConfig::max_file_bytes` it becomes `desktop.log.1`, older generations
+//! shift up, and anything past `max_generations` is dropped.
+//!
+//! Webview console output reaches the same file through `log_from_frontend`
+//! under the `frontend` target, rate limited per window.
+//!
+//! With `LogConfig::native_backend` set, events are also mirrored to the
+//! platform log (journald, the Windows Event Log, or os_log) so fleet tooling
+//! can collect them without scraping files.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde_json::Value;
+use tauri::Webview;
+use tracing::{Event, Subscriber};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::layer::{Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+use crate::config::{DesktopConfigState, LogConfig, LogFormat, LogLevel};
+
+const LOG_FILTER_ENV: &str = "WORLD_MONITOR_LOG";
+/// Identifier used by the native backends (journald `SYSLOG_IDENTIFIER`,
+/// Event Log source, os_log subsystem).
+#[cfg(any(target_os = "linux", windows))]
+const NATIVE_LOG_IDENTIFIER: &str = "world-monitor";
+#[cfg(target_os = "macos")]
+const NATIVE_LOG_SUBSYSTEM: &str = "app.worldmonitor.desktop";
+const FRONTEND_LOG_BURST: f64 = 50.0;
+const FRONTEND_LOG_PER_SEC: f64 = 10.0;
+const FRONTEND_LOG_MAX_CHARS: usize = 4096;
+
+/// Line format shared by the file and stderr outputs. Keeps the historical
+/// `[epoch][LEVEL]` prefix so existing log scrapers continue to work.
+struct DesktopLogFormat;
+
+impl<S, N> FormatEvent<S, N> for DesktopLogFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let meta = event.metadata();
+        write!(
+            writer,
+            "[{}][{}][{}] ",
+            crate::unix_timestamp_secs(),
+            meta.level(),
+            meta.target()
+        )?;
+        ctx.field_format().format_fields(writer.by_ref(), event)?;
+        writeln!(writer)
+    }
+}
+
+static DESKTOP_LOG: OnceLock<RotatingLogFile> = OnceLock::new();
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Append-only log file that rotates itself by size. One `write` call is one
+/// formatted event, so a line never straddles two generations.
+struct RotatingLogFile {
+    path: PathBuf,
+    policy: LogConfig,
+    state: Mutex<OpenLog>,
+}
+
+#[derive(Default)]
+struct OpenLog {
+    file: Option<File>,
+    len: u64,
+}
+
+impl RotatingLogFile {
+    fn new(path: PathBuf, policy: LogConfig) -> Self {
+        RotatingLogFile {
+            path,
+            policy,
+            state: Mutex::new(OpenLog::default()),
+        }
+    }
+
+    fn write_event(&self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.len > 0 && state.len + buf.len() as u64 > self.policy.max_file_bytes {
+            state.file = None;
+            state.len = 0;
+            rotate_generations(&self.path, self.policy.max_generations)?;
+        }
+        if state.file.is_none() {
+            let file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+            state.len = file.metadata().map(|m| m.len()).unwrap_or(0);
+            state.file = Some(file);
+        }
+        let written = match state.file.as_mut() {
+            Some(file) => file.write(buf)?,
+            None => 0,
+        };
+        state.len += written as u64;
+        Ok(written)
+    }
+}
+
+/// Empty `desktop.log` in place and delete its rotated generations, returning
+/// the bytes reclaimed. Holds the writer lock so no event lands mid-truncate.
+pub fn clear_desktop_log(path: &Path) -> io::Result<u64> {
+    let log = DESKTOP_LOG.get();
+    let state = log.map(|log| log.state.lock().unwrap_or_else(|e| e.into_inner()));
+    let mut reclaimed = crate::log_files::truncate_file(path)?;
+    let max_generations = log.map_or(LogConfig::default().max_generations, |log| log.policy.max_generations);
+    for generation in 1..=max_generations {
+        let rotated = generation_path(path, generation);
+        if let Ok(meta) = fs::metadata(&rotated) {
+            fs::remove_file(&rotated)?;
+            reclaimed += meta.len();
+        }
+    }
+    if let Some(mut state) = state {
+        state.len = 0;
+    }
+    Ok(reclaimed)
+}
+
+struct LogWriter(&'static RotatingLogFile);
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write_event(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// `desktop.log` -> `desktop.log.<generation>`.
+pub fn generation_path(path: &Path, generation: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{generation}"));
+    PathBuf::from(name)
+}
+
+/// Shift `log.N-1 -> log.N ... log -> log.1`, discarding the oldest. With zero
+/// generations configured the live file is simply truncated.
+fn rotate_generations(path: &Path, max_generations: usize) -> io::Result<()> {
+    if max_generations == 0 {
+        return fs::remove_file(path).or_else(ignore_missing);
+    }
+    fs::remove_file(generation_path(path, max_generations)).or_else(ignore_missing)?;
+    for generation in (1..max_generations).rev() {
+        let from = generation_path(path, generation);
+        if from.exists() {
+            fs::rename(&from, generation_path(path, generation + 1))?;
+        }
+    }
+    fs::rename(path, generation_path(path, 1)).or_else(ignore_missing)
+}
+
+fn ignore_missing(err: io::Error) -> io::Result<()> {
+    if err.kind() == io::ErrorKind::NotFound {
+        Ok(())
+    } else {
+        Err(err)
+    }
+}
+
+/// Startup cleanup: delete generations beyond `max_generations` and any older
+/// than `retention_days`. Returns how many files were removed.
+fn prune_generations(path: &Path, policy: &LogConfig, now: SystemTime) -> usize {
+    let (Some(dir), Some(base)) = (path.parent(), path.file_name()) else {
+        return 0;
+    };
+    let prefix = format!("{}.", base.to_string_lossy());
+    let retention = Duration::from_secs(policy.retention_days.saturating_mul(24 * 60 * 60));
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(generation) = name
+            .to_string_lossy()
+            .strip_prefix(&prefix)
+            .and_then(|suffix| suffix.parse::<usize>().ok())
+        else {
+            continue;
+        };
+        let expired = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > retention);
+        if (generation > policy.max_generations || expired) && fs::remove_file(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+type BoxedLayer<S> = Box<dyn Layer<S> + Send + Sync>;
+
+/// The platform log layer for this OS, if it can be opened.
+#[cfg(target_os = "linux")]
+fn native_layer<S>() -> Result<BoxedLayer<S>, String>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let layer = tracing_journald::layer().map_err(|e| format!("journald unavailable: {e}"))?;
+    Ok(Box::new(layer.with_syslog_identifier(NATIVE_LOG_IDENTIFIER.to_string())))
+}
+
+#[cfg(windows)]
+fn native_layer<S>() -> Result<BoxedLayer<S>, String>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let layer = tracing_layer_win_eventlog::EventLogLayer::new(NATIVE_LOG_IDENTIFIER)
+        .map_err(|e| format!("Windows Event Log unavailable: {e}"))?;
+    Ok(Box::new(layer))
+}
+
+#[cfg(target_os = "macos")]
+fn native_layer<S>() -> Result<BoxedLayer<S>, String>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    Ok(Box::new(tracing_oslog::OsLogger::new(NATIVE_LOG_SUBSYSTEM, "default")))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn native_layer<S>() -> Result<BoxedLayer<S>, String>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    Err("no native log backend on this platform".to_string())
+}
+
+/// Install the global subscriber. Must be called once, as early as the log
+/// directory can be resolved (i.e. at the top of Tauri `setup`).
+pub fn init(log_path: &Path, policy: LogConfig) -> Result<(), String> {
+    let pruned = prune_generations(log_path, &policy, SystemTime::now());
+    let (native, native_error) = if policy.native_backend {
+        match native_layer() {
+            Ok(layer) => (Some(layer), None),
+            Err(err) => (None, Some(err)),
+        }
+    } else {
+        (None, None)
+    };
+    let filter = EnvFilter::try_from_env(LOG_FILTER_ENV)
+        .unwrap_or_else(|_| EnvFilter::new(policy.level.as_str()));
+    let (filter, filter_handle) = reload::Layer::new(filter);
+    let _ = FILTER_HANDLE.set(filter_handle);
+    let format = policy.format;
+    let log_file = DESKTOP_LOG.get_or_init(|| RotatingLogFile::new(log_path.to_path_buf(), policy));
+
+    let file_layer: BoxedLayer<_> = match format {
+        LogFormat::Human => Box::new(
+            tracing_subscriber::fmt::layer()
+                .event_format(DesktopLogFormat)
+                .with_writer(move || LogWriter(log_file)),
+        ),
+        // The default `SystemTime` timer renders RFC 3339 UTC timestamps.
+        LogFormat::Json => Box::new(
+            tracing_subscriber::fmt::layer()
+                .json()
+                .flatten_event(true)
+                .with_current_span(false)
+                .with_span_list(false)
+                .with_writer(move || LogWriter(log_file)),
+        ),
+    };
+    let stderr_layer = tracing_subscriber::fmt::layer()
+        .event_format(DesktopLogFormat)
+        .with_writer(std::io::stderr);
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(file_layer)
+        .with(stderr_layer)
+        .with(native)
+        .try_init()
+        .map_err(|e| format!("Failed to install log subscriber: {e}"))?;
+
+    if pruned > 0 {
+        tracing::info!(target: "app", removed = pruned, "pruned expired desktop log generations");
+    }
+    if let Some(err) = native_error {
+        tracing::warn!(target: "app", "native log backend disabled: {err}");
+    }
+    Ok(())
+}
+
+/// Replace the active filter with a single global level.
+fn apply_level(level: LogLevel) -> Result<(), String> {
+    let handle = FILTER_HANDLE
+        .get()
+        .ok_or_else(|| "Logging is not initialized".to_string())?;
+    handle
+        .reload(EnvFilter::new(level.as_str()))
+        .map_err(|e| format!("Failed to apply log level: {e}"))
+}
+
+/// Change verbosity at runtime: applied to the Rust logger immediately,
+/// persisted to the desktop config, and forwarded to the sidecar (which also
+/// receives it via `LOCAL_API_LOG_LEVEL` on its next start).
+#[tauri::command]
+pub async fn set_log_level(
+    webview: Webview,
+    app: tauri::AppHandle,
+    config: tauri::State<'_, DesktopConfigState>,
+    level: LogLevel,
+) -> Result<(), String> {
+    crate::metrics::of(&webview)
+        .observe_async("set_log_level", async move {
+            crate::require_trusted_window(webview.label())?;
+            apply_level(level)?;
+            config.update(|c| c.log.level = level)?;
+            tracing::info!(target: "app", level = level.as_str(), "log level changed");
+            if let Err(err) =
+                crate::post_local_api_control(&app, "/api/local-log-level", serde_json::json!({ "level": level.as_str() })).await
+            {
+                tracing::warn!(target: "sidecar", "failed to forward log level to sidecar: {err}");
+            }
+            Ok(())
+        })
+        .await
+}
+
+#[tauri::command]
+pub fn get_log_level(webview: Webview, config: tauri::State<'_, DesktopConfigState>) -> Result<LogLevel, String> {
+    crate::metrics::of(&webview).observe("get_log_level", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(config.snapshot().log.level)
+    })
+}
+
+/// Per-window token bucket guarding `log_from_frontend`, so a render loop
+/// that throws every frame cannot flood (and rotate away) the desktop log.
+#[derive(Default)]
+pub struct FrontendLogLimiter {
+    buckets: Mutex<HashMap<String, LogBucket>>,
+}
+
+struct LogBucket {
+    tokens: f64,
+    refilled_at: Instant,
+    suppressed: u64,
+}
+
+impl FrontendLogLimiter {
+    /// Returns `None` when the event must be dropped, otherwise the number of
+    /// events dropped since the last admitted one.
+    fn admit(&self, window: &str, now: Instant) -> Option<u64> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = buckets.entry(window.to_string()).or_insert(LogBucket {
+            tokens: FRONTEND_LOG_BURST,
+            refilled_at: now,
+            suppressed: 0,
+        });
+        let elapsed = now.saturating_duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * FRONTEND_LOG_PER_SEC).min(FRONTEND_LOG_BURST);
+        bucket.refilled_at = now;
+        if bucket.tokens < 1.0 {
+            bucket.suppressed += 1;
+            return None;
+        }
+        bucket.tokens -= 1.0;
+        Some(std::mem::take(&mut bucket.suppressed))
+    }
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text.to_string(),
+    }
+}
+
+#[tauri::command]
+pub fn log_from_frontend(
+    webview: Webview,
+    limiter: tauri::State<'_, FrontendLogLimiter>,
+    level: String,
+    message: String,
+    context: Option<Value>,
+) -> Result<(), String> {
+    crate::metrics::of(&webview).observe("log_from_frontend", || {
+        crate::require_trusted_window(webview.label())?;
+        let window = webview.label();
+        let Some(suppressed) = limiter.admit(window, Instant::now()) else {
+            return Ok(());
+        };
+        if suppressed > 0 {
+            tracing::warn!(target: "frontend", window, suppressed, "frontend log rate limit dropped events");
+        }
+
+        let message = truncate_chars(&message, FRONTEND_LOG_MAX_CHARS);
+        let context = context
+            .filter(|c| !c.is_null())
+            .map(|c| truncate_chars(&c.to_string(), FRONTEND_LOG_MAX_CHARS));
+        let context = context.as_deref();
+        match level.to_ascii_lowercase().as_str() {
+            "error" => tracing::error!(target: "frontend", window, context, "{message}"),
+            "warn" | "warning" => tracing::warn!(target: "frontend", window, context, "{message}"),
+            "debug" => tracing::debug!(target: "frontend", window, context, "{message}"),
+            "trace" => tracing::trace!(target: "frontend", window, context, "{message}"),
+            _ => tracing::info!(target: "frontend", window, context, "{message}"),
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod frontend_log_tests {
+    use super::{truncate_chars, FrontendLogLimiter, FRONTEND_LOG_BURST};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn limiter_drops_after_burst_and_reports_suppressed_count() {
+        let limiter = FrontendLogLimiter::default();
+        let start = Instant::now();
+        for _ in 0..FRONTEND_LOG_BURST as usize {
+            assert_eq!(limiter.admit("main", start), Some(0));
+        }
+        assert_eq!(limiter.admit("main", start), None);
+        assert_eq!(limiter.admit("main", start), None);
+        // Other windows have their own budget.
+        assert_eq!(limiter.admit("settings", start), Some(0));
+        // One second refills enough for more events; drops are reported once.
+        let later = start + Duration::from_secs(1);
+        assert_eq!(limiter.admit("main", later), Some(2));
+        assert_eq!(limiter.admit("main", later), Some(0));
+    }
+
+    #[test]
+    fn truncate_respects_char_boundaries() {
+        assert_eq!(truncate_chars("héllo", 2), "hé…");
+        assert_eq!(truncate_chars("short", 10), "short");
+    }
+}
+
+#[cfg(test)]
+mod rotation_tests {
+    use super::{generation_path, prune_generations, RotatingLogFile};
+    use crate::config::LogConfig;
+    use std::fs::{self, File};
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    fn scratch_log(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("wm-log-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("desktop.log")
+    }
+
+    fn policy(max_file_bytes: u64, max_generations: usize) -> LogConfig {
+        LogConfig {
+            max_file_bytes,
+            max_generations,
+            retention_days: 14,
+            ..LogConfig::default()
+        }
+    }
+
+    #[test]
+    fn rotates_when_size_exceeded_and_caps_generations() {
+        let path = scratch_log("rotate");
+        let log = RotatingLogFile::new(path.clone(), policy(10, 2));
+        for line in ["first-xxx\n", "second-xx\n", "third-xxx\n", "fourth-xx\n"] {
+            log.write_event(line.as_bytes()).unwrap();
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fourth-xx\n");
+        assert_eq!(fs::read_to_string(generation_path(&path, 1)).unwrap(), "third-xxx\n");
+        assert_eq!(fs::read_to_string(generation_path(&path, 2)).unwrap(), "second-xx\n");
+        assert!(!generation_path(&path, 3).exists());
+    }
+
+    #[test]
+    fn oversized_single_event_is_still_written() {
+        let path = scratch_log("oversized");
+        let log = RotatingLogFile::new(path.clone(), policy(4, 1));
+        log.write_event(b"longer than the limit\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "longer than the limit\n");
+    }
+
+    #[test]
+    fn prune_removes_stale_and_excess_generations() {
+        let path = scratch_log("prune");
+        fs::write(&path, "live").unwrap();
+        for generation in 1..=4 {
+            fs::write(generation_path(&path, generation), "old").unwrap();
+        }
+        let stale = SystemTime::now() - Duration::from_secs(30 * 24 * 60 * 60);
+        File::options()
+            .write(true)
+            .open(generation_path(&path, 1))
+            .unwrap()
+            .set_modified(stale)
+            .unwrap();
+
+        let removed = prune_generations(&path, &policy(1024, 3), SystemTime::now());
+        assert_eq!(removed, 2);
+        assert!(path.exists());
+        assert!(!generation_path(&path, 1).exists());
+        assert!(generation_path(&path, 2).exists());
+        assert!(generation_path(&path, 3).exists());
+        assert!(!generation_path(&path, 4).exists());
+    }
+}
@@ -1,33 +1,141 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod about;
+mod accent_color;
+mod acled;
+mod adsb;
+mod ais;
+mod aivdm;
+mod alert_status;
+mod alerts;
+mod anomalies;
+mod appearance;
+mod attention;
+mod autostart;
+mod badging;
+mod clipboard;
+mod cloudflare;
+mod config;
+mod context_menu;
+mod crash_report;
+mod cyber;
+mod deep_links;
+mod diagnostics;
+mod digest;
+mod dock;
+mod eia;
+mod error_reporting;
+mod exports;
+mod faa;
+mod file_import;
+mod focus_mode;
+mod fred;
+mod gdelt;
+mod geo_export;
+mod geodesic;
+mod geofences;
+mod geometry;
+mod hazards;
+mod idle;
+mod image_metadata;
+mod keep_awake;
+mod launch;
+mod links;
+mod llm;
+mod locale;
+mod log_files;
+mod logging;
+mod map_display;
+mod maritime;
+mod markets;
+mod metrics;
+mod monitoring;
+mod mqtt;
+mod network;
+mod news;
+mod notifications;
+mod opensky;
+mod orbits;
+mod panel_windows;
+mod pdf_export;
+mod poll;
+mod power;
+mod proxy;
+mod relay;
+mod restart;
+mod runtime;
+mod screenshot;
+mod share;
+mod shortcuts;
+mod single_instance;
+mod sounds;
+mod speech;
+mod splash;
+mod startup;
+mod store;
+mod sun;
+mod swpc;
+mod ticker;
+mod tile_cache;
+mod timezone;
+mod tray;
+mod usgs;
+mod watchdog;
+mod watchlists;
+mod weather;
+mod webhooks;
+mod window_state;
+
 use std::collections::HashMap;
 use std::env;
 use std::fs::{self, File, OpenOptions};
-use std::io::Write;
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
 use std::sync::Mutex;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use keyring::Entry;
 use reqwest::Url;
+use serde::de::DeserializeOwned;
 use serde::Serialize;
 use serde_json::{Map, Value};
-use tauri::menu::{AboutMetadata, Menu, MenuItem, PredefinedMenuItem, Submenu};
-use tauri::{AppHandle, Manager, RunEvent, Webview, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+use config::{DesktopConfig, DesktopConfigState, LaunchWindow};
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::webview::PageLoadEvent;
+use tauri::{AppHandle, DragDropEvent, Emitter, Manager, RunEvent, Webview, WebviewUrl, WebviewWindowBuilder, WindowEvent};
 
 const DEFAULT_LOCAL_API_PORT: u16 = 46123;
 const KEYRING_SERVICE: &str = "world-monitor";
 const LOCAL_API_LOG_FILE: &str = "local-api.log";
 const DESKTOP_LOG_FILE: &str = "desktop.log";
+const SIDECAR_EXIT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Sent to the feeds and tile servers the shell fetches from directly.
+const USER_AGENT: &str = concat!("WorldMonitor/", env!("CARGO_PKG_VERSION"), " (+https://worldmonitor.app)");
+/// Settings tabs that can be opened directly.
+const SETTINGS_SECTIONS: [&str; 5] = ["worldmonitor", "llms", "api-keys", "desktop", "debug"];
+const SETTINGS_NAVIGATE_EVENT: &str = "settings://navigate";
 const MENU_FILE_SETTINGS_ID: &str = "file.settings";
-const MENU_HELP_GITHUB_ID: &str = "help.github";
+const MENU_FILE_QUIT_ID: &str = "file.quit";
+const MENU_VIEW_ID: &str = "view";
+const MENU_VIEW_ALWAYS_ON_TOP_ID: &str = "view.always-on-top";
+const MENU_VIEW_ZOOM_IN_ID: &str = "view.zoom-in";
+const MENU_VIEW_ZOOM_OUT_ID: &str = "view.zoom-out";
+const MENU_VIEW_ZOOM_RESET_ID: &str = "view.zoom-reset";
+const MENU_VIEW_RELOAD_ID: &str = "view.reload";
+const MENU_VIEW_FORCE_RELOAD_ID: &str = "view.force-reload";
 #[cfg(feature = "devtools")]
-const MENU_HELP_DEVTOOLS_ID: &str = "help.devtools";
-const TRUSTED_WINDOWS: [&str; 3] = ["main", "settings", "live-channels"];
-const SUPPORTED_SECRET_KEYS: [&str; 22] = [
+const MENU_VIEW_DEVTOOLS_ID: &str = "view.devtools";
+const MENU_WINDOW_MAP_DISPLAY_ID: &str = "window.map-display";
+const MENU_DEBUG_LOG_VIEWER_ID: &str = "debug.log-viewer";
+const MENU_DEBUG_DESKTOP_LOG_ID: &str = "debug.desktop-log";
+const MENU_DEBUG_SIDECAR_LOG_ID: &str = "debug.sidecar-log";
+const MENU_DEBUG_ALL_LOGS_ID: &str = "debug.all-logs";
+const MENU_HELP_GITHUB_ID: &str = "help.github";
+const TRUSTED_WINDOWS: [&str; 6] = ["main", "settings", "live-channels", "log-viewer", "map-display", "ticker"];
+const SUPPORTED_SECRET_KEYS: [&str; 27] = [
     "GROQ_API_KEY",
     "OPENROUTER_API_KEY",
     "FRED_API_KEY",
@@ -50,6 +158,11 @@ const SUPPORTED_SECRET_KEYS: [&str; 22] = [
     "OLLAMA_MODEL",
     "WORLDMONITOR_API_KEY",
     "WTO_API_KEY",
+    "FAA_NOTAM_CLIENT_ID",
+    "FAA_NOTAM_CLIENT_SECRET",
+    "RELAY_SHARED_SECRET",
+    "MQTT_PASSWORD",
+    "ALERT_WEBHOOK_SECRET",
 ];
 
 #[derive(Default)]
@@ -61,8 +174,11 @@ struct LocalApiState {
 
 /// In-memory cache for keychain secrets. Populated once at startup to avoid
 /// repeated macOS Keychain prompts (each `Entry::get_password()` triggers one).
+/// Vault writes are serialized by `write_lock` rather than `secrets`, so
+/// readers are not held up while a write waits on the keychain.
 struct SecretsCache {
     secrets: Mutex<HashMap<String, String>>,
+    write_lock: Mutex<()>,
 }
 
 /// In-memory mirror of persistent-cache.json. The file can grow to 10+ MB,
@@ -72,6 +188,22 @@ struct PersistentCache {
     data: Mutex<Map<String, Value>>,
     dirty: Mutex<bool>,
     write_lock: Mutex<()>,
+    recovery: Option<CacheRecoveryReport>,
+    /// The unreadable file could not be moved aside, so it is never written
+    /// over: writes fail until it is dealt with.
+    unquarantined: bool,
+}
+
+/// Diagnostic produced when persistent-cache.json fails to parse at startup.
+/// The unreadable file is moved aside (never overwritten) and the last good
+/// backup, if any, is restored in its place.
+#[derive(Clone, Debug, Serialize)]
+struct CacheRecoveryReport {
+    error: String,
+    quarantined_path: Option<String>,
+    restored_from_backup: bool,
+    recovered_entries: usize,
+    detected_at: u64,
 }
 
 impl SecretsCache {
@@ -87,8 +219,10 @@ impl SecretsCache {
                         })
                         .map(|(k, v)| (k, v.trim().to_string()))
                         .collect();
+                    tracing::info!(target: "secrets", count = secrets.len(), "loaded secrets vault");
                     return SecretsCache {
                         secrets: Mutex::new(secrets),
+                        write_lock: Mutex::new(()),
                     };
                 }
             }
@@ -113,6 +247,11 @@ impl SecretsCache {
             if let Ok(json) = serde_json::to_string(&secrets) {
                 if let Ok(vault_entry) = Entry::new(KEYRING_SERVICE, "secrets-vault") {
                     if vault_entry.set_password(&json).is_ok() {
+                        tracing::info!(
+                            target: "secrets",
+                            count = secrets.len(),
+                            "migrated legacy keychain entries into secrets vault"
+                        );
                         for key in SUPPORTED_SECRET_KEYS.iter() {
                             if let Ok(entry) = Entry::new(KEYRING_SERVICE, key) {
                                 let _ = entry.delete_credential();
@@ -125,25 +264,67 @@ impl SecretsCache {
 
         SecretsCache {
             secrets: Mutex::new(secrets),
+            write_lock: Mutex::new(()),
         }
     }
+
+    fn get(&self, key: &str) -> Option<String> {
+        self.secrets.lock().unwrap_or_else(|e| e.into_inner()).get(key).cloned()
+    }
+
+    /// Apply `change` to a copy of the secrets, persist it, then commit it
+    /// to the cache. Blocks on the keychain, which may prompt.
+    fn update(&self, change: impl FnOnce(&mut HashMap<String, String>)) -> Result<(), String> {
+        let _write_guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut proposed = self.secrets.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        change(&mut proposed);
+        save_vault(&proposed)?;
+        *self.secrets.lock().unwrap_or_else(|e| e.into_inner()) = proposed;
+        Ok(())
+    }
+
+    /// Merge `entries` (already limited to supported keys) into the vault.
+    fn import(&self, entries: &HashMap<String, String>) -> Result<(), String> {
+        self.update(|secrets| secrets.extend(entries.iter().map(|(k, v)| (k.clone(), v.clone()))))
+    }
 }
 
 impl PersistentCache {
     fn load(path: &Path) -> Self {
-        let data = if path.exists() {
-            std::fs::read_to_string(path)
-                .ok()
-                .and_then(|s| serde_json::from_str::<Value>(&s).ok())
-                .and_then(|v| v.as_object().cloned())
-                .unwrap_or_default()
-        } else {
-            Map::new()
+        let backup = cache_backup_path(path);
+        let (data, recovery) = match read_cache_file(path) {
+            Ok(Some(data)) => {
+                // Snapshot the last successfully parsed state for recovery.
+                let _ = fs::copy(path, &backup);
+                (data, None)
+            }
+            Ok(None) => (Map::new(), None),
+            Err(error) => {
+                let detected_at = unix_timestamp_secs();
+                let quarantine = quarantine_path(path, detected_at);
+                let quarantined_path = fs::rename(path, &quarantine)
+                    .ok()
+                    .map(|_| quarantine.display().to_string());
+                let restored = read_cache_file(&backup).ok().flatten();
+                let report = CacheRecoveryReport {
+                    error,
+                    quarantined_path,
+                    restored_from_backup: restored.is_some(),
+                    recovered_entries: restored.as_ref().map_or(0, |m| m.len()),
+                    detected_at,
+                };
+                (restored.unwrap_or_default(), Some(report))
+            }
         };
+        let unquarantined = recovery.as_ref().is_some_and(|r| r.quarantined_path.is_none());
+        // A restored backup has not been written back yet; flush it on exit.
+        let dirty = !unquarantined && recovery.as_ref().is_some_and(|r| r.restored_from_backup);
         PersistentCache {
             data: Mutex::new(data),
-            dirty: Mutex::new(false),
+            dirty: Mutex::new(dirty),
             write_lock: Mutex::new(()),
+            recovery,
+            unquarantined,
         }
     }
 
@@ -164,18 +345,200 @@ impl PersistentCache {
             return Ok(false);
         }
 
+        self.write_to_disk(path)?;
+        let mut dirty = self.dirty.lock().unwrap_or_else(|e| e.into_inner());
+        *dirty = false;
+        Ok(true)
+    }
+
+    /// Serialize and atomically replace the cache file (write temp + rename) so
+    /// a crash mid-write cannot leave a truncated, unparseable store behind.
+    /// Callers must hold `write_lock`.
+    fn write_to_disk(&self, path: &Path) -> Result<(), String> {
+        if self.unquarantined {
+            return Err(format!("Cache {} is unreadable and could not be moved aside", path.display()));
+        }
         let data = self.data.lock().unwrap_or_else(|e| e.into_inner());
         let serialized = serde_json::to_string(&Value::Object(data.clone()))
             .map_err(|e| format!("Failed to serialize cache: {e}"))?;
         drop(data);
-        std::fs::write(path, serialized)
-            .map_err(|e| format!("Failed to write cache {}: {e}", path.display()))?;
-        let mut dirty = self.dirty.lock().unwrap_or_else(|e| e.into_inner());
-        *dirty = false;
-        Ok(true)
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serialized)
+            .map_err(|e| format!("Failed to write cache {}: {e}", tmp_path.display()))?;
+        std::fs::rename(&tmp_path, path)
+            .map_err(|e| format!("Failed to replace cache {}: {e}", path.display()))
+    }
+}
+
+/// Read and parse a cache file. `Ok(None)` means the file does not exist;
+/// `Err` means it exists but is unreadable or not a JSON object.
+fn read_cache_file(path: &Path) -> Result<Option<Map<String, Value>>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read cache {}: {e}", path.display()))?;
+    match serde_json::from_str::<Value>(&contents) {
+        Ok(Value::Object(map)) => Ok(Some(map)),
+        Ok(_) => Err("Cache root is not a JSON object".to_string()),
+        Err(e) => Err(format!("Cache JSON parse failed: {e}")),
+    }
+}
+
+/// Cache keys are namespaced by their first `:`-separated segment
+/// (e.g. `feed:world`, `summary:world-brief`).
+fn cache_namespace(key: &str) -> Option<String> {
+    key.split_once(':')
+        .map(|(namespace, _)| namespace.to_string())
+        .filter(|namespace| !namespace.is_empty())
+}
+
+fn emit_cache_changed(app: &AppHandle, key: String, change: &'static str, origin: &str) {
+    let event = CacheChangedEvent {
+        namespace: cache_namespace(&key),
+        key,
+        change,
+        origin: origin.to_string(),
+    };
+    let _ = app.emit("cache://changed", event);
+}
+
+fn cache_backup_path(path: &Path) -> PathBuf {
+    path.with_extension("json.bak")
+}
+
+fn quarantine_path(path: &Path, timestamp: u64) -> PathBuf {
+    path.with_extension(format!("corrupt-{timestamp}.json"))
+}
+
+/// Read a JSON file the app rewrites as it changes (alert rules, geofences,
+/// export jobs). A missing file is the default. One that cannot be read or
+/// parsed is logged and moved aside like the cache, so the next save starts
+/// fresh instead of overwriting it; the flag is true if it could not be
+/// moved, and then the caller must not save over it.
+fn load_json_file<T: DeserializeOwned + Default>(path: &Path) -> (T, bool) {
+    let error = match fs::read_to_string(path) {
+        Ok(contents) => match serde_json::from_str(&contents) {
+            Ok(value) => return (value, false),
+            Err(e) => e.to_string(),
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return (T::default(), false),
+        Err(e) => e.to_string(),
+    };
+    let quarantine = quarantine_path(path, unix_timestamp_secs());
+    let quarantined_path = fs::rename(path, &quarantine)
+        .ok()
+        .map(|_| quarantine.display().to_string());
+    tracing::error!(
+        target: "app",
+        path = %path.display(),
+        %error,
+        quarantined = quarantined_path.as_deref().unwrap_or("<rename failed>"),
+        "unreadable file ignored"
+    );
+    (T::default(), quarantined_path.is_none())
+}
+
+fn unix_timestamp_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod load_json_file_tests {
+    use super::load_json_file;
+    use std::fs;
+
+    #[test]
+    fn moves_an_unparseable_file_aside() {
+        let dir = std::env::temp_dir().join(format!("wm-load-json-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("rules.json");
+        assert_eq!(load_json_file::<Vec<u32>>(&path), (Vec::new(), false));
+        fs::write(&path, "[1, 2").unwrap();
+        assert_eq!(load_json_file::<Vec<u32>>(&path), (Vec::new(), false));
+        assert!(!path.exists());
+        let moved: Vec<_> = fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+        assert_eq!(moved.len(), 1);
+        assert_eq!(fs::read_to_string(&moved[0]).unwrap(), "[1, 2");
+        fs::write(&path, "[3]").unwrap();
+        assert_eq!(load_json_file::<Vec<u32>>(&path), (vec![3], false));
+    }
+}
+
+#[cfg(test)]
+mod persistent_cache_tests {
+    use super::{cache_backup_path, cache_namespace, PersistentCache};
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("wm-cache-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn valid_file_loads_and_refreshes_backup() {
+        let dir = scratch_dir("valid");
+        let path = dir.join("persistent-cache.json");
+        fs::write(&path, r#"{"a":1}"#).unwrap();
+        let cache = PersistentCache::load(&path);
+        assert!(cache.recovery.is_none());
+        assert_eq!(cache.get("a"), Some(serde_json::json!(1)));
+        assert_eq!(fs::read_to_string(cache_backup_path(&path)).unwrap(), r#"{"a":1}"#);
+    }
+
+    #[test]
+    fn corrupt_file_is_quarantined_and_backup_restored() {
+        let dir = scratch_dir("corrupt");
+        let path = dir.join("persistent-cache.json");
+        fs::write(cache_backup_path(&path), r#"{"good":true}"#).unwrap();
+        fs::write(&path, "{\"trunc").unwrap();
+        let cache = PersistentCache::load(&path);
+        let report = cache.recovery.clone().expect("corruption reported");
+        assert!(report.restored_from_backup);
+        assert_eq!(report.recovered_entries, 1);
+        assert_eq!(cache.get("good"), Some(serde_json::json!(true)));
+        assert!(!path.exists());
+        let quarantined = PathBuf::from(report.quarantined_path.unwrap());
+        assert_eq!(fs::read_to_string(quarantined).unwrap(), "{\"trunc");
+    }
+
+    #[test]
+    fn namespace_is_first_key_segment() {
+        assert_eq!(cache_namespace("feed:world:tech"), Some("feed".to_string()));
+        assert_eq!(cache_namespace("summary:world-brief"), Some("summary".to_string()));
+        assert_eq!(cache_namespace("plain-key"), None);
+        assert_eq!(cache_namespace(":orphan"), None);
+    }
+
+    #[test]
+    fn non_object_root_without_backup_starts_empty() {
+        let dir = scratch_dir("array");
+        let path = dir.join("persistent-cache.json");
+        fs::write(&path, "[1,2,3]").unwrap();
+        let cache = PersistentCache::load(&path);
+        let report = cache.recovery.clone().expect("corruption reported");
+        assert!(!report.restored_from_backup);
+        assert!(cache.get("anything").is_none());
     }
 }
 
+/// Payload of `cache://changed`, emitted to every window after a cache entry
+/// is durably written or removed so other windows can react without polling.
+#[derive(Clone, Serialize)]
+struct CacheChangedEvent {
+    key: String,
+    namespace: Option<String>,
+    change: &'static str,
+    origin: String,
+}
+
 #[derive(Serialize)]
 struct DesktopRuntimeInfo {
     os: String,
@@ -201,7 +564,7 @@ fn generate_local_token() -> String {
 }
 
 fn require_trusted_window(label: &str) -> Result<(), String> {
-    if TRUSTED_WINDOWS.contains(&label) {
+    if TRUSTED_WINDOWS.contains(&label) || panel_windows::is_panel_window(label) {
         Ok(())
     } else {
         Err(format!("Command not allowed from window '{label}'"))
@@ -210,14 +573,16 @@ fn require_trusted_window(label: &str) -> Result<(), String> {
 
 #[tauri::command]
 fn get_local_api_token(webview: Webview, state: tauri::State<'_, LocalApiState>) -> Result<String, String> {
-    require_trusted_window(webview.label())?;
-    let token = state
-        .token
-        .lock()
-        .map_err(|_| "Failed to lock local API token".to_string())?;
-    token
-        .clone()
-        .ok_or_else(|| "Token not generated".to_string())
+    metrics::of(&webview).observe("get_local_api_token", || {
+        require_trusted_window(webview.label())?;
+        let token = state
+            .token
+            .lock()
+            .map_err(|_| "Failed to lock local API token".to_string())?;
+        token
+            .clone()
+            .ok_or_else(|| "Token not generated".to_string())
+    })
 }
 
 #[tauri::command]
@@ -232,10 +597,12 @@ fn get_desktop_runtime_info(state: tauri::State<'_, LocalApiState>) -> DesktopRu
 
 #[tauri::command]
 fn get_local_api_port(webview: Webview, state: tauri::State<'_, LocalApiState>) -> Result<u16, String> {
-    require_trusted_window(webview.label())?;
-    state.port.lock()
-        .map_err(|_| "Failed to lock port state".to_string())?
-        .ok_or_else(|| "Port not yet assigned".to_string())
+    metrics::of(&webview).observe("get_local_api_port", || {
+        require_trusted_window(webview.label())?;
+        state.port.lock()
+            .map_err(|_| "Failed to lock port state".to_string())?
+            .ok_or_else(|| "Port not yet assigned".to_string())
+    })
 }
 
 #[tauri::command]
@@ -252,70 +619,73 @@ fn get_secret(
     key: String,
     cache: tauri::State<'_, SecretsCache>,
 ) -> Result<Option<String>, String> {
-    require_trusted_window(webview.label())?;
-    if !SUPPORTED_SECRET_KEYS.contains(&key.as_str()) {
-        return Err(format!("Unsupported secret key: {key}"));
-    }
-    let secrets = cache
-        .secrets
-        .lock()
-        .map_err(|_| "Lock poisoned".to_string())?;
-    Ok(secrets.get(&key).cloned())
+    metrics::of(&webview).observe("get_secret", || {
+        require_trusted_window(webview.label())?;
+        if !SUPPORTED_SECRET_KEYS.contains(&key.as_str()) {
+            return Err(format!("Unsupported secret key: {key}"));
+        }
+        let secrets = cache
+            .secrets
+            .lock()
+            .map_err(|_| "Lock poisoned".to_string())?;
+        Ok(secrets.get(&key).cloned())
+    })
 }
 
 #[tauri::command]
 fn get_all_secrets(webview: Webview, cache: tauri::State<'_, SecretsCache>) -> Result<HashMap<String, String>, String> {
-    require_trusted_window(webview.label())?;
-    Ok(cache
-        .secrets
-        .lock()
-        .unwrap_or_else(|e| e.into_inner())
-        .clone())
+    metrics::of(&webview).observe("get_all_secrets", || {
+        require_trusted_window(webview.label())?;
+        Ok(cache
+            .secrets
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone())
+    })
 }
 
 #[tauri::command]
-fn set_secret(
-    webview: Webview,
-    key: String,
-    value: String,
-    cache: tauri::State<'_, SecretsCache>,
-) -> Result<(), String> {
-    require_trusted_window(webview.label())?;
-    if !SUPPORTED_SECRET_KEYS.contains(&key.as_str()) {
-        return Err(format!("Unsupported secret key: {key}"));
-    }
-    let mut secrets = cache
-        .secrets
-        .lock()
-        .map_err(|_| "Lock poisoned".to_string())?;
-    let trimmed = value.trim().to_string();
-    // Build proposed state, persist first, then commit to cache
-    let mut proposed = secrets.clone();
-    if trimmed.is_empty() {
-        proposed.remove(&key);
-    } else {
-        proposed.insert(key, trimmed);
-    }
-    save_vault(&proposed)?;
-    *secrets = proposed;
-    Ok(())
+async fn set_secret(webview: Webview, app: AppHandle, key: String, value: String) -> Result<(), String> {
+    metrics::of(&webview)
+        .observe_async("set_secret", async move {
+            require_trusted_window(webview.label())?;
+            if !SUPPORTED_SECRET_KEYS.contains(&key.as_str()) {
+                return Err(format!("Unsupported secret key: {key}"));
+            }
+            let trimmed = value.trim().to_string();
+            runtime::blocking(move || {
+                // Persist first, then commit to cache
+                app.state::<SecretsCache>().update(|secrets| {
+                    if trimmed.is_empty() {
+                        secrets.remove(&key);
+                    } else {
+                        secrets.insert(key.clone(), trimmed);
+                    }
+                })?;
+                tracing::debug!(target: "secrets", key = %key, "secret updated");
+                Ok(())
+            })
+            .await
+        })
+        .await
 }
 
 #[tauri::command]
-fn delete_secret(webview: Webview, key: String, cache: tauri::State<'_, SecretsCache>) -> Result<(), String> {
-    require_trusted_window(webview.label())?;
-    if !SUPPORTED_SECRET_KEYS.contains(&key.as_str()) {
-        return Err(format!("Unsupported secret key: {key}"));
-    }
-    let mut secrets = cache
-        .secrets
-        .lock()
-        .map_err(|_| "Lock poisoned".to_string())?;
-    let mut proposed = secrets.clone();
-    proposed.remove(&key);
-    save_vault(&proposed)?;
-    *secrets = proposed;
-    Ok(())
+async fn delete_secret(webview: Webview, app: AppHandle, key: String) -> Result<(), String> {
+    metrics::of(&webview)
+        .observe_async("delete_secret", async move {
+            require_trusted_window(webview.label())?;
+            if !SUPPORTED_SECRET_KEYS.contains(&key.as_str()) {
+                return Err(format!("Unsupported secret key: {key}"));
+            }
+            runtime::blocking(move || {
+                app.state::<SecretsCache>().update(|secrets| {
+                    secrets.remove(&key);
+                })
+            })
+            .await
+        })
+        .await
 }
 
 fn cache_file_path(app: &AppHandle) -> Result<PathBuf, String> {
@@ -328,55 +698,134 @@ fn cache_file_path(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(dir.join("persistent-cache.json"))
 }
 
+/// Entries can be several megabytes, so the copy is made off the IPC thread.
 #[tauri::command]
-fn read_cache_entry(webview: Webview, cache: tauri::State<'_, PersistentCache>, key: String) -> Result<Option<Value>, String> {
-    require_trusted_window(webview.label())?;
-    Ok(cache.get(&key))
+async fn read_cache_entry(webview: Webview, app: AppHandle, key: String) -> Result<Option<Value>, String> {
+    metrics::of(&webview)
+        .observe_async("read_cache_entry", async move {
+            require_trusted_window(webview.label())?;
+            runtime::blocking(move || Ok(app.state::<PersistentCache>().get(&key))).await
+        })
+        .await
 }
 
 #[tauri::command]
-fn delete_cache_entry(webview: Webview, cache: tauri::State<'_, PersistentCache>, key: String) -> Result<(), String> {
-    require_trusted_window(webview.label())?;
-    {
-        let mut data = cache.data.lock().unwrap_or_else(|e| e.into_inner());
-        data.remove(&key);
-    }
-    {
-        let mut dirty = cache.dirty.lock().unwrap_or_else(|e| e.into_inner());
-        *dirty = true;
-    }
-    // Disk flush deferred to exit handler (cache.flush) — avoids blocking main thread
-    Ok(())
+fn get_cache_recovery_report(webview: Webview, cache: tauri::State<'_, PersistentCache>) -> Result<Option<CacheRecoveryReport>, String> {
+    metrics::of(&webview).observe("get_cache_recovery_report", || {
+        require_trusted_window(webview.label())?;
+        Ok(cache.recovery.clone())
+    })
 }
 
 #[tauri::command]
-fn write_cache_entry(webview: Webview, app: AppHandle, cache: tauri::State<'_, PersistentCache>, key: String, value: String) -> Result<(), String> {
-    require_trusted_window(webview.label())?;
-    let parsed_value: Value = serde_json::from_str(&value)
-        .map_err(|e| format!("Invalid cache payload JSON: {e}"))?;
-    let _write_guard = cache.write_lock.lock().unwrap_or_else(|e| e.into_inner());
-    {
-        let mut data = cache.data.lock().unwrap_or_else(|e| e.into_inner());
-        data.insert(key, parsed_value);
-    }
-    {
-        let mut dirty = cache.dirty.lock().unwrap_or_else(|e| e.into_inner());
-        *dirty = true;
-    }
+async fn delete_cache_entry(webview: Webview, app: AppHandle, key: String) -> Result<(), String> {
+    metrics::of(&webview)
+        .observe_async("delete_cache_entry", async move {
+            require_trusted_window(webview.label())?;
+            let origin = webview.label().to_string();
+            runtime::blocking(move || {
+                let cache = app.state::<PersistentCache>();
+                let _write_guard = cache.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+                let removed = {
+                    let mut data = cache.data.lock().unwrap_or_else(|e| e.into_inner());
+                    data.remove(&key).is_some()
+                };
+                if !removed {
+                    return Ok(());
+                }
+                {
+                    let mut dirty = cache.dirty.lock().unwrap_or_else(|e| e.into_inner());
+                    *dirty = true;
+                }
 
-    // Flush synchronously under write lock so concurrent writes cannot reorder.
-    let path = cache_file_path(&app)?;
-    let data = cache.data.lock().unwrap_or_else(|e| e.into_inner());
-    let serialized = serde_json::to_string(&Value::Object(data.clone()))
-        .map_err(|e| format!("Failed to serialize cache: {e}"))?;
-    drop(data);
-    std::fs::write(&path, &serialized)
-        .map_err(|e| format!("Failed to write cache {}: {e}", path.display()))?;
-    {
-        let mut dirty = cache.dirty.lock().unwrap_or_else(|e| e.into_inner());
-        *dirty = false;
-    }
-    Ok(())
+                // Flush under write lock, as for writes, so the event follows the disk.
+                let path = cache_file_path(&app)?;
+                cache.write_to_disk(&path)?;
+                {
+                    let mut dirty = cache.dirty.lock().unwrap_or_else(|e| e.into_inner());
+                    *dirty = false;
+                }
+                emit_cache_changed(&app, key, "deleted", &origin);
+                Ok(())
+            })
+            .await
+        })
+        .await
+}
+
+#[tauri::command]
+async fn write_cache_entry(webview: Webview, app: AppHandle, key: String, value: String) -> Result<(), String> {
+    metrics::of(&webview)
+        .observe_async("write_cache_entry", async move {
+            require_trusted_window(webview.label())?;
+            let origin = webview.label().to_string();
+            runtime::blocking(move || {
+                let parsed_value: Value = serde_json::from_str(&value)
+                    .map_err(|e| format!("Invalid cache payload JSON: {e}"))?;
+                let cache = app.state::<PersistentCache>();
+                let _write_guard = cache.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+                {
+                    let mut data = cache.data.lock().unwrap_or_else(|e| e.into_inner());
+                    data.insert(key.clone(), parsed_value);
+                }
+                {
+                    let mut dirty = cache.dirty.lock().unwrap_or_else(|e| e.into_inner());
+                    *dirty = true;
+                }
+
+                // Flush under write lock so concurrent writes cannot reorder.
+                let path = cache_file_path(&app)?;
+                cache.write_to_disk(&path)?;
+                {
+                    let mut dirty = cache.dirty.lock().unwrap_or_else(|e| e.into_inner());
+                    *dirty = false;
+                }
+                emit_cache_changed(&app, key, "written", &origin);
+                Ok(())
+            })
+            .await
+        })
+        .await
+}
+
+fn desktop_config_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(cache_file_path(app)?.with_file_name(config::DESKTOP_CONFIG_FILE))
+}
+
+fn window_state_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(cache_file_path(app)?.with_file_name(window_state::WINDOW_STATE_FILE))
+}
+
+fn panel_windows_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(cache_file_path(app)?.with_file_name(panel_windows::PANEL_WINDOWS_FILE))
+}
+
+fn map_display_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(cache_file_path(app)?.with_file_name(map_display::MAP_DISPLAY_FILE))
+}
+
+fn alert_rules_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(cache_file_path(app)?.with_file_name(alerts::ALERT_RULES_FILE))
+}
+
+fn export_jobs_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(cache_file_path(app)?.with_file_name(exports::EXPORT_JOBS_FILE))
+}
+
+fn geofences_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(cache_file_path(app)?.with_file_name(geofences::GEOFENCES_FILE))
+}
+
+fn weather_locations_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(cache_file_path(app)?.with_file_name(weather::WEATHER_LOCATIONS_FILE))
+}
+
+fn restart_state_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(cache_file_path(app)?.with_file_name(restart::RESTART_STATE_FILE))
+}
+
+fn store_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(cache_file_path(app)?.with_file_name(store::STORE_FILE))
 }
 
 fn logs_dir_path(app: &AppHandle) -> Result<PathBuf, String> {
@@ -397,22 +846,6 @@ fn desktop_log_path(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(logs_dir_path(app)?.join(DESKTOP_LOG_FILE))
 }
 
-fn append_desktop_log(app: &AppHandle, level: &str, message: &str) {
-    let Ok(path) = desktop_log_path(app) else {
-        return;
-    };
-
-    let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) else {
-        return;
-    };
-
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0);
-    let _ = writeln!(file, "[{timestamp}][{level}] {message}");
-}
-
 fn open_in_shell(arg: &str) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     let mut command = {
@@ -445,17 +878,88 @@ fn open_path_in_shell(path: &Path) -> Result<(), String> {
     open_in_shell(&path.to_string_lossy())
 }
 
-#[tauri::command]
-fn open_url(url: String) -> Result<(), String> {
-    let parsed = Url::parse(&url).map_err(|_| "Invalid URL".to_string())?;
-
-    match parsed.scheme() {
-        "https" => open_in_shell(parsed.as_str()),
-        "http" => match parsed.host_str() {
-            Some("localhost") | Some("127.0.0.1") => open_in_shell(parsed.as_str()),
-            _ => Err("Only https:// URLs are allowed (http:// only for localhost)".to_string()),
-        },
-        _ => Err("Only https:// URLs are allowed (http:// only for localhost)".to_string()),
+/// Open the file manager on `path`'s folder with the file selected. Linux has
+/// no universal "select" verb, so it asks the FileManager1 D-Bus service and
+/// falls back to opening the folder.
+fn reveal_in_shell(path: &Path) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        Command::new("open")
+            .arg("-R")
+            .arg(path)
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to reveal {}: {e}", path.display()))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        Command::new("explorer")
+            .arg("/select,")
+            .arg(path)
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to reveal {}: {e}", path.display()))
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        let uri = Url::from_file_path(path)
+            .map_err(|_| format!("Invalid log path {}", path.display()))?;
+        let shown = Command::new("dbus-send")
+            .args([
+                "--session",
+                "--dest=org.freedesktop.FileManager1",
+                "--type=method_call",
+                "/org/freedesktop/FileManager1",
+                "org.freedesktop.FileManager1.ShowItems",
+            ])
+            .arg(format!("array:string:{uri}"))
+            .arg("string:")
+            .status()
+            .is_ok_and(|status| status.success());
+        if shown {
+            return Ok(());
+        }
+        open_path_in_shell(path.parent().unwrap_or(path))
+    }
+}
+
+/// The most recently modified log in `dir`, counting rotated generations
+/// (`desktop.log.1`, ...) as well as the live files.
+fn most_recent_log(dir: &Path) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            name.starts_with(DESKTOP_LOG_FILE) || name.starts_with(LOCAL_API_LOG_FILE)
+        })
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+#[cfg(test)]
+mod most_recent_log_tests {
+    use super::most_recent_log;
+    use std::fs::{self, File};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn picks_newest_log_across_rotations() {
+        let dir = std::env::temp_dir().join(format!("wm-recent-log-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        assert!(most_recent_log(&dir).is_none());
+
+        let now = SystemTime::now();
+        for (name, age_secs) in [("desktop.log", 60), ("desktop.log.1", 5), ("local-api.log", 30), ("crash-1.json", 0)] {
+            let file = File::create(dir.join(name)).unwrap();
+            file.set_modified(now - Duration::from_secs(age_secs)).unwrap();
+        }
+        assert_eq!(most_recent_log(&dir), Some(dir.join("desktop.log.1")));
+        let _ = fs::remove_dir_all(&dir);
     }
 }
 
@@ -475,29 +979,112 @@ fn open_sidecar_log_impl(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(log_path)
 }
 
+fn open_desktop_log_impl(app: &AppHandle) -> Result<PathBuf, String> {
+    let log_path = desktop_log_path(app)?;
+    if !log_path.exists() {
+        File::create(&log_path)
+            .map_err(|e| format!("Failed to create desktop log {}: {e}", log_path.display()))?;
+    }
+    open_path_in_shell(&log_path)?;
+    Ok(log_path)
+}
+
+/// Open the logs folder with the most recently written log highlighted, or
+/// just the folder when there are no logs yet.
+fn open_all_logs_impl(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = logs_dir_path(app)?;
+    match most_recent_log(&dir) {
+        Some(latest) => {
+            reveal_in_shell(&latest)?;
+            Ok(latest)
+        }
+        None => {
+            open_path_in_shell(&dir)?;
+            Ok(dir)
+        }
+    }
+}
+
+#[tauri::command]
+async fn open_logs_folder(app: AppHandle) -> Result<String, String> {
+    metrics::of(&app)
+        .observe_async("open_logs_folder", async move {
+            runtime::blocking(move || open_logs_folder_impl(&app).map(|path| path.display().to_string())).await
+        })
+        .await
+}
+
+#[tauri::command]
+async fn open_sidecar_log_file(app: AppHandle) -> Result<String, String> {
+    metrics::of(&app)
+        .observe_async("open_sidecar_log_file", async move {
+            runtime::blocking(move || open_sidecar_log_impl(&app).map(|path| path.display().to_string())).await
+        })
+        .await
+}
+
 #[tauri::command]
-fn open_logs_folder(app: AppHandle) -> Result<String, String> {
-    open_logs_folder_impl(&app).map(|path| path.display().to_string())
+async fn open_desktop_log_file(app: AppHandle) -> Result<String, String> {
+    metrics::of(&app)
+        .observe_async("open_desktop_log_file", async move {
+            runtime::blocking(move || open_desktop_log_impl(&app).map(|path| path.display().to_string())).await
+        })
+        .await
 }
 
 #[tauri::command]
-fn open_sidecar_log_file(app: AppHandle) -> Result<String, String> {
-    open_sidecar_log_impl(&app).map(|path| path.display().to_string())
+async fn open_all_logs(app: AppHandle) -> Result<String, String> {
+    metrics::of(&app)
+        .observe_async("open_all_logs", async move {
+            runtime::blocking(move || open_all_logs_impl(&app).map(|path| path.display().to_string())).await
+        })
+        .await
 }
 
+/// Open Settings, optionally on one of `SETTINGS_SECTIONS` (e.g. `api-keys`
+/// from a "key missing" banner).
 #[tauri::command]
-async fn open_settings_window_command(app: AppHandle) -> Result<(), String> {
-    open_settings_window(&app)
+async fn open_settings_window_command(app: AppHandle, section: Option<String>) -> Result<(), String> {
+    metrics::of(&app)
+        .observe_async("open_settings_window_command", async move {
+            open_settings_window(&app, section.as_deref())
+        })
+        .await
 }
 
 #[tauri::command]
 fn close_settings_window(app: AppHandle) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("settings") {
-        window
-            .close()
-            .map_err(|e| format!("Failed to close settings window: {e}"))?;
-    }
-    Ok(())
+    metrics::of(&app).observe("close_settings_window", || {
+        if let Some(window) = app.get_webview_window("settings") {
+            window
+                .close()
+                .map_err(|e| format!("Failed to close settings window: {e}"))?;
+        }
+        Ok(())
+    })
+}
+
+/// Keep the calling window above other windows. Persisted per window for
+/// the main, settings, and panel windows.
+#[tauri::command]
+async fn set_always_on_top(webview: Webview, app: AppHandle, enabled: bool) -> Result<(), String> {
+    metrics::of(&webview)
+        .observe_async("set_always_on_top", async move {
+            require_trusted_window(webview.label())?;
+            let label = webview.label().to_string();
+            runtime::blocking(move || {
+                let window = app
+                    .get_webview_window(&label)
+                    .ok_or_else(|| format!("Window not found: {label}"))?;
+                window_state::set_always_on_top(&window, enabled)?;
+                if label == "main" {
+                    sync_always_on_top_menu(&app);
+                }
+                Ok(())
+            })
+            .await
+        })
+        .await
 }
 
 #[tauri::command]
@@ -505,71 +1092,175 @@ async fn open_live_channels_window_command(
     app: AppHandle,
     base_url: Option<String>,
 ) -> Result<(), String> {
-    open_live_channels_window(&app, base_url)
+    metrics::of(&app)
+        .observe_async("open_live_channels_window_command", async move {
+            open_live_channels_window(&app, base_url)
+        })
+        .await
 }
 
 #[tauri::command]
 fn close_live_channels_window(app: AppHandle) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("live-channels") {
-        window
-            .close()
-            .map_err(|e| format!("Failed to close live channels window: {e}"))?;
-    }
-    Ok(())
+    metrics::of(&app).observe("close_live_channels_window", || {
+        if let Some(window) = app.get_webview_window("live-channels") {
+            window
+                .close()
+                .map_err(|e| format!("Failed to close live channels window: {e}"))?;
+        }
+        Ok(())
+    })
+}
+
+#[tauri::command]
+async fn open_log_viewer_window_command(app: AppHandle) -> Result<(), String> {
+    metrics::of(&app)
+        .observe_async("open_log_viewer_window_command", async move {
+            open_log_viewer_window(&app)
+        })
+        .await
 }
 
 /// Fetch JSON from Polymarket Gamma API using native TLS (bypasses Cloudflare JA3 blocking).
 /// Called from frontend when browser CORS and sidecar Node.js TLS both fail.
 #[tauri::command]
 async fn fetch_polymarket(webview: Webview, path: String, params: String) -> Result<String, String> {
-    require_trusted_window(webview.label())?;
-    let allowed = ["events", "markets", "tags"];
-    let segment = path.trim_start_matches('/');
-    if !allowed.iter().any(|a| segment.starts_with(a)) {
-        return Err("Invalid Polymarket path".into());
-    }
-    let url = format!("https://gamma-api.polymarket.com/{}?{}", segment, params);
-    let client = reqwest::Client::builder()
-        .use_native_tls()
-        .build()
-        .map_err(|e| format!("HTTP client error: {e}"))?;
-    let resp = client
-        .get(&url)
-        .header("Accept", "application/json")
-        .timeout(std::time::Duration::from_secs(10))
-        .send()
+    metrics::of(&webview)
+        .observe_async("fetch_polymarket", async move {
+            require_trusted_window(webview.label())?;
+            let allowed = ["events", "markets", "tags"];
+            let segment = path.trim_start_matches('/');
+            if !allowed.iter().any(|a| segment.starts_with(a)) {
+                return Err("Invalid Polymarket path".into());
+            }
+            let url = format!("https://gamma-api.polymarket.com/{}?{}", segment, params);
+            let client = reqwest::Client::builder()
+                .use_native_tls()
+                .build()
+                .map_err(|e| format!("HTTP client error: {e}"))?;
+            let resp = client
+                .get(&url)
+                .header("Accept", "application/json")
+                .timeout(std::time::Duration::from_secs(10))
+                .send()
+                .await
+                .map_err(|e| format!("Polymarket fetch failed: {e}"))?;
+            if !resp.status().is_success() {
+                return Err(format!("Polymarket HTTP {}", resp.status()));
+            }
+            resp.text()
+                .await
+                .map_err(|e| format!("Read body failed: {e}"))
+        })
         .await
-        .map_err(|e| format!("Polymarket fetch failed: {e}"))?;
-    if !resp.status().is_success() {
-        return Err(format!("Polymarket HTTP {}", resp.status()));
+}
+
+/// Build the main window from its `tauri.conf.json` entry (which has
+/// `"create": false`) and apply saved geometry. It is shown only if
+/// `visible`; otherwise it loads hidden for the tray or settings launch.
+fn create_main_window(app: &AppHandle, visible: bool) -> Result<(), String> {
+    if app.get_webview_window("main").is_some() {
+        return Ok(());
     }
-    resp.text()
-        .await
-        .map_err(|e| format!("Read body failed: {e}"))
+    let config = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|window| window.label == "main")
+        .ok_or_else(|| "Main window missing from tauri.conf.json".to_string())?;
+    let appearance = app.state::<DesktopConfigState>().snapshot().appearance;
+    let window = WebviewWindowBuilder::from_config(app, config)
+        .and_then(|builder| appearance::apply(builder, &appearance).build())
+        .map_err(|e| format!("Failed to create main window: {e}"))?;
+    window_state::restore(&window);
+    if visible {
+        let _ = window.show();
+    }
+    Ok(())
 }
 
-fn open_settings_window(app: &AppHandle) -> Result<(), String> {
+/// Start the sidecar off the event loop, then swap the splash for the main
+/// window once the local API port is known (or startup gave up on it).
+fn launch_services(app: &AppHandle) {
+    let app = app.clone();
+    let launch = move || {
+        if let Err(err) = start_local_api(&app) {
+            tracing::error!(target: "sidecar", "local API sidecar failed to start: {err}");
+            tray::set_status(&app, tray::SidecarStatus::Failed);
+        }
+        let handle = app.clone();
+        let shown = app.run_on_main_thread(move || {
+            let window = launch::resolve(&handle);
+            let dashboard = window == LaunchWindow::Dashboard;
+            if let Err(err) = create_main_window(&handle, dashboard) {
+                crash_report::record_fatal_error(err);
+                handle.exit(1);
+                return;
+            }
+            if dashboard {
+                panel_windows::restore_all(&handle);
+                map_display::restore(&handle);
+            }
+            ticker::restore(&handle);
+            splash::close(&handle);
+            launch::open(&handle, window);
+            restart::reopen_settings(&handle);
+        });
+        if let Err(err) = shown {
+            tracing::error!(target: "app", "failed to show main window: {err}");
+        }
+    };
+    if let Err(e) = std::thread::Builder::new().name("local-api-startup".into()).spawn(launch) {
+        crash_report::record_fatal_error(format!("failed to start local API startup thread: {e}"));
+        std::process::exit(1);
+    }
+}
+
+fn validate_settings_section(section: &str) -> Result<(), String> {
+    if SETTINGS_SECTIONS.contains(&section) {
+        Ok(())
+    } else {
+        Err(format!("Unknown settings section: {section}"))
+    }
+}
+
+/// Open (or focus) the settings window. A `section` selects that tab: in the
+/// URL for a new window, via `SETTINGS_NAVIGATE_EVENT` for an open one.
+fn open_settings_window(app: &AppHandle, section: Option<&str>) -> Result<(), String> {
+    if let Some(section) = section {
+        validate_settings_section(section)?;
+    }
     if let Some(window) = app.get_webview_window("settings") {
         let _ = window.show();
+        if let Some(section) = section {
+            let _ = app.emit_to("settings", SETTINGS_NAVIGATE_EVENT, section);
+        }
         window
             .set_focus()
             .map_err(|e| format!("Failed to focus settings window: {e}"))?;
         return Ok(());
     }
 
-    let _settings_window = WebviewWindowBuilder::new(app, "settings", WebviewUrl::App("settings.html".into()))
+    let url = match section {
+        Some(section) => format!("settings.html?section={section}"),
+        None => "settings.html".to_string(),
+    };
+    let settings_window = WebviewWindowBuilder::new(app, "settings", WebviewUrl::App(url.into()))
         .title("World Monitor Settings")
         .inner_size(980.0, 760.0)
         .min_inner_size(820.0, 620.0)
         .resizable(true)
+        .visible(false)
         .background_color(tauri::webview::Color(26, 28, 30, 255))
         .build()
         .map_err(|e| format!("Failed to create settings window: {e}"))?;
+    window_state::restore(&settings_window);
+    let _ = settings_window.show();
 
     // On Windows/Linux, menus are per-window. Remove the inherited app menu
     // from the settings window (macOS uses a shared app-wide menu bar instead).
     #[cfg(not(target_os = "macos"))]
-    let _ = _settings_window.remove_menu();
+    let _ = settings_window.remove_menu();
 
     Ok(())
 }
@@ -609,6 +1300,30 @@ fn open_live_channels_window(app: &AppHandle, base_url: Option<String>) -> Resul
     Ok(())
 }
 
+fn open_log_viewer_window(app: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window("log-viewer") {
+        let _ = window.show();
+        window
+            .set_focus()
+            .map_err(|e| format!("Failed to focus log viewer window: {e}"))?;
+        return Ok(());
+    }
+
+    let _log_viewer_window = WebviewWindowBuilder::new(app, "log-viewer", WebviewUrl::App("log-viewer.html".into()))
+        .title("Log Viewer - World Monitor")
+        .inner_size(1100.0, 720.0)
+        .min_inner_size(720.0, 480.0)
+        .resizable(true)
+        .background_color(tauri::webview::Color(26, 28, 30, 255))
+        .build()
+        .map_err(|e| format!("Failed to create log viewer window: {e}"))?;
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = _log_viewer_window.remove_menu();
+
+    Ok(())
+}
+
 fn open_youtube_login_window(app: &AppHandle) -> Result<(), String> {
     if let Some(window) = app.get_webview_window("youtube-login") {
         let _ = window.show();
@@ -638,7 +1353,11 @@ fn open_youtube_login_window(app: &AppHandle) -> Result<(), String> {
 
 #[tauri::command]
 async fn open_youtube_login(app: AppHandle) -> Result<(), String> {
-    open_youtube_login_window(&app)
+    metrics::of(&app)
+        .observe_async("open_youtube_login", async move {
+            open_youtube_login_window(&app)
+        })
+        .await
 }
 
 fn build_app_menu(handle: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
@@ -650,24 +1369,147 @@ fn build_app_menu(handle: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
         Some("CmdOrCtrl+,"),
     )?;
     let separator = PredefinedMenuItem::separator(handle)?;
-    let quit_item = PredefinedMenuItem::quit(handle, Some("Quit"))?;
-    let file_menu = Submenu::with_items(
+    // Not `PredefinedMenuItem::quit`: that is a no-op on Linux, and with
+    // close-to-tray this is the only way out besides the tray menu.
+    let quit_item = MenuItem::with_id(handle, MENU_FILE_QUIT_ID, "Quit", true, Some("CmdOrCtrl+Q"))?;
+    let (export_pdf_item, page_setup_menu) = pdf_export::file_menu_items(handle)?;
+    let screenshot_item = screenshot::file_menu_item(handle)?;
+    let file_menu = Submenu::with_id_and_items(
         handle,
+        pdf_export::MENU_FILE_ID,
         "File",
         true,
-        &[&settings_item, &separator, &quit_item],
+        &[
+            &settings_item,
+            &separator,
+            &export_pdf_item,
+            &page_setup_menu,
+            &screenshot_item,
+            &PredefinedMenuItem::separator(handle)?,
+            &quit_item,
+        ],
     )?;
 
-    let about_metadata = AboutMetadata {
-        name: Some("World Monitor".into()),
-        version: Some(env!("CARGO_PKG_VERSION").into()),
-        copyright: Some("\u{00a9} 2025 Elie Habib".into()),
-        website: Some("https://worldmonitor.app".into()),
-        website_label: Some("worldmonitor.app".into()),
-        ..Default::default()
-    };
-    let about_item =
-        PredefinedMenuItem::about(handle, Some("About World Monitor"), Some(about_metadata))?;
+    // Checked state is synced from the saved window state once it loads.
+    let always_on_top_item = CheckMenuItem::with_id(
+        handle,
+        MENU_VIEW_ALWAYS_ON_TOP_ID,
+        "Always on Top",
+        true,
+        false,
+        None::<&str>,
+    )?;
+    let zoom_in_item = MenuItem::with_id(handle, MENU_VIEW_ZOOM_IN_ID, "Zoom In", true, Some("CmdOrCtrl+="))?;
+    let zoom_out_item = MenuItem::with_id(handle, MENU_VIEW_ZOOM_OUT_ID, "Zoom Out", true, Some("CmdOrCtrl+-"))?;
+    let zoom_reset_item =
+        MenuItem::with_id(handle, MENU_VIEW_ZOOM_RESET_ID, "Actual Size", true, Some("CmdOrCtrl+0"))?;
+    // Release builds enable these once the config loads (`sync_reload_menu`).
+    let reload_item = MenuItem::with_id(
+        handle,
+        MENU_VIEW_RELOAD_ID,
+        "Reload",
+        cfg!(debug_assertions),
+        Some("CmdOrCtrl+R"),
+    )?;
+    let force_reload_item = MenuItem::with_id(
+        handle,
+        MENU_VIEW_FORCE_RELOAD_ID,
+        "Force Reload",
+        cfg!(debug_assertions),
+        Some("CmdOrCtrl+Shift+R"),
+    )?;
+    #[cfg(feature = "devtools")]
+    let devtools_item = MenuItem::with_id(
+        handle,
+        MENU_VIEW_DEVTOOLS_ID,
+        "Toggle Developer Tools",
+        true,
+        Some("CmdOrCtrl+Alt+I"),
+    )?;
+    let view_menu = Submenu::with_id_and_items(
+        handle,
+        MENU_VIEW_ID,
+        "View",
+        true,
+        &[
+            &reload_item,
+            &force_reload_item,
+            #[cfg(feature = "devtools")]
+            &devtools_item,
+            &PredefinedMenuItem::separator(handle)?,
+            &zoom_in_item,
+            &zoom_out_item,
+            &zoom_reset_item,
+            &PredefinedMenuItem::separator(handle)?,
+            &always_on_top_item,
+        ],
+    )?;
+
+    let map_display_item = MenuItem::with_id(
+        handle,
+        MENU_WINDOW_MAP_DISPLAY_ID,
+        "Open Map Display",
+        true,
+        None::<&str>,
+    )?;
+    let window_menu = Submenu::with_items(
+        handle,
+        "Window",
+        true,
+        &[
+            &PredefinedMenuItem::minimize(handle, None)?,
+            &PredefinedMenuItem::separator(handle)?,
+            &map_display_item,
+        ],
+    )?;
+
+    let log_viewer_item = MenuItem::with_id(
+        handle,
+        MENU_DEBUG_LOG_VIEWER_ID,
+        "Log Viewer",
+        true,
+        None::<&str>,
+    )?;
+    let desktop_log_item = MenuItem::with_id(
+        handle,
+        MENU_DEBUG_DESKTOP_LOG_ID,
+        "Open Desktop Log",
+        true,
+        None::<&str>,
+    )?;
+    let sidecar_log_item = MenuItem::with_id(
+        handle,
+        MENU_DEBUG_SIDECAR_LOG_ID,
+        "Open API Log",
+        true,
+        None::<&str>,
+    )?;
+    let all_logs_item = MenuItem::with_id(
+        handle,
+        MENU_DEBUG_ALL_LOGS_ID,
+        "Open All Logs",
+        true,
+        None::<&str>,
+    )?;
+    let debug_separator = PredefinedMenuItem::separator(handle)?;
+    let debug_menu = Submenu::with_items(
+        handle,
+        "Debug",
+        true,
+        &[
+            &log_viewer_item,
+            &debug_separator,
+            &desktop_log_item,
+            &sidecar_log_item,
+            &all_logs_item,
+        ],
+    )?;
+
+    let about_item = PredefinedMenuItem::about(
+        handle,
+        Some(&format!("About {}", handle.package_info().name)),
+        Some(about::metadata(handle)),
+    )?;
     let github_item = MenuItem::with_id(
         handle,
         MENU_HELP_GITHUB_ID,
@@ -677,24 +1519,6 @@ fn build_app_menu(handle: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
     )?;
     let help_separator = PredefinedMenuItem::separator(handle)?;
 
-    #[cfg(feature = "devtools")]
-    let help_menu = {
-        let devtools_item = MenuItem::with_id(
-            handle,
-            MENU_HELP_DEVTOOLS_ID,
-            "Toggle Developer Tools",
-            true,
-            Some("CmdOrCtrl+Alt+I"),
-        )?;
-        Submenu::with_items(
-            handle,
-            "Help",
-            true,
-            &[&about_item, &help_separator, &github_item, &devtools_item],
-        )?
-    };
-
-    #[cfg(not(feature = "devtools"))]
     let help_menu = Submenu::with_items(
         handle,
         "Help",
@@ -718,23 +1542,122 @@ fn build_app_menu(handle: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
         )?
     };
 
-    Menu::with_items(handle, &[&file_menu, &edit_menu, &help_menu])
+    Menu::with_items(
+        handle,
+        &[&file_menu, &edit_menu, &view_menu, &window_menu, &debug_menu, &help_menu],
+    )
+}
+
+/// Reflect the main window's always-on-top state in the View menu.
+fn sync_always_on_top_menu(app: &AppHandle) {
+    let item = app
+        .menu()
+        .and_then(|menu| menu.get(MENU_VIEW_ID))
+        .and_then(|view| view.as_submenu().and_then(|view| view.get(MENU_VIEW_ALWAYS_ON_TOP_ID)))
+        .and_then(|item| item.as_check_menuitem().cloned());
+    if let Some(item) = item {
+        let _ = item.set_checked(window_state::is_always_on_top(app, "main"));
+    }
+}
+
+fn toggle_main_always_on_top(app: &AppHandle) -> Result<(), String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+    let enabled = !window_state::is_always_on_top(app, "main");
+    window_state::set_always_on_top(&window, enabled)
+}
+
+/// The focused window, or the main window if none is focused.
+fn focused_window(app: &AppHandle) -> Result<tauri::WebviewWindow, String> {
+    app.webview_windows()
+        .into_values()
+        .find(|window| window.is_focused().unwrap_or(false))
+        .or_else(|| app.get_webview_window("main"))
+        .ok_or_else(|| "Main window not found".to_string())
+}
+
+fn zoom_focused_window(app: &AppHandle, steps: Option<i32>) -> Result<(), String> {
+    window_state::zoom(&focused_window(app)?, steps)
+}
+
+fn reload_menu_enabled(app: &AppHandle) -> bool {
+    cfg!(debug_assertions)
+        || app
+            .try_state::<DesktopConfigState>()
+            .is_some_and(|config| config.snapshot().developer.reload_menu)
+}
+
+/// Enable View → Reload / Force Reload in release builds that opted in.
+fn sync_reload_menu(app: &AppHandle) {
+    let Some(view) = app
+        .menu()
+        .and_then(|menu| menu.get(MENU_VIEW_ID))
+        .and_then(|view| view.as_submenu().cloned())
+    else {
+        return;
+    };
+    let enabled = reload_menu_enabled(app);
+    for id in [MENU_VIEW_RELOAD_ID, MENU_VIEW_FORCE_RELOAD_ID] {
+        if let Some(item) = view.get(id).and_then(|item| item.as_menuitem().cloned()) {
+            let _ = item.set_enabled(enabled);
+        }
+    }
+}
+
+/// Reload the focused window. Force Reload first clears the webview's
+/// browsing data (HTTP and service worker caches, but also cookies and site
+/// storage), for a page wedged on a stale or corrupt cache.
+fn reload_focused_window(app: &AppHandle, clear_cache: bool) -> Result<(), String> {
+    if !reload_menu_enabled(app) {
+        return Err("Reload is disabled in this build".to_string());
+    }
+    let window = focused_window(app)?;
+    if clear_cache {
+        window
+            .clear_all_browsing_data()
+            .map_err(|e| format!("Failed to clear webview cache: {e}"))?;
+    }
+    window.reload().map_err(|e| format!("Failed to reload {}: {e}", window.label()))?;
+    tracing::info!(target: "menu", window = window.label(), clear_cache, "window reloaded");
+    Ok(())
 }
 
 fn handle_menu_event(app: &AppHandle, event: tauri::menu::MenuEvent) {
     match event.id().as_ref() {
         MENU_FILE_SETTINGS_ID => {
-            if let Err(err) = open_settings_window(app) {
-                append_desktop_log(app, "ERROR", &format!("settings menu failed: {err}"));
-                eprintln!("[tauri] settings menu failed: {err}");
+            if let Err(err) = open_settings_window(app, None) {
+                tracing::error!(target: "menu", "settings menu failed: {err}");
             }
         }
-        MENU_HELP_GITHUB_ID => {
-            let _ = open_in_shell("https://github.com/koala73/worldmonitor");
+        MENU_FILE_QUIT_ID => app.exit(0),
+        id if pdf_export::owns_menu_id(id) => pdf_export::handle_menu_event(app, id),
+        screenshot::MENU_CAPTURE_ID => screenshot::capture_and_reveal(app),
+        MENU_VIEW_ZOOM_IN_ID | MENU_VIEW_ZOOM_OUT_ID | MENU_VIEW_ZOOM_RESET_ID => {
+            let steps = match event.id().as_ref() {
+                MENU_VIEW_ZOOM_IN_ID => Some(1),
+                MENU_VIEW_ZOOM_OUT_ID => Some(-1),
+                _ => None,
+            };
+            if let Err(err) = zoom_focused_window(app, steps) {
+                tracing::error!(target: "menu", "zoom menu failed: {err}");
+            }
+        }
+        MENU_VIEW_ALWAYS_ON_TOP_ID => {
+            if let Err(err) = toggle_main_always_on_top(app) {
+                tracing::error!(target: "menu", "always on top menu failed: {err}");
+            }
+            sync_always_on_top_menu(app);
+        }
+        MENU_VIEW_RELOAD_ID | MENU_VIEW_FORCE_RELOAD_ID => {
+            let clear_cache = event.id().as_ref() == MENU_VIEW_FORCE_RELOAD_ID;
+            if let Err(err) = reload_focused_window(app, clear_cache) {
+                tracing::error!(target: "menu", "reload menu failed: {err}");
+            }
         }
         #[cfg(feature = "devtools")]
-        MENU_HELP_DEVTOOLS_ID => {
-            if let Some(window) = app.get_webview_window("main") {
+        MENU_VIEW_DEVTOOLS_ID => {
+            if let Ok(window) = focused_window(app) {
                 if window.is_devtools_open() {
                     window.close_devtools();
                 } else {
@@ -742,6 +1665,35 @@ fn handle_menu_event(app: &AppHandle, event: tauri::menu::MenuEvent) {
                 }
             }
         }
+        MENU_WINDOW_MAP_DISPLAY_ID => {
+            if let Err(err) = map_display::open(app) {
+                tracing::error!(target: "menu", "map display menu failed: {err}");
+            }
+        }
+        MENU_DEBUG_LOG_VIEWER_ID => {
+            if let Err(err) = open_log_viewer_window(app) {
+                tracing::error!(target: "menu", "log viewer menu failed: {err}");
+            }
+        }
+        MENU_DEBUG_DESKTOP_LOG_ID => {
+            if let Err(err) = open_desktop_log_impl(app) {
+                tracing::error!(target: "menu", "open desktop log failed: {err}");
+            }
+        }
+        MENU_DEBUG_SIDECAR_LOG_ID => {
+            if let Err(err) = open_sidecar_log_impl(app) {
+                tracing::error!(target: "menu", "open API log failed: {err}");
+            }
+        }
+        MENU_DEBUG_ALL_LOGS_ID => {
+            if let Err(err) = open_all_logs_impl(app) {
+                tracing::error!(target: "menu", "open all logs failed: {err}");
+            }
+        }
+        id if context_menu::owns(id) => context_menu::on_selected(app, id),
+        MENU_HELP_GITHUB_ID => {
+            let _ = open_in_shell("https://github.com/koala73/worldmonitor");
+        }
         _ => {}
     }
 }
@@ -832,13 +1784,10 @@ fn resolve_node_binary(app: &AppHandle) -> Option<PathBuf> {
         if explicit_path.is_file() {
             return Some(explicit_path);
         }
-        append_desktop_log(
-            app,
-            "WARN",
-            &format!(
-                "LOCAL_API_NODE_BIN is set but not a valid file: {}",
-                explicit_path.display()
-            ),
+        tracing::warn!(
+            target: "sidecar",
+            path = %explicit_path.display(),
+            "LOCAL_API_NODE_BIN is set but not a valid file"
         );
     }
 
@@ -897,6 +1846,7 @@ fn read_port_file(path: &Path, timeout_ms: u64) -> Option<u16> {
 }
 
 fn start_local_api(app: &AppHandle) -> Result<(), String> {
+    tray::set_status(app, tray::SidecarStatus::Starting);
     let state = app.state::<LocalApiState>();
     let mut slot = state
         .child
@@ -918,7 +1868,7 @@ fn start_local_api(app: &AppHandle) -> Result<(), String> {
             script.display()
         ));
     }
-    let node_binary = resolve_node_binary(app).ok_or_else(|| {
+    let node_binary = startup::measure("node_resolution", || resolve_node_binary(app)).ok_or_else(|| {
         "Node.js executable not found. Install Node 18+ or set LOCAL_API_NODE_BIN".to_string()
     })?;
 
@@ -935,29 +1885,19 @@ fn start_local_api(app: &AppHandle) -> Result<(), String> {
         .try_clone()
         .map_err(|e| format!("Failed to clone local API log handle: {e}"))?;
 
-    append_desktop_log(
-        app,
-        "INFO",
-        &format!(
-            "starting local API sidecar script={} resource_root={} log={}",
-            script.display(),
-            resource_root.display(),
-            log_path.display()
-        ),
-    );
-    append_desktop_log(
-        app,
-        "INFO",
-        &format!("resolved node binary={}", node_binary.display()),
+    tracing::info!(
+        target: "sidecar",
+        script = %script.display(),
+        resource_root = %resource_root.display(),
+        log = %log_path.display(),
+        "starting local API sidecar"
     );
-    append_desktop_log(
-        app,
-        "INFO",
-        &format!(
-            "local API sidecar preferred port={} port_file={}",
-            DEFAULT_LOCAL_API_PORT,
-            port_file.display()
-        ),
+    tracing::info!(target: "sidecar", node = %node_binary.display(), "resolved node binary");
+    tracing::info!(
+        target: "sidecar",
+        port = DEFAULT_LOCAL_API_PORT,
+        port_file = %port_file.display(),
+        "local API sidecar preferred port"
     );
 
     // Generate a unique token for local API auth (prevents other local processes from accessing sidecar)
@@ -979,10 +1919,11 @@ fn start_local_api(app: &AppHandle) -> Result<(), String> {
                                     // cause EISDIR errors in Node.js module resolution.
     let script_for_node = sanitize_path_for_node(&script);
     let resource_for_node = sanitize_path_for_node(&resource_root);
-    append_desktop_log(
-        app,
-        "INFO",
-        &format!("node args: script={script_for_node} resource_dir={resource_for_node}"),
+    tracing::debug!(
+        target: "sidecar",
+        script = %script_for_node,
+        resource_dir = %resource_for_node,
+        "node args"
     );
     let data_dir = logs_dir_path(app)
         .map(|p| sanitize_path_for_node(&p))
@@ -994,6 +1935,8 @@ fn start_local_api(app: &AppHandle) -> Result<(), String> {
         .env("LOCAL_API_DATA_DIR", &data_dir)
         .env("LOCAL_API_MODE", "tauri-sidecar")
         .env("LOCAL_API_TOKEN", &local_api_token)
+        .env("LOCAL_API_LOG_LEVEL", app.state::<DesktopConfigState>().snapshot().log.level.as_str())
+        .env("LOCAL_API_LOW_POWER", power::sidecar_low_power(app).to_string())
         .stdout(Stdio::from(log_file))
         .stderr(Stdio::from(log_file_err));
     if let Some(parent) = script.parent() {
@@ -1009,11 +1952,7 @@ fn start_local_api(app: &AppHandle) -> Result<(), String> {
             secret_count += 1;
         }
     }
-    append_desktop_log(
-        app,
-        "INFO",
-        &format!("injected {secret_count} keychain secrets into sidecar env"),
-    );
+    tracing::info!(target: "secrets", count = secret_count, "injected keychain secrets into sidecar env");
 
     // Inject build-time secrets (CI) with runtime env fallback (dev)
     if let Some(url) = option_env!("CONVEX_URL") {
@@ -1022,47 +1961,114 @@ fn start_local_api(app: &AppHandle) -> Result<(), String> {
         cmd.env("CONVEX_URL", url);
     }
 
-    let child = cmd
-        .spawn()
+    // Corporate networks: route upstream fetches through the system proxy.
+    for (key, value) in startup::measure("proxy_detection", proxy::sidecar_env) {
+        cmd.env(key, value);
+    }
+
+    let child = startup::measure("sidecar_spawn", || cmd.spawn())
         .map_err(|e| format!("Failed to launch local API: {e}"))?;
-    append_desktop_log(
-        app,
-        "INFO",
-        &format!("local API sidecar started pid={}", child.id()),
-    );
+    tracing::info!(target: "sidecar", pid = child.id(), "local API sidecar started");
+    let pid = child.id();
     *slot = Some(child);
     drop(slot);
+    spawn_local_api_exit_monitor(app, pid);
 
     // Wait for sidecar to write confirmed port (up to 5s)
-    if let Some(confirmed_port) = read_port_file(&port_file, 5000) {
-        append_desktop_log(
-            app,
-            "INFO",
-            &format!("sidecar confirmed port={confirmed_port}"),
-        );
+    if let Some(confirmed_port) = startup::measure("readiness_probe", || read_port_file(&port_file, 5000)) {
+        tracing::info!(target: "sidecar", port = confirmed_port, "sidecar confirmed port");
         if let Ok(mut port_slot) = state.port.lock() {
             *port_slot = Some(confirmed_port);
         }
+        tray::set_status(app, tray::SidecarStatus::Running { port: confirmed_port });
     } else {
-        append_desktop_log(
-            app,
-            "WARN",
-            "sidecar port file not found within timeout, using default",
-        );
+        tracing::warn!(target: "sidecar", "sidecar port file not found within timeout, using default");
         if let Ok(mut port_slot) = state.port.lock() {
             *port_slot = Some(DEFAULT_LOCAL_API_PORT);
         }
+        tray::set_status(app, tray::SidecarStatus::Running { port: DEFAULT_LOCAL_API_PORT });
     }
 
     Ok(())
 }
 
+/// Poll the sidecar for an exit nobody asked for. `stop_local_api` takes the
+/// child out of the slot before killing it, so a child still in the slot that
+/// has exited crashed.
+fn spawn_local_api_exit_monitor(app: &AppHandle, pid: u32) {
+    let app = app.clone();
+    let monitor = move || loop {
+        std::thread::sleep(SIDECAR_EXIT_POLL_INTERVAL);
+        let state = app.state::<LocalApiState>();
+        let mut slot = state.child.lock().unwrap_or_else(|e| e.into_inner());
+        // Stopped, or replaced by a restart with its own monitor.
+        let Some(child) = slot.as_mut().filter(|c| c.id() == pid) else {
+            return;
+        };
+        match child.try_wait() {
+            Ok(None) => {}
+            Ok(Some(status)) => {
+                slot.take();
+                drop(slot);
+                if let Ok(mut port_slot) = state.port.lock() {
+                    *port_slot = None;
+                }
+                tracing::error!(target: "sidecar", pid, %status, "local API sidecar exited unexpectedly");
+                tray::set_status(&app, tray::SidecarStatus::Crashed);
+                error_reporting::report(
+                    error_reporting::ErrorSource::Sidecar,
+                    "local API sidecar exited unexpectedly",
+                    Some(status.to_string()),
+                );
+                return;
+            }
+            Err(e) => {
+                tracing::warn!(target: "sidecar", pid, "stopped watching local API sidecar: {e}");
+                return;
+            }
+        }
+    };
+    if let Err(e) = std::thread::Builder::new().name("sidecar-exit-monitor".into()).spawn(monitor) {
+        tracing::warn!(target: "sidecar", "failed to start sidecar exit monitor: {e}");
+    }
+}
+
+/// POST a JSON body to one of the sidecar's authenticated `/api/local-*`
+/// control endpoints.
+async fn post_local_api_control(app: &AppHandle, path: &str, body: Value) -> Result<(), String> {
+    let state = app.state::<LocalApiState>();
+    let port = state
+        .port
+        .lock()
+        .ok()
+        .and_then(|g| *g)
+        .ok_or_else(|| "Local API is not running".to_string())?;
+    let token = state
+        .token
+        .lock()
+        .ok()
+        .and_then(|g| g.clone())
+        .ok_or_else(|| "Token not generated".to_string())?;
+    let resp = reqwest::Client::new()
+        .post(format!("http://127.0.0.1:{port}{path}"))
+        .bearer_auth(token)
+        .json(&body)
+        .timeout(std::time::Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(|e| format!("Local API control request failed: {e}"))?;
+    if !resp.status().is_success() {
+        return Err(format!("Local API control {path} returned HTTP {}", resp.status()));
+    }
+    Ok(())
+}
+
 fn stop_local_api(app: &AppHandle) {
     if let Ok(state) = app.try_state::<LocalApiState>().ok_or(()) {
         if let Ok(mut slot) = state.child.lock() {
             if let Some(mut child) = slot.take() {
                 let _ = child.kill();
-                append_desktop_log(app, "INFO", "local API sidecar stopped");
+                tracing::info!(target: "sidecar", "local API sidecar stopped");
             }
         }
         if let Ok(mut port_slot) = state.port.lock() {
@@ -1071,9 +2077,18 @@ fn stop_local_api(app: &AppHandle) {
         if let Ok(log_dir) = logs_dir_path(app) {
             let _ = fs::remove_file(log_dir.join("sidecar.port"));
         }
+        tray::set_status(app, tray::SidecarStatus::Stopped);
     }
 }
 
+/// Stop the sidecar (if running) and start a fresh one. Blocks while the new
+/// sidecar starts up, so call off the main thread.
+fn restart_local_api(app: &AppHandle) -> Result<(), String> {
+    tracing::info!(target: "sidecar", "restarting local API sidecar");
+    stop_local_api(app);
+    start_local_api(app).inspect_err(|_| tray::set_status(app, tray::SidecarStatus::Failed))
+}
+
 #[cfg(target_os = "linux")]
 fn resolve_appimage_gio_module_dir() -> Option<PathBuf> {
     let appdir = env::var_os("APPDIR")?;
@@ -1119,6 +2134,9 @@ fn resolve_appimage_gio_module_dir() -> Option<PathBuf> {
 }
 
 fn main() {
+    startup::begin();
+    crash_report::install_panic_hook();
+
     // Work around WebKitGTK rendering issues on Linux that can cause blank white
     // screens. DMA-BUF renderer failures are common with NVIDIA drivers and on
     // immutable distros (e.g. Bazzite/Fedora Atomic).  Setting the env var before
@@ -1181,11 +2199,73 @@ fn main() {
         }
     }
 
+    let shared_runtime = runtime::init();
+
     tauri::Builder::default()
-        .menu(build_app_menu)
+        // Must be the first plugin so a second instance exits before any
+        // other plugin or the sidecar starts.
+        .plugin(tauri_plugin_single_instance::init(single_instance::on_second_instance))
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(autostart::plugin())
+        .menu(|handle| startup::measure("menu_build", || build_app_menu(handle)))
         .on_menu_event(handle_menu_event)
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(shortcuts::handle)
+                .build(),
+        )
+        .manage(shared_runtime)
         .manage(LocalApiState::default())
-        .manage(SecretsCache::load_from_keychain())
+        .manage(logging::FrontendLogLimiter::default())
+        .manage(log_files::LogTailState::default())
+        .manage(metrics::CommandMetrics::default())
+        .manage(diagnostics::DiagnosticsExportState::default())
+        .manage(watchdog::WatchdogState::default())
+        .manage(keep_awake::KeepAwakeState::default())
+        .manage(network::NetworkState::default())
+        .manage(news::NewsState::default())
+        .manage(idle::IdleState::default())
+        .manage(locale::LocaleState::default())
+        .manage(power::PowerState::default())
+        .manage(timezone::TimezoneState::default())
+        .manage(focus_mode::FocusModeState::default())
+        .manage(sounds::SoundsState::default())
+        .manage(speech::SpeechState::default())
+        .manage(gdelt::GdeltState::default())
+        .manage(usgs::UsgsState::default())
+        .manage(hazards::HazardsState::default())
+        .manage(accent_color::AccentColorState::default())
+        .manage(acled::AcledState::default())
+        .manage(cloudflare::CloudflareState::default())
+        .manage(fred::FredState::default())
+        .manage(eia::EiaState::default())
+        .manage(faa::FaaState::default())
+        .manage(orbits::OrbitsState::default())
+        .manage(swpc::SwpcState::default())
+        .manage(relay::RelayState::default())
+        .manage(mqtt::MqttState::default())
+        .manage(webhooks::WebhooksState::default())
+        .manage(cyber::CyberState::default())
+        .manage(markets::MarketsState::default())
+        .manage(maritime::MaritimeState::default())
+        .manage(anomalies::AnomaliesState::default())
+        .manage(digest::DigestState::default())
+        .manage(llm::LlmState::default())
+        .manage(adsb::AdsbState::default())
+        .manage(ais::AisState::default())
+        .manage(opensky::OpenSkyState::default())
+        .manage(file_import::PendingImports::default())
+        .manage(context_menu::ContextMenuState::default())
+        .manage(alert_status::AlertStatusState::default())
+        .manage(badging::BadgeState::default())
+        .manage(ticker::TickerState::default())
+        .manage(monitoring::MonitoringState::default())
+        .manage(tile_cache::TileCacheState::default())
+        .manage(sun::SunLocationsState::default())
+        .register_asynchronous_uri_scheme_protocol(tile_cache::SCHEME, tile_cache::handle)
         .invoke_handler(tauri::generate_handler![
             list_supported_secret_keys,
             get_secret,
@@ -1198,35 +2278,355 @@ fn main() {
             read_cache_entry,
             write_cache_entry,
             delete_cache_entry,
+            get_cache_recovery_report,
             open_logs_folder,
             open_sidecar_log_file,
+            open_desktop_log_file,
+            open_all_logs,
             open_settings_window_command,
             close_settings_window,
             open_live_channels_window_command,
             close_live_channels_window,
-            open_url,
+            open_log_viewer_window_command,
+            panel_windows::open_panel_window,
+            links::open_external_url,
             open_youtube_login,
-            fetch_polymarket
+            fetch_polymarket,
+            logging::log_from_frontend,
+            log_files::tail_log,
+            log_files::stop_log_tail,
+            log_files::query_logs,
+            log_files::clear_logs,
+            diagnostics::export_diagnostics_bundle,
+            diagnostics::get_system_info,
+            crash_report::get_last_crash_report,
+            crash_report::acknowledge_crash_report,
+            logging::set_log_level,
+            set_always_on_top,
+            shortcuts::get_global_shortcut,
+            shortcuts::set_global_shortcut,
+            deep_links::take_pending_navigation,
+            file_import::take_pending_imports,
+            image_metadata::read_image_metadata,
+            about::get_about_info,
+            context_menu::show_context_menu,
+            map_display::close_map_display,
+            alert_status::update_alert_status,
+            alert_status::get_alert_status,
+            alerts::list_alert_rules,
+            alerts::save_alert_rule,
+            alerts::delete_alert_rule,
+            alerts::record_alert_metric,
+            alerts::get_fired_alerts,
+            ticker::activate_ticker,
+            launch::get_launch_window,
+            launch::set_launch_window,
+            restart::restart_app,
+            autostart::get_launch_at_login,
+            autostart::set_launch_at_login,
+            keep_awake::get_keep_awake,
+            keep_awake::set_keep_awake,
+            network::get_network_online,
+            idle::get_system_idle,
+            locale::get_locale,
+            power::get_power_status,
+            power::get_battery_refresh_multiplier,
+            power::set_battery_refresh_multiplier,
+            timezone::get_timezone,
+            focus_mode::get_focus_mode_status,
+            focus_mode::set_critical_breaks_through,
+            sounds::play_sound,
+            sounds::set_sound_volume,
+            speech::speak,
+            speech::stop_speaking,
+            speech::speak_digest,
+            gdelt::get_gdelt_status,
+            gdelt::query_gdelt_events,
+            usgs::get_usgs_status,
+            usgs::query_earthquakes,
+            hazards::get_hazards_status,
+            hazards::query_hazards,
+            weather::get_weather,
+            weather::get_weather_locations,
+            weather::set_weather_locations,
+            news::fetch_news_feed,
+            news::get_news_items,
+            accent_color::get_accent_color,
+            acled::sync_acled_events,
+            acled::get_acled_sync_status,
+            acled::query_acled_events,
+            cloudflare::sync_internet_outages,
+            cloudflare::get_outage_sync_status,
+            cloudflare::query_internet_outages,
+            fred::sync_fred_series,
+            fred::get_fred_sync_status,
+            fred::get_fred_series,
+            eia::sync_eia_data,
+            eia::get_eia_sync_status,
+            eia::get_eia_data,
+            digest::preview_digest,
+            llm::summarize,
+            llm::translate_text,
+            digest::send_digest,
+            digest::get_last_digest,
+            faa::get_faa_status,
+            faa::get_airspace_notices,
+            orbits::get_orbits_status,
+            orbits::get_satellite_positions,
+            orbits::get_ground_track,
+            orbits::predict_passes,
+            swpc::get_swpc_status,
+            swpc::get_space_weather,
+            relay::get_relay_status,
+            mqtt::get_mqtt_status,
+            webhooks::get_webhook_deliveries,
+            cyber::sync_cyber_threats,
+            cyber::get_cyber_sync_status,
+            cyber::query_threat_indicators,
+            cyber::query_exploited_vulnerabilities,
+            markets::sync_markets,
+            markets::get_market_sync_status,
+            markets::get_market_series,
+            store::query_events,
+            store::aggregate_events,
+            store::prune_events,
+            store::record_events,
+            store::get_track,
+            geo_export::export_geodata,
+            geofences::list_geofences,
+            geofences::save_geofence,
+            geofences::delete_geofence,
+            geofences::get_geofence_occupants,
+            geofences::get_geofence_events,
+            maritime::lookup_maritime_zone,
+            maritime::list_maritime_zones,
+            maritime::get_maritime_status,
+            anomalies::get_flight_anomalies,
+            exports::list_export_jobs,
+            exports::save_export_job,
+            exports::delete_export_job,
+            exports::run_export_job,
+            watchlists::list_watchlist,
+            watchlists::add_to_watchlist,
+            watchlists::remove_from_watchlist,
+            watchlists::import_watchlist,
+            tile_cache::get_tile_cache_status,
+            tile_cache::set_tile_cache_config,
+            tile_cache::clear_tile_cache,
+            tile_cache::prefetch_tiles,
+            geometry::simplify_geojson,
+            geometry::geojson_bounds,
+            geometry::clip_geojson,
+            geometry::points_in_polygons,
+            geodesic::geodesic_legs,
+            geodesic::geodesic_destinations,
+            geodesic::geodesic_routes,
+            sun::get_sun_state,
+            sun::set_sun_locations,
+            adsb::get_adsb_receiver,
+            adsb::set_adsb_receiver,
+            adsb::get_adsb_status,
+            ais::start_ais_stream,
+            ais::stop_ais_stream,
+            ais::get_ais_stream_status,
+            aivdm::decode_ais_sentences,
+            opensky::start_opensky_poller,
+            opensky::stop_opensky_poller,
+            opensky::get_opensky_snapshot,
+            opensky::get_opensky_status,
+            proxy::get_system_proxy,
+            clipboard::copy_to_clipboard,
+            clipboard::copy_coordinates_to_clipboard,
+            clipboard::copy_image_to_clipboard,
+            clipboard::read_clipboard_text,
+            share::share_content,
+            pdf_export::export_pdf,
+            screenshot::capture_screenshot,
+            notifications::send_notification,
+            badging::set_badge,
+            dock::set_badge_count,
+            attention::notify_attention,
+            monitoring::get_monitoring_paused,
+            monitoring::set_monitoring_paused,
+            error_reporting::get_error_reporting,
+            error_reporting::set_error_reporting,
+            logging::get_log_level,
+            metrics::get_metrics,
+            watchdog::watchdog_ack,
+            startup::get_startup_report
         ])
+        .on_page_load(|webview, payload| {
+            if webview.label() == "main" && payload.event() == PageLoadEvent::Finished {
+                startup::mark_first_window_shown();
+                // Only now is there a page to listen; `get_cache_recovery_report`
+                // has the same report for one that loads later.
+                if let Some(report) = webview.try_state::<PersistentCache>().and_then(|cache| cache.recovery.clone()) {
+                    let _ = webview.emit("cache://corrupted", report);
+                }
+            }
+        })
         .setup(|app| {
+            let config_path = desktop_config_path(app.handle()).unwrap_or_default();
+            let (desktop_config, config_error) = match config::load(&config_path) {
+                Ok(config) => (config, None),
+                Err(err) => (DesktopConfig::default(), Some(err)),
+            };
+            match desktop_log_path(app.handle()) {
+                Ok(path) => {
+                    if let Err(err) = logging::init(&path, desktop_config.log.clone()) {
+                        eprintln!("[tauri] {err}");
+                    }
+                }
+                Err(err) => eprintln!("[tauri] desktop log unavailable: {err}"),
+            }
+            if let Some(err) = config_error {
+                tracing::warn!(target: "app", "using default desktop config: {err}");
+            }
+            if let Ok(dir) = logs_dir_path(app.handle()) {
+                if let Err(err) = error_reporting::configure(&desktop_config.error_reporting, &dir) {
+                    tracing::warn!(target: "app", "error reporting disabled: {err}");
+                }
+                crash_report::set_crash_dir(dir);
+            }
+            let watchdog_config = desktop_config.watchdog.clone();
+            let idle_config = desktop_config.idle.clone();
+            app.manage(DesktopConfigState::new(config_path, desktop_config));
+            app.manage(restart::load(app.handle()));
+
+            if launch::requested(app.handle()) == LaunchWindow::Dashboard {
+                splash::show(app.handle());
+            }
+
+            // The main and panel windows are created by `launch_services`
+            // once the sidecar is up, with their saved geometry.
+            app.manage(window_state::WindowStateStore::load(
+                window_state_path(app.handle()).unwrap_or_default(),
+            ));
+            sync_always_on_top_menu(app.handle());
+            sync_reload_menu(app.handle());
+            pdf_export::sync_menu(app.handle());
+            app.manage(panel_windows::PanelWindowsState::load(
+                panel_windows_path(app.handle()).unwrap_or_default(),
+            ));
+            app.manage(map_display::MapDisplayState::load(
+                map_display_path(app.handle()).unwrap_or_default(),
+            ));
+            app.manage(alerts::AlertsState::load(
+                alert_rules_path(app.handle()).unwrap_or_default(),
+            ));
+            app.manage(geofences::GeofencesState::load(
+                geofences_path(app.handle()).unwrap_or_default(),
+            ));
+            app.manage(exports::ExportsState::load(
+                export_jobs_path(app.handle()).unwrap_or_default(),
+            ));
+            app.manage(weather::WeatherState::load(
+                weather_locations_path(app.handle()).unwrap_or_default(),
+            ));
+            shortcuts::init(app.handle());
+            deep_links::init(app.handle());
+            file_import::open_launch_files(app.handle());
+            restart::requeue_pending(app.handle());
+            dock::init(app.handle());
+            notifications::init(app.handle());
+
+            // Keychain is read after logging is up so vault migration is recorded.
+            app.manage(SecretsCache::load_from_keychain());
+
             // Load persistent cache into memory (avoids 14MB file I/O on every IPC call)
-            let cache_path = cache_file_path(&app.handle()).unwrap_or_default();
-            app.manage(PersistentCache::load(&cache_path));
-
-            if let Err(err) = start_local_api(&app.handle()) {
-                append_desktop_log(
-                    &app.handle(),
-                    "ERROR",
-                    &format!("local API sidecar failed to start: {err}"),
+            let cache_path = cache_file_path(app.handle()).unwrap_or_default();
+            let cache = PersistentCache::load(&cache_path);
+            if let Some(report) = &cache.recovery {
+                tracing::error!(
+                    target: "cache",
+                    error = %report.error,
+                    quarantined = report.quarantined_path.as_deref().unwrap_or("<rename failed>"),
+                    restored_from_backup = report.restored_from_backup,
+                    entries = report.recovered_entries,
+                    "persistent cache corrupted"
                 );
-                eprintln!("[tauri] local API sidecar failed to start: {err}");
             }
+            app.manage(cache);
+            app.manage(store::EventStore::open(&store_path(app.handle()).unwrap_or_default()));
+            app.manage(watchlists::WatchlistsState::load(&app.state::<store::EventStore>()));
+
+            if let Err(err) = tray::init(app.handle()) {
+                tracing::warn!(target: "app", "system tray unavailable: {err}");
+            }
+
+            launch_services(app.handle());
+
+            watchdog::start(app.handle(), watchdog_config);
+            network::start(app.handle());
+            idle::start(app.handle(), idle_config);
+            locale::start(app.handle());
+            power::start(app.handle());
+            timezone::start(app.handle());
+            focus_mode::start(app.handle());
+            sounds::start(app.handle());
+            speech::start(app.handle());
+            accent_color::start(app.handle());
+            adsb::start(app.handle());
+            store::start(app.handle());
+            tile_cache::start(app.handle());
+            sun::start(app.handle());
+            acled::start(app.handle());
+            cloudflare::start(app.handle());
+            fred::start(app.handle());
+            eia::start(app.handle());
+            digest::start(app.handle());
+            gdelt::start(app.handle());
+            usgs::start(app.handle());
+            hazards::start(app.handle());
+            faa::start(app.handle());
+            orbits::start(app.handle());
+            swpc::start(app.handle());
+            relay::start(app.handle());
+            mqtt::start(app.handle());
+            cyber::start(app.handle());
+            markets::start(app.handle());
+            maritime::start(app.handle());
+            anomalies::start(app.handle());
+            exports::start(app.handle());
+            weather::start(app.handle());
+            news::start(app.handle());
 
             Ok(())
         })
         .build(tauri::generate_context!())
-        .expect("error while running world-monitor tauri application")
+        .unwrap_or_else(|err| {
+            crash_report::record_fatal_error(format!("error while building world-monitor tauri application: {err}"));
+            std::process::exit(1);
+        })
         .run(|app, event| {
+            if let RunEvent::WindowEvent {
+                label,
+                event: WindowEvent::CloseRequested { .. },
+                ..
+            } = &event
+            {
+                window_state::save(app, label);
+                panel_windows::on_close_requested(app, label);
+                map_display::on_close_requested(app, label);
+                ticker::on_close_requested(app, label);
+                launch::on_close_requested(app, label);
+            }
+            if let RunEvent::WindowEvent {
+                label,
+                event: WindowEvent::Moved(_),
+                ..
+            } = &event
+            {
+                ticker::on_moved(app, label);
+            }
+            if let RunEvent::WindowEvent {
+                label,
+                event: WindowEvent::DragDrop(DragDropEvent::Drop { paths, .. }),
+                ..
+            } = &event
+            {
+                file_import::on_drop(app, label, paths);
+            }
             match &event {
                 // macOS: hide window on close instead of quitting (standard behavior)
                 #[cfg(target_os = "macos")]
@@ -1240,6 +2640,36 @@ fn main() {
                         let _ = w.hide();
                     }
                 }
+                // Windows/Linux: hide to the tray instead of exiting when enabled,
+                // so the sidecar and feeds keep running.
+                #[cfg(not(target_os = "macos"))]
+                RunEvent::WindowEvent {
+                    label,
+                    event: WindowEvent::CloseRequested { api, .. },
+                    ..
+                } if label == "main" && tray::closes_to_tray(app) => {
+                    api.prevent_close();
+                    tray::hide_main_window(app);
+                }
+                RunEvent::WindowEvent {
+                    label,
+                    event: WindowEvent::Resized(_),
+                    ..
+                } if label == "main" && tray::minimizes_to_tray(app) => {
+                    let minimized = app
+                        .get_webview_window("main")
+                        .and_then(|w| w.is_minimized().ok())
+                        .unwrap_or(false);
+                    if minimized {
+                        tray::hide_main_window(app);
+                    }
+                }
+                // macOS: files opened with the app (deep links arrive through the plugin)
+                #[cfg(target_os = "macos")]
+                RunEvent::Opened { urls } => {
+                    let paths = urls.iter().filter_map(|url| url.to_file_path().ok()).collect();
+                    file_import::on_open(app, paths);
+                }
                 // macOS: reshow window when dock icon is clicked
                 #[cfg(target_os = "macos")]
                 RunEvent::Reopen { .. } => {
@@ -1263,6 +2693,8 @@ fn main() {
                     }
                 }
                 RunEvent::ExitRequested { .. } | RunEvent::Exit => {
+                    keep_awake::release(app);
+                    window_state::save_all(app);
                     // Flush in-memory cache to disk before quitting
                     if let Ok(path) = cache_file_path(app) {
                         if let Some(cache) = app.try_state::<PersistentCache>() {
@@ -0,0 +1,149 @@
+//! Secondary map display. Window → Open Map Display opens a chromeless
+//! `map-display` window showing only the map (`panel.html?id=map&display=1`),
+//! meant to sit full-screen on a second monitor. The first time it opens on
+//! the first non-primary monitor, maximized; afterwards `window_state` puts it
+//! back on the display it was last on. Whether it was open is kept in
+//! `map-display.json` so it reopens on the next launch; closing it (Escape or
+//! `close_map_display`) turns that off.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::{AppHandle, Manager, PhysicalPosition, Webview, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+pub const MAP_DISPLAY_FILE: &str = "map-display.json";
+pub const MAP_DISPLAY_WINDOW: &str = "map-display";
+
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+struct Persisted {
+    open: bool,
+}
+
+/// Managed state: whether the display should be open at launch.
+pub struct MapDisplayState {
+    path: PathBuf,
+    open: AtomicBool,
+}
+
+impl MapDisplayState {
+    /// Load `map-display.json`; a missing or unreadable file means closed.
+    pub fn load(path: PathBuf) -> Self {
+        let persisted: Persisted = fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        MapDisplayState {
+            path,
+            open: AtomicBool::new(persisted.open),
+        }
+    }
+
+    fn set_open(&self, open: bool) {
+        if self.open.swap(open, Ordering::SeqCst) == open {
+            return;
+        }
+        let written = serde_json::to_string_pretty(&Persisted { open })
+            .map_err(|e| format!("Failed to serialize map display state: {e}"))
+            .and_then(|serialized| {
+                fs::write(&self.path, serialized)
+                    .map_err(|e| format!("Failed to write map display state {}: {e}", self.path.display()))
+            });
+        if let Err(err) = written {
+            tracing::warn!(target: "app", "{err}");
+        }
+    }
+}
+
+/// Without saved geometry, move the window onto a secondary monitor (if any)
+/// and fill it.
+fn place_on_secondary_monitor(window: &WebviewWindow) {
+    let primary = window.primary_monitor().ok().flatten();
+    let secondary = window
+        .available_monitors()
+        .unwrap_or_default()
+        .into_iter()
+        .find(|m| primary.as_ref().is_none_or(|p| p.position() != m.position()));
+    if let Some(monitor) = secondary {
+        let _ = window.set_position(PhysicalPosition::new(monitor.position().x, monitor.position().y));
+    }
+    let _ = window.maximize();
+}
+
+/// Open (or focus) the map display.
+pub fn open(app: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(MAP_DISPLAY_WINDOW) {
+        let _ = window.show();
+        let _ = window.unminimize();
+        return window
+            .set_focus()
+            .map_err(|e| format!("Failed to focus map display: {e}"));
+    }
+
+    let window = WebviewWindowBuilder::new(
+        app,
+        MAP_DISPLAY_WINDOW,
+        WebviewUrl::App("panel.html?id=map&display=1".into()),
+    )
+    .title(format!("{} Map", app.package_info().name))
+    .inner_size(1280.0, 800.0)
+    .min_inner_size(480.0, 320.0)
+    .decorations(false)
+    .resizable(true)
+    .visible(false)
+    .background_color(tauri::webview::Color(26, 28, 30, 255))
+    .build()
+    .map_err(|e| format!("Failed to create map display: {e}"))?;
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = window.remove_menu();
+
+    if crate::window_state::has_saved(app, MAP_DISPLAY_WINDOW) {
+        crate::window_state::restore(&window);
+    } else {
+        place_on_secondary_monitor(&window);
+    }
+    let _ = window.show();
+
+    if let Some(state) = app.try_state::<MapDisplayState>() {
+        state.set_open(true);
+    }
+    tracing::info!(target: "app", "map display opened");
+    Ok(())
+}
+
+/// The user closed the display: don't bring it back on launch.
+pub fn on_close_requested(app: &AppHandle, label: &str) {
+    if label != MAP_DISPLAY_WINDOW {
+        return;
+    }
+    if let Some(state) = app.try_state::<MapDisplayState>() {
+        state.set_open(false);
+    }
+}
+
+/// Reopen the display if it was open when the app last quit.
+pub fn restore(app: &AppHandle) {
+    let reopen = app
+        .try_state::<MapDisplayState>()
+        .is_some_and(|state| state.open.load(Ordering::SeqCst));
+    if reopen {
+        if let Err(err) = open(app) {
+            tracing::warn!(target: "app", "failed to restore map display: {err}");
+        }
+    }
+}
+
+/// Close the map display; it has no title bar, so the page calls this on Escape.
+#[tauri::command]
+pub fn close_map_display(webview: Webview, app: AppHandle) -> Result<(), String> {
+    crate::metrics::of(&webview).observe("close_map_display", || {
+        crate::require_trusted_window(webview.label())?;
+        if let Some(window) = app.get_webview_window(MAP_DISPLAY_WINDOW) {
+            window
+                .close()
+                .map_err(|e| format!("Failed to close map display: {e}"))?;
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,408 @@
+//! Maritime zones: exclusive economic zones (200 nm) and territorial seas
+//! (12 nm) from Marine Regions, for `lookup_maritime_zone` and for
+//! geofences drawn as a whole zone. Each layer is read at startup from the
+//! cache (`maritime/<kind>.geojson` in the app cache directory), else from
+//! the app's bundled `data/maritime`, else downloaded once from the URL in
+//! `maritime` config, simplified to `maritime.tolerance` degrees and cached.
+//!
+//! Zones are indexed by the bounding box of each of their polygons, so a
+//! zone split at the antimeridian doesn't cover the whole world. Once both
+//! layers are in, the index is handed to `geofences`.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use geo::{BoundingRect, Intersects, MultiPolygon, Point, Simplify};
+use geojson::{Feature, FeatureCollection, GeoJson};
+use rstar::primitives::{GeomWithData, Rectangle};
+use rstar::RTree;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager, Webview};
+
+use crate::config::DesktopConfigState;
+
+const CACHE_DIR: &str = "maritime";
+/// The full-resolution layers are hundreds of megabytes.
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// Ordered from the coast outwards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ZoneKind {
+    /// Territorial sea, out to 12 nautical miles.
+    Territorial,
+    /// Exclusive economic zone, out to 200 nautical miles.
+    Eez,
+}
+
+impl ZoneKind {
+    const ALL: [ZoneKind; 2] = [ZoneKind::Territorial, ZoneKind::Eez];
+
+    fn name(self) -> &'static str {
+        match self {
+            ZoneKind::Territorial => "territorial",
+            ZoneKind::Eez => "eez",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaritimeZone {
+    /// `<kind>:<MRGID>`, e.g. `eez:8442`; what a zone geofence refers to.
+    pub id: String,
+    pub kind: ZoneKind,
+    pub name: String,
+    pub territory: String,
+    pub sovereign: String,
+    /// ISO 3166 alpha-3 code of the sovereign.
+    pub iso: String,
+    /// `200NM`, `12NM`, `Overlapping claim`, `Joint regime`...
+    pub pol_type: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaritimeStatus {
+    pub loading: bool,
+    pub eez: usize,
+    pub territorial: usize,
+    pub error: Option<String>,
+}
+
+struct Zone {
+    info: MaritimeZone,
+    shape: Arc<MultiPolygon<f64>>,
+}
+
+/// Zones indexed for point lookups.
+pub struct ZoneIndex {
+    zones: Vec<Zone>,
+    tree: RTree<GeomWithData<Rectangle<[f64; 2]>, usize>>,
+}
+
+/// One box per polygon of `shape`.
+pub(crate) fn polygon_boxes(shape: &MultiPolygon<f64>) -> Vec<Rectangle<[f64; 2]>> {
+    shape
+        .iter()
+        .filter_map(|polygon| polygon.bounding_rect())
+        .map(|rect| Rectangle::from_corners([rect.min().x, rect.min().y], [rect.max().x, rect.max().y]))
+        .collect()
+}
+
+impl ZoneIndex {
+    fn new(zones: Vec<(MaritimeZone, MultiPolygon<f64>)>) -> Self {
+        let mut boxes = Vec::new();
+        let zones: Vec<Zone> = zones
+            .into_iter()
+            .enumerate()
+            .map(|(index, (info, shape))| {
+                boxes.extend(polygon_boxes(&shape).into_iter().map(|b| GeomWithData::new(b, index)));
+                Zone { info, shape: Arc::new(shape) }
+            })
+            .collect();
+        ZoneIndex { zones, tree: RTree::bulk_load(boxes) }
+    }
+
+    /// The zones containing a point, territorial seas first.
+    pub fn lookup(&self, lat: f64, lon: f64) -> Vec<MaritimeZone> {
+        let point = Point::new(lon, lat);
+        let mut found: Vec<usize> = self
+            .tree
+            .locate_all_at_point(&[lon, lat])
+            .map(|entry| entry.data)
+            .filter(|&index| self.zones[index].shape.intersects(&point))
+            .collect();
+        found.sort_unstable();
+        found.dedup();
+        let mut zones: Vec<MaritimeZone> = found.into_iter().map(|i| self.zones[i].info.clone()).collect();
+        zones.sort_by_key(|z| z.kind);
+        zones
+    }
+
+    pub fn shape(&self, id: &str) -> Option<Arc<MultiPolygon<f64>>> {
+        self.zones.iter().find(|z| z.info.id == id).map(|z| z.shape.clone())
+    }
+
+    fn count(&self, kind: ZoneKind) -> usize {
+        self.zones.iter().filter(|z| z.info.kind == kind).count()
+    }
+}
+
+/// Whether `id` has the form of a zone id.
+pub fn is_zone_id(id: &str) -> bool {
+    id.split_once(':').is_some_and(|(kind, mrgid)| {
+        ZoneKind::ALL.iter().any(|k| k.name() == kind) && !mrgid.is_empty() && mrgid.bytes().all(|b| b.is_ascii_digit())
+    })
+}
+
+#[derive(Default)]
+pub struct MaritimeState {
+    index: RwLock<Option<Arc<ZoneIndex>>>,
+    status: Mutex<MaritimeStatus>,
+}
+
+impl MaritimeState {
+    pub fn index(&self) -> Option<Arc<ZoneIndex>> {
+        self.index.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+fn update_status(app: &AppHandle, f: impl FnOnce(&mut MaritimeStatus)) {
+    f(&mut app.state::<MaritimeState>().status.lock().unwrap_or_else(|e| e.into_inner()));
+}
+
+/// A property as text; Marine Regions spells the keys in either case.
+fn property(properties: &Map<String, Value>, key: &str) -> String {
+    match properties.iter().find(|(k, _)| k.eq_ignore_ascii_case(key)).map(|(_, v)| v) {
+        Some(Value::String(s)) => s.trim().to_string(),
+        Some(Value::Number(n)) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// The zones of one layer's GeoJSON, simplified when `tolerance` > 0.
+/// Features without an MRGID or an area are skipped.
+fn parse_layer(kind: ZoneKind, text: &str, tolerance: f64) -> Result<Vec<(MaritimeZone, MultiPolygon<f64>)>, String> {
+    let geojson: GeoJson = text.parse().map_err(|e| format!("Invalid GeoJSON: {e}"))?;
+    let GeoJson::FeatureCollection(collection) = geojson else {
+        return Err("Expected a feature collection".to_string());
+    };
+    let mut zones = Vec::new();
+    for feature in collection.features {
+        let properties = feature.properties.unwrap_or_default();
+        let mrgid = property(&properties, "mrgid");
+        let Some(geometry) = feature.geometry.filter(|_| !mrgid.is_empty()) else {
+            continue;
+        };
+        let shape: MultiPolygon<f64> = match geo::Geometry::<f64>::try_from(geometry) {
+            Ok(geo::Geometry::Polygon(polygon)) => polygon.into(),
+            Ok(geo::Geometry::MultiPolygon(polygons)) => polygons,
+            _ => continue,
+        };
+        let shape = if tolerance > 0.0 { shape.simplify(tolerance) } else { shape };
+        if shape.0.is_empty() {
+            continue;
+        }
+        zones.push((
+            MaritimeZone {
+                id: format!("{}:{mrgid}", kind.name()),
+                kind,
+                name: property(&properties, "geoname"),
+                territory: property(&properties, "territory1"),
+                sovereign: property(&properties, "sovereign1"),
+                iso: property(&properties, "iso_sov1"),
+                pol_type: property(&properties, "pol_type"),
+            },
+            shape,
+        ));
+    }
+    Ok(zones)
+}
+
+/// Zones as GeoJSON `parse_layer` reads back, for the cache.
+fn to_geojson(zones: &[(MaritimeZone, MultiPolygon<f64>)]) -> String {
+    let features = zones
+        .iter()
+        .map(|(zone, shape)| {
+            let mrgid = zone.id.split_once(':').map_or("", |(_, id)| id);
+            let properties = serde_json::json!({
+                "mrgid": mrgid,
+                "geoname": zone.name,
+                "territory1": zone.territory,
+                "sovereign1": zone.sovereign,
+                "iso_sov1": zone.iso,
+                "pol_type": zone.pol_type,
+            });
+            Feature {
+                geometry: Some(geojson::Geometry::new(geojson::Value::from(shape))),
+                properties: properties.as_object().cloned(),
+                ..Default::default()
+            }
+        })
+        .collect();
+    GeoJson::FeatureCollection(FeatureCollection { bbox: None, features, foreign_members: None }).to_string()
+}
+
+/// A layer from the cache or the bundle, if either has it.
+fn read_local(app: &AppHandle, kind: ZoneKind, cached: &Option<PathBuf>) -> Option<String> {
+    let file = format!("{}.geojson", kind.name());
+    let bundled = app.path().resource_dir().ok().into_iter().flat_map(|dir| {
+        [dir.join("_up_").join("data").join(CACHE_DIR).join(&file), dir.join("data").join(CACHE_DIR).join(&file)]
+    });
+    cached.iter().cloned().chain(bundled).find_map(|path| fs::read_to_string(path).ok())
+}
+
+async fn load_layer(app: &AppHandle, kind: ZoneKind) -> Result<Vec<(MaritimeZone, MultiPolygon<f64>)>, String> {
+    let config = app.state::<DesktopConfigState>().snapshot().maritime;
+    let url = match kind {
+        ZoneKind::Territorial => config.territorial_url,
+        ZoneKind::Eez => config.eez_url,
+    };
+    let cached = app.path().app_cache_dir().ok().map(|dir| dir.join(CACHE_DIR).join(format!("{}.geojson", kind.name())));
+    let local = {
+        let (app, cached) = (app.clone(), cached.clone());
+        crate::runtime::blocking(move || Ok(read_local(&app, kind, &cached))).await?
+    };
+    if let Some(text) = local {
+        return crate::runtime::blocking(move || parse_layer(kind, &text, 0.0)).await;
+    }
+    if url.is_empty() {
+        return Ok(Vec::new());
+    }
+    tracing::info!(target: "app", layer = kind.name(), "downloading maritime zones");
+    let client = reqwest::Client::builder()
+        .use_native_tls()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .build()
+        .map_err(|e| format!("HTTP client unavailable: {e}"))?;
+    let response = client.get(&url).send().await.map_err(|e| format!("download failed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("download failed: HTTP {}", response.status()));
+    }
+    let text = response.text().await.map_err(|e| format!("download failed: {e}"))?;
+    let tolerance = config.tolerance.max(0.0);
+    crate::runtime::blocking(move || {
+        let zones = parse_layer(kind, &text, tolerance)?;
+        if let Some(path) = cached {
+            let written = path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|_| fs::write(&path, to_geojson(&zones)));
+            if let Err(err) = written {
+                tracing::warn!(target: "app", path = %path.display(), "could not cache maritime zones: {err}");
+            }
+        }
+        Ok(zones)
+    })
+    .await
+}
+
+async fn load(app: AppHandle) {
+    update_status(&app, |status| status.loading = true);
+    let mut zones = Vec::new();
+    let mut errors = Vec::new();
+    for kind in ZoneKind::ALL {
+        match load_layer(&app, kind).await {
+            Ok(layer) => zones.extend(layer),
+            Err(err) => errors.push(format!("{}: {err}", kind.name())),
+        }
+    }
+    let index = Arc::new(ZoneIndex::new(zones));
+    update_status(&app, |status| {
+        status.loading = false;
+        status.eez = index.count(ZoneKind::Eez);
+        status.territorial = index.count(ZoneKind::Territorial);
+        status.error = (!errors.is_empty()).then(|| errors.join("; "));
+    });
+    if !errors.is_empty() {
+        tracing::warn!(target: "app", "maritime zones incomplete: {}", errors.join("; "));
+    }
+    *app.state::<MaritimeState>().index.write().unwrap_or_else(|e| e.into_inner()) = Some(index.clone());
+    crate::geofences::set_zones(&app, index);
+}
+
+/// Load the zones in the background unless `maritime.enabled` is off.
+pub fn start(app: &AppHandle) {
+    if !app.state::<DesktopConfigState>().snapshot().maritime.enabled {
+        return;
+    }
+    tauri::async_runtime::spawn(load(app.clone()));
+}
+
+/// The territorial sea and EEZ containing a point; empty on the high seas
+/// or before the zones have loaded.
+#[tauri::command]
+pub fn lookup_maritime_zone(
+    webview: Webview,
+    state: tauri::State<'_, MaritimeState>,
+    lat: f64,
+    lon: f64,
+) -> Result<Vec<MaritimeZone>, String> {
+    crate::metrics::of(&webview).observe("lookup_maritime_zone", || {
+        crate::require_trusted_window(webview.label())?;
+        if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+            return Err("Coordinates out of range".to_string());
+        }
+        Ok(state.index().map(|index| index.lookup(lat, lon)).unwrap_or_default())
+    })
+}
+
+/// Every loaded zone, for picking one to watch; optionally one kind only.
+#[tauri::command]
+pub fn list_maritime_zones(
+    webview: Webview,
+    state: tauri::State<'_, MaritimeState>,
+    kind: Option<ZoneKind>,
+) -> Result<Vec<MaritimeZone>, String> {
+    crate::metrics::of(&webview).observe("list_maritime_zones", || {
+        crate::require_trusted_window(webview.label())?;
+        let Some(index) = state.index() else {
+            return Ok(Vec::new());
+        };
+        Ok(index.zones.iter().filter(|z| kind.is_none_or(|k| z.info.kind == k)).map(|z| z.info.clone()).collect())
+    })
+}
+
+#[tauri::command]
+pub fn get_maritime_status(webview: Webview, state: tauri::State<'_, MaritimeState>) -> Result<MaritimeStatus, String> {
+    crate::metrics::of(&webview).observe("get_maritime_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.status.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod maritime_tests {
+    use super::*;
+
+    /// A square EEZ around (5, 5) with a territorial sea in one corner, and
+    /// a Fiji-like EEZ split at the antimeridian.
+    pub(crate) fn index() -> ZoneIndex {
+        let eez = r#"{"type":"FeatureCollection","features":[
+            {"type":"Feature","properties":{"MRGID":8442,"GEONAME":"Testland Exclusive Economic Zone","TERRITORY1":"Testland",
+             "SOVEREIGN1":"Testland","ISO_SOV1":"TST","POL_TYPE":"200NM"},
+             "geometry":{"type":"Polygon","coordinates":[[[0,0],[10,0],[10,10],[0,10],[0,0]]]}},
+            {"type":"Feature","properties":{"mrgid":"8325","geoname":"Fijian Exclusive Economic Zone","pol_type":"200NM"},
+             "geometry":{"type":"MultiPolygon","coordinates":[
+                [[[175,-20],[180,-20],[180,-15],[175,-15],[175,-20]]],
+                [[[-180,-20],[-178,-20],[-178,-15],[-180,-15],[-180,-20]]]]}},
+            {"type":"Feature","properties":{"geoname":"No id"},
+             "geometry":{"type":"Polygon","coordinates":[[[0,0],[1,0],[1,1],[0,0]]]}}
+        ]}"#;
+        let territorial = r#"{"type":"FeatureCollection","features":[
+            {"type":"Feature","properties":{"mrgid":"48000","geoname":"Testland 12 NM","pol_type":"12NM"},
+             "geometry":{"type":"Polygon","coordinates":[[[0,0],[2,0],[2,2],[0,2],[0,0]]]}}]}"#;
+        let mut zones = parse_layer(ZoneKind::Eez, eez, 0.01).unwrap();
+        zones.extend(parse_layer(ZoneKind::Territorial, territorial, 0.0).unwrap());
+        ZoneIndex::new(zones)
+    }
+
+    #[test]
+    fn looks_up_zones_by_point() {
+        let index = index();
+        assert_eq!((index.count(ZoneKind::Eez), index.count(ZoneKind::Territorial)), (2, 1));
+        let ids = |lat, lon| index.lookup(lat, lon).into_iter().map(|z| z.id).collect::<Vec<_>>();
+        assert_eq!(ids(1.0, 1.0), ["territorial:48000", "eez:8442"]);
+        assert_eq!(ids(5.0, 5.0), ["eez:8442"]);
+        assert!(ids(20.0, 5.0).is_empty());
+        assert_eq!(ids(-17.0, 179.5), ["eez:8325"]);
+        assert_eq!(ids(-17.0, -179.0), ["eez:8325"]);
+        assert!(ids(-17.0, 0.0).is_empty());
+        let testland = &index.lookup(5.0, 5.0)[0];
+        assert_eq!((testland.iso.as_str(), testland.pol_type.as_str()), ("TST", "200NM"));
+        // The Fijian zone's two halves are indexed apart.
+        assert_eq!(index.tree.size(), 4);
+    }
+
+    #[test]
+    fn caches_what_it_parsed() {
+        let index = index();
+        let zone = index.lookup(5.0, 5.0).remove(0);
+        let shape = index.shape(&zone.id).unwrap().as_ref().clone();
+        let parsed = parse_layer(ZoneKind::Eez, &to_geojson(&[(zone.clone(), shape.clone())]), 0.0).unwrap();
+        assert_eq!(parsed, [(zone, shape)]);
+        assert!(is_zone_id("eez:8442") && is_zone_id("territorial:1"));
+        assert!(!is_zone_id("eez:") && !is_zone_id("contiguous:1") && !is_zone_id("8442"));
+    }
+}
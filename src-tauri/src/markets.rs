@@ -0,0 +1,500 @@
+//! Market indices, commodities and FX pairs polled into the local `store`,
+//! so the markets panel has intraday history offline and threshold rules
+//! can watch prices. The instruments in `markets.symbols` are polled at
+//! startup and then every `markets.interval_secs`, or right away with
+//! `sync_markets`, each from its configured provider:
+//!
+//! - `yahoo`: Yahoo Finance's chart API, the day's 5-minute closes;
+//! - `finnhub`: Finnhub's quote API with `FINNHUB_API_KEY`, the current
+//!   price as one tick per poll.
+//!
+//! Each poll stores the latest quote and any new ticks; ticks older than
+//! `markets.retention_days` are pruned. Every price is recorded as the
+//! `market.<symbol>` metric, and its change from the previous close in
+//! percent as `market.<symbol>.change`, so a rule like "Brent above $90" is
+//! a threshold on `market.BZ=F`. Symbols with new ticks go out together as
+//! `markets://updated`, shaped like `get_market_series` returns them.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+use tokio::sync::watch;
+
+use crate::config::{DesktopConfigState, MarketKind, MarketProvider, MarketSymbol};
+use crate::store::with_store;
+
+pub const UPDATED_EVENT: &str = "markets://updated";
+
+const YAHOO_URL: &str = "https://query1.finance.yahoo.com/v8/finance/chart";
+const FINNHUB_URL: &str = "https://finnhub.io/api/v1/quote";
+const FINNHUB_SECRET: &str = "FINNHUB_API_KEY";
+/// Yahoo turns away clients that don't look like a browser.
+const BROWSER_USER_AGENT: &str =
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+const MIN_INTERVAL: Duration = Duration::from_secs(60);
+/// Between two requests of one poll, to stay clear of rate limits.
+const REQUEST_SPACING: Duration = Duration::from_millis(250);
+const DEFAULT_HOURS: u32 = 24;
+/// Points per series when the caller doesn't ask for a number.
+const DEFAULT_POINTS: usize = 120;
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketSyncStatus {
+    pub syncing: bool,
+    /// Unix seconds of the last completed poll.
+    pub last_sync: Option<u64>,
+    /// Symbols with new ticks in the last completed poll.
+    pub updated: Vec<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct MarketsState {
+    /// Bumped to request a poll now.
+    trigger: Mutex<Option<watch::Sender<u64>>>,
+    status: Mutex<MarketSyncStatus>,
+}
+
+fn update_status(app: &AppHandle, f: impl FnOnce(&mut MarketSyncStatus)) -> MarketSyncStatus {
+    let state = app.state::<MarketsState>();
+    let mut status = state.status.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut status);
+    status.clone()
+}
+
+/// One instrument's stored history, ready for a sparkline.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketSeries {
+    pub symbol: String,
+    pub name: String,
+    pub kind: MarketKind,
+    pub currency: Option<String>,
+    /// Tick times as milliseconds since the epoch, oldest first.
+    pub timestamps: Vec<i64>,
+    pub values: Vec<f64>,
+    pub price: Option<f64>,
+    pub previous_close: Option<f64>,
+    pub change: Option<f64>,
+    pub change_percent: Option<f64>,
+    /// Milliseconds since the epoch of the latest quote.
+    pub updated_at: Option<i64>,
+}
+
+/// A provider's answer for one symbol.
+#[derive(Clone, Debug, PartialEq)]
+struct Quote {
+    price: f64,
+    previous_close: Option<f64>,
+    currency: Option<String>,
+    /// Milliseconds since the epoch.
+    time: i64,
+    ticks: Vec<(i64, f64)>,
+}
+
+impl Quote {
+    fn change(&self) -> Option<(f64, f64)> {
+        let previous = self.previous_close.filter(|p| *p != 0.0)?;
+        let change = self.price - previous;
+        Some((change, change / previous.abs() * 100.0))
+    }
+
+    fn to_value(&self) -> Value {
+        json!({
+            "price": self.price,
+            "previousClose": self.previous_close,
+            "currency": self.currency,
+            "time": self.time,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct YahooResponse {
+    chart: YahooChart,
+}
+
+#[derive(Deserialize)]
+struct YahooChart {
+    #[serde(default)]
+    result: Option<Vec<YahooResult>>,
+    #[serde(default)]
+    error: Option<YahooError>,
+}
+
+#[derive(Deserialize)]
+struct YahooError {
+    #[serde(default)]
+    description: String,
+}
+
+#[derive(Deserialize)]
+struct YahooResult {
+    meta: YahooMeta,
+    #[serde(default)]
+    timestamp: Vec<i64>,
+    #[serde(default)]
+    indicators: YahooIndicators,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct YahooMeta {
+    currency: Option<String>,
+    regular_market_price: Option<f64>,
+    regular_market_time: Option<i64>,
+    chart_previous_close: Option<f64>,
+    previous_close: Option<f64>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct YahooIndicators {
+    quote: Vec<YahooCloses>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct YahooCloses {
+    close: Vec<Option<f64>>,
+}
+
+/// Finnhub answers unknown symbols with all zeroes.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct FinnhubQuote {
+    c: f64,
+    pc: f64,
+    h: f64,
+    l: f64,
+    t: i64,
+}
+
+fn parse_yahoo(text: &str) -> Result<Quote, String> {
+    let response: YahooResponse = serde_json::from_str(text).map_err(|e| format!("invalid response: {e}"))?;
+    if let Some(error) = response.chart.error {
+        return Err(error.description);
+    }
+    let result = response.chart.result.and_then(|r| r.into_iter().next()).ok_or("no such symbol")?;
+    let closes = result.indicators.quote.into_iter().next().unwrap_or_default().close;
+    let ticks: Vec<(i64, f64)> = result
+        .timestamp
+        .iter()
+        .zip(closes)
+        .filter_map(|(time, close)| Some((time * 1000, close.filter(|c| c.is_finite())?)))
+        .collect();
+    let meta = result.meta;
+    let (time, price) = match (meta.regular_market_time, meta.regular_market_price) {
+        (Some(time), Some(price)) => (time * 1000, price),
+        _ => *ticks.last().ok_or("no price")?,
+    };
+    Ok(Quote {
+        price,
+        previous_close: meta.chart_previous_close.or(meta.previous_close),
+        currency: meta.currency,
+        time,
+        ticks,
+    })
+}
+
+fn parse_finnhub(text: &str) -> Result<Quote, String> {
+    let quote: FinnhubQuote = serde_json::from_str(text).map_err(|e| format!("invalid response: {e}"))?;
+    if quote.c == 0.0 && quote.h == 0.0 && quote.l == 0.0 {
+        return Err("no such symbol".to_string());
+    }
+    let time = quote.t * 1000;
+    Ok(Quote {
+        price: quote.c,
+        previous_close: Some(quote.pc).filter(|pc| *pc != 0.0),
+        currency: None,
+        time,
+        ticks: vec![(time, quote.c)],
+    })
+}
+
+async fn fetch_quote(app: &AppHandle, client: &reqwest::Client, symbol: &MarketSymbol) -> Result<Quote, String> {
+    let request = match symbol.provider {
+        MarketProvider::Yahoo => client
+            .get(format!("{YAHOO_URL}/{}", urlencode(&symbol.symbol)))
+            .query(&[("range", "1d"), ("interval", "5m")])
+            .header(reqwest::header::USER_AGENT, BROWSER_USER_AGENT),
+        MarketProvider::Finnhub => {
+            let key = app
+                .state::<crate::SecretsCache>()
+                .get(FINNHUB_SECRET)
+                .ok_or_else(|| format!("{FINNHUB_SECRET} is not set"))?;
+            client.get(FINNHUB_URL).query(&[("symbol", symbol.symbol.as_str()), ("token", key.as_str())])
+        }
+    };
+    // Finnhub's key is in the URL; keep it out of error messages.
+    let response = request.send().await.map_err(|e| format!("request failed: {}", e.without_url()))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("HTTP {status}"));
+    }
+    let text = response.text().await.map_err(|e| format!("request failed: {}", e.without_url()))?;
+    match symbol.provider {
+        MarketProvider::Yahoo => parse_yahoo(&text),
+        MarketProvider::Finnhub => parse_finnhub(&text),
+    }
+}
+
+/// A path segment with everything but unreserved characters escaped
+/// (`^GSPC`, `BZ=F`).
+fn urlencode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// At most `points` ticks, the last of each even slice, so a sparkline
+/// keeps its shape and ends on the latest price.
+fn downsample(ticks: Vec<(i64, f64)>, points: usize) -> Vec<(i64, f64)> {
+    if points == 0 || ticks.len() <= points {
+        return ticks;
+    }
+    (1..=points).map(|i| ticks[i * ticks.len() / points - 1]).collect()
+}
+
+fn series(symbol: &MarketSymbol, quote: &Value, ticks: Vec<(i64, f64)>, points: usize) -> MarketSeries {
+    let price = quote["price"].as_f64();
+    let previous_close = quote["previousClose"].as_f64().filter(|p| *p != 0.0);
+    let change = price.zip(previous_close).map(|(p, c)| p - c);
+    let (timestamps, values) = downsample(ticks, points).into_iter().unzip();
+    MarketSeries {
+        symbol: symbol.symbol.clone(),
+        name: if symbol.name.is_empty() { symbol.symbol.clone() } else { symbol.name.clone() },
+        kind: symbol.kind,
+        currency: quote["currency"].as_str().map(str::to_string),
+        timestamps,
+        values,
+        price,
+        previous_close,
+        change,
+        change_percent: change.zip(previous_close).map(|(c, p)| c / p.abs() * 100.0),
+        updated_at: quote["time"].as_i64(),
+    }
+}
+
+/// Poll every configured symbol, returning those with new ticks.
+async fn sync(app: &AppHandle, client: &reqwest::Client) -> Result<Vec<MarketSeries>, String> {
+    let config = app.state::<DesktopConfigState>().snapshot().markets;
+    let now = chrono::Utc::now().timestamp_millis();
+    let since = now - i64::from(DEFAULT_HOURS) * 60 * 60 * 1000;
+    let mut updated = Vec::new();
+    let mut errors = Vec::new();
+    for (i, symbol) in config.symbols.iter().enumerate() {
+        if i > 0 {
+            tokio::time::sleep(REQUEST_SPACING).await;
+        }
+        let quote = match fetch_quote(app, client, symbol).await {
+            Ok(quote) => quote,
+            Err(err) => {
+                errors.push(format!("{}: {err}", symbol.symbol));
+                continue;
+            }
+        };
+        crate::alerts::record_metric(app, &format!("market.{}", symbol.symbol), quote.price);
+        if let Some((_, percent)) = quote.change() {
+            crate::alerts::record_metric(app, &format!("market.{}.change", symbol.symbol), percent);
+        }
+        let stored = with_store(app, {
+            let symbol = symbol.clone();
+            move |store| {
+                let value = quote.to_value();
+                if store.store_market_quote(&symbol.symbol, &value, &quote.ticks)? == 0 {
+                    return Ok(None);
+                }
+                Ok(Some(series(&symbol, &value, store.market_ticks(&symbol.symbol, since)?, DEFAULT_POINTS)))
+            }
+        })
+        .await?;
+        updated.extend(stored);
+    }
+    let cutoff = now - i64::from(config.retention_days.max(1)) * 24 * 60 * 60 * 1000;
+    with_store(app, move |store| store.prune_market_ticks(cutoff)).await?;
+    if !updated.is_empty() {
+        let _ = app.emit(UPDATED_EVENT, &updated);
+    }
+    if errors.is_empty() {
+        Ok(updated)
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+struct Poller {
+    app: AppHandle,
+    client: reqwest::Client,
+}
+
+impl crate::poll::Poller for Poller {
+    async fn round(&mut self) -> Duration {
+        let app = &self.app;
+        update_status(app, |status| status.syncing = true);
+        let result = sync(app, &self.client).await;
+        let status = update_status(app, |status| {
+            status.syncing = false;
+            status.last_sync = Some(crate::unix_timestamp_secs());
+            match result {
+                Ok(updated) => {
+                    status.updated = updated.into_iter().map(|s| s.symbol).collect();
+                    status.error = None;
+                }
+                Err(err) => status.error = Some(err),
+            }
+        });
+        match &status.error {
+            Some(err) => tracing::warn!(target: "app", "Market poll failed: {err}"),
+            None => tracing::debug!(target: "app", updated = ?status.updated, "Market poll complete"),
+        }
+        let interval = Duration::from_secs(app.state::<DesktopConfigState>().snapshot().markets.interval_secs);
+        interval.max(MIN_INTERVAL)
+    }
+}
+
+async fn run(app: AppHandle, trigger: watch::Receiver<u64>) {
+    let client = match reqwest::Client::builder().use_native_tls().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!(target: "app", "Market polling unavailable: {err}");
+            return;
+        }
+    };
+    let poller = Poller {
+        app: app.clone(),
+        client,
+    };
+    crate::poll::run_triggered(&app, trigger, poller).await;
+}
+
+/// Start the poller unless `markets.enabled` is off; it polls right away.
+pub fn start(app: &AppHandle) {
+    if !app.state::<DesktopConfigState>().snapshot().markets.enabled {
+        return;
+    }
+    let (sender, receiver) = watch::channel(0);
+    *app.state::<MarketsState>().trigger.lock().unwrap_or_else(|e| e.into_inner()) = Some(sender);
+    tauri::async_runtime::spawn(run(app.clone(), receiver));
+}
+
+/// Poll now, e.g. right after the symbols changed. Returns at once.
+#[tauri::command]
+pub fn sync_markets(webview: Webview, state: tauri::State<'_, MarketsState>) -> Result<MarketSyncStatus, String> {
+    crate::metrics::of(&webview).observe("sync_markets", || {
+        crate::require_trusted_window(webview.label())?;
+        let status = state.status.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        if !status.syncing {
+            match state.trigger.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+                Some(trigger) => trigger.send_modify(|n| *n += 1),
+                None => return Err("Market polling is disabled".to_string()),
+            }
+        }
+        Ok(status)
+    })
+}
+
+#[tauri::command]
+pub fn get_market_sync_status(webview: Webview, state: tauri::State<'_, MarketsState>) -> Result<MarketSyncStatus, String> {
+    crate::metrics::of(&webview).observe("get_market_sync_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.status.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+/// Stored series (the configured ones when `symbols` is empty) over the
+/// last `hours`, each cut down to at most `points` ticks; symbols never
+/// polled are left out.
+#[tauri::command]
+pub async fn get_market_series(
+    webview: Webview,
+    app: AppHandle,
+    symbols: Option<Vec<String>>,
+    hours: Option<u32>,
+    points: Option<usize>,
+) -> Result<Vec<MarketSeries>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("get_market_series", async move {
+            crate::require_trusted_window(webview.label())?;
+            let configured = app.state::<DesktopConfigState>().snapshot().markets.symbols;
+            let symbols: Vec<MarketSymbol> = match symbols.filter(|s| !s.is_empty()) {
+                Some(symbols) => symbols
+                    .into_iter()
+                    .map(|s| {
+                        configured.iter().find(|c| c.symbol == s).cloned().unwrap_or(MarketSymbol {
+                            symbol: s,
+                            ..Default::default()
+                        })
+                    })
+                    .collect(),
+                None => configured,
+            };
+            let since =
+                chrono::Utc::now().timestamp_millis() - i64::from(hours.unwrap_or(DEFAULT_HOURS)) * 60 * 60 * 1000;
+            let points = points.unwrap_or(DEFAULT_POINTS);
+            with_store(&app, move |store| {
+                let mut all = Vec::new();
+                for symbol in symbols {
+                    if let Some(quote) = store.market_quote(&symbol.symbol)? {
+                        all.push(series(&symbol, &quote, store.market_ticks(&symbol.symbol, since)?, points));
+                    }
+                }
+                Ok(all)
+            })
+            .await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod markets_tests {
+    use super::*;
+
+    #[test]
+    fn parses_provider_quotes() {
+        let quote = parse_yahoo(
+            r#"{"chart":{"result":[{"meta":{"currency":"USD","symbol":"BZ=F","regularMarketPrice":84.1,
+                "regularMarketTime":1778400300,"chartPreviousClose":82.0},
+                "timestamp":[1778399700,1778400000,1778400300],
+                "indicators":{"quote":[{"close":[83.9,null,84.1]}]}}],"error":null}}"#,
+        )
+        .unwrap();
+        assert_eq!(quote.ticks, [(1_778_399_700_000, 83.9), (1_778_400_300_000, 84.1)]);
+        assert_eq!((quote.price, quote.time, quote.currency.as_deref()), (84.1, 1_778_400_300_000, Some("USD")));
+        let (change, percent) = quote.change().unwrap();
+        assert!((change - 2.1).abs() < 1e-9 && (percent - 2.5609756).abs() < 1e-6);
+        let missing = r#"{"chart":{"result":null,"error":{"code":"Not Found","description":"No data found, symbol may be delisted"}}}"#;
+        assert_eq!(parse_yahoo(missing), Err("No data found, symbol may be delisted".to_string()));
+
+        let quote = parse_finnhub(r#"{"c":2401.5,"d":1.5,"dp":0.06,"h":2405,"l":2390,"o":2395,"pc":2400,"t":1778400000}"#).unwrap();
+        assert_eq!((quote.ticks, quote.previous_close), (vec![(1_778_400_000_000, 2401.5)], Some(2400.0)));
+        assert!(parse_finnhub(r#"{"c":0,"d":null,"dp":null,"h":0,"l":0,"o":0,"pc":0,"t":0}"#).is_err());
+        assert_eq!(urlencode("^GSPC"), "%5EGSPC");
+        assert_eq!(urlencode("BZ=F"), "BZ%3DF");
+    }
+
+    #[test]
+    fn shapes_series_for_sparklines() {
+        let ticks: Vec<(i64, f64)> = (0..10).map(|i| (i, i as f64)).collect();
+        assert_eq!(downsample(ticks.clone(), 3), [(2, 2.0), (5, 5.0), (9, 9.0)]);
+        assert_eq!(downsample(ticks.clone(), 20).len(), 10);
+
+        let symbol = MarketSymbol { symbol: "JPY=X".to_string(), kind: MarketKind::Fx, ..Default::default() };
+        let quote = json!({ "price": 150.0, "previousClose": 160.0, "currency": "JPY", "time": 9 });
+        let data = series(&symbol, &quote, ticks, 5);
+        assert_eq!((data.name.as_str(), data.kind), ("JPY=X", MarketKind::Fx));
+        assert_eq!(data.values, [1.0, 3.0, 5.0, 7.0, 9.0]);
+        assert_eq!((data.change, data.change_percent), (Some(-10.0), Some(-6.25)));
+        assert_eq!(serde_json::to_value(&data).unwrap()["kind"], "fx");
+    }
+}
@@ -0,0 +1,166 @@
+//! Per-command invocation metrics: call counts, error counts, and a latency
+//! histogram for every fallible Tauri command, exposed through `get_metrics`
+//! for the diagnostics panel.
+//!
+//! Tauri gives the invoke handler no completion hook (async commands resolve
+//! on their own task), so each command body runs through
+//! `CommandMetrics::observe` / `observe_async` instead:
+//!
+//! ```ignore
+//! metrics::of(&webview).observe("get_secret", || { ... })
+//! metrics::of(&app).observe_async("fetch_polymarket", async move { ... }).await
+//! ```
+//!
+//! Failed calls are also handed to `error_reporting` (a no-op unless the user
+//! has opted in).
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{Manager, Runtime, Webview};
+
+use crate::error_reporting::ErrorSource;
+
+/// Upper bounds (inclusive, in milliseconds) of the latency buckets. Calls
+/// slower than the last bound land in a final overflow bucket.
+const LATENCY_BUCKETS_MS: [u64; 11] = [1, 5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000];
+
+#[derive(Clone, Debug, Default, Serialize, PartialEq, Eq)]
+pub struct CommandStats {
+    calls: u64,
+    errors: u64,
+    total_micros: u64,
+    max_micros: u64,
+    /// One count per `LATENCY_BUCKETS_MS` entry, plus the overflow bucket.
+    latency_buckets: Vec<u64>,
+}
+
+impl CommandStats {
+    fn record(&mut self, elapsed: Duration, failed: bool) {
+        if self.latency_buckets.is_empty() {
+            self.latency_buckets = vec![0; LATENCY_BUCKETS_MS.len() + 1];
+        }
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        self.calls += 1;
+        self.errors += u64::from(failed);
+        self.total_micros = self.total_micros.saturating_add(micros);
+        self.max_micros = self.max_micros.max(micros);
+        let millis = elapsed.as_millis();
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|&bound| millis <= u128::from(bound))
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.latency_buckets[bucket] += 1;
+    }
+}
+
+#[derive(Serialize)]
+pub struct MetricsSnapshot {
+    uptime_secs: u64,
+    latency_bucket_bounds_ms: &'static [u64],
+    commands: BTreeMap<&'static str, CommandStats>,
+}
+
+/// Managed state; cheap to clone so async commands can carry it into their
+/// future.
+#[derive(Clone)]
+pub struct CommandMetrics {
+    started: Instant,
+    commands: Arc<Mutex<BTreeMap<&'static str, CommandStats>>>,
+}
+
+impl Default for CommandMetrics {
+    fn default() -> Self {
+        CommandMetrics {
+            started: Instant::now(),
+            commands: Arc::default(),
+        }
+    }
+}
+
+impl CommandMetrics {
+    fn record(&self, command: &'static str, elapsed: Duration, failed: bool) {
+        self.commands
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(command)
+            .or_default()
+            .record(elapsed, failed);
+    }
+
+    fn finish<T, E: Display>(&self, command: &'static str, started: Instant, result: &Result<T, E>) {
+        self.record(command, started.elapsed(), result.is_err());
+        if let Err(err) = result {
+            crate::error_reporting::report(ErrorSource::Command, err.to_string(), Some(command.to_string()));
+        }
+    }
+
+    pub fn observe<T, E: Display>(&self, command: &'static str, f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+        let started = Instant::now();
+        let result = f();
+        self.finish(command, started, &result);
+        result
+    }
+
+    pub async fn observe_async<T, E: Display>(
+        self,
+        command: &'static str,
+        future: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        let started = Instant::now();
+        let result = future.await;
+        self.finish(command, started, &result);
+        result
+    }
+
+    fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            uptime_secs: self.started.elapsed().as_secs(),
+            latency_bucket_bounds_ms: &LATENCY_BUCKETS_MS,
+            commands: self.commands.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+        }
+    }
+}
+
+/// The app's `CommandMetrics`, from anything that can reach managed state.
+pub fn of<R: Runtime>(manager: &impl Manager<R>) -> CommandMetrics {
+    manager.state::<CommandMetrics>().inner().clone()
+}
+
+#[tauri::command]
+pub fn get_metrics(webview: Webview, metrics: tauri::State<'_, CommandMetrics>) -> Result<MetricsSnapshot, String> {
+    crate::require_trusted_window(webview.label())?;
+    Ok(metrics.snapshot())
+}
+
+#[cfg(test)]
+mod metrics_tests {
+    use super::{CommandMetrics, CommandStats};
+    use std::time::Duration;
+
+    #[test]
+    fn buckets_latency_and_counts_errors() {
+        let mut stats = CommandStats::default();
+        stats.record(Duration::from_micros(400), false);
+        stats.record(Duration::from_millis(30), true);
+        stats.record(Duration::from_secs(9), false);
+        assert_eq!(stats.calls, 3);
+        assert_eq!(stats.errors, 1);
+        assert_eq!(stats.max_micros, 9_000_000);
+        assert_eq!(stats.latency_buckets, vec![1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn observe_records_result() {
+        let metrics = CommandMetrics::default();
+        assert_eq!(metrics.observe("ok", || Ok::<_, String>(1)), Ok(1));
+        assert!(metrics.observe("fails", || Err::<(), _>("nope".to_string())).is_err());
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.commands["ok"].errors, 0);
+        assert_eq!(snapshot.commands["fails"].errors, 1);
+    }
+}
@@ -0,0 +1,48 @@
+//! Pause/resume for the dashboard's data refreshes. The flag lives in the
+//! shell so native surfaces (the dock menu) can flip it; changes are
+//! broadcast as `monitoring://paused` and the dashboard's refresh scheduler
+//! holds its timers while paused. Not persisted: every launch starts live.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+pub const PAUSED_EVENT: &str = "monitoring://paused";
+
+#[derive(Default)]
+pub struct MonitoringState {
+    paused: AtomicBool,
+}
+
+pub fn is_paused(app: &AppHandle) -> bool {
+    app.try_state::<MonitoringState>()
+        .is_some_and(|state| state.paused.load(Ordering::SeqCst))
+}
+
+pub fn set_paused(app: &AppHandle, paused: bool) {
+    let Some(state) = app.try_state::<MonitoringState>() else {
+        return;
+    };
+    if state.paused.swap(paused, Ordering::SeqCst) == paused {
+        return;
+    }
+    tracing::info!(target: "app", paused, "monitoring pause changed");
+    let _ = app.emit(PAUSED_EVENT, paused);
+}
+
+#[tauri::command]
+pub fn get_monitoring_paused(webview: Webview, app: AppHandle) -> Result<bool, String> {
+    crate::metrics::of(&webview).observe("get_monitoring_paused", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(is_paused(&app))
+    })
+}
+
+#[tauri::command]
+pub fn set_monitoring_paused(webview: Webview, app: AppHandle, paused: bool) -> Result<(), String> {
+    crate::metrics::of(&webview).observe("set_monitoring_paused", || {
+        crate::require_trusted_window(webview.label())?;
+        set_paused(&app, paused);
+        Ok(())
+    })
+}
@@ -0,0 +1,372 @@
+//! Alerts, watchlist hits and selected metrics published to an MQTT broker,
+//! for home-automation and ops systems. With `mqtt.enabled` and a
+//! `mqtt.host`, `start` connects (over TLS unless `mqtt.tls` is off) with
+//! `mqtt.username` and the `MQTT_PASSWORD` secret, and publishes JSON:
+//!
+//! - every fired alert, as `alerts://fired` carries it, to `alert_topic`
+//!   with `{rule}` the rule's name;
+//! - watchlist hits to `watchlist_topic` with `{kind}` and `{entry}`, once
+//!   per subject per `HIT_REPEAT`, as positions keep matching;
+//! - `{name, value, time}` to `metric_topic` for each metric value whose
+//!   name is listed in `mqtt.metrics`, retained with `retain_metrics`.
+//!
+//! Values substituted into topics have `/`, `+` and `#` replaced, so they
+//! stay one level. The connection, TLS, keep-alive and QoS 1 session are
+//! rumqttc's. Messages wait in its request queue of `QUEUE` while
+//! disconnected and are dropped (and counted) past that, as are messages
+//! too long to encode; with a `client_id` the broker keeps the session, so
+//! QoS 1 messages not yet acknowledged are sent again after a reconnect.
+//! Reconnects back off from 5 seconds up to 5 minutes.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use rumqttc::{
+    AsyncClient, ConnectReturnCode, ConnectionError, Event, EventLoop, MqttOptions, NetworkOptions, Outgoing, Packet,
+    QoS, TlsConfiguration, Transport,
+};
+use serde::Serialize;
+use serde_json::json;
+use tauri::{AppHandle, Manager, Webview};
+
+use crate::alerts::FiredAlert;
+use crate::config::{DesktopConfigState, MqttConfig};
+use crate::watchlists::WatchlistHit;
+
+const PASSWORD_SECRET: &str = "MQTT_PASSWORD";
+
+const QUEUE: usize = 1000;
+/// Unacknowledged QoS 1 messages before the queue stops draining.
+const MAX_IN_FLIGHT: u16 = 100;
+/// Largest packet sent; bigger messages are dropped rather than sent.
+const MAX_PACKET_BYTES: usize = 1024 * 1024;
+/// MQTT prefixes topics and credentials with a 16-bit length.
+const MAX_STRING_BYTES: usize = u16::MAX as usize;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+const RECONNECT_MIN: Duration = Duration::from_secs(5);
+const RECONNECT_MAX: Duration = Duration::from_secs(5 * 60);
+const HIT_REPEAT: Duration = Duration::from_secs(15 * 60);
+const MAX_RECENT_HITS: usize = 10_000;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Message {
+    topic: String,
+    payload: Vec<u8>,
+    retain: bool,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MqttStatus {
+    pub enabled: bool,
+    pub connected: bool,
+    /// Messages written to the broker since startup.
+    pub published: u64,
+    /// Messages dropped because the queue was full.
+    pub dropped: u64,
+    pub error: Option<String>,
+}
+
+struct Outbox {
+    config: MqttConfig,
+    client: AsyncClient,
+}
+
+#[derive(Default)]
+pub struct MqttState {
+    outbox: Mutex<Option<Outbox>>,
+    /// When each (kind, entry, subject) was last published.
+    recent_hits: Mutex<HashMap<(String, String, String), Instant>>,
+    status: Mutex<MqttStatus>,
+}
+
+fn update_status(app: &AppHandle, f: impl FnOnce(&mut MqttStatus)) {
+    f(&mut app.state::<MqttState>().status.lock().unwrap_or_else(|e| e.into_inner()));
+}
+
+/// Queue the messages `build` makes from the config, if publishing.
+fn enqueue(app: &AppHandle, build: impl FnOnce(&MqttConfig) -> Vec<Message>) {
+    let Some(state) = app.try_state::<MqttState>() else {
+        return;
+    };
+    let mut dropped = 0;
+    {
+        let outbox = state.outbox.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(outbox) = outbox.as_ref() else {
+            return;
+        };
+        let qos = if outbox.config.qos == 0 { QoS::AtMostOnce } else { QoS::AtLeastOnce };
+        for message in build(&outbox.config) {
+            if let Err(err) = check_length(&message) {
+                tracing::warn!(target: "app", "MQTT message dropped: {err}");
+                dropped += 1;
+                continue;
+            }
+            if outbox.client.try_publish(message.topic, qos, message.retain, message.payload).is_err() {
+                dropped += 1;
+            }
+        }
+    }
+    if dropped > 0 {
+        update_status(app, |status| status.dropped += dropped);
+    }
+}
+
+pub fn publish_alert(app: &AppHandle, alert: &FiredAlert) {
+    enqueue(app, |config| {
+        if config.alert_topic.is_empty() {
+            return Vec::new();
+        }
+        vec![Message {
+            topic: render(&config.alert_topic, &[("rule", &alert.rule_name)]),
+            payload: json!(alert).to_string().into_bytes(),
+            retain: false,
+        }]
+    });
+}
+
+pub fn publish_watchlist_hits(app: &AppHandle, hits: &[WatchlistHit]) {
+    let Some(state) = app.try_state::<MqttState>() else {
+        return;
+    };
+    let fresh: Vec<&WatchlistHit> = {
+        let now = Instant::now();
+        let mut recent = state.recent_hits.lock().unwrap_or_else(|e| e.into_inner());
+        if recent.len() >= MAX_RECENT_HITS {
+            recent.retain(|_, at| now.duration_since(*at) < HIT_REPEAT);
+        }
+        hits.iter()
+            .filter(|hit| {
+                let key = (hit.kind.as_str().to_string(), hit.entry.clone(), hit.subject.clone());
+                match recent.get(&key) {
+                    Some(at) if now.duration_since(*at) < HIT_REPEAT => false,
+                    _ => {
+                        recent.insert(key, now);
+                        true
+                    }
+                }
+            })
+            .collect()
+    };
+    if fresh.is_empty() {
+        return;
+    }
+    let time = crate::unix_timestamp_secs();
+    enqueue(app, |config| {
+        if config.watchlist_topic.is_empty() {
+            return Vec::new();
+        }
+        fresh
+            .iter()
+            .map(|hit| Message {
+                topic: render(&config.watchlist_topic, &[("kind", hit.kind.as_str()), ("entry", &hit.entry)]),
+                payload: json!({
+                    "kind": hit.kind.as_str(),
+                    "entry": hit.entry,
+                    "subject": hit.subject,
+                    "description": hit.description,
+                    "time": time,
+                })
+                .to_string()
+                .into_bytes(),
+                retain: false,
+            })
+            .collect()
+    });
+}
+
+pub fn publish_metric(app: &AppHandle, name: &str, value: f64) {
+    enqueue(app, |config| {
+        if config.metric_topic.is_empty() || !metric_selected(&config.metrics, name) {
+            return Vec::new();
+        }
+        vec![Message {
+            topic: render(&config.metric_topic, &[("name", name)]),
+            payload: json!({ "name": name, "value": value, "time": crate::unix_timestamp_secs() }).to_string().into_bytes(),
+            retain: config.retain_metrics,
+        }]
+    });
+}
+
+fn metric_selected(patterns: &[String], name: &str) -> bool {
+    patterns.iter().any(|pattern| match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => pattern == name,
+    })
+}
+
+/// `template` with each `{key}` replaced by its value, made safe to use as
+/// one topic level.
+fn render(template: &str, values: &[(&str, &str)]) -> String {
+    values.iter().fold(template.to_string(), |topic, (key, value)| {
+        let level: String = value
+            .trim()
+            .chars()
+            .map(|c| if matches!(c, '/' | '+' | '#') || c.is_control() { '_' } else { c })
+            .collect();
+        topic.replace(&format!("{{{key}}}"), if level.is_empty() { "_" } else { &level })
+    })
+}
+
+/// Why `message` can't be encoded, if it can't: rumqttc would truncate a
+/// topic past the 16-bit length prefix and refuse an oversized packet.
+fn check_length(message: &Message) -> Result<(), String> {
+    if message.topic.len() > MAX_STRING_BYTES {
+        return Err(format!("topic is {} bytes, the limit is {MAX_STRING_BYTES}", message.topic.len()));
+    }
+    // Fixed header, topic length prefix and packet id.
+    let size = message.topic.len() + message.payload.len() + 9;
+    if size > MAX_PACKET_BYTES {
+        return Err(format!("message is {size} bytes, the limit is {MAX_PACKET_BYTES}"));
+    }
+    Ok(())
+}
+
+fn options(config: &MqttConfig, password: Option<String>) -> Result<MqttOptions, String> {
+    let client_id = config.client_id.trim();
+    let username = config.username.as_deref().unwrap_or_default();
+    let password = password.unwrap_or_default();
+    for (name, value) in [("mqtt.client_id", client_id), ("mqtt.username", username), (PASSWORD_SECRET, &password)] {
+        if value.len() > MAX_STRING_BYTES {
+            return Err(format!("{name} is longer than {MAX_STRING_BYTES} bytes"));
+        }
+    }
+    let mut options = MqttOptions::new(client_id, config.host.trim(), config.port);
+    options
+        .set_keep_alive(Duration::from_secs(u64::from(config.keep_alive_secs.clamp(10, 3600))))
+        // The broker can only keep a session for a named client.
+        .set_clean_session(client_id.is_empty())
+        .set_inflight(MAX_IN_FLIGHT)
+        .set_request_channel_capacity(QUEUE)
+        .set_max_packet_size(MAX_PACKET_BYTES, MAX_PACKET_BYTES);
+    if !username.is_empty() {
+        options.set_credentials(username, password);
+    }
+    if config.tls {
+        options.set_transport(Transport::tls_with_config(TlsConfiguration::Native));
+    }
+    Ok(options)
+}
+
+fn describe(err: &ConnectionError) -> String {
+    match err {
+        ConnectionError::ConnectionRefused(ConnectReturnCode::BadUserNamePassword) => {
+            "bad username or password".to_string()
+        }
+        ConnectionError::ConnectionRefused(ConnectReturnCode::NotAuthorized) => "not authorized".to_string(),
+        ConnectionError::NetworkTimeout => "timed out".to_string(),
+        err => err.to_string(),
+    }
+}
+
+/// Drive the connection until every client handle is gone; rumqttc
+/// reconnects on the next poll after an error.
+async fn run(app: AppHandle, host: String, mut events: EventLoop) {
+    let mut backoff = RECONNECT_MIN;
+    loop {
+        match events.poll().await {
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                tracing::info!(target: "app", %host, "connected to MQTT broker");
+                update_status(&app, |status| {
+                    status.connected = true;
+                    status.error = None;
+                });
+                backoff = RECONNECT_MIN;
+            }
+            Ok(Event::Outgoing(Outgoing::Publish(_))) => update_status(&app, |status| status.published += 1),
+            Ok(_) => {}
+            Err(ConnectionError::RequestsDone) => break,
+            Err(err) => {
+                let reason = describe(&err);
+                tracing::warn!(target: "app", "MQTT connection failed: {reason}");
+                update_status(&app, |status| {
+                    status.connected = false;
+                    status.error = Some(reason);
+                });
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(RECONNECT_MAX);
+            }
+        }
+    }
+    update_status(&app, |status| status.connected = false);
+}
+
+pub fn start(app: &AppHandle) {
+    let config = app.state::<DesktopConfigState>().snapshot().mqtt;
+    if !config.enabled {
+        return;
+    }
+    if config.host.trim().is_empty() || config.qos > 1 {
+        tracing::warn!(target: "app", "MQTT needs a host and a QoS of 0 or 1");
+        update_status(app, |status| status.error = Some("mqtt.host is empty or mqtt.qos is not 0 or 1".to_string()));
+        return;
+    }
+    let password = app.state::<crate::SecretsCache>().get(PASSWORD_SECRET);
+    let options = match options(&config, password) {
+        Ok(options) => options,
+        Err(err) => {
+            tracing::warn!(target: "app", "MQTT not started: {err}");
+            update_status(app, |status| status.error = Some(err));
+            return;
+        }
+    };
+    let (client, mut events) = AsyncClient::new(options, QUEUE);
+    let mut network = NetworkOptions::new();
+    network.set_connection_timeout(CONNECT_TIMEOUT.as_secs());
+    events.set_network_options(network);
+    let host = config.host.trim().to_string();
+    *app.state::<MqttState>().outbox.lock().unwrap_or_else(|e| e.into_inner()) = Some(Outbox { config, client });
+    update_status(app, |status| status.enabled = true);
+    tauri::async_runtime::spawn(run(app.clone(), host, events));
+}
+
+#[tauri::command]
+pub fn get_mqtt_status(webview: Webview, state: tauri::State<'_, MqttState>) -> Result<MqttStatus, String> {
+    crate::metrics::of(&webview).observe("get_mqtt_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.status.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+#[cfg(test)]
+mod mqtt_tests {
+    use super::*;
+
+    #[test]
+    fn renders_topics_one_level_per_value() {
+        assert_eq!(render("wm/alerts/{rule}", &[("rule", "Ships in Hormuz")]), "wm/alerts/Ships in Hormuz");
+        assert_eq!(
+            render("wm/{kind}/{entry}", &[("kind", "region"), ("entry", "a/b+#")]),
+            "wm/region/a_b__"
+        );
+        assert_eq!(render("wm/metrics/{name}", &[("name", " ")]), "wm/metrics/_");
+        assert!(metric_selected(&["swpc.*".to_string(), "alerts".to_string()], "swpc.kp"));
+        assert!(metric_selected(&["alerts".to_string()], "alerts"));
+        assert!(!metric_selected(&["alerts".to_string()], "alerts.critical"));
+    }
+
+    #[test]
+    fn rejects_what_cannot_be_encoded() {
+        let message = |topic: String, payload: Vec<u8>| Message {
+            topic,
+            payload,
+            retain: false,
+        };
+        assert!(check_length(&message("a/b".to_string(), b"{}".to_vec())).is_ok());
+        assert!(check_length(&message("t".repeat(MAX_STRING_BYTES + 1), Vec::new())).is_err());
+        assert!(check_length(&message("a/b".to_string(), vec![0; MAX_PACKET_BYTES])).is_err());
+
+        let config = MqttConfig {
+            host: "broker.example".to_string(),
+            ..MqttConfig::default()
+        };
+        assert!(options(&config, Some("secret".to_string())).is_ok());
+        assert!(options(&config, Some("p".repeat(MAX_STRING_BYTES + 1))).is_err());
+        let long_user = MqttConfig {
+            username: Some("u".repeat(MAX_STRING_BYTES + 1)),
+            ..config.clone()
+        };
+        assert!(options(&long_user, None).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+//! Connectivity monitoring. A background thread watches the machine's
+//! network interfaces and probes reachability, on every interface change
+//! and periodically in between, then broadcasts `network://online` or
+//! `network://offline` on a transition. The dashboard holds its refreshes of
+//! the local API while offline and shows a banner instead of letting every
+//! panel fail on its own.
+//!
+//! The probe is a TCP connect to public anycast resolvers, falling back to a
+//! DNS lookup so networks that block direct egress (proxied corporate
+//! networks) still count as online. Going offline takes two failed probes in
+//! a row, unless no usable interface is left; one success is enough to come
+//! back.
+
+use std::collections::BTreeSet;
+use std::net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use sysinfo::Networks;
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+pub const ONLINE_EVENT: &str = "network://online";
+pub const OFFLINE_EVENT: &str = "network://offline";
+
+/// How often the interface list is compared; cheap, so a cable pull or Wi-Fi
+/// drop is noticed within seconds.
+const INTERFACE_POLL: Duration = Duration::from_secs(3);
+const PROBE_INTERVAL_ONLINE: Duration = Duration::from_secs(30);
+const PROBE_INTERVAL_OFFLINE: Duration = Duration::from_secs(10);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+const OFFLINE_AFTER_FAILURES: u32 = 2;
+
+const PROBE_ADDRS: &[&str] = &["1.1.1.1:443", "8.8.8.8:443", "[2606:4700:4700::1111]:443"];
+const PROBE_HOST: &str = "dns.google:443";
+
+pub struct NetworkState {
+    online: AtomicBool,
+}
+
+impl Default for NetworkState {
+    fn default() -> Self {
+        // Assume online until a probe says otherwise so startup fetches run.
+        NetworkState {
+            online: AtomicBool::new(true),
+        }
+    }
+}
+
+pub fn is_online(app: &AppHandle) -> bool {
+    app.try_state::<NetworkState>()
+        .is_none_or(|state| state.online.load(Ordering::SeqCst))
+}
+
+fn set_online(app: &AppHandle, online: bool) {
+    let Some(state) = app.try_state::<NetworkState>() else {
+        return;
+    };
+    if state.online.swap(online, Ordering::SeqCst) == online {
+        return;
+    }
+    tracing::info!(target: "app", online, "network connectivity changed");
+    let _ = app.emit(if online { ONLINE_EVENT } else { OFFLINE_EVENT }, ());
+}
+
+/// Debounces probe results into an online/offline state.
+#[derive(Debug)]
+struct Tracker {
+    online: bool,
+    failures: u32,
+}
+
+impl Tracker {
+    /// Record a probe and return the new state if it changed. `no_interfaces`
+    /// skips the debounce: there is nothing left to reach the network with.
+    fn record(&mut self, reachable: bool, no_interfaces: bool) -> Option<bool> {
+        let online = if reachable {
+            self.failures = 0;
+            true
+        } else {
+            self.failures += 1;
+            self.online && !no_interfaces && self.failures < OFFLINE_AFTER_FAILURES
+        };
+        (online != self.online).then(|| {
+            self.online = online;
+            online
+        })
+    }
+}
+
+/// Whether an address can carry traffic beyond this machine or its link.
+fn is_routable(addr: &IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(v4) => !v4.is_loopback() && !v4.is_link_local() && !v4.is_unspecified(),
+        IpAddr::V6(v6) => !v6.is_loopback() && !v6.is_unspecified() && (v6.segments()[0] & 0xffc0) != 0xfe80,
+    }
+}
+
+/// Interfaces with a routable address, as `name/addr`; compared between
+/// polls to spot network changes.
+fn interface_fingerprint(networks: &Networks) -> BTreeSet<String> {
+    networks
+        .iter()
+        .flat_map(|(name, data)| {
+            data.ip_networks()
+                .iter()
+                .filter(|net| is_routable(&net.addr))
+                .map(move |net| format!("{name}/{}", net.addr))
+        })
+        .collect()
+}
+
+fn probe() -> bool {
+    let connected = PROBE_ADDRS
+        .iter()
+        .filter_map(|addr| addr.parse::<SocketAddr>().ok())
+        .any(|addr| TcpStream::connect_timeout(&addr, PROBE_TIMEOUT).is_ok());
+    connected || PROBE_HOST.to_socket_addrs().is_ok_and(|mut addrs| addrs.next().is_some())
+}
+
+fn run(app: AppHandle) {
+    let mut networks = Networks::new();
+    let mut tracker = Tracker {
+        online: true,
+        failures: 0,
+    };
+    let mut fingerprint = None;
+    let mut last_probe: Option<Instant> = None;
+    loop {
+        networks.refresh(true);
+        let current = interface_fingerprint(&networks);
+        let changed = fingerprint.as_ref() != Some(&current);
+        let interval = if tracker.online {
+            PROBE_INTERVAL_ONLINE
+        } else {
+            PROBE_INTERVAL_OFFLINE
+        };
+        if changed || last_probe.is_none_or(|at| at.elapsed() >= interval) {
+            if changed && fingerprint.is_some() {
+                tracing::debug!(target: "app", interfaces = current.len(), "network interfaces changed");
+            }
+            let reachable = !current.is_empty() && probe();
+            if let Some(online) = tracker.record(reachable, current.is_empty()) {
+                set_online(&app, online);
+            }
+            last_probe = Some(Instant::now());
+        }
+        fingerprint = Some(current);
+        std::thread::sleep(INTERFACE_POLL);
+    }
+}
+
+/// Start the connectivity monitor thread.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    if let Err(e) = std::thread::Builder::new()
+        .name("network-monitor".into())
+        .spawn(move || run(app))
+    {
+        tracing::warn!(target: "app", "failed to start network monitor: {e}");
+    }
+}
+
+#[tauri::command]
+pub fn get_network_online(webview: Webview, app: AppHandle) -> Result<bool, String> {
+    crate::metrics::of(&webview).observe("get_network_online", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(is_online(&app))
+    })
+}
+
+#[cfg(test)]
+mod network_tests {
+    use super::*;
+
+    fn online() -> Tracker {
+        Tracker {
+            online: true,
+            failures: 0,
+        }
+    }
+
+    #[test]
+    fn goes_offline_after_consecutive_failures() {
+        let mut tracker = online();
+        assert_eq!(tracker.record(false, false), None);
+        assert_eq!(tracker.record(false, false), Some(false));
+        assert_eq!(tracker.record(false, false), None);
+        assert_eq!(tracker.record(true, false), Some(true));
+    }
+
+    #[test]
+    fn success_resets_the_failure_count() {
+        let mut tracker = online();
+        assert_eq!(tracker.record(false, false), None);
+        assert_eq!(tracker.record(true, false), None);
+        assert_eq!(tracker.record(false, false), None);
+    }
+
+    #[test]
+    fn losing_every_interface_is_offline_at_once() {
+        let mut tracker = online();
+        assert_eq!(tracker.record(false, true), Some(false));
+    }
+
+    #[test]
+    fn loopback_and_link_local_are_not_routable() {
+        for addr in ["127.0.0.1", "169.254.10.1", "::1", "fe80::1", "0.0.0.0"] {
+            assert!(!is_routable(&addr.parse().unwrap()), "{addr}");
+        }
+        for addr in ["192.168.1.20", "10.0.0.5", "2001:db8::1"] {
+            assert!(is_routable(&addr.parse().unwrap()), "{addr}");
+        }
+    }
+}
@@ -0,0 +1,472 @@
+//! RSS/Atom news feeds fetched by the app instead of the dashboard. The
+//! dashboard keeps its feed list and registers each feed with
+//! `fetch_news_feed` the first time it shows it; from then on the scheduler
+//! here refreshes every registered feed each `REFRESH_INTERVAL` (on the
+//! `poll` schedule, so held and stretched like the other pollers), up to
+//! `MAX_CONCURRENT` at a time, with conditional requests (ETag and
+//! Last-Modified) so unchanged feeds cost a 304.
+//!
+//! RSS 2.0, RSS 1.0 (RDF) and Atom are parsed here into headlines, which go
+//! into the local `store` keyed by link, so a story carried by several
+//! feeds is stored once. Headlines new to the store are emitted in one
+//! `news://items` batch per refresh round, and `get_news_items` reads them
+//! back across feeds. Like the dashboard's own fetcher, a feed that fails
+//! twice in a row sits out `FAILURE_COOLDOWN`.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+use crate::alerts::Headline;
+use crate::store::{EventStore, NewsQuery, NewsRow};
+
+pub const ITEMS_EVENT: &str = "news://items";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// Shortest wait between scheduler rounds, and the wait while no feeds are
+/// registered.
+const SCHEDULER_TICK: Duration = Duration::from_secs(60);
+const FAILURE_COOLDOWN: Duration = Duration::from_secs(5 * 60);
+const MAX_FAILURES: u32 = 2;
+const MAX_CONCURRENT: usize = 6;
+const MAX_FEED_BYTES: usize = 5 * 1024 * 1024;
+const MAX_ITEMS_PER_FEED: usize = 50;
+const DEFAULT_FEED_ITEMS: usize = 5;
+const RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+const MRSS_NS: &str = "http://search.yahoo.com/mrss/";
+
+/// A feed as the dashboard configures it.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct NewsFeed {
+    pub name: String,
+    pub url: String,
+    pub lang: Option<String>,
+}
+
+/// A parsed headline, shaped like the dashboard's `NewsItem` before
+/// classification.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewsItem {
+    pub source: String,
+    pub title: String,
+    pub link: String,
+    /// RFC 3339.
+    pub pub_date: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+}
+
+struct FeedState {
+    feed: NewsFeed,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fetched_at: Option<Instant>,
+    in_flight: bool,
+    failures: u32,
+    cooldown_until: Option<Instant>,
+}
+
+impl FeedState {
+    fn is_due(&self, now: Instant) -> bool {
+        !self.in_flight
+            && self.cooldown_until.is_none_or(|until| now >= until)
+            && self.fetched_at.is_none_or(|at| now.duration_since(at) >= REFRESH_INTERVAL)
+    }
+
+    /// How long until `is_due`, ignoring a fetch in flight.
+    fn due_in(&self, now: Instant) -> Duration {
+        let cooldown = self.cooldown_until.map_or(Duration::ZERO, |until| until.saturating_duration_since(now));
+        let refresh = self.fetched_at.map_or(Duration::ZERO, |at| (at + REFRESH_INTERVAL).saturating_duration_since(now));
+        cooldown.max(refresh)
+    }
+}
+
+#[derive(Default)]
+pub struct NewsState {
+    /// By feed URL.
+    feeds: Mutex<HashMap<String, FeedState>>,
+    client: OnceLock<reqwest::Client>,
+}
+
+impl NewsState {
+    fn client(&self) -> Result<&reqwest::Client, String> {
+        if let Some(client) = self.client.get() {
+            return Ok(client);
+        }
+        let client = reqwest::Client::builder()
+            .use_native_tls()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(crate::USER_AGENT)
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+        Ok(self.client.get_or_init(|| client))
+    }
+}
+
+fn local_name<'a>(node: &roxmltree::Node<'a, '_>) -> &'a str {
+    node.tag_name().name()
+}
+
+fn child<'a, 'input>(node: &roxmltree::Node<'a, 'input>, name: &str) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|n| n.is_element() && local_name(n) == name && n.tag_name().namespace() != Some(MRSS_NS))
+}
+
+/// All text under `node`, CDATA included, trimmed.
+fn text(node: roxmltree::Node) -> String {
+    let text: String = node.descendants().filter_map(|n| n.is_text().then(|| n.text()).flatten()).collect();
+    text.trim().to_string()
+}
+
+fn child_text(node: &roxmltree::Node, name: &str) -> Option<String> {
+    child(node, name).map(text).filter(|t| !t.is_empty())
+}
+
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+    let date = date.trim();
+    DateTime::parse_from_rfc2822(date)
+        .or_else(|_| DateTime::parse_from_rfc3339(date))
+        .ok()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+/// The first image among `media:content`, `media:thumbnail` and image
+/// enclosures.
+fn image_url(item: &roxmltree::Node) -> Option<String> {
+    let media = |name: &str| {
+        item.children()
+            .filter(|n| n.is_element() && n.tag_name().namespace() == Some(MRSS_NS) && local_name(n) == name)
+            .collect::<Vec<_>>()
+    };
+    let content = media("content").into_iter().find(|n| {
+        let (medium, kind) = (n.attribute("medium"), n.attribute("type"));
+        medium == Some("image") || kind.is_some_and(|t| t.starts_with("image/")) || (medium.is_none() && kind.is_none())
+    });
+    let thumbnail = || media("thumbnail").into_iter().next();
+    let enclosure = || {
+        item.children().find(|n| {
+            n.is_element() && local_name(n) == "enclosure" && n.attribute("type").is_some_and(|t| t.starts_with("image/"))
+        })
+    };
+    content.or_else(thumbnail).or_else(enclosure)?.attribute("url").map(str::to_string)
+}
+
+/// Headlines from an RSS or Atom document, in document order. Items
+/// without a title or link are skipped; items without a readable date get
+/// `now`.
+fn parse_feed(xml: &str, feed: &NewsFeed, now: DateTime<Utc>) -> Result<Vec<NewsItem>, String> {
+    let options = roxmltree::ParsingOptions { allow_dtd: true, ..Default::default() };
+    let document = roxmltree::Document::parse_with_options(xml, options).map_err(|e| format!("invalid feed: {e}"))?;
+    let root = document.root_element();
+    let (container, item_name, atom) = match local_name(&root) {
+        "rss" => (child(&root, "channel").ok_or("RSS feed without a channel")?, "item", false),
+        "RDF" => (root, "item", false),
+        "feed" => (root, "entry", true),
+        other => return Err(format!("not an RSS or Atom feed: <{other}>")),
+    };
+    let items = container
+        .children()
+        .filter(|n| n.is_element() && local_name(n) == item_name)
+        .filter_map(|item| {
+            let title = child_text(&item, "title")?;
+            let link = if atom {
+                item.children()
+                    .filter(|n| n.is_element() && local_name(n) == "link")
+                    .find(|n| n.attribute("rel").is_none_or(|rel| rel == "alternate"))?
+                    .attribute("href")?
+                    .trim()
+                    .to_string()
+            } else {
+                child_text(&item, "link").or_else(|| child_text(&item, "guid"))?
+            };
+            let date = ["pubDate", "published", "updated", "date"]
+                .iter()
+                .find_map(|name| child_text(&item, name).as_deref().and_then(parse_date))
+                .unwrap_or(now);
+            Some(NewsItem {
+                source: feed.name.clone(),
+                title,
+                link,
+                pub_date: date.to_rfc3339(),
+                image_url: image_url(&item),
+                lang: feed.lang.clone(),
+            })
+        })
+        .take(MAX_ITEMS_PER_FEED)
+        .collect();
+    Ok(items)
+}
+
+fn is_http_url(url: &str) -> bool {
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+fn feed_state<T>(app: &AppHandle, url: &str, f: impl FnOnce(&mut FeedState) -> T) -> Option<T> {
+    let state = app.state::<NewsState>();
+    let mut feeds = state.feeds.lock().unwrap_or_else(|e| e.into_inner());
+    feeds.get_mut(url).map(f)
+}
+
+/// Store parsed items, returning the ones that were new.
+async fn store(app: &AppHandle, items: Vec<NewsItem>) -> Result<Vec<Value>, String> {
+    let app = app.clone();
+    crate::runtime::blocking(move || {
+        let rows: Vec<NewsRow> = items
+            .iter()
+            .map(|item| NewsRow {
+                link: item.link.clone(),
+                source: item.source.clone(),
+                published: DateTime::parse_from_rfc3339(&item.pub_date).map(|d| d.timestamp_millis()).unwrap_or(0),
+                data: json!(item),
+            })
+            .collect();
+        let added = app.state::<EventStore>().insert_news(&rows)?;
+        let headlines: Vec<Headline> = added
+            .iter()
+            .map(|&i| Headline {
+                source: &items[i].source,
+                title: &items[i].title,
+                link: &items[i].link,
+            })
+            .collect();
+        crate::alerts::observe_headlines(&app, &headlines);
+        crate::watchlists::observe_headlines(&app, &headlines);
+        Ok(added.into_iter().map(|i| rows[i].data.clone()).collect())
+    })
+    .await
+}
+
+/// One conditional fetch of the feed at `url`. Returns the new items.
+async fn fetch(app: &AppHandle, url: &str) -> Result<Vec<Value>, String> {
+    let Some((feed, etag, last_modified)) = feed_state(app, url, |state| {
+        state.in_flight = true;
+        (state.feed.clone(), state.etag.clone(), state.last_modified.clone())
+    }) else {
+        return Ok(Vec::new());
+    };
+    let result = async {
+        let client = app.state::<NewsState>().client()?.clone();
+        let mut request = client.get(url).header("Accept", "application/rss+xml, application/atom+xml, application/xml, text/xml");
+        if let Some(etag) = &etag {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &last_modified {
+            request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+        }
+        let response = request.send().await.map_err(|e| format!("request failed: {e}"))?;
+        let header = |name| response.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+        let validators = (header(reqwest::header::ETAG), header(reqwest::header::LAST_MODIFIED));
+        match response.status() {
+            reqwest::StatusCode::NOT_MODIFIED => return Ok((Vec::new(), validators)),
+            status if !status.is_success() => return Err(format!("HTTP {status}")),
+            _ => {}
+        }
+        let bytes = response.bytes().await.map_err(|e| format!("download failed: {e}"))?;
+        if bytes.len() > MAX_FEED_BYTES {
+            return Err(format!("feed larger than {MAX_FEED_BYTES} bytes"));
+        }
+        let items = parse_feed(&String::from_utf8_lossy(&bytes), &feed, Utc::now())?;
+        Ok((store(app, items).await?, validators))
+    }
+    .await;
+
+    let now = Instant::now();
+    feed_state(app, url, |state| {
+        state.in_flight = false;
+        state.fetched_at = Some(now);
+        match &result {
+            Ok((_, (etag, last_modified))) => {
+                state.failures = 0;
+                state.cooldown_until = None;
+                if etag.is_some() || last_modified.is_some() {
+                    state.etag = etag.clone();
+                    state.last_modified = last_modified.clone();
+                }
+            }
+            Err(err) => {
+                state.failures += 1;
+                if state.failures >= MAX_FAILURES {
+                    state.cooldown_until = Some(now + FAILURE_COOLDOWN);
+                    tracing::warn!(target: "app", feed = %state.feed.name, failures = state.failures, "news feed on cooldown: {err}");
+                }
+            }
+        }
+    });
+    result.map(|(added, _)| added)
+}
+
+/// Refresh every due feed.
+async fn refresh(app: &AppHandle) {
+    let due: Vec<String> = {
+        let state = app.state::<NewsState>();
+        let feeds = state.feeds.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        feeds.iter().filter(|(_, feed)| feed.is_due(now)).map(|(url, _)| url.clone()).collect()
+    };
+    if due.is_empty() {
+        return;
+    }
+    let added: Vec<Value> = futures_util::stream::iter(due)
+        .map(|url| async move { fetch(app, &url).await.unwrap_or_default() })
+        .buffer_unordered(MAX_CONCURRENT)
+        .flat_map(futures_util::stream::iter)
+        .collect()
+        .await;
+    if !added.is_empty() {
+        tracing::debug!(target: "app", added = added.len(), "news feeds refreshed");
+        let _ = app.emit(ITEMS_EVENT, added);
+    }
+    let cutoff = Utc::now().timestamp_millis() - RETENTION.as_millis() as i64;
+    if let Err(err) = app.state::<EventStore>().prune_news(cutoff) {
+        tracing::warn!(target: "app", "failed to prune news: {err}");
+    }
+}
+
+struct Poller {
+    app: AppHandle,
+}
+
+impl crate::poll::Poller for Poller {
+    /// Refresh the due feeds, then wait until the next one is due.
+    async fn round(&mut self) -> Duration {
+        refresh(&self.app).await;
+        let state = self.app.state::<NewsState>();
+        let feeds = state.feeds.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        feeds.values().map(|feed| feed.due_in(now)).min().unwrap_or_default().max(SCHEDULER_TICK)
+    }
+}
+
+/// Start the refresh scheduler. It idles until the dashboard registers feeds.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move { crate::poll::run(&app, Poller { app: app.clone() }).await });
+}
+
+/// Register `feed` for background refreshes, fetch it now unless it was
+/// fetched recently, and return its latest `limit` items (5 by default).
+#[tauri::command]
+pub async fn fetch_news_feed(
+    webview: Webview,
+    app: AppHandle,
+    feed: NewsFeed,
+    limit: Option<usize>,
+) -> Result<Vec<Value>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("fetch_news_feed", async move {
+            crate::require_trusted_window(webview.label())?;
+            if !is_http_url(&feed.url) {
+                return Err("Feed URL must be http or https".to_string());
+            }
+            let url = feed.url.clone();
+            let due = {
+                let state = app.state::<NewsState>();
+                let mut feeds = state.feeds.lock().unwrap_or_else(|e| e.into_inner());
+                let entry = feeds.entry(url.clone()).or_insert_with(|| FeedState {
+                    feed: feed.clone(),
+                    etag: None,
+                    last_modified: None,
+                    fetched_at: None,
+                    in_flight: false,
+                    failures: 0,
+                    cooldown_until: None,
+                });
+                entry.feed = feed.clone();
+                entry.is_due(Instant::now())
+            };
+            if due {
+                let added = fetch(&app, &url).await?;
+                if !added.is_empty() {
+                    let _ = app.emit(ITEMS_EVENT, added);
+                }
+            }
+            let query = NewsQuery {
+                sources: vec![feed.name],
+                since: None,
+                limit: Some(limit.unwrap_or(DEFAULT_FEED_ITEMS)),
+            };
+            crate::runtime::blocking(move || app.state::<EventStore>().news(&query)).await
+        })
+        .await
+}
+
+/// Stored headlines across feeds, newest first.
+#[tauri::command]
+pub async fn get_news_items(webview: Webview, app: AppHandle, query: Option<NewsQuery>) -> Result<Vec<Value>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("get_news_items", async move {
+            crate::require_trusted_window(webview.label())?;
+            let query = query.unwrap_or_default();
+            crate::runtime::blocking(move || app.state::<EventStore>().news(&query)).await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod news_tests {
+    use super::*;
+
+    fn feed() -> NewsFeed {
+        NewsFeed {
+            name: "Example".to_string(),
+            url: "https://example.com/feed".to_string(),
+            lang: Some("en".to_string()),
+        }
+    }
+
+    #[test]
+    fn parses_rss() {
+        let xml = r#"<?xml version="1.0"?>
+            <rss version="2.0" xmlns:media="http://search.yahoo.com/mrss/">
+              <channel><title>Example</title>
+                <item>
+                  <title><![CDATA[Ceasefire talks resume]]></title>
+                  <link>https://example.com/a</link>
+                  <pubDate>Sat, 14 Mar 2026 12:30:00 GMT</pubDate>
+                  <media:thumbnail url="https://example.com/a.jpg"/>
+                </item>
+                <item><title>No date</title><guid>https://example.com/b</guid></item>
+                <item><link>https://example.com/untitled</link></item>
+              </channel>
+            </rss>"#;
+        let now = parse_date("2026-03-15T00:00:00Z").unwrap();
+        let items = parse_feed(xml, &feed(), now).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].title, "Ceasefire talks resume");
+        assert_eq!(items[0].link, "https://example.com/a");
+        assert_eq!(items[0].pub_date, "2026-03-14T12:30:00+00:00");
+        assert_eq!(items[0].image_url.as_deref(), Some("https://example.com/a.jpg"));
+        assert_eq!(items[1].link, "https://example.com/b");
+        assert_eq!(items[1].pub_date, now.to_rfc3339());
+    }
+
+    #[test]
+    fn parses_atom() {
+        let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+              <entry>
+                <title type="html">Quake hits coast</title>
+                <link rel="self" href="https://example.com/self"/>
+                <link href="https://example.com/quake"/>
+                <updated>2026-03-14T08:00:00+01:00</updated>
+              </entry>
+            </feed>"#;
+        let items = parse_feed(xml, &feed(), Utc::now()).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].link, "https://example.com/quake");
+        assert_eq!(items[0].pub_date, "2026-03-14T07:00:00+00:00");
+        assert_eq!(items[0].lang.as_deref(), Some("en"));
+
+        assert!(parse_feed("<html></html>", &feed(), Utc::now()).is_err());
+        assert!(parse_feed("not xml", &feed(), Utc::now()).is_err());
+    }
+}
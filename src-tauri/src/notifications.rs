@@ -0,0 +1,149 @@
+//! Native desktop notifications with click actions, the delivery channel for
+//! dashboard alerts. `send_notification` shows a notification on behalf of
+//! the calling window and tags it with an action id; when the user clicks
+//! it, that window is brought up (or reopened, for settings; the dashboard
+//! otherwise) and receives the action id as `notification://action`.
+//!
+//! Urgency maps onto what the notification plugin offers on every platform:
+//! low is silent, normal plays the default sound, and critical also flashes
+//! the taskbar button or bounces the dock icon. While the OS is in Do Not
+//! Disturb (see `focus_mode`), notifications are held back; critical ones
+//! still go out if the user allows it.
+
+use std::sync::atomic::{AtomicI32, Ordering};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+use tauri_plugin_notification::{ActionPerformed, NotificationExt};
+
+pub const ACTION_EVENT: &str = "notification://action";
+
+/// Keys in the notification's `extra` data, read back when it is clicked.
+const EXTRA_ACTION: &str = "worldmonitor.action";
+const EXTRA_WINDOW: &str = "worldmonitor.window";
+
+#[cfg(any(windows, target_os = "macos"))]
+const DEFAULT_SOUND: &str = "Default";
+#[cfg(not(any(windows, target_os = "macos")))]
+const DEFAULT_SOUND: &str = "message-new-instant";
+
+static NEXT_ID: AtomicI32 = AtomicI32::new(1);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationUrgency {
+    Low,
+    #[default]
+    Normal,
+    Critical,
+}
+
+/// Payload of `notification://action`.
+#[derive(Clone, Debug, Serialize)]
+pub struct NotificationAction {
+    pub id: i32,
+    pub action: String,
+}
+
+/// Route notification clicks. Call from `setup`: notifications shown before
+/// a handler exists are not tracked.
+pub fn init(app: &AppHandle) {
+    let handle = app.clone();
+    if let Err(err) = app.notification().on_action(move |performed| on_action(&handle, performed)) {
+        tracing::warn!(target: "app", "notification clicks unavailable: {err}");
+    }
+}
+
+fn on_action(app: &AppHandle, performed: &ActionPerformed) {
+    let Some(notification) = performed.notification() else {
+        return;
+    };
+    let extra = notification.extra();
+    let Some(action) = extra.get(EXTRA_ACTION).and_then(|value| value.as_str()) else {
+        return;
+    };
+    let label = extra.get(EXTRA_WINDOW).and_then(|value| value.as_str()).unwrap_or("main");
+    let label = focus_window(app, label);
+    tracing::debug!(target: "app", id = notification.id(), action, window = label, "notification clicked");
+    let payload = NotificationAction {
+        id: notification.id(),
+        action: action.to_string(),
+    };
+    if let Err(err) = app.emit_to(label, ACTION_EVENT, payload) {
+        tracing::warn!(target: "app", "failed to emit {ACTION_EVENT}: {err}");
+    }
+}
+
+/// Bring up the window a notification came from and return its label;
+/// windows that have since closed fall back to the dashboard.
+fn focus_window<'a>(app: &AppHandle, label: &'a str) -> &'a str {
+    match label {
+        "main" => {}
+        "settings" => match crate::open_settings_window(app, None) {
+            Ok(()) => return label,
+            Err(err) => tracing::warn!(target: "app", "failed to reopen settings: {err}"),
+        },
+        _ => {
+            if let Some(window) = app.get_webview_window(label) {
+                let _ = window.show();
+                let _ = window.unminimize();
+                let _ = window.set_focus();
+                return label;
+            }
+        }
+    }
+    crate::tray::show_dashboard(app);
+    "main"
+}
+
+/// Show a notification and return its id, which comes back with the
+/// `notification://action` event when `action` is set and the user clicks it.
+/// Returns `None` when Do Not Disturb held the notification back.
+pub fn send(
+    app: &AppHandle,
+    window: &str,
+    title: &str,
+    body: &str,
+    urgency: NotificationUrgency,
+    action: Option<&str>,
+) -> Result<Option<i32>, String> {
+    if !crate::focus_mode::allows(app, urgency == NotificationUrgency::Critical) {
+        tracing::debug!(target: "app", title, "notification held back by focus mode");
+        return Ok(None);
+    }
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let mut builder = app.notification().builder().id(id).title(title).body(body);
+    if urgency != NotificationUrgency::Low {
+        builder = builder.sound(DEFAULT_SOUND);
+    }
+    if let Some(action) = action {
+        builder = builder.extra(EXTRA_ACTION, action).extra(EXTRA_WINDOW, window);
+    }
+    builder.show().map_err(|e| format!("Failed to show notification: {e}"))?;
+    if urgency == NotificationUrgency::Critical {
+        crate::attention::request(app, true)?;
+    }
+    Ok(Some(id))
+}
+
+#[tauri::command]
+pub fn send_notification(
+    webview: Webview,
+    app: AppHandle,
+    title: String,
+    body: String,
+    urgency: Option<NotificationUrgency>,
+    action: Option<String>,
+) -> Result<Option<i32>, String> {
+    crate::metrics::of(&webview).observe("send_notification", || {
+        crate::require_trusted_window(webview.label())?;
+        send(
+            &app,
+            webview.window().label(),
+            &title,
+            &body,
+            urgency.unwrap_or_default(),
+            action.as_deref(),
+        )
+    })
+}
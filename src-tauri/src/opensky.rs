@@ -0,0 +1,562 @@
+//! OpenSky state vectors polled natively. `start_opensky_poller` fetches
+//! `/states/all` for a bounding box (the whole world by default) and keeps
+//! the last snapshot here; the dashboard reads it once with
+//! `get_opensky_snapshot` and then follows `opensky://diff` events, which
+//! carry only the aircraft that appeared, moved or disappeared.
+//!
+//! Requests are authenticated with an OAuth token from
+//! `OPENSKY_CLIENT_ID` / `OPENSKY_CLIENT_SECRET` when both are stored
+//! (anonymous otherwise), refreshed shortly before it expires. OpenSky
+//! charges credits per request by the area queried and grants a daily
+//! allowance, so the poll interval is recomputed after every response to
+//! spread the remaining credits (from `X-Rate-Limit-Remaining`) over what is
+//! left of the UTC day. A 429 waits for as long as OpenSky says.
+//!
+//! Aircraft from a local receiver (see `adsb`) are merged into the same
+//! snapshot and diffs, taking precedence over OpenSky's report of the same
+//! aircraft.
+//!
+//! While the built-in `relay` is serving, the poller is kept running for the
+//! whole world, as with the AIS stream.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::Timelike;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, Webview};
+use tokio::sync::watch;
+
+use crate::ais::BoundingBox;
+use crate::alerts::{Sighting, Target};
+use crate::store::{EventKind, Observation};
+
+pub const DIFF_EVENT: &str = "opensky://diff";
+
+const STATES_URL: &str = "https://opensky-network.org/api/states/all";
+const TOKEN_URL: &str = "https://auth.opensky-network.org/auth/realms/opensky-network/protocol/openid-connect/token";
+const CLIENT_ID_SECRET: &str = "OPENSKY_CLIENT_ID";
+const CLIENT_SECRET_SECRET: &str = "OPENSKY_CLIENT_SECRET";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+/// Refresh tokens this long before OpenSky says they expire.
+const TOKEN_MARGIN: Duration = Duration::from_secs(60);
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+/// Daily credits for anonymous and authenticated users.
+const ANONYMOUS_CREDITS: u32 = 400;
+const AUTHENTICATED_CREDITS: u32 = 4000;
+/// OpenSky's time resolution for anonymous and authenticated users.
+const ANONYMOUS_MIN_INTERVAL: Duration = Duration::from_secs(10);
+const AUTHENTICATED_MIN_INTERVAL: Duration = Duration::from_secs(5);
+const ERROR_RETRY: Duration = Duration::from_secs(60);
+
+/// `time` plus `states` rows, shaped like OpenSky's own response.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Snapshot {
+    pub time: u64,
+    pub states: Vec<Value>,
+}
+
+/// Payload of `opensky://diff`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct StateDiff {
+    pub time: u64,
+    /// New or changed state vectors, whole.
+    pub updated: Vec<Value>,
+    /// `icao24` of aircraft no longer reported.
+    pub removed: Vec<String>,
+}
+
+impl StateDiff {
+    fn is_empty(&self) -> bool {
+        self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct OpenSkyStatus {
+    pub running: bool,
+    pub authenticated: bool,
+    /// Credits left today, as of the last response.
+    pub remaining_credits: Option<u32>,
+    /// Credits each poll costs for the current bounding box.
+    pub credits_per_poll: u32,
+    pub interval_secs: u64,
+    pub aircraft: usize,
+    pub error: Option<String>,
+}
+
+struct Token {
+    access_token: String,
+    expires_at: Instant,
+}
+
+/// OAuth client-credentials tokens, fetched on demand and reused until
+/// shortly before they expire. The lock also keeps fetches from
+/// overlapping.
+#[derive(Default)]
+struct TokenManager(tokio::sync::Mutex<Option<Token>>);
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default = "default_expires_in")]
+    expires_in: u64,
+}
+
+fn default_expires_in() -> u64 {
+    1800
+}
+
+impl TokenManager {
+    async fn get(&self, client: &reqwest::Client, client_id: &str, client_secret: &str) -> Result<String, String> {
+        let mut token = self.0.lock().await;
+        if let Some(token) = token.as_ref().filter(|t| Instant::now() + TOKEN_MARGIN < t.expires_at) {
+            return Ok(token.access_token.clone());
+        }
+        let response = client
+            .post(TOKEN_URL)
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", client_id),
+                ("client_secret", client_secret),
+            ])
+            .send()
+            .await
+            .map_err(|e| format!("token request failed: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!("token request failed with HTTP {}", response.status()));
+        }
+        let response: TokenResponse = response.json().await.map_err(|e| format!("invalid token response: {e}"))?;
+        let access_token = response.access_token;
+        *token = Some(Token {
+            access_token: access_token.clone(),
+            expires_at: Instant::now() + Duration::from_secs(response.expires_in),
+        });
+        Ok(access_token)
+    }
+
+    async fn invalidate(&self) {
+        self.0.lock().await.take();
+    }
+}
+
+#[derive(Default)]
+pub struct OpenSkyState {
+    /// Dropping the sender stops the poller.
+    bounding_box: Mutex<Option<watch::Sender<Option<BoundingBox>>>>,
+    /// Latest rows by `icao24` from each source, and their union as last
+    /// published.
+    network: Mutex<HashMap<String, Value>>,
+    local: Mutex<HashMap<String, Value>>,
+    published: Mutex<HashMap<String, Value>>,
+    time: Mutex<u64>,
+    status: Mutex<OpenSkyStatus>,
+    token: TokenManager,
+}
+
+/// Credits a `/states/all` request costs, by the area it covers in square
+/// degrees.
+fn credits_for(bounding_box: Option<&BoundingBox>) -> u32 {
+    let Some(b) = bounding_box else {
+        return 4;
+    };
+    match (b.north - b.south) * (b.east - b.west) {
+        area if area <= 25.0 => 1,
+        area if area <= 100.0 => 2,
+        area if area <= 400.0 => 3,
+        _ => 4,
+    }
+}
+
+/// How long to wait between polls so `remaining` credits (a full day's
+/// allowance when unknown) last until they reset, never faster than `floor`.
+fn poll_interval(cost: u32, remaining: Option<u32>, daily: u32, until_reset: Duration, floor: Duration) -> Duration {
+    let (credits, window) = match remaining {
+        Some(remaining) => (remaining, until_reset),
+        None => (daily, DAY),
+    };
+    let polls = credits / cost.max(1);
+    if polls == 0 {
+        return window.max(floor);
+    }
+    (window / polls).max(floor)
+}
+
+fn until_utc_midnight() -> Duration {
+    let elapsed = u64::from(chrono::Utc::now().num_seconds_from_midnight());
+    DAY.saturating_sub(Duration::from_secs(elapsed)).max(Duration::from_secs(1))
+}
+
+fn icao24(state: &Value) -> Option<&str> {
+    state.get(0)?.as_str()
+}
+
+/// A state vector row with a position, for alert rules.
+fn sighting(state: &Value) -> Option<Sighting<'_>> {
+    Some(Sighting {
+        kind: Target::Aircraft,
+        id: icao24(state)?,
+        label: state.get(1).and_then(Value::as_str).unwrap_or("").trim(),
+        imo: None,
+        lon: state.get(5)?.as_f64()?,
+        lat: state.get(6)?.as_f64()?,
+    })
+}
+
+/// The same report apart from its timestamps (`time_position` and
+/// `last_contact`), which change on every poll.
+fn same_state(a: &Value, b: &Value) -> bool {
+    match (a.as_array(), b.as_array()) {
+        (Some(a), Some(b)) => {
+            a.len() == b.len() && a.iter().zip(b).enumerate().all(|(i, (x, y))| i == 3 || i == 4 || x == y)
+        }
+        _ => a == b,
+    }
+}
+
+/// Replace `current` with `states` and return what changed.
+fn apply(current: &mut HashMap<String, Value>, time: u64, states: Vec<Value>) -> StateDiff {
+    let mut next = HashMap::with_capacity(states.len());
+    let mut diff = StateDiff {
+        time,
+        ..StateDiff::default()
+    };
+    for state in states {
+        let Some(id) = icao24(&state).map(str::to_string) else {
+            continue;
+        };
+        if !current.get(&id).is_some_and(|previous| same_state(previous, &state)) {
+            diff.updated.push(state.clone());
+        }
+        next.insert(id, state);
+    }
+    diff.removed = current.keys().filter(|id| !next.contains_key(*id)).cloned().collect();
+    *current = next;
+    diff
+}
+
+fn by_icao24(states: Vec<Value>) -> HashMap<String, Value> {
+    states
+        .into_iter()
+        .filter_map(|state| Some((icao24(&state)?.to_string(), state)))
+        .collect()
+}
+
+/// Publish the union of both sources as of `time`, emitting what changed.
+fn publish(app: &AppHandle, time: u64) {
+    let state = app.state::<OpenSkyState>();
+    let merged: Vec<Value> = {
+        let network = state.network.lock().unwrap_or_else(|e| e.into_inner());
+        let local = state.local.lock().unwrap_or_else(|e| e.into_inner());
+        network
+            .iter()
+            .filter(|(id, _)| !local.contains_key(*id))
+            .map(|(_, row)| row)
+            .chain(local.values())
+            .cloned()
+            .collect()
+    };
+    let sightings: Vec<Sighting> = merged.iter().filter_map(sighting).collect();
+    crate::alerts::observe_positions(app, &sightings);
+    crate::watchlists::observe_positions(app, &sightings);
+    crate::geofences::observe_positions(app, &sightings);
+    crate::anomalies::observe_aircraft(app, time, &merged);
+    let tracks = merged
+        .iter()
+        .filter_map(|row| {
+            let s = sighting(row)?;
+            Some(Observation {
+                kind: EventKind::Flight,
+                source: "opensky".to_string(),
+                id: s.id.to_string(),
+                time: (time * 1000) as i64,
+                lat: Some(s.lat),
+                lon: Some(s.lon),
+                title: if s.label.is_empty() { s.id } else { s.label }.to_string(),
+                data: row.clone(),
+            })
+        })
+        .collect();
+    crate::store::record_tracks(app, tracks);
+    let diff = {
+        let mut published = state.published.lock().unwrap_or_else(|e| e.into_inner());
+        let mut last = state.time.lock().unwrap_or_else(|e| e.into_inner());
+        *last = (*last).max(time);
+        let diff = apply(&mut published, *last, merged);
+        update_status(app, |status| status.aircraft = published.len());
+        diff
+    };
+    if !diff.is_empty() {
+        crate::relay::publish(app, "opensky", &diff);
+        let _ = app.emit(DIFF_EVENT, diff);
+    }
+}
+
+/// Replace the local receiver's aircraft (OpenSky state vector rows) and
+/// publish them.
+pub fn set_local(app: &AppHandle, time: u64, states: Vec<Value>) {
+    let Some(state) = app.try_state::<OpenSkyState>() else {
+        return;
+    };
+    *state.local.lock().unwrap_or_else(|e| e.into_inner()) = by_icao24(states);
+    publish(app, time);
+}
+
+fn update_status(app: &AppHandle, f: impl FnOnce(&mut OpenSkyStatus)) {
+    let state = app.state::<OpenSkyState>();
+    f(&mut state.status.lock().unwrap_or_else(|e| e.into_inner()));
+}
+
+fn header_u64(response: &reqwest::Response, name: &str) -> Option<u64> {
+    response.headers().get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+#[derive(Deserialize)]
+struct StatesResponse {
+    time: u64,
+    states: Option<Vec<Value>>,
+}
+
+/// One request. Returns how long to wait before the next.
+async fn poll(app: &AppHandle, client: &reqwest::Client, bounding_box: Option<BoundingBox>) -> Result<Duration, String> {
+    let state = app.state::<OpenSkyState>();
+    let secrets = app.state::<crate::SecretsCache>();
+    let credentials = secrets.get(CLIENT_ID_SECRET).zip(secrets.get(CLIENT_SECRET_SECRET));
+    let token = match &credentials {
+        Some((id, secret)) => match state.token.get(client, id, secret).await {
+            Ok(token) => Some(token),
+            Err(err) => {
+                tracing::warn!(target: "app", "OpenSky {err}; polling anonymously");
+                None
+            }
+        },
+        None => None,
+    };
+    let (daily, floor) = if token.is_some() {
+        (AUTHENTICATED_CREDITS, AUTHENTICATED_MIN_INTERVAL)
+    } else {
+        (ANONYMOUS_CREDITS, ANONYMOUS_MIN_INTERVAL)
+    };
+
+    let mut request = client.get(STATES_URL).header("Accept", "application/json");
+    if let Some(b) = &bounding_box {
+        request = request.query(&[("lamin", b.south), ("lomin", b.west), ("lamax", b.north), ("lomax", b.east)]);
+    }
+    if let Some(token) = &token {
+        request = request.bearer_auth(token);
+    }
+    let response = request.send().await.map_err(|e| format!("request failed: {e}"))?;
+    let remaining = header_u64(&response, "X-Rate-Limit-Remaining").and_then(|r| u32::try_from(r).ok());
+    let cost = credits_for(bounding_box.as_ref());
+    update_status(app, |status| {
+        status.authenticated = token.is_some();
+        status.credits_per_poll = cost;
+        if remaining.is_some() {
+            status.remaining_credits = remaining;
+        }
+    });
+
+    match response.status() {
+        reqwest::StatusCode::TOO_MANY_REQUESTS => {
+            let retry = header_u64(&response, "X-Rate-Limit-Retry-After-Seconds")
+                .map(Duration::from_secs)
+                .unwrap_or_else(until_utc_midnight);
+            update_status(app, |status| status.remaining_credits = Some(0));
+            tracing::warn!(target: "app", retry_secs = retry.as_secs(), "OpenSky credits exhausted");
+            return Ok(retry.max(floor));
+        }
+        reqwest::StatusCode::UNAUTHORIZED if token.is_some() => {
+            state.token.invalidate().await;
+            return Err("token rejected".to_string());
+        }
+        status if !status.is_success() => return Err(format!("HTTP {status}")),
+        _ => {}
+    }
+    let body: StatesResponse = response.json().await.map_err(|e| format!("invalid response: {e}"))?;
+
+    *state.network.lock().unwrap_or_else(|e| e.into_inner()) = by_icao24(body.states.unwrap_or_default());
+    publish(app, body.time);
+
+    let remaining = state.status.lock().unwrap_or_else(|e| e.into_inner()).remaining_credits;
+    Ok(poll_interval(cost, remaining, daily, until_utc_midnight(), floor))
+}
+
+struct Poller {
+    app: AppHandle,
+    client: reqwest::Client,
+    bounding_box: watch::Receiver<Option<BoundingBox>>,
+}
+
+impl crate::poll::Poller for Poller {
+    async fn round(&mut self) -> Duration {
+        let area = *self.bounding_box.borrow_and_update();
+        match poll(&self.app, &self.client, area).await {
+            Ok(wait) => {
+                update_status(&self.app, |status| {
+                    status.interval_secs = wait.as_secs();
+                    status.error = None;
+                });
+                wait
+            }
+            Err(err) => {
+                tracing::warn!(target: "app", "OpenSky poll failed: {err}");
+                update_status(&self.app, |status| status.error = Some(err));
+                ERROR_RETRY
+            }
+        }
+    }
+}
+
+async fn run(app: AppHandle, bounding_box: watch::Receiver<Option<BoundingBox>>) {
+    let client = match reqwest::Client::builder().use_native_tls().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!(target: "app", "OpenSky poller unavailable: {err}");
+            update_status(&app, |status| status.running = false);
+            return;
+        }
+    };
+    let poller = Poller {
+        app: app.clone(),
+        client,
+        bounding_box: bounding_box.clone(),
+    };
+    crate::poll::run_triggered(&app, bounding_box, poller).await;
+    tracing::info!(target: "app", "OpenSky poller stopped");
+}
+
+/// Start polling (or move the poller to a new bounding box, polling right
+/// away). `bounding_box` defaults to the whole world.
+#[tauri::command]
+pub fn start_opensky_poller(
+    webview: Webview,
+    app: AppHandle,
+    state: tauri::State<'_, OpenSkyState>,
+    bounding_box: Option<BoundingBox>,
+) -> Result<OpenSkyStatus, String> {
+    crate::metrics::of(&webview).observe("start_opensky_poller", || {
+        crate::require_trusted_window(webview.label())?;
+        if bounding_box.is_some_and(|b| !b.is_valid() || b.west > b.east) {
+            return Err("Bounding box must be in degrees, with south <= north and west <= east".to_string());
+        }
+        watch_area(&app, &state, bounding_box);
+        Ok(state.status.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+fn watch_area(app: &AppHandle, state: &OpenSkyState, bounding_box: Option<BoundingBox>) {
+    let mut sender = state.bounding_box.lock().unwrap_or_else(|e| e.into_inner());
+    match sender.as_ref().filter(|sender| !sender.is_closed()) {
+        Some(sender) => {
+            sender.send_replace(bounding_box);
+        }
+        None => {
+            let (tx, rx) = watch::channel(bounding_box);
+            *sender = Some(tx);
+            update_status(app, |status| {
+                status.running = true;
+                status.error = None;
+            });
+            tracing::info!(target: "app", "starting OpenSky poller");
+            tauri::async_runtime::spawn(run(app.clone(), rx));
+        }
+    }
+}
+
+/// Poll the whole world unless already polling; for `relay`.
+pub fn ensure_running(app: &AppHandle) {
+    let state = app.state::<OpenSkyState>();
+    if state.bounding_box.lock().unwrap_or_else(|e| e.into_inner()).as_ref().is_some_and(|s| !s.is_closed()) {
+        return;
+    }
+    watch_area(app, &state, None);
+}
+
+#[tauri::command]
+pub fn stop_opensky_poller(webview: Webview, app: AppHandle, state: tauri::State<'_, OpenSkyState>) -> Result<(), String> {
+    crate::metrics::of(&webview).observe("stop_opensky_poller", || {
+        crate::require_trusted_window(webview.label())?;
+        if crate::relay::is_serving(&app) {
+            if let Some(sender) = state.bounding_box.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+                sender.send_replace(None);
+            }
+            return Ok(());
+        }
+        if state.bounding_box.lock().unwrap_or_else(|e| e.into_inner()).take().is_some() {
+            update_status(&app, |status| status.running = false);
+        }
+        Ok(())
+    })
+}
+
+pub fn snapshot(state: &OpenSkyState) -> Snapshot {
+    let states = state.published.lock().unwrap_or_else(|e| e.into_inner());
+    Snapshot {
+        time: *state.time.lock().unwrap_or_else(|e| e.into_inner()),
+        states: states.values().cloned().collect(),
+    }
+}
+
+/// The last snapshot, to apply `opensky://diff` events to.
+#[tauri::command]
+pub fn get_opensky_snapshot(webview: Webview, state: tauri::State<'_, OpenSkyState>) -> Result<Snapshot, String> {
+    crate::metrics::of(&webview).observe("get_opensky_snapshot", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(snapshot(&state))
+    })
+}
+
+#[tauri::command]
+pub fn get_opensky_status(webview: Webview, state: tauri::State<'_, OpenSkyState>) -> Result<OpenSkyStatus, String> {
+    crate::metrics::of(&webview).observe("get_opensky_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.status.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+#[cfg(test)]
+mod opensky_tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn credits_scale_with_area() {
+        let area = |size: f64| BoundingBox { south: 0.0, west: 0.0, north: size, east: size };
+        assert_eq!(credits_for(Some(&area(5.0))), 1);
+        assert_eq!(credits_for(Some(&area(10.0))), 2);
+        assert_eq!(credits_for(Some(&area(20.0))), 3);
+        assert_eq!(credits_for(Some(&area(21.0))), 4);
+        assert_eq!(credits_for(None), 4);
+    }
+
+    #[test]
+    fn spreads_remaining_credits_until_reset() {
+        let hour = Duration::from_secs(3600);
+        let floor = Duration::from_secs(5);
+        // 4000 credits a day at 4 per poll: one poll every 86.4 s.
+        assert_eq!(poll_interval(4, None, 4000, hour, floor), Duration::from_millis(86_400));
+        assert_eq!(poll_interval(1, Some(360), 4000, hour, floor), Duration::from_secs(10));
+        assert_eq!(poll_interval(1, Some(100_000), 4000, hour, floor), floor);
+        assert_eq!(poll_interval(4, Some(3), 4000, hour, floor), hour);
+    }
+
+    #[test]
+    fn diffs_ignore_timestamps() {
+        let row = |id: &str, t: u64, lon: f64| json!([id, "CALL", "X", t, t, lon, 1.0, 100.0, false]);
+        let mut current = HashMap::new();
+        let first = apply(&mut current, 1, vec![row("a", 1, 1.0), row("b", 1, 2.0)]);
+        assert_eq!(first.updated.len(), 2);
+        assert!(first.removed.is_empty());
+
+        let second = apply(&mut current, 2, vec![row("a", 2, 1.0), row("c", 2, 3.0)]);
+        assert_eq!(second.updated, vec![row("c", 2, 3.0)]);
+        assert_eq!(second.removed, vec!["b".to_string()]);
+
+        let third = apply(&mut current, 3, vec![row("a", 3, 1.5), row("c", 3, 3.0)]);
+        assert_eq!(third.updated, vec![row("a", 3, 1.5)]);
+        assert_eq!(current.len(), 2);
+    }
+}
@@ -0,0 +1,792 @@
+//! Satellite tracking in the shell. With `orbits.enabled` set, the worker
+//! downloads two-line element sets for the Celestrak groups in
+//! `orbits.groups` (every `REFRESH_INTERVAL` on the `poll` schedule, cached
+//! in `orbit-elements.json` so tracking resumes offline), propagates every
+//! satellite with SGP4 each `orbits.interval_secs`, and emits the
+//! subsatellite points as one `orbits://positions` batch. With an
+//! `orbits.observer` set it also predicts the next `orbits.pass_hours` of
+//! passes above `orbits.min_elevation` for `orbits.pass_satellites`, emitted
+//! hourly as `orbits://passes`; `predict_passes` and `get_ground_track`
+//! compute the same on demand.
+//!
+//! Propagation is SGP4 from the `sgp4` crate, as revised by Vallado et al.
+//! (2006) and with WGS-72 constants, so it matches the AFSPC reference;
+//! orbits of 225 minutes or longer (navigation and geostationary
+//! satellites) take its deep-space branch with the lunar-solar terms.
+//! Positions are rotated from TEME to Earth-fixed by Greenwich mean
+//! sidereal time.
+
+use std::collections::BTreeMap;
+use std::f64::consts::TAU;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+use tokio::sync::watch;
+
+use crate::config::{DesktopConfigState, Observer, OrbitsConfig};
+
+pub const POSITIONS_EVENT: &str = "orbits://positions";
+pub const PASSES_EVENT: &str = "orbits://passes";
+pub const ELEMENTS_FILE: &str = "orbit-elements.json";
+
+const CELESTRAK_URL: &str = "https://celestrak.org/NORAD/elements/gp.php";
+/// Celestrak asks clients not to download a group more than every two hours.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+const ERROR_RETRY: Duration = Duration::from_secs(30 * 60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const PASS_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const MAX_SATELLITES: usize = 20_000;
+const MAX_PASS_SATELLITES: usize = 200;
+const MAX_PASS_HOURS: u32 = 72;
+const MAX_PASSES: usize = 50;
+/// Pass search step; shorter than any pass worth reporting.
+const PASS_STEP_MS: i64 = 30_000;
+const MAX_TRACK_MINUTES: u32 = 24 * 60;
+
+const MINUTES_PER_DAY: f64 = 1440.0;
+// WGS-84, for geodetic coordinates.
+const WGS84_A_KM: f64 = 6378.137;
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+/// Mean elements from a TLE, in radians and radians per minute.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Elements {
+    pub id: u32,
+    pub name: String,
+    /// Milliseconds since the epoch.
+    pub epoch: i64,
+    bstar: f64,
+    inclination: f64,
+    node: f64,
+    eccentricity: f64,
+    perigee: f64,
+    mean_anomaly: f64,
+    /// Kozai mean motion.
+    mean_motion: f64,
+}
+
+fn field(line: &str, range: std::ops::Range<usize>) -> Result<&str, String> {
+    line.get(range.clone()).map(str::trim).ok_or_else(|| format!("TLE line too short for columns {range:?}"))
+}
+
+fn number(line: &str, range: std::ops::Range<usize>) -> Result<f64, String> {
+    let text = field(line, range)?;
+    text.parse().map_err(|_| format!("invalid TLE field {text:?}"))
+}
+
+/// A TLE exponent field such as ` 28098-4` (0.28098e-4).
+fn exponent_field(line: &str, range: std::ops::Range<usize>) -> Result<f64, String> {
+    let text = field(line, range)?.replace(' ', "");
+    if text.is_empty() {
+        return Ok(0.0);
+    }
+    let split = text.rfind(['-', '+']).filter(|i| *i > 0).ok_or_else(|| format!("invalid TLE field {text:?}"))?;
+    let (mantissa, exponent) = text.split_at(split);
+    let (sign, digits) = match mantissa.strip_prefix('-') {
+        Some(digits) => (-1.0, digits),
+        None => (1.0, mantissa.trim_start_matches('+')),
+    };
+    let mantissa: f64 = format!("0.{digits}").parse().map_err(|_| format!("invalid TLE field {text:?}"))?;
+    let exponent: i32 = exponent.parse().map_err(|_| format!("invalid TLE field {text:?}"))?;
+    Ok(sign * mantissa * 10f64.powi(exponent))
+}
+
+impl Elements {
+    /// Parse a name line (may be empty) and the two element lines.
+    pub fn parse(name: &str, line1: &str, line2: &str) -> Result<Self, String> {
+        if !line1.starts_with('1') || !line2.starts_with('2') {
+            return Err("not a two-line element set".to_string());
+        }
+        let id = field(line1, 2..7)?.parse().map_err(|_| "invalid catalogue number".to_string())?;
+        let year = number(line1, 18..20)? as i32;
+        let year = if year < 57 { 2000 + year } else { 1900 + year };
+        let day = number(line1, 20..32)?;
+        let new_year = chrono::NaiveDate::from_ymd_opt(year, 1, 1).ok_or("invalid epoch year")?;
+        let epoch = new_year.and_hms_opt(0, 0, 0).ok_or("invalid epoch")?.and_utc().timestamp_millis()
+            + ((day - 1.0) * 86_400_000.0).round() as i64;
+        let degrees = |range| number(line2, range).map(f64::to_radians);
+        let eccentricity: f64 = format!("0.{}", field(line2, 26..33)?).parse().map_err(|_| "invalid eccentricity")?;
+        let name = name.trim().trim_start_matches("0 ").trim();
+        Ok(Elements {
+            id,
+            name: if name.is_empty() { id.to_string() } else { name.to_string() },
+            epoch,
+            bstar: exponent_field(line1, 53..61)?,
+            inclination: degrees(8..16)?,
+            node: degrees(17..25)?,
+            eccentricity,
+            perigee: degrees(34..42)?,
+            mean_anomaly: degrees(43..51)?,
+            mean_motion: number(line2, 52..63)? * TAU / MINUTES_PER_DAY,
+        })
+    }
+}
+
+/// Every element set in a Celestrak TLE listing (name line plus two
+/// lines); sets that do not parse are skipped and counted.
+fn parse_tle_text(text: &str) -> (Vec<Elements>, usize) {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).filter(|l| !l.trim().is_empty()).collect();
+    let (mut elements, mut skipped) = (Vec::new(), 0);
+    let mut i = 0;
+    while i < lines.len() {
+        let (name, first) = if lines[i].starts_with("1 ") { ("", i) } else { (lines[i], i + 1) };
+        match (lines.get(first), lines.get(first + 1)) {
+            (Some(line1), Some(line2)) if line1.starts_with("1 ") && line2.starts_with("2 ") => {
+                match Elements::parse(name, line1, line2) {
+                    Ok(e) => elements.push(e),
+                    Err(_) => skipped += 1,
+                }
+                i = first + 2;
+            }
+            _ => {
+                skipped += 1;
+                i += 1;
+            }
+        }
+    }
+    (elements, skipped)
+}
+
+/// SGP4 state initialised from a set of elements.
+#[derive(Clone, Debug)]
+pub struct Satellite {
+    pub elements: Elements,
+    pub group: String,
+    constants: sgp4::Constants,
+    /// Minutes per revolution.
+    period: f64,
+}
+
+/// TEME position and velocity, in km and km/s.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct State {
+    pub position: [f64; 3],
+    pub velocity: [f64; 3],
+}
+
+impl Satellite {
+    pub fn new(elements: Elements, group: &str) -> Result<Self, String> {
+        let e = &elements;
+        let invalid = |err: &dyn std::fmt::Display| format!("{}: {err}", e.id);
+        let orbit = sgp4::Orbit::from_kozai_elements(
+            &sgp4::WGS72,
+            e.inclination,
+            e.node,
+            e.eccentricity,
+            e.perigee,
+            e.mean_anomaly,
+            e.mean_motion,
+        )
+        .map_err(|err| invalid(&err))?;
+        let period = TAU / orbit.mean_motion;
+        let epoch = chrono::DateTime::from_timestamp_millis(e.epoch).ok_or_else(|| invalid(&"epoch out of range"))?;
+        let constants = sgp4::Constants::new(
+            sgp4::WGS72,
+            sgp4::afspc_epoch_to_sidereal_time,
+            sgp4::julian_years_since_j2000_afspc_compatibility_mode(&epoch.naive_utc()),
+            e.bstar,
+            orbit,
+        )
+        .map_err(|err| invalid(&err))?;
+        Ok(Satellite { elements, group: group.to_string(), constants, period })
+    }
+
+    /// Propagate `minutes` from the element epoch. Fails once the orbit has
+    /// decayed or the elements have diverged.
+    pub fn propagate(&self, minutes: f64) -> Result<State, String> {
+        let id = self.elements.id;
+        let prediction = self
+            .constants
+            .propagate_afspc_compatibility_mode(sgp4::MinutesSinceEpoch(minutes))
+            .map_err(|err| format!("{id}: {err}"))?;
+        let [x, y, z] = prediction.position;
+        if (x * x + y * y + z * z).sqrt() < sgp4::WGS72.ae {
+            return Err(format!("{id}: decayed"));
+        }
+        Ok(State { position: prediction.position, velocity: prediction.velocity })
+    }
+
+    /// Propagate to `time`, in milliseconds since the epoch.
+    pub fn at(&self, time: i64) -> Result<State, String> {
+        self.propagate((time - self.elements.epoch) as f64 / 60_000.0)
+    }
+}
+
+/// Greenwich mean sidereal time (IAU 1982) in radians.
+fn gmst(time: i64) -> f64 {
+    let t = (time as f64 / 86_400_000.0 + 2_440_587.5 - 2_451_545.0) / 36_525.0;
+    let seconds = 67_310.548_41 + (876_600.0 * 3600.0 + 8_640_184.812_866) * t + 0.093_104 * t * t - 6.2e-6 * t * t * t;
+    (seconds * TAU / 86_400.0).rem_euclid(TAU)
+}
+
+/// Earth-fixed coordinates of a TEME position at `time`.
+fn earth_fixed(position: [f64; 3], time: i64) -> [f64; 3] {
+    let (s, c) = gmst(time).sin_cos();
+    let [x, y, z] = position;
+    [c * x + s * y, -s * x + c * y, z]
+}
+
+/// Geodetic `[lat, lon]` in degrees and height in km of an Earth-fixed
+/// position.
+fn geodetic([x, y, z]: [f64; 3]) -> ([f64; 2], f64) {
+    let e2 = WGS84_F * (2.0 - WGS84_F);
+    let p = x.hypot(y);
+    let mut lat = z.atan2(p * (1.0 - e2));
+    let mut n = WGS84_A_KM;
+    for _ in 0..5 {
+        n = WGS84_A_KM / (1.0 - e2 * lat.sin().powi(2)).sqrt();
+        lat = (z + e2 * n * lat.sin()).atan2(p);
+    }
+    let height = if lat.cos().abs() > 1.0e-9 { p / lat.cos() - n } else { z.abs() - n * (1.0 - e2) };
+    ([lat.to_degrees(), y.atan2(x).to_degrees()], height)
+}
+
+fn observer_position(observer: &Observer) -> [f64; 3] {
+    let e2 = WGS84_F * (2.0 - WGS84_F);
+    let (lat, lon) = (observer.lat.to_radians(), observer.lon.to_radians());
+    let n = WGS84_A_KM / (1.0 - e2 * lat.sin().powi(2)).sqrt();
+    let h = observer.alt_m / 1000.0;
+    [(n + h) * lat.cos() * lon.cos(), (n + h) * lat.cos() * lon.sin(), (n * (1.0 - e2) + h) * lat.sin()]
+}
+
+/// Azimuth (from north, clockwise) and elevation in degrees, and range in
+/// km, of an Earth-fixed position seen from `observer`.
+fn look_angles(observer: &Observer, site: [f64; 3], target: [f64; 3]) -> (f64, f64, f64) {
+    let (lat, lon) = (observer.lat.to_radians(), observer.lon.to_radians());
+    let r = [target[0] - site[0], target[1] - site[1], target[2] - site[2]];
+    let south = lat.sin() * lon.cos() * r[0] + lat.sin() * lon.sin() * r[1] - lat.cos() * r[2];
+    let east = -lon.sin() * r[0] + lon.cos() * r[1];
+    let zenith = lat.cos() * lon.cos() * r[0] + lat.cos() * lon.sin() * r[1] + lat.sin() * r[2];
+    let range = (r[0] * r[0] + r[1] * r[1] + r[2] * r[2]).sqrt();
+    let azimuth = east.atan2(-south).to_degrees().rem_euclid(360.0);
+    (azimuth, (zenith / range).asin().to_degrees(), range)
+}
+
+/// Payload of `orbits://positions` (a batch of these).
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SatellitePosition {
+    /// NORAD catalogue number.
+    pub id: u32,
+    pub name: String,
+    pub group: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub alt_km: f64,
+    pub speed_kms: f64,
+}
+
+fn position(satellite: &Satellite, time: i64) -> Option<SatellitePosition> {
+    let state = satellite.at(time).ok()?;
+    let ([lat, lon], alt_km) = geodetic(earth_fixed(state.position, time));
+    let [vx, vy, vz] = state.velocity;
+    Some(SatellitePosition {
+        id: satellite.elements.id,
+        name: satellite.elements.name.clone(),
+        group: satellite.group.clone(),
+        lat,
+        lon,
+        alt_km,
+        speed_kms: (vx * vx + vy * vy + vz * vz).sqrt(),
+    })
+}
+
+/// One pass over an observer. Times are milliseconds since the epoch.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pass {
+    pub id: u32,
+    pub name: String,
+    /// The start of the search window if the satellite was already up.
+    pub rise: i64,
+    pub rise_azimuth: f64,
+    pub culmination: i64,
+    pub max_elevation: f64,
+    pub set: i64,
+    pub set_azimuth: f64,
+}
+
+/// Passes above `min_elevation` degrees in `[from, to)`.
+fn passes(satellite: &Satellite, observer: &Observer, min_elevation: f64, from: i64, to: i64) -> Vec<Pass> {
+    let site = observer_position(observer);
+    let look = |time: i64| {
+        let state = satellite.at(time).ok()?;
+        Some(look_angles(observer, site, earth_fixed(state.position, time)))
+    };
+    let elevation = |time: i64| look(time).map_or(-90.0, |(_, el, _)| el);
+    // The moment between `a` (below) and `b` (above), or the reverse, to a
+    // second.
+    let crossing = |mut below: i64, mut above: i64| {
+        while (above - below).abs() > 1_000 {
+            let mid = below + (above - below) / 2;
+            if elevation(mid) >= min_elevation {
+                above = mid;
+            } else {
+                below = mid;
+            }
+        }
+        above
+    };
+
+    let mut found = Vec::new();
+    let mut rise = (elevation(from) >= min_elevation).then_some(from);
+    let mut previous = from;
+    let mut time = from + PASS_STEP_MS;
+    while time < to + PASS_STEP_MS && found.len() < MAX_PASSES {
+        let time_clamped = time.min(to);
+        let up = elevation(time_clamped) >= min_elevation;
+        match (rise, up) {
+            (None, true) => rise = Some(crossing(previous, time_clamped)),
+            (Some(start), false) => {
+                let set = crossing(time_clamped, previous);
+                found.push((start, set));
+                rise = None;
+            }
+            _ => {}
+        }
+        previous = time_clamped;
+        time += PASS_STEP_MS;
+    }
+    found
+        .into_iter()
+        .filter_map(|(rise, set)| {
+            // Golden-section search for the highest point.
+            let (mut a, mut b) = (rise as f64, set as f64);
+            let ratio = (5f64.sqrt() - 1.0) / 2.0;
+            while b - a > 1_000.0 {
+                let (c, d) = (b - ratio * (b - a), a + ratio * (b - a));
+                if elevation(c as i64) > elevation(d as i64) {
+                    b = d;
+                } else {
+                    a = c;
+                }
+            }
+            let culmination = ((a + b) / 2.0) as i64;
+            Some(Pass {
+                id: satellite.elements.id,
+                name: satellite.elements.name.clone(),
+                rise,
+                rise_azimuth: look(rise)?.0,
+                culmination,
+                max_elevation: elevation(culmination),
+                set,
+                set_azimuth: look(set)?.0,
+            })
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrbitsStatus {
+    pub enabled: bool,
+    pub satellites: usize,
+    /// Element sets that could not be read or initialised.
+    pub skipped: usize,
+    /// When the elements were downloaded, in milliseconds since the epoch.
+    pub last_fetch: Option<i64>,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct OrbitsState {
+    status: Mutex<OrbitsStatus>,
+    satellites: Mutex<Arc<Vec<Satellite>>>,
+}
+
+impl OrbitsState {
+    fn satellites(&self) -> Arc<Vec<Satellite>> {
+        self.satellites.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+fn update_status(app: &AppHandle, f: impl FnOnce(&mut OrbitsStatus)) {
+    let state = app.state::<OrbitsState>();
+    f(&mut state.status.lock().unwrap_or_else(|e| e.into_inner()));
+}
+
+/// The downloaded TLE listings, by group.
+#[derive(Default, Serialize, Deserialize)]
+struct ElementsCache {
+    fetched: i64,
+    groups: BTreeMap<String, String>,
+}
+
+fn cache_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::cache_file_path(app)?.with_file_name(ELEMENTS_FILE))
+}
+
+/// Initialise the satellites in `cache`, once each, up to `limit`.
+fn satellites(cache: &ElementsCache, limit: usize) -> (Vec<Satellite>, usize) {
+    let (mut satellites, mut skipped) = (Vec::new(), 0);
+    let mut seen = std::collections::HashSet::new();
+    for (group, text) in &cache.groups {
+        let (elements, unread) = parse_tle_text(text);
+        skipped += unread;
+        for elements in elements {
+            if satellites.len() >= limit || !seen.insert(elements.id) {
+                continue;
+            }
+            match Satellite::new(elements, group) {
+                Ok(satellite) => satellites.push(satellite),
+                Err(_) => skipped += 1,
+            }
+        }
+    }
+    (satellites, skipped)
+}
+
+fn install(app: &AppHandle, cache: &ElementsCache, config: &OrbitsConfig) {
+    let (satellites, skipped) = satellites(cache, config.max_satellites.min(MAX_SATELLITES));
+    update_status(app, |status| {
+        status.satellites = satellites.len();
+        status.skipped = skipped;
+        status.last_fetch = Some(cache.fetched);
+    });
+    *app.state::<OrbitsState>().satellites.lock().unwrap_or_else(|e| e.into_inner()) = Arc::new(satellites);
+}
+
+async fn fetch(client: &reqwest::Client, groups: &[String]) -> Result<ElementsCache, String> {
+    let mut cache = ElementsCache { fetched: chrono::Utc::now().timestamp_millis(), groups: BTreeMap::new() };
+    for group in groups {
+        let text = client
+            .get(CELESTRAK_URL)
+            .query(&[("GROUP", group.as_str()), ("FORMAT", "tle")])
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("{group}: request failed: {e}"))?
+            .text()
+            .await
+            .map_err(|e| format!("{group}: request failed: {e}"))?;
+        if parse_tle_text(&text).0.is_empty() {
+            return Err(format!("{group}: no element sets (unknown group?)"));
+        }
+        cache.groups.insert(group.clone(), text);
+    }
+    Ok(cache)
+}
+
+fn emit_positions(app: &AppHandle) {
+    let satellites = app.state::<OrbitsState>().satellites();
+    let now = chrono::Utc::now().timestamp_millis();
+    let positions: Vec<SatellitePosition> = satellites.iter().filter_map(|s| position(s, now)).collect();
+    let _ = app.emit(POSITIONS_EVENT, positions);
+}
+
+fn predict(satellites: &[Satellite], ids: &[u32], observer: &Observer, min_elevation: f64, hours: u32) -> Vec<Pass> {
+    let from = chrono::Utc::now().timestamp_millis();
+    let to = from + i64::from(hours.clamp(1, MAX_PASS_HOURS)) * 3_600_000;
+    let mut found: Vec<Pass> = satellites
+        .iter()
+        .filter(|s| ids.contains(&s.elements.id))
+        .flat_map(|s| passes(s, observer, min_elevation, from, to))
+        .collect();
+    found.sort_by_key(|p| p.rise);
+    found
+}
+
+/// Downloads the element sets on the `poll` schedule, installs them and
+/// signals `elements`, so passes are recomputed.
+struct Poller {
+    app: AppHandle,
+    client: reqwest::Client,
+    config: OrbitsConfig,
+    path: Option<PathBuf>,
+    elements: watch::Sender<()>,
+    next_fetch: Instant,
+}
+
+impl crate::poll::Poller for Poller {
+    async fn round(&mut self) -> Duration {
+        let due_in = self.next_fetch.saturating_duration_since(Instant::now());
+        if !due_in.is_zero() {
+            return due_in;
+        }
+        match fetch(&self.client, &self.config.groups).await {
+            Ok(cache) => {
+                install(&self.app, &cache, &self.config);
+                let _ = self.elements.send(());
+                if let (Some(path), Ok(text)) = (self.path.clone(), serde_json::to_string(&cache)) {
+                    let written = crate::runtime::blocking(move || fs::write(path, text).map_err(|e| e.to_string())).await;
+                    if let Err(err) = written {
+                        tracing::warn!(target: "app", "failed to cache orbital elements: {err}");
+                    }
+                }
+                update_status(&self.app, |status| status.error = None);
+                self.next_fetch = Instant::now() + REFRESH_INTERVAL;
+                REFRESH_INTERVAL
+            }
+            Err(err) => {
+                tracing::warn!(target: "app", "orbital elements download failed: {err}");
+                update_status(&self.app, |status| status.error = Some(err));
+                self.next_fetch = Instant::now() + ERROR_RETRY;
+                ERROR_RETRY
+            }
+        }
+    }
+}
+
+async fn run(app: AppHandle, config: OrbitsConfig) {
+    let client = match reqwest::Client::builder().use_native_tls().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!(target: "app", "satellite tracking unavailable: {err}");
+            return;
+        }
+    };
+    let path = cache_path(&app).ok();
+    let cached: Option<ElementsCache> = match path.clone() {
+        Some(path) => crate::runtime::blocking(move || Ok(fs::read_to_string(path).ok())).await.ok().flatten(),
+        None => None,
+    }
+    .and_then(|text| serde_json::from_str(&text).ok())
+    .filter(|cache: &ElementsCache| config.groups.iter().all(|g| cache.groups.contains_key(g)));
+    let mut next_fetch = Instant::now();
+    if let Some(cache) = &cached {
+        install(&app, cache, &config);
+        let age = chrono::Utc::now().timestamp_millis().saturating_sub(cache.fetched).max(0) as u64;
+        next_fetch += REFRESH_INTERVAL.saturating_sub(Duration::from_millis(age));
+    }
+    let (elements, mut installed) = watch::channel(());
+    let poller = Poller {
+        app: app.clone(),
+        client,
+        config: config.clone(),
+        path,
+        elements,
+        next_fetch,
+    };
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move { crate::poll::run(&handle, poller).await });
+
+    // Propagation needs no network, so it keeps running while downloads
+    // are held.
+    let mut next_passes = Instant::now();
+    let mut tick = tokio::time::interval(Duration::from_secs(config.interval_secs.max(1)));
+    loop {
+        tick.tick().await;
+        if installed.has_changed().unwrap_or(false) {
+            installed.borrow_and_update();
+            next_passes = Instant::now();
+        }
+        let handle = app.clone();
+        let _ = tauri::async_runtime::spawn_blocking(move || emit_positions(&handle)).await;
+
+        if let Some(observer) = config.observer.clone().filter(|_| Instant::now() >= next_passes) {
+            next_passes = Instant::now() + PASS_INTERVAL;
+            let handle = app.clone();
+            let config = config.clone();
+            let _ = tauri::async_runtime::spawn_blocking(move || {
+                let satellites = handle.state::<OrbitsState>().satellites();
+                let found = predict(&satellites, &config.pass_satellites, &observer, config.min_elevation, config.pass_hours);
+                let _ = handle.emit(PASSES_EVENT, found);
+            })
+            .await;
+        }
+    }
+}
+
+/// Start tracking if `orbits.enabled` is set.
+pub fn start(app: &AppHandle) {
+    let config = app.state::<DesktopConfigState>().snapshot().orbits;
+    update_status(app, |status| status.enabled = config.enabled);
+    if config.enabled && !config.groups.is_empty() {
+        tauri::async_runtime::spawn(run(app.clone(), config));
+    }
+}
+
+#[tauri::command]
+pub fn get_orbits_status(webview: Webview, state: tauri::State<'_, OrbitsState>) -> Result<OrbitsStatus, String> {
+    crate::metrics::of(&webview).observe("get_orbits_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.status.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+/// Every tracked satellite's subsatellite point at `time` (milliseconds,
+/// default now).
+#[tauri::command]
+pub async fn get_satellite_positions(
+    webview: Webview,
+    app: AppHandle,
+    time: Option<i64>,
+) -> Result<Vec<SatellitePosition>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("get_satellite_positions", async move {
+            crate::require_trusted_window(webview.label())?;
+            let time = time.unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+            crate::runtime::blocking(move || {
+                let satellites = app.state::<OrbitsState>().satellites();
+                Ok(satellites.iter().filter_map(|s| position(s, time)).collect())
+            })
+            .await
+        })
+        .await
+}
+
+/// A satellite's ground track as `[lon, lat]` points a minute apart, from
+/// `before` minutes ago to `after` minutes ahead (one orbit ahead by
+/// default).
+#[tauri::command]
+pub fn get_ground_track(
+    webview: Webview,
+    state: tauri::State<'_, OrbitsState>,
+    id: u32,
+    before: Option<u32>,
+    after: Option<u32>,
+) -> Result<Vec<[f64; 2]>, String> {
+    crate::metrics::of(&webview).observe("get_ground_track", || {
+        crate::require_trusted_window(webview.label())?;
+        let satellites = state.satellites();
+        let satellite = satellites.iter().find(|s| s.elements.id == id).ok_or(format!("Satellite {id} is not tracked"))?;
+        let period = satellite.period.ceil() as u32;
+        let (before, after) = (before.unwrap_or(0).min(MAX_TRACK_MINUTES), after.unwrap_or(period).min(MAX_TRACK_MINUTES));
+        let now = chrono::Utc::now().timestamp_millis();
+        Ok((-i64::from(before)..=i64::from(after))
+            .filter_map(|minute| {
+                let time = now + minute * 60_000;
+                let state = satellite.at(time).ok()?;
+                let ([lat, lon], _) = geodetic(earth_fixed(state.position, time));
+                Some([lon, lat])
+            })
+            .collect())
+    })
+}
+
+/// Passes of the given satellites over `observer` (default
+/// `orbits.observer`) in the next `hours`, soonest first.
+#[tauri::command]
+pub async fn predict_passes(
+    webview: Webview,
+    app: AppHandle,
+    ids: Vec<u32>,
+    observer: Option<Observer>,
+    hours: Option<u32>,
+    min_elevation: Option<f64>,
+) -> Result<Vec<Pass>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("predict_passes", async move {
+            crate::require_trusted_window(webview.label())?;
+            if ids.len() > MAX_PASS_SATELLITES {
+                return Err(format!("At most {MAX_PASS_SATELLITES} satellites per prediction"));
+            }
+            let config = app.state::<DesktopConfigState>().snapshot().orbits;
+            let observer = observer.or(config.observer).ok_or("No observer location given or configured")?;
+            if !(-90.0..=90.0).contains(&observer.lat) || !(-180.0..=180.0).contains(&observer.lon) {
+                return Err("Observer coordinates out of range".to_string());
+            }
+            let hours = hours.unwrap_or(config.pass_hours);
+            let min_elevation = min_elevation.unwrap_or(config.min_elevation).clamp(0.0, 90.0);
+            crate::runtime::blocking(move || {
+                let satellites = app.state::<OrbitsState>().satellites();
+                Ok(predict(&satellites, &ids, &observer, min_elevation, hours))
+            })
+            .await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod orbits_tests {
+    use super::*;
+
+    // The first case of Vallado's SGP4 verification set.
+    const VANGUARD: [&str; 3] = [
+        "VANGUARD 1",
+        "1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753",
+        "2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667",
+    ];
+    // A GPS satellite from the same set, on the deep-space branch.
+    const GPS: [&str; 3] = [
+        "GPS BIIR-10",
+        "1 28129U 03058A   06175.57071136 -.00000104  00000-0  10000-3 0   459",
+        "2 28129  54.7298 324.8098 0048506 266.2640  93.1663  2.00562768 18443",
+    ];
+    const ISS: [&str; 3] = [
+        "ISS (ZARYA)",
+        "1 25544U 98067A   26288.50000000  .00016717  00000-0  30000-3 0  9993",
+        "2 25544  51.6400 200.0000 0005000  90.0000 270.0000 15.50000000    07",
+    ];
+
+    fn satellite(tle: [&str; 3]) -> Satellite {
+        Satellite::new(Elements::parse(tle[0], tle[1], tle[2]).unwrap(), "test").unwrap()
+    }
+
+    fn close(actual: [f64; 3], expected: [f64; 3], tolerance: f64) -> bool {
+        actual.iter().zip(expected).all(|(a, e)| (a - e).abs() < tolerance)
+    }
+
+    #[test]
+    fn parses_element_sets() {
+        let e = Elements::parse(VANGUARD[0], VANGUARD[1], VANGUARD[2]).unwrap();
+        assert_eq!((e.id, e.name.as_str()), (5, "VANGUARD 1"));
+        assert!((e.bstar - 0.28098e-4).abs() < 1e-12);
+        assert!((e.eccentricity - 0.1859667).abs() < 1e-12);
+        assert_eq!(exponent_field(" -11606-4", 0..9).unwrap(), -0.11606e-4);
+        // 2000 day 179.78495062 is 27 June 18:50:19.733 UTC.
+        assert_eq!(e.epoch, 962_131_819_734);
+
+        let listing = format!("{}\n{}\n{}\n\nBROKEN\n1 short\n{}\n{}\n", ISS[0], ISS[1], ISS[2], VANGUARD[1], VANGUARD[2]);
+        let (elements, skipped) = parse_tle_text(&listing);
+        assert_eq!(elements.iter().map(|e| e.id).collect::<Vec<_>>(), [25544, 5]);
+        assert_eq!(elements[1].name, "5");
+        assert_eq!(skipped, 2);
+    }
+
+    #[test]
+    fn matches_the_verification_vectors() {
+        let vanguard = satellite(VANGUARD);
+        assert!(vanguard.period < 225.0);
+        let start = vanguard.propagate(0.0).unwrap();
+        assert!(close(start.position, [7022.46529266, -1400.08296755, 0.03995155], 1e-3), "{start:?}");
+        assert!(close(start.velocity, [1.893841015, 6.405893759, 4.534807250], 1e-6), "{start:?}");
+        let later = vanguard.propagate(360.0).unwrap();
+        assert!(close(later.position, [-7154.03120202, -3783.17682504, -3536.19412294], 1e-3), "{later:?}");
+        assert!(close(later.velocity, [4.741887409, -4.151817765, -2.093935425], 1e-6), "{later:?}");
+
+        let gps = satellite(GPS);
+        assert!(gps.period >= 225.0);
+        let later = gps.propagate(120.0).unwrap();
+        assert!(close(later.position, [18616.75971861, 3166.15177043, 18833.41523210], 1e-3), "{later:?}");
+        assert!(close(later.velocity, [-2.076122016, 2.838457575, 1.586210535], 1e-6), "{later:?}");
+    }
+
+    #[test]
+    fn finds_subsatellite_points() {
+        let iss = satellite(ISS);
+        let position = position(&iss, iss.elements.epoch + 3_600_000).unwrap();
+        assert!(position.lat.abs() <= 51.7, "{position:?}");
+        assert!((380.0..450.0).contains(&position.alt_km), "{position:?}");
+        assert!((7.5..7.8).contains(&position.speed_kms), "{position:?}");
+
+        // A point over the equator and prime meridian is found there.
+        let ([lat, lon], height) = geodetic([WGS84_A_KM + 400.0, 0.0, 0.0]);
+        assert!(lat.abs() < 1e-9 && lon.abs() < 1e-9 && (height - 400.0).abs() < 1e-6);
+        let ([lat, _], height) = geodetic([0.0, 0.0, 7000.0]);
+        assert!((lat - 90.0).abs() < 1e-9 && (height - (7000.0 - 6356.752)).abs() < 1e-3);
+        // GMST at J2000.0 is 280.46°.
+        assert!((gmst(946_728_000_000).to_degrees() - 280.46061837).abs() < 1e-6);
+    }
+
+    #[test]
+    fn predicts_passes() {
+        let iss = satellite(ISS);
+        let observer = Observer { lat: 40.0, lon: -75.0, alt_m: 0.0 };
+        let from = iss.elements.epoch;
+        let found = passes(&iss, &observer, 10.0, from, from + 24 * 3_600_000);
+        assert!(!found.is_empty() && found.len() < 12, "{} passes", found.len());
+        for pass in &found {
+            assert!(pass.rise < pass.culmination && pass.culmination < pass.set, "{pass:?}");
+            assert!(pass.set - pass.rise < 12 * 60_000, "{pass:?}");
+            assert!((10.0..=90.0).contains(&pass.max_elevation), "{pass:?}");
+        }
+        // Never visible from near the pole.
+        let arctic = Observer { lat: 85.0, lon: 0.0, alt_m: 0.0 };
+        assert!(passes(&iss, &arctic, 10.0, from, from + 24 * 3_600_000).is_empty());
+    }
+}
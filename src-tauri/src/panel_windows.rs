@@ -0,0 +1,169 @@
+//! Detached panel windows. `open_panel_window` pops a dashboard panel (news
+//! feed, map, …) into its own `panel-<id>` window loading `panel.html?id=<id>`,
+//! which boots the regular app in single-panel mode. Open panels are listed
+//! in `panel-windows.json` in the app data dir and reopened on the next
+//! launch; their geometry is kept by `window_state` like the main window's.
+//! Closing a panel window removes it from the list, quitting the app does not.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, Webview, WebviewUrl, WebviewWindowBuilder};
+
+pub const PANEL_WINDOWS_FILE: &str = "panel-windows.json";
+const LABEL_PREFIX: &str = "panel-";
+const MAX_PANEL_ID_LEN: usize = 64;
+
+/// Managed state: ids of the panels currently detached.
+pub struct PanelWindowsState {
+    path: PathBuf,
+    open: Mutex<BTreeSet<String>>,
+}
+
+impl PanelWindowsState {
+    /// Load `panel-windows.json`; a missing or unreadable file starts empty.
+    pub fn load(path: PathBuf) -> Self {
+        let open: BTreeSet<String> = fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        PanelWindowsState {
+            path,
+            open: Mutex::new(open.into_iter().filter(|id| validate_panel_id(id).is_ok()).collect()),
+        }
+    }
+
+    fn snapshot(&self) -> Vec<String> {
+        self.open.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect()
+    }
+
+    fn update(&self, update: impl FnOnce(&mut BTreeSet<String>) -> bool) {
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        if update(&mut open) {
+            if let Err(err) = write(&self.path, &open) {
+                tracing::warn!(target: "app", "{err}");
+            }
+        }
+    }
+}
+
+fn write(path: &Path, open: &BTreeSet<String>) -> Result<(), String> {
+    let serialized =
+        serde_json::to_string_pretty(open).map_err(|e| format!("Failed to serialize panel windows: {e}"))?;
+    fs::write(path, serialized).map_err(|e| format!("Failed to write panel windows {}: {e}", path.display()))
+}
+
+/// Panel ids become part of a window label and URL, so keep them to the
+/// lowercase slug form the frontend uses (`live-news`, `markets`, `map`).
+pub(crate) fn validate_panel_id(id: &str) -> Result<(), String> {
+    let valid = !id.is_empty()
+        && id.len() <= MAX_PANEL_ID_LEN
+        && id.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid panel id: {id}"))
+    }
+}
+
+pub fn is_panel_window(label: &str) -> bool {
+    label
+        .strip_prefix(LABEL_PREFIX)
+        .is_some_and(|id| validate_panel_id(id).is_ok())
+}
+
+fn panel_id(label: &str) -> Option<&str> {
+    label.strip_prefix(LABEL_PREFIX).filter(|_| is_panel_window(label))
+}
+
+/// Open (or focus) the window for `panel_id` and record it as detached.
+pub fn open(app: &AppHandle, panel_id: &str) -> Result<(), String> {
+    validate_panel_id(panel_id)?;
+    let label = format!("{LABEL_PREFIX}{panel_id}");
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.show();
+        let _ = window.unminimize();
+        return window
+            .set_focus()
+            .map_err(|e| format!("Failed to focus panel window: {e}"));
+    }
+
+    let window = WebviewWindowBuilder::new(app, &label, WebviewUrl::App(format!("panel.html?id={panel_id}").into()))
+        .title("World Monitor")
+        .inner_size(640.0, 720.0)
+        .min_inner_size(360.0, 320.0)
+        .resizable(true)
+        .visible(false)
+        .background_color(tauri::webview::Color(26, 28, 30, 255))
+        .build()
+        .map_err(|e| format!("Failed to create panel window: {e}"))?;
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = window.remove_menu();
+
+    crate::window_state::restore(&window);
+    let _ = window.show();
+
+    if let Some(state) = app.try_state::<PanelWindowsState>() {
+        state.update(|open| open.insert(panel_id.to_string()));
+    }
+    tracing::info!(target: "app", panel = panel_id, "panel window opened");
+    Ok(())
+}
+
+/// The user closed a panel window: stop restoring it on launch.
+pub fn on_close_requested(app: &AppHandle, label: &str) {
+    let (Some(id), Some(state)) = (panel_id(label), app.try_state::<PanelWindowsState>()) else {
+        return;
+    };
+    state.update(|open| open.remove(id));
+}
+
+/// Reopen the panels that were detached when the app last quit.
+pub fn restore_all(app: &AppHandle) {
+    let Some(state) = app.try_state::<PanelWindowsState>() else {
+        return;
+    };
+    for id in state.snapshot() {
+        if let Err(err) = open(app, &id) {
+            tracing::warn!(target: "app", panel = id, "failed to restore panel window: {err}");
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn open_panel_window(webview: Webview, app: AppHandle, panel_id: String) -> Result<(), String> {
+    crate::metrics::of(&webview)
+        .observe_async("open_panel_window", async move {
+            crate::require_trusted_window(webview.label())?;
+            open(&app, &panel_id)
+        })
+        .await
+}
+
+#[cfg(test)]
+mod panel_windows_tests {
+    use super::{is_panel_window, panel_id, validate_panel_id};
+
+    #[test]
+    fn accepts_slug_panel_ids_only() {
+        assert!(validate_panel_id("live-news").is_ok());
+        assert!(validate_panel_id("map").is_ok());
+        assert!(validate_panel_id("").is_err());
+        assert!(validate_panel_id("News").is_err());
+        assert!(validate_panel_id("../settings").is_err());
+        assert!(validate_panel_id("a?b=c").is_err());
+        assert!(validate_panel_id(&"x".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn recognizes_panel_window_labels() {
+        assert!(is_panel_window("panel-markets"));
+        assert_eq!(panel_id("panel-markets"), Some("markets"));
+        assert!(!is_panel_window("panel-"));
+        assert!(!is_panel_window("main"));
+        assert_eq!(panel_id("settings"), None);
+    }
+}
@@ -0,0 +1,402 @@
+//! File → Print / Export PDF: writes the dashboard as it is now to a
+//! timestamped PDF in Downloads (falling back to the logs dir) for briefings,
+//! using each platform webview's own printing to file:
+//!
+//! - Windows: WebView2's `PrintToPdf`.
+//! - macOS: a `WKWebView` print operation saved to a file instead of printed.
+//! - Linux: a WebKitGTK print operation on GTK's Print to File printer.
+//!
+//! Page size and orientation come from `PdfConfig`, set from the File →
+//! Page Setup submenu or per export through `export_pdf`.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::time::Duration;
+
+use tauri::menu::{CheckMenuItem, IsMenuItem, MenuItem, Submenu};
+use tauri::{AppHandle, Manager, Webview, Wry};
+
+use crate::config::{DesktopConfigState, PageSize, PdfConfig};
+
+pub const MENU_FILE_ID: &str = "file";
+const MENU_PREFIX: &str = "file.pdf.";
+const MENU_EXPORT_ID: &str = "file.pdf.export";
+const MENU_PAGE_SETUP_ID: &str = "file.pdf.page-setup";
+const MENU_LANDSCAPE_ID: &str = "file.pdf.landscape";
+const PAGE_SIZES: [(PageSize, &str, &str); 4] = [
+    (PageSize::A4, "file.pdf.page-size.a4", "A4"),
+    (PageSize::A3, "file.pdf.page-size.a3", "A3"),
+    (PageSize::Letter, "file.pdf.page-size.letter", "US Letter"),
+    (PageSize::Legal, "file.pdf.page-size.legal", "US Legal"),
+];
+
+/// Large dashboards can take a while to lay out for print.
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(60);
+
+static EXPORTING: AtomicBool = AtomicBool::new(false);
+
+type Done = Sender<Result<(), String>>;
+
+/// Portrait width and height in millimeters.
+fn page_mm(size: PageSize) -> (f64, f64) {
+    match size {
+        PageSize::A4 => (210.0, 297.0),
+        PageSize::A3 => (297.0, 420.0),
+        PageSize::Letter => (215.9, 279.4),
+        PageSize::Legal => (215.9, 355.6),
+    }
+}
+
+fn export_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    match app.path().download_dir() {
+        Ok(dir) if dir.is_dir() => Ok(dir),
+        _ => crate::logs_dir_path(app),
+    }
+}
+
+/// Write the main window's page to a new PDF and return its path. Blocks
+/// until the webview finishes, so never call it on the main thread.
+pub fn export(app: &AppHandle, options: PdfConfig) -> Result<PathBuf, String> {
+    if EXPORTING.swap(true, Ordering::SeqCst) {
+        return Err("A PDF export is already in progress".to_string());
+    }
+    let result = (|| {
+        let window = app
+            .get_webview_window("main")
+            .ok_or_else(|| "Main window not found".to_string())?;
+        let target = export_dir(app)?.join(format!(
+            "world-monitor-dashboard-{}.pdf",
+            crate::unix_timestamp_secs()
+        ));
+        let (done, finished) = mpsc::channel();
+        let path = target.clone();
+        window
+            .with_webview(move |webview| platform::print_to_pdf(webview, &path, options, done))
+            .map_err(|e| format!("Failed to export PDF: {e}"))?;
+        finished
+            .recv_timeout(EXPORT_TIMEOUT)
+            .map_err(|_| "PDF export timed out".to_string())??;
+        tracing::info!(target: "app", path = %target.display(), ?options, "dashboard exported to PDF");
+        Ok(target)
+    })();
+    EXPORTING.store(false, Ordering::SeqCst);
+    result
+}
+
+/// Export with the saved page setup and show the file. Returns immediately.
+fn export_and_reveal(app: &AppHandle) {
+    let app = app.clone();
+    let spawned = std::thread::Builder::new().name("pdf-export".into()).spawn(move || {
+        let options = app.state::<DesktopConfigState>().snapshot().pdf;
+        match export(&app, options) {
+            Ok(path) => {
+                if let Err(err) = crate::reveal_in_shell(&path) {
+                    tracing::warn!(target: "menu", "{err}");
+                }
+            }
+            Err(err) => tracing::error!(target: "menu", "PDF export failed: {err}"),
+        }
+    });
+    if let Err(err) = spawned {
+        tracing::error!(target: "menu", "failed to start PDF export: {err}");
+    }
+}
+
+/// The File menu's export item and Page Setup submenu. Checked states are
+/// synced by `sync_menu` once the config has loaded.
+pub fn file_menu_items(handle: &AppHandle) -> tauri::Result<(MenuItem<Wry>, Submenu<Wry>)> {
+    let defaults = PdfConfig::default();
+    let export_item = MenuItem::with_id(handle, MENU_EXPORT_ID, "Print / Export PDF...", true, Some("CmdOrCtrl+P"))?;
+    let size_items = PAGE_SIZES
+        .iter()
+        .map(|(size, id, label)| CheckMenuItem::with_id(handle, *id, *label, true, *size == defaults.page_size, None::<&str>))
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let landscape_item = CheckMenuItem::with_id(handle, MENU_LANDSCAPE_ID, "Landscape", true, defaults.landscape, None::<&str>)?;
+    let separator = tauri::menu::PredefinedMenuItem::separator(handle)?;
+    let mut items: Vec<&dyn IsMenuItem<Wry>> = size_items.iter().map(|item| item as &dyn IsMenuItem<Wry>).collect();
+    items.extend([&separator as &dyn IsMenuItem<Wry>, &landscape_item]);
+    let page_setup = Submenu::with_id_and_items(handle, MENU_PAGE_SETUP_ID, "Page Setup", true, &items)?;
+    Ok((export_item, page_setup))
+}
+
+/// Reflect the saved page setup in File → Page Setup.
+pub fn sync_menu(app: &AppHandle) {
+    let Some(page_setup) = app
+        .menu()
+        .and_then(|menu| menu.get(MENU_FILE_ID))
+        .and_then(|file| file.as_submenu().and_then(|file| file.get(MENU_PAGE_SETUP_ID)))
+        .and_then(|page_setup| page_setup.as_submenu().cloned())
+    else {
+        return;
+    };
+    let pdf = app.state::<DesktopConfigState>().snapshot().pdf;
+    let check = |id: &str, checked: bool| {
+        if let Some(item) = page_setup.get(id).and_then(|item| item.as_check_menuitem().cloned()) {
+            let _ = item.set_checked(checked);
+        }
+    };
+    for (size, id, _) in PAGE_SIZES {
+        check(id, size == pdf.page_size);
+    }
+    check(MENU_LANDSCAPE_ID, pdf.landscape);
+}
+
+pub fn owns_menu_id(id: &str) -> bool {
+    id.starts_with(MENU_PREFIX)
+}
+
+fn update(app: &AppHandle, change: impl FnOnce(&mut PdfConfig)) {
+    if let Err(err) = app.state::<DesktopConfigState>().update(|c| change(&mut c.pdf)) {
+        tracing::error!(target: "menu", "failed to save page setup: {err}");
+    }
+    sync_menu(app);
+}
+
+pub fn handle_menu_event(app: &AppHandle, id: &str) {
+    match id {
+        MENU_EXPORT_ID => export_and_reveal(app),
+        MENU_LANDSCAPE_ID => update(app, |pdf| pdf.landscape = !pdf.landscape),
+        _ => {
+            if let Some((size, _, _)) = PAGE_SIZES.iter().find(|(_, size_id, _)| *size_id == id) {
+                let size = *size;
+                update(app, |pdf| pdf.page_size = size);
+            }
+        }
+    }
+}
+
+/// Export the dashboard to PDF, overriding the saved page setup where given,
+/// and return the file's path.
+#[tauri::command]
+pub async fn export_pdf(
+    webview: Webview,
+    app: AppHandle,
+    page_size: Option<PageSize>,
+    landscape: Option<bool>,
+) -> Result<String, String> {
+    crate::metrics::of(&webview)
+        .observe_async("export_pdf", async move {
+            crate::require_trusted_window(webview.label())?;
+            let mut options = app.state::<DesktopConfigState>().snapshot().pdf;
+            options.page_size = page_size.unwrap_or(options.page_size);
+            options.landscape = landscape.unwrap_or(options.landscape);
+            crate::runtime::blocking(move || export(&app, options)).await
+                .map(|path| path.display().to_string())
+        })
+        .await
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::path::Path;
+
+    use tauri::webview::PlatformWebview;
+    use webview2_com::Microsoft::Web::WebView2::Win32::{
+        ICoreWebView2Environment6, ICoreWebView2_7, COREWEBVIEW2_PRINT_ORIENTATION_LANDSCAPE,
+        COREWEBVIEW2_PRINT_ORIENTATION_PORTRAIT,
+    };
+    use webview2_com::PrintToPdfCompletedHandler;
+    use windows::core::{Interface, HSTRING};
+
+    use super::{page_mm, Done};
+    use crate::config::PdfConfig;
+
+    const MM_PER_INCH: f64 = 25.4;
+
+    pub fn print_to_pdf(webview: PlatformWebview, path: &Path, options: PdfConfig, done: Done) {
+        let completed = done.clone();
+        let handler = PrintToPdfCompletedHandler::create(Box::new(move |result, written| {
+            let _ = completed.send(match result {
+                Ok(()) if written => Ok(()),
+                Ok(()) => Err("WebView2 could not write the PDF".to_string()),
+                Err(e) => Err(format!("Failed to export PDF: {e}")),
+            });
+            Ok(())
+        }));
+        let (width, height) = page_mm(options.page_size);
+        let orientation = if options.landscape {
+            COREWEBVIEW2_PRINT_ORIENTATION_LANDSCAPE
+        } else {
+            COREWEBVIEW2_PRINT_ORIENTATION_PORTRAIT
+        };
+        let started = unsafe {
+            webview
+                .environment()
+                .cast::<ICoreWebView2Environment6>()
+                .and_then(|environment| environment.CreatePrintSettings())
+                .and_then(|settings| {
+                    settings.SetPageWidth(width / MM_PER_INCH)?;
+                    settings.SetPageHeight(height / MM_PER_INCH)?;
+                    settings.SetOrientation(orientation)?;
+                    settings.SetShouldPrintBackgrounds(true)?;
+                    let core = webview.controller().CoreWebView2()?.cast::<ICoreWebView2_7>()?;
+                    core.PrintToPdf(&HSTRING::from(path), &settings, &handler)
+                })
+        };
+        if let Err(e) = started {
+            let _ = done.send(Err(format!("Failed to export PDF: {e}")));
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::cell::OnceCell;
+    use std::ffi::c_void;
+    use std::path::Path;
+
+    use objc2::rc::Retained;
+    use objc2::runtime::AnyObject;
+    use objc2::{define_class, msg_send, sel, AnyThread, MainThreadMarker, MainThreadOnly};
+    use objc2_app_kit::{NSPaperOrientation, NSPrintInfo, NSPrintJobSavingURL, NSPrintOperation, NSPrintSaveJob, NSWindow};
+    use objc2_foundation::{NSObject, NSSize, NSURL};
+    use objc2_web_kit::WKWebView;
+    use tauri::webview::PlatformWebview;
+
+    use super::{page_mm, Done};
+    use crate::config::PdfConfig;
+
+    const POINTS_PER_MM: f64 = 72.0 / 25.4;
+
+    // Receives the print operation's completion; `context` is the boxed
+    // `Done` sender for that export.
+    define_class!(
+        #[unsafe(super(NSObject))]
+        #[thread_kind = MainThreadOnly]
+        #[name = "WorldMonitorPdfExportDelegate"]
+        struct PdfExportDelegate;
+
+        impl PdfExportDelegate {
+            #[unsafe(method(printOperationDidRun:success:contextInfo:))]
+            fn did_run(&self, _operation: &NSPrintOperation, success: bool, context: *mut c_void) {
+                let done = unsafe { Box::from_raw(context.cast::<Done>()) };
+                let _ = done.send(if success {
+                    Ok(())
+                } else {
+                    Err("The print operation did not complete".to_string())
+                });
+            }
+        }
+    );
+
+    thread_local! {
+        // Print operations hold their delegate weakly, so keep it alive here.
+        static DELEGATE: OnceCell<Retained<PdfExportDelegate>> = const { OnceCell::new() };
+    }
+
+    pub fn print_to_pdf(webview: PlatformWebview, path: &Path, options: PdfConfig, done: Done) {
+        let Some(mtm) = MainThreadMarker::new() else {
+            let _ = done.send(Err("PDF export must start on the main thread".to_string()));
+            return;
+        };
+        let Some(url) = NSURL::from_file_path(path) else {
+            let _ = done.send(Err(format!("Invalid export path: {}", path.display())));
+            return;
+        };
+        let (width, height) = page_mm(options.page_size);
+        let info = NSPrintInfo::init(NSPrintInfo::alloc());
+        info.setPaperSize(NSSize::new(width * POINTS_PER_MM, height * POINTS_PER_MM));
+        info.setOrientation(if options.landscape {
+            NSPaperOrientation::Landscape
+        } else {
+            NSPaperOrientation::Portrait
+        });
+        unsafe {
+            info.setJobDisposition(NSPrintSaveJob);
+            let url: &AnyObject = &url;
+            info.dictionary().insert(NSPrintJobSavingURL, url);
+        }
+        let wk_webview: &WKWebView = unsafe { &*webview.inner().cast() };
+        let window: &NSWindow = unsafe { &*webview.ns_window().cast() };
+        let operation = unsafe { wk_webview.printOperationWithPrintInfo(&info) };
+        operation.setShowsPrintPanel(false);
+        operation.setShowsProgressPanel(false);
+        // Without an explicit frame WebKit lays the page out at zero size
+        // and prints blank pages.
+        if let Some(view) = operation.view() {
+            view.setFrame(wk_webview.bounds());
+        }
+        let context = Box::into_raw(Box::new(done)).cast::<c_void>();
+        DELEGATE.with(|delegate| {
+            let delegate = delegate.get_or_init(|| unsafe { msg_send![PdfExportDelegate::alloc(mtm), init] });
+            let delegate: &AnyObject = delegate.as_ref();
+            unsafe {
+                operation.runOperationModalForWindow_delegate_didRunSelector_contextInfo(
+                    window,
+                    Some(delegate),
+                    Some(sel!(printOperationDidRun:success:contextInfo:)),
+                    context,
+                );
+            }
+        });
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use std::path::Path;
+
+    use tauri::webview::PlatformWebview;
+    use webkit2gtk::PrintOperationExt;
+
+    use super::{page_mm, Done};
+    use crate::config::PdfConfig;
+
+    /// GTK's built-in file "printer".
+    const PRINT_TO_FILE_PRINTER: &str = "Print to File";
+
+    pub fn print_to_pdf(webview: PlatformWebview, path: &Path, options: PdfConfig, done: Done) {
+        let uri = match gtk::glib::filename_to_uri(path, None) {
+            Ok(uri) => uri,
+            Err(e) => {
+                let _ = done.send(Err(format!("Invalid export path {}: {e}", path.display())));
+                return;
+            }
+        };
+        let (width, height) = page_mm(options.page_size);
+        let paper = gtk::PaperSize::new_custom("world-monitor", "World Monitor", width, height, gtk::Unit::Mm);
+        let orientation = if options.landscape {
+            gtk::PageOrientation::Landscape
+        } else {
+            gtk::PageOrientation::Portrait
+        };
+
+        let settings = gtk::PrintSettings::new();
+        settings.set_printer(PRINT_TO_FILE_PRINTER);
+        settings.set(gtk::PRINT_SETTINGS_OUTPUT_FILE_FORMAT, Some("pdf"));
+        settings.set(gtk::PRINT_SETTINGS_OUTPUT_URI, Some(&uri));
+        settings.set_paper_size(&paper);
+        settings.set_orientation(orientation);
+        let page_setup = gtk::PageSetup::new();
+        page_setup.set_paper_size(&paper);
+        page_setup.set_orientation(orientation);
+
+        let operation = webkit2gtk::PrintOperation::new(&webview.inner());
+        operation.set_print_settings(&settings);
+        operation.set_page_setup(&page_setup);
+        // A failure is followed by `finished`; the receiver only reads the first result.
+        let failed = done.clone();
+        operation.connect_failed(move |_, error| {
+            let _ = failed.send(Err(format!("Failed to export PDF: {error}")));
+        });
+        operation.connect_finished(move |_| {
+            let _ = done.send(Ok(()));
+        });
+        operation.print();
+    }
+}
+
+#[cfg(test)]
+mod pdf_export_tests {
+    use super::page_mm;
+    use crate::config::PageSize;
+
+    #[test]
+    fn page_sizes_are_portrait() {
+        for size in [PageSize::A4, PageSize::A3, PageSize::Letter, PageSize::Legal] {
+            let (width, height) = page_mm(size);
+            assert!(width < height, "{size:?}");
+        }
+    }
+}
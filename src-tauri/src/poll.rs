@@ -0,0 +1,100 @@
+//! The schedule every background poller runs on, matching the dashboard's
+//! refresh scheduler. Rounds are held while monitoring is paused or the
+//! network is offline, and run as soon as both clear; the wait between
+//! rounds stretches while the machine is idle and by the battery
+//! multiplier. A poller only says how to run one round and how long to
+//! wait after it; `run` does the rest, so nothing is fetched (or logged as
+//! failing) while no one could use it.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use tauri::AppHandle;
+use tokio::sync::watch;
+
+/// As `IDLE_REFRESH_MULTIPLIER` in the dashboard.
+const IDLE_MULTIPLIER: u32 = 4;
+/// How often a held or waiting poller looks at the gates again, so pausing,
+/// reconnecting, going idle or unplugging take effect within seconds.
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+pub trait Poller: Send {
+    /// Run one round; returns how long to wait before the next at full
+    /// cadence.
+    fn round(&mut self) -> impl Future<Output = Duration> + Send;
+}
+
+/// Whether rounds are held: monitoring paused or no connectivity.
+pub fn held(app: &AppHandle) -> bool {
+    crate::monitoring::is_paused(app) || !crate::network::is_online(app)
+}
+
+fn stretched(wait: Duration, battery_multiplier: u32, idle: bool) -> Duration {
+    wait * battery_multiplier.max(1) * if idle { IDLE_MULTIPLIER } else { 1 }
+}
+
+enum Wake {
+    Elapsed,
+    Triggered,
+    Closed,
+}
+
+async fn sleep<T>(trigger: &mut Option<watch::Receiver<T>>, duration: Duration) -> Wake {
+    let Some(trigger) = trigger else {
+        tokio::time::sleep(duration).await;
+        return Wake::Elapsed;
+    };
+    tokio::select! {
+        _ = tokio::time::sleep(duration) => Wake::Elapsed,
+        changed = trigger.changed() => if changed.is_ok() { Wake::Triggered } else { Wake::Closed },
+    }
+}
+
+/// Run `poller` for good.
+pub async fn run(app: &AppHandle, poller: impl Poller) {
+    schedule(app, None::<watch::Receiver<()>>, poller).await;
+}
+
+/// Run `poller` until `trigger` closes. A value sent on `trigger` cuts the
+/// wait short, e.g. for a "Sync now" command or a new area to poll.
+pub async fn run_triggered<T: Send + Sync>(app: &AppHandle, trigger: watch::Receiver<T>, poller: impl Poller) {
+    schedule(app, Some(trigger), poller).await;
+}
+
+async fn schedule<T: Send + Sync>(app: &AppHandle, mut trigger: Option<watch::Receiver<T>>, mut poller: impl Poller) {
+    loop {
+        while held(app) {
+            if let Wake::Closed = sleep(&mut trigger, CHECK_INTERVAL).await {
+                return;
+            }
+        }
+        let wait = poller.round().await;
+        let finished = Instant::now();
+        loop {
+            let wait = stretched(wait, crate::power::current(app).refresh_multiplier, crate::idle::is_idle(app));
+            let remaining = wait.saturating_sub(finished.elapsed());
+            if remaining.is_zero() {
+                break;
+            }
+            match sleep(&mut trigger, remaining.min(CHECK_INTERVAL)).await {
+                Wake::Elapsed => {}
+                Wake::Triggered => break,
+                Wake::Closed => return,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod poll_tests {
+    use super::*;
+
+    #[test]
+    fn stretches_for_battery_and_idle() {
+        let wait = Duration::from_secs(60);
+        assert_eq!(stretched(wait, 1, false), wait);
+        assert_eq!(stretched(wait, 0, false), wait);
+        assert_eq!(stretched(wait, 3, false), Duration::from_secs(180));
+        assert_eq!(stretched(wait, 2, true), Duration::from_secs(480));
+    }
+}
@@ -0,0 +1,375 @@
+//! Battery-aware polling. A background thread reads whether the machine is
+//! running on battery and how charged it is, and broadcasts `app://power`
+//! whenever that changes. On battery the dashboard stretches its refresh
+//! intervals by `power.battery_refresh_multiplier` and the sidecar is told
+//! to spend less CPU per response (via `/api/local-power-mode`). A
+//! multiplier of 1 turns battery saving off.
+//!
+//! Power state comes from `GetSystemPowerStatus` on Windows, `pmset` on
+//! macOS and `/sys/class/power_supply` on Linux. Desktops without a battery
+//! always report AC power.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+use crate::config::{DesktopConfigState, PowerConfig};
+
+pub const POWER_EVENT: &str = "app://power";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+const MAX_REFRESH_MULTIPLIER: u32 = 8;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PowerSource {
+    pub on_battery: bool,
+    /// Charge of the system battery, if there is one.
+    pub battery_percent: Option<u8>,
+}
+
+/// What the frontend sees: the power source plus how much to slow refreshes
+/// because of it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct PowerStatus {
+    pub on_battery: bool,
+    pub battery_percent: Option<u8>,
+    /// 1 on AC power or when battery saving is off.
+    pub refresh_multiplier: u32,
+}
+
+#[derive(Default)]
+pub struct PowerState {
+    source: Mutex<Option<PowerSource>>,
+    /// Last low-power flag sent to the sidecar.
+    sidecar_low_power: AtomicBool,
+}
+
+pub fn clamp_multiplier(multiplier: u32) -> u32 {
+    multiplier.clamp(1, MAX_REFRESH_MULTIPLIER)
+}
+
+fn status(source: PowerSource, config: &PowerConfig) -> PowerStatus {
+    PowerStatus {
+        on_battery: source.on_battery,
+        battery_percent: source.battery_percent,
+        refresh_multiplier: if source.on_battery {
+            clamp_multiplier(config.battery_refresh_multiplier)
+        } else {
+            1
+        },
+    }
+}
+
+fn source(app: &AppHandle) -> PowerSource {
+    app.try_state::<PowerState>()
+        .and_then(|state| *state.source.lock().unwrap_or_else(|e| e.into_inner()))
+        .or_else(platform::read)
+        .unwrap_or_default()
+}
+
+pub fn current(app: &AppHandle) -> PowerStatus {
+    status(source(app), &app.state::<DesktopConfigState>().snapshot().power)
+}
+
+/// Whether the sidecar should start in low-power mode.
+pub fn sidecar_low_power(app: &AppHandle) -> bool {
+    let low_power = current(app).refresh_multiplier > 1;
+    if let Some(state) = app.try_state::<PowerState>() {
+        state.sidecar_low_power.store(low_power, Ordering::SeqCst);
+    }
+    low_power
+}
+
+/// Emit the current status and, when it flipped, tell the sidecar.
+fn broadcast(app: &AppHandle) {
+    let status = current(app);
+    let _ = app.emit(POWER_EVENT, status);
+    let low_power = status.refresh_multiplier > 1;
+    let Some(state) = app.try_state::<PowerState>() else {
+        return;
+    };
+    if state.sidecar_low_power.swap(low_power, Ordering::SeqCst) == low_power {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let body = serde_json::json!({ "lowPower": low_power });
+        if let Err(err) = crate::post_local_api_control(&app, "/api/local-power-mode", body).await {
+            tracing::warn!(target: "sidecar", "failed to forward power mode to sidecar: {err}");
+        }
+    });
+}
+
+fn run(app: AppHandle) {
+    let state = app.state::<PowerState>();
+    let mut unavailable_logged = false;
+    loop {
+        match platform::read() {
+            Some(source) => {
+                let previous = state.source.lock().unwrap_or_else(|e| e.into_inner()).replace(source);
+                if previous != Some(source) {
+                    if previous.map(|p| p.on_battery) != Some(source.on_battery) {
+                        tracing::info!(
+                            target: "app",
+                            on_battery = source.on_battery,
+                            battery_percent = source.battery_percent,
+                            "power source changed"
+                        );
+                    }
+                    broadcast(&app);
+                }
+            }
+            None if !unavailable_logged => {
+                unavailable_logged = true;
+                tracing::info!(target: "app", "power source unavailable; assuming AC power");
+            }
+            None => {}
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Start the power monitor thread.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    if let Err(e) = std::thread::Builder::new()
+        .name("power-monitor".into())
+        .spawn(move || run(app))
+    {
+        tracing::warn!(target: "app", "failed to start power monitor: {e}");
+    }
+}
+
+/// One entry under `/sys/class/power_supply`.
+#[cfg_attr(not(any(target_os = "linux", test)), allow(dead_code))]
+struct Supply {
+    kind: String,
+    online: bool,
+    status: String,
+    capacity: Option<u8>,
+    /// Peripheral batteries (mice, headsets) have `scope` `Device`.
+    device_scope: bool,
+}
+
+/// On battery when no external supply is online and a system battery is
+/// discharging.
+#[cfg_attr(not(any(target_os = "linux", test)), allow(dead_code))]
+fn from_supplies(supplies: &[Supply]) -> Option<PowerSource> {
+    if supplies.is_empty() {
+        return None;
+    }
+    let batteries: Vec<&Supply> = supplies
+        .iter()
+        .filter(|s| s.kind == "Battery" && !s.device_scope)
+        .collect();
+    let external_online = supplies.iter().any(|s| s.kind != "Battery" && s.online);
+    let discharging = batteries.iter().any(|s| s.status == "Discharging");
+    let capacities: Vec<u32> = batteries.iter().filter_map(|s| s.capacity).map(u32::from).collect();
+    let battery_percent = (!capacities.is_empty())
+        .then(|| (capacities.iter().sum::<u32>() / capacities.len() as u32).min(100) as u8);
+    Some(PowerSource {
+        on_battery: !external_online && discharging,
+        battery_percent,
+    })
+}
+
+/// Parse `pmset -g batt`: a `Now drawing from 'Battery Power'` line, then
+/// one line per battery with its charge, e.g. `85%; discharging`.
+#[cfg_attr(not(any(target_os = "macos", test)), allow(dead_code))]
+fn parse_pmset(output: &str) -> Option<PowerSource> {
+    let mut lines = output.lines();
+    let source = lines.next()?.split('\'').nth(1)?;
+    let battery_percent = lines
+        .flat_map(|line| line.split(|c: char| c.is_whitespace() || c == ';'))
+        .find_map(|token| token.strip_suffix('%')?.parse::<u8>().ok())
+        .map(|percent| percent.min(100));
+    Some(PowerSource {
+        on_battery: source == "Battery Power",
+        battery_percent,
+    })
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::fs;
+    use std::path::Path;
+
+    use super::{PowerSource, Supply};
+
+    const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+    fn attribute(dir: &Path, name: &str) -> String {
+        fs::read_to_string(dir.join(name)).map(|v| v.trim().to_string()).unwrap_or_default()
+    }
+
+    pub fn read() -> Option<PowerSource> {
+        let supplies: Vec<Supply> = fs::read_dir(POWER_SUPPLY_DIR)
+            .ok()?
+            .flatten()
+            .map(|entry| {
+                let dir = entry.path();
+                Supply {
+                    kind: attribute(&dir, "type"),
+                    online: attribute(&dir, "online") == "1",
+                    status: attribute(&dir, "status"),
+                    capacity: attribute(&dir, "capacity").parse().ok(),
+                    device_scope: attribute(&dir, "scope") == "Device",
+                }
+            })
+            .collect();
+        super::from_supplies(&supplies)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::process::Command;
+
+    use super::PowerSource;
+
+    pub fn read() -> Option<PowerSource> {
+        let output = Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        super::parse_pmset(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    use super::PowerSource;
+
+    const AC_OFFLINE: u8 = 0;
+    const NO_SYSTEM_BATTERY: u8 = 128;
+    const UNKNOWN_PERCENT: u8 = 255;
+
+    pub fn read() -> Option<PowerSource> {
+        let mut status = SYSTEM_POWER_STATUS::default();
+        unsafe { GetSystemPowerStatus(&mut status) }.ok()?;
+        let has_battery = status.BatteryFlag & NO_SYSTEM_BATTERY == 0;
+        Some(PowerSource {
+            on_battery: has_battery && status.ACLineStatus == AC_OFFLINE,
+            battery_percent: (has_battery && status.BatteryLifePercent != UNKNOWN_PERCENT)
+                .then_some(status.BatteryLifePercent.min(100)),
+        })
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    pub fn read() -> Option<super::PowerSource> {
+        None
+    }
+}
+
+#[tauri::command]
+pub fn get_power_status(webview: Webview, app: AppHandle) -> Result<PowerStatus, String> {
+    crate::metrics::of(&webview).observe("get_power_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(current(&app))
+    })
+}
+
+#[tauri::command]
+pub fn get_battery_refresh_multiplier(
+    webview: Webview,
+    config: tauri::State<'_, DesktopConfigState>,
+) -> Result<u32, String> {
+    crate::metrics::of(&webview).observe("get_battery_refresh_multiplier", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(clamp_multiplier(config.snapshot().power.battery_refresh_multiplier))
+    })
+}
+
+/// How many times slower to refresh on battery; 1 refreshes at the normal
+/// rate. Returns the stored value.
+#[tauri::command]
+pub async fn set_battery_refresh_multiplier(
+    webview: Webview,
+    app: AppHandle,
+    multiplier: u32,
+) -> Result<u32, String> {
+    crate::metrics::of(&webview)
+        .observe_async("set_battery_refresh_multiplier", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                let config = app.state::<DesktopConfigState>();
+                let multiplier = clamp_multiplier(multiplier);
+                config.update(|c| c.power.battery_refresh_multiplier = multiplier)?;
+                tracing::info!(target: "app", multiplier, "battery refresh multiplier changed");
+                broadcast(&app);
+                Ok(multiplier)
+            })
+            .await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod power_tests {
+    use super::*;
+
+    fn supply(kind: &str, online: bool, status: &str, capacity: Option<u8>) -> Supply {
+        Supply {
+            kind: kind.to_string(),
+            online,
+            status: status.to_string(),
+            capacity,
+            device_scope: false,
+        }
+    }
+
+    #[test]
+    fn reads_linux_power_supplies() {
+        let unplugged = [
+            supply("Mains", false, "", None),
+            supply("Battery", false, "Discharging", Some(80)),
+            supply("Battery", false, "Discharging", Some(60)),
+        ];
+        assert_eq!(
+            from_supplies(&unplugged),
+            Some(PowerSource { on_battery: true, battery_percent: Some(70) })
+        );
+
+        let charging = [supply("Mains", true, "", None), supply("Battery", false, "Charging", Some(40))];
+        assert!(!from_supplies(&charging).unwrap().on_battery);
+
+        let mut mouse = supply("Battery", false, "Discharging", Some(10));
+        mouse.device_scope = true;
+        assert_eq!(
+            from_supplies(&[supply("Mains", true, "", None), mouse]),
+            Some(PowerSource { on_battery: false, battery_percent: None })
+        );
+        assert_eq!(from_supplies(&[]), None);
+    }
+
+    #[test]
+    fn parses_pmset() {
+        let battery = "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=4653155)\t85%; discharging; 4:12 remaining present: true\n";
+        assert_eq!(
+            parse_pmset(battery),
+            Some(PowerSource { on_battery: true, battery_percent: Some(85) })
+        );
+        assert_eq!(
+            parse_pmset("Now drawing from 'AC Power'\n"),
+            Some(PowerSource { on_battery: false, battery_percent: None })
+        );
+        assert_eq!(parse_pmset(""), None);
+    }
+
+    #[test]
+    fn multiplier_applies_only_on_battery() {
+        let config = |battery_refresh_multiplier| PowerConfig { battery_refresh_multiplier };
+        let on_battery = PowerSource { on_battery: true, battery_percent: Some(50) };
+        assert_eq!(status(on_battery, &config(2)).refresh_multiplier, 2);
+        assert_eq!(status(on_battery, &config(0)).refresh_multiplier, 1);
+        assert_eq!(status(on_battery, &config(100)).refresh_multiplier, MAX_REFRESH_MULTIPLIER);
+        assert_eq!(status(PowerSource::default(), &config(4)).refresh_multiplier, 1);
+    }
+}
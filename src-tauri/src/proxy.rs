@@ -0,0 +1,485 @@
+//! System proxy detection. `HTTP(S)_PROXY`/`NO_PROXY` in the environment win;
+//! otherwise the OS settings are read (WinINet's per-user settings on
+//! Windows, `scutil --proxy` on macOS, GNOME's `org.gnome.system.proxy` on
+//! Linux). `get_system_proxy` reports the result to the frontend with
+//! credentials stripped, and the sidecar launcher passes a manual proxy on
+//! as environment variables that Node's `NODE_USE_ENV_PROXY` honors.
+//!
+//! Proxy auto-config (PAC) scripts and auto-discovery are reported but not
+//! evaluated: the sidecar cannot use them, so they are logged as the likely
+//! cause when it cannot reach upstream feeds.
+
+use std::process::Command;
+
+use serde::Serialize;
+use tauri::Webview;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxySource {
+    #[default]
+    None,
+    Environment,
+    System,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct SystemProxy {
+    pub source: ProxySource,
+    pub http: Option<String>,
+    pub https: Option<String>,
+    /// Hosts that bypass the proxy, as configured.
+    pub bypass: Vec<String>,
+    pub pac_url: Option<String>,
+    /// WPAD auto-discovery.
+    pub auto_detect: bool,
+}
+
+impl SystemProxy {
+    fn is_configured(&self) -> bool {
+        self.http.is_some() || self.https.is_some() || self.pac_url.is_some() || self.auto_detect
+    }
+
+    /// Environment for the sidecar. An environment proxy is already
+    /// inherited and only needs Node told to use it.
+    pub fn sidecar_env(&self) -> Vec<(&'static str, String)> {
+        let mut env = Vec::new();
+        if self.http.is_none() && self.https.is_none() {
+            return env;
+        }
+        env.push(("NODE_USE_ENV_PROXY", "1".to_string()));
+        if self.source != ProxySource::System {
+            return env;
+        }
+        if let Some(http) = &self.http {
+            env.push(("HTTP_PROXY", http.clone()));
+        }
+        if let Some(https) = &self.https {
+            env.push(("HTTPS_PROXY", https.clone()));
+        }
+        // The dashboard reaches the sidecar over loopback; never proxy that.
+        let mut no_proxy = vec!["localhost".to_string(), "127.0.0.1".to_string(), "::1".to_string()];
+        no_proxy.extend(
+            self.bypass
+                .iter()
+                // Windows' `<local>` has no NO_PROXY equivalent.
+                .filter(|host| !host.starts_with('<'))
+                .map(|host| host.strip_prefix('*').unwrap_or(host).to_string()),
+        );
+        env.push(("NO_PROXY", no_proxy.join(",")));
+        env
+    }
+
+    /// A copy safe to show or log, without `user:password@` in proxy URLs.
+    pub fn redacted(&self) -> SystemProxy {
+        SystemProxy {
+            http: self.http.as_deref().map(strip_credentials),
+            https: self.https.as_deref().map(strip_credentials),
+            pac_url: self.pac_url.as_deref().map(strip_credentials),
+            ..self.clone()
+        }
+    }
+}
+
+fn strip_credentials(url: &str) -> String {
+    let (scheme, rest) = url.split_once("://").unwrap_or(("", url));
+    let authority_end = rest.find('/').unwrap_or(rest.len());
+    match rest[..authority_end].rfind('@') {
+        Some(at) if scheme.is_empty() => rest[at + 1..].to_string(),
+        Some(at) => format!("{scheme}://{}", &rest[at + 1..]),
+        None => url.to_string(),
+    }
+}
+
+/// `host:port` as OS settings store it, or a full URL, as a proxy URL.
+fn proxy_url(server: &str) -> Option<String> {
+    let server = server.trim();
+    if server.is_empty() {
+        None
+    } else if server.contains("://") {
+        Some(server.to_string())
+    } else {
+        Some(format!("http://{server}"))
+    }
+}
+
+fn host_port(host: &str, port: Option<&str>) -> Option<String> {
+    let host = host.trim();
+    if host.is_empty() {
+        return None;
+    }
+    match port.map(str::trim).filter(|p| !p.is_empty() && *p != "0") {
+        Some(port) => proxy_url(&format!("{host}:{port}")),
+        None => proxy_url(host),
+    }
+}
+
+fn from_env(var: impl Fn(&str) -> Option<String>) -> Option<SystemProxy> {
+    let get = |name: &str| {
+        var(name)
+            .or_else(|| var(&name.to_ascii_lowercase()))
+            .filter(|v| !v.trim().is_empty())
+    };
+    let all = get("ALL_PROXY");
+    let http = get("HTTP_PROXY").or_else(|| all.clone());
+    let https = get("HTTPS_PROXY").or(all);
+    if http.is_none() && https.is_none() {
+        return None;
+    }
+    let bypass = get("NO_PROXY")
+        .map(|list| list.split(',').map(str::trim).filter(|h| !h.is_empty()).map(String::from).collect())
+        .unwrap_or_default();
+    Some(SystemProxy {
+        source: ProxySource::Environment,
+        http: http.as_deref().and_then(proxy_url),
+        https: https.as_deref().and_then(proxy_url),
+        bypass,
+        ..SystemProxy::default()
+    })
+}
+
+/// Current proxy configuration. Spawns `scutil`/`gsettings` on macOS and
+/// Linux; call off the main thread.
+pub fn detect() -> SystemProxy {
+    if let Some(proxy) = from_env(|name| std::env::var(name).ok()) {
+        return proxy;
+    }
+    let mut proxy = platform::detect();
+    if proxy.is_configured() {
+        proxy.source = ProxySource::System;
+    }
+    proxy
+}
+
+/// Log what the sidecar will run behind and return its extra environment.
+pub fn sidecar_env() -> Vec<(&'static str, String)> {
+    let proxy = detect();
+    let shown = proxy.redacted();
+    if shown.is_configured() {
+        tracing::info!(
+            target: "sidecar",
+            source = ?shown.source,
+            http = shown.http.as_deref().unwrap_or("-"),
+            https = shown.https.as_deref().unwrap_or("-"),
+            "proxy detected"
+        );
+    }
+    if let Some(pac) = &shown.pac_url {
+        tracing::warn!(target: "sidecar", pac, "proxy auto-config is not applied to the local API");
+    } else if shown.auto_detect {
+        tracing::warn!(target: "sidecar", "proxy auto-discovery is not applied to the local API");
+    }
+    proxy.sidecar_env()
+}
+
+#[cfg_attr(windows, allow(dead_code))]
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parse `scutil --proxy`, a dump of the SystemConfiguration proxy dictionary.
+#[cfg_attr(not(any(target_os = "macos", test)), allow(dead_code))]
+fn parse_scutil(output: &str) -> SystemProxy {
+    let mut values = std::collections::HashMap::new();
+    let mut bypass = Vec::new();
+    let mut in_exceptions = false;
+    for line in output.lines().map(str::trim) {
+        if in_exceptions {
+            if line == "}" {
+                in_exceptions = false;
+            } else if let Some((_, host)) = line.split_once(" : ") {
+                bypass.push(host.trim().to_string());
+            }
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(" : ") {
+            if key == "ExceptionsList" {
+                in_exceptions = true;
+            } else {
+                values.insert(key.trim(), value.trim());
+            }
+        }
+    }
+    let enabled = |key: &str| values.get(key) == Some(&"1");
+    let server = |prefix: &str| {
+        if !enabled(&format!("{prefix}Enable")) {
+            return None;
+        }
+        let host = values.get(format!("{prefix}Proxy").as_str())?;
+        host_port(host, values.get(format!("{prefix}Port").as_str()).copied())
+    };
+    SystemProxy {
+        http: server("HTTP"),
+        https: server("HTTPS"),
+        bypass,
+        pac_url: values
+            .get("ProxyAutoConfigURLString")
+            .filter(|_| enabled("ProxyAutoConfigEnable"))
+            .map(|url| url.to_string()),
+        auto_detect: enabled("ProxyAutoDiscoveryEnable"),
+        ..SystemProxy::default()
+    }
+}
+
+/// Strip GVariant quoting from a `gsettings get` string value.
+#[cfg_attr(not(any(target_os = "linux", test)), allow(dead_code))]
+fn gvariant_string(value: &str) -> String {
+    value.trim().trim_matches('\'').to_string()
+}
+
+#[cfg_attr(not(any(target_os = "linux", test)), allow(dead_code))]
+fn gvariant_string_list(value: &str) -> Vec<String> {
+    let value = value.trim();
+    let inner = value.strip_prefix("@as ").unwrap_or(value);
+    inner
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(gvariant_string)
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Read GNOME's proxy settings through `get(schema, key)`.
+#[cfg_attr(not(any(target_os = "linux", test)), allow(dead_code))]
+fn from_gnome(get: impl Fn(&str, &str) -> Option<String>) -> SystemProxy {
+    const SCHEMA: &str = "org.gnome.system.proxy";
+    match get(SCHEMA, "mode").map(|mode| gvariant_string(&mode)).as_deref() {
+        Some("manual") => {
+            let server = |protocol: &str| {
+                let schema = format!("{SCHEMA}.{protocol}");
+                let host = gvariant_string(&get(&schema, "host")?);
+                host_port(&host, get(&schema, "port").as_deref())
+            };
+            SystemProxy {
+                http: server("http"),
+                https: server("https"),
+                bypass: get(SCHEMA, "ignore-hosts")
+                    .map(|hosts| gvariant_string_list(&hosts))
+                    .unwrap_or_default(),
+                ..SystemProxy::default()
+            }
+        }
+        Some("auto") => {
+            let pac_url = get(SCHEMA, "autoconfig-url")
+                .map(|url| gvariant_string(&url))
+                .filter(|url| !url.is_empty());
+            SystemProxy {
+                auto_detect: pac_url.is_none(),
+                pac_url,
+                ..SystemProxy::default()
+            }
+        }
+        _ => SystemProxy::default(),
+    }
+}
+
+/// Parse WinINet's `ProxyServer` value: one `host:port` for every protocol,
+/// or `http=host:port;https=host:port;…`.
+#[cfg_attr(not(any(windows, test)), allow(dead_code))]
+fn parse_wininet_servers(value: &str) -> (Option<String>, Option<String>) {
+    if !value.contains('=') {
+        let server = proxy_url(value);
+        return (server.clone(), server);
+    }
+    let mut http = None;
+    let mut https = None;
+    for entry in value.split(';') {
+        match entry.trim().split_once('=') {
+            Some(("http", server)) => http = proxy_url(server),
+            Some(("https", server)) => https = proxy_url(server),
+            _ => {}
+        }
+    }
+    (http, https)
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::SystemProxy;
+
+    pub fn detect() -> SystemProxy {
+        super::command_output("scutil", &["--proxy"])
+            .map(|output| super::parse_scutil(&output))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::SystemProxy;
+
+    pub fn detect() -> SystemProxy {
+        super::from_gnome(|schema, key| super::command_output("gsettings", &["get", schema, key]))
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::{GlobalFree, HGLOBAL};
+    use windows::Win32::Networking::WinHttp::{
+        WinHttpGetIEProxyConfigForCurrentUser, WINHTTP_CURRENT_USER_IE_PROXY_CONFIG,
+    };
+
+    use super::SystemProxy;
+
+    /// Copy out and free a string WinHTTP allocated.
+    fn take(value: PWSTR) -> Option<String> {
+        if value.is_null() {
+            return None;
+        }
+        let text = unsafe { value.to_string() }.ok();
+        unsafe {
+            let _ = GlobalFree(Some(HGLOBAL(value.0.cast())));
+        }
+        text.filter(|text| !text.trim().is_empty())
+    }
+
+    pub fn detect() -> SystemProxy {
+        let mut config = WINHTTP_CURRENT_USER_IE_PROXY_CONFIG::default();
+        if let Err(err) = unsafe { WinHttpGetIEProxyConfigForCurrentUser(&mut config) } {
+            tracing::debug!(target: "app", "failed to read proxy settings: {err}");
+            return SystemProxy::default();
+        }
+        let pac_url = take(config.lpszAutoConfigUrl);
+        let servers = take(config.lpszProxy);
+        let bypass = take(config.lpszProxyBypass);
+        let (http, https) = servers
+            .as_deref()
+            .map(super::parse_wininet_servers)
+            .unwrap_or_default();
+        SystemProxy {
+            http,
+            https,
+            bypass: bypass
+                .map(|list| {
+                    list.split([';', ' '])
+                        .filter(|host| !host.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            pac_url,
+            auto_detect: config.fAutoDetect.as_bool(),
+            ..SystemProxy::default()
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+mod platform {
+    pub fn detect() -> super::SystemProxy {
+        super::SystemProxy::default()
+    }
+}
+
+#[tauri::command]
+pub async fn get_system_proxy(webview: Webview) -> Result<SystemProxy, String> {
+    crate::metrics::of(&webview)
+        .observe_async("get_system_proxy", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(|| Ok(detect().redacted())).await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod proxy_tests {
+    use super::*;
+
+    #[test]
+    fn environment_proxy_falls_back_to_all_proxy() {
+        let proxy = from_env(|name| match name {
+            "all_proxy" => Some("socks5://proxy:1080".to_string()),
+            "HTTPS_PROXY" => Some("proxy.corp:3128".to_string()),
+            "NO_PROXY" => Some("localhost, .corp".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(proxy.source, ProxySource::Environment);
+        assert_eq!(proxy.http.as_deref(), Some("socks5://proxy:1080"));
+        assert_eq!(proxy.https.as_deref(), Some("http://proxy.corp:3128"));
+        assert_eq!(proxy.bypass, ["localhost", ".corp"]);
+        assert!(from_env(|_| None).is_none());
+    }
+
+    #[test]
+    fn parses_scutil_dictionary() {
+        let output = "<dictionary> {\n  ExceptionsList : <array> {\n    0 : *.local\n    1 : 169.254/16\n  }\n  FTPPassive : 1\n  HTTPEnable : 1\n  HTTPPort : 8080\n  HTTPProxy : proxy.corp\n  HTTPSEnable : 0\n  HTTPSProxy : ignored.corp\n  ProxyAutoConfigEnable : 1\n  ProxyAutoConfigURLString : http://wpad.corp/proxy.pac\n  ProxyAutoDiscoveryEnable : 0\n}\n";
+        let proxy = parse_scutil(output);
+        assert_eq!(proxy.http.as_deref(), Some("http://proxy.corp:8080"));
+        assert_eq!(proxy.https, None);
+        assert_eq!(proxy.bypass, ["*.local", "169.254/16"]);
+        assert_eq!(proxy.pac_url.as_deref(), Some("http://wpad.corp/proxy.pac"));
+        assert!(!proxy.auto_detect);
+    }
+
+    #[test]
+    fn parses_gnome_settings() {
+        let manual = from_gnome(|schema, key| {
+            match (schema, key) {
+                ("org.gnome.system.proxy", "mode") => Some("'manual'\n"),
+                ("org.gnome.system.proxy", "ignore-hosts") => Some("['localhost', '127.0.0.0/8']\n"),
+                ("org.gnome.system.proxy.https", "host") => Some("'proxy.corp'\n"),
+                ("org.gnome.system.proxy.https", "port") => Some("3128\n"),
+                ("org.gnome.system.proxy.http", "host") => Some("''\n"),
+                _ => None,
+            }
+            .map(String::from)
+        });
+        assert_eq!(manual.http, None);
+        assert_eq!(manual.https.as_deref(), Some("http://proxy.corp:3128"));
+        assert_eq!(manual.bypass, ["localhost", "127.0.0.0/8"]);
+
+        let auto = from_gnome(|_, key| match key {
+            "mode" => Some("'auto'".to_string()),
+            "autoconfig-url" => Some("''".to_string()),
+            _ => None,
+        });
+        assert!(auto.auto_detect);
+        assert_eq!(auto.pac_url, None);
+    }
+
+    #[test]
+    fn parses_wininet_proxy_server() {
+        assert_eq!(
+            parse_wininet_servers("proxy.corp:8080"),
+            (Some("http://proxy.corp:8080".into()), Some("http://proxy.corp:8080".into()))
+        );
+        assert_eq!(
+            parse_wininet_servers("ftp=ftp.corp:21;https=secure.corp:443"),
+            (None, Some("http://secure.corp:443".into()))
+        );
+    }
+
+    #[test]
+    fn system_proxy_env_for_sidecar() {
+        let proxy = SystemProxy {
+            source: ProxySource::System,
+            https: Some("http://proxy.corp:3128".into()),
+            bypass: vec!["*.corp".into(), "<local>".into()],
+            ..SystemProxy::default()
+        };
+        assert_eq!(
+            proxy.sidecar_env(),
+            [
+                ("NODE_USE_ENV_PROXY", "1".to_string()),
+                ("HTTPS_PROXY", "http://proxy.corp:3128".to_string()),
+                ("NO_PROXY", "localhost,127.0.0.1,::1,.corp".to_string()),
+            ]
+        );
+        assert!(SystemProxy::default().sidecar_env().is_empty());
+    }
+
+    #[test]
+    fn redacts_credentials() {
+        assert_eq!(strip_credentials("http://user:pw@proxy:8080/"), "http://proxy:8080/");
+        assert_eq!(strip_credentials("proxy:8080"), "proxy:8080");
+        assert_eq!(strip_credentials("http://proxy/a@b"), "http://proxy/a@b");
+    }
+}
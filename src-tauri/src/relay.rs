@@ -0,0 +1,344 @@
+//! A WebSocket relay hosted by the app itself, for setups that would
+//! otherwise deploy `scripts/ais-relay.cjs` just to share the live feeds.
+//! With `relay.enabled`, `start` listens on `relay.port` (loopback only,
+//! or every interface with `relay.lan`) and keeps the AIS stream and the
+//! OpenSky poller running for the whole world while it serves.
+//!
+//! Clients get one JSON text message per update, `{"type", "data"}`:
+//! `opensky-snapshot` right after connecting, then `opensky` with each
+//! `opensky://diff` and `ais` with each `ais://positions` batch. Nothing
+//! sent by clients is read beyond close frames.
+//!
+//! Like the external relay, clients authenticate with `RELAY_SHARED_SECRET`
+//! in an `x-relay-key` header, as a bearer token, or (for browsers, which
+//! cannot set headers) a `token` query parameter. Without the secret only
+//! clients on this machine are accepted. Browsers must come from the app's
+//! own origins, so a web page cannot read the feed through a local relay.
+//! Rejected clients are closed with 1008, and clients past
+//! `relay.max_clients` with 1013. A client that falls more than
+//! `CLIENT_BACKLOG` messages behind skips the ones it missed.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use futures_util::stream::SplitSink;
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use serde_json::json;
+use tauri::{AppHandle, Manager, Webview};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::frame::{CloseFrame, Utf8Bytes};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+use crate::config::DesktopConfigState;
+
+const SECRET: &str = "RELAY_SHARED_SECRET";
+const AUTH_HEADER: &str = "x-relay-key";
+/// Messages a client may fall behind before it skips ahead.
+const CLIENT_BACKLOG: usize = 256;
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+/// A client that cannot take a message for this long is dropped.
+const SEND_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Origins of the app's own webview; in dev builds the dev server's origin
+/// (`dev_origin`) is allowed too.
+const ALLOWED_ORIGINS: [&str; 3] = ["tauri://localhost", "http://tauri.localhost", "https://tauri.localhost"];
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayStatus {
+    pub running: bool,
+    /// `host:port` the relay listens on.
+    pub address: Option<String>,
+    /// Whether `RELAY_SHARED_SECRET` is set, so LAN clients can connect.
+    pub secured: bool,
+    pub clients: usize,
+    pub max_clients: usize,
+    /// Connections turned away since startup, for auth or capacity.
+    pub rejected: u64,
+    pub error: Option<String>,
+}
+
+pub struct RelayState {
+    updates: broadcast::Sender<Utf8Bytes>,
+    status: Mutex<RelayStatus>,
+}
+
+impl Default for RelayState {
+    fn default() -> Self {
+        RelayState {
+            updates: broadcast::channel(CLIENT_BACKLOG).0,
+            status: Mutex::new(RelayStatus::default()),
+        }
+    }
+}
+
+fn update_status(app: &AppHandle, f: impl FnOnce(&mut RelayStatus)) {
+    f(&mut app.state::<RelayState>().status.lock().unwrap_or_else(|e| e.into_inner()));
+}
+
+/// Whether the relay is up, so its upstreams should stay running.
+pub fn is_serving(app: &AppHandle) -> bool {
+    app.try_state::<RelayState>()
+        .is_some_and(|state| state.status.lock().unwrap_or_else(|e| e.into_inner()).running)
+}
+
+/// Send an update to every connected client.
+pub fn publish(app: &AppHandle, kind: &str, data: &impl Serialize) {
+    let Some(state) = app.try_state::<RelayState>() else {
+        return;
+    };
+    if state.updates.receiver_count() == 0 {
+        return;
+    }
+    let _ = state.updates.send(message(kind, data));
+}
+
+fn message(kind: &str, data: &impl Serialize) -> Utf8Bytes {
+    json!({ "type": kind, "data": data }).to_string().into()
+}
+
+/// The shared secret from the header, a bearer token or `?token=`.
+fn provided_secret(request: &Request) -> Option<String> {
+    let headers = request.headers();
+    let header = |name| headers.get(name).and_then(|v| v.to_str().ok()).map(str::trim);
+    if let Some(key) = header(AUTH_HEADER).filter(|k| !k.is_empty()) {
+        return Some(key.to_string());
+    }
+    let bearer = header("authorization")
+        .filter(|auth| auth.len() > 7 && auth[..7].eq_ignore_ascii_case("bearer "))
+        .map(|auth| auth[7..].trim())
+        .filter(|token| !token.is_empty());
+    if let Some(token) = bearer {
+        return Some(token.to_string());
+    }
+    let url = reqwest::Url::parse(&format!("ws://relay{}", request.uri())).ok()?;
+    let token = url.query_pairs().find(|(name, _)| name == "token")?.1;
+    Some(token.into_owned()).filter(|token| !token.is_empty())
+}
+
+/// The `build.devUrl` origin (e.g. `http://localhost:5173`), in dev builds.
+fn dev_origin(app: &AppHandle) -> Option<String> {
+    let url = app.config().build.dev_url.as_ref().filter(|_| cfg!(debug_assertions))?;
+    Some(url.origin().ascii_serialization())
+}
+
+fn origin_allowed(origin: &str, dev_origin: Option<&str>) -> bool {
+    ALLOWED_ORIGINS.contains(&origin) || dev_origin == Some(origin)
+}
+
+fn secrets_match(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Why a client is turned away, if it is.
+fn admit(
+    peer: IpAddr,
+    origin: Option<&str>,
+    dev_origin: Option<&str>,
+    provided: Option<&str>,
+    secret: Option<&str>,
+) -> Result<(), &'static str> {
+    if origin.is_some_and(|origin| !origin_allowed(origin, dev_origin)) {
+        return Err("origin not allowed");
+    }
+    match secret {
+        Some(secret) if provided.is_some_and(|provided| secrets_match(provided, secret)) => Ok(()),
+        Some(_) => Err("unauthorized"),
+        None if peer.is_loopback() => Ok(()),
+        None => Err("RELAY_SHARED_SECRET is not set"),
+    }
+}
+
+/// Counts a client for as long as it is connected.
+struct Slot(AppHandle);
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        update_status(&self.0, |status| status.clients -= 1);
+    }
+}
+
+async fn close(mut socket: WebSocketStream<TcpStream>, code: CloseCode, reason: &str) {
+    let frame = CloseFrame {
+        code,
+        reason: reason.into(),
+    };
+    let _ = tokio::time::timeout(SEND_TIMEOUT, socket.close(Some(frame))).await;
+}
+
+/// Whether `text` went out in time.
+async fn send(sink: &mut SplitSink<WebSocketStream<TcpStream>, Message>, text: Utf8Bytes) -> bool {
+    matches!(tokio::time::timeout(SEND_TIMEOUT, sink.send(Message::Text(text))).await, Ok(Ok(())))
+}
+
+// The handshake callback's error type is tungstenite's.
+#[allow(clippy::result_large_err)]
+async fn serve_client(app: AppHandle, stream: TcpStream, peer: SocketAddr) {
+    let mut verdict = Err("no handshake");
+    let dev_origin = dev_origin(&app);
+    let handshake = tokio_tungstenite::accept_hdr_async(stream, |request: &Request, response: Response| {
+        let origin = request.headers().get("origin").and_then(|v| v.to_str().ok());
+        let secret = app.state::<crate::SecretsCache>().get(SECRET);
+        verdict = admit(peer.ip(), origin, dev_origin.as_deref(), provided_secret(request).as_deref(), secret.as_deref());
+        Ok(response)
+    });
+    let socket = match tokio::time::timeout(HANDSHAKE_TIMEOUT, handshake).await {
+        Ok(Ok(socket)) => socket,
+        Ok(Err(err)) => return tracing::debug!(target: "app", %peer, "relay handshake failed: {err}"),
+        Err(_) => return,
+    };
+    if let Err(reason) = verdict {
+        tracing::warn!(target: "app", %peer, "relay client rejected: {reason}");
+        update_status(&app, |status| status.rejected += 1);
+        return close(socket, CloseCode::Policy, reason).await;
+    }
+
+    let mut admitted = false;
+    update_status(&app, |status| {
+        if status.clients < status.max_clients {
+            status.clients += 1;
+            admitted = true;
+        } else {
+            status.rejected += 1;
+        }
+    });
+    if !admitted {
+        return close(socket, CloseCode::Again, "too many clients").await;
+    }
+    let _slot = Slot(app.clone());
+    tracing::info!(target: "app", %peer, "relay client connected");
+
+    // Subscribe before taking the snapshot so no diff falls in between.
+    let mut updates = app.state::<RelayState>().updates.subscribe();
+    let snapshot = crate::opensky::snapshot(&app.state::<crate::opensky::OpenSkyState>());
+    let (mut sink, mut incoming) = socket.split();
+    if !send(&mut sink, message("opensky-snapshot", &snapshot)).await {
+        return;
+    }
+    loop {
+        tokio::select! {
+            update = updates.recv() => match update {
+                Ok(text) => if !send(&mut sink, text).await { break },
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::debug!(target: "app", %peer, skipped, "relay client fell behind");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            frame = incoming.next() => match frame {
+                None | Some(Err(_)) | Some(Ok(Message::Close(_))) => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    tracing::info!(target: "app", %peer, "relay client disconnected");
+}
+
+async fn run(app: AppHandle, listener: TcpListener) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                tauri::async_runtime::spawn(serve_client(app.clone(), stream, peer));
+            }
+            Err(err) => {
+                tracing::warn!(target: "app", "relay accept failed: {err}");
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+}
+
+pub fn start(app: &AppHandle) {
+    let config = app.state::<DesktopConfigState>().snapshot().relay;
+    if !config.enabled {
+        return;
+    }
+    let host = if config.lan { Ipv4Addr::UNSPECIFIED } else { Ipv4Addr::LOCALHOST };
+    let address = SocketAddr::from((host, config.port));
+    let secured = app.state::<crate::SecretsCache>().get(SECRET).is_some();
+    update_status(app, |status| {
+        status.address = Some(address.to_string());
+        status.max_clients = config.max_clients;
+        status.secured = secured;
+    });
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let listener = match TcpListener::bind(address).await {
+            Ok(listener) => listener,
+            Err(err) => {
+                tracing::warn!(target: "app", %address, "relay failed to listen: {err}");
+                return update_status(&app, |status| status.error = Some(err.to_string()));
+            }
+        };
+        update_status(&app, |status| status.running = true);
+        if config.lan && !secured {
+            tracing::warn!(target: "app", "relay listens on the LAN, but only local clients can connect until RELAY_SHARED_SECRET is set");
+        }
+        tracing::info!(target: "app", %address, "relay listening");
+        if let Err(err) = crate::ais::ensure_running(&app) {
+            tracing::warn!(target: "app", "relay is serving without AIS: {err}");
+        }
+        crate::opensky::ensure_running(&app);
+        run(app, listener).await;
+    });
+}
+
+#[tauri::command]
+pub fn get_relay_status(webview: Webview, state: tauri::State<'_, RelayState>) -> Result<RelayStatus, String> {
+    crate::metrics::of(&webview).observe("get_relay_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.status.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+#[cfg(test)]
+mod relay_tests {
+    use super::*;
+
+    const LAN: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20));
+    const LOCAL: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+    fn request(uri: &str, headers: &[(&str, &str)]) -> Request {
+        let mut builder = Request::builder().uri(uri);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        builder.body(()).unwrap()
+    }
+
+    #[test]
+    fn reads_the_secret_from_header_bearer_or_query() {
+        assert_eq!(provided_secret(&request("/", &[("X-Relay-Key", " s3cret ")])).as_deref(), Some("s3cret"));
+        assert_eq!(provided_secret(&request("/", &[("Authorization", "Bearer s3cret")])).as_deref(), Some("s3cret"));
+        assert_eq!(provided_secret(&request("/?a=1&token=s3%2Bcret", &[])).as_deref(), Some("s3+cret"));
+        assert_eq!(provided_secret(&request("/?token=", &[("Authorization", "Basic xyz")])), None);
+    }
+
+    #[test]
+    fn admits_by_secret_or_loopback_and_app_origin() {
+        const DEV: Option<&str> = Some("http://localhost:5173");
+        assert_eq!(admit(LOCAL, None, None, None, None), Ok(()));
+        assert_eq!(admit(LOCAL, Some("tauri://localhost"), None, None, None), Ok(()));
+        assert_eq!(admit(LOCAL, Some("http://localhost:5173"), DEV, None, None), Ok(()));
+        assert_eq!(admit(LAN, None, None, None, None), Err("RELAY_SHARED_SECRET is not set"));
+        assert_eq!(admit(LAN, None, None, Some("s3cret"), Some("s3cret")), Ok(()));
+        assert_eq!(admit(LOCAL, None, None, Some("guess"), Some("s3cret")), Err("unauthorized"));
+        assert_eq!(admit(LOCAL, None, None, None, Some("s3cret")), Err("unauthorized"));
+        for origin in [
+            "https://example.com",
+            "http://localhost.evil.com",
+            "tauri://localhost:80",
+            "http://localhost:3000",
+            "http://127.0.0.1:5173",
+            "http://localhost:5173",
+        ] {
+            assert_eq!(admit(LOCAL, Some(origin), None, Some("s3cret"), Some("s3cret")), Err("origin not allowed"), "{origin}");
+        }
+        assert_eq!(admit(LOCAL, Some("http://localhost:3000"), DEV, None, None), Err("origin not allowed"));
+    }
+}
@@ -0,0 +1,193 @@
+//! `restart_app`: relaunch the app in place, for settings that only apply at
+//! startup. Before exiting it records in `restart-state.json` which windows
+//! were up and anything the dashboard had not picked up yet, and stops the
+//! sidecar; the usual exit path then saves window geometry and flushes the
+//! cache before the executable is started again with the same arguments.
+//!
+//! The relaunch reads the file once and deletes it. It opens the windows that
+//! were open instead of the configured launch window, re-queues the pending
+//! deep links and imports, and skips the links and files on its command
+//! line, which were already handled. A file older than `MAX_AGE` (a restart
+//! that never happened) is ignored.
+
+use std::fs;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Webview};
+
+use crate::config::LaunchWindow;
+use crate::deep_links::DeepLink;
+use crate::file_import::FileImport;
+
+pub const RESTART_STATE_FILE: &str = "restart-state.json";
+
+/// Seconds a restart may take before its state is considered stale.
+const MAX_AGE: u64 = 120;
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RestartState {
+    saved_at: u64,
+    dashboard_visible: bool,
+    settings_open: bool,
+    #[serde(default)]
+    navigation: Vec<DeepLink>,
+    #[serde(default)]
+    imports: Vec<FileImport>,
+}
+
+impl RestartState {
+    /// The dashboard if it was showing, else settings if that was open, else
+    /// just the tray.
+    fn launch_window(&self) -> LaunchWindow {
+        if self.dashboard_visible {
+            LaunchWindow::Dashboard
+        } else if self.settings_open {
+            LaunchWindow::Settings
+        } else {
+            LaunchWindow::Tray
+        }
+    }
+
+    fn is_fresh(&self, now: u64) -> bool {
+        now.saturating_sub(self.saved_at) <= MAX_AGE
+    }
+}
+
+/// The state carried over from the previous process, if this is a restart.
+#[derive(Default)]
+pub struct ResumedState(Mutex<Option<RestartState>>);
+
+fn snapshot(app: &AppHandle) -> RestartState {
+    RestartState {
+        saved_at: crate::unix_timestamp_secs(),
+        dashboard_visible: app
+            .get_webview_window("main")
+            .and_then(|w| w.is_visible().ok())
+            .unwrap_or(true),
+        settings_open: app.get_webview_window("settings").is_some(),
+        navigation: crate::deep_links::pending(app),
+        imports: crate::file_import::pending(app),
+    }
+}
+
+fn save(app: &AppHandle) -> Result<(), String> {
+    let path = crate::restart_state_path(app)?;
+    let json = serde_json::to_vec(&snapshot(app)).map_err(|e| format!("Failed to serialize restart state: {e}"))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Read and delete the previous process's restart state. Call early in
+/// setup, before the launch window is chosen.
+pub fn load(app: &AppHandle) -> ResumedState {
+    let Ok(path) = crate::restart_state_path(app) else {
+        return ResumedState::default();
+    };
+    let Ok(json) = fs::read_to_string(&path) else {
+        return ResumedState::default();
+    };
+    let _ = fs::remove_file(&path);
+    let state = match serde_json::from_str::<RestartState>(&json) {
+        Ok(state) if state.is_fresh(crate::unix_timestamp_secs()) => state,
+        Ok(_) => {
+            tracing::warn!(target: "app", "ignoring stale restart state");
+            return ResumedState::default();
+        }
+        Err(err) => {
+            tracing::warn!(target: "app", "ignoring unreadable restart state: {err}");
+            return ResumedState::default();
+        }
+    };
+    tracing::info!(target: "app", window = ?state.launch_window(), "resuming after restart");
+    ResumedState(Mutex::new(Some(state)))
+}
+
+fn with_state<T>(app: &AppHandle, f: impl FnOnce(&mut RestartState) -> T) -> Option<T> {
+    let state = app.try_state::<ResumedState>()?;
+    let mut state = state.0.lock().unwrap_or_else(|e| e.into_inner());
+    state.as_mut().map(f)
+}
+
+/// Whether this process was started by `restart_app`.
+pub fn is_resumed(app: &AppHandle) -> bool {
+    with_state(app, |_| ()).is_some()
+}
+
+/// The window that was up before the restart.
+pub fn launch_window(app: &AppHandle) -> Option<LaunchWindow> {
+    with_state(app, |state| state.launch_window())
+}
+
+/// Put the deep links and imports the dashboard had not taken back in their
+/// queues. Call once both queues exist.
+pub fn requeue_pending(app: &AppHandle) {
+    let Some((navigation, imports)) =
+        with_state(app, |state| (std::mem::take(&mut state.navigation), std::mem::take(&mut state.imports)))
+    else {
+        return;
+    };
+    crate::deep_links::restore_pending(app, navigation);
+    crate::file_import::restore_pending(app, imports);
+}
+
+/// Reopen settings alongside the dashboard if both were open. Call after
+/// `launch::open`.
+pub fn reopen_settings(app: &AppHandle) {
+    let reopen = with_state(app, |state| state.dashboard_visible && state.settings_open).unwrap_or(false);
+    if reopen {
+        if let Err(err) = crate::open_settings_window(app, None) {
+            tracing::warn!(target: "app", "failed to reopen settings after restart: {err}");
+        }
+    }
+}
+
+/// Relaunch the app, keeping its windows and pending work. Returns before
+/// the process exits.
+#[tauri::command]
+pub async fn restart_app(webview: Webview, app: AppHandle) -> Result<(), String> {
+    crate::metrics::of(&webview)
+        .observe_async("restart_app", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                save(&app)?;
+                tracing::info!(target: "app", "restarting");
+                crate::stop_local_api(&app);
+                app.request_restart();
+                Ok(())
+            })
+            .await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod restart_tests {
+    use super::*;
+
+    #[test]
+    fn reopens_what_was_showing() {
+        let state = |dashboard_visible, settings_open| RestartState {
+            dashboard_visible,
+            settings_open,
+            ..RestartState::default()
+        };
+        assert_eq!(state(true, true).launch_window(), LaunchWindow::Dashboard);
+        assert_eq!(state(false, true).launch_window(), LaunchWindow::Settings);
+        assert_eq!(state(false, false).launch_window(), LaunchWindow::Tray);
+    }
+
+    #[test]
+    fn round_trips_and_expires() {
+        let state = RestartState {
+            saved_at: 1_000,
+            dashboard_visible: true,
+            navigation: vec![DeepLink::Panel { id: "live-news".to_string() }],
+            ..RestartState::default()
+        };
+        let json = serde_json::to_string(&state).unwrap();
+        let parsed: RestartState = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, state);
+        assert!(parsed.is_fresh(1_000 + MAX_AGE));
+        assert!(!parsed.is_fresh(1_001 + MAX_AGE));
+    }
+}
@@ -0,0 +1,71 @@
+//! The tokio runtime shared by async commands and the background workers.
+//! It is built before Tauri starts and handed to `tauri::async_runtime`, so
+//! every `spawn`, `spawn_blocking` and async command in the app runs on it,
+//! and managed as `SharedRuntime` so it lives as long as the app.
+//!
+//! Commands that touch the keyring or the file system are async and do that
+//! work through `blocking`, on the runtime's blocking pool: synchronous
+//! commands run on the main thread, where a keychain prompt or a large
+//! cache write would stall the UI.
+
+use tokio::runtime::{Builder, Runtime};
+
+/// Upper bound on threads running `blocking` work at once.
+const MAX_BLOCKING_THREADS: usize = 64;
+
+/// Managed state owning the runtime. Dropping it shuts the runtime down
+/// without waiting for tasks still in flight.
+pub struct SharedRuntime(Option<Runtime>);
+
+impl Drop for SharedRuntime {
+    fn drop(&mut self) {
+        if let Some(runtime) = self.0.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// Build the runtime and make it Tauri's. Must run before the builder; if
+/// the runtime cannot be built, Tauri starts its own default one instead.
+pub fn init() -> SharedRuntime {
+    let built = Builder::new_multi_thread()
+        .enable_all()
+        .thread_name("wm-runtime")
+        .max_blocking_threads(MAX_BLOCKING_THREADS)
+        .build();
+    match built {
+        Ok(runtime) => {
+            tauri::async_runtime::set(runtime.handle().clone());
+            SharedRuntime(Some(runtime))
+        }
+        Err(err) => {
+            // Logging is not set up yet.
+            eprintln!("[tauri] failed to build the shared runtime, using the default: {err}");
+            SharedRuntime(None)
+        }
+    }
+}
+
+/// Run `f` on the blocking pool and wait for it.
+pub async fn blocking<T: Send + 'static>(f: impl FnOnce() -> Result<T, String> + Send + 'static) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| format!("Blocking task failed: {e}"))?
+}
+
+#[cfg(test)]
+mod runtime_tests {
+    use super::*;
+
+    #[test]
+    fn blocking_work_runs_off_the_calling_thread() {
+        let runtime = Builder::new_multi_thread().enable_all().build().unwrap();
+        let caller = std::thread::current().id();
+        let (ran_on, result) = runtime.block_on(async {
+            let ran_on = blocking(|| Ok(std::thread::current().id())).await.unwrap();
+            (ran_on, blocking(|| Err::<(), _>("keyring locked".to_string())).await)
+        });
+        assert_ne!(ran_on, caller);
+        assert_eq!(result, Err("keyring locked".to_string()));
+    }
+}
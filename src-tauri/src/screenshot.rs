@@ -0,0 +1,277 @@
+//! Window snapshots for reports. `capture_screenshot` (and File → Save
+//! Screenshot) writes what a window shows right now to a timestamped PNG,
+//! by default under `screenshots` in the app data dir, using each platform
+//! webview's own snapshot API:
+//!
+//! - Windows: WebView2's `CapturePreview`.
+//! - macOS: `WKWebView`'s `takeSnapshotWithConfiguration`.
+//! - Linux: WebKitGTK's snapshot of the visible region.
+//!
+//! The command can also put the image on the clipboard.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::menu::MenuItem;
+use tauri::{AppHandle, Manager, Webview, Wry};
+
+pub const MENU_CAPTURE_ID: &str = "file.screenshot";
+const SCREENSHOTS_DIR: &str = "screenshots";
+
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(20);
+
+type Done = Sender<Result<(), String>>;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Screenshot {
+    pub path: String,
+    /// Whether the image was also copied to the clipboard.
+    pub copied: bool,
+}
+
+/// `world-monitor-<label>-<timestamp>.png`, with anything but letters,
+/// digits and dashes in the label replaced.
+fn file_name(label: &str, timestamp: u64) -> String {
+    let label: String = label
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '-' })
+        .collect();
+    format!("world-monitor-{label}-{timestamp}.png")
+}
+
+fn target_dir(app: &AppHandle, folder: Option<&str>) -> Result<PathBuf, String> {
+    let Some(folder) = folder else {
+        return app
+            .path()
+            .app_data_dir()
+            .map(|dir| dir.join(SCREENSHOTS_DIR))
+            .map_err(|e| format!("Failed to resolve app data dir: {e}"));
+    };
+    let folder = PathBuf::from(folder);
+    if !folder.is_absolute() || !folder.is_dir() {
+        return Err(format!("Not a folder: {}", folder.display()));
+    }
+    Ok(folder)
+}
+
+/// Snapshot window `label` into `dir` and return the file's path. Blocks
+/// until the webview finishes, so never call it on the main thread.
+pub fn capture(app: &AppHandle, label: &str, dir: &Path) -> Result<PathBuf, String> {
+    let window = app
+        .get_webview_window(label)
+        .ok_or_else(|| format!("Window not found: {label}"))?;
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let target = dir.join(file_name(label, crate::unix_timestamp_secs()));
+    let (done, finished) = mpsc::channel();
+    let path = target.clone();
+    window
+        .with_webview(move |webview| platform::snapshot(webview, &path, done))
+        .map_err(|e| format!("Failed to capture screenshot: {e}"))?;
+    finished
+        .recv_timeout(CAPTURE_TIMEOUT)
+        .map_err(|_| "Screenshot timed out".to_string())??;
+    tracing::info!(target: "app", window = label, path = %target.display(), "screenshot saved");
+    Ok(target)
+}
+
+fn copy_to_clipboard(app: &AppHandle, path: &Path) -> Result<(), String> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    let image = tauri::image::Image::from_path(path).map_err(|e| format!("Failed to read screenshot: {e}"))?;
+    app.clipboard()
+        .write_image(&image)
+        .map_err(|e| format!("Failed to copy screenshot to clipboard: {e}"))
+}
+
+/// File → Save Screenshot.
+pub fn file_menu_item(handle: &AppHandle) -> tauri::Result<MenuItem<Wry>> {
+    MenuItem::with_id(handle, MENU_CAPTURE_ID, "Save Screenshot", true, Some("CmdOrCtrl+Shift+S"))
+}
+
+/// Snapshot the dashboard and show the file. Returns immediately.
+pub fn capture_and_reveal(app: &AppHandle) {
+    let app = app.clone();
+    let spawned = std::thread::Builder::new().name("screenshot".into()).spawn(move || {
+        let captured = target_dir(&app, None).and_then(|dir| capture(&app, "main", &dir));
+        match captured {
+            Ok(path) => {
+                if let Err(err) = crate::reveal_in_shell(&path) {
+                    tracing::warn!(target: "menu", "{err}");
+                }
+            }
+            Err(err) => tracing::error!(target: "menu", "screenshot failed: {err}"),
+        }
+    });
+    if let Err(err) = spawned {
+        tracing::error!(target: "menu", "failed to start screenshot: {err}");
+    }
+}
+
+/// Snapshot `window` (the dashboard by default) into `folder` (an absolute
+/// path; the app data dir by default), optionally copying it to the
+/// clipboard too.
+#[tauri::command]
+pub async fn capture_screenshot(
+    webview: Webview,
+    app: AppHandle,
+    window: Option<String>,
+    folder: Option<String>,
+    copy_to_clipboard: Option<bool>,
+) -> Result<Screenshot, String> {
+    crate::metrics::of(&webview)
+        .observe_async("capture_screenshot", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                let dir = target_dir(&app, folder.as_deref())?;
+                let path = capture(&app, window.as_deref().unwrap_or("main"), &dir)?;
+                let copied = copy_to_clipboard.unwrap_or(false);
+                if copied {
+                    self::copy_to_clipboard(&app, &path)?;
+                }
+                Ok(Screenshot {
+                    path: path.display().to_string(),
+                    copied,
+                })
+            })
+            .await
+        })
+        .await
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::path::Path;
+
+    use tauri::webview::PlatformWebview;
+    use webview2_com::CapturePreviewCompletedHandler;
+    use webview2_com::Microsoft::Web::WebView2::Win32::COREWEBVIEW2_CAPTURE_PREVIEW_IMAGE_FORMAT_PNG;
+    use windows::core::HSTRING;
+    use windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_NORMAL;
+    use windows::Win32::System::Com::{STGM_CREATE, STGM_WRITE};
+    use windows::Win32::UI::Shell::SHCreateStreamOnFileEx;
+
+    use super::Done;
+
+    pub fn snapshot(webview: PlatformWebview, path: &Path, done: Done) {
+        let stream = unsafe {
+            SHCreateStreamOnFileEx(
+                &HSTRING::from(path),
+                STGM_CREATE.0 | STGM_WRITE.0,
+                FILE_ATTRIBUTE_NORMAL.0,
+                true,
+                None,
+            )
+        };
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                let _ = done.send(Err(format!("Failed to create {}: {e}", path.display())));
+                return;
+            }
+        };
+        let completed = done.clone();
+        let written = stream.clone();
+        let handler = CapturePreviewCompletedHandler::create(Box::new(move |result| {
+            // Close the file before reporting it written.
+            drop(written);
+            let _ = completed.send(result.map_err(|e| format!("Failed to capture screenshot: {e}")));
+            Ok(())
+        }));
+        let started = unsafe {
+            webview
+                .controller()
+                .CoreWebView2()
+                .and_then(|core| core.CapturePreview(COREWEBVIEW2_CAPTURE_PREVIEW_IMAGE_FORMAT_PNG, &stream, &handler))
+        };
+        if let Err(e) = started {
+            let _ = done.send(Err(format!("Failed to capture screenshot: {e}")));
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::path::{Path, PathBuf};
+
+    use block2::RcBlock;
+    use objc2_app_kit::{NSBitmapImageFileType, NSBitmapImageRep, NSImage};
+    use objc2_foundation::{NSDictionary, NSError};
+    use objc2_web_kit::WKWebView;
+    use tauri::webview::PlatformWebview;
+
+    use super::Done;
+
+    fn write_png(image: &NSImage, path: &Path) -> Result<(), String> {
+        let tiff = image.TIFFRepresentation().ok_or("The snapshot has no image data")?;
+        let bitmap = NSBitmapImageRep::imageRepWithData(&tiff).ok_or("The snapshot has no bitmap")?;
+        let png = unsafe { bitmap.representationUsingType_properties(NSBitmapImageFileType::PNG, &NSDictionary::new()) }
+            .ok_or("Failed to encode the snapshot as PNG")?;
+        std::fs::write(path, png.to_vec()).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    }
+
+    pub fn snapshot(webview: PlatformWebview, path: &Path, done: Done) {
+        let path: PathBuf = path.to_path_buf();
+        let wk_webview: &WKWebView = unsafe { &*webview.inner().cast() };
+        let handler = RcBlock::new(move |image: *mut NSImage, error: *mut NSError| {
+            let result = match unsafe { image.as_ref() } {
+                Some(image) => write_png(image, &path),
+                None => Err(match unsafe { error.as_ref() } {
+                    Some(error) => format!("Failed to capture screenshot: {}", error.localizedDescription()),
+                    None => "Failed to capture screenshot".to_string(),
+                }),
+            };
+            let _ = done.send(result);
+        });
+        unsafe { wk_webview.takeSnapshotWithConfiguration_completionHandler(None, &handler) };
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use std::path::Path;
+
+    use gtk::cairo::ImageSurface;
+    use tauri::webview::PlatformWebview;
+    use webkit2gtk::{SnapshotOptions, SnapshotRegion, WebViewExt};
+
+    use super::Done;
+
+    pub fn snapshot(webview: PlatformWebview, path: &Path, done: Done) {
+        let path = path.to_path_buf();
+        webview.inner().snapshot(
+            SnapshotRegion::Visible,
+            SnapshotOptions::NONE,
+            None::<&gtk::gio::Cancellable>,
+            move |result| {
+                let saved = result
+                    .map_err(|e| format!("Failed to capture screenshot: {e}"))
+                    .and_then(|surface| {
+                        let image = ImageSurface::try_from(surface)
+                            .map_err(|_| "The snapshot is not an image".to_string())?;
+                        gtk::gdk::pixbuf_get_from_surface(&image, 0, 0, image.width(), image.height())
+                            .ok_or_else(|| "Failed to read the snapshot".to_string())
+                    })
+                    .and_then(|pixbuf| {
+                        pixbuf
+                            .savev(&path, "png", &[])
+                            .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+                    });
+                let _ = done.send(saved);
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod screenshot_tests {
+    use super::file_name;
+
+    #[test]
+    fn file_names_are_safe_and_timestamped() {
+        assert_eq!(file_name("main", 1_700_000_000), "world-monitor-main-1700000000.png");
+        assert_eq!(file_name("panel:news/feed", 1), "world-monitor-panel-news-feed-1.png");
+    }
+}
@@ -0,0 +1,356 @@
+//! Native sharing. `share_content` hands a link, text and/or a generated
+//! file (a story card, a report) to the OS share UI: `NSSharingServicePicker`
+//! on macOS and the Windows share flyout. Linux has no share sheet, so the
+//! link (or text, or PNG image) is copied to the clipboard instead and the
+//! caller is told so.
+//!
+//! Files arrive base64-encoded and are written to a scratch directory under
+//! the system temp dir, which is emptied at the start of every share.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use tauri::{Url, Webview};
+
+/// Generated files only; anything larger is not a story card or report.
+const MAX_FILE_BYTES: usize = 32 * 1024 * 1024;
+const SHARE_DIR: &str = "world-monitor-share";
+const SHAREABLE_EXTENSIONS: [&str; 7] = ["png", "jpg", "jpeg", "pdf", "csv", "json", "txt"];
+
+#[derive(Deserialize)]
+pub struct SharedFile {
+    pub name: String,
+    /// Base64 file contents.
+    pub data: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShareResult {
+    /// The share UI was shown.
+    #[cfg_attr(not(any(target_os = "macos", windows)), allow(dead_code))]
+    Shared,
+    /// No share UI on this platform; the content was copied instead.
+    Copied,
+}
+
+/// Validated share content.
+struct Share {
+    /// Only the Windows share UI shows a title.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    title: Option<String>,
+    text: Option<String>,
+    url: Option<Url>,
+    file: Option<PathBuf>,
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+fn parse_url(url: &str) -> Result<Url, String> {
+    let parsed = Url::parse(url).map_err(|_| "Invalid URL".to_string())?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("Only http:// and https:// links can be shared".to_string());
+    }
+    Ok(parsed)
+}
+
+/// The bare file name, if it has a shareable extension.
+fn shareable_name(name: &str) -> Result<String, String> {
+    let name = Path::new(name)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .filter(|n| !n.starts_with('.'))
+        .ok_or_else(|| "Invalid file name".to_string())?;
+    let extension = Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    if !SHAREABLE_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(format!("Cannot share .{extension} files"));
+    }
+    Ok(name.to_string())
+}
+
+fn decode(file: &SharedFile) -> Result<Vec<u8>, String> {
+    // Base64 is 4/3 the size of its contents.
+    if file.data.len() / 4 * 3 > MAX_FILE_BYTES {
+        return Err("File too large to share".to_string());
+    }
+    base64::engine::general_purpose::STANDARD
+        .decode(file.data.trim())
+        .map_err(|e| format!("Invalid file data: {e}"))
+}
+
+fn stage(name: &str, bytes: &[u8]) -> Result<PathBuf, String> {
+    let dir = std::env::temp_dir().join(SHARE_DIR);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to prepare file for sharing: {e}"))?;
+    let path = dir.join(name);
+    fs::write(&path, bytes).map_err(|e| format!("Failed to prepare file for sharing: {e}"))?;
+    Ok(path)
+}
+
+/// Copy what a share sheet would have offered: the link, else the text,
+/// else a PNG as an image.
+#[cfg_attr(any(target_os = "macos", windows), allow(dead_code))]
+fn copy_instead(webview: &Webview, share: &Share) -> Result<ShareResult, String> {
+    use tauri::Manager;
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    let clipboard = webview.app_handle().clipboard();
+    let copied = if let Some(url) = &share.url {
+        clipboard.write_text(url.as_str())
+    } else if let Some(text) = &share.text {
+        clipboard.write_text(text)
+    } else if let Some(path) = share.file.as_deref().filter(|p| p.extension().is_some_and(|e| e.eq_ignore_ascii_case("png"))) {
+        let image = tauri::image::Image::from_path(path).map_err(|e| format!("Failed to read image: {e}"))?;
+        clipboard.write_image(&image)
+    } else {
+        return Err("Sharing files is not supported on this system".to_string());
+    };
+    copied.map_err(|e| format!("Failed to copy to clipboard: {e}"))?;
+    Ok(ShareResult::Copied)
+}
+
+fn share(
+    webview: &Webview,
+    title: Option<String>,
+    text: Option<String>,
+    url: Option<String>,
+    file: Option<SharedFile>,
+) -> Result<ShareResult, String> {
+    let url = non_empty(url).map(|url| parse_url(&url)).transpose()?;
+    let text = non_empty(text);
+    let file = match file {
+        Some(file) => {
+            let name = shareable_name(&file.name)?;
+            Some(stage(&name, &decode(&file)?)?)
+        }
+        None => None,
+    };
+    if url.is_none() && text.is_none() && file.is_none() {
+        return Err("Nothing to share".to_string());
+    }
+    let share = Share {
+        title: non_empty(title),
+        text,
+        url,
+        file,
+    };
+    platform::show(webview, share)
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::cell::RefCell;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    use objc2::rc::Retained;
+    use objc2::runtime::AnyObject;
+    use objc2::{AnyThread, MainThreadMarker};
+    use objc2_app_kit::{NSSharingServicePicker, NSWindow};
+    use objc2_foundation::{NSArray, NSPoint, NSRect, NSRectEdge, NSSize, NSString, NSURL};
+    use tauri::{Webview, Window};
+
+    use super::{Share, ShareResult};
+
+    thread_local! {
+        // Keep the picker alive while it is on screen.
+        static PICKER: RefCell<Option<Retained<NSSharingServicePicker>>> = const { RefCell::new(None) };
+    }
+
+    fn present(window: &Window, share: &Share) -> Result<(), String> {
+        MainThreadMarker::new().ok_or("The share sheet must open on the main thread")?;
+        let ns_window = window.ns_window().map_err(|e| format!("No native window: {e}"))?;
+        let ns_window: &NSWindow = unsafe { &*ns_window.cast() };
+        let view = ns_window.contentView().ok_or("Window has no content view")?;
+
+        let mut items: Vec<Retained<AnyObject>> = Vec::new();
+        if let Some(text) = &share.text {
+            items.push(Retained::into_super(Retained::into_super(NSString::from_str(text))));
+        }
+        if let Some(url) = share.url.as_ref().and_then(|url| NSURL::URLWithString(&NSString::from_str(url.as_str()))) {
+            items.push(Retained::into_super(Retained::into_super(url)));
+        }
+        if let Some(file) = share.file.as_deref().and_then(NSURL::from_file_path) {
+            items.push(Retained::into_super(Retained::into_super(file)));
+        }
+        let items = NSArray::from_retained_slice(&items);
+        let picker = unsafe { NSSharingServicePicker::initWithItems(NSSharingServicePicker::alloc(), &items) };
+
+        // Anchor to the top centre of the window, below the title bar.
+        let bounds = view.bounds();
+        let anchor = NSRect::new(
+            NSPoint::new(bounds.size.width / 2.0, bounds.size.height - 1.0),
+            NSSize::new(1.0, 1.0),
+        );
+        picker.showRelativeToRect_ofView_preferredEdge(anchor, &view, NSRectEdge::MinY);
+        PICKER.with(|slot| *slot.borrow_mut() = Some(picker));
+        Ok(())
+    }
+
+    pub fn show(webview: &Webview, share: Share) -> Result<ShareResult, String> {
+        let window = webview.window();
+        let (done, result) = mpsc::channel();
+        let target = window.clone();
+        window
+            .run_on_main_thread(move || {
+                let _ = done.send(present(&target, &share));
+            })
+            .map_err(|e| format!("Failed to open the share sheet: {e}"))?;
+        result
+            .recv_timeout(Duration::from_secs(10))
+            .map_err(|_| "The share sheet did not open".to_string())??;
+        Ok(ShareResult::Shared)
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::cell::RefCell;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    use tauri::Webview;
+    use windows::core::{factory, AgileReference, Interface, Ref, HSTRING};
+    use windows::ApplicationModel::DataTransfer::{DataRequestedEventArgs, DataTransferManager};
+    use windows::Foundation::{TypedEventHandler, Uri};
+    use windows::Storage::{IStorageItem, StorageFile};
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::Shell::IDataTransferManagerInterop;
+    use windows_collections::IIterable;
+
+    use super::{Share, ShareResult};
+
+    thread_local! {
+        // The `DataRequested` handler of the last share, replaced by the next.
+        static HANDLER: RefCell<Option<(DataTransferManager, i64)>> = const { RefCell::new(None) };
+    }
+
+    fn fill(args: Ref<DataRequestedEventArgs>, share: &Share, file: Option<&AgileReference<StorageFile>>) -> windows::core::Result<()> {
+        let Some(args) = args.as_ref() else {
+            return Ok(());
+        };
+        let data = args.Request()?.Data()?;
+        let properties = data.Properties()?;
+        properties.SetTitle(&HSTRING::from(share.title.as_deref().unwrap_or("World Monitor")))?;
+        if let Some(text) = &share.text {
+            data.SetText(&HSTRING::from(text))?;
+        }
+        if let Some(url) = &share.url {
+            data.SetWebLink(&Uri::CreateUri(&HSTRING::from(url.as_str()))?)?;
+        }
+        if let Some(file) = file {
+            let items: IIterable<IStorageItem> = vec![Some(file.resolve()?.cast::<IStorageItem>()?)].into();
+            data.SetStorageItemsReadOnly(&items)?;
+        }
+        Ok(())
+    }
+
+    fn present(hwnd: HWND, share: Share, file: Option<AgileReference<StorageFile>>) -> windows::core::Result<()> {
+        let interop = factory::<DataTransferManager, IDataTransferManagerInterop>()?;
+        let manager: DataTransferManager = unsafe { interop.GetForWindow(hwnd) }?;
+        HANDLER.with(|slot| {
+            if let Some((previous, token)) = slot.borrow_mut().take() {
+                let _ = previous.RemoveDataRequested(token);
+            }
+        });
+        let token = manager.DataRequested(&TypedEventHandler::new(move |_, args| fill(args, &share, file.as_ref())))?;
+        HANDLER.with(|slot| *slot.borrow_mut() = Some((manager, token)));
+        unsafe { interop.ShowShareUIForWindow(hwnd) }
+    }
+
+    pub fn show(webview: &Webview, share: Share) -> Result<ShareResult, String> {
+        let window = webview.window();
+        let hwnd = HWND(window.hwnd().map_err(|e| format!("No native window: {e}"))?.0);
+        // Resolving the file blocks, so do it here rather than on the UI thread.
+        let file = share
+            .file
+            .as_deref()
+            .map(|path| AgileReference::new(&StorageFile::GetFileFromPathAsync(&HSTRING::from(path.as_os_str()))?.join()?))
+            .transpose()
+            .map_err(|e| format!("Failed to prepare file for sharing: {e}"))?;
+        let (done, result) = mpsc::channel();
+        let hwnd = hwnd.0 as isize;
+        window
+            .run_on_main_thread(move || {
+                let _ = done.send(present(HWND(hwnd as _), share, file).map_err(|e| e.to_string()));
+            })
+            .map_err(|e| format!("Failed to open the share UI: {e}"))?;
+        result
+            .recv_timeout(Duration::from_secs(10))
+            .map_err(|_| "The share UI did not open".to_string())?
+            .map_err(|e| format!("Failed to open the share UI: {e}"))?;
+        Ok(ShareResult::Shared)
+    }
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+mod platform {
+    use tauri::Webview;
+
+    use super::{Share, ShareResult};
+
+    pub fn show(webview: &Webview, share: Share) -> Result<ShareResult, String> {
+        super::copy_instead(webview, &share)
+    }
+}
+
+/// Share via the OS share UI. Resolves to `copied` where there is none and
+/// the content went to the clipboard instead.
+#[tauri::command]
+pub async fn share_content(
+    webview: Webview,
+    title: Option<String>,
+    text: Option<String>,
+    url: Option<String>,
+    file: Option<SharedFile>,
+) -> Result<ShareResult, String> {
+    crate::metrics::of(&webview)
+        .observe_async("share_content", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || share(&webview, title, text, url, file)).await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod share_tests {
+    use super::*;
+
+    #[test]
+    fn only_generated_file_types_are_shared() {
+        assert_eq!(shareable_name("ua-worldmonitor.png").unwrap(), "ua-worldmonitor.png");
+        assert_eq!(shareable_name("../../etc/report.PDF").unwrap(), "report.PDF");
+        assert!(shareable_name("payload.exe").is_err());
+        assert!(shareable_name(".png").is_err());
+        assert!(shareable_name("..").is_err());
+    }
+
+    #[test]
+    fn only_web_links_are_shared() {
+        assert!(parse_url("https://worldmonitor.app/?country=UA").is_ok());
+        assert!(parse_url("file:///etc/passwd").is_err());
+        assert!(parse_url("javascript:alert(1)").is_err());
+    }
+
+    #[test]
+    fn decodes_file_data() {
+        let file = SharedFile {
+            name: "a.txt".to_string(),
+            data: "aGVsbG8=".to_string(),
+        };
+        assert_eq!(decode(&file).unwrap(), b"hello");
+        let garbage = SharedFile {
+            name: "a.txt".to_string(),
+            data: "not base64!".to_string(),
+        };
+        assert!(decode(&garbage).is_err());
+    }
+}
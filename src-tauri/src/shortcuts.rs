@@ -0,0 +1,118 @@
+//! Global show/hide shortcut. A system-wide accelerator (default
+//! `CommandOrControl+Shift+W`) toggles the main window even while the app is
+//! in the background or hidden to the tray. The binding lives in
+//! `DesktopConfig::shortcuts` and is changed at runtime through
+//! `set_global_shortcut`; `None` disables it.
+
+use std::str::FromStr;
+
+use tauri::{AppHandle, Manager, Webview};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::config::DesktopConfigState;
+
+/// Parse an accelerator such as `CommandOrControl+Shift+W`.
+pub fn parse(accelerator: &str) -> Result<Shortcut, String> {
+    Shortcut::from_str(accelerator.trim()).map_err(|e| format!("Invalid shortcut \"{accelerator}\": {e}"))
+}
+
+/// Hide the main window if it is in front, otherwise bring it forward.
+fn toggle_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let in_front = window.is_visible().unwrap_or(false)
+        && !window.is_minimized().unwrap_or(false)
+        && window.is_focused().unwrap_or(false);
+    if in_front {
+        let _ = window.hide();
+    } else {
+        crate::tray::show_dashboard(app);
+    }
+}
+
+/// Plugin handler; only the toggle shortcut is ever registered.
+pub fn handle(app: &AppHandle, _shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state == ShortcutState::Pressed {
+        toggle_main_window(app);
+    }
+}
+
+/// Register the configured shortcut. Another app already owning the
+/// combination is logged, not fatal.
+pub fn init(app: &AppHandle) {
+    let Some(accelerator) = app.state::<DesktopConfigState>().snapshot().shortcuts.toggle_window else {
+        return;
+    };
+    let registered = parse(&accelerator).and_then(|shortcut| {
+        app.global_shortcut()
+            .register(shortcut)
+            .map_err(|e| format!("Failed to register shortcut \"{accelerator}\": {e}"))
+    });
+    match registered {
+        Ok(()) => tracing::info!(target: "app", shortcut = accelerator, "global shortcut registered"),
+        Err(err) => tracing::warn!(target: "app", "{err}"),
+    }
+}
+
+#[tauri::command]
+pub fn get_global_shortcut(
+    webview: Webview,
+    config: tauri::State<'_, DesktopConfigState>,
+) -> Result<Option<String>, String> {
+    crate::metrics::of(&webview).observe("get_global_shortcut", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(config.snapshot().shortcuts.toggle_window)
+    })
+}
+
+/// Rebind (or with `None`, disable) the show/hide shortcut. The new binding
+/// is registered before the old one is dropped, so a rejected combination
+/// leaves the current one working.
+#[tauri::command]
+pub async fn set_global_shortcut(
+    webview: Webview,
+    app: AppHandle,
+    shortcut: Option<String>,
+) -> Result<(), String> {
+    crate::metrics::of(&webview)
+        .observe_async("set_global_shortcut", async move {
+            crate::require_trusted_window(webview.label())?;
+            let shortcut = shortcut.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+            let next = shortcut.as_deref().map(parse).transpose()?;
+            let config = app.state::<DesktopConfigState>();
+            let previous = config
+                .snapshot()
+                .shortcuts
+                .toggle_window
+                .and_then(|s| parse(&s).ok());
+            let global = app.global_shortcut();
+            // Registering the same binding again is how a user retries one
+            // that was taken by another app at startup.
+            if let Some(next) = next.filter(|n| !global.is_registered(*n)) {
+                global
+                    .register(next)
+                    .map_err(|e| format!("Failed to register shortcut: {e}"))?;
+            }
+            if let Some(previous) = previous.filter(|p| Some(*p) != next && global.is_registered(*p)) {
+                let _ = global.unregister(previous);
+            }
+            config.update(|c| c.shortcuts.toggle_window = shortcut.clone())?;
+            tracing::info!(target: "app", shortcut = shortcut.as_deref(), "global shortcut changed");
+            Ok(())
+        })
+        .await
+}
+
+#[cfg(test)]
+mod shortcuts_tests {
+    use super::parse;
+
+    #[test]
+    fn parses_default_and_rejects_garbage() {
+        assert!(parse("CommandOrControl+Shift+W").is_ok());
+        assert!(parse(" Alt+F12 ").is_ok());
+        assert!(parse("Shift+").is_err());
+        assert!(parse("NotAKey").is_err());
+    }
+}
@@ -0,0 +1,29 @@
+//! Single-instance enforcement. A second launch would start its own sidecar
+//! and fight the first one over the local API port, so the plugin hands its
+//! command line to the running instance and exits before `setup` runs. The
+//! running instance brings its dashboard forward, imports any settings
+//! bundles among the arguments, and re-emits them as `app://second-instance`
+//! for whatever else wants them (deep links).
+
+use std::path::Path;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+pub const SECOND_INSTANCE_EVENT: &str = "app://second-instance";
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SecondInstance {
+    /// Arguments after the executable path.
+    pub args: Vec<String>,
+    pub cwd: String,
+}
+
+/// Plugin callback, run in the first instance.
+pub fn on_second_instance(app: &AppHandle, argv: Vec<String>, cwd: String) {
+    let args: Vec<String> = argv.into_iter().skip(1).collect();
+    tracing::info!(target: "app", args = ?args, "second instance launched; focusing existing window");
+    crate::tray::show_dashboard(app);
+    crate::file_import::on_open(app, crate::file_import::bundle_paths(&args, Path::new(&cwd)));
+    let _ = app.emit(SECOND_INSTANCE_EVENT, SecondInstance { args, cwd });
+}
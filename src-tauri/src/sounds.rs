@@ -0,0 +1,261 @@
+//! Alert sounds played by the shell rather than the webview, whose audio
+//! stalls while the window is unfocused or hidden to the tray. Each
+//! severity has its own built-in tone pattern, or the WAV file set for it
+//! in `sounds.files`, played at `sounds.volume`.
+//!
+//! Sounds stay silent during Do Not Disturb (see `focus_mode`) and between
+//! `sounds.quiet_from` and `sounds.quiet_until`; critical ones still play
+//! through both while `focus_mode.critical_breaks_through` is on. Sounds
+//! play one at a time on a player thread, and a burst of alerts that
+//! arrives while one is playing is played once, at its highest severity.
+
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::NaiveTime;
+use rodio::source::{SineWave, Zero};
+use rodio::{Decoder, OutputStream, OutputStreamBuilder, Sink, Source};
+use tauri::{AppHandle, Manager, Webview};
+
+use crate::badging::AlertSeverity;
+use crate::config::{DesktopConfig, DesktopConfigState};
+
+const SAMPLE_RATE: u32 = 44_100;
+/// Tones are mixed below full scale, leaving `sounds.volume` the headroom.
+const TONE_LEVEL: f32 = 0.4;
+
+/// A sound to play.
+struct Cue {
+    severity: AlertSeverity,
+    file: Option<PathBuf>,
+    volume: f32,
+}
+
+#[derive(Default)]
+pub struct SoundsState {
+    sender: Mutex<Option<mpsc::Sender<Cue>>>,
+}
+
+/// The built-in pattern for `severity`: (frequency in Hz, milliseconds)
+/// pairs, where a zero frequency is a pause.
+fn tones(severity: AlertSeverity) -> &'static [(f32, u64)] {
+    match severity {
+        AlertSeverity::Low => &[(660.0, 120)],
+        AlertSeverity::Medium => &[(880.0, 120), (0.0, 80), (880.0, 120)],
+        AlertSeverity::High => &[(880.0, 120), (0.0, 60), (1100.0, 120), (0.0, 60), (1320.0, 180)],
+        AlertSeverity::Critical => &[
+            (1320.0, 150),
+            (880.0, 150),
+            (1320.0, 150),
+            (880.0, 150),
+            (0.0, 150),
+            (1320.0, 150),
+            (880.0, 150),
+            (1320.0, 150),
+            (880.0, 150),
+        ],
+    }
+}
+
+/// Whether `now` falls between `from` and `until` (`HH:MM`), which may span
+/// midnight. Unset or unreadable times mean no quiet hours.
+fn in_quiet_hours(from: &str, until: &str, now: NaiveTime) -> bool {
+    let parse = |time: &str| NaiveTime::parse_from_str(time.trim(), "%H:%M").ok();
+    let (Some(from), Some(until)) = (parse(from), parse(until)) else {
+        return false;
+    };
+    if from <= until {
+        from <= now && now < until
+    } else {
+        now >= from || now < until
+    }
+}
+
+/// Whether quiet hours keep an alert silent at `now`. `speech` follows them
+/// too.
+pub fn quiet(config: &DesktopConfig, critical: bool, now: NaiveTime) -> bool {
+    in_quiet_hours(&config.sounds.quiet_from, &config.sounds.quiet_until, now)
+        && !(critical && config.focus_mode.critical_breaks_through)
+}
+
+/// Whether an alert of `severity` may play a sound at `now`.
+fn audible(config: &DesktopConfig, focus_allows: bool, severity: AlertSeverity, now: NaiveTime) -> bool {
+    config.sounds.enabled
+        && config.sounds.volume > 0.0
+        && focus_allows
+        && !quiet(config, severity == AlertSeverity::Critical, now)
+}
+
+fn append(sink: &Sink, cue: &Cue) {
+    if let Some(path) = &cue.file {
+        let decoded = File::open(path)
+            .map_err(|e| e.to_string())
+            .and_then(|file| Decoder::try_from(file).map_err(|e| e.to_string()));
+        match decoded {
+            Ok(decoder) => return sink.append(decoder),
+            Err(err) => tracing::warn!(target: "app", path = %path.display(), "failed to read alert sound: {err}"),
+        }
+    }
+    for &(frequency, millis) in tones(cue.severity) {
+        let duration = Duration::from_millis(millis);
+        if frequency > 0.0 {
+            sink.append(SineWave::new(frequency).take_duration(duration).amplify(TONE_LEVEL));
+        } else {
+            sink.append(Zero::new(1, SAMPLE_RATE).take_duration(duration));
+        }
+    }
+}
+
+/// The default output, with `failed` set once the stream reports an error.
+fn open(failed: &Arc<AtomicBool>) -> Result<OutputStream, rodio::StreamError> {
+    let failed = failed.clone();
+    OutputStreamBuilder::from_default_device()?
+        .with_error_callback(move |err| {
+            tracing::warn!(target: "app", "alert sound output failed: {err}");
+            failed.store(true, Ordering::Relaxed);
+        })
+        .open_stream_or_fallback()
+}
+
+fn run(receiver: mpsc::Receiver<Cue>) {
+    // Opened on first use, and again after the stream fails, e.g. because
+    // the device went away.
+    let mut stream: Option<OutputStream> = None;
+    let failed = Arc::new(AtomicBool::new(false));
+    while let Ok(mut cue) = receiver.recv() {
+        while let Ok(next) = receiver.try_recv() {
+            if next.severity >= cue.severity {
+                cue = next;
+            }
+        }
+        if failed.swap(false, Ordering::Relaxed) {
+            stream = None;
+        }
+        if stream.is_none() {
+            match open(&failed) {
+                Ok(mut opened) => {
+                    opened.log_on_drop(false);
+                    stream = Some(opened);
+                }
+                Err(err) => {
+                    tracing::warn!(target: "app", "no audio output for alert sounds: {err}");
+                    continue;
+                }
+            }
+        }
+        let Some(output) = &stream else {
+            continue;
+        };
+        let sink = Sink::connect_new(output.mixer());
+        sink.set_volume(cue.volume);
+        append(&sink, &cue);
+        sink.sleep_until_end();
+    }
+}
+
+/// Start the player thread.
+pub fn start(app: &AppHandle) {
+    let (sender, receiver) = mpsc::channel();
+    match std::thread::Builder::new()
+        .name("sound-player".into())
+        .spawn(move || run(receiver))
+    {
+        Ok(_) => {
+            *app.state::<SoundsState>().sender.lock().unwrap_or_else(|e| e.into_inner()) = Some(sender);
+        }
+        Err(e) => tracing::warn!(target: "app", "failed to start sound player: {e}"),
+    }
+}
+
+/// Play the alert sound for `severity`, unless sounds are off or held back.
+/// Returns whether it was played.
+pub fn play(app: &AppHandle, severity: AlertSeverity) -> Result<bool, String> {
+    let config = app.state::<DesktopConfigState>().snapshot();
+    let focus_allows = crate::focus_mode::allows(app, severity == AlertSeverity::Critical);
+    if !audible(&config, focus_allows, severity, chrono::Local::now().time()) {
+        tracing::debug!(target: "app", ?severity, "alert sound held back");
+        return Ok(false);
+    }
+    let cue = Cue {
+        severity,
+        file: config.sounds.files.get(&severity).filter(|path| !path.trim().is_empty()).map(PathBuf::from),
+        volume: config.sounds.volume.clamp(0.0, 1.0) as f32,
+    };
+    let state = app.state::<SoundsState>();
+    let sender = state.sender.lock().unwrap_or_else(|e| e.into_inner());
+    let sender = sender.as_ref().ok_or("Sound player is not running")?;
+    sender.send(cue).map_err(|_| "Sound player stopped".to_string())?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn play_sound(webview: Webview, app: AppHandle, severity: AlertSeverity) -> Result<bool, String> {
+    crate::metrics::of(&webview).observe("play_sound", || {
+        crate::require_trusted_window(webview.label())?;
+        play(&app, severity)
+    })
+}
+
+/// `volume` limited to 0..=1; not a number is an error.
+pub fn clamp_volume(volume: f64) -> Result<f64, String> {
+    if !volume.is_finite() {
+        return Err("Volume must be a number".to_string());
+    }
+    Ok(volume.clamp(0.0, 1.0))
+}
+
+/// Set the alert sound volume, from 0 to 1. Returns the stored value.
+#[tauri::command]
+pub async fn set_sound_volume(webview: Webview, app: AppHandle, volume: f64) -> Result<f64, String> {
+    crate::metrics::of(&webview)
+        .observe_async("set_sound_volume", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                let config = app.state::<DesktopConfigState>();
+                let volume = clamp_volume(volume)?;
+                config.update(|c| c.sounds.volume = volume)?;
+                tracing::info!(target: "app", volume, "alert sound volume changed");
+                Ok(volume)
+            })
+            .await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod sounds_tests {
+    use super::*;
+
+    fn at(time: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(time, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn quiet_hours_may_span_midnight() {
+        assert!(in_quiet_hours("22:00", "07:00", at("23:30")));
+        assert!(in_quiet_hours("22:00", "07:00", at("06:59")));
+        assert!(!in_quiet_hours("22:00", "07:00", at("07:00")));
+        assert!(in_quiet_hours("13:00", "14:00", at("13:00")));
+        assert!(!in_quiet_hours("13:00", "14:00", at("14:30")));
+        assert!(!in_quiet_hours("", "07:00", at("03:00")));
+    }
+
+    #[test]
+    fn critical_alerts_sound_through_quiet_hours() {
+        let mut config = DesktopConfig::default();
+        config.sounds.quiet_from = "22:00".to_string();
+        config.sounds.quiet_until = "07:00".to_string();
+        assert!(audible(&config, true, AlertSeverity::High, at("12:00")));
+        assert!(!audible(&config, true, AlertSeverity::High, at("23:00")));
+        assert!(audible(&config, true, AlertSeverity::Critical, at("23:00")));
+        assert!(!audible(&config, false, AlertSeverity::Critical, at("12:00")));
+        config.focus_mode.critical_breaks_through = false;
+        assert!(!audible(&config, true, AlertSeverity::Critical, at("23:00")));
+        config.sounds.volume = 0.0;
+        assert!(!audible(&config, true, AlertSeverity::Critical, at("12:00")));
+    }
+}
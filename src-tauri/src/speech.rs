@@ -0,0 +1,320 @@
+//! Spoken briefings through the platform's text-to-speech, for operators
+//! watching other screens. `speak` reads any text aloud and `speak_digest`
+//! (also "Read Latest Digest" in the tray) the last delivered digest, or
+//! what one would say now if none has gone out yet. With
+//! `speech.announce_alerts` critical alerts are read out as they fire, and
+//! with `speech.announce_digests` each digest as it is delivered.
+//! Announcements follow Do Not Disturb (see `focus_mode`) and the alert
+//! sound quiet hours (see `sounds`).
+//!
+//! What each platform speaks with:
+//!
+//! - macOS: `say`.
+//! - Windows: System.Speech, through PowerShell.
+//! - Linux: Speech Dispatcher's `spd-say`, else `espeak-ng` or `espeak`.
+//!
+//! Text is handed over on standard input, never on a command line.
+//! Utterances are spoken one at a time; `stop_speaking` cuts off the
+//! current one and drops the rest.
+
+use std::io::{self, Write};
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, Webview};
+
+use crate::alerts::FiredAlert;
+use crate::config::{DesktopConfigState, SpeechConfig};
+use crate::digest::Digest;
+use crate::notifications::NotificationUrgency;
+
+const MAX_CHARS: usize = 4_000;
+/// How often a running utterance is checked for having finished.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Words per minute at `speech.rate` 1.
+const WORDS_PER_MINUTE: f64 = 175.0;
+
+struct Utterance {
+    text: String,
+    /// `SpeechState::generation` when queued; older ones were stopped.
+    generation: u64,
+}
+
+#[derive(Default)]
+pub struct SpeechState {
+    sender: Mutex<Option<mpsc::Sender<Utterance>>>,
+    speaking: Mutex<Option<Child>>,
+    generation: AtomicU64,
+}
+
+/// A text-to-speech program and its arguments.
+#[derive(Debug, PartialEq)]
+struct Invocation {
+    program: &'static str,
+    args: Vec<String>,
+}
+
+/// `say` reads standard input when given no text.
+fn say(voice: &str, rate: f64) -> Vec<Invocation> {
+    let mut args = vec!["-r".to_string(), format!("{:.0}", WORDS_PER_MINUTE * rate)];
+    if !voice.is_empty() {
+        args.extend(["-v".to_string(), voice.to_string()]);
+    }
+    vec![Invocation { program: "say", args }]
+}
+
+fn powershell(voice: &str, rate: f64) -> Vec<Invocation> {
+    let mut script = format!(
+        "[Console]::InputEncoding = [Text.Encoding]::UTF8; Add-Type -AssemblyName System.Speech; \
+         $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; $s.Rate = {};",
+        ((rate - 1.0) * 10.0).round().clamp(-10.0, 10.0)
+    );
+    if !voice.is_empty() {
+        script.push_str(&format!(" $s.SelectVoice('{}');", voice.replace('\'', "''")));
+    }
+    script.push_str(" $s.Speak([Console]::In.ReadToEnd())");
+    vec![Invocation {
+        program: "powershell",
+        args: ["-NoProfile", "-NonInteractive", "-Command", &script].map(str::to_string).to_vec(),
+    }]
+}
+
+fn linux(voice: &str, rate: f64) -> Vec<Invocation> {
+    let mut spd = vec![
+        "--wait".to_string(),
+        "--rate".to_string(),
+        format!("{:.0}", ((rate - 1.0) * 100.0).clamp(-100.0, 100.0)),
+    ];
+    let mut espeak = vec!["-s".to_string(), format!("{:.0}", WORDS_PER_MINUTE * rate)];
+    if !voice.is_empty() {
+        spd.extend(["--synthesis-voice".to_string(), voice.to_string()]);
+        espeak.extend(["-v".to_string(), voice.to_string()]);
+    }
+    spd.push("--pipe-mode".to_string());
+    espeak.push("--stdin".to_string());
+    vec![
+        Invocation { program: "spd-say", args: spd },
+        Invocation { program: "espeak-ng", args: espeak.clone() },
+        Invocation { program: "espeak", args: espeak },
+    ]
+}
+
+/// The programs to try, in order, on this platform.
+fn invocations(config: &SpeechConfig) -> Vec<Invocation> {
+    let (voice, rate) = (config.voice.trim(), config.rate.clamp(0.5, 2.0));
+    if cfg!(target_os = "macos") {
+        say(voice, rate)
+    } else if cfg!(windows) {
+        powershell(voice, rate)
+    } else if cfg!(target_os = "linux") {
+        linux(voice, rate)
+    } else {
+        Vec::new()
+    }
+}
+
+/// `text` as it should be read: one line, symbols spelled out, clipped.
+fn spoken(text: &str) -> String {
+    let text = text.replace('≥', " at least ").replace('≤', " at most ").replace('\n', ". ");
+    let words: Vec<&str> = text.split_whitespace().collect();
+    words.join(" ").chars().take(MAX_CHARS).collect()
+}
+
+/// Start the first available program speaking `text`.
+fn spawn(config: &SpeechConfig, text: &str) -> Result<Child, String> {
+    for invocation in invocations(config) {
+        let mut command = Command::new(invocation.program);
+        command.args(&invocation.args).stdin(Stdio::piped()).stdout(Stdio::null()).stderr(Stdio::null());
+        #[cfg(windows)]
+        command.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(format!("Failed to start {}: {err}", invocation.program)),
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(text.as_bytes())
+                .map_err(|e| format!("Failed to pass text to {}: {e}", invocation.program))?;
+        }
+        return Ok(child);
+    }
+    Err("No text-to-speech engine found".to_string())
+}
+
+fn run(app: AppHandle, receiver: mpsc::Receiver<Utterance>) {
+    let state = app.state::<SpeechState>();
+    while let Ok(utterance) = receiver.recv() {
+        if utterance.generation != state.generation.load(Ordering::SeqCst) {
+            continue;
+        }
+        let config = app.state::<DesktopConfigState>().snapshot().speech;
+        match spawn(&config, &utterance.text) {
+            Ok(child) => *state.speaking.lock().unwrap_or_else(|e| e.into_inner()) = Some(child),
+            Err(err) => {
+                tracing::warn!(target: "app", "speech failed: {err}");
+                continue;
+            }
+        }
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let mut speaking = state.speaking.lock().unwrap_or_else(|e| e.into_inner());
+            let done = match speaking.as_mut() {
+                Some(child) => !matches!(child.try_wait(), Ok(None)),
+                None => true,
+            };
+            if done {
+                *speaking = None;
+                break;
+            }
+        }
+    }
+}
+
+/// Start the speaker thread.
+pub fn start(app: &AppHandle) {
+    let (sender, receiver) = mpsc::channel();
+    let handle = app.clone();
+    match std::thread::Builder::new()
+        .name("speech".into())
+        .spawn(move || run(handle, receiver))
+    {
+        Ok(_) => {
+            *app.state::<SpeechState>().sender.lock().unwrap_or_else(|e| e.into_inner()) = Some(sender);
+        }
+        Err(e) => tracing::warn!(target: "app", "failed to start speech: {e}"),
+    }
+}
+
+/// Stop speaking and drop what is queued.
+pub fn stop(app: &AppHandle) {
+    let state = app.state::<SpeechState>();
+    state.generation.fetch_add(1, Ordering::SeqCst);
+    let mut speaking = state.speaking.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(child) = speaking.as_mut() {
+        let _ = child.kill();
+    }
+}
+
+/// Queue `text` to be read aloud, after what is already queued unless
+/// `interrupt` is set.
+pub fn say_text(app: &AppHandle, text: &str, interrupt: bool) -> Result<(), String> {
+    if !app.state::<DesktopConfigState>().snapshot().speech.enabled {
+        return Err("Speech is turned off".to_string());
+    }
+    let text = spoken(text);
+    if text.is_empty() {
+        return Ok(());
+    }
+    if interrupt {
+        stop(app);
+    }
+    let state = app.state::<SpeechState>();
+    let utterance = Utterance {
+        text,
+        generation: state.generation.load(Ordering::SeqCst),
+    };
+    let sender = state.sender.lock().unwrap_or_else(|e| e.into_inner());
+    let sender = sender.as_ref().ok_or("Speech is not running")?;
+    sender.send(utterance).map_err(|_| "Speech stopped".to_string())
+}
+
+fn digest_text(digest: &Digest) -> String {
+    format!("{}. {}", digest.title, digest.body)
+}
+
+/// Read the latest digest aloud, interrupting whatever is being said.
+pub async fn read_digest(app: &AppHandle) -> Result<Digest, String> {
+    let digest = crate::digest::latest(app).await?;
+    say_text(app, &digest_text(&digest), true)?;
+    Ok(digest)
+}
+
+/// Whether an announcement may be spoken now.
+fn may_announce(app: &AppHandle, critical: bool) -> bool {
+    let config = app.state::<DesktopConfigState>().snapshot();
+    config.speech.enabled
+        && crate::focus_mode::allows(app, critical)
+        && !crate::sounds::quiet(&config, critical, chrono::Local::now().time())
+}
+
+/// Read out a critical alert that just fired, if `speech.announce_alerts`.
+pub fn announce_alert(app: &AppHandle, alert: &FiredAlert, urgency: NotificationUrgency) {
+    if urgency != NotificationUrgency::Critical
+        || !app.state::<DesktopConfigState>().snapshot().speech.announce_alerts
+        || !may_announce(app, true)
+    {
+        return;
+    }
+    if let Err(err) = say_text(app, &format!("Alert. {}. {}", alert.title, alert.body), false) {
+        tracing::warn!(target: "app", "alert announcement failed: {err}");
+    }
+}
+
+/// Read out a digest just delivered, if `speech.announce_digests`.
+pub fn announce_digest(app: &AppHandle, digest: &Digest) {
+    if !app.state::<DesktopConfigState>().snapshot().speech.announce_digests || !may_announce(app, false) {
+        return;
+    }
+    if let Err(err) = say_text(app, &digest_text(digest), false) {
+        tracing::warn!(target: "app", "digest announcement failed: {err}");
+    }
+}
+
+/// Read `text` aloud. `interrupt` cuts off what is being said instead of
+/// waiting for it.
+#[tauri::command]
+pub fn speak(webview: Webview, app: AppHandle, text: String, interrupt: Option<bool>) -> Result<(), String> {
+    crate::metrics::of(&webview).observe("speak", || {
+        crate::require_trusted_window(webview.label())?;
+        say_text(&app, &text, interrupt.unwrap_or(false))
+    })
+}
+
+#[tauri::command]
+pub fn stop_speaking(webview: Webview, app: AppHandle) -> Result<(), String> {
+    crate::metrics::of(&webview).observe("stop_speaking", || {
+        crate::require_trusted_window(webview.label())?;
+        stop(&app);
+        Ok(())
+    })
+}
+
+/// Read the latest digest aloud and return it.
+#[tauri::command]
+pub async fn speak_digest(webview: Webview, app: AppHandle) -> Result<Digest, String> {
+    crate::metrics::of(&webview)
+        .observe_async("speak_digest", async move {
+            crate::require_trusted_window(webview.label())?;
+            read_digest(&app).await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod speech_tests {
+    use super::*;
+
+    #[test]
+    fn builds_engine_arguments() {
+        assert_eq!(say("Samantha", 1.0)[0].args, ["-r", "175", "-v", "Samantha"]);
+        let script = &powershell("Microsoft Zira's", 1.5)[0].args[3];
+        assert!(script.contains("$s.Rate = 5;"));
+        assert!(script.contains("$s.SelectVoice('Microsoft Zira''s');"));
+        let engines = linux("", 0.5);
+        assert_eq!(engines[0].args, ["--wait", "--rate", "-50", "--pipe-mode"]);
+        assert_eq!(engines[1], Invocation { program: "espeak-ng", args: vec!["-s".into(), "88".into(), "--stdin".into()] });
+    }
+
+    #[test]
+    fn reads_digest_lines_as_sentences() {
+        assert_eq!(
+            spoken("2 quakes ≥ M5.0: M6.4 Chile\n  1 alert: Hormuz"),
+            "2 quakes at least M5.0: M6.4 Chile. 1 alert: Hormuz"
+        );
+    }
+}
@@ -0,0 +1,37 @@
+//! Launch splash. The main window is not created by the config
+//! (`"create": false`) because the dashboard resolves the local API port as
+//! soon as it loads; instead a small frameless `splash` window appears at
+//! once, node resolution and the sidecar readiness probe run on a background
+//! thread, and only then is the main window built and the splash closed.
+
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+pub const SPLASH_WINDOW: &str = "splash";
+
+/// Show the splash. Failure only costs the splash, never the launch.
+pub fn show(app: &AppHandle) {
+    let built = WebviewWindowBuilder::new(app, SPLASH_WINDOW, WebviewUrl::App("splash.html".into()))
+        .title(&app.package_info().name)
+        .inner_size(360.0, 240.0)
+        .resizable(false)
+        .decorations(false)
+        .center()
+        .skip_taskbar(true)
+        .background_color(tauri::webview::Color(26, 28, 30, 255))
+        .build();
+    match built {
+        #[cfg(not(target_os = "macos"))]
+        Ok(window) => {
+            let _ = window.remove_menu();
+        }
+        #[cfg(target_os = "macos")]
+        Ok(_) => {}
+        Err(err) => tracing::warn!(target: "app", "failed to show splash window: {err}"),
+    }
+}
+
+pub fn close(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(SPLASH_WINDOW) {
+        let _ = window.close();
+    }
+}
@@ -0,0 +1,87 @@
+//! Startup phase timing. Phases (menu build, node resolution, sidecar spawn,
+//! readiness probe) are recorded relative to process start until the main
+//! window finishes its first page load; at that point a one-line summary is
+//! logged and the timeline is frozen for `get_startup_report`.
+
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::Webview;
+
+static PROCESS_START: OnceLock<Instant> = OnceLock::new();
+static TIMELINE: Mutex<StartupReport> = Mutex::new(StartupReport {
+    phases: Vec::new(),
+    first_window_shown_ms: None,
+});
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PhaseTiming {
+    phase: &'static str,
+    /// Offset from process start.
+    started_at_ms: u64,
+    duration_ms: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct StartupReport {
+    phases: Vec<PhaseTiming>,
+    /// Process start to the main window's first finished page load; `None`
+    /// while startup is still in progress.
+    first_window_shown_ms: Option<u64>,
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+fn process_start() -> Instant {
+    *PROCESS_START.get_or_init(Instant::now)
+}
+
+/// Anchor the timeline. Call first thing in `main`.
+pub fn begin() {
+    process_start();
+}
+
+/// Run `f` and record it as a startup phase. Once startup has completed
+/// (e.g. a later sidecar restart) the timing is not recorded.
+pub fn measure<T>(phase: &'static str, f: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let result = f();
+    let mut timeline = TIMELINE.lock().unwrap_or_else(|e| e.into_inner());
+    if timeline.first_window_shown_ms.is_none() {
+        timeline.phases.push(PhaseTiming {
+            phase,
+            started_at_ms: millis(started.saturating_duration_since(process_start())),
+            duration_ms: millis(started.elapsed()),
+        });
+    }
+    result
+}
+
+/// Close the timeline when the main window first finishes loading and log
+/// the summary line. Later calls (reloads) are ignored.
+pub fn mark_first_window_shown() {
+    let mut timeline = TIMELINE.lock().unwrap_or_else(|e| e.into_inner());
+    if timeline.first_window_shown_ms.is_some() {
+        return;
+    }
+    let total_ms = millis(process_start().elapsed());
+    timeline.first_window_shown_ms = Some(total_ms);
+    let phases = timeline
+        .phases
+        .iter()
+        .map(|p| format!("{}={}ms", p.phase, p.duration_ms))
+        .collect::<Vec<_>>()
+        .join(" ");
+    tracing::info!(target: "app", total_ms, "startup complete: {phases}");
+}
+
+#[tauri::command]
+pub fn get_startup_report(webview: Webview) -> Result<StartupReport, String> {
+    crate::metrics::of(&webview).observe("get_startup_report", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(TIMELINE.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
@@ -0,0 +1,2401 @@
+//! Local SQLite store (`events.sqlite` in the app data directory), so event
+//! panels keep working offline and the app can answer questions like "what
+//! happened in this area in the last day".
+//!
+//! Feeds synced from upstream (`acled`, `gdelt`, `cloudflare`) write their
+//! events under their own `source` name, keyed by the feed's event id, along
+//! with a sync cursor they can resume from. Those events are stored as the JSON the
+//! dashboard expects, next to the columns queries filter on: event date,
+//! event type and coordinates. News headlines from `news` live in their own
+//! table, one row per link, so the same story from two feeds is kept once,
+//! and earthquakes from `usgs` in theirs, one row per USGS event id with the
+//! magnitude alongside the time and epicentre. Volcano notices and tsunami
+//! bulletins from `hazards` share a table, one row per volcano or bulletin
+//! with its kind and alert level. FRED series from `fred` keep
+//! their metadata and one row per observation date, and EIA datasets from
+//! `eia` one row per period. The `watchlists` are kept here too, one row per
+//! kind and value, and the airspace notices `faa` finds active (TFRs and
+//! NOTAMs), one GeoJSON feature per notice with its bounding box. Space
+//! weather from `swpc` keeps one row per series and reading time (Kp, solar
+//! wind) and one per flare or SWPC message. Cyber threat indicators from
+//! `cyber` are kept one row per indicator, merged across feeds, and CISA's
+//! known exploited vulnerabilities one row per CVE. Market quotes from
+//! `markets` keep the latest quote per symbol and one row per price tick.
+//! Headlines `translate_text` has translated are cached one row per target
+//! language and source text.
+//!
+//! Everything is also written to a normalized `timeline` of observations
+//! (flights, vessels, incidents, headlines, quakes, hazards) with a time and, where
+//! there is a position, a geohash; both are indexed, so `query_events` and
+//! `aggregate_events` can filter by area and time range across kinds.
+//! Aircraft and vessel tracks are sampled every `TRACK_INTERVAL` per
+//! subject, and sooner where one turns, so `get_track` can hand the map a
+//! trail for a selected aircraft or ship. The timeline keeps `RETENTION`
+//! unless pruned sooner with `prune_events`. If the file cannot be opened
+//! the store runs in memory for the session.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use geo::{Bearing, Distance, Haversine, Point};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Webview};
+
+use crate::ais::BoundingBox;
+use crate::hazards::{HazardKind, HazardLevel};
+
+pub const STORE_FILE: &str = "events.sqlite";
+/// The timeline source of quakes from `upsert_quakes`.
+pub const QUAKE_SOURCE: &str = "usgs";
+
+const DEFAULT_LIMIT: usize = 500;
+const MAX_LIMIT: usize = 10_000;
+/// How often an aircraft or vessel's position goes into the timeline.
+const TRACK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// Turns are sampled at most this often...
+const TURN_INTERVAL: Duration = Duration::from_secs(30);
+/// ...when the course changes by this many degrees...
+const TURN_DEGREES: f64 = 15.0;
+/// ...over at least this many metres, so GPS jitter at anchor is ignored.
+const TURN_DISTANCE: f64 = 500.0;
+const RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// About 38 m × 19 m.
+const GEOHASH_PRECISION: usize = 8;
+/// Most geohash cells a bounding box query is turned into.
+const MAX_COVER_CELLS: usize = 32;
+const MAX_RECORDED: usize = 5_000;
+const GEOHASH_ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS events (
+        source TEXT NOT NULL,
+        id TEXT NOT NULL,
+        event_date TEXT NOT NULL,
+        event_type TEXT NOT NULL,
+        lat REAL NOT NULL,
+        lon REAL NOT NULL,
+        data TEXT NOT NULL,
+        PRIMARY KEY (source, id)
+    );
+    CREATE INDEX IF NOT EXISTS events_by_date ON events (source, event_date);
+    CREATE TABLE IF NOT EXISTS sync_state (
+        source TEXT PRIMARY KEY,
+        cursor TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS news_items (
+        link TEXT PRIMARY KEY,
+        source TEXT NOT NULL,
+        published INTEGER NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS news_by_published ON news_items (published);
+    CREATE TABLE IF NOT EXISTS quakes (
+        id TEXT PRIMARY KEY,
+        time INTEGER NOT NULL,
+        magnitude REAL NOT NULL,
+        lat REAL NOT NULL,
+        lon REAL NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS quakes_by_time ON quakes (time);
+    CREATE TABLE IF NOT EXISTS hazards (
+        id TEXT PRIMARY KEY,
+        kind TEXT NOT NULL,
+        level INTEGER NOT NULL,
+        time INTEGER NOT NULL,
+        lat REAL,
+        lon REAL,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS hazards_by_time ON hazards (time);
+    CREATE TABLE IF NOT EXISTS fred_series (
+        id TEXT PRIMARY KEY,
+        title TEXT NOT NULL,
+        units TEXT NOT NULL,
+        frequency TEXT NOT NULL,
+        last_updated TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS eia_observations (
+        dataset TEXT NOT NULL,
+        period TEXT NOT NULL,
+        value REAL NOT NULL,
+        PRIMARY KEY (dataset, period)
+    );
+    CREATE TABLE IF NOT EXISTS fred_observations (
+        series TEXT NOT NULL,
+        date TEXT NOT NULL,
+        value REAL NOT NULL,
+        PRIMARY KEY (series, date)
+    );
+    CREATE TABLE IF NOT EXISTS watchlist (
+        kind TEXT NOT NULL,
+        value TEXT NOT NULL,
+        label TEXT NOT NULL,
+        region TEXT,
+        added INTEGER NOT NULL,
+        PRIMARY KEY (kind, value)
+    );
+    CREATE TABLE IF NOT EXISTS airspace_notices (
+        id TEXT PRIMARY KEY,
+        source TEXT NOT NULL,
+        south REAL NOT NULL,
+        west REAL NOT NULL,
+        north REAL NOT NULL,
+        east REAL NOT NULL,
+        ends INTEGER,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS space_weather (
+        series TEXT NOT NULL,
+        time INTEGER NOT NULL,
+        value REAL NOT NULL,
+        PRIMARY KEY (series, time)
+    );
+    CREATE TABLE IF NOT EXISTS space_weather_events (
+        id TEXT PRIMARY KEY,
+        time INTEGER NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS space_weather_events_by_time ON space_weather_events (time);
+    CREATE TABLE IF NOT EXISTS market_quotes (
+        symbol TEXT PRIMARY KEY,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS market_ticks (
+        symbol TEXT NOT NULL,
+        time INTEGER NOT NULL,
+        price REAL NOT NULL,
+        PRIMARY KEY (symbol, time)
+    );
+    CREATE TABLE IF NOT EXISTS threat_indicators (
+        indicator_type TEXT NOT NULL,
+        indicator TEXT NOT NULL,
+        severity INTEGER NOT NULL,
+        last_seen INTEGER NOT NULL,
+        lat REAL,
+        lon REAL,
+        data TEXT NOT NULL,
+        PRIMARY KEY (indicator_type, indicator)
+    );
+    CREATE INDEX IF NOT EXISTS threat_indicators_by_last_seen ON threat_indicators (last_seen);
+    CREATE TABLE IF NOT EXISTS exploited_vulnerabilities (
+        cve TEXT PRIMARY KEY,
+        added TEXT NOT NULL,
+        ransomware INTEGER NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS translations (
+        lang TEXT NOT NULL,
+        text TEXT NOT NULL,
+        translated TEXT NOT NULL,
+        time INTEGER NOT NULL,
+        PRIMARY KEY (lang, text)
+    );
+    CREATE INDEX IF NOT EXISTS translations_by_time ON translations (time);
+    CREATE TABLE IF NOT EXISTS timeline (
+        kind TEXT NOT NULL,
+        source TEXT NOT NULL,
+        id TEXT NOT NULL,
+        time INTEGER NOT NULL,
+        lat REAL,
+        lon REAL,
+        geohash TEXT,
+        title TEXT NOT NULL,
+        data TEXT NOT NULL,
+        PRIMARY KEY (kind, source, id, time)
+    );
+    CREATE INDEX IF NOT EXISTS timeline_by_time ON timeline (time);
+    CREATE INDEX IF NOT EXISTS timeline_by_geohash ON timeline (geohash, time);
+    CREATE INDEX IF NOT EXISTS timeline_by_subject ON timeline (id, time);
+";
+
+/// One event as a feed hands it to the store.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StoredEvent {
+    pub id: String,
+    /// `YYYY-MM-DD`, so dates compare as text.
+    pub event_date: String,
+    pub event_type: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub data: Value,
+}
+
+/// Filters for `EventStore::query`. Dates are inclusive `YYYY-MM-DD`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EventQuery {
+    pub bounding_box: Option<BoundingBox>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    /// Any of these; all types when empty.
+    pub event_types: Vec<String>,
+    pub limit: Option<usize>,
+}
+
+/// One headline as `news` hands it to the store.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NewsRow {
+    pub link: String,
+    /// The feed's name.
+    pub source: String,
+    /// Milliseconds since the epoch.
+    pub published: i64,
+    pub data: Value,
+}
+
+/// Filters for `EventStore::news`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NewsQuery {
+    /// Feed names; all feeds when empty.
+    pub sources: Vec<String>,
+    /// Only items published at or after this, in milliseconds.
+    pub since: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+/// One earthquake as `usgs` hands it to the store.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuakeRow {
+    /// The USGS event id.
+    pub id: String,
+    /// Milliseconds since the epoch.
+    pub time: i64,
+    pub magnitude: f64,
+    pub lat: f64,
+    pub lon: f64,
+    pub data: Value,
+}
+
+/// Filters for `EventStore::quakes`. Times are inclusive milliseconds since
+/// the epoch.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuakeQuery {
+    pub bounding_box: Option<BoundingBox>,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub min_magnitude: Option<f64>,
+    pub limit: Option<usize>,
+}
+
+/// One volcano notice or tsunami bulletin as `hazards` hands it to the
+/// store.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HazardRow {
+    /// `volcano:<number>`, `gvp:<number>` or `tsunami:<bulletin id>`.
+    pub id: String,
+    pub kind: HazardKind,
+    pub level: HazardLevel,
+    /// The feed it came from, as the timeline source.
+    pub source: String,
+    pub title: String,
+    /// Milliseconds since the epoch.
+    pub time: i64,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    pub data: Value,
+}
+
+/// Filters for `EventStore::hazards`. Times are inclusive milliseconds
+/// since the epoch; every kind matches when `kinds` is empty.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HazardQuery {
+    pub kinds: Vec<HazardKind>,
+    pub min_level: Option<HazardLevel>,
+    pub bounding_box: Option<BoundingBox>,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+/// One watchlist entry as `watchlists` stores it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WatchlistRow {
+    pub kind: String,
+    pub value: String,
+    pub label: String,
+    pub region: Option<BoundingBox>,
+    /// Milliseconds since the epoch.
+    pub added: i64,
+}
+
+/// One active TFR or NOTAM as `faa` hands it to the store.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoticeRow {
+    pub id: String,
+    /// `tfr` or `notam`.
+    pub source: String,
+    pub bounds: BoundingBox,
+    /// Milliseconds since the epoch; open-ended when unset.
+    pub ends: Option<i64>,
+    /// A GeoJSON feature.
+    pub data: Value,
+}
+
+/// Filters for `EventStore::notices`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NoticeQuery {
+    /// `tfr` or `notam`; both when unset.
+    pub source: Option<String>,
+    /// Notices whose bounding box overlaps this one.
+    pub bounding_box: Option<BoundingBox>,
+}
+
+/// A flare or SWPC message as `swpc` hands it to the store.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpaceWeatherEventRow {
+    pub id: String,
+    /// Milliseconds since the epoch.
+    pub time: i64,
+    pub data: Value,
+}
+
+/// An indicator of compromise as `cyber` hands it to the store.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndicatorRow {
+    /// `ip`, `domain` or `url`.
+    pub indicator_type: String,
+    pub indicator: String,
+    /// 1 (low) to 4 (critical).
+    pub severity: u8,
+    /// Milliseconds since the epoch.
+    pub last_seen: i64,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    pub data: Value,
+}
+
+/// Filters for `EventStore::indicators`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IndicatorQuery {
+    /// Any of these; all types when empty.
+    pub indicator_types: Vec<String>,
+    /// 1 (low) to 4 (critical).
+    pub min_severity: Option<u8>,
+    /// Only indicators seen at or after this, in milliseconds.
+    pub since: Option<i64>,
+    /// Part of the indicator, case-insensitive.
+    pub search: Option<String>,
+    /// Only indicators placed inside this box.
+    pub bounding_box: Option<BoundingBox>,
+    pub limit: Option<usize>,
+}
+
+/// A known exploited vulnerability as `cyber` hands it to the store.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VulnerabilityRow {
+    pub cve: String,
+    /// `YYYY-MM-DD`, when CISA added it.
+    pub added: String,
+    /// Known to be used in ransomware campaigns.
+    pub ransomware: bool,
+    pub data: Value,
+}
+
+/// Filters for `EventStore::vulnerabilities`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VulnerabilityQuery {
+    /// Only entries added on or after this `YYYY-MM-DD`.
+    pub since: Option<String>,
+    /// Part of the CVE id, vendor, product or name, case-insensitive.
+    pub search: Option<String>,
+    pub ransomware: bool,
+    pub limit: Option<usize>,
+}
+
+/// A FRED series' metadata as `fred` stores it.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FredMeta {
+    pub id: String,
+    pub title: String,
+    pub units: String,
+    pub frequency: String,
+    /// FRED's own `last_updated`, compared to skip unchanged series.
+    pub last_updated: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    Flight,
+    Vessel,
+    Incident,
+    Headline,
+    Quake,
+    Hazard,
+}
+
+impl EventKind {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            EventKind::Flight => "flight",
+            EventKind::Vessel => "vessel",
+            EventKind::Incident => "incident",
+            EventKind::Headline => "headline",
+            EventKind::Quake => "quake",
+            EventKind::Hazard => "hazard",
+        }
+    }
+
+    fn parse(kind: &str) -> Option<Self> {
+        [
+            EventKind::Flight,
+            EventKind::Vessel,
+            EventKind::Incident,
+            EventKind::Headline,
+            EventKind::Quake,
+            EventKind::Hazard,
+        ]
+        .into_iter()
+            .find(|k| k.as_str() == kind)
+    }
+}
+
+/// One row of the normalized timeline.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Observation {
+    pub kind: EventKind,
+    /// The feed it came from (`opensky`, `ais`, `acled`, a news feed, ...).
+    pub source: String,
+    /// The subject's id within the source: ICAO24, MMSI, event id, link.
+    pub id: String,
+    /// Milliseconds since the epoch.
+    pub time: i64,
+    #[serde(default)]
+    pub lat: Option<f64>,
+    #[serde(default)]
+    pub lon: Option<f64>,
+    pub title: String,
+    #[serde(default)]
+    pub data: Value,
+}
+
+/// Filters for `query_events` and `aggregate_events`. Times are inclusive
+/// milliseconds since the epoch.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TimelineQuery {
+    /// Any of these; all kinds when empty.
+    pub kinds: Vec<EventKind>,
+    pub bounding_box: Option<BoundingBox>,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+/// One position of a `get_track` trail.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct TrackPoint {
+    /// Milliseconds since the epoch.
+    pub time: i64,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// The last position of a subject written to the timeline.
+#[derive(Clone, Copy, Debug)]
+struct Sample {
+    time: i64,
+    point: Point<f64>,
+    /// Bearing from the sample before, if it moved far enough to tell.
+    course: Option<f64>,
+}
+
+impl Sample {
+    /// The sample `o` would become if it is due, `None` if not.
+    fn next(last: Option<&Sample>, o: &Observation) -> Option<Sample> {
+        let point = Point::new(o.lon?, o.lat?);
+        let Some(last) = last else {
+            return Some(Sample { time: o.time, point, course: None });
+        };
+        let elapsed = o.time.saturating_sub(last.time);
+        let moved = Haversine.distance(last.point, point) >= TURN_DISTANCE;
+        let course = moved.then(|| Haversine.bearing(last.point, point));
+        let turned = match (last.course, course) {
+            (Some(before), Some(after)) => ((after - before + 180.0).rem_euclid(360.0) - 180.0).abs() >= TURN_DEGREES,
+            _ => false,
+        };
+        let due = elapsed >= TRACK_INTERVAL.as_millis() as i64
+            || (turned && elapsed >= TURN_INTERVAL.as_millis() as i64);
+        due.then_some(Sample { time: o.time, point, course: course.or(last.course) })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    Kind,
+    /// UTC hours, keyed `YYYY-MM-DDTHH:00Z`.
+    Hour,
+    /// UTC days, keyed `YYYY-MM-DD`.
+    Day,
+    /// Geohash cells of the given precision; rows without a position are
+    /// left out.
+    Geohash,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Bucket {
+    pub key: String,
+    pub count: u64,
+}
+
+pub struct EventStore {
+    connection: Mutex<Connection>,
+    /// When each tracked aircraft or vessel was last written to the timeline.
+    tracked: Mutex<HashMap<(EventKind, String), Sample>>,
+}
+
+fn sql_error(e: rusqlite::Error) -> String {
+    format!("Event store error: {e}")
+}
+
+impl EventStore {
+    /// Open (or create) the store at `path`, falling back to memory.
+    pub fn open(path: &Path) -> Self {
+        match Connection::open(path).map_err(sql_error).and_then(Self::init) {
+            Ok(store) => store,
+            Err(err) => {
+                tracing::warn!(target: "app", path = %path.display(), "event store in memory only: {err}");
+                Self::in_memory()
+            }
+        }
+    }
+
+    fn in_memory() -> Self {
+        Connection::open_in_memory()
+            .map_err(sql_error)
+            .and_then(Self::init)
+            .expect("an in-memory SQLite database always opens")
+    }
+
+    fn init(connection: Connection) -> Result<Self, String> {
+        connection.execute_batch(SCHEMA).map_err(sql_error)?;
+        Ok(EventStore {
+            connection: Mutex::new(connection),
+            tracked: Mutex::default(),
+        })
+    }
+
+    fn connection(&self) -> MutexGuard<'_, Connection> {
+        self.connection.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Insert or replace `events`, returning the ids that were new.
+    pub fn upsert(&self, source: &str, events: &[StoredEvent]) -> Result<Vec<String>, String> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction().map_err(sql_error)?;
+        let mut added = Vec::new();
+        {
+            let mut exists = transaction
+                .prepare_cached("SELECT 1 FROM events WHERE source = ?1 AND id = ?2")
+                .map_err(sql_error)?;
+            let mut insert = transaction
+                .prepare_cached(
+                    "INSERT OR REPLACE INTO events (source, id, event_date, event_type, lat, lon, data)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                )
+                .map_err(sql_error)?;
+            let mut timeline = transaction.prepare_cached(INSERT_OBSERVATION).map_err(sql_error)?;
+            for event in events {
+                if !exists.exists(params![source, event.id]).map_err(sql_error)? {
+                    added.push(event.id.clone());
+                }
+                let time = NaiveDate::parse_from_str(&event.event_date, "%Y-%m-%d")
+                    .map(|d| d.and_time(chrono::NaiveTime::MIN).and_utc().timestamp_millis())
+                    .unwrap_or(0);
+                insert_observation(
+                    &mut timeline,
+                    &Observation {
+                        kind: EventKind::Incident,
+                        source: source.to_string(),
+                        id: event.id.clone(),
+                        time,
+                        lat: Some(event.lat),
+                        lon: Some(event.lon),
+                        title: event.event_type.clone(),
+                        data: event.data.clone(),
+                    },
+                )?;
+                insert
+                    .execute(params![
+                        source,
+                        event.id,
+                        event.event_date,
+                        event.event_type,
+                        event.lat,
+                        event.lon,
+                        event.data.to_string(),
+                    ])
+                    .map_err(sql_error)?;
+            }
+        }
+        transaction.commit().map_err(sql_error)?;
+        Ok(added)
+    }
+
+    /// Matching events' JSON, newest first.
+    pub fn query(&self, source: &str, query: &EventQuery) -> Result<Vec<Value>, String> {
+        let mut sql = "SELECT data FROM events WHERE source = ?".to_string();
+        let mut args: Vec<rusqlite::types::Value> = vec![source.to_string().into()];
+        if let Some(start) = &query.start_date {
+            sql.push_str(" AND event_date >= ?");
+            args.push(start.clone().into());
+        }
+        if let Some(end) = &query.end_date {
+            sql.push_str(" AND event_date <= ?");
+            args.push(end.clone().into());
+        }
+        if let Some(b) = &query.bounding_box {
+            // A box with west > east crosses the antimeridian.
+            let join = if b.west <= b.east { "AND" } else { "OR" };
+            sql.push_str(&format!(" AND lat BETWEEN ? AND ? AND (lon >= ? {join} lon <= ?)"));
+            args.extend([b.south, b.north, b.west, b.east].map(rusqlite::types::Value::from));
+        }
+        if !query.event_types.is_empty() {
+            let placeholders = vec!["?"; query.event_types.len()].join(", ");
+            sql.push_str(&format!(" AND event_type IN ({placeholders})"));
+            args.extend(query.event_types.iter().map(|t| t.clone().into()));
+        }
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+        sql.push_str(&format!(" ORDER BY event_date DESC, id LIMIT {limit}"));
+
+        let connection = self.connection();
+        let mut statement = connection.prepare(&sql).map_err(sql_error)?;
+        let rows = statement
+            .query_map(params_from_iter(args), |row| row.get::<_, String>(0))
+            .map_err(sql_error)?;
+        let mut events = Vec::new();
+        for row in rows {
+            if let Ok(event) = serde_json::from_str(&row.map_err(sql_error)?) {
+                events.push(event);
+            }
+        }
+        Ok(events)
+    }
+
+    /// Delete `source`'s events dated before `date`, returning how many.
+    pub fn prune(&self, source: &str, date: &str) -> Result<usize, String> {
+        self.connection()
+            .execute("DELETE FROM events WHERE source = ?1 AND event_date < ?2", params![source, date])
+            .map_err(sql_error)
+    }
+
+    pub fn count(&self, source: &str) -> Result<usize, String> {
+        self.connection()
+            .query_row("SELECT COUNT(*) FROM events WHERE source = ?1", params![source], |row| row.get(0))
+            .map_err(sql_error)
+    }
+
+    pub fn cursor(&self, source: &str) -> Result<Option<String>, String> {
+        self.connection()
+            .query_row("SELECT cursor FROM sync_state WHERE source = ?1", params![source], |row| row.get(0))
+            .optional()
+            .map_err(sql_error)
+    }
+
+    pub fn set_cursor(&self, source: &str, cursor: &str) -> Result<(), String> {
+        self.connection()
+            .execute(
+                "INSERT OR REPLACE INTO sync_state (source, cursor) VALUES (?1, ?2)",
+                params![source, cursor],
+            )
+            .map(|_| ())
+            .map_err(sql_error)
+    }
+
+    /// Insert the headlines whose links are not stored yet, returning their
+    /// indices in `rows`.
+    pub fn insert_news(&self, rows: &[NewsRow]) -> Result<Vec<usize>, String> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction().map_err(sql_error)?;
+        let mut added = Vec::new();
+        {
+            let mut insert = transaction
+                .prepare_cached("INSERT OR IGNORE INTO news_items (link, source, published, data) VALUES (?1, ?2, ?3, ?4)")
+                .map_err(sql_error)?;
+            let mut timeline = transaction.prepare_cached(INSERT_OBSERVATION).map_err(sql_error)?;
+            for (i, row) in rows.iter().enumerate() {
+                if insert.execute(params![row.link, row.source, row.published, row.data.to_string()]).map_err(sql_error)? > 0 {
+                    added.push(i);
+                    insert_observation(
+                        &mut timeline,
+                        &Observation {
+                            kind: EventKind::Headline,
+                            source: row.source.clone(),
+                            id: row.link.clone(),
+                            time: row.published,
+                            lat: None,
+                            lon: None,
+                            title: row.data["title"].as_str().unwrap_or_default().to_string(),
+                            data: row.data.clone(),
+                        },
+                    )?;
+                }
+            }
+        }
+        transaction.commit().map_err(sql_error)?;
+        Ok(added)
+    }
+
+    /// Matching headlines' JSON, newest first.
+    pub fn news(&self, query: &NewsQuery) -> Result<Vec<Value>, String> {
+        let mut sql = "SELECT data FROM news_items WHERE published >= ?".to_string();
+        let mut args: Vec<rusqlite::types::Value> = vec![query.since.unwrap_or(i64::MIN).into()];
+        if !query.sources.is_empty() {
+            let placeholders = vec!["?"; query.sources.len()].join(", ");
+            sql.push_str(&format!(" AND source IN ({placeholders})"));
+            args.extend(query.sources.iter().map(|s| s.clone().into()));
+        }
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+        sql.push_str(&format!(" ORDER BY published DESC LIMIT {limit}"));
+
+        let connection = self.connection();
+        let mut statement = connection.prepare(&sql).map_err(sql_error)?;
+        let rows = statement
+            .query_map(params_from_iter(args), |row| row.get::<_, String>(0))
+            .map_err(sql_error)?;
+        let mut items = Vec::new();
+        for row in rows {
+            if let Ok(item) = serde_json::from_str(&row.map_err(sql_error)?) {
+                items.push(item);
+            }
+        }
+        Ok(items)
+    }
+
+    /// Delete headlines published before `published`, returning how many.
+    pub fn prune_news(&self, published: i64) -> Result<usize, String> {
+        self.connection()
+            .execute("DELETE FROM news_items WHERE published < ?1", params![published])
+            .map_err(sql_error)
+    }
+
+    /// Insert or replace `quakes` (USGS revises magnitudes and locations),
+    /// returning the indices of those that were new.
+    pub fn upsert_quakes(&self, quakes: &[QuakeRow]) -> Result<Vec<usize>, String> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction().map_err(sql_error)?;
+        let mut added = Vec::new();
+        {
+            let mut exists = transaction.prepare_cached("SELECT 1 FROM quakes WHERE id = ?1").map_err(sql_error)?;
+            let mut insert = transaction
+                .prepare_cached(
+                    "INSERT OR REPLACE INTO quakes (id, time, magnitude, lat, lon, data) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                )
+                .map_err(sql_error)?;
+            let mut timeline = transaction.prepare_cached(INSERT_OBSERVATION).map_err(sql_error)?;
+            for (i, quake) in quakes.iter().enumerate() {
+                if !exists.exists(params![quake.id]).map_err(sql_error)? {
+                    added.push(i);
+                }
+                insert
+                    .execute(params![quake.id, quake.time, quake.magnitude, quake.lat, quake.lon, quake.data.to_string()])
+                    .map_err(sql_error)?;
+                insert_observation(
+                    &mut timeline,
+                    &Observation {
+                        kind: EventKind::Quake,
+                        source: QUAKE_SOURCE.to_string(),
+                        id: quake.id.clone(),
+                        time: quake.time,
+                        lat: Some(quake.lat),
+                        lon: Some(quake.lon),
+                        title: format!("M{:.1} {}", quake.magnitude, quake.data["place"].as_str().unwrap_or_default()),
+                        data: quake.data.clone(),
+                    },
+                )?;
+            }
+        }
+        transaction.commit().map_err(sql_error)?;
+        Ok(added)
+    }
+
+    /// Matching quakes' JSON, newest first.
+    pub fn quakes(&self, query: &QuakeQuery) -> Result<Vec<Value>, String> {
+        let mut sql = "SELECT data FROM quakes WHERE time >= ? AND time <= ? AND magnitude >= ?".to_string();
+        let mut args: Vec<rusqlite::types::Value> = vec![
+            query.since.unwrap_or(i64::MIN).into(),
+            query.until.unwrap_or(i64::MAX).into(),
+            query.min_magnitude.unwrap_or(f64::MIN).into(),
+        ];
+        if let Some(b) = &query.bounding_box {
+            let join = if b.west <= b.east { "AND" } else { "OR" };
+            sql.push_str(&format!(" AND lat BETWEEN ? AND ? AND (lon >= ? {join} lon <= ?)"));
+            args.extend([b.south, b.north, b.west, b.east].map(rusqlite::types::Value::from));
+        }
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+        sql.push_str(&format!(" ORDER BY time DESC LIMIT {limit}"));
+
+        let connection = self.connection();
+        let mut statement = connection.prepare(&sql).map_err(sql_error)?;
+        let rows = statement
+            .query_map(params_from_iter(args), |row| row.get::<_, String>(0))
+            .map_err(sql_error)?;
+        let mut quakes = Vec::new();
+        for row in rows {
+            if let Ok(quake) = serde_json::from_str(&row.map_err(sql_error)?) {
+                quakes.push(quake);
+            }
+        }
+        Ok(quakes)
+    }
+
+    /// Delete quakes from before `time`, returning how many.
+    pub fn prune_quakes(&self, time: i64) -> Result<usize, String> {
+        self.connection()
+            .execute("DELETE FROM quakes WHERE time < ?1", params![time])
+            .map_err(sql_error)
+    }
+
+    pub fn count_quakes(&self) -> Result<usize, String> {
+        self.connection()
+            .query_row("SELECT COUNT(*) FROM quakes", [], |row| row.get(0))
+            .map_err(sql_error)
+    }
+
+    /// Insert or replace `hazards`, returning each one's previously stored
+    /// level (`None` for those that were new).
+    pub fn upsert_hazards(&self, hazards: &[HazardRow]) -> Result<Vec<Option<HazardLevel>>, String> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction().map_err(sql_error)?;
+        let mut previous = Vec::with_capacity(hazards.len());
+        {
+            let mut level = transaction.prepare_cached("SELECT level FROM hazards WHERE id = ?1").map_err(sql_error)?;
+            let mut insert = transaction
+                .prepare_cached(
+                    "INSERT OR REPLACE INTO hazards (id, kind, level, time, lat, lon, data)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                )
+                .map_err(sql_error)?;
+            let mut timeline = transaction.prepare_cached(INSERT_OBSERVATION).map_err(sql_error)?;
+            for hazard in hazards {
+                let stored: Option<i64> =
+                    level.query_row(params![hazard.id], |row| row.get(0)).optional().map_err(sql_error)?;
+                previous.push(stored.and_then(HazardLevel::from_rank));
+                insert
+                    .execute(params![
+                        hazard.id,
+                        hazard.kind.as_str(),
+                        hazard.level.rank(),
+                        hazard.time,
+                        hazard.lat,
+                        hazard.lon,
+                        hazard.data.to_string()
+                    ])
+                    .map_err(sql_error)?;
+                insert_observation(
+                    &mut timeline,
+                    &Observation {
+                        kind: EventKind::Hazard,
+                        source: hazard.source.clone(),
+                        id: hazard.id.clone(),
+                        time: hazard.time,
+                        lat: hazard.lat,
+                        lon: hazard.lon,
+                        title: hazard.title.clone(),
+                        data: hazard.data.clone(),
+                    },
+                )?;
+            }
+        }
+        transaction.commit().map_err(sql_error)?;
+        Ok(previous)
+    }
+
+    /// Matching hazards' JSON, newest first. Hazards without a position are
+    /// left out when filtering by area.
+    pub fn hazards(&self, query: &HazardQuery) -> Result<Vec<Value>, String> {
+        let mut sql = "SELECT data FROM hazards WHERE time >= ? AND time <= ? AND level >= ?".to_string();
+        let mut args: Vec<rusqlite::types::Value> = vec![
+            query.since.unwrap_or(i64::MIN).into(),
+            query.until.unwrap_or(i64::MAX).into(),
+            query.min_level.map_or(0, HazardLevel::rank).into(),
+        ];
+        if !query.kinds.is_empty() {
+            sql.push_str(&format!(" AND kind IN ({})", vec!["?"; query.kinds.len()].join(", ")));
+            args.extend(query.kinds.iter().map(|kind| kind.as_str().to_string().into()));
+        }
+        if let Some(b) = &query.bounding_box {
+            let join = if b.west <= b.east { "AND" } else { "OR" };
+            sql.push_str(&format!(" AND lat BETWEEN ? AND ? AND (lon >= ? {join} lon <= ?)"));
+            args.extend([b.south, b.north, b.west, b.east].map(rusqlite::types::Value::from));
+        }
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+        sql.push_str(&format!(" ORDER BY time DESC LIMIT {limit}"));
+
+        let connection = self.connection();
+        let mut statement = connection.prepare(&sql).map_err(sql_error)?;
+        let rows = statement
+            .query_map(params_from_iter(args), |row| row.get::<_, String>(0))
+            .map_err(sql_error)?;
+        let mut hazards = Vec::new();
+        for row in rows {
+            if let Ok(hazard) = serde_json::from_str(&row.map_err(sql_error)?) {
+                hazards.push(hazard);
+            }
+        }
+        Ok(hazards)
+    }
+
+    /// Delete hazards last reported before `time`, returning how many.
+    pub fn prune_hazards(&self, time: i64) -> Result<usize, String> {
+        self.connection()
+            .execute("DELETE FROM hazards WHERE time < ?1", params![time])
+            .map_err(sql_error)
+    }
+
+    pub fn count_hazards(&self) -> Result<usize, String> {
+        self.connection()
+            .query_row("SELECT COUNT(*) FROM hazards", [], |row| row.get(0))
+            .map_err(sql_error)
+    }
+
+    /// Store `(period, value)` observations of an EIA dataset, returning how
+    /// many were new or revised.
+    pub fn store_eia_observations(&self, dataset: &str, observations: &[(String, f64)]) -> Result<usize, String> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction().map_err(sql_error)?;
+        let mut changed = 0;
+        {
+            let mut existing = transaction
+                .prepare_cached("SELECT value FROM eia_observations WHERE dataset = ?1 AND period = ?2")
+                .map_err(sql_error)?;
+            let mut insert = transaction
+                .prepare_cached("INSERT OR REPLACE INTO eia_observations (dataset, period, value) VALUES (?1, ?2, ?3)")
+                .map_err(sql_error)?;
+            for (period, value) in observations {
+                let stored: Option<f64> =
+                    existing.query_row(params![dataset, period], |row| row.get(0)).optional().map_err(sql_error)?;
+                if stored != Some(*value) {
+                    changed += 1;
+                    insert.execute(params![dataset, period, value]).map_err(sql_error)?;
+                }
+            }
+        }
+        transaction.commit().map_err(sql_error)?;
+        Ok(changed)
+    }
+
+    /// The latest `limit` observations of an EIA dataset, oldest first.
+    /// Periods of one frequency sort as text.
+    pub fn eia_observations(&self, dataset: &str, limit: usize) -> Result<Vec<(String, f64)>, String> {
+        let connection = self.connection();
+        let mut statement = connection
+            .prepare_cached("SELECT period, value FROM eia_observations WHERE dataset = ?1 ORDER BY period DESC LIMIT ?2")
+            .map_err(sql_error)?;
+        let rows = statement
+            .query_map(params![dataset, limit.min(MAX_LIMIT) as i64], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(sql_error)?;
+        let mut observations = rows.collect::<Result<Vec<(String, f64)>, _>>().map_err(sql_error)?;
+        observations.reverse();
+        Ok(observations)
+    }
+
+    pub fn fred_meta(&self, id: &str) -> Result<Option<FredMeta>, String> {
+        self.connection()
+            .query_row(
+                "SELECT id, title, units, frequency, last_updated FROM fred_series WHERE id = ?1",
+                params![id],
+                |row| {
+                    Ok(FredMeta {
+                        id: row.get(0)?,
+                        title: row.get(1)?,
+                        units: row.get(2)?,
+                        frequency: row.get(3)?,
+                        last_updated: row.get(4)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(sql_error)
+    }
+
+    /// Store a series' metadata and `(date, value)` observations, returning
+    /// whether any observation was new or revised.
+    pub fn store_fred_series(&self, meta: &FredMeta, observations: &[(String, f64)]) -> Result<bool, String> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction().map_err(sql_error)?;
+        let mut changed = false;
+        {
+            let mut existing = transaction
+                .prepare_cached("SELECT value FROM fred_observations WHERE series = ?1 AND date = ?2")
+                .map_err(sql_error)?;
+            let mut insert = transaction
+                .prepare_cached("INSERT OR REPLACE INTO fred_observations (series, date, value) VALUES (?1, ?2, ?3)")
+                .map_err(sql_error)?;
+            for (date, value) in observations {
+                let stored: Option<f64> =
+                    existing.query_row(params![meta.id, date], |row| row.get(0)).optional().map_err(sql_error)?;
+                if stored != Some(*value) {
+                    changed = true;
+                    insert.execute(params![meta.id, date, value]).map_err(sql_error)?;
+                }
+            }
+        }
+        transaction
+            .execute(
+                "INSERT OR REPLACE INTO fred_series (id, title, units, frequency, last_updated) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![meta.id, meta.title, meta.units, meta.frequency, meta.last_updated],
+            )
+            .map_err(sql_error)?;
+        transaction.commit().map_err(sql_error)?;
+        Ok(changed)
+    }
+
+    /// The latest `limit` observations of a series, oldest first.
+    pub fn fred_observations(&self, id: &str, limit: usize) -> Result<Vec<(String, f64)>, String> {
+        let connection = self.connection();
+        let mut statement = connection
+            .prepare_cached("SELECT date, value FROM fred_observations WHERE series = ?1 ORDER BY date DESC LIMIT ?2")
+            .map_err(sql_error)?;
+        let rows = statement
+            .query_map(params![id, limit.min(MAX_LIMIT) as i64], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(sql_error)?;
+        let mut observations = rows.collect::<Result<Vec<(String, f64)>, _>>().map_err(sql_error)?;
+        observations.reverse();
+        Ok(observations)
+    }
+
+    /// Every watchlist entry, oldest first.
+    pub fn watchlist(&self) -> Result<Vec<WatchlistRow>, String> {
+        let connection = self.connection();
+        let mut statement = connection
+            .prepare("SELECT kind, value, label, region, added FROM watchlist ORDER BY added, kind, value")
+            .map_err(sql_error)?;
+        let rows = statement
+            .query_map([], |row| {
+                Ok(WatchlistRow {
+                    kind: row.get(0)?,
+                    value: row.get(1)?,
+                    label: row.get(2)?,
+                    region: row.get::<_, Option<String>>(3)?.and_then(|r| serde_json::from_str(&r).ok()),
+                    added: row.get(4)?,
+                })
+            })
+            .map_err(sql_error)?;
+        rows.collect::<Result<_, _>>().map_err(sql_error)
+    }
+
+    /// Add watchlist entries, updating the label and region of those already
+    /// listed, and return the indices of the new ones.
+    pub fn add_to_watchlist(&self, rows: &[WatchlistRow]) -> Result<Vec<usize>, String> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction().map_err(sql_error)?;
+        let mut added = Vec::new();
+        {
+            let mut exists = transaction
+                .prepare_cached("SELECT 1 FROM watchlist WHERE kind = ?1 AND value = ?2")
+                .map_err(sql_error)?;
+            let mut insert = transaction
+                .prepare_cached(
+                    "INSERT INTO watchlist (kind, value, label, region, added) VALUES (?1, ?2, ?3, ?4, ?5)
+                     ON CONFLICT (kind, value) DO UPDATE SET label = excluded.label, region = excluded.region",
+                )
+                .map_err(sql_error)?;
+            for (index, row) in rows.iter().enumerate() {
+                let known = exists.exists(params![row.kind, row.value]).map_err(sql_error)?;
+                let region = row.region.map(|r| serde_json::to_string(&r).unwrap_or_default());
+                insert
+                    .execute(params![row.kind, row.value, row.label, region, row.added])
+                    .map_err(sql_error)?;
+                if !known {
+                    added.push(index);
+                }
+            }
+        }
+        transaction.commit().map_err(sql_error)?;
+        Ok(added)
+    }
+
+    /// Remove `(kind, value)` entries, returning how many were listed.
+    pub fn remove_from_watchlist(&self, keys: &[(String, String)]) -> Result<usize, String> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction().map_err(sql_error)?;
+        let mut removed = 0;
+        {
+            let mut delete = transaction
+                .prepare_cached("DELETE FROM watchlist WHERE kind = ?1 AND value = ?2")
+                .map_err(sql_error)?;
+            for (kind, value) in keys {
+                removed += delete.execute(params![kind, value]).map_err(sql_error)?;
+            }
+        }
+        transaction.commit().map_err(sql_error)?;
+        Ok(removed)
+    }
+
+    /// Make `notices` the active set for `source`: store them, delete the
+    /// source's notices that are no longer listed, and return the indices of
+    /// the new ones.
+    pub fn replace_notices(&self, source: &str, notices: &[NoticeRow]) -> Result<Vec<usize>, String> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction().map_err(sql_error)?;
+        let mut added = Vec::new();
+        {
+            let listed: Vec<String> = transaction
+                .prepare_cached("SELECT id FROM airspace_notices WHERE source = ?1")
+                .map_err(sql_error)?
+                .query_map(params![source], |row| row.get(0))
+                .map_err(sql_error)?
+                .collect::<Result<_, _>>()
+                .map_err(sql_error)?;
+            let mut insert = transaction
+                .prepare_cached(
+                    "INSERT OR REPLACE INTO airspace_notices (id, source, south, west, north, east, ends, data)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                )
+                .map_err(sql_error)?;
+            for (i, notice) in notices.iter().enumerate() {
+                if !listed.contains(&notice.id) {
+                    added.push(i);
+                }
+                let b = notice.bounds;
+                insert
+                    .execute(params![notice.id, source, b.south, b.west, b.north, b.east, notice.ends, notice.data.to_string()])
+                    .map_err(sql_error)?;
+            }
+            let mut delete = transaction.prepare_cached("DELETE FROM airspace_notices WHERE id = ?1").map_err(sql_error)?;
+            for id in listed.iter().filter(|id| !notices.iter().any(|n| &n.id == *id)) {
+                delete.execute(params![id]).map_err(sql_error)?;
+            }
+        }
+        transaction.commit().map_err(sql_error)?;
+        Ok(added)
+    }
+
+    /// Matching notices' GeoJSON features that have not ended by `now`.
+    pub fn notices(&self, query: &NoticeQuery, now: i64) -> Result<Vec<Value>, String> {
+        let mut sql = "SELECT data FROM airspace_notices WHERE (ends IS NULL OR ends >= ?)".to_string();
+        let mut args: Vec<rusqlite::types::Value> = vec![now.into()];
+        if let Some(source) = &query.source {
+            sql.push_str(" AND source = ?");
+            args.push(source.clone().into());
+        }
+        if let Some(b) = &query.bounding_box {
+            sql.push_str(" AND south <= ? AND north >= ?");
+            args.extend([b.north, b.south].map(rusqlite::types::Value::from));
+            // Stored boxes never cross the antimeridian; a query box may.
+            let join = if b.west <= b.east { "AND" } else { "OR" };
+            sql.push_str(&format!(" AND (west <= ? {join} east >= ?)"));
+            args.extend([b.east, b.west].map(rusqlite::types::Value::from));
+        }
+        sql.push_str(" ORDER BY source, id");
+
+        let connection = self.connection();
+        let mut statement = connection.prepare(&sql).map_err(sql_error)?;
+        let rows = statement
+            .query_map(params_from_iter(args), |row| row.get::<_, String>(0))
+            .map_err(sql_error)?;
+        let mut notices = Vec::new();
+        for row in rows {
+            if let Ok(notice) = serde_json::from_str(&row.map_err(sql_error)?) {
+                notices.push(notice);
+            }
+        }
+        Ok(notices)
+    }
+
+    /// Store `(time, value)` readings of a space weather series, replacing
+    /// revised ones.
+    pub fn store_space_weather(&self, series: &str, readings: &[(i64, f64)]) -> Result<(), String> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction().map_err(sql_error)?;
+        {
+            let mut insert = transaction
+                .prepare_cached("INSERT OR REPLACE INTO space_weather (series, time, value) VALUES (?1, ?2, ?3)")
+                .map_err(sql_error)?;
+            for (time, value) in readings {
+                insert.execute(params![series, time, value]).map_err(sql_error)?;
+            }
+        }
+        transaction.commit().map_err(sql_error)
+    }
+
+    /// A series' readings from `since` on, oldest first.
+    pub fn space_weather(&self, series: &str, since: i64) -> Result<Vec<(i64, f64)>, String> {
+        let connection = self.connection();
+        let mut statement = connection
+            .prepare_cached(
+                "SELECT time, value FROM space_weather WHERE series = ?1 AND time >= ?2 ORDER BY time LIMIT ?3",
+            )
+            .map_err(sql_error)?;
+        let rows = statement
+            .query_map(params![series, since, MAX_LIMIT as i64], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(sql_error)?;
+        rows.collect::<Result<_, _>>().map_err(sql_error)
+    }
+
+    /// Insert or replace flares and messages (SWPC revises a flare's class
+    /// as it grows), returning what each replaced; `None` for new ones.
+    pub fn upsert_space_weather_events(&self, events: &[SpaceWeatherEventRow]) -> Result<Vec<Option<Value>>, String> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction().map_err(sql_error)?;
+        let mut previous = Vec::new();
+        {
+            let mut existing = transaction
+                .prepare_cached("SELECT data FROM space_weather_events WHERE id = ?1")
+                .map_err(sql_error)?;
+            let mut insert = transaction
+                .prepare_cached("INSERT OR REPLACE INTO space_weather_events (id, time, data) VALUES (?1, ?2, ?3)")
+                .map_err(sql_error)?;
+            for event in events {
+                let stored: Option<String> =
+                    existing.query_row(params![event.id], |row| row.get(0)).optional().map_err(sql_error)?;
+                previous.push(stored.and_then(|data| serde_json::from_str(&data).ok()));
+                insert.execute(params![event.id, event.time, event.data.to_string()]).map_err(sql_error)?;
+            }
+        }
+        transaction.commit().map_err(sql_error)?;
+        Ok(previous)
+    }
+
+    /// Flares and messages from `since` on, newest first.
+    pub fn space_weather_events(&self, since: i64, limit: usize) -> Result<Vec<Value>, String> {
+        let connection = self.connection();
+        let mut statement = connection
+            .prepare_cached("SELECT data FROM space_weather_events WHERE time >= ?1 ORDER BY time DESC LIMIT ?2")
+            .map_err(sql_error)?;
+        let rows = statement
+            .query_map(params![since, limit.min(MAX_LIMIT) as i64], |row| row.get::<_, String>(0))
+            .map_err(sql_error)?;
+        let mut events = Vec::new();
+        for row in rows {
+            if let Ok(event) = serde_json::from_str(&row.map_err(sql_error)?) {
+                events.push(event);
+            }
+        }
+        Ok(events)
+    }
+
+    /// Delete space weather readings and events from before `time`,
+    /// returning how many.
+    pub fn prune_space_weather(&self, time: i64) -> Result<usize, String> {
+        let connection = self.connection();
+        let readings = connection
+            .execute("DELETE FROM space_weather WHERE time < ?1", params![time])
+            .map_err(sql_error)?;
+        let events = connection
+            .execute("DELETE FROM space_weather_events WHERE time < ?1", params![time])
+            .map_err(sql_error)?;
+        Ok(readings + events)
+    }
+
+    /// Store a symbol's latest quote and its `(time, price)` ticks,
+    /// returning how many ticks were new.
+    pub fn store_market_quote(&self, symbol: &str, quote: &Value, ticks: &[(i64, f64)]) -> Result<usize, String> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction().map_err(sql_error)?;
+        let mut added = 0;
+        {
+            let mut insert = transaction
+                .prepare_cached("INSERT OR IGNORE INTO market_ticks (symbol, time, price) VALUES (?1, ?2, ?3)")
+                .map_err(sql_error)?;
+            for (time, price) in ticks {
+                added += insert.execute(params![symbol, time, price]).map_err(sql_error)?;
+            }
+        }
+        transaction
+            .execute(
+                "INSERT OR REPLACE INTO market_quotes (symbol, data) VALUES (?1, ?2)",
+                params![symbol, quote.to_string()],
+            )
+            .map_err(sql_error)?;
+        transaction.commit().map_err(sql_error)?;
+        Ok(added)
+    }
+
+    pub fn market_quote(&self, symbol: &str) -> Result<Option<Value>, String> {
+        let data: Option<String> = self
+            .connection()
+            .query_row("SELECT data FROM market_quotes WHERE symbol = ?1", params![symbol], |row| row.get(0))
+            .optional()
+            .map_err(sql_error)?;
+        Ok(data.and_then(|data| serde_json::from_str(&data).ok()))
+    }
+
+    /// A symbol's latest ticks from `since` on, oldest first.
+    pub fn market_ticks(&self, symbol: &str, since: i64) -> Result<Vec<(i64, f64)>, String> {
+        let connection = self.connection();
+        let mut statement = connection
+            .prepare_cached(
+                "SELECT time, price FROM market_ticks WHERE symbol = ?1 AND time >= ?2 ORDER BY time DESC LIMIT ?3",
+            )
+            .map_err(sql_error)?;
+        let rows = statement
+            .query_map(params![symbol, since, MAX_LIMIT as i64], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(sql_error)?;
+        let mut ticks = rows.collect::<Result<Vec<(i64, f64)>, _>>().map_err(sql_error)?;
+        ticks.reverse();
+        Ok(ticks)
+    }
+
+    /// Delete ticks older than `time`, returning how many.
+    pub fn prune_market_ticks(&self, time: i64) -> Result<usize, String> {
+        self.connection()
+            .execute("DELETE FROM market_ticks WHERE time < ?1", params![time])
+            .map_err(sql_error)
+    }
+
+    /// The cached translations into `lang` of those `texts` that have one.
+    pub fn translations(&self, lang: &str, texts: &[String]) -> Result<HashMap<String, String>, String> {
+        let connection = self.connection();
+        let mut statement = connection
+            .prepare_cached("SELECT translated FROM translations WHERE lang = ?1 AND text = ?2")
+            .map_err(sql_error)?;
+        let mut found = HashMap::new();
+        for text in texts {
+            let translated: Option<String> =
+                statement.query_row(params![lang, text], |row| row.get(0)).optional().map_err(sql_error)?;
+            if let Some(translated) = translated {
+                found.insert(text.clone(), translated);
+            }
+        }
+        Ok(found)
+    }
+
+    /// Cache translations into `lang`, as `(text, translated)` pairs.
+    pub fn store_translations(&self, lang: &str, pairs: &[(String, String)], time: i64) -> Result<(), String> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction().map_err(sql_error)?;
+        {
+            let mut insert = transaction
+                .prepare_cached("INSERT OR REPLACE INTO translations (lang, text, translated, time) VALUES (?1, ?2, ?3, ?4)")
+                .map_err(sql_error)?;
+            for (text, translated) in pairs {
+                insert.execute(params![lang, text, translated, time]).map_err(sql_error)?;
+            }
+        }
+        transaction.commit().map_err(sql_error)
+    }
+
+    /// Delete translations cached before `time`, returning how many.
+    pub fn prune_translations(&self, time: i64) -> Result<usize, String> {
+        self.connection()
+            .execute("DELETE FROM translations WHERE time < ?1", params![time])
+            .map_err(sql_error)
+    }
+
+    /// Insert `rows`, or `merge` each into the one stored for the same
+    /// indicator, returning the indices of those that were new.
+    pub fn upsert_indicators(
+        &self,
+        rows: &[IndicatorRow],
+        merge: impl Fn(&IndicatorRow, &Value) -> IndicatorRow,
+    ) -> Result<Vec<usize>, String> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction().map_err(sql_error)?;
+        let mut added = Vec::new();
+        {
+            let mut existing = transaction
+                .prepare_cached("SELECT data FROM threat_indicators WHERE indicator_type = ?1 AND indicator = ?2")
+                .map_err(sql_error)?;
+            let mut insert = transaction
+                .prepare_cached(
+                    "INSERT OR REPLACE INTO threat_indicators (indicator_type, indicator, severity, last_seen, lat, lon, data)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                )
+                .map_err(sql_error)?;
+            for (i, row) in rows.iter().enumerate() {
+                let stored: Option<String> = existing
+                    .query_row(params![row.indicator_type, row.indicator], |r| r.get(0))
+                    .optional()
+                    .map_err(sql_error)?;
+                let merged = match stored.and_then(|data| serde_json::from_str::<Value>(&data).ok()) {
+                    Some(stored) => merge(row, &stored),
+                    None => {
+                        added.push(i);
+                        row.clone()
+                    }
+                };
+                insert
+                    .execute(params![
+                        merged.indicator_type,
+                        merged.indicator,
+                        merged.severity,
+                        merged.last_seen,
+                        merged.lat,
+                        merged.lon,
+                        merged.data.to_string()
+                    ])
+                    .map_err(sql_error)?;
+            }
+        }
+        transaction.commit().map_err(sql_error)?;
+        Ok(added)
+    }
+
+    /// Matching indicators' JSON, most recently seen first.
+    pub fn indicators(&self, query: &IndicatorQuery) -> Result<Vec<Value>, String> {
+        let mut sql = "SELECT data FROM threat_indicators WHERE last_seen >= ? AND severity >= ?".to_string();
+        let mut args: Vec<rusqlite::types::Value> =
+            vec![query.since.unwrap_or(i64::MIN).into(), i64::from(query.min_severity.unwrap_or(0)).into()];
+        if !query.indicator_types.is_empty() {
+            let placeholders = vec!["?"; query.indicator_types.len()].join(", ");
+            sql.push_str(&format!(" AND indicator_type IN ({placeholders})"));
+            args.extend(query.indicator_types.iter().map(|t| t.clone().into()));
+        }
+        if let Some(search) = query.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            sql.push_str(" AND instr(lower(indicator), lower(?)) > 0");
+            args.push(search.to_string().into());
+        }
+        if let Some(b) = &query.bounding_box {
+            let join = if b.west <= b.east { "AND" } else { "OR" };
+            sql.push_str(&format!(" AND lat BETWEEN ? AND ? AND (lon >= ? {join} lon <= ?)"));
+            args.extend([b.south, b.north, b.west, b.east].map(rusqlite::types::Value::from));
+        }
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+        sql.push_str(&format!(" ORDER BY last_seen DESC LIMIT {limit}"));
+        self.select_data(&sql, args)
+    }
+
+    /// Delete indicators last seen before `time`, returning how many.
+    pub fn prune_indicators(&self, time: i64) -> Result<usize, String> {
+        self.connection()
+            .execute("DELETE FROM threat_indicators WHERE last_seen < ?1", params![time])
+            .map_err(sql_error)
+    }
+
+    pub fn count_indicators(&self) -> Result<usize, String> {
+        self.connection()
+            .query_row("SELECT COUNT(*) FROM threat_indicators", [], |row| row.get(0))
+            .map_err(sql_error)
+    }
+
+    /// Insert or replace vulnerabilities (CISA revises entries), returning
+    /// the indices of those that were new.
+    pub fn upsert_vulnerabilities(&self, rows: &[VulnerabilityRow]) -> Result<Vec<usize>, String> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction().map_err(sql_error)?;
+        let mut added = Vec::new();
+        {
+            let mut exists = transaction
+                .prepare_cached("SELECT 1 FROM exploited_vulnerabilities WHERE cve = ?1")
+                .map_err(sql_error)?;
+            let mut insert = transaction
+                .prepare_cached(
+                    "INSERT OR REPLACE INTO exploited_vulnerabilities (cve, added, ransomware, data) VALUES (?1, ?2, ?3, ?4)",
+                )
+                .map_err(sql_error)?;
+            for (i, row) in rows.iter().enumerate() {
+                if !exists.exists(params![row.cve]).map_err(sql_error)? {
+                    added.push(i);
+                }
+                insert.execute(params![row.cve, row.added, row.ransomware, row.data.to_string()]).map_err(sql_error)?;
+            }
+        }
+        transaction.commit().map_err(sql_error)?;
+        Ok(added)
+    }
+
+    /// Matching vulnerabilities' JSON, most recently added first.
+    pub fn vulnerabilities(&self, query: &VulnerabilityQuery) -> Result<Vec<Value>, String> {
+        let mut sql = "SELECT data FROM exploited_vulnerabilities WHERE added >= ?".to_string();
+        let mut args: Vec<rusqlite::types::Value> = vec![query.since.clone().unwrap_or_default().into()];
+        if let Some(search) = query.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            sql.push_str(" AND instr(lower(data), lower(?)) > 0");
+            args.push(search.to_string().into());
+        }
+        if query.ransomware {
+            sql.push_str(" AND ransomware");
+        }
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+        sql.push_str(&format!(" ORDER BY added DESC, cve DESC LIMIT {limit}"));
+        self.select_data(&sql, args)
+    }
+
+    pub fn count_vulnerabilities(&self) -> Result<usize, String> {
+        self.connection()
+            .query_row("SELECT COUNT(*) FROM exploited_vulnerabilities", [], |row| row.get(0))
+            .map_err(sql_error)
+    }
+
+    /// The `data` column of each row `sql` selects, parsed.
+    fn select_data(&self, sql: &str, args: Vec<rusqlite::types::Value>) -> Result<Vec<Value>, String> {
+        let connection = self.connection();
+        let mut statement = connection.prepare(sql).map_err(sql_error)?;
+        let rows = statement
+            .query_map(params_from_iter(args), |row| row.get::<_, String>(0))
+            .map_err(sql_error)?;
+        let mut items = Vec::new();
+        for row in rows {
+            if let Ok(item) = serde_json::from_str(&row.map_err(sql_error)?) {
+                items.push(item);
+            }
+        }
+        Ok(items)
+    }
+
+    /// Insert or replace timeline rows.
+    pub fn record(&self, observations: &[Observation]) -> Result<(), String> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction().map_err(sql_error)?;
+        {
+            let mut insert = transaction.prepare_cached(INSERT_OBSERVATION).map_err(sql_error)?;
+            for observation in observations {
+                insert_observation(&mut insert, observation)?;
+            }
+        }
+        transaction.commit().map_err(sql_error)
+    }
+
+    /// Record the aircraft or vessel positions whose subject has not been
+    /// recorded in the last `TRACK_INTERVAL`, or has turned since it last
+    /// was, returning how many.
+    pub fn record_tracks(&self, observations: Vec<Observation>) -> Result<usize, String> {
+        let interval = TRACK_INTERVAL.as_millis() as i64;
+        let due: Vec<Observation> = {
+            let mut tracked = self.tracked.lock().unwrap_or_else(|e| e.into_inner());
+            let due = observations
+                .into_iter()
+                .filter(|o| {
+                    let key = (o.kind, o.id.clone());
+                    let Some(sample) = Sample::next(tracked.get(&key), o) else {
+                        return false;
+                    };
+                    tracked.insert(key, sample);
+                    true
+                })
+                .collect();
+            let newest = tracked.values().map(|s| s.time).max().unwrap_or(0);
+            tracked.retain(|_, last| newest.saturating_sub(last.time) < 2 * interval);
+            due
+        };
+        self.record(&due)?;
+        Ok(due.len())
+    }
+
+    /// Positions of aircraft or vessel `id` between `from` and `to`
+    /// (inclusive milliseconds), oldest first; the newest `MAX_LIMIT` when
+    /// there are more.
+    pub fn track(&self, kind: Option<EventKind>, id: &str, from: i64, to: i64) -> Result<Vec<TrackPoint>, String> {
+        let kinds = match kind {
+            Some(kind) => vec![kind.as_str()],
+            None => vec![EventKind::Flight.as_str(), EventKind::Vessel.as_str()],
+        };
+        let sql = format!(
+            "SELECT time, lat, lon FROM timeline
+             WHERE id = ? AND time >= ? AND time <= ? AND lat IS NOT NULL AND lon IS NOT NULL AND kind IN ({})
+             ORDER BY time DESC LIMIT {MAX_LIMIT}",
+            vec!["?"; kinds.len()].join(", ")
+        );
+        let mut args: Vec<rusqlite::types::Value> = vec![id.to_string().into(), from.into(), to.into()];
+        args.extend(kinds.into_iter().map(|k| k.to_string().into()));
+        let connection = self.connection();
+        let mut statement = connection.prepare(&sql).map_err(sql_error)?;
+        let rows = statement
+            .query_map(params_from_iter(args), |row| {
+                Ok(TrackPoint {
+                    time: row.get(0)?,
+                    lat: row.get(1)?,
+                    lon: row.get(2)?,
+                })
+            })
+            .map_err(sql_error)?;
+        let mut points = rows.collect::<Result<Vec<_>, _>>().map_err(sql_error)?;
+        points.reverse();
+        Ok(points)
+    }
+
+    /// Matching timeline rows, newest first.
+    pub fn events(&self, query: &TimelineQuery) -> Result<Vec<Observation>, String> {
+        let (filter, args) = timeline_filter(query);
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+        let sql = format!(
+            "SELECT kind, source, id, time, lat, lon, title, data FROM timeline WHERE {filter}
+             ORDER BY time DESC LIMIT {limit}"
+        );
+        let connection = self.connection();
+        let mut statement = connection.prepare(&sql).map_err(sql_error)?;
+        let rows = statement
+            .query_map(params_from_iter(args), |row| {
+                let Some(kind) = EventKind::parse(&row.get::<_, String>(0)?) else {
+                    return Ok(None);
+                };
+                Ok(Some(Observation {
+                    kind,
+                    source: row.get(1)?,
+                    id: row.get(2)?,
+                    time: row.get(3)?,
+                    lat: row.get(4)?,
+                    lon: row.get(5)?,
+                    title: row.get(6)?,
+                    data: serde_json::from_str(&row.get::<_, String>(7)?).unwrap_or(Value::Null),
+                }))
+            })
+            .map_err(sql_error)?;
+        let mut events = Vec::new();
+        for row in rows {
+            events.extend(row.map_err(sql_error)?);
+        }
+        Ok(events)
+    }
+
+    /// Count matching timeline rows per `group_by` key, in key order.
+    pub fn aggregate(&self, query: &TimelineQuery, group_by: GroupBy, precision: usize) -> Result<Vec<Bucket>, String> {
+        let (mut filter, args) = timeline_filter(query);
+        let key = match group_by {
+            GroupBy::Kind => "kind".to_string(),
+            GroupBy::Hour => "strftime('%Y-%m-%dT%H:00Z', time / 1000, 'unixepoch')".to_string(),
+            GroupBy::Day => "strftime('%Y-%m-%d', time / 1000, 'unixepoch')".to_string(),
+            GroupBy::Geohash => {
+                filter.push_str(" AND geohash IS NOT NULL");
+                format!("substr(geohash, 1, {})", precision.clamp(1, GEOHASH_PRECISION))
+            }
+        };
+        let sql = format!("SELECT {key} AS bucket, COUNT(*) FROM timeline WHERE {filter} GROUP BY bucket ORDER BY bucket");
+        let connection = self.connection();
+        let mut statement = connection.prepare(&sql).map_err(sql_error)?;
+        let rows = statement
+            .query_map(params_from_iter(args), |row| {
+                Ok(Bucket {
+                    key: row.get(0)?,
+                    count: row.get(1)?,
+                })
+            })
+            .map_err(sql_error)?;
+        rows.collect::<Result<_, _>>().map_err(sql_error)
+    }
+
+    /// Delete timeline rows older than `time` (of `kinds`, or all kinds when
+    /// empty), returning how many.
+    pub fn prune_timeline(&self, time: i64, kinds: &[EventKind]) -> Result<usize, String> {
+        let mut sql = "DELETE FROM timeline WHERE time < ?".to_string();
+        let mut args: Vec<rusqlite::types::Value> = vec![time.into()];
+        if !kinds.is_empty() {
+            sql.push_str(&format!(" AND kind IN ({})", vec!["?"; kinds.len()].join(", ")));
+            args.extend(kinds.iter().map(|k| k.as_str().to_string().into()));
+        }
+        self.connection().execute(&sql, params_from_iter(args)).map_err(sql_error)
+    }
+}
+
+const INSERT_OBSERVATION: &str = "INSERT OR REPLACE INTO timeline (kind, source, id, time, lat, lon, geohash, title, data)
+     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)";
+
+fn insert_observation(insert: &mut rusqlite::CachedStatement<'_>, o: &Observation) -> Result<(), String> {
+    let geohash = o.lat.zip(o.lon).map(|(lat, lon)| geohash(lat, lon, GEOHASH_PRECISION));
+    insert
+        .execute(params![o.kind.as_str(), o.source, o.id, o.time, o.lat, o.lon, geohash, o.title, o.data.to_string()])
+        .map(|_| ())
+        .map_err(sql_error)
+}
+
+/// The `WHERE` clause for a timeline query and its arguments. A bounding
+/// box becomes geohash prefix ranges, which use the index, plus the exact
+/// coordinate test.
+fn timeline_filter(query: &TimelineQuery) -> (String, Vec<rusqlite::types::Value>) {
+    let mut sql = "time >= ? AND time <= ?".to_string();
+    let mut args: Vec<rusqlite::types::Value> =
+        vec![query.since.unwrap_or(i64::MIN).into(), query.until.unwrap_or(i64::MAX).into()];
+    if !query.kinds.is_empty() {
+        sql.push_str(&format!(" AND kind IN ({})", vec!["?"; query.kinds.len()].join(", ")));
+        args.extend(query.kinds.iter().map(|k| k.as_str().to_string().into()));
+    }
+    if let Some(b) = &query.bounding_box {
+        let prefixes = cover(b);
+        let ranges = vec!["(geohash >= ? AND geohash < ?)"; prefixes.len()].join(" OR ");
+        sql.push_str(&format!(" AND ({ranges})"));
+        for prefix in prefixes {
+            // `{` sorts right after `z`, the last geohash character.
+            let end = format!("{prefix}{{");
+            args.extend([prefix.into(), end.into()]);
+        }
+        let join = if b.west <= b.east { "AND" } else { "OR" };
+        sql.push_str(&format!(" AND lat BETWEEN ? AND ? AND (lon >= ? {join} lon <= ?)"));
+        args.extend([b.south, b.north, b.west, b.east].map(rusqlite::types::Value::from));
+    }
+    (sql, args)
+}
+
+fn geohash(lat: f64, lon: f64, precision: usize) -> String {
+    let (mut lat_range, mut lon_range) = ((-90.0, 90.0), (-180.0, 180.0));
+    let mut hash = String::with_capacity(precision);
+    let (mut bits, mut value, mut even) = (0, 0usize, true);
+    while hash.len() < precision {
+        let (range, coordinate) = if even { (&mut lon_range, lon) } else { (&mut lat_range, lat) };
+        let mid = (range.0 + range.1) / 2.0;
+        value <<= 1;
+        if coordinate >= mid {
+            value |= 1;
+            range.0 = mid;
+        } else {
+            range.1 = mid;
+        }
+        even = !even;
+        bits += 1;
+        if bits == 5 {
+            hash.push(GEOHASH_ALPHABET[value] as char);
+            (bits, value) = (0, 0);
+        }
+    }
+    hash
+}
+
+/// Size in degrees (lat, lon) of a geohash cell.
+fn cell_size(precision: usize) -> (f64, f64) {
+    let bits = 5 * precision as i32;
+    (180.0 / 2f64.powi(bits / 2), 360.0 / 2f64.powi(bits - bits / 2))
+}
+
+/// Geohash prefixes of the cells covering `b`, at the finest precision that
+/// needs at most `MAX_COVER_CELLS`.
+fn cover(b: &BoundingBox) -> Vec<String> {
+    let spans: Vec<(f64, f64)> = if b.west <= b.east {
+        vec![(b.west, b.east)]
+    } else {
+        vec![(b.west, 180.0), (-180.0, b.east)]
+    };
+    let cells = |precision: usize| {
+        let (height, width) = cell_size(precision);
+        let rows = ((b.north / height).floor() - (b.south / height).floor()) as usize + 1;
+        spans
+            .iter()
+            .map(|(west, east)| rows * (((east / width).floor() - (west / width).floor()) as usize + 1))
+            .sum::<usize>()
+    };
+    let precision = (1..=GEOHASH_PRECISION)
+        .take_while(|&p| cells(p) <= MAX_COVER_CELLS)
+        .last()
+        .unwrap_or(1);
+    let (height, width) = cell_size(precision);
+    let mut prefixes = Vec::new();
+    for (west, east) in spans {
+        let mut lat = (b.south / height).floor() * height;
+        while lat <= b.north {
+            let mut lon = (west / width).floor() * width;
+            while lon <= east {
+                let center = ((lat + height / 2.0).clamp(-90.0, 90.0), (lon + width / 2.0).clamp(-180.0, 180.0));
+                let prefix = geohash(center.0, center.1, precision);
+                if !prefixes.contains(&prefix) {
+                    prefixes.push(prefix);
+                }
+                lon += width;
+            }
+            lat += height;
+        }
+    }
+    prefixes
+}
+
+/// Record aircraft or vessel positions off the async runtime.
+pub fn record_tracks(app: &AppHandle, observations: Vec<Observation>) {
+    if observations.is_empty() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let Some(store) = app.try_state::<EventStore>() else {
+            return;
+        };
+        if let Err(err) = store.record_tracks(observations) {
+            tracing::warn!(target: "app", "failed to record tracks: {err}");
+        }
+    });
+}
+
+/// Prune the timeline and cached translations to `RETENTION` now and
+/// every `PRUNE_INTERVAL`.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let handle = app.clone();
+            let cutoff = (crate::unix_timestamp_secs().saturating_sub(RETENTION.as_secs()) * 1000) as i64;
+            let pruned = crate::runtime::blocking(move || {
+                let store = handle.state::<EventStore>();
+                if let Err(err) = store.prune_translations(cutoff) {
+                    tracing::warn!(target: "app", "translation prune failed: {err}");
+                }
+                store.prune_timeline(cutoff, &[])
+            })
+            .await;
+            match pruned {
+                Ok(0) => {}
+                Ok(pruned) => tracing::debug!(target: "app", pruned, "timeline pruned"),
+                Err(err) => tracing::warn!(target: "app", "timeline prune failed: {err}"),
+            }
+            tokio::time::sleep(PRUNE_INTERVAL).await;
+        }
+    });
+}
+
+/// Run `f` against the store on the blocking pool.
+pub async fn with_store<T: Send + 'static>(
+    app: &AppHandle,
+    f: impl FnOnce(&EventStore) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    let app = app.clone();
+    crate::runtime::blocking(move || f(&app.state::<EventStore>())).await
+}
+
+/// Timeline rows across kinds, newest first.
+#[tauri::command]
+pub async fn query_events(webview: Webview, app: AppHandle, query: Option<TimelineQuery>) -> Result<Vec<Observation>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("query_events", async move {
+            crate::require_trusted_window(webview.label())?;
+            let query = query.unwrap_or_default();
+            with_store(&app, move |store| store.events(&query)).await
+        })
+        .await
+}
+
+/// Counts of timeline rows grouped by kind, hour, day or geohash cell
+/// (`precision` 1-8, default 4).
+#[tauri::command]
+pub async fn aggregate_events(
+    webview: Webview,
+    app: AppHandle,
+    query: Option<TimelineQuery>,
+    group_by: GroupBy,
+    precision: Option<usize>,
+) -> Result<Vec<Bucket>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("aggregate_events", async move {
+            crate::require_trusted_window(webview.label())?;
+            let query = query.unwrap_or_default();
+            with_store(&app, move |store| store.aggregate(&query, group_by, precision.unwrap_or(4))).await
+        })
+        .await
+}
+
+/// Delete timeline rows older than `before` (milliseconds), optionally only
+/// of some kinds. Returns how many were deleted.
+#[tauri::command]
+pub async fn prune_events(
+    webview: Webview,
+    app: AppHandle,
+    before: i64,
+    kinds: Option<Vec<EventKind>>,
+) -> Result<usize, String> {
+    crate::metrics::of(&webview)
+        .observe_async("prune_events", async move {
+            crate::require_trusted_window(webview.label())?;
+            let kinds = kinds.unwrap_or_default();
+            with_store(&app, move |store| store.prune_timeline(before, &kinds)).await
+        })
+        .await
+}
+
+/// The recorded trail of aircraft (ICAO24) or vessel (MMSI) `id` between
+/// `from` and `to` (milliseconds, default the whole retention), oldest
+/// first. `kind` narrows it to flights or vessels.
+#[tauri::command]
+pub async fn get_track(
+    webview: Webview,
+    app: AppHandle,
+    id: String,
+    from: Option<i64>,
+    to: Option<i64>,
+    kind: Option<EventKind>,
+) -> Result<Vec<TrackPoint>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("get_track", async move {
+            crate::require_trusted_window(webview.label())?;
+            if kind.is_some_and(|k| !matches!(k, EventKind::Flight | EventKind::Vessel)) {
+                return Err("Tracks are kept for flights and vessels".to_string());
+            }
+            let (from, to) = (from.unwrap_or(i64::MIN), to.unwrap_or(i64::MAX));
+            with_store(&app, move |store| store.track(kind, &id, from, to)).await
+        })
+        .await
+}
+
+/// Add observations the dashboard fetched itself, such as earthquakes.
+#[tauri::command]
+pub async fn record_events(webview: Webview, app: AppHandle, events: Vec<Observation>) -> Result<(), String> {
+    crate::metrics::of(&webview)
+        .observe_async("record_events", async move {
+            crate::require_trusted_window(webview.label())?;
+            if events.len() > MAX_RECORDED {
+                return Err(format!("At most {MAX_RECORDED} events per call"));
+            }
+            let valid = |o: &Observation| match (o.lat, o.lon) {
+                (Some(lat), Some(lon)) => (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon),
+                (None, None) => true,
+                _ => false,
+            };
+            if !events.iter().all(valid) {
+                return Err("Events need both coordinates, in range, or neither".to_string());
+            }
+            with_store(&app, move |store| store.record(&events)).await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod store_tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event(id: &str, event_date: &str, event_type: &str, lat: f64, lon: f64) -> StoredEvent {
+        StoredEvent {
+            id: id.to_string(),
+            event_date: event_date.to_string(),
+            event_type: event_type.to_string(),
+            lat,
+            lon,
+            data: json!({ "id": id }),
+        }
+    }
+
+    fn ids(events: Vec<Value>) -> Vec<String> {
+        events.into_iter().map(|e| e["id"].as_str().unwrap().to_string()).collect()
+    }
+
+    #[test]
+    fn upserts_and_filters() {
+        let store = EventStore::in_memory();
+        let events = [
+            event("a", "2026-01-01", "Battles", 10.0, 10.0),
+            event("b", "2026-01-03", "Protests", 10.0, 179.0),
+            event("c", "2026-01-02", "Battles", -10.0, -179.0),
+        ];
+        assert_eq!(store.upsert("acled", &events).unwrap(), ["a", "b", "c"]);
+        assert!(store.upsert("acled", &events[..1]).unwrap().is_empty());
+        assert_eq!(store.count("acled").unwrap(), 3);
+        assert_eq!(store.count("gdelt").unwrap(), 0);
+
+        let all = store.query("acled", &EventQuery::default()).unwrap();
+        assert_eq!(ids(all), ["b", "c", "a"]);
+        let query = EventQuery {
+            start_date: Some("2026-01-02".to_string()),
+            event_types: vec!["Battles".to_string()],
+            ..EventQuery::default()
+        };
+        assert_eq!(ids(store.query("acled", &query).unwrap()), ["c"]);
+        let across_antimeridian = EventQuery {
+            bounding_box: Some(BoundingBox { south: -20.0, west: 170.0, north: 20.0, east: -170.0 }),
+            ..EventQuery::default()
+        };
+        assert_eq!(ids(store.query("acled", &across_antimeridian).unwrap()), ["b", "c"]);
+
+        assert_eq!(store.prune("acled", "2026-01-02").unwrap(), 1);
+        assert_eq!(store.count("acled").unwrap(), 2);
+    }
+
+    #[test]
+    fn keeps_each_headline_link_once() {
+        let store = EventStore::in_memory();
+        let row = |link: &str, source: &str, published| NewsRow {
+            link: link.to_string(),
+            source: source.to_string(),
+            published,
+            data: json!({ "id": link }),
+        };
+        let rows = [row("a", "BBC", 3), row("b", "BBC", 1), row("a", "AP", 2), row("c", "AP", 2)];
+        assert_eq!(store.insert_news(&rows).unwrap(), [0, 1, 3]);
+        assert!(store.insert_news(&rows[..2]).unwrap().is_empty());
+
+        assert_eq!(ids(store.news(&NewsQuery::default()).unwrap()), ["a", "c", "b"]);
+        let query = NewsQuery {
+            sources: vec!["AP".to_string()],
+            ..NewsQuery::default()
+        };
+        assert_eq!(ids(store.news(&query).unwrap()), ["c"]);
+        let query = NewsQuery {
+            since: Some(2),
+            limit: Some(1),
+            ..NewsQuery::default()
+        };
+        assert_eq!(ids(store.news(&query).unwrap()), ["a"]);
+        assert_eq!(store.prune_news(2).unwrap(), 1);
+    }
+
+    #[test]
+    fn stores_quakes_by_magnitude() {
+        let store = EventStore::in_memory();
+        let quake = |id: &str, time, magnitude, lon| QuakeRow {
+            id: id.to_string(),
+            time,
+            magnitude,
+            lat: 0.0,
+            lon,
+            data: json!({ "id": id, "place": "somewhere", "magnitude": magnitude }),
+        };
+        let quakes = [quake("a", 1, 4.6, 10.0), quake("b", 3, 6.1, 179.5), quake("c", 2, 5.0, -179.5)];
+        assert_eq!(store.upsert_quakes(&quakes).unwrap(), [0, 1, 2]);
+        // A revised magnitude replaces the row without counting as new.
+        assert!(store.upsert_quakes(&[quake("a", 1, 4.8, 10.0)]).unwrap().is_empty());
+        assert_eq!(store.count_quakes().unwrap(), 3);
+
+        assert_eq!(ids(store.quakes(&QuakeQuery::default()).unwrap()), ["b", "c", "a"]);
+        assert_eq!(store.quakes(&QuakeQuery::default()).unwrap()[2]["magnitude"], json!(4.8));
+        let query = QuakeQuery {
+            min_magnitude: Some(5.0),
+            ..QuakeQuery::default()
+        };
+        assert_eq!(ids(store.quakes(&query).unwrap()), ["b", "c"]);
+        let query = QuakeQuery {
+            bounding_box: Some(BoundingBox { south: -1.0, west: 179.0, north: 1.0, east: -179.0 }),
+            until: Some(2),
+            ..QuakeQuery::default()
+        };
+        assert_eq!(ids(store.quakes(&query).unwrap()), ["c"]);
+
+        let timeline = TimelineQuery {
+            kinds: vec![EventKind::Quake],
+            ..TimelineQuery::default()
+        };
+        let recorded = store.events(&timeline).unwrap();
+        assert_eq!(recorded.len(), 3);
+        assert_eq!(recorded[0].title, "M6.1 somewhere");
+        assert_eq!(store.prune_quakes(2).unwrap(), 1);
+    }
+
+    #[test]
+    fn tracks_hazard_levels_across_updates() {
+        let store = EventStore::in_memory();
+        let hazard = |id: &str, kind, level, time, position: Option<(f64, f64)>| HazardRow {
+            id: id.to_string(),
+            kind,
+            level,
+            source: "test".to_string(),
+            title: format!("{id} notice"),
+            time,
+            lat: position.map(|(lat, _)| lat),
+            lon: position.map(|(_, lon)| lon),
+            data: json!({ "id": id, "level": level }),
+        };
+        let hazards = [
+            hazard("volcano:1", HazardKind::Volcano, HazardLevel::Advisory, 1, Some((61.0, -152.0))),
+            hazard("tsunami:a", HazardKind::Tsunami, HazardLevel::Warning, 3, Some((-20.0, -174.0))),
+            hazard("gvp:2", HazardKind::Volcano, HazardLevel::Information, 2, None),
+        ];
+        assert_eq!(store.upsert_hazards(&hazards).unwrap(), [None, None, None]);
+        // A raised alert level replaces the row and hands back the old one.
+        let raised = hazard("volcano:1", HazardKind::Volcano, HazardLevel::Watch, 4, Some((61.0, -152.0)));
+        assert_eq!(store.upsert_hazards(&[raised]).unwrap(), [Some(HazardLevel::Advisory)]);
+        assert_eq!(store.count_hazards().unwrap(), 3);
+
+        assert_eq!(ids(store.hazards(&HazardQuery::default()).unwrap()), ["volcano:1", "tsunami:a", "gvp:2"]);
+        let query = HazardQuery {
+            kinds: vec![HazardKind::Volcano],
+            min_level: Some(HazardLevel::Advisory),
+            ..HazardQuery::default()
+        };
+        assert_eq!(ids(store.hazards(&query).unwrap()), ["volcano:1"]);
+        let query = HazardQuery {
+            bounding_box: Some(BoundingBox { south: -30.0, west: 170.0, north: -10.0, east: -170.0 }),
+            ..HazardQuery::default()
+        };
+        assert_eq!(ids(store.hazards(&query).unwrap()), ["tsunami:a"]);
+
+        let timeline = TimelineQuery {
+            kinds: vec![EventKind::Hazard],
+            ..TimelineQuery::default()
+        };
+        // Both notices for the volcano stay on the timeline.
+        assert_eq!(store.events(&timeline).unwrap().len(), 4);
+        assert_eq!(store.prune_hazards(3).unwrap(), 1);
+    }
+
+    #[test]
+    fn detects_new_and_revised_fred_observations() {
+        let store = EventStore::in_memory();
+        let meta = FredMeta {
+            id: "UNRATE".to_string(),
+            title: "Unemployment Rate".to_string(),
+            units: "Percent".to_string(),
+            frequency: "Monthly".to_string(),
+            last_updated: "2026-03-06 07:44:02-06".to_string(),
+        };
+        let observations = |values: &[(&str, f64)]| values.iter().map(|(d, v)| (d.to_string(), *v)).collect::<Vec<_>>();
+        assert!(store.store_fred_series(&meta, &observations(&[("2026-01-01", 4.3), ("2026-02-01", 4.4)])).unwrap());
+        assert!(!store.store_fred_series(&meta, &observations(&[("2026-02-01", 4.4)])).unwrap());
+        // A revision counts as a change, as does a new month.
+        assert!(store.store_fred_series(&meta, &observations(&[("2026-02-01", 4.5)])).unwrap());
+        assert!(store.store_fred_series(&meta, &observations(&[("2026-03-01", 4.4)])).unwrap());
+
+        assert_eq!(store.fred_meta("UNRATE").unwrap(), Some(meta));
+        assert_eq!(store.fred_meta("DGS10").unwrap(), None);
+        assert_eq!(
+            store.fred_observations("UNRATE", 2).unwrap(),
+            observations(&[("2026-02-01", 4.5), ("2026-03-01", 4.4)])
+        );
+    }
+
+    #[test]
+    fn stores_eia_observations_by_period() {
+        let store = EventStore::in_memory();
+        let rows = |values: &[(&str, f64)]| values.iter().map(|(p, v)| (p.to_string(), *v)).collect::<Vec<_>>();
+        assert_eq!(store.store_eia_observations("crude", &rows(&[("2026-02-27", 430.1), ("2026-03-06", 428.4)])).unwrap(), 2);
+        assert_eq!(store.store_eia_observations("crude", &rows(&[("2026-03-06", 428.4), ("2026-03-13", 425.0)])).unwrap(), 1);
+        assert_eq!(store.store_eia_observations("gas", &rows(&[("2026-03-13", 1800.0)])).unwrap(), 1);
+        assert_eq!(
+            store.eia_observations("crude", 2).unwrap(),
+            rows(&[("2026-03-06", 428.4), ("2026-03-13", 425.0)])
+        );
+    }
+
+    fn observation(kind: EventKind, id: &str, time: i64, position: Option<(f64, f64)>) -> Observation {
+        Observation {
+            kind,
+            source: "test".to_string(),
+            id: id.to_string(),
+            time,
+            lat: position.map(|p| p.0),
+            lon: position.map(|p| p.1),
+            title: id.to_string(),
+            data: json!({ "id": id }),
+        }
+    }
+
+    #[test]
+    fn encodes_geohashes() {
+        assert_eq!(geohash(57.64911, 10.40744, 11), "u4pruydqqvj");
+        assert_eq!(geohash(-25.382708, -49.265506, 8), "6gkzwgjz");
+        let cells = cover(&BoundingBox { south: 57.0, west: 10.0, north: 58.0, east: 11.0 });
+        assert!(cells.len() <= MAX_COVER_CELLS);
+        assert!(cells.iter().any(|c| "u4pruydqqvj".starts_with(c.as_str())));
+    }
+
+    #[test]
+    fn queries_the_timeline_across_kinds() {
+        let store = EventStore::in_memory();
+        store.upsert("acled", &[event("a", "2026-01-02", "Battles", 10.0, 10.0)]).unwrap();
+        store
+            .record(&[
+                observation(EventKind::Quake, "q1", 1_000, Some((10.5, 10.5))),
+                observation(EventKind::Quake, "q2", 2_000, Some((-10.0, 179.5))),
+                observation(EventKind::Headline, "h1", 3_000, None),
+            ])
+            .unwrap();
+
+        let all: Vec<String> = store.events(&TimelineQuery::default()).unwrap().into_iter().map(|o| o.id).collect();
+        assert_eq!(all, ["a", "h1", "q2", "q1"]);
+        let incident = &store.events(&TimelineQuery::default()).unwrap()[0];
+        assert_eq!(incident.kind, EventKind::Incident);
+        assert_eq!(incident.time, 1_767_312_000_000);
+
+        let near = TimelineQuery {
+            bounding_box: Some(BoundingBox { south: 9.0, west: 9.0, north: 11.0, east: 11.0 }),
+            until: Some(10_000),
+            ..TimelineQuery::default()
+        };
+        let ids: Vec<String> = store.events(&near).unwrap().into_iter().map(|o| o.id).collect();
+        assert_eq!(ids, ["q1"]);
+        let across_antimeridian = TimelineQuery {
+            bounding_box: Some(BoundingBox { south: -20.0, west: 170.0, north: 0.0, east: -170.0 }),
+            ..TimelineQuery::default()
+        };
+        assert_eq!(store.events(&across_antimeridian).unwrap()[0].id, "q2");
+
+        let by_kind = store.aggregate(&TimelineQuery::default(), GroupBy::Kind, 4).unwrap();
+        let counts: Vec<(&str, u64)> = by_kind.iter().map(|b| (b.key.as_str(), b.count)).collect();
+        assert_eq!(counts, [("headline", 1), ("incident", 1), ("quake", 2)]);
+        let by_day = store.aggregate(&TimelineQuery::default(), GroupBy::Day, 4).unwrap();
+        assert_eq!(by_day[0], Bucket { key: "1970-01-01".to_string(), count: 3 });
+        let by_cell = store.aggregate(&TimelineQuery::default(), GroupBy::Geohash, 1).unwrap();
+        assert_eq!(by_cell.iter().map(|b| b.count).sum::<u64>(), 3);
+
+        assert_eq!(store.prune_timeline(2_500, &[EventKind::Quake]).unwrap(), 2);
+        assert_eq!(store.prune_timeline(i64::MAX, &[]).unwrap(), 2);
+    }
+
+    #[test]
+    fn samples_tracks() {
+        let store = EventStore::in_memory();
+        let minute = 60_000;
+        let track = |time| vec![observation(EventKind::Vessel, "244", time, Some((52.0, 4.0)))];
+        assert_eq!(store.record_tracks(track(0)).unwrap(), 1);
+        assert_eq!(store.record_tracks(track(minute)).unwrap(), 0);
+        assert_eq!(store.record_tracks(track(5 * minute)).unwrap(), 1);
+        assert_eq!(store.events(&TimelineQuery::default()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn samples_turns_into_trails() {
+        let store = EventStore::in_memory();
+        let second = 1_000;
+        let fix = |time, lat, lon| vec![observation(EventKind::Flight, "3c6444", time, Some((lat, lon)))];
+        // Heading north, then a turn east after a minute.
+        assert_eq!(store.record_tracks(fix(0, 50.0, 8.0)).unwrap(), 1);
+        assert_eq!(store.record_tracks(fix(60 * second, 50.1, 8.0)).unwrap(), 0);
+        assert_eq!(store.record_tracks(fix(6 * 60 * second, 50.5, 8.0)).unwrap(), 1);
+        assert_eq!(store.record_tracks(fix(7 * 60 * second, 50.6, 8.0)).unwrap(), 0);
+        assert_eq!(store.record_tracks(fix(8 * 60 * second, 50.5, 8.2)).unwrap(), 1);
+        store.record(&[observation(EventKind::Vessel, "3c6444", 0, Some((0.0, 0.0)))]).unwrap();
+
+        let trail = store.track(Some(EventKind::Flight), "3c6444", 0, 10 * 60 * second).unwrap();
+        let times: Vec<i64> = trail.iter().map(|p| p.time).collect();
+        assert_eq!(times, [0, 6 * 60 * second, 8 * 60 * second]);
+        assert_eq!(trail[2], TrackPoint { time: 8 * 60 * second, lat: 50.5, lon: 8.2 });
+        assert_eq!(store.track(None, "3c6444", 1, i64::MAX).unwrap().len(), 2);
+        assert_eq!(store.track(None, "3c6444", i64::MIN, i64::MAX).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn keeps_watchlist_entries_once() {
+        let store = EventStore::in_memory();
+        let row = |kind: &str, value: &str, label: &str, added| WatchlistRow {
+            kind: kind.to_string(),
+            value: value.to_string(),
+            label: label.to_string(),
+            region: None,
+            added,
+        };
+        let region = WatchlistRow {
+            region: Some(BoundingBox { south: 25.0, west: 55.0, north: 27.5, east: 57.5 }),
+            ..row("region", "Hormuz", "", 3)
+        };
+        assert_eq!(store.add_to_watchlist(&[row("aircraft", "ae1234", "", 1), region.clone()]).unwrap(), [0, 1]);
+        assert_eq!(store.add_to_watchlist(&[row("aircraft", "ae1234", "RCH123", 2)]).unwrap(), Vec::<usize>::new());
+
+        let listed = store.watchlist().unwrap();
+        assert_eq!(listed, [row("aircraft", "ae1234", "RCH123", 1), region]);
+        let keys = [("aircraft".to_string(), "ae1234".to_string()), ("vessel".to_string(), "1".to_string())];
+        assert_eq!(store.remove_from_watchlist(&keys).unwrap(), 1);
+        assert_eq!(store.watchlist().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn replaces_active_airspace_notices() {
+        let store = EventStore::in_memory();
+        let notice = |id: &str, source: &str, west, east, ends| NoticeRow {
+            id: id.to_string(),
+            source: source.to_string(),
+            bounds: BoundingBox { south: 0.0, west, north: 1.0, east },
+            ends,
+            data: json!({ "type": "Feature", "id": id }),
+        };
+        let tfrs = [notice("t1", "tfr", 10.0, 11.0, None), notice("t2", "tfr", 178.0, 180.0, Some(5))];
+        assert_eq!(store.replace_notices("tfr", &tfrs).unwrap(), [0, 1]);
+        store.replace_notices("notam", &[notice("n1", "notam", -20.0, -19.0, None)]).unwrap();
+        // t1 lapsed from the feed; t3 is new.
+        let tfrs = [notice("t2", "tfr", 178.0, 180.0, Some(5)), notice("t3", "tfr", 0.0, 1.0, None)];
+        assert_eq!(store.replace_notices("tfr", &tfrs).unwrap(), [1]);
+
+        assert_eq!(ids(store.notices(&NoticeQuery::default(), 0).unwrap()), ["n1", "t2", "t3"]);
+        assert_eq!(ids(store.notices(&NoticeQuery::default(), 10).unwrap()), ["n1", "t3"]);
+        let query = NoticeQuery {
+            source: Some("tfr".to_string()),
+            bounding_box: Some(BoundingBox { south: -1.0, west: 179.0, north: 1.0, east: -179.0 }),
+        };
+        assert_eq!(ids(store.notices(&query, 0).unwrap()), ["t2"]);
+    }
+
+    #[test]
+    fn keeps_space_weather_history() {
+        let store = EventStore::in_memory();
+        store.store_space_weather("kp", &[(1, 3.0), (2, 6.67)]).unwrap();
+        store.store_space_weather("kp", &[(2, 7.0), (3, 7.33)]).unwrap();
+        store.store_space_weather("speed", &[(2, 650.0)]).unwrap();
+        assert_eq!(store.space_weather("kp", 2).unwrap(), [(2, 7.0), (3, 7.33)]);
+
+        let flare = |class: &str, time| SpaceWeatherEventRow {
+            id: format!("flare:{time}"),
+            time,
+            data: json!({ "id": format!("flare:{time}"), "class": class }),
+        };
+        assert_eq!(store.upsert_space_weather_events(&[flare("M2.1", 1), flare("C1.0", 3)]).unwrap(), [None, None]);
+        let previous = store.upsert_space_weather_events(&[flare("X1.4", 1)]).unwrap();
+        assert_eq!(previous[0].as_ref().unwrap()["class"], "M2.1");
+        assert_eq!(ids(store.space_weather_events(0, 10).unwrap()), ["flare:3", "flare:1"]);
+
+        assert_eq!(store.prune_space_weather(2).unwrap(), 2);
+        assert_eq!(store.space_weather("kp", 0).unwrap().len(), 2);
+        assert_eq!(ids(store.space_weather_events(0, 10).unwrap()), ["flare:3"]);
+    }
+
+    #[test]
+    fn keeps_market_ticks_once_per_time() {
+        let store = EventStore::in_memory();
+        let quote = json!({ "symbol": "BZ=F", "price": 82.5 });
+        assert_eq!(store.store_market_quote("BZ=F", &quote, &[(1_000, 82.0), (2_000, 82.5)]).unwrap(), 2);
+        assert_eq!(store.store_market_quote("BZ=F", &quote, &[(2_000, 82.5), (3_000, 83.0)]).unwrap(), 1);
+        store.store_market_quote("GC=F", &json!({}), &[(2_500, 2400.0)]).unwrap();
+        assert_eq!(store.market_ticks("BZ=F", 1_500).unwrap(), [(2_000, 82.5), (3_000, 83.0)]);
+        assert_eq!(store.market_quote("BZ=F").unwrap(), Some(quote));
+        assert_eq!(store.market_quote("CL=F").unwrap(), None);
+        assert_eq!(store.prune_market_ticks(2_600).unwrap(), 3);
+        assert_eq!(store.market_ticks("BZ=F", 0).unwrap(), [(3_000, 83.0)]);
+    }
+
+    #[test]
+    fn caches_translations_per_language() {
+        let store = EventStore::in_memory();
+        let pair = |text: &str, translated: &str| (text.to_string(), translated.to_string());
+        store.store_translations("en", &[pair("Hola", "Hello"), pair("Adiós", "Goodbye")], 1_000).unwrap();
+        store.store_translations("fr", &[pair("Hola", "Bonjour")], 2_000).unwrap();
+        let texts = ["Hola".to_string(), "Gracias".to_string()];
+        let found = store.translations("en", &texts).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found["Hola"], "Hello");
+        assert_eq!(store.translations("fr", &texts).unwrap()["Hola"], "Bonjour");
+        assert_eq!(store.prune_translations(1_500).unwrap(), 2);
+        assert!(store.translations("en", &texts).unwrap().is_empty());
+    }
+
+    #[test]
+    fn merges_indicators_and_searches_vulnerabilities() {
+        let store = EventStore::in_memory();
+        let indicator = |value: &str, severity, last_seen, source: &str| IndicatorRow {
+            indicator_type: "ip".to_string(),
+            indicator: value.to_string(),
+            severity,
+            last_seen,
+            lat: Some(50.0),
+            lon: Some(10.0),
+            data: json!({ "id": value, "sources": [source] }),
+        };
+        let merge = |row: &IndicatorRow, stored: &Value| {
+            let mut merged = row.clone();
+            merged.data["sources"] = json!([stored["sources"][0], row.data["sources"][0]]);
+            merged
+        };
+        let added = store
+            .upsert_indicators(&[indicator("1.2.3.4", 3, 10, "feodo"), indicator("5.6.7.8", 1, 20, "otx")], merge)
+            .unwrap();
+        assert_eq!(added, [0, 1]);
+        assert!(store.upsert_indicators(&[indicator("1.2.3.4", 4, 30, "otx")], merge).unwrap().is_empty());
+        let all = store.indicators(&IndicatorQuery::default()).unwrap();
+        assert_eq!(ids(all.clone()), ["1.2.3.4", "5.6.7.8"]);
+        assert_eq!(all[0]["sources"], json!(["feodo", "otx"]));
+        let query = IndicatorQuery { min_severity: Some(2), ..Default::default() };
+        assert_eq!(ids(store.indicators(&query).unwrap()), ["1.2.3.4"]);
+        let query = IndicatorQuery { search: Some("5.6".to_string()), ..Default::default() };
+        assert_eq!(ids(store.indicators(&query).unwrap()), ["5.6.7.8"]);
+        assert_eq!(store.prune_indicators(25).unwrap(), 1);
+        assert_eq!(store.count_indicators().unwrap(), 1);
+
+        let vulnerability = |cve: &str, added: &str, ransomware| VulnerabilityRow {
+            cve: cve.to_string(),
+            added: added.to_string(),
+            ransomware,
+            data: json!({ "id": cve, "vendor": if ransomware { "Fortinet" } else { "Microsoft" } }),
+        };
+        let rows = [vulnerability("CVE-2024-1", "2024-01-02", false), vulnerability("CVE-2024-2", "2024-02-01", true)];
+        assert_eq!(store.upsert_vulnerabilities(&rows).unwrap(), [0, 1]);
+        assert!(store.upsert_vulnerabilities(&rows[..1]).unwrap().is_empty());
+        assert_eq!(ids(store.vulnerabilities(&VulnerabilityQuery::default()).unwrap()), ["CVE-2024-2", "CVE-2024-1"]);
+        let query = VulnerabilityQuery { search: Some("microsoft".to_string()), ..Default::default() };
+        assert_eq!(ids(store.vulnerabilities(&query).unwrap()), ["CVE-2024-1"]);
+        let query = VulnerabilityQuery { since: Some("2024-01-15".to_string()), ransomware: true, ..Default::default() };
+        assert_eq!(ids(store.vulnerabilities(&query).unwrap()), ["CVE-2024-2"]);
+    }
+
+    #[test]
+    fn keeps_a_cursor_per_source() {
+        let store = EventStore::in_memory();
+        assert_eq!(store.cursor("acled").unwrap(), None);
+        store.set_cursor("acled", "2026-01-01").unwrap();
+        store.set_cursor("acled", "2026-01-05").unwrap();
+        assert_eq!(store.cursor("acled").unwrap().as_deref(), Some("2026-01-05"));
+    }
+}
@@ -0,0 +1,284 @@
+//! Sun position for the map's day/night shading. Once a minute the shell
+//! computes the subsolar point, the terminator line and the night-side
+//! polygon, plus sunrise and sunset for the locations registered with
+//! `set_sun_locations`, and emits them as `sun://state`; `get_sun_state`
+//! returns the same thing on demand.
+//!
+//! Positions use the usual low-precision solar formulas (good to about a
+//! hundredth of a degree this century), and sunrise/sunset the standard
+//! -0.833° altitude for refraction and the solar disc, so times are within a
+//! minute or so of published tables.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+pub const STATE_EVENT: &str = "sun://state";
+
+const UPDATE_INTERVAL: Duration = Duration::from_secs(60);
+const TERMINATOR_STEP_DEGREES: usize = 2;
+const HORIZON_DEGREES: f64 = -0.833;
+const MAX_LOCATIONS: usize = 200;
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SunLocation {
+    /// Echoed back so the caller can match results to its places.
+    pub id: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Polar {
+    /// The sun stays up all day.
+    Day,
+    /// The sun stays down all day.
+    Night,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationSun {
+    pub id: String,
+    /// Sunrise and sunset either side of the location's solar noon on the
+    /// current UTC day, in milliseconds since the epoch; `None` during polar
+    /// day or night.
+    pub sunrise: Option<i64>,
+    pub sunset: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub polar: Option<Polar>,
+    /// Whether the sun is up right now.
+    pub daylight: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SunState {
+    /// Milliseconds since the epoch.
+    pub time: i64,
+    /// `[lon, lat]` of the point with the sun straight overhead.
+    pub subsolar: [f64; 2],
+    /// `[lon, lat]` points from -180° to 180° longitude.
+    pub terminator: Vec<[f64; 2]>,
+    /// Closed `[lon, lat]` ring of the night side, for a fill layer.
+    pub night: Vec<[f64; 2]>,
+    pub locations: Vec<LocationSun>,
+}
+
+/// The places registered with `set_sun_locations`.
+#[derive(Default)]
+pub struct SunLocationsState(Mutex<Vec<SunLocation>>);
+
+/// Declination, right ascension (both degrees) and the equation of time
+/// (minutes) at `time` (milliseconds since the epoch).
+fn solar_coordinates(time: i64) -> (f64, f64, f64) {
+    let days = time as f64 / DAY_MS as f64 - 10_957.5; // since J2000.0
+    let mean_longitude = (280.460 + 0.985_647_4 * days).rem_euclid(360.0);
+    let anomaly = (357.528 + 0.985_600_3 * days).rem_euclid(360.0).to_radians();
+    let longitude = (mean_longitude + 1.915 * anomaly.sin() + 0.020 * (2.0 * anomaly).sin()).to_radians();
+    let obliquity = (23.439 - 0.000_000_4 * days).to_radians();
+    let right_ascension = (obliquity.cos() * longitude.sin()).atan2(longitude.cos()).to_degrees();
+    let declination = (obliquity.sin() * longitude.sin()).asin().to_degrees();
+    let equation_of_time = 4.0 * wrap_degrees(mean_longitude - right_ascension);
+    (declination, right_ascension, equation_of_time)
+}
+
+/// Into -180..180.
+fn wrap_degrees(degrees: f64) -> f64 {
+    (degrees + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// `[lon, lat]` of the subsolar point.
+fn subsolar_point(time: i64) -> [f64; 2] {
+    let (declination, right_ascension, _) = solar_coordinates(time);
+    let days = time as f64 / DAY_MS as f64 - 10_957.5;
+    let sidereal = 280.460_618_37 + 360.985_647_366_29 * days;
+    [wrap_degrees(right_ascension - sidereal), declination]
+}
+
+/// The terminator, where the sun's centre is on the horizon, and the night
+/// ring closed over the dark pole.
+fn terminator(subsolar: [f64; 2]) -> (Vec<[f64; 2]>, Vec<[f64; 2]>) {
+    let [sun_lon, declination] = subsolar;
+    // At the equinoxes the terminator runs pole to pole; nudge off zero.
+    let declination = if declination.abs() < 1e-6 { 1e-6 } else { declination }.to_radians();
+    let line: Vec<[f64; 2]> = (0..=360)
+        .step_by(TERMINATOR_STEP_DEGREES)
+        .map(|step| {
+            let lon = step as f64 - 180.0;
+            let hour_angle = (lon - sun_lon).to_radians();
+            let lat = (-hour_angle.cos() / declination.tan()).atan().to_degrees();
+            [lon, lat]
+        })
+        .collect();
+    // The pole in darkness is the one away from the sun.
+    let pole = if declination > 0.0 { -90.0 } else { 90.0 };
+    let mut night = line.clone();
+    night.extend([[180.0, pole], [-180.0, pole], line[0]]);
+    (line, night)
+}
+
+fn altitude(lat: f64, lon: f64, subsolar: [f64; 2]) -> f64 {
+    let (lat, declination) = (lat.to_radians(), subsolar[1].to_radians());
+    let hour_angle = (lon - subsolar[0]).to_radians();
+    (lat.sin() * declination.sin() + lat.cos() * declination.cos() * hour_angle.cos())
+        .asin()
+        .to_degrees()
+}
+
+fn location_sun(location: &SunLocation, time: i64, subsolar: [f64; 2]) -> LocationSun {
+    // Solar noon on the UTC day of `time`, refined once with that instant's
+    // equation of time.
+    let midnight = time.div_euclid(DAY_MS) * DAY_MS;
+    let noon_at = |equation_of_time: f64| {
+        midnight + ((720.0 - 4.0 * location.lon - equation_of_time) * 60_000.0) as i64
+    };
+    let (_, _, equation_of_time) = solar_coordinates(midnight + DAY_MS / 2);
+    let noon = noon_at(equation_of_time);
+    let (declination, _, equation_of_time) = solar_coordinates(noon);
+    let noon = noon_at(equation_of_time);
+
+    let (lat, declination) = (location.lat.to_radians(), declination.to_radians());
+    let cos_hour_angle =
+        (HORIZON_DEGREES.to_radians().sin() - lat.sin() * declination.sin()) / (lat.cos() * declination.cos());
+    let (sunrise, sunset, polar) = if cos_hour_angle > 1.0 {
+        (None, None, Some(Polar::Night))
+    } else if cos_hour_angle < -1.0 {
+        (None, None, Some(Polar::Day))
+    } else {
+        let half_day = (cos_hour_angle.acos().to_degrees() * 4.0 * 60_000.0) as i64;
+        (Some(noon - half_day), Some(noon + half_day), None)
+    };
+    LocationSun {
+        id: location.id.clone(),
+        sunrise,
+        sunset,
+        polar,
+        daylight: altitude(location.lat, location.lon, subsolar) > HORIZON_DEGREES,
+    }
+}
+
+fn compute(time: i64, locations: &[SunLocation]) -> SunState {
+    let subsolar = subsolar_point(time);
+    let (terminator, night) = terminator(subsolar);
+    SunState {
+        time,
+        subsolar,
+        terminator,
+        night,
+        locations: locations.iter().map(|l| location_sun(l, time, subsolar)).collect(),
+    }
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn current(app: &AppHandle) -> SunState {
+    let state = app.state::<SunLocationsState>();
+    let locations = state.0.lock().unwrap_or_else(|e| e.into_inner());
+    compute(now_ms(), &locations)
+}
+
+/// Emit `sun://state` now and then every minute.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut tick = tokio::time::interval(UPDATE_INTERVAL);
+        loop {
+            tick.tick().await;
+            let _ = app.emit(STATE_EVENT, current(&app));
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_sun_state(webview: Webview, app: AppHandle) -> Result<SunState, String> {
+    crate::metrics::of(&webview).observe("get_sun_state", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(current(&app))
+    })
+}
+
+/// Replace the places whose sunrise and sunset are reported, and return the
+/// state with them.
+#[tauri::command]
+pub fn set_sun_locations(
+    webview: Webview,
+    app: AppHandle,
+    state: tauri::State<'_, SunLocationsState>,
+    locations: Vec<SunLocation>,
+) -> Result<SunState, String> {
+    crate::metrics::of(&webview).observe("set_sun_locations", || {
+        crate::require_trusted_window(webview.label())?;
+        if locations.len() > MAX_LOCATIONS {
+            return Err(format!("At most {MAX_LOCATIONS} locations"));
+        }
+        if !locations.iter().all(|l| (-90.0..=90.0).contains(&l.lat) && (-180.0..=180.0).contains(&l.lon)) {
+            return Err("Location coordinates out of range".to_string());
+        }
+        *state.0.lock().unwrap_or_else(|e| e.into_inner()) = locations;
+        Ok(current(&app))
+    })
+}
+
+#[cfg(test)]
+mod sun_tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> i64 {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap().timestamp_millis()
+    }
+
+    fn location(lat: f64, lon: f64) -> SunLocation {
+        SunLocation { id: "x".to_string(), lat, lon }
+    }
+
+    #[test]
+    fn finds_the_subsolar_point() {
+        // June solstice, noon UTC: over the Tropic of Cancer near Greenwich.
+        let [lon, lat] = subsolar_point(at(2026, 6, 21, 12, 0));
+        assert!((lat - 23.43).abs() < 0.1, "lat {lat}");
+        assert!(lon.abs() < 1.0, "lon {lon}");
+        // Six hours later it has moved 90° west.
+        let [later, _] = subsolar_point(at(2026, 6, 21, 18, 0));
+        assert!((later - (lon - 90.0)).abs() < 0.1, "lon {later}");
+    }
+
+    #[test]
+    fn draws_the_terminator() {
+        let time = at(2026, 6, 21, 12, 0);
+        let subsolar = subsolar_point(time);
+        let (line, night) = terminator(subsolar);
+        assert_eq!(line.len(), 181);
+        assert_eq!(night.first(), night.last());
+        for [lon, lat] in &line {
+            assert!(altitude(*lat, *lon, subsolar).abs() < 0.01);
+        }
+        // Northern summer: the south pole is in the dark.
+        assert_eq!(night[night.len() - 2], [-180.0, -90.0]);
+    }
+
+    #[test]
+    fn computes_sunrise_and_sunset() {
+        let time = at(2026, 6, 21, 12, 0);
+        let london = location_sun(&location(51.5074, -0.1278), time, subsolar_point(time));
+        let minutes = |ms: i64| (ms - at(2026, 6, 21, 0, 0)) / 60_000;
+        // Published: 03:43 and 20:21 UTC.
+        assert!((minutes(london.sunrise.unwrap()) - (3 * 60 + 43)).abs() <= 2);
+        assert!((minutes(london.sunset.unwrap()) - (20 * 60 + 21)).abs() <= 2);
+        assert!(london.daylight);
+
+        let tromso = location_sun(&location(69.65, 18.96), time, subsolar_point(time));
+        assert_eq!((tromso.sunrise, tromso.polar), (None, Some(Polar::Day)));
+        let december = at(2026, 12, 21, 12, 0);
+        let tromso = location_sun(&location(69.65, 18.96), december, subsolar_point(december));
+        assert_eq!(tromso.polar, Some(Polar::Night));
+        assert!(!tromso.daylight);
+    }
+}
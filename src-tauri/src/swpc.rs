@@ -0,0 +1,476 @@
+//! NOAA Space Weather Prediction Center products polled into the local
+//! `store`. With `swpc.enabled` set (the default), the worker fetches every
+//! `UPDATE_INTERVAL` the planetary Kp index, the DSCOVR solar wind plasma
+//! and magnetic field (the past day on the first poll, two hours after
+//! that), the GOES X-ray flare list and the SWPC alerts, watches and
+//! warnings. Readings are kept as series (`kp`, `speed`, `density`, `bz`,
+//! `bt`) and flares and messages as events, all for `RETENTION_DAYS`;
+//! `get_space_weather` reads them back.
+//!
+//! The latest Kp sets the geomagnetic storm level on NOAA's G scale. When it
+//! rises, or a flare is new or has grown, within `ALERT_WITHIN`, the change
+//! goes to the alert engine, where `spaceWeather` rules fire as it crosses
+//! their level (G3 and X1 by default); each poll also records the
+//! `swpc.kp`, `swpc.speed`, `swpc.density` and `swpc.bz` metrics for
+//! threshold rules, and emits the latest conditions as `swpc://update`.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+use crate::config::DesktopConfigState;
+use crate::store::{EventStore, SpaceWeatherEventRow};
+
+pub const UPDATE_EVENT: &str = "swpc://update";
+
+const KP_URL: &str = "https://services.swpc.noaa.gov/products/noaa-planetary-k-index.json";
+const SOLAR_WIND_URL: &str = "https://services.swpc.noaa.gov/products/solar-wind";
+const FLARES_URL: &str = "https://services.swpc.noaa.gov/json/goes/primary/xray-flares-7-day.json";
+const MESSAGES_URL: &str = "https://services.swpc.noaa.gov/products/alerts.json";
+
+const UPDATE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const ERROR_RETRY: Duration = Duration::from_secs(10 * 60);
+/// Older storms and flares are stored but not alerted on, so the first poll
+/// after a restart does not replay the week.
+const ALERT_WITHIN: Duration = Duration::from_secs(6 * 60 * 60);
+const RETENTION_DAYS: u64 = 30;
+const DEFAULT_HOURS: u32 = 72;
+const MAX_EVENTS: usize = 500;
+/// Kp at which each G-scale level starts: 5-, 6-, 7-, 8- and 9o.
+const STORM_KP: [f64; 5] = [4.67, 5.67, 6.67, 7.67, 9.0];
+
+/// A rise in the storm level or a new or larger flare, for the alert
+/// engine.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpaceWeatherEvent {
+    pub subject: String,
+    pub change: SpaceWeatherChange,
+    pub description: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpaceWeatherChange {
+    /// G-scale levels, 0 when quiet.
+    Storm { level: u8, previous: u8 },
+    /// Peak 1-8 Å flux in W/m².
+    Flare { flux: f64, previous: Option<f64> },
+}
+
+/// The G-scale level of a Kp index.
+pub fn storm_level(kp: f64) -> u8 {
+    STORM_KP.iter().filter(|start| kp >= **start - 0.005).count() as u8
+}
+
+/// Peak flux of a flare class such as `X1.4` or `M`.
+pub fn flare_flux(class: &str) -> Option<f64> {
+    let class = class.trim();
+    let scale = match class.chars().next()?.to_ascii_uppercase() {
+        'A' => 1e-8,
+        'B' => 1e-7,
+        'C' => 1e-6,
+        'M' => 1e-5,
+        'X' => 1e-4,
+        _ => return None,
+    };
+    let multiplier = match &class[1..] {
+        "" => 1.0,
+        digits => digits.parse::<f64>().ok().filter(|m| *m > 0.0 && m.is_finite())?,
+    };
+    Some(scale * multiplier)
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwpcStatus {
+    pub enabled: bool,
+    /// When the products were last fetched, in milliseconds since the epoch.
+    pub last_update: Option<i64>,
+    /// The current G-scale level.
+    pub storm_level: Option<u8>,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct SwpcState {
+    status: Mutex<SwpcStatus>,
+}
+
+fn update_status(app: &AppHandle, f: impl FnOnce(&mut SwpcStatus)) {
+    let state = app.state::<SwpcState>();
+    f(&mut state.status.lock().unwrap_or_else(|e| e.into_inner()));
+}
+
+/// Rows of an SWPC product, which come either as an array of objects or as
+/// an array of arrays headed by the column names.
+fn table(text: &str) -> Result<Vec<Map<String, Value>>, String> {
+    let rows: Vec<Value> = serde_json::from_str(text).map_err(|e| format!("invalid product: {e}"))?;
+    let Some(Value::Array(header)) = rows.first() else {
+        return Ok(rows.into_iter().filter_map(|row| row.as_object().cloned()).collect());
+    };
+    let columns: Vec<String> = header.iter().map(|c| c.as_str().unwrap_or_default().to_string()).collect();
+    Ok(rows[1..]
+        .iter()
+        .filter_map(Value::as_array)
+        .map(|row| columns.iter().cloned().zip(row.iter().cloned()).collect())
+        .collect())
+}
+
+/// A number, which SWPC sends as a string in some products.
+fn number(row: &Map<String, Value>, key: &str) -> Option<f64> {
+    match row.get(key)? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+    .filter(|n: &f64| n.is_finite())
+}
+
+/// A UTC time such as `2026-05-10 12:00:00.000` or `2026-05-10T12:00:00Z`,
+/// in milliseconds since the epoch.
+fn time(row: &Map<String, Value>, key: &str) -> Option<i64> {
+    let text = row.get(key)?.as_str()?.trim().trim_end_matches('Z').replace('T', " ");
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| chrono::NaiveDateTime::parse_from_str(&text, format).ok())
+        .map(|t| t.and_utc().timestamp_millis())
+}
+
+/// `(time, value)` readings of one column, oldest first.
+fn series(rows: &[Map<String, Value>], column: &str) -> Vec<(i64, f64)> {
+    let mut readings: Vec<(i64, f64)> =
+        rows.iter().filter_map(|row| Some((time(row, "time_tag")?, number(row, column)?))).collect();
+    readings.sort_by_key(|(time, _)| *time);
+    readings
+}
+
+fn parse_flares(text: &str) -> Result<Vec<SpaceWeatherEventRow>, String> {
+    Ok(table(text)?
+        .iter()
+        .filter_map(|row| {
+            let begin = time(row, "begin_time")?;
+            let class = row.get("max_class")?.as_str()?.trim().to_string();
+            let flux = number(row, "max_xrlong").or_else(|| flare_flux(&class))?;
+            let id = format!("flare:{begin}");
+            Some(SpaceWeatherEventRow {
+                data: json!({
+                    "id": id,
+                    "kind": "flare",
+                    "time": begin,
+                    "peak": time(row, "max_time"),
+                    "end": time(row, "end_time"),
+                    "class": class,
+                    "flux": flux,
+                    "satellite": row.get("satellite"),
+                }),
+                id,
+                time: begin,
+            })
+        })
+        .collect())
+}
+
+fn parse_messages(text: &str) -> Result<Vec<SpaceWeatherEventRow>, String> {
+    Ok(table(text)?
+        .iter()
+        .filter_map(|row| {
+            let issued = time(row, "issue_datetime")?;
+            let product = row.get("product_id")?.as_str()?.trim();
+            let message = row.get("message")?.as_str()?.trim();
+            let id = format!("message:{product}:{issued}");
+            Some(SpaceWeatherEventRow {
+                data: json!({ "id": id, "kind": "message", "time": issued, "product": product, "message": message }),
+                id,
+                time: issued,
+            })
+        })
+        .collect())
+}
+
+/// The storm level of the latest Kp reading, and the alert event when it
+/// rose from `previous` (the level of the reading before it on the first
+/// poll) within `ALERT_WITHIN` of `now`.
+fn storm_change(previous: Option<u8>, kp: &[(i64, f64)], now: i64) -> (Option<u8>, Option<SpaceWeatherEvent>) {
+    let Some(&(time, value)) = kp.last() else {
+        return (previous, None);
+    };
+    let level = storm_level(value);
+    let previous = previous.unwrap_or_else(|| kp.iter().rev().nth(1).map_or(0, |(_, kp)| storm_level(*kp)));
+    if level <= previous || now - time > ALERT_WITHIN.as_millis() as i64 {
+        return (Some(level), None);
+    }
+    let event = SpaceWeatherEvent {
+        subject: format!("storm:{time}"),
+        change: SpaceWeatherChange::Storm { level, previous },
+        description: format!("G{level} geomagnetic storm (Kp {value:.2})"),
+    };
+    (Some(level), Some(event))
+}
+
+/// Flares that are new or have grown, and began within `ALERT_WITHIN` of
+/// `now`.
+fn flare_changes(flares: &[SpaceWeatherEventRow], previous: &[Option<Value>], now: i64) -> Vec<SpaceWeatherEvent> {
+    flares
+        .iter()
+        .zip(previous)
+        .filter(|(flare, _)| now - flare.time <= ALERT_WITHIN.as_millis() as i64)
+        .filter_map(|(flare, previous)| {
+            let flux = flare.data["flux"].as_f64()?;
+            let previous = previous.as_ref().and_then(|p| p["flux"].as_f64());
+            if previous.is_some_and(|p| p >= flux) {
+                return None;
+            }
+            Some(SpaceWeatherEvent {
+                subject: flare.id.clone(),
+                change: SpaceWeatherChange::Flare { flux, previous },
+                description: format!("{} solar flare", flare.data["class"].as_str().unwrap_or_default()),
+            })
+        })
+        .collect()
+}
+
+async fn get(client: &reqwest::Client, url: &str) -> Result<String, String> {
+    client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("{url}: request failed: {e}"))?
+        .text()
+        .await
+        .map_err(|e| format!("{url}: request failed: {e}"))
+}
+
+/// The latest reading of each series, as `swpc://update` carries them.
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Conditions {
+    pub storm_level: Option<u8>,
+    pub kp: Option<f64>,
+    /// km/s.
+    pub speed: Option<f64>,
+    /// Protons per cm³.
+    pub density: Option<f64>,
+    /// nT, GSM.
+    pub bz: Option<f64>,
+    pub bt: Option<f64>,
+}
+
+/// One poll of every product; `window` is the solar wind span (`1-day` or
+/// `2-hour`).
+async fn update(app: &AppHandle, client: &reqwest::Client, window: &str) -> Result<(), String> {
+    let kp = series(&table(&get(client, KP_URL).await?)?, "Kp");
+    let plasma = table(&get(client, &format!("{SOLAR_WIND_URL}/plasma-{window}.json")).await?)?;
+    let mag = table(&get(client, &format!("{SOLAR_WIND_URL}/mag-{window}.json")).await?)?;
+    let flares = parse_flares(&get(client, FLARES_URL).await?)?;
+    let messages = parse_messages(&get(client, MESSAGES_URL).await?)?;
+    let readings = [
+        ("kp", kp),
+        ("speed", series(&plasma, "speed")),
+        ("density", series(&plasma, "density")),
+        ("bz", series(&mag, "bz_gsm")),
+        ("bt", series(&mag, "bt")),
+    ];
+
+    let handle = app.clone();
+    let (readings, flares, previous) = crate::runtime::blocking(move || {
+        let store = handle.state::<EventStore>();
+        for (name, readings) in &readings {
+            store.store_space_weather(name, readings)?;
+        }
+        let previous = store.upsert_space_weather_events(&flares)?;
+        store.upsert_space_weather_events(&messages)?;
+        let cutoff = crate::unix_timestamp_secs().saturating_sub(RETENTION_DAYS * 24 * 60 * 60) as i64 * 1000;
+        store.prune_space_weather(cutoff)?;
+        Ok::<_, String>((readings, flares, previous))
+    })
+    .await?;
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let stored_level = app.state::<SwpcState>().status.lock().unwrap_or_else(|e| e.into_inner()).storm_level;
+    let (level, storm) = storm_change(stored_level, &readings[0].1, now);
+    let mut events = flare_changes(&flares, &previous, now);
+    events.extend(storm);
+    update_status(app, |status| {
+        status.last_update = Some(now);
+        status.storm_level = level;
+    });
+    crate::alerts::observe_space_weather(app, &events);
+
+    let latest = |i: usize| readings[i].1.last().map(|(_, value)| *value);
+    let conditions = Conditions {
+        storm_level: level,
+        kp: latest(0),
+        speed: latest(1),
+        density: latest(2),
+        bz: latest(3),
+        bt: latest(4),
+    };
+    // Every series but `bt` is a metric.
+    for (i, (name, _)) in readings.iter().enumerate().take(4) {
+        if let Some(value) = latest(i) {
+            crate::alerts::record_metric(app, &format!("swpc.{name}"), value);
+        }
+    }
+    tracing::debug!(target: "app", storm_level = ?level, flares = events.len(), "SWPC products polled");
+    let _ = app.emit(UPDATE_EVENT, conditions);
+    Ok(())
+}
+
+struct Poller {
+    app: AppHandle,
+    client: reqwest::Client,
+    /// The feeds' time window: a day to backfill, then two hours.
+    window: &'static str,
+}
+
+impl crate::poll::Poller for Poller {
+    async fn round(&mut self) -> Duration {
+        match update(&self.app, &self.client, self.window).await {
+            Ok(()) => {
+                update_status(&self.app, |status| status.error = None);
+                self.window = "2-hour";
+                UPDATE_INTERVAL
+            }
+            Err(err) => {
+                tracing::warn!(target: "app", "SWPC update failed: {err}");
+                update_status(&self.app, |status| status.error = Some(err));
+                ERROR_RETRY
+            }
+        }
+    }
+}
+
+async fn run(app: AppHandle) {
+    let client = match reqwest::Client::builder().use_native_tls().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!(target: "app", "space weather polling unavailable: {err}");
+            return;
+        }
+    };
+    let poller = Poller {
+        app: app.clone(),
+        client,
+        window: "1-day",
+    };
+    crate::poll::run(&app, poller).await;
+}
+
+/// Start polling if `swpc.enabled` is set.
+pub fn start(app: &AppHandle) {
+    let config = app.state::<DesktopConfigState>().snapshot().swpc;
+    update_status(app, |status| status.enabled = config.enabled);
+    if config.enabled {
+        tauri::async_runtime::spawn(run(app.clone()));
+    }
+}
+
+#[tauri::command]
+pub fn get_swpc_status(webview: Webview, state: tauri::State<'_, SwpcState>) -> Result<SwpcStatus, String> {
+    crate::metrics::of(&webview).observe("get_swpc_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.status.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+/// Stored space weather: `[time, value]` readings oldest first and flares
+/// and messages newest first.
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpaceWeather {
+    pub storm_level: Option<u8>,
+    pub kp: Vec<(i64, f64)>,
+    pub speed: Vec<(i64, f64)>,
+    pub density: Vec<(i64, f64)>,
+    pub bz: Vec<(i64, f64)>,
+    pub bt: Vec<(i64, f64)>,
+    pub events: Vec<Value>,
+}
+
+/// The last `hours` of stored space weather (72 by default).
+#[tauri::command]
+pub async fn get_space_weather(webview: Webview, app: AppHandle, hours: Option<u32>) -> Result<SpaceWeather, String> {
+    crate::metrics::of(&webview)
+        .observe_async("get_space_weather", async move {
+            crate::require_trusted_window(webview.label())?;
+            let hours = i64::from(hours.unwrap_or(DEFAULT_HOURS).min(RETENTION_DAYS as u32 * 24));
+            let since = chrono::Utc::now().timestamp_millis() - hours * 3_600_000;
+            let storm_level = app.state::<SwpcState>().status.lock().unwrap_or_else(|e| e.into_inner()).storm_level;
+            crate::runtime::blocking(move || {
+                let store = app.state::<EventStore>();
+                Ok(SpaceWeather {
+                    storm_level,
+                    kp: store.space_weather("kp", since)?,
+                    speed: store.space_weather("speed", since)?,
+                    density: store.space_weather("density", since)?,
+                    bz: store.space_weather("bz", since)?,
+                    bt: store.space_weather("bt", since)?,
+                    events: store.space_weather_events(since, MAX_EVENTS)?,
+                })
+            })
+            .await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod swpc_tests {
+    use super::*;
+
+    #[test]
+    fn reads_both_product_layouts() {
+        let arrays = r#"[["time_tag","Kp","a_running","station_count"],
+            ["2026-05-10 09:00:00.000","6.67","111","8"],
+            ["2026-05-10 06:00:00.000","5.33","56","8"],
+            ["2026-05-10 12:00:00.000","bad","0","8"]]"#;
+        let objects = r#"[{"time_tag":"2026-05-10T06:00:00","Kp":5.33},{"time_tag":"2026-05-10T09:00:00","Kp":6.67}]"#;
+        let expected = [(1_778_392_800_000, 5.33), (1_778_403_600_000, 6.67)];
+        assert_eq!(series(&table(arrays).unwrap(), "Kp"), expected);
+        assert_eq!(series(&table(objects).unwrap(), "Kp"), expected);
+        assert!(table("<html>").is_err());
+
+        let flares = r#"[{"time_tag":"2026-05-10T18:00:00Z","begin_time":"2026-05-10T17:02:00Z","begin_class":"M1.0",
+            "max_time":"2026-05-10T17:21:00Z","max_class":"X2.4","max_xrlong":0.00024,"end_time":"Unk","satellite":18}]"#;
+        let flares = parse_flares(flares).unwrap();
+        assert_eq!(flares[0].id, "flare:1778432520000");
+        assert_eq!((flares[0].data["class"].as_str(), flares[0].data["end"].as_i64()), (Some("X2.4"), None));
+        let messages = r#"[{"product_id":"K07A","issue_datetime":"2026-05-10 17:44:12.207","message":"ALERT: Geomagnetic K-index of 7"}]"#;
+        assert_eq!(parse_messages(messages).unwrap()[0].id, "message:K07A:1778435052207");
+    }
+
+    #[test]
+    fn grades_storms_and_flares() {
+        assert_eq!([4.33, 4.67, 6.67, 7.33, 8.67, 9.0].map(storm_level), [0, 1, 3, 3, 4, 5]);
+        assert_eq!(flare_flux("X1.4"), Some(1.4e-4));
+        assert_eq!(flare_flux("m"), Some(1e-5));
+        assert_eq!(flare_flux("Z1"), None);
+        assert_eq!(flare_flux("X-1"), None);
+    }
+
+    #[test]
+    fn reports_rising_storms_and_growing_flares() {
+        let now = 1_778_403_600_000;
+        let kp = [(now - 3 * 3_600_000, 5.33), (now, 6.67)];
+        let (level, event) = storm_change(None, &kp, now);
+        assert_eq!(level, Some(3));
+        assert_eq!(event.unwrap().change, SpaceWeatherChange::Storm { level: 3, previous: 1 });
+        assert_eq!(storm_change(Some(3), &kp, now).1, None);
+        // A stale reading sets the level without an alert.
+        assert_eq!(storm_change(Some(0), &kp, now + 7 * 3_600_000), (Some(3), None));
+
+        let flare = |flux: f64, time| SpaceWeatherEventRow {
+            id: format!("flare:{time}"),
+            time,
+            data: json!({ "class": "X", "flux": flux }),
+        };
+        let flares = [flare(2e-4, now), flare(5e-5, now), flare(3e-4, now - 7 * 3_600_000)];
+        let previous = [Some(json!({ "flux": 5e-5 })), Some(json!({ "flux": 5e-5 })), None];
+        let events = flare_changes(&flares, &previous, now);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].change, SpaceWeatherChange::Flare { flux: 2e-4, previous: Some(5e-5) });
+    }
+}
@@ -0,0 +1,333 @@
+//! Mini ticker: a small frameless, always-on-top `ticker` window showing the
+//! live alert and headline counts from `alert_status`. It is toggled from the
+//! tray menu, which also offers click-through (clicks go to the windows
+//! underneath) and the screen corner it sits in (`TickerConfig`).
+//!
+//! The ticker always sits in a corner of its monitor's work area. It can be
+//! dragged to another monitor or corner; once the drag settles it snaps to
+//! the nearest corner, which becomes the saved one.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use tauri::menu::{CheckMenuItem, IsMenuItem, PredefinedMenuItem, Submenu};
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, Webview, WebviewUrl, WebviewWindow, WebviewWindowBuilder, Wry};
+
+use crate::config::{DesktopConfigState, TickerConfig, TickerCorner};
+
+pub const TICKER_WINDOW: &str = "ticker";
+const TICKER_WIDTH: f64 = 320.0;
+const TICKER_HEIGHT: f64 = 36.0;
+/// Logical pixels between the ticker and the work area edges.
+const CORNER_MARGIN: f64 = 12.0;
+/// Moves within this window count as one drag.
+const SNAP_DELAY: Duration = Duration::from_millis(500);
+
+const MENU_PREFIX: &str = "tray.ticker.";
+const MENU_SHOW_ID: &str = "tray.ticker.show";
+const MENU_CLICK_THROUGH_ID: &str = "tray.ticker.click-through";
+const CORNERS: [(TickerCorner, &str, &str); 4] = [
+    (TickerCorner::TopLeft, "tray.ticker.top-left", "Top Left"),
+    (TickerCorner::TopRight, "tray.ticker.top-right", "Top Right"),
+    (TickerCorner::BottomLeft, "tray.ticker.bottom-left", "Bottom Left"),
+    (TickerCorner::BottomRight, "tray.ticker.bottom-right", "Bottom Right"),
+];
+
+/// Managed state: counts window moves so only the last one of a drag snaps.
+#[derive(Default)]
+pub struct TickerState {
+    moves: AtomicU64,
+}
+
+/// Managed once the tray is built: the ticker's tray menu items.
+struct TickerMenu {
+    show_item: CheckMenuItem<Wry>,
+    click_through_item: CheckMenuItem<Wry>,
+    corner_items: Vec<(TickerCorner, CheckMenuItem<Wry>)>,
+}
+
+/// Top-left position for a window of `size` in `corner` of a work area.
+fn corner_origin(
+    area_origin: PhysicalPosition<i32>,
+    area_size: PhysicalSize<u32>,
+    size: PhysicalSize<u32>,
+    corner: TickerCorner,
+    margin: i32,
+) -> PhysicalPosition<i32> {
+    let left = area_origin.x + margin;
+    let top = area_origin.y + margin;
+    let right = area_origin.x + area_size.width as i32 - size.width as i32 - margin;
+    let bottom = area_origin.y + area_size.height as i32 - size.height as i32 - margin;
+    match corner {
+        TickerCorner::TopLeft => PhysicalPosition::new(left, top),
+        TickerCorner::TopRight => PhysicalPosition::new(right.max(left), top),
+        TickerCorner::BottomLeft => PhysicalPosition::new(left, bottom.max(top)),
+        TickerCorner::BottomRight => PhysicalPosition::new(right.max(left), bottom.max(top)),
+    }
+}
+
+/// The work area corner closest to the window's center.
+fn nearest_corner(
+    area_origin: PhysicalPosition<i32>,
+    area_size: PhysicalSize<u32>,
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+) -> TickerCorner {
+    let center_x = i64::from(position.x) + i64::from(size.width) / 2;
+    let center_y = i64::from(position.y) + i64::from(size.height) / 2;
+    let left = center_x < i64::from(area_origin.x) + i64::from(area_size.width) / 2;
+    let top = center_y < i64::from(area_origin.y) + i64::from(area_size.height) / 2;
+    match (top, left) {
+        (true, true) => TickerCorner::TopLeft,
+        (true, false) => TickerCorner::TopRight,
+        (false, true) => TickerCorner::BottomLeft,
+        (false, false) => TickerCorner::BottomRight,
+    }
+}
+
+/// Move the ticker into `corner` of the monitor it is on (or the primary one).
+fn snap_to(window: &WebviewWindow, corner: TickerCorner) {
+    let Some(monitor) = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .or_else(|| window.primary_monitor().ok().flatten())
+    else {
+        return;
+    };
+    let Ok(size) = window.outer_size() else {
+        return;
+    };
+    let area = monitor.work_area();
+    let margin = (CORNER_MARGIN * monitor.scale_factor()).round() as i32;
+    let target = corner_origin(area.position, area.size, size, corner, margin);
+    if window.outer_position().ok() != Some(target) {
+        let _ = window.set_position(target);
+    }
+}
+
+fn apply(window: &WebviewWindow, ticker: &TickerConfig) {
+    if let Err(err) = window.set_ignore_cursor_events(ticker.click_through) {
+        tracing::warn!(target: "app", "failed to set ticker click-through: {err}");
+    }
+    snap_to(window, ticker.corner);
+}
+
+fn open(app: &AppHandle) -> Result<(), String> {
+    let ticker = app.state::<DesktopConfigState>().snapshot().ticker;
+    if let Some(window) = app.get_webview_window(TICKER_WINDOW) {
+        apply(&window, &ticker);
+        return window.show().map_err(|e| format!("Failed to show ticker: {e}"));
+    }
+
+    let window = WebviewWindowBuilder::new(app, TICKER_WINDOW, WebviewUrl::App("ticker.html".into()))
+        .title(format!("{} Ticker", app.package_info().name))
+        .inner_size(TICKER_WIDTH, TICKER_HEIGHT)
+        .decorations(false)
+        .resizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .focused(false)
+        .shadow(false)
+        .visible(false)
+        .background_color(tauri::webview::Color(26, 28, 30, 255))
+        .build()
+        .map_err(|e| format!("Failed to create ticker window: {e}"))?;
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = window.remove_menu();
+
+    apply(&window, &ticker);
+    let _ = window.show();
+    tracing::info!(target: "app", "ticker opened");
+    Ok(())
+}
+
+fn close(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(TICKER_WINDOW) {
+        let _ = window.destroy();
+    }
+}
+
+/// Reopen the ticker if it was showing when the app last quit.
+pub fn restore(app: &AppHandle) {
+    if app.state::<DesktopConfigState>().snapshot().ticker.visible {
+        if let Err(err) = open(app) {
+            tracing::warn!(target: "app", "failed to restore ticker: {err}");
+        }
+    }
+}
+
+/// Persist a ticker preference, then bring the window and tray menu in line
+/// with what was actually saved.
+fn update(app: &AppHandle, change: impl FnOnce(&mut TickerConfig)) {
+    let config = app.state::<DesktopConfigState>();
+    let ticker = match config.update(|c| change(&mut c.ticker)) {
+        Ok(saved) => saved.ticker,
+        Err(err) => {
+            tracing::error!(target: "menu", "failed to save ticker preference: {err}");
+            config.snapshot().ticker
+        }
+    };
+    if ticker.visible {
+        if let Err(err) = open(app) {
+            tracing::error!(target: "app", "{err}");
+        }
+    } else {
+        close(app);
+    }
+    sync_menu(app, &ticker);
+}
+
+fn sync_menu(app: &AppHandle, ticker: &TickerConfig) {
+    let Some(menu) = app.try_state::<TickerMenu>() else {
+        return;
+    };
+    let _ = menu.show_item.set_checked(ticker.visible);
+    let _ = menu.click_through_item.set_checked(ticker.click_through);
+    for (corner, item) in &menu.corner_items {
+        let _ = item.set_checked(*corner == ticker.corner);
+    }
+}
+
+/// The tray's "Ticker" submenu. Registers the items so clicks and config
+/// changes can update their check marks.
+pub fn tray_submenu(app: &AppHandle) -> tauri::Result<Submenu<Wry>> {
+    let ticker = app.state::<DesktopConfigState>().snapshot().ticker;
+    let show_item = CheckMenuItem::with_id(app, MENU_SHOW_ID, "Show Ticker", true, ticker.visible, None::<&str>)?;
+    let click_through_item = CheckMenuItem::with_id(
+        app,
+        MENU_CLICK_THROUGH_ID,
+        "Click-Through",
+        true,
+        ticker.click_through,
+        None::<&str>,
+    )?;
+    let corner_items = CORNERS
+        .iter()
+        .map(|(corner, id, label)| {
+            CheckMenuItem::with_id(app, *id, *label, true, *corner == ticker.corner, None::<&str>)
+                .map(|item| (*corner, item))
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let corner_refs: Vec<&dyn IsMenuItem<Wry>> = corner_items.iter().map(|(_, item)| item as &dyn IsMenuItem<Wry>).collect();
+    let position = Submenu::with_items(app, "Position", true, &corner_refs)?;
+    let submenu = Submenu::with_items(
+        app,
+        "Ticker",
+        true,
+        &[&show_item, &click_through_item, &PredefinedMenuItem::separator(app)?, &position],
+    )?;
+    app.manage(TickerMenu {
+        show_item,
+        click_through_item,
+        corner_items,
+    });
+    Ok(submenu)
+}
+
+pub fn owns_menu_id(id: &str) -> bool {
+    id.starts_with(MENU_PREFIX)
+}
+
+pub fn handle_menu_event(app: &AppHandle, id: &str) {
+    match id {
+        MENU_SHOW_ID => update(app, |t| t.visible = !t.visible),
+        MENU_CLICK_THROUGH_ID => update(app, |t| t.click_through = !t.click_through),
+        _ => {
+            if let Some((corner, _, _)) = CORNERS.iter().find(|(_, corner_id, _)| *corner_id == id) {
+                let corner = *corner;
+                update(app, |t| t.corner = corner);
+            }
+        }
+    }
+}
+
+/// The ticker was moved (dragged, or by us). Once moves stop for
+/// `SNAP_DELAY`, snap it to the nearest corner and remember that corner.
+pub fn on_moved(app: &AppHandle, label: &str) {
+    if label != TICKER_WINDOW {
+        return;
+    }
+    let Some(state) = app.try_state::<TickerState>() else {
+        return;
+    };
+    let generation = state.moves.fetch_add(1, Ordering::SeqCst) + 1;
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(SNAP_DELAY);
+        if app.state::<TickerState>().moves.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        let handle = app.clone();
+        let _ = app.run_on_main_thread(move || settle(&handle));
+    });
+}
+
+fn settle(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(TICKER_WINDOW) else {
+        return;
+    };
+    let (Ok(position), Ok(size), Ok(Some(monitor))) =
+        (window.outer_position(), window.outer_size(), window.current_monitor())
+    else {
+        return;
+    };
+    let area = monitor.work_area();
+    let corner = nearest_corner(area.position, area.size, position, size);
+    snap_to(&window, corner);
+    let config = app.state::<DesktopConfigState>();
+    if config.snapshot().ticker.corner != corner {
+        match config.update(|c| c.ticker.corner = corner) {
+            Ok(saved) => sync_menu(app, &saved.ticker),
+            Err(err) => tracing::warn!(target: "app", "failed to save ticker corner: {err}"),
+        }
+    }
+}
+
+/// The window was closed some other way (e.g. Alt+F4): keep it closed.
+pub fn on_close_requested(app: &AppHandle, label: &str) {
+    if label == TICKER_WINDOW {
+        update(app, |t| t.visible = false);
+    }
+}
+
+/// Clicking the ticker brings up the dashboard.
+#[tauri::command]
+pub fn activate_ticker(webview: Webview, app: AppHandle) -> Result<(), String> {
+    crate::metrics::of(&webview).observe("activate_ticker", || {
+        crate::require_trusted_window(webview.label())?;
+        crate::tray::show_dashboard(&app);
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod ticker_tests {
+    use super::{corner_origin, nearest_corner};
+    use crate::config::TickerCorner;
+    use tauri::{PhysicalPosition, PhysicalSize};
+
+    // A 1920x1040 work area on a monitor to the right of the primary one.
+    const ORIGIN: PhysicalPosition<i32> = PhysicalPosition { x: 1920, y: 0 };
+    const AREA: PhysicalSize<u32> = PhysicalSize { width: 1920, height: 1040 };
+    const TICKER: PhysicalSize<u32> = PhysicalSize { width: 320, height: 36 };
+
+    #[test]
+    fn places_window_in_each_corner() {
+        let origin = |corner| corner_origin(ORIGIN, AREA, TICKER, corner, 12);
+        assert_eq!(origin(TickerCorner::TopLeft), PhysicalPosition::new(1932, 12));
+        assert_eq!(origin(TickerCorner::TopRight), PhysicalPosition::new(3508, 12));
+        assert_eq!(origin(TickerCorner::BottomLeft), PhysicalPosition::new(1932, 992));
+        assert_eq!(origin(TickerCorner::BottomRight), PhysicalPosition::new(3508, 992));
+    }
+
+    #[test]
+    fn snaps_to_nearest_corner() {
+        let nearest = |x, y| nearest_corner(ORIGIN, AREA, PhysicalPosition::new(x, y), TICKER);
+        assert_eq!(nearest(2000, 100), TickerCorner::TopLeft);
+        assert_eq!(nearest(3000, 400), TickerCorner::TopRight);
+        assert_eq!(nearest(1800, 900), TickerCorner::BottomLeft);
+        assert_eq!(nearest(3700, 1100), TickerCorner::BottomRight);
+    }
+}
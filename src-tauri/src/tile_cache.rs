@@ -0,0 +1,537 @@
+//! Offline map tile cache. On the desktop the map loads basemap tiles as
+//! `tiles://localhost/{z}/{x}/{y}` (`http://tiles.localhost/...` on Windows)
+//! rather than from the provider, and the protocol handler here serves them
+//! from a disk cache under the app cache directory. A tile that is missing,
+//! or older than `tiles.max_age_days`, is fetched from the provider in
+//! `tiles.url` while online; offline, cached tiles are served however old
+//! they are and missing ones come back empty (204), so the map still draws
+//! every area that was looked at before.
+//!
+//! The cache holds at most `tiles.max_bytes`, evicting the tiles fetched
+//! longest ago once it is full. Tiles are kept per provider URL, so changing
+//! the provider never mixes the two. `prefetch_tiles` downloads a region
+//! ahead of time, e.g. before going somewhere without connectivity,
+//! reporting progress as `tiles://prefetch`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+
+use futures_util::StreamExt;
+use serde::Serialize;
+use tauri::http::{header, Response, StatusCode, Uri};
+use tauri::{AppHandle, Emitter, Manager, UriSchemeContext, UriSchemeResponder, Webview, Wry};
+
+use crate::ais::BoundingBox;
+use crate::config::{DesktopConfigState, TileCacheConfig};
+
+pub const SCHEME: &str = "tiles";
+pub const PREFETCH_EVENT: &str = "tiles://prefetch";
+
+const TILES_DIR: &str = "tiles";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+const MAX_ZOOM: u8 = 22;
+const MAX_PREFETCH_TILES: usize = 20_000;
+const PREFETCH_CONCURRENCY: usize = 4;
+const PROGRESS_EVERY: usize = 50;
+const MIN_MAX_BYTES: u64 = 16 * 1024 * 1024;
+/// Web Mercator's latitude limit.
+const MAX_LATITUDE: f64 = 85.051_128_78;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Tile {
+    z: u8,
+    x: u32,
+    y: u32,
+}
+
+impl Tile {
+    fn new(z: u8, x: u32, y: u32) -> Option<Tile> {
+        (z <= MAX_ZOOM && x < 1 << z && y < 1 << z).then_some(Tile { z, x, y })
+    }
+
+    /// The last three segments of a `tiles:` URI's host and path, so both
+    /// `tiles://localhost/3/4/2` and `tiles://3/4/2` work. An extension on
+    /// `y` is ignored.
+    fn from_uri(uri: &Uri) -> Option<Tile> {
+        let segments: Vec<&str> = uri
+            .host()
+            .into_iter()
+            .chain(uri.path().split('/'))
+            .filter(|s| !s.is_empty())
+            .collect();
+        let [z, x, y] = segments.get(segments.len().checked_sub(3)?..)? else {
+            return None;
+        };
+        let y = y.split('.').next()?;
+        Tile::new(z.parse().ok()?, x.parse().ok()?, y.parse().ok()?)
+    }
+
+    fn url(self, config: &TileCacheConfig) -> String {
+        let subdomain = match config.subdomains.len() {
+            0 => "",
+            n => config.subdomains[(self.x + self.y) as usize % n].as_str(),
+        };
+        config
+            .url
+            .replace("{z}", &self.z.to_string())
+            .replace("{x}", &self.x.to_string())
+            .replace("{y}", &self.y.to_string())
+            .replace("{s}", subdomain)
+    }
+}
+
+fn tile_x(lon: f64, z: u8) -> u32 {
+    let n = f64::from(1u32 << z);
+    (((lon + 180.0) / 360.0 * n).floor() as u32).min((1 << z) - 1)
+}
+
+fn tile_y(lat: f64, z: u8) -> u32 {
+    let n = f64::from(1u32 << z);
+    let lat = lat.clamp(-MAX_LATITUDE, MAX_LATITUDE).to_radians();
+    let y = (1.0 - lat.tan().asinh() / std::f64::consts::PI) / 2.0 * n;
+    (y.floor().max(0.0) as u32).min((1 << z) - 1)
+}
+
+/// Every tile covering `b` from `min_zoom` to `max_zoom`, or `Err` past
+/// `MAX_PREFETCH_TILES`.
+fn tiles_in(b: &BoundingBox, min_zoom: u8, max_zoom: u8) -> Result<Vec<Tile>, String> {
+    let mut tiles = Vec::new();
+    for z in min_zoom..=max_zoom {
+        let (west, east) = (tile_x(b.west, z), tile_x(b.east, z));
+        // A box with west > east crosses the antimeridian.
+        let columns: Vec<u32> = if b.west <= b.east {
+            (west..=east).collect()
+        } else {
+            (west..1 << z).chain(0..=east).collect()
+        };
+        let rows = tile_y(b.north, z)..=tile_y(b.south, z);
+        if tiles.len() + columns.len() * rows.clone().count() > MAX_PREFETCH_TILES {
+            return Err(format!("The region needs more than {MAX_PREFETCH_TILES} tiles; zoom in or lower the max zoom"));
+        }
+        for x in columns {
+            tiles.extend(rows.clone().map(|y| Tile { z, x, y }));
+        }
+    }
+    Ok(tiles)
+}
+
+fn content_type(url: &str) -> &'static str {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    match path.rsplit('.').next().unwrap_or_default() {
+        "mvt" | "pbf" => "application/x-protobuf",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "webp" => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+/// FNV-1a, to name a provider's directory after its URL.
+fn provider_key(url: &str) -> String {
+    let hash = url
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, b| (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3));
+    format!("{hash:016x}")
+}
+
+#[derive(Default)]
+pub struct TileCacheState {
+    root: OnceLock<PathBuf>,
+    /// Bytes on disk, once counted at startup.
+    bytes: Mutex<Option<u64>>,
+    client: OnceLock<reqwest::Client>,
+    prefetching: AtomicBool,
+}
+
+impl TileCacheState {
+    fn client(&self) -> Result<&reqwest::Client, String> {
+        if let Some(client) = self.client.get() {
+            return Ok(client);
+        }
+        let client = reqwest::Client::builder()
+            .use_native_tls()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(crate::USER_AGENT)
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+        Ok(self.client.get_or_init(|| client))
+    }
+
+    fn path(&self, config: &TileCacheConfig, tile: Tile) -> Option<PathBuf> {
+        let root = self.root.get()?;
+        Some(
+            root.join(provider_key(&config.url))
+                .join(tile.z.to_string())
+                .join(tile.x.to_string())
+                .join(tile.y.to_string()),
+        )
+    }
+
+    /// Write a fetched tile and evict if that took the cache over `max_bytes`.
+    fn write(&self, path: &Path, bytes: &[u8], max_bytes: u64) {
+        let previous = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        let written = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(path, bytes));
+        if let Err(err) = written {
+            tracing::debug!(target: "app", path = %path.display(), "failed to cache tile: {err}");
+            return;
+        }
+        let over = {
+            let mut total = self.bytes.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(total) = total.as_mut() {
+                *total = (*total + bytes.len() as u64).saturating_sub(previous);
+            }
+            total.is_some_and(|total| total > max_bytes)
+        };
+        if over {
+            self.evict(max_bytes);
+        }
+    }
+
+    /// Delete the oldest tiles until the cache is down to 90% of `max_bytes`.
+    fn evict(&self, max_bytes: u64) {
+        let Some(root) = self.root.get() else {
+            return;
+        };
+        let mut files = Vec::new();
+        collect_files(root, &mut files);
+        let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+        let target = max_bytes / 10 * 9;
+        if total > max_bytes {
+            files.sort_by_key(|(modified, _, _)| *modified);
+            let mut evicted = 0;
+            for (_, len, path) in files {
+                if total <= target {
+                    break;
+                }
+                if fs::remove_file(&path).is_ok() {
+                    total -= len;
+                    evicted += 1;
+                }
+            }
+            tracing::info!(target: "app", evicted, bytes = total, "tile cache trimmed");
+        }
+        *self.bytes.lock().unwrap_or_else(|e| e.into_inner()) = Some(total);
+    }
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<(SystemTime, u64, PathBuf)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            collect_files(&entry.path(), files);
+        } else {
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            files.push((modified, metadata.len(), entry.path()));
+        }
+    }
+}
+
+/// The cached copy and how old it is.
+fn read_cached(path: &Path) -> Option<(Vec<u8>, Duration)> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    let bytes = fs::read(path).ok()?;
+    Some((bytes, modified.elapsed().unwrap_or_default()))
+}
+
+/// Fetch from the provider; empty when it has no tile there.
+async fn fetch(state: &TileCacheState, url: &str) -> Result<Vec<u8>, String> {
+    let response = state.client()?.get(url).send().await.map_err(|e| e.to_string())?;
+    match response.status() {
+        StatusCode::NO_CONTENT | StatusCode::NOT_FOUND => Ok(Vec::new()),
+        status if status.is_success() => response.bytes().await.map(|b| b.to_vec()).map_err(|e| e.to_string()),
+        status => Err(format!("HTTP {status}")),
+    }
+}
+
+/// A tile's bytes (empty when the provider has none there) and whether they
+/// were just fetched; `None` when it is neither cached nor fetchable.
+async fn load(app: &AppHandle, tile: Tile, config: &TileCacheConfig) -> Option<(Vec<u8>, bool)> {
+    let state = app.state::<TileCacheState>();
+    let path = state.path(config, tile);
+    let cached = match path.clone() {
+        Some(path) => crate::runtime::blocking(move || Ok(read_cached(&path))).await.ok().flatten(),
+        None => None,
+    };
+    let max_age = Duration::from_secs(config.max_age_days * 24 * 60 * 60);
+    if let Some((bytes, age)) = &cached {
+        if *age < max_age || !crate::network::is_online(app) {
+            return Some((bytes.clone(), false));
+        }
+    }
+    match fetch(&state, &tile.url(config)).await {
+        Ok(bytes) => {
+            if let Some(path) = path {
+                let (app, bytes, max_bytes) = (app.clone(), bytes.clone(), config.max_bytes);
+                tauri::async_runtime::spawn_blocking(move || {
+                    app.state::<TileCacheState>().write(&path, &bytes, max_bytes)
+                });
+            }
+            Some((bytes, true))
+        }
+        Err(err) => {
+            tracing::debug!(target: "app", z = tile.z, x = tile.x, y = tile.y, "tile fetch failed: {err}");
+            cached.map(|(bytes, _)| (bytes, false))
+        }
+    }
+}
+
+async fn respond(app: &AppHandle, uri: &Uri) -> Response<Vec<u8>> {
+    let builder = Response::builder().header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*");
+    let Some(tile) = Tile::from_uri(uri) else {
+        return builder.status(StatusCode::BAD_REQUEST).body(Vec::new()).unwrap_or_default();
+    };
+    let config = app.state::<DesktopConfigState>().snapshot().tiles;
+    match load(app, tile, &config).await {
+        Some((bytes, _)) if !bytes.is_empty() => builder
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, content_type(&config.url))
+            .body(bytes)
+            .unwrap_or_default(),
+        _ => builder.status(StatusCode::NO_CONTENT).body(Vec::new()).unwrap_or_default(),
+    }
+}
+
+/// Handler for the `tiles` URI scheme.
+pub fn handle(ctx: UriSchemeContext<'_, Wry>, request: tauri::http::Request<Vec<u8>>, responder: UriSchemeResponder) {
+    let app = ctx.app_handle().clone();
+    tauri::async_runtime::spawn(async move {
+        responder.respond(respond(&app, request.uri()).await);
+    });
+}
+
+/// Resolve the cache directory, then count what is in it (trimming it if the
+/// limit went down since the last run).
+pub fn start(app: &AppHandle) {
+    let root = match app.path().app_cache_dir() {
+        Ok(dir) => dir.join(TILES_DIR),
+        Err(err) => {
+            tracing::warn!(target: "app", "map tiles will not be cached: {err}");
+            return;
+        }
+    };
+    if let Err(err) = fs::create_dir_all(&root) {
+        tracing::warn!(target: "app", path = %root.display(), "map tiles will not be cached: {err}");
+        return;
+    }
+    let _ = app.state::<TileCacheState>().root.set(root);
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let max_bytes = app.state::<DesktopConfigState>().snapshot().tiles.max_bytes;
+        app.state::<TileCacheState>().evict(max_bytes);
+    });
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TileCacheStatus {
+    /// Where the map loads tiles from: `{baseUrl}/{z}/{x}/{y}`.
+    pub base_url: &'static str,
+    /// The provider URL template the cache fronts.
+    pub url: String,
+    /// `None` until the cache has been counted.
+    pub bytes: Option<u64>,
+    pub max_bytes: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrefetchProgress {
+    pub total: usize,
+    pub done: usize,
+    /// Tiles downloaded, as opposed to already cached.
+    pub fetched: usize,
+    pub failed: usize,
+}
+
+#[tauri::command]
+pub fn get_tile_cache_status(
+    webview: Webview,
+    config: tauri::State<'_, DesktopConfigState>,
+    state: tauri::State<'_, TileCacheState>,
+) -> Result<TileCacheStatus, String> {
+    crate::metrics::of(&webview).observe("get_tile_cache_status", || {
+        crate::require_trusted_window(webview.label())?;
+        let tiles = config.snapshot().tiles;
+        Ok(TileCacheStatus {
+            base_url: if cfg!(windows) { "http://tiles.localhost" } else { "tiles://localhost" },
+            url: tiles.url,
+            bytes: *state.bytes.lock().unwrap_or_else(|e| e.into_inner()),
+            max_bytes: tiles.max_bytes,
+        })
+    })
+}
+
+/// `tiles` with its URL trimmed, or why it cannot be used.
+pub fn validate(tiles: TileCacheConfig) -> Result<TileCacheConfig, String> {
+    let tiles = TileCacheConfig {
+        url: tiles.url.trim().to_string(),
+        ..tiles
+    };
+    if !(tiles.url.starts_with("https://") || tiles.url.starts_with("http://")) {
+        return Err("Tile URL must be http(s)".to_string());
+    }
+    if !["{z}", "{x}", "{y}"].iter().all(|p| tiles.url.contains(p)) {
+        return Err("Tile URL needs {z}, {x} and {y}".to_string());
+    }
+    if tiles.url.contains("{s}") && tiles.subdomains.is_empty() {
+        return Err("Tile URL uses {s} but no subdomains are set".to_string());
+    }
+    if tiles.max_bytes < MIN_MAX_BYTES {
+        return Err(format!("The cache needs at least {} MB", MIN_MAX_BYTES / 1024 / 1024));
+    }
+    Ok(tiles)
+}
+
+/// Change the provider or limits. A lower `max_bytes` trims the cache.
+#[tauri::command]
+pub async fn set_tile_cache_config(
+    webview: Webview,
+    app: AppHandle,
+    tiles: TileCacheConfig,
+) -> Result<TileCacheConfig, String> {
+    crate::metrics::of(&webview)
+        .observe_async("set_tile_cache_config", async move {
+            crate::require_trusted_window(webview.label())?;
+            let tiles = validate(tiles)?;
+            app.state::<DesktopConfigState>().update(|c| c.tiles = tiles.clone())?;
+            let max_bytes = tiles.max_bytes;
+            crate::runtime::blocking(move || {
+                app.state::<TileCacheState>().evict(max_bytes);
+                Ok(())
+            })
+            .await?;
+            Ok(tiles)
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn clear_tile_cache(webview: Webview, app: AppHandle) -> Result<(), String> {
+    crate::metrics::of(&webview)
+        .observe_async("clear_tile_cache", async move {
+            crate::require_trusted_window(webview.label())?;
+            crate::runtime::blocking(move || {
+                let state = app.state::<TileCacheState>();
+                let Some(root) = state.root.get() else {
+                    return Ok(());
+                };
+                fs::remove_dir_all(root)
+                    .and_then(|_| fs::create_dir_all(root))
+                    .map_err(|e| format!("Failed to clear tile cache: {e}"))?;
+                *state.bytes.lock().unwrap_or_else(|e| e.into_inner()) = Some(0);
+                Ok(())
+            })
+            .await
+        })
+        .await
+}
+
+/// Download every tile of a region that is not cached yet, so it can be
+/// viewed offline. Resolves when done.
+#[tauri::command]
+pub async fn prefetch_tiles(
+    webview: Webview,
+    app: AppHandle,
+    bounding_box: BoundingBox,
+    min_zoom: u8,
+    max_zoom: u8,
+) -> Result<PrefetchProgress, String> {
+    crate::metrics::of(&webview)
+        .observe_async("prefetch_tiles", async move {
+            crate::require_trusted_window(webview.label())?;
+            if !bounding_box.is_valid() {
+                return Err("Invalid bounding box".to_string());
+            }
+            if min_zoom > max_zoom || max_zoom > MAX_ZOOM {
+                return Err(format!("Zoom levels must be 0-{MAX_ZOOM}, min first"));
+            }
+            if !crate::network::is_online(&app) {
+                return Err("Tiles cannot be downloaded while offline".to_string());
+            }
+            let tiles = tiles_in(&bounding_box, min_zoom, max_zoom)?;
+            let state = app.state::<TileCacheState>();
+            if state.prefetching.swap(true, Ordering::SeqCst) {
+                return Err("A region is already downloading".to_string());
+            }
+            let progress = prefetch(&app, tiles).await;
+            state.prefetching.store(false, Ordering::SeqCst);
+            tracing::info!(target: "app", fetched = progress.fetched, failed = progress.failed, "tile prefetch finished");
+            Ok(progress)
+        })
+        .await
+}
+
+async fn prefetch(app: &AppHandle, tiles: Vec<Tile>) -> PrefetchProgress {
+    let config = app.state::<DesktopConfigState>().snapshot().tiles;
+    let mut progress = PrefetchProgress {
+        total: tiles.len(),
+        ..PrefetchProgress::default()
+    };
+    let mut results = futures_util::stream::iter(tiles)
+        .map(|tile| load(app, tile, &config))
+        .buffer_unordered(PREFETCH_CONCURRENCY);
+    while let Some(result) = results.next().await {
+        progress.done += 1;
+        match result {
+            Some((_, true)) => progress.fetched += 1,
+            Some(_) => {}
+            None => progress.failed += 1,
+        }
+        if progress.done.is_multiple_of(PROGRESS_EVERY) || progress.done == progress.total {
+            let _ = app.emit(PREFETCH_EVENT, &progress);
+        }
+    }
+    progress
+}
+
+#[cfg(test)]
+mod tile_cache_tests {
+    use super::*;
+
+    #[test]
+    fn parses_tile_uris() {
+        let parse = |uri: &str| Tile::from_uri(&uri.parse().unwrap());
+        assert_eq!(parse("tiles://localhost/3/4/2"), Some(Tile { z: 3, x: 4, y: 2 }));
+        assert_eq!(parse("http://tiles.localhost/3/4/2.mvt"), Some(Tile { z: 3, x: 4, y: 2 }));
+        assert_eq!(parse("tiles://3/4/2"), Some(Tile { z: 3, x: 4, y: 2 }));
+        assert_eq!(parse("tiles://localhost/3/8/2"), None);
+        assert_eq!(parse("tiles://localhost/4/2"), None);
+        assert_eq!(parse("tiles://localhost/23/0/0"), None);
+    }
+
+    #[test]
+    fn expands_the_provider_template() {
+        let config = TileCacheConfig::default();
+        let tile = Tile { z: 5, x: 17, y: 10 };
+        assert_eq!(
+            tile.url(&config),
+            "https://tiles-d.basemaps.cartocdn.com/vectortiles/carto.streets/v1/5/17/10.mvt"
+        );
+        assert_eq!(content_type(&config.url), "application/x-protobuf");
+        assert_eq!(content_type("https://tile.example.com/{z}/{x}/{y}.png?key=1"), "image/png");
+    }
+
+    #[test]
+    fn covers_regions_with_tiles() {
+        let world = BoundingBox { south: -90.0, west: -180.0, north: 90.0, east: 180.0 };
+        assert_eq!(tiles_in(&world, 0, 2).unwrap().len(), 1 + 4 + 16);
+        // London at z10.
+        assert_eq!((tile_x(-0.1276, 10), tile_y(51.5072, 10)), (511, 340));
+        let pacific = BoundingBox { south: -10.0, west: 170.0, north: 10.0, east: -170.0 };
+        let tiles = tiles_in(&pacific, 3, 3).unwrap();
+        let columns: Vec<u32> = tiles.iter().map(|t| t.x).collect();
+        assert_eq!(columns, [7, 7, 0, 0]);
+        assert!(tiles_in(&world, 0, 12).is_err());
+    }
+}
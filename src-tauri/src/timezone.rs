@@ -0,0 +1,109 @@
+//! System timezone tracking. Laptops left running cross timezones and DST
+//! boundaries, and webviews do not always notice: timestamps then skew by
+//! the difference. A background thread re-reads the IANA zone and current
+//! UTC offset and broadcasts `app://timezone-changed` when either changes,
+//! so the dashboard can re-render times against the new zone.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{Local, Offset};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+pub const CHANGED_EVENT: &str = "app://timezone-changed";
+
+/// DST transitions land on the minute; half a minute keeps the skew short.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SystemTimezone {
+    /// IANA name, e.g. `Europe/Berlin`, when the OS reports one.
+    pub name: Option<String>,
+    /// Minutes east of UTC, including any DST shift in effect.
+    pub utc_offset_mins: i32,
+    /// The same offset as `+01:00`.
+    pub utc_offset: String,
+}
+
+#[derive(Default)]
+pub struct TimezoneState {
+    current: Mutex<Option<SystemTimezone>>,
+}
+
+fn offset_label(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let minutes = minutes.unsigned_abs();
+    format!("{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+fn read() -> SystemTimezone {
+    let utc_offset_mins = Local::now().offset().fix().local_minus_utc() / 60;
+    SystemTimezone {
+        name: iana_time_zone::get_timezone().ok().filter(|name| !name.is_empty()),
+        utc_offset_mins,
+        utc_offset: offset_label(utc_offset_mins),
+    }
+}
+
+/// The current system timezone, as last read by the monitor thread.
+pub fn current(app: &AppHandle) -> SystemTimezone {
+    app.try_state::<TimezoneState>()
+        .and_then(|state| state.current.lock().unwrap_or_else(|e| e.into_inner()).clone())
+        .unwrap_or_else(read)
+}
+
+fn run(app: AppHandle) {
+    let state = app.state::<TimezoneState>();
+    loop {
+        let timezone = read();
+        let previous = state
+            .current
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .replace(timezone.clone());
+        if let Some(previous) = previous.filter(|previous| *previous != timezone) {
+            tracing::info!(
+                target: "app",
+                from = previous.name.as_deref().unwrap_or(&previous.utc_offset),
+                to = timezone.name.as_deref().unwrap_or(&timezone.utc_offset),
+                utc_offset = %timezone.utc_offset,
+                "system timezone changed"
+            );
+            let _ = app.emit(CHANGED_EVENT, timezone);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Start the timezone monitor thread.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    if let Err(e) = std::thread::Builder::new()
+        .name("timezone-monitor".into())
+        .spawn(move || run(app))
+    {
+        tracing::warn!(target: "app", "failed to start timezone monitor: {e}");
+    }
+}
+
+#[tauri::command]
+pub fn get_timezone(webview: Webview, app: AppHandle) -> Result<SystemTimezone, String> {
+    crate::metrics::of(&webview).observe("get_timezone", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(current(&app))
+    })
+}
+
+#[cfg(test)]
+mod timezone_tests {
+    use super::*;
+
+    #[test]
+    fn formats_utc_offsets() {
+        assert_eq!(offset_label(0), "+00:00");
+        assert_eq!(offset_label(330), "+05:30");
+        assert_eq!(offset_label(-210), "-03:30");
+        assert_eq!(offset_label(-600), "-10:00");
+    }
+}
@@ -0,0 +1,273 @@
+//! System tray icon. The tooltip and a disabled first menu entry show the
+//! local API sidecar's status; the rest of the menu offers Show Dashboard,
+//! Open Settings, Restart Local API, Read Latest Digest (see `speech`), and
+//! Quit. A left click shows the dashboard. Sidecar lifecycle code reports
+//! changes through `set_status`.
+//!
+//! The menu also carries the "Close to Tray" / "Minimize to Tray"
+//! preferences (`TrayConfig`). While the tray exists, closing or minimizing
+//! the main window can hide it instead, keeping the sidecar and feeds alive;
+//! the first time that happens a notification explains where the app went.
+//! A "Ticker" submenu (see `ticker`) controls the mini ticker window, and
+//! "Keep Awake" (see `keep_awake`) holds off system sleep.
+
+use std::sync::Mutex;
+
+use tauri::menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::config::{DesktopConfigState, TrayConfig};
+
+const TRAY_ID: &str = "main-tray";
+const TRAY_STATUS_ID: &str = "tray.status";
+const TRAY_SHOW_ID: &str = "tray.show";
+const TRAY_SETTINGS_ID: &str = "tray.settings";
+const TRAY_RESTART_API_ID: &str = "tray.restart-api";
+const TRAY_SPEAK_DIGEST_ID: &str = "tray.speak-digest";
+const TRAY_CLOSE_TO_TRAY_ID: &str = "tray.close-to-tray";
+const TRAY_MINIMIZE_TO_TRAY_ID: &str = "tray.minimize-to-tray";
+const TRAY_QUIT_ID: &str = "tray.quit";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SidecarStatus {
+    Starting,
+    Running { port: u16 },
+    Stopped,
+    Failed,
+    Crashed,
+}
+
+impl SidecarStatus {
+    fn label(self) -> String {
+        match self {
+            SidecarStatus::Starting => "Local API: starting…".to_string(),
+            SidecarStatus::Running { port } => format!("Local API: running on port {port}"),
+            SidecarStatus::Stopped => "Local API: stopped".to_string(),
+            SidecarStatus::Failed => "Local API: failed to start".to_string(),
+            SidecarStatus::Crashed => "Local API: exited unexpectedly".to_string(),
+        }
+    }
+}
+
+/// Managed once the tray is built; absent if the platform has no tray.
+pub struct TrayState {
+    status: Mutex<SidecarStatus>,
+    status_item: MenuItem<Wry>,
+    close_to_tray_item: CheckMenuItem<Wry>,
+    minimize_to_tray_item: CheckMenuItem<Wry>,
+}
+
+/// Show, unminimize, and focus the main window.
+pub fn show_dashboard(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        TRAY_SHOW_ID => show_dashboard(app),
+        TRAY_SETTINGS_ID => {
+            if let Err(err) = crate::open_settings_window(app, None) {
+                tracing::error!(target: "menu", "tray settings failed: {err}");
+            }
+        }
+        TRAY_RESTART_API_ID => {
+            let app = app.clone();
+            // Restarting waits on the sidecar's port file; keep it off the
+            // event loop.
+            std::thread::spawn(move || {
+                if let Err(err) = crate::restart_local_api(&app) {
+                    tracing::error!(target: "sidecar", "local API restart failed: {err}");
+                }
+            });
+        }
+        TRAY_SPEAK_DIGEST_ID => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(err) = crate::speech::read_digest(&app).await {
+                    tracing::warn!(target: "menu", "reading digest failed: {err}");
+                }
+            });
+        }
+        TRAY_CLOSE_TO_TRAY_ID => toggle_preference(app, |c| &mut c.close_to_tray, |s| &s.close_to_tray_item),
+        TRAY_MINIMIZE_TO_TRAY_ID => {
+            toggle_preference(app, |c| &mut c.minimize_to_tray, |s| &s.minimize_to_tray_item)
+        }
+        TRAY_QUIT_ID => app.exit(0),
+        id if crate::ticker::owns_menu_id(id) => crate::ticker::handle_menu_event(app, id),
+        id if crate::keep_awake::owns_menu_id(id) => crate::keep_awake::handle_menu_event(app),
+        _ => {}
+    }
+}
+
+/// Flip a boolean `TrayConfig` preference, persist it, and sync the check
+/// mark to what was actually saved.
+fn toggle_preference(
+    app: &AppHandle,
+    field: impl Fn(&mut TrayConfig) -> &mut bool,
+    item: impl Fn(&TrayState) -> &CheckMenuItem<Wry>,
+) {
+    let config = app.state::<DesktopConfigState>();
+    let enabled = match config.update(|c| {
+        let value = field(&mut c.tray);
+        *value = !*value;
+    }) {
+        Ok(mut saved) => *field(&mut saved.tray),
+        Err(err) => {
+            tracing::error!(target: "menu", "failed to save tray preference: {err}");
+            *field(&mut config.snapshot().tray)
+        }
+    };
+    if let Some(state) = app.try_state::<TrayState>() {
+        let _ = item(&state).set_checked(enabled);
+    }
+}
+
+fn handle_tray_event(tray: &TrayIcon, event: TrayIconEvent) {
+    if let TrayIconEvent::Click {
+        button: MouseButton::Left,
+        button_state: MouseButtonState::Up,
+        ..
+    } = event
+    {
+        show_dashboard(tray.app_handle());
+    }
+}
+
+/// Build the tray icon and register `TrayState`. Call from `setup` before
+/// the sidecar starts so its first status change is shown.
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+    let status = SidecarStatus::Starting;
+    let status_item = MenuItem::with_id(app, TRAY_STATUS_ID, status.label(), false, None::<&str>)?;
+    let show_item = MenuItem::with_id(app, TRAY_SHOW_ID, "Show Dashboard", true, None::<&str>)?;
+    let settings_item = MenuItem::with_id(app, TRAY_SETTINGS_ID, "Open Settings", true, None::<&str>)?;
+    let restart_item = MenuItem::with_id(app, TRAY_RESTART_API_ID, "Restart Local API", true, None::<&str>)?;
+    let speak_digest_item = MenuItem::with_id(app, TRAY_SPEAK_DIGEST_ID, "Read Latest Digest", true, None::<&str>)?;
+    let prefs = app.state::<DesktopConfigState>().snapshot().tray;
+    let close_to_tray_item = CheckMenuItem::with_id(
+        app,
+        TRAY_CLOSE_TO_TRAY_ID,
+        "Close to Tray",
+        true,
+        prefs.close_to_tray,
+        None::<&str>,
+    )?;
+    let minimize_to_tray_item = CheckMenuItem::with_id(
+        app,
+        TRAY_MINIMIZE_TO_TRAY_ID,
+        "Minimize to Tray",
+        true,
+        prefs.minimize_to_tray,
+        None::<&str>,
+    )?;
+    let ticker_menu = crate::ticker::tray_submenu(app)?;
+    let keep_awake_item = crate::keep_awake::tray_item(app)?;
+    let quit_item = MenuItem::with_id(app, TRAY_QUIT_ID, "Quit", true, None::<&str>)?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &status_item,
+            &PredefinedMenuItem::separator(app)?,
+            &show_item,
+            &settings_item,
+            &restart_item,
+            &speak_digest_item,
+            &PredefinedMenuItem::separator(app)?,
+            &ticker_menu,
+            &keep_awake_item,
+            &PredefinedMenuItem::separator(app)?,
+            #[cfg(not(target_os = "macos"))]
+            &close_to_tray_item,
+            &minimize_to_tray_item,
+            &PredefinedMenuItem::separator(app)?,
+            &quit_item,
+        ],
+    )?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip(format!("World Monitor — {}", status.label()))
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(handle_menu_event)
+        .on_tray_icon_event(handle_tray_event);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    app.manage(TrayState {
+        status: Mutex::new(status),
+        status_item,
+        close_to_tray_item,
+        minimize_to_tray_item,
+    });
+    Ok(())
+}
+
+/// Whether closing the main window should hide it instead of exiting. Never
+/// true without a tray, which would leave the app unreachable.
+#[cfg(not(target_os = "macos"))]
+pub fn closes_to_tray(app: &AppHandle) -> bool {
+    app.try_state::<TrayState>().is_some() && app.state::<DesktopConfigState>().snapshot().tray.close_to_tray
+}
+
+/// The tray icon, if the platform has one.
+pub fn icon(app: &AppHandle) -> Option<TrayIcon> {
+    app.tray_by_id(TRAY_ID)
+}
+
+/// Whether the tray icon was created, i.e. a hidden dashboard can be reopened.
+pub fn is_available(app: &AppHandle) -> bool {
+    app.try_state::<TrayState>().is_some()
+}
+
+/// Whether minimizing the main window should hide it to the tray.
+pub fn minimizes_to_tray(app: &AppHandle) -> bool {
+    app.try_state::<TrayState>().is_some() && app.state::<DesktopConfigState>().snapshot().tray.minimize_to_tray
+}
+
+/// Hide the main window, explaining the first time that the app keeps running
+/// in the tray.
+pub fn hide_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.hide();
+    }
+    let config = app.state::<DesktopConfigState>();
+    if config.snapshot().tray.hide_notice_shown {
+        return;
+    }
+    let shown = app
+        .notification()
+        .builder()
+        .title("World Monitor is still running")
+        .body("Feeds keep updating in the background. Use the tray icon to reopen the dashboard, or choose Quit from its menu to exit.")
+        .show();
+    if let Err(err) = shown {
+        tracing::warn!(target: "app", "failed to show tray notice: {err}");
+    }
+    if let Err(err) = config.update(|c| c.tray.hide_notice_shown = true) {
+        tracing::warn!(target: "app", "{err}");
+    }
+}
+
+/// Reflect a sidecar status change in the tray menu and tooltip.
+pub fn set_status(app: &AppHandle, status: SidecarStatus) {
+    let Some(state) = app.try_state::<TrayState>() else {
+        return;
+    };
+    let mut current = state.status.lock().unwrap_or_else(|e| e.into_inner());
+    if *current == status {
+        return;
+    }
+    *current = status;
+    let label = status.label();
+    let _ = state.status_item.set_text(&label);
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_tooltip(Some(format!("World Monitor — {label}")));
+    }
+}
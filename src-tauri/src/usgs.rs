@@ -0,0 +1,365 @@
+//! USGS earthquakes polled into the local `store`, so the seismic panel
+//! works offline. With `usgs.enabled` set (the default), the worker fetches
+//! the configured summary feed (`significant`, `4.5` or `all`) every
+//! `UPDATE_INTERVAL`: the past week's on the first poll, to fill in after
+//! the app was closed, and the past day's after that. Quakes below
+//! `usgs.min_magnitude` or outside `usgs.bounding_boxes` are skipped.
+//!
+//! USGS revises magnitudes and locations as more stations report, so every
+//! poll replaces the stored copies; quakes new to the store go out in one
+//! `usgs://quakes` batch. Those that happened within `NOTIFY_WITHIN` go to
+//! the alert engine's `Hazard` rules, and those of at least
+//! `usgs.notify_magnitude` raise a notification (critical from M7 or with a
+//! tsunami flag). `query_earthquakes` reads the store, shaped like the
+//! seismology service's `Earthquake`. Quakes older than
+//! `RETENTION_DAYS` are pruned.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+use crate::config::{DesktopConfigState, UsgsConfig, UsgsFeed};
+use crate::hazards::HazardEvent;
+use crate::notifications::NotificationUrgency;
+use crate::store::{EventStore, QuakeQuery, QuakeRow};
+
+pub const QUAKES_EVENT: &str = "usgs://quakes";
+
+const FEED_URL: &str = "https://earthquake.usgs.gov/earthquakes/feed/v1.0/summary";
+
+const UPDATE_INTERVAL: Duration = Duration::from_secs(2 * 60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const ERROR_RETRY: Duration = Duration::from_secs(5 * 60);
+const NOTIFY_WITHIN: Duration = Duration::from_secs(60 * 60);
+const CRITICAL_MAGNITUDE: f64 = 7.0;
+const RETENTION_DAYS: u64 = 30;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct UsgsStatus {
+    pub enabled: bool,
+    pub feed: Option<UsgsFeed>,
+    /// When the feed was last fetched, in milliseconds since the epoch.
+    pub last_update: Option<i64>,
+    pub stored_quakes: usize,
+    /// New quakes from the last poll.
+    pub added: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct UsgsState {
+    status: Mutex<UsgsStatus>,
+}
+
+fn update_status(app: &AppHandle, f: impl FnOnce(&mut UsgsStatus)) {
+    let state = app.state::<UsgsState>();
+    f(&mut state.status.lock().unwrap_or_else(|e| e.into_inner()));
+}
+
+#[derive(Deserialize)]
+struct Feed {
+    features: Vec<Feature>,
+}
+
+#[derive(Deserialize)]
+struct Feature {
+    id: String,
+    properties: Properties,
+    geometry: Option<Geometry>,
+}
+
+#[derive(Deserialize)]
+struct Geometry {
+    /// Longitude, latitude and depth in kilometres.
+    coordinates: Vec<Option<f64>>,
+}
+
+#[derive(Deserialize)]
+struct Properties {
+    mag: Option<f64>,
+    place: Option<String>,
+    time: Option<i64>,
+    updated: Option<i64>,
+    url: Option<String>,
+    tsunami: Option<u8>,
+    /// PAGER alert level: green, yellow, orange or red.
+    alert: Option<String>,
+    sig: Option<u32>,
+    #[serde(rename = "type")]
+    event_type: Option<String>,
+}
+
+/// The quakes in a summary feed that pass the configured filters.
+fn parse_feed(text: &str, config: &UsgsConfig) -> Result<Vec<QuakeRow>, String> {
+    let feed: Feed = serde_json::from_str(text).map_err(|e| format!("invalid feed: {e}"))?;
+    Ok(feed
+        .features
+        .into_iter()
+        .filter_map(|feature| {
+            let coordinates = feature.geometry?.coordinates;
+            let (lon, lat) = (coordinates.first().copied()??, coordinates.get(1).copied()??);
+            let depth = coordinates.get(2).copied().flatten().unwrap_or(0.0);
+            let p = feature.properties;
+            let (magnitude, time) = (p.mag?, p.time?);
+            if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+                return None;
+            }
+            if config.min_magnitude.is_some_and(|min| magnitude < min) {
+                return None;
+            }
+            if !config.bounding_boxes.is_empty() && !config.bounding_boxes.iter().any(|b| b.contains(lat, lon)) {
+                return None;
+            }
+            Some(QuakeRow {
+                data: json!({
+                    "id": feature.id,
+                    "place": p.place.unwrap_or_default(),
+                    "magnitude": magnitude,
+                    "depthKm": depth,
+                    "location": { "latitude": lat, "longitude": lon },
+                    "occurredAt": time,
+                    "updatedAt": p.updated,
+                    "sourceUrl": p.url.unwrap_or_default(),
+                    "tsunami": p.tsunami == Some(1),
+                    "alert": p.alert,
+                    "significance": p.sig,
+                    "eventType": p.event_type.unwrap_or_else(|| "earthquake".to_string()),
+                }),
+                id: feature.id,
+                time,
+                magnitude,
+                lat,
+                lon,
+            })
+        })
+        .collect())
+}
+
+/// Whether a newly stored quake is worth a notification at `now`
+/// (milliseconds), and how urgent it is.
+fn notification(quake: &QuakeRow, config: &UsgsConfig, now: i64) -> Option<NotificationUrgency> {
+    let threshold = config.notify_magnitude?;
+    if quake.magnitude < threshold || now - quake.time > NOTIFY_WITHIN.as_millis() as i64 {
+        return None;
+    }
+    let tsunami = quake.data["tsunami"].as_bool().unwrap_or(false);
+    Some(if quake.magnitude >= CRITICAL_MAGNITUDE || tsunami {
+        NotificationUrgency::Critical
+    } else {
+        NotificationUrgency::Normal
+    })
+}
+
+fn notify(app: &AppHandle, quake: &QuakeRow, urgency: NotificationUrgency) {
+    if crate::digest::holds(app, urgency) {
+        return;
+    }
+    let title = format!("M{:.1} earthquake", quake.magnitude);
+    let mut body = quake.data["place"].as_str().unwrap_or_default().to_string();
+    if quake.data["tsunami"].as_bool().unwrap_or(false) {
+        body.push_str(" — tsunami possible");
+    }
+    let action = format!("quake:{}", quake.id);
+    if let Err(err) = crate::notifications::send(app, "main", &title, &body, urgency, Some(&action)) {
+        tracing::warn!(target: "app", "earthquake notification failed: {err}");
+    }
+}
+
+/// One poll of the feed covering `window` (`day` or `week`).
+async fn update(app: &AppHandle, client: &reqwest::Client, config: &UsgsConfig, window: &str) -> Result<(), String> {
+    let url = format!("{FEED_URL}/{}_{window}.geojson", config.feed.as_str());
+    let text = client
+        .get(&url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("request failed: {e}"))?
+        .text()
+        .await
+        .map_err(|e| format!("request failed: {e}"))?;
+    let quakes = parse_feed(&text, config)?;
+
+    let handle = app.clone();
+    let (added, stored) = crate::runtime::blocking(move || {
+        let store = handle.state::<EventStore>();
+        let added = store.upsert_quakes(&quakes)?;
+        let cutoff = crate::unix_timestamp_secs().saturating_sub(RETENTION_DAYS * 24 * 60 * 60) as i64 * 1000;
+        store.prune_quakes(cutoff)?;
+        let added: Vec<QuakeRow> = added.into_iter().map(|i| quakes[i].clone()).collect();
+        Ok::<_, String>((added, store.count_quakes()?))
+    })
+    .await?;
+
+    let now = chrono::Utc::now().timestamp_millis();
+    tracing::debug!(target: "app", added = added.len(), "USGS feed polled");
+    update_status(app, |status| {
+        status.last_update = Some(now);
+        status.added = added.len();
+        status.stored_quakes = stored;
+    });
+    for quake in &added {
+        if let Some(urgency) = notification(quake, config, now) {
+            notify(app, quake, urgency);
+        }
+    }
+    let recent: Vec<HazardEvent> = added
+        .iter()
+        .filter(|quake| now - quake.time <= NOTIFY_WITHIN.as_millis() as i64)
+        .map(HazardEvent::quake)
+        .collect();
+    crate::alerts::observe_hazards(app, &recent);
+    if !added.is_empty() {
+        let _ = app.emit(QUAKES_EVENT, added.into_iter().map(|q| q.data).collect::<Vec<Value>>());
+    }
+    Ok(())
+}
+
+struct Poller {
+    app: AppHandle,
+    client: reqwest::Client,
+    config: UsgsConfig,
+    /// The feed's time window: a week to backfill, then a day.
+    window: &'static str,
+}
+
+impl crate::poll::Poller for Poller {
+    async fn round(&mut self) -> Duration {
+        match update(&self.app, &self.client, &self.config, self.window).await {
+            Ok(()) => {
+                update_status(&self.app, |status| status.error = None);
+                self.window = "day";
+                UPDATE_INTERVAL
+            }
+            Err(err) => {
+                tracing::warn!(target: "app", "USGS update failed: {err}");
+                update_status(&self.app, |status| status.error = Some(err));
+                ERROR_RETRY
+            }
+        }
+    }
+}
+
+async fn run(app: AppHandle, config: UsgsConfig) {
+    let client = match reqwest::Client::builder().use_native_tls().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!(target: "app", "USGS polling unavailable: {err}");
+            return;
+        }
+    };
+    let poller = Poller {
+        app: app.clone(),
+        client,
+        config,
+        window: "week",
+    };
+    crate::poll::run(&app, poller).await;
+}
+
+/// Start polling if `usgs.enabled` is set.
+pub fn start(app: &AppHandle) {
+    let config = app.state::<DesktopConfigState>().snapshot().usgs;
+    update_status(app, |status| {
+        status.enabled = config.enabled;
+        status.feed = config.enabled.then_some(config.feed);
+    });
+    if config.enabled {
+        tauri::async_runtime::spawn(run(app.clone(), config));
+    }
+}
+
+#[tauri::command]
+pub fn get_usgs_status(webview: Webview, state: tauri::State<'_, UsgsState>) -> Result<UsgsStatus, String> {
+    crate::metrics::of(&webview).observe("get_usgs_status", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.status.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    })
+}
+
+/// Stored earthquakes, newest first.
+#[tauri::command]
+pub async fn query_earthquakes(webview: Webview, app: AppHandle, query: Option<QuakeQuery>) -> Result<Vec<Value>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("query_earthquakes", async move {
+            crate::require_trusted_window(webview.label())?;
+            let query = query.unwrap_or_default();
+            crate::runtime::blocking(move || app.state::<EventStore>().quakes(&query)).await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod usgs_tests {
+    use super::*;
+    use crate::ais::BoundingBox;
+
+    const FEED: &str = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {
+                "type": "Feature",
+                "id": "us7000abcd",
+                "properties": {
+                    "mag": 6.4, "place": "45 km SSW of Hualien City, Taiwan", "time": 1773489600000,
+                    "updated": 1773490000000, "url": "https://earthquake.usgs.gov/earthquakes/eventpage/us7000abcd",
+                    "tsunami": 1, "alert": "yellow", "sig": 630, "type": "earthquake"
+                },
+                "geometry": { "type": "Point", "coordinates": [121.5, 23.6, 18.2] }
+            },
+            {
+                "type": "Feature",
+                "id": "nc75000001",
+                "properties": { "mag": 2.1, "place": "5 km N of The Geysers, CA", "time": 1773489000000, "tsunami": 0 },
+                "geometry": { "type": "Point", "coordinates": [-122.76, 38.82, 2.0] }
+            },
+            {
+                "type": "Feature",
+                "id": "ak0260001",
+                "properties": { "mag": null, "place": "Alaska", "time": 1773488000000 },
+                "geometry": { "type": "Point", "coordinates": [-150.0, 61.0, 30.0] }
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn parses_and_filters_the_feed() {
+        let quakes = parse_feed(FEED, &UsgsConfig::default()).unwrap();
+        assert_eq!(quakes.len(), 2);
+        let taiwan = &quakes[0];
+        assert_eq!((taiwan.id.as_str(), taiwan.magnitude, taiwan.time), ("us7000abcd", 6.4, 1773489600000));
+        assert_eq!(taiwan.data["location"], json!({ "latitude": 23.6, "longitude": 121.5 }));
+        assert_eq!(taiwan.data["depthKm"], json!(18.2));
+        assert_eq!(taiwan.data["tsunami"], json!(true));
+
+        let config = UsgsConfig {
+            min_magnitude: Some(4.5),
+            ..UsgsConfig::default()
+        };
+        assert_eq!(parse_feed(FEED, &config).unwrap().len(), 1);
+        let config = UsgsConfig {
+            bounding_boxes: vec![BoundingBox { south: 30.0, west: -130.0, north: 50.0, east: -110.0 }],
+            ..UsgsConfig::default()
+        };
+        assert_eq!(parse_feed(FEED, &config).unwrap()[0].id, "nc75000001");
+        assert!(parse_feed("not json", &config).is_err());
+    }
+
+    #[test]
+    fn notifies_for_large_recent_quakes() {
+        let quakes = parse_feed(FEED, &UsgsConfig::default()).unwrap();
+        let config = UsgsConfig::default();
+        let now = 1773489600000 + 60_000;
+        // The tsunami flag makes an M6.4 critical.
+        assert_eq!(notification(&quakes[0], &config, now), Some(NotificationUrgency::Critical));
+        assert_eq!(notification(&quakes[1], &config, now), None);
+        assert_eq!(notification(&quakes[0], &config, now + 2 * 60 * 60 * 1000), None);
+        let quiet = UsgsConfig {
+            notify_magnitude: None,
+            ..UsgsConfig::default()
+        };
+        assert_eq!(notification(&quakes[0], &quiet, now), None);
+    }
+}
@@ -0,0 +1,166 @@
+//! Main-window responsiveness watchdog. A background thread emits
+//! `watchdog://ping` to the main window every `interval_secs`; the frontend
+//! answers with `watchdog_ack`. If no ack arrives for
+//! `unresponsive_after_secs` the window is logged as frozen, a
+//! `freeze-<epoch>.json` snapshot is written next to the logs, and (when
+//! `auto_reload` is set) the window is reloaded.
+
+use std::fs;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Webview, WebviewWindow};
+
+use crate::config::WatchdogConfig;
+use crate::diagnostics::SystemInfo;
+
+const PING_EVENT: &str = "watchdog://ping";
+const WATCHED_WINDOW: &str = "main";
+const SNAPSHOT_LOG_TAIL_LINES: usize = 200;
+
+pub struct WatchdogState {
+    last_ack: Mutex<Heartbeat>,
+}
+
+struct Heartbeat {
+    at: Instant,
+    seq: u64,
+}
+
+impl Default for WatchdogState {
+    fn default() -> Self {
+        WatchdogState {
+            last_ack: Mutex::new(Heartbeat { at: Instant::now(), seq: 0 }),
+        }
+    }
+}
+
+impl WatchdogState {
+    fn ack(&self, seq: u64) {
+        let mut last = self.last_ack.lock().unwrap_or_else(|e| e.into_inner());
+        last.at = Instant::now();
+        last.seq = last.seq.max(seq);
+    }
+
+    /// Restart the grace period, e.g. after a reload or while hidden.
+    fn reset(&self) {
+        self.last_ack.lock().unwrap_or_else(|e| e.into_inner()).at = Instant::now();
+    }
+
+    fn silent_for(&self) -> (Duration, u64) {
+        let last = self.last_ack.lock().unwrap_or_else(|e| e.into_inner());
+        (last.at.elapsed(), last.seq)
+    }
+}
+
+#[derive(Serialize)]
+struct FreezeSnapshot {
+    timestamp: u64,
+    silent_for_secs: u64,
+    last_acked_seq: u64,
+    pings_sent: u64,
+    auto_reload: bool,
+    system: SystemInfo,
+    recent_log: Vec<String>,
+}
+
+fn write_snapshot(app: &AppHandle, snapshot: &FreezeSnapshot) -> Result<(), String> {
+    let path = crate::logs_dir_path(app)?.join(format!("freeze-{}.json", snapshot.timestamp));
+    let json = serde_json::to_string_pretty(snapshot).map_err(|e| format!("Failed to serialize freeze snapshot: {e}"))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write freeze snapshot {}: {e}", path.display()))
+}
+
+fn on_frozen(app: &AppHandle, window: &WebviewWindow, config: &WatchdogConfig, silent_for: Duration, last_acked_seq: u64, pings_sent: u64) {
+    tracing::error!(
+        target: "app",
+        silent_for_secs = silent_for.as_secs(),
+        last_acked_seq,
+        "main window stopped responding"
+    );
+    let recent_log = crate::desktop_log_path(app)
+        .ok()
+        .and_then(|path| crate::log_files::read_tail_lines(&path, SNAPSHOT_LOG_TAIL_LINES).ok())
+        .map(|(lines, _)| lines)
+        .unwrap_or_default();
+    let snapshot = FreezeSnapshot {
+        timestamp: crate::unix_timestamp_secs(),
+        silent_for_secs: silent_for.as_secs(),
+        last_acked_seq,
+        pings_sent,
+        auto_reload: config.auto_reload,
+        system: crate::diagnostics::system_info(app),
+        recent_log,
+    };
+    if let Err(err) = write_snapshot(app, &snapshot) {
+        tracing::warn!(target: "app", "{err}");
+    }
+    if config.auto_reload {
+        tracing::warn!(target: "app", "reloading unresponsive main window");
+        match window.reload() {
+            Ok(()) => app.state::<WatchdogState>().reset(),
+            Err(e) => tracing::error!(target: "app", "failed to reload main window: {e}"),
+        }
+    }
+}
+
+fn run(app: AppHandle, config: WatchdogConfig) {
+    let interval = Duration::from_secs(config.interval_secs.max(1));
+    let threshold = Duration::from_secs(config.unresponsive_after_secs).max(interval * 2);
+    let state = app.state::<WatchdogState>();
+    let mut pings_sent = 0u64;
+    let mut frozen = false;
+    state.reset();
+    loop {
+        std::thread::sleep(interval);
+        // Not created yet (splash still up) or already closed.
+        let Some(window) = app.get_webview_window(WATCHED_WINDOW) else {
+            state.reset();
+            continue;
+        };
+        // Hidden or minimized webviews may be throttled by the OS; don't
+        // mistake that for a freeze.
+        if !window.is_visible().unwrap_or(true) || window.is_minimized().unwrap_or(false) {
+            state.reset();
+            continue;
+        }
+        pings_sent += 1;
+        let _ = app.emit_to(WATCHED_WINDOW, PING_EVENT, pings_sent);
+
+        let (silent_for, last_acked_seq) = state.silent_for();
+        if silent_for >= threshold {
+            if !frozen {
+                frozen = true;
+                on_frozen(&app, &window, &config, silent_for, last_acked_seq, pings_sent);
+            }
+        } else if frozen {
+            frozen = false;
+            tracing::info!(target: "app", "main window responsive again");
+        }
+    }
+}
+
+/// Start the watchdog thread if enabled in the desktop config.
+pub fn start(app: &AppHandle, config: WatchdogConfig) {
+    if !config.enabled {
+        return;
+    }
+    let app = app.clone();
+    if let Err(e) = std::thread::Builder::new()
+        .name("webview-watchdog".into())
+        .spawn(move || run(app, config))
+    {
+        tracing::warn!(target: "app", "failed to start webview watchdog: {e}");
+    }
+}
+
+#[tauri::command]
+pub fn watchdog_ack(webview: Webview, state: tauri::State<'_, WatchdogState>, seq: u64) -> Result<(), String> {
+    crate::metrics::of(&webview).observe("watchdog_ack", || {
+        crate::require_trusted_window(webview.label())?;
+        if webview.label() == WATCHED_WINDOW {
+            state.ack(seq);
+        }
+        Ok(())
+    })
+}
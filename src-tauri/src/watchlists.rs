@@ -0,0 +1,689 @@
+//! Watchlists of aircraft, vessels, regions and keywords, kept in the local
+//! `store` and matched in the shell as the streams arrive: positions from
+//! `opensky` (network and local receiver) and `ais`, headlines from `news`.
+//! Every match is handed to the alert engine as a hit, where `Watchlists`
+//! rules decide whether and how to deliver it.
+//!
+//! - Aircraft are listed by ICAO24 address or by registration or callsign;
+//!   a registration matches while the aircraft flies under it.
+//! - Vessels are listed by MMSI, by IMO number (`IMO9811000`, matched once
+//!   the vessel has sent its static data) or by name.
+//! - Regions are named bounding boxes; any aircraft or vessel inside counts,
+//!   as does a new airspace restriction from `faa` that overlaps one.
+//! - Keywords match headline titles, case-insensitively.
+//!
+//! Entries are kept in memory, indexed by identifier, so a batch of
+//! thousands of positions costs a lookup each. The last hits are logged in
+//! memory too, once per subject until it has been gone for 15 minutes, for
+//! `exports` to write out.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Webview};
+
+use crate::ais::BoundingBox;
+use crate::alerts::{Headline, Sighting, Target};
+use crate::runtime::blocking;
+use crate::store::{EventStore, WatchlistRow};
+
+const MAX_ENTRIES: usize = 10_000;
+const MAX_VALUE_LEN: usize = 200;
+const MAX_ACTIVITY: usize = 5_000;
+/// A hit on a subject not matched for this long is logged again.
+const ABSENCE_SECS: u64 = 15 * 60;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchKind {
+    Aircraft,
+    Vessel,
+    Region,
+    Keyword,
+}
+
+impl WatchKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WatchKind::Aircraft => "aircraft",
+            WatchKind::Vessel => "vessel",
+            WatchKind::Region => "region",
+            WatchKind::Keyword => "keyword",
+        }
+    }
+
+    fn parse(kind: &str) -> Option<Self> {
+        [WatchKind::Aircraft, WatchKind::Vessel, WatchKind::Region, WatchKind::Keyword]
+            .into_iter()
+            .find(|k| k.as_str().eq_ignore_ascii_case(kind.trim()))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchlistEntry {
+    pub kind: WatchKind,
+    /// Normalized when added: an ICAO24 address in lower case, or a
+    /// registration or callsign in upper case without dashes; an MMSI,
+    /// `IMO` and seven digits, or a vessel name in upper case; a region's
+    /// name; a keyword in lower case.
+    pub value: String,
+    /// A note shown with hits, such as the operator.
+    #[serde(default)]
+    pub label: String,
+    /// Required for regions.
+    #[serde(default)]
+    pub region: Option<BoundingBox>,
+    /// Milliseconds since the epoch.
+    #[serde(default)]
+    pub added: i64,
+}
+
+impl WatchlistEntry {
+    fn normalized(mut self) -> Result<Self, String> {
+        self.value = normalize(self.kind, &self.value)?;
+        self.label = self.label.trim().to_string();
+        match (self.kind, self.region) {
+            (WatchKind::Region, Some(region)) if region.is_valid() => {}
+            (WatchKind::Region, _) => return Err(format!("Region {} needs a valid bounding box", self.value)),
+            _ => self.region = None,
+        }
+        Ok(self)
+    }
+
+    fn row(&self) -> WatchlistRow {
+        WatchlistRow {
+            kind: self.kind.as_str().to_string(),
+            value: self.value.clone(),
+            label: self.label.clone(),
+            region: self.region,
+            added: self.added,
+        }
+    }
+
+    fn from_row(row: WatchlistRow) -> Option<Self> {
+        Some(WatchlistEntry {
+            kind: WatchKind::parse(&row.kind)?,
+            value: row.value,
+            label: row.label,
+            region: row.region,
+            added: row.added,
+        })
+    }
+}
+
+/// Identifies an entry to remove.
+#[derive(Clone, Debug, Deserialize)]
+pub struct WatchlistKey {
+    pub kind: WatchKind,
+    pub value: String,
+}
+
+/// Something covering an area, such as a TFR, to match against regions.
+pub struct Coverage<'a> {
+    pub id: &'a str,
+    pub bounds: BoundingBox,
+    pub description: &'a str,
+}
+
+/// A match between an entry and incoming data, for the alert engine.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WatchlistHit {
+    pub kind: WatchKind,
+    /// The entry's value.
+    pub entry: String,
+    /// The aircraft or vessel id, the headline link, or the notice id.
+    pub subject: String,
+    pub description: String,
+}
+
+/// A logged hit.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchlistActivity {
+    /// Seconds since the epoch.
+    pub time: u64,
+    pub kind: WatchKind,
+    pub entry: String,
+    pub subject: String,
+    pub description: String,
+}
+
+/// Recent hits, with when each was last matched.
+#[derive(Default)]
+struct ActivityLog {
+    last_seen: HashMap<String, u64>,
+    entries: VecDeque<WatchlistActivity>,
+}
+
+impl ActivityLog {
+    fn record(&mut self, hits: &[WatchlistHit], time: u64) {
+        for hit in hits {
+            let key = format!("{}:{}:{}", hit.kind.as_str(), hit.entry, hit.subject);
+            let previous = self.last_seen.insert(key, time);
+            if previous.is_some_and(|seen| time.saturating_sub(seen) < ABSENCE_SECS) {
+                continue;
+            }
+            if self.entries.len() == MAX_ACTIVITY {
+                self.entries.pop_front();
+            }
+            self.entries.push_back(WatchlistActivity {
+                time,
+                kind: hit.kind,
+                entry: hit.entry.clone(),
+                subject: hit.subject.clone(),
+                description: hit.description.clone(),
+            });
+        }
+        self.last_seen.retain(|_, seen| time.saturating_sub(*seen) < ABSENCE_SECS);
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct WatchlistImport {
+    pub added: usize,
+    pub updated: usize,
+    /// Lines that could not be read, with why.
+    pub skipped: Vec<String>,
+}
+
+fn compact(value: &str) -> String {
+    value.chars().filter(|c| !c.is_whitespace() && *c != '-').collect::<String>().to_uppercase()
+}
+
+fn vessel_name(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ").to_uppercase()
+}
+
+fn normalize(kind: WatchKind, value: &str) -> Result<String, String> {
+    let value = value.trim();
+    if value.is_empty() || value.len() > MAX_VALUE_LEN {
+        return Err(format!("A watchlist value must be 1-{MAX_VALUE_LEN} characters"));
+    }
+    Ok(match kind {
+        WatchKind::Aircraft if value.len() == 6 && value.chars().all(|c| c.is_ascii_hexdigit()) => value.to_lowercase(),
+        WatchKind::Aircraft => compact(value),
+        WatchKind::Vessel => {
+            let imo = value.get(..3).filter(|p| p.eq_ignore_ascii_case("imo")).map(|_| value[3..].trim());
+            let digits = imo.unwrap_or(value);
+            match (imo.is_some(), digits.len(), digits.chars().all(|c| c.is_ascii_digit())) {
+                (_, 7, true) => format!("IMO{digits}"),
+                (true, _, _) => return Err(format!("{value} is not a seven-digit IMO number")),
+                (false, _, true) => digits.to_string(),
+                _ => vessel_name(value),
+            }
+        }
+        WatchKind::Region => value.to_string(),
+        WatchKind::Keyword => value.to_lowercase(),
+    })
+}
+
+/// The entries, indexed for matching.
+#[derive(Default)]
+struct Matcher {
+    entries: Vec<WatchlistEntry>,
+    /// Upper-cased values of aircraft and vessel entries.
+    aircraft: HashMap<String, usize>,
+    vessels: HashMap<String, usize>,
+    regions: Vec<usize>,
+    keywords: Vec<usize>,
+}
+
+impl Matcher {
+    fn new(entries: Vec<WatchlistEntry>) -> Self {
+        let mut matcher = Matcher::default();
+        for (index, entry) in entries.iter().enumerate() {
+            match entry.kind {
+                WatchKind::Aircraft => drop(matcher.aircraft.insert(entry.value.to_uppercase(), index)),
+                WatchKind::Vessel => drop(matcher.vessels.insert(entry.value.to_uppercase(), index)),
+                WatchKind::Region => matcher.regions.push(index),
+                WatchKind::Keyword => matcher.keywords.push(index),
+            }
+        }
+        matcher.entries = entries;
+        matcher
+    }
+
+    fn positions(&self, sightings: &[Sighting]) -> Vec<WatchlistHit> {
+        let mut hits = Vec::new();
+        for s in sightings {
+            let listed = match s.kind {
+                Target::Vessel => [Some(s.id.to_uppercase()), Some(vessel_name(s.label)), s.imo.map(|imo| format!("IMO{imo}"))]
+                    .into_iter()
+                    .flatten()
+                    .find_map(|key| self.vessels.get(&key)),
+                _ => [s.id.to_uppercase(), compact(s.label)].into_iter().find_map(|key| self.aircraft.get(&key)),
+            };
+            if let Some(&index) = listed {
+                let entry = &self.entries[index];
+                let note = if entry.label.is_empty() { String::new() } else { format!(" [{}]", entry.label) };
+                hits.push(WatchlistHit {
+                    kind: entry.kind,
+                    entry: entry.value.clone(),
+                    subject: s.id.to_string(),
+                    description: format!("{}{note} sighted at {:.3}, {:.3}", s.describe(), s.lat, s.lon),
+                });
+            }
+            for &index in &self.regions {
+                let entry = &self.entries[index];
+                if entry.region.is_some_and(|r| r.contains(s.lat, s.lon)) {
+                    hits.push(WatchlistHit {
+                        kind: WatchKind::Region,
+                        entry: entry.value.clone(),
+                        subject: s.id.to_string(),
+                        description: format!("{} entered {}", s.describe(), entry.value),
+                    });
+                }
+            }
+        }
+        hits
+    }
+
+    fn coverage(&self, areas: &[Coverage]) -> Vec<WatchlistHit> {
+        let mut hits = Vec::new();
+        for area in areas {
+            for &index in &self.regions {
+                let entry = &self.entries[index];
+                if entry.region.is_some_and(|r| r.intersects(&area.bounds)) {
+                    hits.push(WatchlistHit {
+                        kind: WatchKind::Region,
+                        entry: entry.value.clone(),
+                        subject: area.id.to_string(),
+                        description: format!("{} in {}", area.description, entry.value),
+                    });
+                }
+            }
+        }
+        hits
+    }
+
+    /// One hit per keyword per batch, naming the first headline.
+    fn headlines(&self, headlines: &[Headline]) -> Vec<WatchlistHit> {
+        let titles: Vec<String> = headlines.iter().map(|h| h.title.to_lowercase()).collect();
+        self.keywords
+            .iter()
+            .filter_map(|&index| {
+                let entry = &self.entries[index];
+                let matches: Vec<&Headline> = headlines
+                    .iter()
+                    .zip(&titles)
+                    .filter(|(_, title)| title.contains(entry.value.as_str()))
+                    .map(|(h, _)| h)
+                    .collect();
+                let first = matches.first()?;
+                let description = match matches.len() {
+                    1 => format!("{}: {}", first.source, first.title),
+                    n => format!("{}: {} (+{} more)", first.source, first.title, n - 1),
+                };
+                Some(WatchlistHit {
+                    kind: WatchKind::Keyword,
+                    entry: entry.value.clone(),
+                    subject: first.link.to_string(),
+                    description,
+                })
+            })
+            .collect()
+    }
+}
+
+pub struct WatchlistsState {
+    matcher: Mutex<Arc<Matcher>>,
+    activity: Mutex<ActivityLog>,
+}
+
+impl WatchlistsState {
+    /// Load the entries from the store; none if it cannot be read.
+    pub fn load(store: &EventStore) -> Self {
+        let entries = store.watchlist().unwrap_or_else(|err| {
+            tracing::warn!(target: "app", "failed to load watchlists: {err}");
+            Vec::new()
+        });
+        WatchlistsState {
+            matcher: Mutex::new(Arc::new(Matcher::new(entries.into_iter().filter_map(WatchlistEntry::from_row).collect()))),
+            activity: Mutex::default(),
+        }
+    }
+
+    fn matcher(&self) -> Arc<Matcher> {
+        self.matcher.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn reload(&self, store: &EventStore) -> Result<(), String> {
+        let entries = store.watchlist()?.into_iter().filter_map(WatchlistEntry::from_row).collect();
+        *self.matcher.lock().unwrap_or_else(|e| e.into_inner()) = Arc::new(Matcher::new(entries));
+        Ok(())
+    }
+}
+
+fn report(app: &AppHandle, hits: &[WatchlistHit]) {
+    if !hits.is_empty() {
+        let state = app.state::<WatchlistsState>();
+        state.activity.lock().unwrap_or_else(|e| e.into_inner()).record(hits, crate::unix_timestamp_secs());
+    }
+    crate::mqtt::publish_watchlist_hits(app, hits);
+    crate::alerts::observe_watchlist_hits(app, hits);
+}
+
+/// Match aircraft or vessel positions and report hits to the alert engine.
+pub fn observe_positions(app: &AppHandle, sightings: &[Sighting]) {
+    let Some(state) = app.try_state::<WatchlistsState>() else {
+        return;
+    };
+    let hits = state.matcher().positions(sightings);
+    report(app, &hits);
+}
+
+/// Hits logged at or after `since` (seconds), oldest first.
+pub fn activity_since(app: &AppHandle, since: u64) -> Vec<WatchlistActivity> {
+    let Some(state) = app.try_state::<WatchlistsState>() else {
+        return Vec::new();
+    };
+    let activity = state.activity.lock().unwrap_or_else(|e| e.into_inner());
+    activity.entries.iter().filter(|hit| hit.time >= since).cloned().collect()
+}
+
+/// The named regions, for `anomalies` to watch for circling aircraft.
+pub fn regions(app: &AppHandle) -> Vec<(String, BoundingBox)> {
+    let Some(state) = app.try_state::<WatchlistsState>() else {
+        return Vec::new();
+    };
+    let matcher = state.matcher();
+    matcher
+        .regions
+        .iter()
+        .filter_map(|&index| {
+            let entry = &matcher.entries[index];
+            Some((entry.value.clone(), entry.region?))
+        })
+        .collect()
+}
+
+/// Match new headlines and report hits to the alert engine.
+pub fn observe_headlines(app: &AppHandle, headlines: &[Headline]) {
+    let Some(state) = app.try_state::<WatchlistsState>() else {
+        return;
+    };
+    let hits = state.matcher().headlines(headlines);
+    report(app, &hits);
+}
+
+/// Match new areas, such as airspace restrictions, against regions and
+/// report hits to the alert engine.
+pub fn observe_coverage(app: &AppHandle, areas: &[Coverage]) {
+    let Some(state) = app.try_state::<WatchlistsState>() else {
+        return;
+    };
+    let hits = state.matcher().coverage(areas);
+    report(app, &hits);
+}
+
+/// Entries from an import: a JSON array of entries (as `list_watchlist`
+/// returns them), or lines of `kind,value[,label]`, `region,name,south,
+/// west,north,east[,label]`, or with a default `kind` just `value[,label]`.
+/// Blank lines, `#` comments and a `kind,...` header are skipped.
+fn parse_import(text: &str, default: Option<WatchKind>) -> (Vec<WatchlistEntry>, Vec<String>) {
+    if text.trim_start().starts_with('[') {
+        return match serde_json::from_str::<Vec<WatchlistEntry>>(text) {
+            Ok(entries) => (entries, Vec::new()),
+            Err(err) => (Vec::new(), vec![format!("invalid JSON: {err}")]),
+        };
+    }
+    let (mut entries, mut skipped) = (Vec::new(), Vec::new());
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if fields[0].eq_ignore_ascii_case("kind") {
+            continue;
+        }
+        let (kind, rest) = match (WatchKind::parse(fields[0]), default) {
+            (Some(kind), _) => (kind, &fields[1..]),
+            (None, Some(kind)) => (kind, &fields[..]),
+            (None, None) => {
+                skipped.push(format!("{line}: unknown kind"));
+                continue;
+            }
+        };
+        let entry = match (kind, rest) {
+            (WatchKind::Region, [name, south, west, north, east, label @ ..]) => {
+                let bounds: Result<Vec<f64>, _> = [south, west, north, east].iter().map(|v| v.parse::<f64>()).collect();
+                match bounds {
+                    Ok(b) => WatchlistEntry {
+                        kind,
+                        value: name.to_string(),
+                        label: label.join(","),
+                        region: Some(BoundingBox { south: b[0], west: b[1], north: b[2], east: b[3] }),
+                        added: 0,
+                    },
+                    Err(_) => {
+                        skipped.push(format!("{line}: bounds must be numbers"));
+                        continue;
+                    }
+                }
+            }
+            (WatchKind::Region, _) => {
+                skipped.push(format!("{line}: a region needs a name, south, west, north and east"));
+                continue;
+            }
+            (_, [value, label @ ..]) => WatchlistEntry {
+                kind,
+                value: value.to_string(),
+                label: label.join(","),
+                region: None,
+                added: 0,
+            },
+            (_, []) => {
+                skipped.push(format!("{line}: missing value"));
+                continue;
+            }
+        };
+        entries.push(entry);
+    }
+    (entries, skipped)
+}
+
+/// Normalize and store `entries`, returning them as stored and how many
+/// were new.
+fn add(app: &AppHandle, entries: Vec<WatchlistEntry>) -> Result<(Vec<WatchlistEntry>, usize), String> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let entries = entries
+        .into_iter()
+        .map(|entry| {
+            let mut entry = entry.normalized()?;
+            entry.added = now;
+            Ok(entry)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let store = app.state::<EventStore>();
+    let state = app.state::<WatchlistsState>();
+    let listed = state.matcher().entries.len();
+    if listed + entries.len() > MAX_ENTRIES {
+        return Err(format!("Watchlists hold at most {MAX_ENTRIES} entries"));
+    }
+    let rows: Vec<WatchlistRow> = entries.iter().map(WatchlistEntry::row).collect();
+    let added = store.add_to_watchlist(&rows)?.len();
+    state.reload(&store)?;
+    Ok((entries, added))
+}
+
+/// Entries, optionally of one kind, oldest first.
+#[tauri::command]
+pub fn list_watchlist(
+    webview: Webview,
+    state: tauri::State<'_, WatchlistsState>,
+    kind: Option<WatchKind>,
+) -> Result<Vec<WatchlistEntry>, String> {
+    crate::metrics::of(&webview).observe("list_watchlist", || {
+        crate::require_trusted_window(webview.label())?;
+        let matcher = state.matcher();
+        Ok(matcher.entries.iter().filter(|e| kind.is_none_or(|k| e.kind == k)).cloned().collect())
+    })
+}
+
+/// Add entries, or update the label and region of listed ones. Returns them
+/// normalized.
+#[tauri::command]
+pub async fn add_to_watchlist(
+    webview: Webview,
+    app: AppHandle,
+    entries: Vec<WatchlistEntry>,
+) -> Result<Vec<WatchlistEntry>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("add_to_watchlist", async move {
+            crate::require_trusted_window(webview.label())?;
+            blocking(move || add(&app, entries).map(|(entries, _)| entries)).await
+        })
+        .await
+}
+
+/// Remove entries, returning how many were listed.
+#[tauri::command]
+pub async fn remove_from_watchlist(webview: Webview, app: AppHandle, entries: Vec<WatchlistKey>) -> Result<usize, String> {
+    crate::metrics::of(&webview)
+        .observe_async("remove_from_watchlist", async move {
+            crate::require_trusted_window(webview.label())?;
+            blocking(move || {
+                let keys = entries
+                    .iter()
+                    .map(|key| Ok((key.kind.as_str().to_string(), normalize(key.kind, &key.value)?)))
+                    .collect::<Result<Vec<_>, String>>()?;
+                let store = app.state::<EventStore>();
+                let removed = store.remove_from_watchlist(&keys)?;
+                app.state::<WatchlistsState>().reload(&store)?;
+                Ok(removed)
+            })
+            .await
+        })
+        .await
+}
+
+/// Add the entries in `text` (see `parse_import`); `kind` applies to lines
+/// that do not start with one. Unreadable lines are skipped and reported.
+#[tauri::command]
+pub async fn import_watchlist(
+    webview: Webview,
+    app: AppHandle,
+    text: String,
+    kind: Option<WatchKind>,
+) -> Result<WatchlistImport, String> {
+    crate::metrics::of(&webview)
+        .observe_async("import_watchlist", async move {
+            crate::require_trusted_window(webview.label())?;
+            blocking(move || {
+                let (entries, mut skipped) = parse_import(&text, kind);
+                let valid: Vec<WatchlistEntry> = entries
+                    .into_iter()
+                    .filter_map(|entry| {
+                        let value = entry.value.clone();
+                        entry.normalized().map_err(|err| skipped.push(format!("{value}: {err}"))).ok()
+                    })
+                    .collect();
+                let total = valid.len();
+                let (_, added) = add(&app, valid)?;
+                Ok(WatchlistImport { added, updated: total - added, skipped })
+            })
+            .await
+        })
+        .await
+}
+
+#[cfg(test)]
+mod watchlists_tests {
+    use super::*;
+
+    fn entry(kind: WatchKind, value: &str) -> WatchlistEntry {
+        WatchlistEntry { kind, value: value.to_string(), label: String::new(), region: None, added: 0 }.normalized().unwrap()
+    }
+
+    #[test]
+    fn logs_hits_once_per_visit() {
+        let hit = |subject: &str| WatchlistHit {
+            kind: WatchKind::Region,
+            entry: "Hormuz".to_string(),
+            subject: subject.to_string(),
+            description: format!("{subject} entered Hormuz"),
+        };
+        let mut log = ActivityLog::default();
+        log.record(&[hit("1"), hit("2")], 1_000);
+        log.record(&[hit("1")], 1_060);
+        assert_eq!(log.entries.len(), 2);
+        // Gone for longer than the absence window, then back.
+        log.record(&[hit("1")], 1_060 + ABSENCE_SECS);
+        assert_eq!(log.entries.len(), 3);
+        assert_eq!(log.entries[2].time, 1_060 + ABSENCE_SECS);
+    }
+
+    #[test]
+    fn normalizes_identifiers() {
+        assert_eq!(entry(WatchKind::Aircraft, " AE1234 ").value, "ae1234");
+        assert_eq!(entry(WatchKind::Aircraft, "d-aibl").value, "DAIBL");
+        assert_eq!(entry(WatchKind::Vessel, "imo 9811000").value, "IMO9811000");
+        assert_eq!(entry(WatchKind::Vessel, "9811000").value, "IMO9811000");
+        assert_eq!(entry(WatchKind::Vessel, "244660000").value, "244660000");
+        assert_eq!(entry(WatchKind::Vessel, "Ever  given").value, "EVER GIVEN");
+        assert_eq!(entry(WatchKind::Keyword, "Strait of Hormuz").value, "strait of hormuz");
+        assert!(normalize(WatchKind::Vessel, "IMO 12").is_err());
+        let region = WatchlistEntry { kind: WatchKind::Region, value: "Hormuz".to_string(), label: String::new(), region: None, added: 0 };
+        assert!(region.normalized().is_err());
+    }
+
+    #[test]
+    fn matches_positions_and_headlines() {
+        let hormuz = WatchlistEntry {
+            region: Some(BoundingBox { south: 25.0, west: 55.0, north: 27.5, east: 57.5 }),
+            ..WatchlistEntry { kind: WatchKind::Region, value: "Hormuz".to_string(), label: String::new(), region: None, added: 0 }
+        };
+        let matcher = Matcher::new(vec![
+            entry(WatchKind::Aircraft, "N123AB"),
+            entry(WatchKind::Vessel, "IMO9811000"),
+            entry(WatchKind::Keyword, "hormuz"),
+            hormuz,
+        ]);
+        let sightings = [
+            Sighting { kind: Target::Aircraft, id: "a1b2c3", label: "N123AB ", imo: None, lat: 0.0, lon: 0.0 },
+            Sighting { kind: Target::Vessel, id: "244660000", label: "EVER GIVEN", imo: Some(9_811_000), lat: 26.5, lon: 56.2 },
+            Sighting { kind: Target::Vessel, id: "1", label: "", imo: None, lat: 0.0, lon: 0.0 },
+        ];
+        let hits = matcher.positions(&sightings);
+        let found: Vec<(WatchKind, &str)> = hits.iter().map(|h| (h.kind, h.subject.as_str())).collect();
+        assert_eq!(
+            found,
+            [(WatchKind::Aircraft, "a1b2c3"), (WatchKind::Vessel, "244660000"), (WatchKind::Region, "244660000")]
+        );
+
+        let headlines = [
+            Headline { source: "BBC", title: "Tanker seized in Strait of Hormuz", link: "a" },
+            Headline { source: "AP", title: "Hormuz traffic slows", link: "b" },
+        ];
+        let hits = matcher.headlines(&headlines);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].description, "BBC: Tanker seized in Strait of Hormuz (+1 more)");
+
+        let tfr = Coverage {
+            id: "6/1234",
+            bounds: BoundingBox { south: 26.0, west: 57.0, north: 28.0, east: 59.0 },
+            description: "TFR 6/1234",
+        };
+        let hits = matcher.coverage(&[tfr]);
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].subject.as_str(), hits[0].description.as_str()), ("6/1234", "TFR 6/1234 in Hormuz"));
+    }
+
+    #[test]
+    fn imports_lines_and_json() {
+        let text = "kind,value,label\n# military\naircraft,ae1234,RCH\nregion,Hormuz,25,55,27.5,57.5\nregion,Bad,1,2\nsubmarine,x\n";
+        let (entries, skipped) = parse_import(text, None);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].label, "RCH");
+        assert_eq!(entries[1].region.map(|r| r.north), Some(27.5));
+        assert_eq!(skipped.len(), 2);
+
+        let (entries, skipped) = parse_import("244660000,Ever Given\n", Some(WatchKind::Vessel));
+        assert_eq!((entries[0].kind, entries[0].label.as_str(), skipped.len()), (WatchKind::Vessel, "Ever Given", 0));
+
+        let json = serde_json::to_string(&entries).unwrap();
+        assert_eq!(parse_import(&json, None).0, entries);
+    }
+}
@@ -0,0 +1,571 @@
+//! Weather for the places the user pins. Current conditions come from
+//! Open-Meteo (worldwide, no key) and active alerts from the US National
+//! Weather Service, which only covers the US and its territories; elsewhere
+//! its 4xx answer just means no alerts. Responses are cached per location,
+//! conditions for `CONDITIONS_TTL` and alerts for `ALERTS_TTL`, so
+//! `get_weather` only goes to the network for what has gone stale.
+//!
+//! The worker refreshes every `ALERTS_TTL` on the `poll` schedule and emits
+//! the result as `weather://updated`. Severe and extreme alerts not seen
+//! before go out as one `weather://alert` batch. Pinned places are kept in
+//! `weather-locations.json` and replaced with `set_weather_locations`.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+pub const WEATHER_LOCATIONS_FILE: &str = "weather-locations.json";
+pub const UPDATED_EVENT: &str = "weather://updated";
+pub const ALERT_EVENT: &str = "weather://alert";
+
+const OPEN_METEO_URL: &str = "https://api.open-meteo.com/v1/forecast";
+const NWS_ALERTS_URL: &str = "https://api.weather.gov/alerts/active";
+const CURRENT_FIELDS: &str = "temperature_2m,apparent_temperature,relative_humidity_2m,precipitation,weather_code,\
+    cloud_cover,pressure_msl,wind_speed_10m,wind_direction_10m,wind_gusts_10m,is_day";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+const CONDITIONS_TTL: Duration = Duration::from_secs(15 * 60);
+const ALERTS_TTL: Duration = Duration::from_secs(5 * 60);
+const MAX_LOCATIONS: usize = 50;
+const MAX_NAME_LEN: usize = 100;
+const MAX_DESCRIPTION_LEN: usize = 500;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WeatherLocation {
+    pub id: String,
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// Metric units: °C, %, mm, hPa, km/h and degrees.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Conditions {
+    /// When observed, in milliseconds since the epoch.
+    pub time: i64,
+    pub temperature: f64,
+    pub apparent_temperature: f64,
+    pub humidity: f64,
+    pub precipitation: f64,
+    /// WMO weather interpretation code.
+    pub weather_code: u8,
+    pub description: &'static str,
+    pub cloud_cover: f64,
+    pub pressure: f64,
+    pub wind_speed: f64,
+    pub wind_direction: f64,
+    pub wind_gusts: f64,
+    pub is_day: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeatherAlert {
+    pub id: String,
+    pub event: String,
+    /// `Extreme`, `Severe`, `Moderate`, `Minor` or `Unknown`.
+    pub severity: String,
+    pub urgency: String,
+    pub headline: String,
+    pub description: String,
+    pub area: String,
+    /// Milliseconds since the epoch.
+    pub onset: Option<i64>,
+    pub expires: Option<i64>,
+}
+
+impl WeatherAlert {
+    fn is_severe(&self) -> bool {
+        matches!(self.severity.as_str(), "Severe" | "Extreme")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationWeather {
+    #[serde(flatten)]
+    pub location: WeatherLocation,
+    pub conditions: Option<Conditions>,
+    pub alerts: Vec<WeatherAlert>,
+    /// The last fetch error, while it lasts.
+    pub error: Option<String>,
+}
+
+/// Payload of `weather://alert`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SevereAlert {
+    pub location_id: String,
+    pub location_name: String,
+    pub alert: WeatherAlert,
+}
+
+struct Cached<T> {
+    value: T,
+    fetched: Instant,
+}
+
+impl<T> Cached<T> {
+    fn fresh(&self, ttl: Duration, now: Instant) -> bool {
+        now.saturating_duration_since(self.fetched) < ttl
+    }
+}
+
+#[derive(Default)]
+struct CacheEntry {
+    conditions: Option<Cached<Conditions>>,
+    alerts: Option<Cached<Vec<WeatherAlert>>>,
+    error: Option<String>,
+}
+
+pub struct WeatherState {
+    path: PathBuf,
+    locations: Mutex<Vec<WeatherLocation>>,
+    /// Keyed by `cache_key`, so renaming a place does not refetch it.
+    cache: Mutex<HashMap<String, CacheEntry>>,
+    /// Ids of the severe alerts already announced.
+    announced: Mutex<HashSet<String>>,
+    /// One refresh at a time; the worker and `get_weather` would otherwise
+    /// fetch the same stale entries twice.
+    refreshing: tokio::sync::Mutex<()>,
+    client: OnceLock<reqwest::Client>,
+}
+
+impl WeatherState {
+    /// Load `weather-locations.json`; a missing or unreadable file means no
+    /// pinned places.
+    pub fn load(path: PathBuf) -> Self {
+        let locations: Vec<WeatherLocation> = fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        WeatherState {
+            path,
+            locations: Mutex::new(locations),
+            cache: Mutex::default(),
+            announced: Mutex::default(),
+            refreshing: tokio::sync::Mutex::new(()),
+            client: OnceLock::new(),
+        }
+    }
+
+    fn persist(&self, locations: &[WeatherLocation]) -> Result<(), String> {
+        let serialized = serde_json::to_string_pretty(locations)
+            .map_err(|e| format!("Failed to serialize weather locations: {e}"))?;
+        fs::write(&self.path, serialized)
+            .map_err(|e| format!("Failed to write weather locations {}: {e}", self.path.display()))
+    }
+
+    fn client(&self) -> Result<&reqwest::Client, String> {
+        if let Some(client) = self.client.get() {
+            return Ok(client);
+        }
+        let client = reqwest::Client::builder()
+            .use_native_tls()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(crate::USER_AGENT)
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+        Ok(self.client.get_or_init(|| client))
+    }
+
+    fn locations(&self) -> Vec<WeatherLocation> {
+        self.locations.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Every pinned place with whatever is cached for it.
+    fn snapshot(&self) -> Vec<LocationWeather> {
+        let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        self.locations()
+            .into_iter()
+            .map(|location| {
+                let entry = cache.get(&cache_key(&location));
+                LocationWeather {
+                    conditions: entry.and_then(|e| e.conditions.as_ref()).map(|c| c.value.clone()),
+                    alerts: entry.and_then(|e| e.alerts.as_ref()).map(|a| a.value.clone()).unwrap_or_default(),
+                    error: entry.and_then(|e| e.error.clone()),
+                    location,
+                }
+            })
+            .collect()
+    }
+}
+
+fn cache_key(location: &WeatherLocation) -> String {
+    format!("{:.2},{:.2}", location.lat, location.lon)
+}
+
+/// The usual wording of a WMO weather code.
+fn describe(code: u8) -> &'static str {
+    match code {
+        0 => "Clear sky",
+        1 => "Mainly clear",
+        2 => "Partly cloudy",
+        3 => "Overcast",
+        45 | 48 => "Fog",
+        51 | 53 | 55 => "Drizzle",
+        56 | 57 => "Freezing drizzle",
+        61 | 63 | 65 => "Rain",
+        66 | 67 => "Freezing rain",
+        71 | 73 | 75 => "Snow",
+        77 => "Snow grains",
+        80..=82 => "Rain showers",
+        85 | 86 => "Snow showers",
+        95 => "Thunderstorm",
+        96 | 99 => "Thunderstorm with hail",
+        _ => "Unknown",
+    }
+}
+
+#[derive(Deserialize)]
+struct OpenMeteoResponse {
+    current: OpenMeteoCurrent,
+}
+
+#[derive(Deserialize)]
+struct OpenMeteoCurrent {
+    /// Seconds, with `timeformat=unixtime`.
+    time: i64,
+    temperature_2m: f64,
+    apparent_temperature: f64,
+    relative_humidity_2m: f64,
+    precipitation: f64,
+    weather_code: u8,
+    cloud_cover: f64,
+    pressure_msl: f64,
+    wind_speed_10m: f64,
+    wind_direction_10m: f64,
+    wind_gusts_10m: f64,
+    is_day: u8,
+}
+
+fn parse_conditions(text: &str) -> Result<Conditions, String> {
+    let current = serde_json::from_str::<OpenMeteoResponse>(text)
+        .map_err(|e| format!("invalid Open-Meteo response: {e}"))?
+        .current;
+    Ok(Conditions {
+        time: current.time * 1000,
+        temperature: current.temperature_2m,
+        apparent_temperature: current.apparent_temperature,
+        humidity: current.relative_humidity_2m,
+        precipitation: current.precipitation,
+        weather_code: current.weather_code,
+        description: describe(current.weather_code),
+        cloud_cover: current.cloud_cover,
+        pressure: current.pressure_msl,
+        wind_speed: current.wind_speed_10m,
+        wind_direction: current.wind_direction_10m,
+        wind_gusts: current.wind_gusts_10m,
+        is_day: current.is_day == 1,
+    })
+}
+
+#[derive(Deserialize)]
+struct NwsResponse {
+    features: Vec<NwsFeature>,
+}
+
+#[derive(Deserialize)]
+struct NwsFeature {
+    properties: NwsProperties,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NwsProperties {
+    id: String,
+    event: String,
+    #[serde(default)]
+    severity: String,
+    #[serde(default)]
+    urgency: String,
+    headline: Option<String>,
+    description: Option<String>,
+    #[serde(default)]
+    area_desc: String,
+    onset: Option<String>,
+    expires: Option<String>,
+}
+
+fn parse_alerts(text: &str) -> Result<Vec<WeatherAlert>, String> {
+    let response: NwsResponse = serde_json::from_str(text).map_err(|e| format!("invalid NWS response: {e}"))?;
+    let time = |t: Option<String>| {
+        t.and_then(|t| chrono::DateTime::parse_from_rfc3339(&t).ok()).map(|t| t.timestamp_millis())
+    };
+    Ok(response
+        .features
+        .into_iter()
+        .map(|feature| {
+            let p = feature.properties;
+            let description: String = p.description.unwrap_or_default().chars().take(MAX_DESCRIPTION_LEN).collect();
+            WeatherAlert {
+                headline: p.headline.unwrap_or_else(|| p.event.clone()),
+                id: p.id,
+                event: p.event,
+                severity: p.severity,
+                urgency: p.urgency,
+                description,
+                area: p.area_desc,
+                onset: time(p.onset),
+                expires: time(p.expires),
+            }
+        })
+        .collect())
+}
+
+async fn fetch_conditions(client: &reqwest::Client, location: &WeatherLocation) -> Result<Conditions, String> {
+    let text = client
+        .get(OPEN_METEO_URL)
+        .query(&[
+            ("latitude", location.lat.to_string()),
+            ("longitude", location.lon.to_string()),
+            ("current", CURRENT_FIELDS.to_string()),
+            ("timeformat", "unixtime".to_string()),
+        ])
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Open-Meteo request failed: {e}"))?
+        .text()
+        .await
+        .map_err(|e| format!("Open-Meteo request failed: {e}"))?;
+    parse_conditions(&text)
+}
+
+async fn fetch_alerts(client: &reqwest::Client, location: &WeatherLocation) -> Result<Vec<WeatherAlert>, String> {
+    let response = client
+        .get(NWS_ALERTS_URL)
+        .query(&[("point", format!("{:.4},{:.4}", location.lat, location.lon))])
+        .header(reqwest::header::ACCEPT, "application/geo+json")
+        .send()
+        .await
+        .map_err(|e| format!("NWS request failed: {e}"))?;
+    // Points outside the NWS's area are rejected rather than answered.
+    if response.status().is_client_error() {
+        return Ok(Vec::new());
+    }
+    let text = response
+        .error_for_status()
+        .map_err(|e| format!("NWS request failed: {e}"))?
+        .text()
+        .await
+        .map_err(|e| format!("NWS request failed: {e}"))?;
+    parse_alerts(&text)
+}
+
+/// Fetch whatever has gone stale (everything with `force`), announce new
+/// severe alerts and return the result.
+async fn refresh(app: &AppHandle, force: bool) -> Result<Vec<LocationWeather>, String> {
+    let state = app.state::<WeatherState>();
+    let _refreshing = state.refreshing.lock().await;
+    if !crate::network::is_online(app) {
+        return Ok(state.snapshot());
+    }
+    let client = state.client()?;
+    let mut severe = Vec::new();
+    for location in state.locations() {
+        let key = cache_key(&location);
+        let now = Instant::now();
+        let (conditions_stale, alerts_stale) = {
+            let cache = state.cache.lock().unwrap_or_else(|e| e.into_inner());
+            let entry = cache.get(&key);
+            (
+                force || !entry.and_then(|e| e.conditions.as_ref()).is_some_and(|c| c.fresh(CONDITIONS_TTL, now)),
+                force || !entry.and_then(|e| e.alerts.as_ref()).is_some_and(|a| a.fresh(ALERTS_TTL, now)),
+            )
+        };
+        let conditions = if conditions_stale { Some(fetch_conditions(client, &location).await) } else { None };
+        let alerts = if alerts_stale { Some(fetch_alerts(client, &location).await) } else { None };
+
+        let fetched = Instant::now();
+        let mut cache = state.cache.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = cache.entry(key).or_default();
+        let mut errors = Vec::new();
+        match conditions {
+            Some(Ok(value)) => entry.conditions = Some(Cached { value, fetched }),
+            Some(Err(err)) => errors.push(err),
+            None => {}
+        }
+        match alerts {
+            Some(Ok(value)) => {
+                let mut announced = state.announced.lock().unwrap_or_else(|e| e.into_inner());
+                severe.extend(value.iter().filter(|a| a.is_severe() && announced.insert(a.id.clone())).map(|alert| {
+                    SevereAlert {
+                        location_id: location.id.clone(),
+                        location_name: location.name.clone(),
+                        alert: alert.clone(),
+                    }
+                }));
+                entry.alerts = Some(Cached { value, fetched });
+            }
+            Some(Err(err)) => errors.push(err),
+            None => {}
+        }
+        if conditions_stale || alerts_stale {
+            entry.error = (!errors.is_empty()).then(|| errors.join("; "));
+        }
+        if let Some(err) = &entry.error {
+            tracing::warn!(target: "app", location = %location.name, "weather update failed: {err}");
+        }
+    }
+    // Forget places that are no longer pinned.
+    let keys: HashSet<String> = state.locations().iter().map(cache_key).collect();
+    state.cache.lock().unwrap_or_else(|e| e.into_inner()).retain(|key, _| keys.contains(key));
+    if !severe.is_empty() {
+        let _ = app.emit(ALERT_EVENT, &severe);
+    }
+    Ok(state.snapshot())
+}
+
+struct Poller {
+    app: AppHandle,
+}
+
+impl crate::poll::Poller for Poller {
+    async fn round(&mut self) -> Duration {
+        if !self.app.state::<WeatherState>().locations().is_empty() {
+            match refresh(&self.app, false).await {
+                Ok(weather) => {
+                    let _ = self.app.emit(UPDATED_EVENT, weather);
+                }
+                Err(err) => tracing::warn!(target: "app", "weather update failed: {err}"),
+            }
+        }
+        ALERTS_TTL
+    }
+}
+
+/// Refresh now and then every `ALERTS_TTL`, emitting `weather://updated`.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move { crate::poll::run(&app, Poller { app: app.clone() }).await });
+}
+
+/// Weather for every pinned place, fetching what is stale (or everything
+/// with `refresh`).
+#[tauri::command]
+pub async fn get_weather(webview: Webview, app: AppHandle, refresh: Option<bool>) -> Result<Vec<LocationWeather>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("get_weather", async move {
+            crate::require_trusted_window(webview.label())?;
+            self::refresh(&app, refresh.unwrap_or(false)).await
+        })
+        .await
+}
+
+#[tauri::command]
+pub fn get_weather_locations(
+    webview: Webview,
+    state: tauri::State<'_, WeatherState>,
+) -> Result<Vec<WeatherLocation>, String> {
+    crate::metrics::of(&webview).observe("get_weather_locations", || {
+        crate::require_trusted_window(webview.label())?;
+        Ok(state.locations())
+    })
+}
+
+/// Replace the pinned places and return their weather.
+#[tauri::command]
+pub async fn set_weather_locations(
+    webview: Webview,
+    app: AppHandle,
+    locations: Vec<WeatherLocation>,
+) -> Result<Vec<LocationWeather>, String> {
+    crate::metrics::of(&webview)
+        .observe_async("set_weather_locations", async move {
+            crate::require_trusted_window(webview.label())?;
+            validate(&locations)?;
+            let state = app.state::<WeatherState>();
+            state.persist(&locations)?;
+            *state.locations.lock().unwrap_or_else(|e| e.into_inner()) = locations;
+            refresh(&app, false).await
+        })
+        .await
+}
+
+fn validate(locations: &[WeatherLocation]) -> Result<(), String> {
+    if locations.len() > MAX_LOCATIONS {
+        return Err(format!("At most {MAX_LOCATIONS} locations"));
+    }
+    let mut ids = HashSet::new();
+    for location in locations {
+        if location.id.is_empty() || !ids.insert(location.id.as_str()) {
+            return Err("Location ids must be unique and non-empty".to_string());
+        }
+        if location.name.len() > MAX_NAME_LEN {
+            return Err(format!("Location names are at most {MAX_NAME_LEN} characters"));
+        }
+        if !(-90.0..=90.0).contains(&location.lat) || !(-180.0..=180.0).contains(&location.lon) {
+            return Err("Location coordinates out of range".to_string());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod weather_tests {
+    use super::*;
+
+    #[test]
+    fn parses_open_meteo_conditions() {
+        let text = r#"{
+            "latitude": 51.5, "longitude": -0.12,
+            "current": {
+                "time": 1773489600, "interval": 900, "temperature_2m": 11.4, "apparent_temperature": 9.8,
+                "relative_humidity_2m": 81, "precipitation": 0.2, "weather_code": 61, "cloud_cover": 100,
+                "pressure_msl": 1008.3, "wind_speed_10m": 18.7, "wind_direction_10m": 225, "wind_gusts_10m": 38.2,
+                "is_day": 1
+            }
+        }"#;
+        let conditions = parse_conditions(text).unwrap();
+        assert_eq!(conditions.time, 1773489600000);
+        assert_eq!((conditions.temperature, conditions.humidity), (11.4, 81.0));
+        assert_eq!((conditions.weather_code, conditions.description), (61, "Rain"));
+        assert!(conditions.is_day);
+        assert!(parse_conditions(r#"{"error": true, "reason": "bad"}"#).is_err());
+    }
+
+    #[test]
+    fn parses_nws_alerts() {
+        let text = r#"{
+            "features": [
+                {
+                    "properties": {
+                        "id": "urn:oid:2.49.0.1.840.0.abc", "event": "Tornado Warning", "severity": "Extreme",
+                        "urgency": "Immediate", "headline": "Tornado Warning issued March 14 at 3:02PM CDT",
+                        "description": "At 302 PM CDT, a confirmed tornado was located near Moore.",
+                        "areaDesc": "Cleveland, OK", "onset": "2026-03-14T15:02:00-05:00",
+                        "expires": "2026-03-14T15:45:00-05:00"
+                    }
+                },
+                {
+                    "properties": {
+                        "id": "urn:oid:2.49.0.1.840.0.def", "event": "Frost Advisory", "severity": "Minor",
+                        "headline": null, "areaDesc": "Logan, OK", "onset": null, "expires": null
+                    }
+                }
+            ]
+        }"#;
+        let alerts = parse_alerts(text).unwrap();
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].onset, Some(1773518520000));
+        assert!(alerts[0].is_severe());
+        assert_eq!(alerts[1].headline, "Frost Advisory");
+        assert!(!alerts[1].is_severe());
+    }
+
+    #[test]
+    fn validates_locations() {
+        let location = |id: &str, lat| WeatherLocation { id: id.to_string(), name: "Here".to_string(), lat, lon: 0.0 };
+        assert!(validate(&[location("a", 10.0), location("b", -10.0)]).is_ok());
+        assert!(validate(&[location("a", 10.0), location("a", -10.0)]).is_err());
+        assert!(validate(&[location("a", 100.0)]).is_err());
+        assert_eq!(cache_key(&location("a", 51.50739)), "51.51,0.00");
+    }
+}
@@ -0,0 +1,323 @@
+//! Outbound webhooks for fired alerts. Each rule's `delivery.webhooks` (and
+//! the older single `delivery.webhook`) get a POST per alert, with the
+//! `FiredAlert` as the body, or the target's `template` filled in from it.
+//!
+//! Templates are any JSON; `{{ruleId}}`, `{{ruleName}}`, `{{title}}`,
+//! `{{body}}`, `{{subject}}` and `{{firedAt}}` in its strings are replaced,
+//! and a string that is just one placeholder takes the value's own type.
+//!
+//! With `ALERT_WEBHOOK_SECRET` stored, requests carry
+//! `X-WorldMonitor-Timestamp` and `X-WorldMonitor-Signature:
+//! sha256=<hex>`, an HMAC-SHA256 of `<timestamp>.<body>` with the secret,
+//! for receivers to verify. `X-WorldMonitor-Delivery` stays the same across
+//! retries. Network errors, 429s and 5xx responses are retried up to
+//! `MAX_ATTEMPTS` times, backing off from 5 seconds (or as long as
+//! `Retry-After` asks, within reason). The last `MAX_LOG` deliveries are
+//! kept for `get_webhook_deliveries`.
+
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tauri::{AppHandle, Manager, Webview};
+
+use crate::alerts::FiredAlert;
+
+const SECRET: &str = "ALERT_WEBHOOK_SECRET";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_ATTEMPTS: u32 = 5;
+const RETRY_MIN: Duration = Duration::from_secs(5);
+const RETRY_MAX: Duration = Duration::from_secs(5 * 60);
+const MAX_LOG: usize = 200;
+/// Targets per rule.
+pub const MAX_TARGETS: usize = 5;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookTarget {
+    pub url: String,
+    /// The body to send instead of the alert; see the module docs.
+    #[serde(default)]
+    pub template: Option<Value>,
+}
+
+impl WebhookTarget {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.url.starts_with("https://") || self.url.starts_with("http://")) {
+            return Err("Webhook URL must be http(s)".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryState {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+/// One alert sent to one target, as `get_webhook_deliveries` returns it.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub rule_id: String,
+    pub rule_name: String,
+    pub url: String,
+    pub state: DeliveryState,
+    pub attempts: u32,
+    /// The last response's status, if there was one.
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    /// Seconds since the epoch.
+    pub started_at: u64,
+    pub updated_at: u64,
+}
+
+#[derive(Default)]
+pub struct WebhooksState {
+    log: Mutex<VecDeque<WebhookDelivery>>,
+    client: OnceLock<reqwest::Client>,
+}
+
+impl WebhooksState {
+    fn client(&self) -> Result<&reqwest::Client, String> {
+        if let Some(client) = self.client.get() {
+            return Ok(client);
+        }
+        let client = reqwest::Client::builder()
+            .use_native_tls()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+        Ok(self.client.get_or_init(|| client))
+    }
+}
+
+fn update(app: &AppHandle, id: &str, f: impl FnOnce(&mut WebhookDelivery)) {
+    let state = app.state::<WebhooksState>();
+    let mut log = state.log.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(entry) = log.iter_mut().find(|entry| entry.id == id) {
+        f(entry);
+        entry.updated_at = crate::unix_timestamp_secs();
+    }
+}
+
+/// `template` with the alert's fields in place of its placeholders.
+fn render(template: &Value, alert: &FiredAlert) -> Value {
+    let fields = [
+        ("ruleId", json!(alert.rule_id)),
+        ("ruleName", json!(alert.rule_name)),
+        ("title", json!(alert.title)),
+        ("body", json!(alert.body)),
+        ("subject", json!(alert.subject)),
+        ("firedAt", json!(alert.fired_at)),
+    ];
+    match template {
+        Value::String(text) => {
+            if let Some((_, value)) = fields.iter().find(|(name, _)| *text == format!("{{{{{name}}}}}")) {
+                return value.clone();
+            }
+            let filled = fields.iter().fold(text.clone(), |text, (name, value)| {
+                let value = value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string());
+                text.replace(&format!("{{{{{name}}}}}"), &value)
+            });
+            Value::String(filled)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(|item| render(item, alert)).collect()),
+        Value::Object(entries) => {
+            Value::Object(entries.iter().map(|(key, value)| (key.clone(), render(value, alert))).collect())
+        }
+        other => other.clone(),
+    }
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().into()
+}
+
+fn signature(secret: &str, timestamp: u64, body: &[u8]) -> String {
+    let signed = [format!("{timestamp}.").as_bytes(), body].concat();
+    let mac = hmac_sha256(secret.as_bytes(), &signed);
+    format!("sha256={}", mac.iter().map(|b| format!("{b:02x}")).collect::<String>())
+}
+
+/// How long `Retry-After` asks for, in seconds, kept within the backoff
+/// bounds.
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    let secs: u64 = response.headers().get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim().parse().ok()?;
+    Some(Duration::from_secs(secs).clamp(RETRY_MIN, RETRY_MAX))
+}
+
+enum Outcome {
+    Delivered(u16),
+    Retry(Option<u16>, String, Option<Duration>),
+    Failed(u16, String),
+}
+
+async fn attempt(client: &reqwest::Client, id: &str, url: &str, body: &[u8], secret: Option<&str>) -> Outcome {
+    let mut request = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header("X-WorldMonitor-Delivery", id)
+        .body(body.to_vec());
+    if let Some(secret) = secret {
+        let timestamp = crate::unix_timestamp_secs();
+        request = request
+            .header("X-WorldMonitor-Timestamp", timestamp.to_string())
+            .header("X-WorldMonitor-Signature", signature(secret, timestamp, body));
+    }
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(err) => return Outcome::Retry(None, err.to_string(), None),
+    };
+    let status = response.status();
+    if status.is_success() {
+        Outcome::Delivered(status.as_u16())
+    } else if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        Outcome::Retry(Some(status.as_u16()), format!("HTTP {status}"), retry_after(&response))
+    } else {
+        Outcome::Failed(status.as_u16(), format!("HTTP {status}"))
+    }
+}
+
+async fn deliver(app: AppHandle, client: reqwest::Client, id: String, url: String, body: Vec<u8>) {
+    let secret = app.state::<crate::SecretsCache>().get(SECRET);
+    let mut backoff = RETRY_MIN;
+    for attempts in 1..=MAX_ATTEMPTS {
+        let outcome = attempt(&client, &id, &url, &body, secret.as_deref()).await;
+        let (state, status, error, wait) = match outcome {
+            Outcome::Delivered(status) => (DeliveryState::Delivered, Some(status), None, None),
+            Outcome::Failed(status, error) => (DeliveryState::Failed, Some(status), Some(error), None),
+            Outcome::Retry(status, error, _) if attempts == MAX_ATTEMPTS => (DeliveryState::Failed, status, Some(error), None),
+            Outcome::Retry(status, error, wait) => (DeliveryState::Pending, status, Some(error), Some(wait.unwrap_or(backoff))),
+        };
+        if let (DeliveryState::Failed, Some(error)) = (state, &error) {
+            tracing::warn!(target: "app", %url, attempts, "alert webhook failed: {error}");
+        }
+        update(&app, &id, |entry| {
+            entry.state = state;
+            entry.attempts = attempts;
+            entry.status = status;
+            entry.error = error;
+        });
+        let Some(wait) = wait else {
+            return;
+        };
+        tokio::time::sleep(wait).await;
+        backoff = (backoff * 2).min(RETRY_MAX);
+    }
+}
+
+/// POST `alert` to each target in the background.
+pub fn dispatch(app: &AppHandle, alert: &FiredAlert, targets: Vec<WebhookTarget>) {
+    if targets.is_empty() {
+        return;
+    }
+    let state = app.state::<WebhooksState>();
+    let client = match state.client() {
+        Ok(client) => client.clone(),
+        Err(err) => return tracing::warn!(target: "app", "{err}"),
+    };
+    for target in targets {
+        let mut buf = [0u8; 8];
+        if getrandom::getrandom(&mut buf).is_err() {
+            continue;
+        }
+        let id: String = buf.iter().map(|b| format!("{b:02x}")).collect();
+        let body = match &target.template {
+            Some(template) => render(template, alert).to_string(),
+            None => json!(alert).to_string(),
+        };
+        let now = crate::unix_timestamp_secs();
+        {
+            let mut log = state.log.lock().unwrap_or_else(|e| e.into_inner());
+            if log.len() == MAX_LOG {
+                log.pop_front();
+            }
+            log.push_back(WebhookDelivery {
+                id: id.clone(),
+                rule_id: alert.rule_id.clone(),
+                rule_name: alert.rule_name.clone(),
+                url: target.url.clone(),
+                state: DeliveryState::Pending,
+                attempts: 0,
+                status: None,
+                error: None,
+                started_at: now,
+                updated_at: now,
+            });
+        }
+        tauri::async_runtime::spawn(deliver(app.clone(), client.clone(), id, target.url, body.into_bytes()));
+    }
+}
+
+/// Recent deliveries, newest first, optionally for one rule only.
+#[tauri::command]
+pub fn get_webhook_deliveries(
+    webview: Webview,
+    state: tauri::State<'_, WebhooksState>,
+    rule_id: Option<String>,
+) -> Result<Vec<WebhookDelivery>, String> {
+    crate::metrics::of(&webview).observe("get_webhook_deliveries", || {
+        crate::require_trusted_window(webview.label())?;
+        let log = state.log.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(log
+            .iter()
+            .rev()
+            .filter(|entry| rule_id.as_ref().is_none_or(|rule_id| entry.rule_id == *rule_id))
+            .cloned()
+            .collect())
+    })
+}
+
+#[cfg(test)]
+mod webhooks_tests {
+    use super::*;
+
+    #[test]
+    fn signs_with_hmac_sha256() {
+        // RFC 4231, test case 2.
+        let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        let hex: String = mac.iter().map(|b| format!("{b:02x}")).collect();
+        assert_eq!(hex, "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+        // Keys longer than a block are hashed first (test case 6).
+        let mac = hmac_sha256(&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First");
+        assert_eq!(mac[..4], [0x60, 0xe4, 0x31, 0x59]);
+        assert_eq!(signature("Jefe", 1, b"{}"), signature("Jefe", 1, b"{}"));
+        assert_ne!(signature("Jefe", 1, b"{}"), signature("Jefe", 2, b"{}"));
+    }
+
+    #[test]
+    fn fills_templates_from_the_alert() {
+        let alert = FiredAlert {
+            rule_id: "r1".to_string(),
+            rule_name: "Hormuz".to_string(),
+            title: "Vessel entered Hormuz".to_string(),
+            body: "EVER GIVEN \"fast\"".to_string(),
+            subject: "353136000".to_string(),
+            fired_at: 1_700_000_000,
+        };
+        let template = json!({
+            "text": "{{title}}: {{body}}",
+            "at": "{{firedAt}}",
+            "blocks": [{ "rule": "{{ruleName}} ({{ruleId}})", "n": 3 }],
+        });
+        assert_eq!(
+            render(&template, &alert),
+            json!({
+                "text": "Vessel entered Hormuz: EVER GIVEN \"fast\"",
+                "at": 1_700_000_000,
+                "blocks": [{ "rule": "Hormuz (r1)", "n": 3 }],
+            })
+        );
+    }
+}
@@ -0,0 +1,390 @@
+//! Window geometry persistence. The main, settings, panel, and map display
+//! windows' size,
+//! position, maximized state, monitor, always-on-top flag, and zoom factor are
+//! saved to `window-state.json` in the app data dir when they close (and on
+//! exit), then reapplied the next time they open. A saved position that no
+//! longer overlaps any connected monitor (e.g. an unplugged external display)
+//! is replaced by centering the window on its old monitor if present,
+//! otherwise the primary one.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Monitor, PhysicalPosition, PhysicalSize, WebviewWindow};
+
+pub const WINDOW_STATE_FILE: &str = "window-state.json";
+const TRACKED_WINDOWS: [&str; 3] = ["main", "settings", crate::map_display::MAP_DISPLAY_WINDOW];
+
+fn is_tracked(label: &str) -> bool {
+    TRACKED_WINDOWS.contains(&label) || crate::panel_windows::is_panel_window(label)
+}
+/// How much of a saved window (in physical px, each axis) must overlap a
+/// connected monitor for its position to be reused.
+const MIN_VISIBLE_PX: i64 = 64;
+const ZOOM_STEP: f64 = 0.1;
+const MIN_ZOOM: f64 = 0.5;
+const MAX_ZOOM: f64 = 3.0;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    /// Outer position, physical px.
+    x: i32,
+    y: i32,
+    /// Inner size, physical px.
+    width: u32,
+    height: u32,
+    maximized: bool,
+    monitor: Option<String>,
+    #[serde(default)]
+    always_on_top: bool,
+    /// Webview zoom factor, 1.0 = 100%.
+    #[serde(default = "default_zoom")]
+    zoom: f64,
+}
+
+fn default_zoom() -> f64 {
+    1.0
+}
+
+/// `current` moved by `steps` zoom increments, clamped and snapped to the
+/// step so repeated in/out presses return exactly to 100%.
+fn step_zoom(current: f64, steps: i32) -> f64 {
+    let next = ((current / ZOOM_STEP).round() + f64::from(steps)) * ZOOM_STEP;
+    ((next.clamp(MIN_ZOOM, MAX_ZOOM) * 100.0).round()) / 100.0
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Area {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+impl Area {
+    fn of(monitor: &Monitor) -> Self {
+        Area {
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+        }
+    }
+
+    fn overlap(&self, other: &Area) -> (i64, i64) {
+        let span = |a: i32, a_len: u32, b: i32, b_len: u32| {
+            let start = i64::from(a.max(b));
+            let end = (i64::from(a) + i64::from(a_len)).min(i64::from(b) + i64::from(b_len));
+            (end - start).max(0)
+        };
+        (
+            span(self.x, self.width, other.x, other.width),
+            span(self.y, self.height, other.y, other.height),
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Placement {
+    position: (i32, i32),
+    size: (u32, u32),
+}
+
+/// Where to put a window with `saved` geometry given the connected
+/// `monitors` (named) and the primary monitor. `None` when there is no
+/// monitor to place it on, in which case the platform default is kept.
+fn place(saved: &WindowGeometry, monitors: &[(Option<String>, Area)], primary: Option<Area>) -> Option<Placement> {
+    let window = Area {
+        x: saved.x,
+        y: saved.y,
+        width: saved.width,
+        height: saved.height,
+    };
+    let reachable = monitors.iter().any(|(_, area)| {
+        let (w, h) = window.overlap(area);
+        w >= MIN_VISIBLE_PX && h >= MIN_VISIBLE_PX
+    });
+    if reachable {
+        return Some(Placement {
+            position: (saved.x, saved.y),
+            size: (saved.width, saved.height),
+        });
+    }
+    let target = monitors
+        .iter()
+        .find(|(name, _)| name.is_some() && *name == saved.monitor)
+        .map(|(_, area)| *area)
+        .or(primary)
+        .or_else(|| monitors.first().map(|(_, area)| *area))?;
+    let width = saved.width.min(target.width);
+    let height = saved.height.min(target.height);
+    let centered = |origin: i32, outer: u32, inner: u32| origin.saturating_add(i32::try_from((outer - inner) / 2).unwrap_or(0));
+    Some(Placement {
+        position: (centered(target.x, target.width, width), centered(target.y, target.height, height)),
+        size: (width, height),
+    })
+}
+
+/// Managed state: saved geometry per window label.
+pub struct WindowStateStore {
+    path: PathBuf,
+    windows: Mutex<BTreeMap<String, WindowGeometry>>,
+}
+
+impl WindowStateStore {
+    /// Load `window-state.json`; a missing or unreadable file starts empty.
+    pub fn load(path: PathBuf) -> Self {
+        let windows = fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| match serde_json::from_str(&contents) {
+                Ok(windows) => Some(windows),
+                Err(e) => {
+                    tracing::warn!(target: "app", "ignoring invalid window state {}: {e}", path.display());
+                    None
+                }
+            })
+            .unwrap_or_default();
+        WindowStateStore {
+            path,
+            windows: Mutex::new(windows),
+        }
+    }
+
+    fn get(&self, label: &str) -> Option<WindowGeometry> {
+        self.windows.lock().unwrap_or_else(|e| e.into_inner()).get(label).cloned()
+    }
+
+    fn set(&self, label: &str, geometry: WindowGeometry) -> Result<(), String> {
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        if windows.get(label) == Some(&geometry) {
+            return Ok(());
+        }
+        windows.insert(label.to_string(), geometry);
+        write(&self.path, &windows)
+    }
+}
+
+fn write(path: &Path, windows: &BTreeMap<String, WindowGeometry>) -> Result<(), String> {
+    let serialized =
+        serde_json::to_string_pretty(windows).map_err(|e| format!("Failed to serialize window state: {e}"))?;
+    fs::write(path, serialized).map_err(|e| format!("Failed to write window state {}: {e}", path.display()))
+}
+
+/// Apply saved geometry to a tracked window. Call before showing it.
+pub fn restore(window: &WebviewWindow) {
+    let label = window.label();
+    let Some(saved) = window
+        .app_handle()
+        .try_state::<WindowStateStore>()
+        .and_then(|store| store.get(label))
+    else {
+        return;
+    };
+    if saved.always_on_top {
+        let _ = window.set_always_on_top(true);
+    }
+    if saved.zoom != 1.0 {
+        let _ = window.set_zoom(saved.zoom);
+    }
+    let monitors: Vec<(Option<String>, Area)> = window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|m| (m.name().cloned(), Area::of(m)))
+        .collect();
+    let primary = window.primary_monitor().ok().flatten().map(|m| Area::of(&m));
+    let Some(placement) = place(&saved, &monitors, primary) else {
+        return;
+    };
+    if placement.position != (saved.x, saved.y) {
+        tracing::info!(
+            target: "app",
+            window = label,
+            monitor = saved.monitor.as_deref(),
+            "saved window position is off-screen; re-centering"
+        );
+    }
+    let _ = window.set_size(PhysicalSize::new(placement.size.0, placement.size.1));
+    let _ = window.set_position(PhysicalPosition::new(placement.position.0, placement.position.1));
+    if saved.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// Current geometry of `window`, carrying over `previous` where the live
+/// values are unusable: a minimized window keeps its last bounds entirely,
+/// and a maximized one keeps its last normal bounds so unmaximizing after a
+/// restore lands somewhere sensible.
+fn capture(window: &WebviewWindow, previous: Option<WindowGeometry>) -> Option<WindowGeometry> {
+    let always_on_top = previous.as_ref().is_some_and(|p| p.always_on_top);
+    let zoom = previous.as_ref().map_or(1.0, |p| p.zoom);
+    if window.is_minimized().unwrap_or(false) {
+        return previous;
+    }
+    let maximized = window.is_maximized().unwrap_or(false);
+    match previous {
+        Some(previous) if maximized => Some(WindowGeometry { maximized, ..previous }),
+        _ => {
+            let position = window.outer_position().ok()?;
+            let size = window.inner_size().ok()?;
+            Some(WindowGeometry {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+                maximized,
+                monitor: window.current_monitor().ok().flatten().and_then(|m| m.name().cloned()),
+                always_on_top,
+                zoom,
+            })
+        }
+    }
+}
+
+/// Record a tracked window's current geometry.
+pub fn save(app: &AppHandle, label: &str) {
+    if !is_tracked(label) {
+        return;
+    }
+    let (Some(window), Some(store)) = (app.get_webview_window(label), app.try_state::<WindowStateStore>()) else {
+        return;
+    };
+    let Some(geometry) = capture(&window, store.get(label)) else {
+        return;
+    };
+    if let Err(err) = store.set(label, geometry) {
+        tracing::warn!(target: "app", "{err}");
+    }
+}
+
+/// Whether geometry has been saved for `label` before.
+pub fn has_saved(app: &AppHandle, label: &str) -> bool {
+    app.try_state::<WindowStateStore>()
+        .is_some_and(|store| store.get(label).is_some())
+}
+
+/// Whether `label` was last set to stay on top.
+pub fn is_always_on_top(app: &AppHandle, label: &str) -> bool {
+    app.try_state::<WindowStateStore>()
+        .and_then(|store| store.get(label))
+        .is_some_and(|saved| saved.always_on_top)
+}
+
+/// Persist a per-window preference alongside the current geometry of a
+/// tracked window; untracked windows are left alone.
+fn update_saved(window: &WebviewWindow, update: impl FnOnce(&mut WindowGeometry)) -> Result<(), String> {
+    let label = window.label();
+    let Some(store) = window.app_handle().try_state::<WindowStateStore>() else {
+        return Ok(());
+    };
+    if !is_tracked(label) {
+        return Ok(());
+    }
+    match capture(window, store.get(label)) {
+        Some(mut geometry) => {
+            update(&mut geometry);
+            store.set(label, geometry)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Pin or unpin `window` above other windows, persisting the choice for
+/// tracked windows.
+pub fn set_always_on_top(window: &WebviewWindow, enabled: bool) -> Result<(), String> {
+    window
+        .set_always_on_top(enabled)
+        .map_err(|e| format!("Failed to set always on top: {e}"))?;
+    update_saved(window, |geometry| geometry.always_on_top = enabled)
+}
+
+/// The zoom factor last applied to `label`, 1.0 if never changed.
+fn zoom_of(app: &AppHandle, label: &str) -> f64 {
+    app.try_state::<WindowStateStore>()
+        .and_then(|store| store.get(label))
+        .map_or(1.0, |saved| saved.zoom)
+}
+
+/// Zoom `window` in (`steps > 0`), out (`steps < 0`), or back to 100%
+/// (`None`), persisting the factor for tracked windows.
+pub fn zoom(window: &WebviewWindow, steps: Option<i32>) -> Result<(), String> {
+    let factor = match steps {
+        Some(steps) => step_zoom(zoom_of(window.app_handle(), window.label()), steps),
+        None => 1.0,
+    };
+    window.set_zoom(factor).map_err(|e| format!("Failed to set zoom: {e}"))?;
+    tracing::debug!(target: "app", window = window.label(), factor, "zoom changed");
+    update_saved(window, |geometry| geometry.zoom = factor)
+}
+
+/// Save every tracked window that is still open (on exit).
+pub fn save_all(app: &AppHandle) {
+    for label in app.webview_windows().keys() {
+        save(app, label);
+    }
+}
+
+#[cfg(test)]
+mod window_state_tests {
+    use super::{place, step_zoom, Area, Placement, WindowGeometry};
+
+    fn geometry(x: i32, y: i32, monitor: Option<&str>) -> WindowGeometry {
+        WindowGeometry {
+            x,
+            y,
+            width: 1440,
+            height: 900,
+            maximized: false,
+            monitor: monitor.map(str::to_string),
+            always_on_top: false,
+            zoom: 1.0,
+        }
+    }
+
+    const LAPTOP: Area = Area { x: 0, y: 0, width: 1920, height: 1080 };
+    const EXTERNAL: Area = Area { x: 1920, y: 0, width: 2560, height: 1440 };
+
+    #[test]
+    fn keeps_position_on_connected_monitor() {
+        let monitors = [(Some("laptop".to_string()), LAPTOP), (Some("external".to_string()), EXTERNAL)];
+        let saved = geometry(2200, 100, Some("external"));
+        assert_eq!(
+            place(&saved, &monitors, Some(LAPTOP)),
+            Some(Placement { position: (2200, 100), size: (1440, 900) })
+        );
+    }
+
+    #[test]
+    fn recenters_when_monitor_is_gone() {
+        let monitors = [(Some("laptop".to_string()), LAPTOP)];
+        let saved = geometry(2200, 100, Some("external"));
+        assert_eq!(
+            place(&saved, &monitors, Some(LAPTOP)),
+            Some(Placement { position: (240, 90), size: (1440, 900) })
+        );
+    }
+
+    #[test]
+    fn barely_visible_window_is_recentered_and_shrunk() {
+        let small = Area { x: 0, y: 0, width: 1280, height: 800 };
+        let monitors = [(Some("laptop".to_string()), small)];
+        let saved = geometry(1250, 770, Some("laptop"));
+        assert_eq!(
+            place(&saved, &monitors, None),
+            Some(Placement { position: (0, 0), size: (1280, 800) })
+        );
+        assert_eq!(place(&saved, &[], None), None);
+    }
+
+    #[test]
+    fn zoom_steps_snap_and_clamp() {
+        assert_eq!(step_zoom(1.0, 1), 1.1);
+        assert_eq!(step_zoom(step_zoom(1.0, 3), -3), 1.0);
+        assert_eq!(step_zoom(1.04, 0), 1.0);
+        assert_eq!(step_zoom(0.5, -1), 0.5);
+        assert_eq!(step_zoom(3.0, 2), 3.0);
+    }
+}
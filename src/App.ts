@@ -22,6 +22,14 @@ import { preloadCountryGeometry, getCountryNameByCode } from '@/services/country
 import { initI18n } from '@/services/i18n';
 
 import { DesktopUpdater } from '@/app/desktop-updater';
+import { DesktopNavigationHandler } from '@/app/desktop-navigation';
+import { AlertStatusReporter } from '@/app/alert-status-reporter';
+import { MonitoringPauseHandler } from '@/app/monitoring-pause';
+import { NetworkStatusHandler } from '@/app/network-status';
+import { SystemIdleHandler } from '@/app/system-idle';
+import { PowerStatusHandler } from '@/app/power-status';
+import { TimezoneChangeHandler } from '@/app/timezone-change';
+import { FileImportHandler } from '@/app/file-import';
 import { CountryIntelManager } from '@/app/country-intel';
 import { SearchManager } from '@/app/search-manager';
 import { RefreshScheduler } from '@/app/refresh-scheduler';
@@ -44,6 +52,14 @@ export class App {
   private countryIntel: CountryIntelManager;
   private refreshScheduler: RefreshScheduler;
   private desktopUpdater: DesktopUpdater;
+  private desktopNavigation: DesktopNavigationHandler;
+  private alertStatusReporter: AlertStatusReporter;
+  private monitoringPause: MonitoringPauseHandler;
+  private networkStatus: NetworkStatusHandler;
+  private systemIdle: SystemIdleHandler;
+  private powerStatus: PowerStatusHandler;
+  private timezoneChange: TimezoneChangeHandler;
+  private fileImport: FileImportHandler;
 
   private modules: { destroy(): void }[] = [];
   private unsubAiFlow: (() => void) | null = null;
@@ -249,6 +265,23 @@ export class App {
     this.refreshScheduler = new RefreshScheduler(this.state);
     this.countryIntel = new CountryIntelManager(this.state);
     this.desktopUpdater = new DesktopUpdater(this.state);
+    this.desktopNavigation = new DesktopNavigationHandler(this.state);
+    this.alertStatusReporter = new AlertStatusReporter(this.state);
+    this.monitoringPause = new MonitoringPauseHandler(this.state, {
+      setPaused: (paused) => this.refreshScheduler.setPaused(paused),
+    });
+    this.networkStatus = new NetworkStatusHandler(this.state, {
+      setOffline: (offline) => this.refreshScheduler.setOffline(offline),
+    });
+    this.systemIdle = new SystemIdleHandler(this.state, {
+      setIdle: (idle) => this.refreshScheduler.setSystemIdle(idle),
+    });
+    this.powerStatus = new PowerStatusHandler(this.state, {
+      setBatteryMultiplier: (multiplier) => this.refreshScheduler.setBatteryMultiplier(multiplier),
+    });
+    this.timezoneChange = new TimezoneChangeHandler(this.state, {
+      loadAllData: () => this.dataLoader.loadAllData(),
+    });
 
     this.dataLoader = new DataLoaderManager(this.state, {
       renderCriticalBanner: (postures) => this.panelLayout.renderCriticalBanner(postures),
@@ -274,11 +307,23 @@ export class App {
       syncDataFreshnessWithLayers: () => this.dataLoader.syncDataFreshnessWithLayers(),
     });
 
+    this.fileImport = new FileImportHandler(this.state, {
+      showToast: (message) => this.eventHandlers.showToast(message),
+    });
+
     // Wire cross-module callback: DataLoader → SearchManager
     this.dataLoader.updateSearchIndex = () => this.searchManager.updateSearchIndex();
 
     // Track destroy order (reverse of init)
     this.modules = [
+      this.fileImport,
+      this.networkStatus,
+      this.systemIdle,
+      this.powerStatus,
+      this.timezoneChange,
+      this.monitoringPause,
+      this.alertStatusReporter,
+      this.desktopNavigation,
       this.desktopUpdater,
       this.panelLayout,
       this.countryIntel,
@@ -387,9 +432,17 @@ export class App {
     this.eventHandlers.setupSnapshotSaving();
     cleanOldSnapshots().catch((e) => console.warn('[Storage] Snapshot cleanup failed:', e));
 
-    // Phase 8: Deep links + update checks
+    // Phase 8: Deep links, update checks, desktop alert status, pause, connectivity, idle, power, timezone and file drops
     this.handleDeepLinks();
     this.desktopUpdater.init();
+    void this.desktopNavigation.init();
+    this.alertStatusReporter.init();
+    void this.monitoringPause.init();
+    void this.networkStatus.init();
+    void this.systemIdle.init();
+    void this.powerStatus.init();
+    void this.timezoneChange.init();
+    void this.fileImport.init();
 
     // Analytics
     trackEvent('wm_app_loaded', {
@@ -0,0 +1,79 @@
+import type { AppContext, AppModule } from '@/app/app-context';
+import { getRecentAlerts, type AlertPriority, type UnifiedAlert } from '@/services/cross-module-integration';
+import { isDesktopRuntime } from '@/services/runtime';
+import { tryInvokeTauri } from '@/services/tauri-bridge';
+
+/** Mirrors `alert_status::AlertStatus` on the Rust side. */
+interface AlertStatus {
+  alerts: number;
+  critical: number;
+  headlines: number;
+  breaking: number;
+}
+
+const REPORT_INTERVAL_MS = 15_000;
+const SEVERITY_ORDER: AlertPriority[] = ['low', 'medium', 'high', 'critical'];
+const isHighPriority = (priority: AlertPriority) => priority === 'critical' || priority === 'high';
+
+/**
+ * Reports live alert and headline counts to the desktop shell, which feeds
+ * surfaces outside the dashboard (the ticker window), badges the alert count
+ * and severity on the dock, taskbar and tray, and asks for the user's
+ * attention (taskbar flash, dock bounce) when a high-priority alert fires.
+ */
+export class AlertStatusReporter implements AppModule {
+  private ctx: AppContext;
+  private timer: ReturnType<typeof setInterval> | null = null;
+  private lastReported = '';
+  private lastBadge = '';
+  /** Ids of alerts already seen at high priority; null until the first report. */
+  private seenHighPriority: Set<string> | null = null;
+
+  constructor(ctx: AppContext) {
+    this.ctx = ctx;
+  }
+
+  init(): void {
+    if (!isDesktopRuntime()) return;
+    this.report();
+    this.timer = setInterval(() => this.report(), REPORT_INTERVAL_MS);
+  }
+
+  destroy(): void {
+    if (this.timer) clearInterval(this.timer);
+    this.timer = null;
+  }
+
+  private report(): void {
+    if (this.ctx.isDestroyed) return;
+    const alerts = getRecentAlerts();
+    this.reportBadge(alerts);
+    const status: AlertStatus = {
+      alerts: alerts.length,
+      critical: alerts.filter(a => isHighPriority(a.priority)).length,
+      headlines: this.ctx.allNews.length,
+      breaking: this.ctx.allNews.filter(item => item.isAlert).length,
+    };
+    const key = JSON.stringify(status);
+    if (key === this.lastReported) return;
+    this.lastReported = key;
+    void tryInvokeTauri<void>('update_alert_status', { status });
+  }
+
+  private reportBadge(alerts: UnifiedAlert[]): void {
+    const severity = alerts.reduce<AlertPriority | null>(
+      (highest, alert) =>
+        highest && SEVERITY_ORDER.indexOf(highest) >= SEVERITY_ORDER.indexOf(alert.priority) ? highest : alert.priority,
+      null,
+    );
+    const highPriorityIds = alerts.filter(a => isHighPriority(a.priority)).map(a => a.id);
+    // Alerts present at startup are not news; only flash for later ones.
+    const flash = this.seenHighPriority !== null && highPriorityIds.some(id => !this.seenHighPriority?.has(id));
+    this.seenHighPriority = new Set([...(this.seenHighPriority ?? []), ...highPriorityIds]);
+    if (flash) void tryInvokeTauri<void>('notify_attention', { severity, flash });
+    const badge = JSON.stringify({ count: alerts.length, severity });
+    if (badge === this.lastBadge) return;
+    this.lastBadge = badge;
+    void tryInvokeTauri<void>('set_badge', { count: alerts.length, severity });
+  }
+}
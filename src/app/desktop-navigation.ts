@@ -0,0 +1,79 @@
+import type { AppContext, AppModule } from '@/app/app-context';
+import { parseCoordinates, readClipboardText } from '@/services/desktop-clipboard';
+import { isDesktopRuntime } from '@/services/runtime';
+import { listenTauri, tryInvokeTauri } from '@/services/tauri-bridge';
+
+/** Mirrors `deep_links::DeepLink` on the Rust side (settings links never reach the page). */
+type DesktopNavigation =
+  | { kind: 'map'; lat: number; lon: number; zoom: number | null }
+  | { kind: 'panel'; id: string };
+
+const NAVIGATE_EVENT = 'app://navigate';
+
+/**
+ * Applies `worldmonitor://` deep links routed to this window by the desktop
+ * shell: centers the map or scrolls a panel into view. Cmd/Ctrl+Shift+G
+ * jumps the map to coordinates on the clipboard.
+ */
+export class DesktopNavigationHandler implements AppModule {
+  private ctx: AppContext;
+  private unlisten: (() => void) | null = null;
+  private readonly onKeyDown = (e: KeyboardEvent): void => {
+    if (!e.shiftKey || !(e.metaKey || e.ctrlKey) || e.altKey || e.key.toLowerCase() !== 'g') return;
+    e.preventDefault();
+    void this.jumpToClipboardCoordinates();
+  };
+
+  constructor(ctx: AppContext) {
+    this.ctx = ctx;
+  }
+
+  async init(): Promise<void> {
+    if (!isDesktopRuntime()) return;
+    document.addEventListener('keydown', this.onKeyDown);
+    try {
+      this.unlisten = await listenTauri<DesktopNavigation>(NAVIGATE_EVENT, (link) => this.navigate(link));
+    } catch (error) {
+      console.warn('[desktop-navigation] could not subscribe to deep links', error);
+      return;
+    }
+    // Links that launched the app arrived before this listener existed.
+    const pending = await tryInvokeTauri<DesktopNavigation[]>('take_pending_navigation');
+    pending?.forEach((link) => this.navigate(link));
+  }
+
+  destroy(): void {
+    document.removeEventListener('keydown', this.onKeyDown);
+    this.unlisten?.();
+    this.unlisten = null;
+  }
+
+  private async jumpToClipboardCoordinates(): Promise<void> {
+    const text = await readClipboardText().catch((error: unknown) => {
+      console.warn('[desktop-navigation] could not read the clipboard', error);
+      return null;
+    });
+    const coords = text ? parseCoordinates(text) : null;
+    if (!coords) {
+      console.info('[desktop-navigation] clipboard does not hold coordinates');
+      return;
+    }
+    this.navigate({ kind: 'map', lat: coords.lat, lon: coords.lon, zoom: null });
+  }
+
+  private navigate(link: DesktopNavigation): void {
+    if (link.kind === 'map') {
+      this.ctx.map?.setCenter(link.lat, link.lon, link.zoom ?? undefined);
+      document.getElementById('mapSection')?.scrollIntoView({ behavior: 'smooth', block: 'start' });
+      return;
+    }
+    const target = link.id === 'map'
+      ? document.getElementById('mapSection')
+      : document.querySelector<HTMLElement>(`.panel[data-panel="${link.id}"]`);
+    if (!target) {
+      console.warn(`[desktop-navigation] unknown panel "${link.id}"`);
+      return;
+    }
+    target.scrollIntoView({ behavior: 'smooth', block: 'center' });
+  }
+}
@@ -186,9 +186,8 @@ export class DesktopUpdater implements AppModule {
       if (action === 'download') {
         trackUpdateClicked(version);
         if (this.ctx.isDesktopApp) {
-          void invokeTauri<void>('open_url', { url }).catch((error) => {
+          void invokeTauri<boolean>('open_external_url', { url }).catch((error) => {
             this.logUpdaterOutcome('open_failed', { url, error: error instanceof Error ? error.message : String(error) });
-            window.open(url, '_blank', 'noopener');
           });
         } else {
           window.open(url, '_blank', 'noopener');
@@ -45,6 +45,7 @@ import { dataFreshness } from '@/services/data-freshness';
 import { mlWorker } from '@/services/ml-worker';
 import { UnifiedSettings } from '@/components/UnifiedSettings';
 import { t } from '@/services/i18n';
+import { copyText } from '@/services/desktop-clipboard';
 import { TvModeController } from '@/services/tv-mode';
 
 export interface EventHandlerCallbacks {
@@ -175,7 +176,7 @@ export class EventHandlerManager implements AppModule {
       if (!shareUrl) return;
       const button = document.getElementById('copyLinkBtn');
       try {
-        await this.copyToClipboard(shareUrl);
+        await copyText(shareUrl);
         this.setCopyLinkFeedback(button, 'Copied!');
       } catch (error) {
         console.warn('Failed to copy share link:', error);
@@ -281,8 +282,9 @@ export class EventHandlerManager implements AppModule {
           if (url.origin === window.location.origin) return;
           e.preventDefault();
           e.stopPropagation();
-          void invokeTauri<void>('open_url', { url: url.toString() }).catch(() => {
-            window.open(url.toString(), '_blank');
+          // The shell applies the link allowlist; a refused link stays closed.
+          void invokeTauri<boolean>('open_external_url', { url: url.toString() }).catch((error) => {
+            console.warn('[links] could not open external link', error);
           });
         } catch { /* malformed URL -- let browser handle */ }
       };
@@ -355,21 +357,6 @@ export class EventHandlerManager implements AppModule {
     });
   }
 
-  private async copyToClipboard(text: string): Promise<void> {
-    if (navigator.clipboard?.writeText) {
-      await navigator.clipboard.writeText(text);
-      return;
-    }
-    const textarea = document.createElement('textarea');
-    textarea.value = text;
-    textarea.style.position = 'fixed';
-    textarea.style.opacity = '0';
-    document.body.appendChild(textarea);
-    textarea.select();
-    document.execCommand('copy');
-    document.body.removeChild(textarea);
-  }
-
   private setCopyLinkFeedback(button: HTMLElement | null, message: string): void {
     if (!button) return;
     const originalText = button.textContent ?? '';
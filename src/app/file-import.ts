@@ -0,0 +1,101 @@
+import type { AppContext, AppModule } from '@/app/app-context';
+import type { FeatureCollection } from 'geojson';
+import type { ImageMetadata } from '@/services/image-metadata';
+import { isDesktopRuntime } from '@/services/runtime';
+import { listenTauri, tryInvokeTauri } from '@/services/tauri-bridge';
+
+/** Mirrors `file_import::FileImport` on the Rust side. */
+type FileImport = { file: string } & (
+  | { kind: 'secrets'; imported: string[]; ignored: string[] }
+  | { kind: 'overlay'; name: string; format: 'geojson' | 'kml'; features: number; geojson: FeatureCollection }
+  | { kind: 'config'; desktop: boolean; preferences: Record<string, unknown> }
+  | { kind: 'image'; metadata: ImageMetadata }
+  | { kind: 'rejected'; error: string }
+);
+
+export interface FileImportCallbacks {
+  showToast: (message: string) => void;
+}
+
+const FILE_IMPORTED_EVENT = 'file-imported';
+/** Time to read the toast before a settings bundle reloads the page. */
+const RELOAD_DELAY_MS = 1500;
+const PHOTO_ZOOM = 12;
+
+/**
+ * Applies files dropped onto (or settings bundles opened with) the desktop
+ * app once the shell has validated them: map overlays are drawn, settings
+ * bundles are written to localStorage, the map jumps to where a GPS-tagged
+ * photo was taken, and every outcome is reported in a toast.
+ */
+export class FileImportHandler implements AppModule {
+  private ctx: AppContext;
+  private callbacks: FileImportCallbacks;
+  private unlisten: (() => void) | null = null;
+
+  constructor(ctx: AppContext, callbacks: FileImportCallbacks) {
+    this.ctx = ctx;
+    this.callbacks = callbacks;
+  }
+
+  async init(): Promise<void> {
+    if (!isDesktopRuntime()) return;
+    try {
+      this.unlisten = await listenTauri<FileImport>(FILE_IMPORTED_EVENT, (result) => this.apply(result));
+    } catch (error) {
+      console.warn('[file-import] could not subscribe to file imports', error);
+      return;
+    }
+    // Bundles opened before the dashboard was listening, e.g. the one that launched the app.
+    const pending = await tryInvokeTauri<FileImport[]>('take_pending_imports');
+    for (const result of pending ?? []) this.apply(result);
+  }
+
+  destroy(): void {
+    this.unlisten?.();
+    this.unlisten = null;
+  }
+
+  private apply(result: FileImport): void {
+    if (this.ctx.isDestroyed) return;
+    switch (result.kind) {
+      case 'secrets':
+        this.callbacks.showToast(
+          `Imported ${result.imported.length} API key${result.imported.length === 1 ? '' : 's'} from ${result.file}`,
+        );
+        break;
+      case 'overlay':
+        this.ctx.map?.addImportedOverlay({ id: result.file, name: result.name, data: result.geojson });
+        this.callbacks.showToast(`Added ${result.name} to the map (${result.features} features)`);
+        break;
+      case 'config': {
+        const keys = Object.entries(result.preferences);
+        for (const [key, value] of keys) {
+          localStorage.setItem(key, typeof value === 'string' ? value : JSON.stringify(value));
+        }
+        if (keys.length > 0) {
+          this.callbacks.showToast(`Imported settings from ${result.file}; reloading`);
+          setTimeout(() => window.location.reload(), RELOAD_DELAY_MS);
+        } else {
+          this.callbacks.showToast(`Imported desktop settings from ${result.file}; they apply on next launch`);
+        }
+        break;
+      }
+      case 'image': {
+        const { lat, lon, camera, taken } = result.metadata;
+        const details = [camera, taken].filter(Boolean).join(', ');
+        if (lat != null && lon != null) {
+          this.ctx.map?.setCenter(lat, lon, PHOTO_ZOOM);
+          this.ctx.map?.flashLocation(lat, lon);
+          this.callbacks.showToast(`${result.file} was taken at ${lat.toFixed(5)}, ${lon.toFixed(5)}${details ? ` (${details})` : ''}`);
+        } else {
+          this.callbacks.showToast(`${result.file} has no GPS position${details ? ` (${details})` : ''}`);
+        }
+        break;
+      }
+      case 'rejected':
+        this.callbacks.showToast(`Could not import ${result.file}: ${result.error}`);
+        break;
+    }
+  }
+}
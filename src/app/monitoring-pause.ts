@@ -0,0 +1,47 @@
+import type { AppContext, AppModule } from '@/app/app-context';
+import { isDesktopRuntime } from '@/services/runtime';
+import { listenTauri, tryInvokeTauri } from '@/services/tauri-bridge';
+
+export interface MonitoringPauseCallbacks {
+  setPaused: (paused: boolean) => void;
+}
+
+const PAUSED_EVENT = 'monitoring://paused';
+
+/**
+ * Follows the desktop shell's Pause Monitoring toggle (macOS dock menu) and
+ * holds or resumes scheduled refreshes accordingly.
+ */
+export class MonitoringPauseHandler implements AppModule {
+  private ctx: AppContext;
+  private callbacks: MonitoringPauseCallbacks;
+  private unlisten: (() => void) | null = null;
+
+  constructor(ctx: AppContext, callbacks: MonitoringPauseCallbacks) {
+    this.ctx = ctx;
+    this.callbacks = callbacks;
+  }
+
+  async init(): Promise<void> {
+    if (!isDesktopRuntime()) return;
+    try {
+      this.unlisten = await listenTauri<boolean>(PAUSED_EVENT, (paused) => this.apply(paused));
+    } catch (error) {
+      console.warn('[monitoring] could not subscribe to pause changes', error);
+      return;
+    }
+    // The page may have been reloaded while paused.
+    const paused = await tryInvokeTauri<boolean>('get_monitoring_paused');
+    if (paused !== null) this.apply(paused);
+  }
+
+  destroy(): void {
+    this.unlisten?.();
+    this.unlisten = null;
+  }
+
+  private apply(paused: boolean): void {
+    if (this.ctx.isDestroyed) return;
+    this.callbacks.setPaused(paused);
+  }
+}
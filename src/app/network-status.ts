@@ -0,0 +1,66 @@
+import type { AppContext, AppModule } from '@/app/app-context';
+import { t } from '@/services/i18n';
+import { isDesktopRuntime } from '@/services/runtime';
+import { listenTauri, tryInvokeTauri } from '@/services/tauri-bridge';
+
+export interface NetworkStatusCallbacks {
+  setOffline: (offline: boolean) => void;
+}
+
+const ONLINE_EVENT = 'network://online';
+const OFFLINE_EVENT = 'network://offline';
+
+/**
+ * Follows the desktop shell's connectivity monitor: while offline, scheduled
+ * refreshes are held and a single banner replaces per-panel fetch errors.
+ */
+export class NetworkStatusHandler implements AppModule {
+  private ctx: AppContext;
+  private callbacks: NetworkStatusCallbacks;
+  private unlisteners: Array<() => void> = [];
+  private bannerEl: HTMLElement | null = null;
+
+  constructor(ctx: AppContext, callbacks: NetworkStatusCallbacks) {
+    this.ctx = ctx;
+    this.callbacks = callbacks;
+  }
+
+  async init(): Promise<void> {
+    if (!isDesktopRuntime()) return;
+    try {
+      this.unlisteners.push(
+        await listenTauri<null>(ONLINE_EVENT, () => this.apply(false)),
+        await listenTauri<null>(OFFLINE_EVENT, () => this.apply(true)),
+      );
+    } catch (error) {
+      console.warn('[network] could not subscribe to connectivity changes', error);
+      return;
+    }
+    // The page may have been reloaded while offline.
+    const online = await tryInvokeTauri<boolean>('get_network_online');
+    if (online !== null) this.apply(!online);
+  }
+
+  destroy(): void {
+    for (const unlisten of this.unlisteners) unlisten();
+    this.unlisteners = [];
+    this.bannerEl?.remove();
+    this.bannerEl = null;
+  }
+
+  private apply(offline: boolean): void {
+    if (this.ctx.isDestroyed) return;
+    this.callbacks.setOffline(offline);
+    if (!offline) {
+      this.bannerEl?.remove();
+      this.bannerEl = null;
+      return;
+    }
+    if (this.bannerEl) return;
+    this.bannerEl = document.createElement('div');
+    this.bannerEl.className = 'network-offline-banner';
+    this.bannerEl.setAttribute('role', 'status');
+    this.bannerEl.textContent = t('app.offlineBanner');
+    document.body.appendChild(this.bannerEl);
+  }
+}
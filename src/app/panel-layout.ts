@@ -58,6 +58,7 @@ import { BETA_MODE } from '@/config/beta';
 import { t } from '@/services/i18n';
 import { getCurrentTheme } from '@/utils';
 import { trackCriticalBannerAction } from '@/services/analytics';
+import { createPopOutButton } from '@/services/panel-window';
 
 export interface PanelLayoutCallbacks {
   openCountryStory: (code: string, name: string) => void;
@@ -207,6 +208,9 @@ export class PanelLayoutManager implements AppModule {
       </div>
     `;
 
+    const mapPopOutBtn = createPopOutButton('map');
+    if (mapPopOutBtn) document.getElementById('mapPinBtn')?.before(mapPopOutBtn);
+
     this.createPanels();
   }
 
@@ -0,0 +1,53 @@
+import type { AppContext, AppModule } from '@/app/app-context';
+import { isDesktopRuntime } from '@/services/runtime';
+import { listenTauri, tryInvokeTauri } from '@/services/tauri-bridge';
+
+export interface PowerStatus {
+  on_battery: boolean;
+  battery_percent: number | null;
+  /** How many times slower to refresh; 1 on AC power. */
+  refresh_multiplier: number;
+}
+
+export interface PowerStatusCallbacks {
+  setBatteryMultiplier: (multiplier: number) => void;
+}
+
+const POWER_EVENT = 'app://power';
+
+/**
+ * Follows the desktop shell's power source and slows scheduled refreshes
+ * on battery by the multiplier configured in settings.
+ */
+export class PowerStatusHandler implements AppModule {
+  private ctx: AppContext;
+  private callbacks: PowerStatusCallbacks;
+  private unlisten: (() => void) | null = null;
+
+  constructor(ctx: AppContext, callbacks: PowerStatusCallbacks) {
+    this.ctx = ctx;
+    this.callbacks = callbacks;
+  }
+
+  async init(): Promise<void> {
+    if (!isDesktopRuntime()) return;
+    try {
+      this.unlisten = await listenTauri<PowerStatus>(POWER_EVENT, (status) => this.apply(status));
+    } catch (error) {
+      console.warn('[power] could not subscribe to power changes', error);
+      return;
+    }
+    const status = await tryInvokeTauri<PowerStatus>('get_power_status');
+    if (status) this.apply(status);
+  }
+
+  destroy(): void {
+    this.unlisten?.();
+    this.unlisten = null;
+  }
+
+  private apply(status: PowerStatus): void {
+    if (this.ctx.isDestroyed) return;
+    this.callbacks.setBatteryMultiplier(status.refresh_multiplier);
+  }
+}
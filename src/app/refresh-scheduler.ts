@@ -12,6 +12,10 @@ export class RefreshScheduler implements AppModule {
   private refreshTimeoutIds: Map<string, ReturnType<typeof setTimeout>> = new Map();
   private refreshRunners = new Map<string, { run: () => Promise<void>; intervalMs: number }>();
   private hiddenSince = 0;
+  private paused = false;
+  private offline = false;
+  private idleSince = 0;
+  private batteryMultiplier = 1;
 
   constructor(ctx: AppContext) {
     this.ctx = ctx;
@@ -35,6 +39,40 @@ export class RefreshScheduler implements AppModule {
     return this.hiddenSince;
   }
 
+  /** Hold refreshes while paused (desktop "Pause Monitoring"); resuming catches up at once. */
+  setPaused(paused: boolean): void {
+    if (this.paused === paused) return;
+    this.paused = paused;
+    if (!paused && !this.offline) this.runAllNow();
+  }
+
+  /** Hold refreshes while the desktop shell reports no connectivity; reconnecting catches up at once. */
+  setOffline(offline: boolean): void {
+    if (this.offline === offline) return;
+    this.offline = offline;
+    if (!offline && !this.paused) this.runAllNow();
+  }
+
+  /**
+   * Stretch intervals while the machine has seen no input (desktop idle
+   * detection); on return, refreshes that came due meanwhile run at once.
+   */
+  setSystemIdle(idle: boolean): void {
+    if (idle === (this.idleSince > 0)) return;
+    if (idle) {
+      this.idleSince = Date.now();
+      return;
+    }
+    const idleMs = Date.now() - this.idleSince;
+    this.idleSince = 0;
+    if (!this.paused && !this.offline) this.runAllNow((intervalMs) => idleMs >= intervalMs);
+  }
+
+  /** Stretch intervals by `multiplier` while on battery (1 on AC power). Applies from each refresh's next run. */
+  setBatteryMultiplier(multiplier: number): void {
+    this.batteryMultiplier = Math.max(1, multiplier);
+  }
+
   scheduleRefresh(
     name: string,
     fn: () => Promise<void>,
@@ -42,10 +80,14 @@ export class RefreshScheduler implements AppModule {
     condition?: () => boolean
   ): void {
     const HIDDEN_REFRESH_MULTIPLIER = 4;
+    const IDLE_REFRESH_MULTIPLIER = 4;
     const JITTER_FRACTION = 0.1;
     const MIN_REFRESH_MS = 1000;
     const computeDelay = (baseMs: number, isHidden: boolean) => {
-      const adjusted = baseMs * (isHidden ? HIDDEN_REFRESH_MULTIPLIER : 1);
+      const adjusted = baseMs
+        * (isHidden ? HIDDEN_REFRESH_MULTIPLIER : 1)
+        * (this.idleSince ? IDLE_REFRESH_MULTIPLIER : 1)
+        * this.batteryMultiplier;
       const jitterRange = adjusted * JITTER_FRACTION;
       const jittered = adjusted + (Math.random() * 2 - 1) * jitterRange;
       return Math.max(MIN_REFRESH_MS, Math.round(jittered));
@@ -58,7 +100,7 @@ export class RefreshScheduler implements AppModule {
     const run = async () => {
       if (this.ctx.isDestroyed) return;
       const isHidden = document.visibilityState === 'hidden';
-      if (isHidden) {
+      if (isHidden || this.paused || this.offline) {
         scheduleNext(computeDelay(intervalMs, true));
         return;
       }
@@ -89,9 +131,13 @@ export class RefreshScheduler implements AppModule {
     const hiddenMs = Date.now() - this.hiddenSince;
     this.hiddenSince = 0;
 
+    this.runAllNow((intervalMs) => hiddenMs >= intervalMs);
+  }
+
+  private runAllNow(isStale: (intervalMs: number) => boolean = () => true): void {
     let stagger = 0;
     for (const [name, { run, intervalMs }] of this.refreshRunners) {
-      if (hiddenMs < intervalMs) continue;
+      if (!isStale(intervalMs)) continue;
       const pending = this.refreshTimeoutIds.get(name);
       if (pending) clearTimeout(pending);
       const delay = stagger;
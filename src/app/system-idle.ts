@@ -0,0 +1,47 @@
+import type { AppContext, AppModule } from '@/app/app-context';
+import { isDesktopRuntime } from '@/services/runtime';
+import { listenTauri, tryInvokeTauri } from '@/services/tauri-bridge';
+
+export interface SystemIdleCallbacks {
+  setIdle: (idle: boolean) => void;
+}
+
+const IDLE_EVENT = 'app://idle';
+
+/**
+ * Follows the desktop shell's system idle detection (no keyboard or mouse
+ * input anywhere for a while) and slows scheduled refreshes accordingly.
+ */
+export class SystemIdleHandler implements AppModule {
+  private ctx: AppContext;
+  private callbacks: SystemIdleCallbacks;
+  private unlisten: (() => void) | null = null;
+
+  constructor(ctx: AppContext, callbacks: SystemIdleCallbacks) {
+    this.ctx = ctx;
+    this.callbacks = callbacks;
+  }
+
+  async init(): Promise<void> {
+    if (!isDesktopRuntime()) return;
+    try {
+      this.unlisten = await listenTauri<boolean>(IDLE_EVENT, (idle) => this.apply(idle));
+    } catch (error) {
+      console.warn('[idle] could not subscribe to idle changes', error);
+      return;
+    }
+    // The page may have been reloaded while idle.
+    const idle = await tryInvokeTauri<boolean>('get_system_idle');
+    if (idle !== null) this.apply(idle);
+  }
+
+  destroy(): void {
+    this.unlisten?.();
+    this.unlisten = null;
+  }
+
+  private apply(idle: boolean): void {
+    if (this.ctx.isDestroyed) return;
+    this.callbacks.setIdle(idle);
+  }
+}
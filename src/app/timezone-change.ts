@@ -0,0 +1,50 @@
+import type { AppContext, AppModule } from '@/app/app-context';
+import { isDesktopRuntime } from '@/services/runtime';
+import { listenTauri } from '@/services/tauri-bridge';
+
+export interface SystemTimezone {
+  name: string | null;
+  utc_offset_mins: number;
+  utc_offset: string;
+}
+
+export interface TimezoneChangeCallbacks {
+  loadAllData: () => Promise<void>;
+}
+
+const CHANGED_EVENT = 'app://timezone-changed';
+
+/**
+ * Re-renders the dashboard when the desktop shell reports a timezone or DST
+ * change, so panel timestamps stop showing the old zone's local time.
+ */
+export class TimezoneChangeHandler implements AppModule {
+  private ctx: AppContext;
+  private callbacks: TimezoneChangeCallbacks;
+  private unlisten: (() => void) | null = null;
+
+  constructor(ctx: AppContext, callbacks: TimezoneChangeCallbacks) {
+    this.ctx = ctx;
+    this.callbacks = callbacks;
+  }
+
+  async init(): Promise<void> {
+    if (!isDesktopRuntime()) return;
+    try {
+      this.unlisten = await listenTauri<SystemTimezone>(CHANGED_EVENT, (timezone) => this.apply(timezone));
+    } catch (error) {
+      console.warn('[timezone] could not subscribe to timezone changes', error);
+    }
+  }
+
+  destroy(): void {
+    this.unlisten?.();
+    this.unlisten = null;
+  }
+
+  private apply(timezone: SystemTimezone): void {
+    if (this.ctx.isDestroyed) return;
+    console.info(`[timezone] now ${timezone.name ?? 'UTC' + timezone.utc_offset}`);
+    void this.callbacks.loadAllData();
+  }
+}
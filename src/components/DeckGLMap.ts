@@ -89,6 +89,7 @@ import type { HappinessData } from '@/services/happiness-data';
 import type { RenewableInstallation } from '@/services/renewable-installations';
 import type { SpeciesRecovery } from '@/services/conservation-data';
 import { getCountriesGeoJson, getCountryAtCoordinates } from '@/services/country-geometry';
+import { cachedTileUrl, initTileCache } from '@/services/tile-cache';
 import type { FeatureCollection, Geometry } from 'geojson';
 
 export type TimeRange = '1h' | '6h' | '24h' | '48h' | '7d' | 'all';
@@ -102,6 +103,13 @@ export interface CountryClickPayload {
   name?: string;
 }
 
+/** A GeoJSON or KML file the user dropped onto the desktop app. */
+export interface ImportedOverlay {
+  id: string;
+  name: string;
+  data: FeatureCollection;
+}
+
 interface DeckMapState {
   zoom: number;
   pan: { x: number; y: number };
@@ -277,6 +285,7 @@ export class DeckGLMap {
   private news: NewsItem[] = [];
   private newsLocations: Array<{ lat: number; lon: number; title: string; threatLevel: string; timestamp?: Date }> = [];
   private newsLocationFirstSeen = new Map<string, number>();
+  private importedOverlays: ImportedOverlay[] = [];
   private ucdpEvents: UcdpGeoEvent[] = [];
   private displacementFlows: DisplacementFlow[] = [];
   private climateAnomalies: ClimateAnomaly[] = [];
@@ -411,9 +420,15 @@ export class DeckGLMap {
     const preset = VIEW_PRESETS[this.state.view];
     const initialTheme = getCurrentTheme();
 
+    void initTileCache();
     this.maplibreMap = new maplibregl.Map({
       container: 'deckgl-basemap',
       style: initialTheme === 'light' ? LIGHT_STYLE : DARK_STYLE,
+      // On the desktop, basemap tiles go through the app's offline tile cache.
+      transformRequest: (url, resourceType) => {
+        const cached = resourceType === 'Tile' ? cachedTileUrl(url) : null;
+        return { url: cached ?? url };
+      },
       center: [preset.longitude, preset.latitude],
       zoom: preset.zoom,
       renderWorldCopies: false,
@@ -1201,6 +1216,11 @@ export class DeckGLMap {
       layers.push(this.createRenewableInstallationsLayer());
     }
 
+    // Imported overlays (always shown; removed only by reloading)
+    for (const overlay of this.importedOverlays) {
+      layers.push(this.createImportedOverlayLayer(overlay));
+    }
+
     // News geo-locations (always shown if data exists)
     if (this.newsLocations.length > 0) {
       layers.push(...this.createNewsLocationsLayer());
@@ -1316,6 +1336,29 @@ export class DeckGLMap {
     return layer;
   }
 
+  private createImportedOverlayLayer(overlay: ImportedOverlay): GeoJsonLayer {
+    const cacheKey = `imported-overlay-${overlay.id}`;
+    const cached = this.layerCache.get(cacheKey) as GeoJsonLayer | undefined;
+    if (cached) return cached;
+
+    const layer = new GeoJsonLayer({
+      id: cacheKey,
+      data: overlay.data,
+      filled: true,
+      stroked: true,
+      pointType: 'circle',
+      getFillColor: [0, 200, 255, 60],
+      getLineColor: [0, 200, 255, 220],
+      getPointRadius: 5,
+      pointRadiusUnits: 'pixels',
+      getLineWidth: 2,
+      lineWidthMinPixels: 1,
+      pickable: false,
+    });
+    this.layerCache.set(cacheKey, layer);
+    return layer;
+  }
+
   private createBasesLayer(): IconLayer {
     const highlightedBases = this.highlightedAssets.base;
 
@@ -3685,6 +3728,12 @@ export class DeckGLMap {
     this.syncPulseAnimation(now);
   }
 
+  public addImportedOverlay(overlay: ImportedOverlay): void {
+    this.importedOverlays = [...this.importedOverlays.filter(o => o.id !== overlay.id), overlay];
+    this.layerCache.delete(`imported-overlay-${overlay.id}`);
+    this.render();
+  }
+
   public setPositiveEvents(events: PositiveGeoEvent[]): void {
     this.positiveEvents = events;
     this.syncPulseAnimation();
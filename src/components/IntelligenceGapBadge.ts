@@ -4,6 +4,9 @@ import { t } from '@/services/i18n';
 import { getSignalContext } from '@/utils/analysis-constants';
 import { escapeHtml } from '@/utils/sanitize';
 import { trackFindingClicked } from '@/services/analytics';
+import { showNativeContextMenu } from '@/services/native-context-menu';
+import { alertSoundAllowed } from '@/services/focus-mode';
+import { playNativeAlertSound } from '@/services/alert-sounds';
 
 const LOW_COUNT_THRESHOLD = 3;
 const MAX_VISIBLE_FINDINGS = 10;
@@ -63,7 +66,7 @@ export class IntelligenceFindingsBadge {
     this.badge.addEventListener('contextmenu', (e) => {
       e.preventDefault();
       e.stopPropagation();
-      this.showContextMenu(e.clientX, e.clientY);
+      void this.showContextMenu(e.clientX, e.clientY);
     });
 
     // Event delegation for finding items, toggle, and "more" link
@@ -122,8 +125,9 @@ export class IntelligenceFindingsBadge {
     this.audio.volume = 0.3;
   }
 
-  private playSound(): void {
-    if (this.audioEnabled && this.audio) {
+  private playSound(critical: boolean): void {
+    if (!this.audioEnabled || playNativeAlertSound(critical ? 'critical' : 'medium')) return;
+    if (this.audio && alertSoundAllowed(critical)) {
       this.audio.currentTime = 0;
       this.audio.play().catch(() => {});
     }
@@ -173,14 +177,20 @@ export class IntelligenceFindingsBadge {
     }
   }
 
-  private showContextMenu(x: number, y: number): void {
+  private async showContextMenu(x: number, y: number): Promise<void> {
     this.dismissContextMenu();
 
+    const hideLabel = t('components.intelligenceFindings.hideFindings');
+    const native = await showNativeContextMenu([{ type: 'item', id: 'hide', label: hideLabel }], () => {
+      this.setEnabled(false);
+    });
+    if (native) return;
+
     const menu = document.createElement('div');
     menu.className = 'intel-findings-context-menu';
     menu.style.left = `${x}px`;
     menu.style.top = `${y}px`;
-    menu.innerHTML = `<div class="context-menu-item">${t('components.intelligenceFindings.hideFindings')}</div>`;
+    menu.innerHTML = `<div class="context-menu-item">${hideLabel}</div>`;
 
     menu.querySelector('.context-menu-item')!.addEventListener('click', (e) => {
       e.stopPropagation();
@@ -227,7 +237,7 @@ export class IntelligenceFindingsBadge {
     if (count > this.lastFindingCount && this.lastFindingCount > 0) {
       this.badge.classList.add('pulse');
       setTimeout(() => this.badge.classList.remove('pulse'), 1000);
-      if (this.popupEnabled) this.playSound();
+      if (this.popupEnabled) this.playSound(this.findings.some(f => f.priority === 'critical'));
     }
     this.lastFindingCount = count;
 
@@ -52,6 +52,7 @@ import {
 } from '@/services/hotspot-escalation';
 import { getCountryScore } from '@/services/country-instability';
 import { getAlertsNearLocation } from '@/services/geo-convergence';
+import { fetchStoredTrack } from '@/services/stored-tracks';
 import { t } from '@/services/i18n';
 
 export type TimeRange = '1h' | '6h' | '24h' | '48h' | '7d' | 'all';
@@ -2353,6 +2354,7 @@ export class MapComponent {
             x: e.clientX - rect.left,
             y: e.clientY - rect.top,
           });
+          void this.loadStoredTrack(flight, 'flight', flight.hexCode.toLowerCase());
         });
 
         this.overlays.appendChild(div);
@@ -2456,6 +2458,7 @@ export class MapComponent {
             x: e.clientX - rect.left,
             y: e.clientY - rect.top,
           });
+          void this.loadStoredTrack(vessel, 'vessel', vessel.mmsi);
         });
 
         this.overlays.appendChild(div);
@@ -2584,6 +2587,20 @@ export class MapComponent {
     }
   }
 
+  /** Replace a selected aircraft or vessel's trail with the longer one the desktop app recorded. */
+  private async loadStoredTrack(
+    subject: MilitaryFlight | MilitaryVessel,
+    kind: 'flight' | 'vessel',
+    id: string,
+  ): Promise<void> {
+    const stored = await fetchStoredTrack(kind, id);
+    if (!stored) return;
+    const track: [number, number][] = [...stored, [subject.lat, subject.lon]];
+    if (track.length <= (subject.track?.length ?? 0)) return;
+    subject.track = track;
+    this.render();
+  }
+
   private renderWaterways(projection: d3.GeoProjection): void {
     STRATEGIC_WATERWAYS.forEach((waterway) => {
       const pos = projection([waterway.lon, waterway.lat]);
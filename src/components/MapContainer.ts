@@ -4,7 +4,7 @@
  */
 import { isMobileDevice } from '@/utils';
 import { MapComponent } from './Map';
-import { DeckGLMap, type DeckMapView, type CountryClickPayload } from './DeckGLMap';
+import { DeckGLMap, type DeckMapView, type CountryClickPayload, type ImportedOverlay } from './DeckGLMap';
 import type {
   MapLayers,
   Hotspot,
@@ -338,6 +338,13 @@ export class MapContainer {
     }
   }
 
+  public addImportedOverlay(overlay: ImportedOverlay): void {
+    if (this.useDeckGL) {
+      this.deckGLMap?.addImportedOverlay(overlay);
+    }
+    // SVG map does not support imported overlays
+  }
+
   public setPositiveEvents(events: PositiveGeoEvent[]): void {
     if (this.useDeckGL) {
       this.deckGLMap?.setPositiveEvents(events);
@@ -2253,6 +2253,7 @@ export class MapPopup {
       severeStorms: 'high',
       wildfires: 'high',
       volcanoes: 'high',
+      tsunamis: 'high',
       earthquakes: 'elevated',
       floods: 'elevated',
       landslides: 'elevated',
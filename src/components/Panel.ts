@@ -3,6 +3,7 @@ import { invokeTauri } from '../services/tauri-bridge';
 import { t } from '../services/i18n';
 import { h, replaceChildren, safeHtml } from '../utils/dom-utils';
 import { trackPanelResized } from '@/services/analytics';
+import { createPopOutButton } from '@/services/panel-window';
 
 export interface PanelOptions {
   id: string;
@@ -129,6 +130,9 @@ export class Panel {
       this.header.appendChild(this.countEl);
     }
 
+    const popOutBtn = createPopOutButton(options.id);
+    if (popOutBtn) this.header.appendChild(popOutBtn);
+
     this.content = document.createElement('div');
     this.content.className = 'panel-content';
     this.content.id = `${options.id}Content`;
@@ -308,7 +312,7 @@ export class Panel {
         h('button', {
           type: 'button',
           className: 'config-error-settings-btn',
-          onClick: () => void invokeTauri<void>('open_settings_window_command').catch(() => { }),
+          onClick: () => void invokeTauri<void>('open_settings_window_command', { section: 'api-keys' }).catch(() => { }),
         }, t('components.panel.openSettings')),
       );
     }
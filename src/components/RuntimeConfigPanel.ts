@@ -41,6 +41,8 @@ const SIGNUP_URLS: Partial<Record<RuntimeSecretKey, string>> = {
   OLLAMA_API_URL: 'https://ollama.com/download',
   OLLAMA_MODEL: 'https://ollama.com/library',
   WTO_API_KEY: 'https://apiportal.wto.org/',
+  FAA_NOTAM_CLIENT_ID: 'https://api.faa.gov/s/',
+  FAA_NOTAM_CLIENT_SECRET: 'https://api.faa.gov/s/',
 };
 
 const PLAINTEXT_KEYS = new Set<RuntimeSecretKey>([
@@ -362,7 +364,9 @@ export class RuntimeConfigPanel extends Panel {
         const url = link.dataset.signupUrl;
         if (!url) return;
         if (isDesktopRuntime()) {
-          void invokeTauri<void>('open_url', { url }).catch(() => window.open(url, '_blank'));
+          void invokeTauri<boolean>('open_external_url', { url }).catch((error) => {
+            console.warn('[runtime-config] could not open signup link', error);
+          });
         } else {
           window.open(url, '_blank');
         }
@@ -373,7 +377,7 @@ export class RuntimeConfigPanel extends Panel {
 
     if (this.mode === 'alert') {
       this.content.querySelector<HTMLButtonElement>('[data-open-settings]')?.addEventListener('click', () => {
-        void invokeTauri<void>('open_settings_window_command').catch((error) => {
+        void invokeTauri<void>('open_settings_window_command', { section: 'api-keys' }).catch((error) => {
           console.warn('[runtime-config] Failed to open settings window', error);
         });
       });
@@ -5,6 +5,8 @@ import { escapeHtml } from '@/utils/sanitize';
 import { getCSSColor } from '@/utils';
 import { getSignalContext, type SignalType } from '@/utils/analysis-constants';
 import { t } from '@/services/i18n';
+import { alertSoundAllowed } from '@/services/focus-mode';
+import { playNativeAlertSound } from '@/services/alert-sounds';
 
 export class SignalModal {
   private element: HTMLElement;
@@ -221,8 +223,9 @@ export class SignalModal {
     this.element.classList.add('active');
   }
 
-  public playSound(): void {
-    if (this.audioEnabled && this.audio) {
+  public playSound(critical = false): void {
+    if (!this.audioEnabled || playNativeAlertSound(critical ? 'critical' : 'medium')) return;
+    if (this.audio && alertSoundAllowed(critical)) {
       this.audio.currentTime = 0;
       this.audio.play().catch(() => {});
     }
@@ -2,6 +2,8 @@ import type { StoryData } from '@/services/story-data';
 import { renderStoryToCanvas } from '@/services/story-renderer';
 import { generateStoryDeepLink, getShareUrls, shareTexts } from '@/services/story-share';
 import { t } from '@/services/i18n';
+import { copyPngImage, copyText } from '@/services/desktop-clipboard';
+import { shareNatively } from '@/services/native-share';
 
 let modalEl: HTMLElement | null = null;
 let currentDataUrl: string | null = null;
@@ -125,6 +127,17 @@ async function shareWhatsApp(data: StoryData): Promise<void> {
   const file = new File([currentBlob], `${data.countryCode.toLowerCase()}-worldmonitor.png`, { type: 'image/png' });
   const urls = getShareUrls(data);
 
+  try {
+    const result = await shareNatively({
+      title: `${data.countryName} Intelligence Brief`,
+      text: shareTexts.whatsapp(data).replace('\n\n', '\n'),
+      url: generateStoryDeepLink(data.countryCode),
+      file,
+    });
+    if (result === 'copied') flashButton('.story-whatsapp', t('modals.story.copied'), t('modals.story.whatsapp'));
+    if (result) return;
+  } catch { /* fall back to the browser share paths */ }
+
   if (navigator.share && navigator.canShare?.({ files: [file] })) {
     try {
       await navigator.share({
@@ -136,9 +149,7 @@ async function shareWhatsApp(data: StoryData): Promise<void> {
   }
 
   try {
-    await navigator.clipboard.write([
-      new ClipboardItem({ 'image/png': currentBlob }),
-    ]);
+    await copyPngImage(currentBlob);
     flashButton('.story-whatsapp', t('modals.story.copied'), t('modals.story.whatsapp'));
   } catch {
     downloadStory();
@@ -161,7 +172,7 @@ async function shareLinkedIn(data: StoryData): Promise<void> {
 
 async function copyDeepLink(data: StoryData): Promise<void> {
   const link = generateStoryDeepLink(data.countryCode);
-  await navigator.clipboard.writeText(link);
+  await copyText(link);
   flashButton('.story-copy', t('modals.story.copied'), t('modals.story.copyLink'));
 }
 
@@ -0,0 +1,9 @@
+/**
+ * Entry point for the standalone log viewer window (Tauri desktop only,
+ * opened from Debug → Log Viewer or the settings Debug tab).
+ */
+import './styles/main.css';
+import './styles/log-viewer.css';
+import { initLogViewerWindow } from '@/log-viewer-window';
+
+initLogViewerWindow();
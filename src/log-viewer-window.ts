@@ -0,0 +1,122 @@
+/**
+ * Standalone log viewer: pages through desktop.log and local-api.log (newest
+ * first) via the `query_logs` command, with level filtering and text search.
+ */
+import { invokeTauri } from '@/services/tauri-bridge';
+import { escapeHtml } from '@/utils/sanitize';
+
+type LogFileKind = 'desktop' | 'local-api';
+type LogLevel = 'error' | 'warn' | 'info' | 'debug' | 'trace';
+
+interface LogLine {
+  file: LogFileKind;
+  line: number;
+  level: LogLevel;
+  text: string;
+}
+
+interface LogPage {
+  lines: LogLine[];
+  total: number;
+  has_more: boolean;
+}
+
+const PAGE_SIZE = 500;
+const SEARCH_DEBOUNCE_MS = 250;
+
+export function initLogViewerWindow(): void {
+  const appEl = document.getElementById('app');
+  if (!appEl) return;
+
+  appEl.innerHTML = `
+    <div class="log-viewer-shell">
+      <div class="log-viewer-toolbar">
+        <select id="logViewerFile" aria-label="Log file">
+          <option value="">All logs</option>
+          <option value="desktop">desktop.log</option>
+          <option value="local-api">local-api.log</option>
+        </select>
+        <select id="logViewerLevel" aria-label="Minimum level">
+          <option value="">All levels</option>
+          <option value="error">Error</option>
+          <option value="warn">Warn+</option>
+          <option value="info">Info+</option>
+          <option value="debug">Debug+</option>
+        </select>
+        <input id="logViewerSearch" type="search" placeholder="Search…" aria-label="Search logs" />
+        <button id="logViewerRefresh" type="button">Refresh</button>
+        <span id="logViewerStatus" class="log-viewer-status" aria-live="polite"></span>
+      </div>
+      <div id="logViewerLines" class="log-viewer-lines"></div>
+      <div class="log-viewer-footer">
+        <button id="logViewerMore" type="button" hidden>Load older</button>
+      </div>
+    </div>
+  `;
+
+  const fileEl = document.getElementById('logViewerFile') as HTMLSelectElement;
+  const levelEl = document.getElementById('logViewerLevel') as HTMLSelectElement;
+  const searchEl = document.getElementById('logViewerSearch') as HTMLInputElement;
+  const linesEl = document.getElementById('logViewerLines') as HTMLElement;
+  const statusEl = document.getElementById('logViewerStatus') as HTMLElement;
+  const moreBtn = document.getElementById('logViewerMore') as HTMLButtonElement;
+
+  let offset = 0;
+  let requestSeq = 0;
+  let searchTimer: ReturnType<typeof setTimeout> | null = null;
+
+  function renderLines(lines: LogLine[]): string {
+    return lines
+      .map(
+        (line) => `
+        <div class="log-viewer-line level-${line.level}">
+          <span class="log-viewer-source">${line.file === 'desktop' ? 'desktop' : 'api'}:${line.line}</span>
+          <span class="log-viewer-text">${escapeHtml(line.text)}</span>
+        </div>`,
+      )
+      .join('');
+  }
+
+  async function load(append: boolean): Promise<void> {
+    const seq = ++requestSeq;
+    if (!append) offset = 0;
+    statusEl.textContent = 'Loading…';
+    try {
+      const page = await invokeTauri<LogPage>('query_logs', {
+        query: {
+          file: fileEl.value || null,
+          level: levelEl.value || null,
+          search: searchEl.value || null,
+          offset,
+          limit: PAGE_SIZE,
+        },
+      });
+      if (seq !== requestSeq) return;
+      offset += page.lines.length;
+      if (append) {
+        linesEl.insertAdjacentHTML('beforeend', renderLines(page.lines));
+      } else {
+        linesEl.innerHTML = page.lines.length
+          ? renderLines(page.lines)
+          : '<div class="log-viewer-empty">No matching log lines.</div>';
+        linesEl.scrollTop = 0;
+      }
+      statusEl.textContent = `${offset} of ${page.total} lines`;
+      moreBtn.hidden = !page.has_more;
+    } catch (error) {
+      if (seq !== requestSeq) return;
+      statusEl.textContent = `Failed to load logs: ${String(error)}`;
+    }
+  }
+
+  fileEl.addEventListener('change', () => void load(false));
+  levelEl.addEventListener('change', () => void load(false));
+  searchEl.addEventListener('input', () => {
+    if (searchTimer) clearTimeout(searchTimer);
+    searchTimer = setTimeout(() => void load(false), SEARCH_DEBOUNCE_MS);
+  });
+  document.getElementById('logViewerRefresh')?.addEventListener('click', () => void load(false));
+  moreBtn.addEventListener('click', () => void load(true));
+
+  void load(false);
+}
@@ -140,6 +140,10 @@ window.addEventListener('unhandledrejection', (e) => {
 import { debugInjectTestEvents, debugGetCells, getCellCount } from '@/services/geo-convergence';
 import { initMetaTags } from '@/services/meta-tags';
 import { installRuntimeFetchPatch } from '@/services/runtime';
+import { installDesktopLogForwarding } from '@/services/desktop-logger';
+import { installWatchdogResponder } from '@/services/desktop-watchdog';
+import { installSystemAccentColor } from '@/services/system-accent';
+import { applyDetachedPanelMode, getDetachedPanelId, installMapDisplayControls } from '@/services/panel-window';
 import { loadDesktopSecrets } from '@/services/runtime-config';
 import { initAnalytics, trackApiKeysSnapshot } from '@/services/analytics';
 import { applyStoredTheme } from '@/utils/theme-manager';
@@ -160,6 +164,12 @@ initMetaTags();
 
 // In desktop mode, route /api/* calls to the local Tauri sidecar backend.
 installRuntimeFetchPatch();
+// In desktop mode, mirror console errors into desktop.log so they outlive the window.
+installDesktopLogForwarding();
+// In desktop mode, answer the responsiveness watchdog's pings.
+installWatchdogResponder();
+// In desktop mode, expose the OS accent color as --system-accent.
+installSystemAccentColor();
 loadDesktopSecrets().then(async () => {
   await initAnalytics();
   trackApiKeysSnapshot();
@@ -205,6 +215,12 @@ if (urlParams.get('settings') === '1') {
     .init()
     .then(() => {
       clearChunkReloadGuard(chunkReloadStorageKey);
+      // Detached panel windows (panel.html?id=...) boot the full app, then show one panel.
+      const detachedPanelId = getDetachedPanelId();
+      if (detachedPanelId) {
+        applyDetachedPanelMode(detachedPanelId);
+        installMapDisplayControls();
+      }
     })
     .catch(console.error);
 }
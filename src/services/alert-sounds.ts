@@ -0,0 +1,17 @@
+import { isDesktopRuntime } from './runtime';
+import { hasTauriInvokeBridge, tryInvokeTauri } from './tauri-bridge';
+
+/** Mirrors `badging::AlertSeverity` on the Rust side. */
+export type AlertSoundSeverity = 'low' | 'medium' | 'high' | 'critical';
+
+/**
+ * Play an alert sound through the desktop shell, which keeps working while
+ * the window is unfocused or hidden to the tray and applies the user's
+ * volume, quiet hours and Do Not Disturb. Returns false off desktop, where
+ * callers play their own audio.
+ */
+export function playNativeAlertSound(severity: AlertSoundSeverity): boolean {
+  if (!isDesktopRuntime() || !hasTauriInvokeBridge()) return false;
+  void tryInvokeTauri<boolean>('play_sound', { severity });
+  return true;
+}
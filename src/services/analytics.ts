@@ -54,6 +54,11 @@ const SECRET_ANALYTICS_NAMES: Record<RuntimeSecretKey, string> = {
   OLLAMA_MODEL: 'ollama_model',
   WORLDMONITOR_API_KEY: 'worldmonitor',
   WTO_API_KEY: 'wto',
+  FAA_NOTAM_CLIENT_ID: 'faa_notam',
+  FAA_NOTAM_CLIENT_SECRET: 'faa_notam_secret',
+  RELAY_SHARED_SECRET: 'relay_secret',
+  MQTT_PASSWORD: 'mqtt_password',
+  ALERT_WEBHOOK_SECRET: 'alert_webhook_secret',
 };
 
 // ── Typed event schemas (allowlisted properties per event) ──
@@ -9,6 +9,8 @@ import {
 } from '@/generated/client/worldmonitor/conflict/v1/service_client';
 import type { UcdpGeoEvent, UcdpEventType } from '@/types';
 import { createCircuitBreaker } from '@/utils';
+import { isDesktopRuntime } from '../runtime';
+import { tryInvokeTauri } from '../tauri-bridge';
 
 // ---- Client + Circuit Breakers (3 separate breakers for 3 RPCs) ----
 
@@ -227,6 +229,20 @@ const emptyAcledFallback: ListAcledEventsResponse = { events: [], pagination: un
 const emptyUcdpFallback: ListUcdpEventsResponse = { events: [], pagination: undefined };
 const emptyHapiFallback: GetHumanitarianSummaryResponse = { summary: undefined };
 
+// ---- Desktop offline store ----
+
+const ACLED_CONFLICT_TYPES = ['Battles', 'Explosions/Remote violence', 'Violence against civilians'];
+
+/** Conflict events synced into the desktop app's local store (`acled::query_acled_events`). */
+async function fetchStoredAcledEvents(): Promise<ProtoAcledEvent[]> {
+  if (!isDesktopRuntime()) return [];
+  const startDate = new Date(Date.now() - 30 * 24 * 60 * 60 * 1000).toISOString().split('T')[0];
+  const events = await tryInvokeTauri<ProtoAcledEvent[]>('query_acled_events', {
+    query: { startDate, eventTypes: ACLED_CONFLICT_TYPES },
+  });
+  return events ?? [];
+}
+
 // ---- Exported Functions ----
 
 export async function fetchConflictEvents(): Promise<ConflictData> {
@@ -234,7 +250,9 @@ export async function fetchConflictEvents(): Promise<ConflictData> {
     return client.listAcledEvents({ country: '' });
   }, emptyAcledFallback);
 
-  const events = resp.events.map(toConflictEvent);
+  // Offline or upstream down: fall back to the desktop app's synced copy.
+  const protoEvents = resp.events.length > 0 ? resp.events : await fetchStoredAcledEvents();
+  const events = protoEvents.map(toConflictEvent);
 
   const byCountry = new Map<string, ConflictEvent[]>();
   let totalFatalities = 0;
@@ -11,6 +11,8 @@ import type {
   CyberThreatIndicatorType,
 } from '@/types';
 import { createCircuitBreaker } from '@/utils';
+import { isDesktopRuntime } from '../runtime';
+import { tryInvokeTauri } from '../tauri-bridge';
 
 // ---- Client + Circuit Breaker ----
 
@@ -86,6 +88,9 @@ export async function fetchCyberThreats(options: { limit?: number; days?: number
   const days = clampInt(options.days, DEFAULT_DAYS, 1, MAX_DAYS);
   const now = Date.now();
 
+  const stored = await fetchStoredThreats(limit, now - days * 24 * 60 * 60 * 1000);
+  if (stored.length > 0) return stored.map(toCyberThreat);
+
   const resp = await breaker.execute(async () => {
     return client.listCyberThreats({
       timeRange: {
@@ -101,3 +106,10 @@ export async function fetchCyberThreats(options: { limit?: number; days?: number
 
   return resp.threats.map(toCyberThreat);
 }
+
+/** Indicators the desktop app has synced, those placed on the map only. */
+async function fetchStoredThreats(limit: number, since: number): Promise<ProtoCyberThreat[]> {
+  if (!isDesktopRuntime()) return [];
+  const threats = await tryInvokeTauri<ProtoCyberThreat[]>('query_threat_indicators', { query: { since, limit: MAX_LIMIT } });
+  return (threats ?? []).filter((threat) => threat.location).slice(0, limit);
+}
@@ -0,0 +1,80 @@
+import { isDesktopRuntime } from './runtime';
+import { invokeTauri } from './tauri-bridge';
+
+export type CoordinateFormat = 'decimal' | 'dms';
+
+/**
+ * Clipboard access that goes through the desktop shell when available,
+ * where webview clipboard APIs are unreliable, and the browser elsewhere.
+ */
+export async function copyText(text: string): Promise<void> {
+  if (isDesktopRuntime()) {
+    await invokeTauri<void>('copy_to_clipboard', { text });
+    return;
+  }
+  if (navigator.clipboard?.writeText) {
+    await navigator.clipboard.writeText(text);
+    return;
+  }
+  const textarea = document.createElement('textarea');
+  textarea.value = text;
+  textarea.style.position = 'fixed';
+  textarea.style.opacity = '0';
+  document.body.appendChild(textarea);
+  textarea.select();
+  document.execCommand('copy');
+  document.body.removeChild(textarea);
+}
+
+/** Copy `lat, lon` (or degrees/minutes/seconds) and resolve to the copied text. */
+export async function copyCoordinates(lat: number, lon: number, format: CoordinateFormat = 'decimal'): Promise<string> {
+  if (isDesktopRuntime()) {
+    return invokeTauri<string>('copy_coordinates_to_clipboard', { lat, lon, format });
+  }
+  const text = `${lat.toFixed(5)}, ${lon.toFixed(5)}`;
+  await copyText(text);
+  return text;
+}
+
+export async function copyPngImage(png: Blob): Promise<void> {
+  if (isDesktopRuntime()) {
+    await invokeTauri<void>('copy_image_to_clipboard', new Uint8Array(await png.arrayBuffer()));
+    return;
+  }
+  await navigator.clipboard.write([new ClipboardItem({ 'image/png': png })]);
+}
+
+/** Clipboard text, or null when there is none. */
+export async function readClipboardText(): Promise<string | null> {
+  if (isDesktopRuntime()) {
+    return invokeTauri<string | null>('read_clipboard_text');
+  }
+  return (await navigator.clipboard?.readText?.()) || null;
+}
+
+const DECIMAL_PAIR = /^\s*\(?\s*(-?\d{1,3}(?:\.\d+)?)\s*[,;\s]\s*(-?\d{1,3}(?:\.\d+)?)\s*\)?\s*$/;
+const DMS_PART = /(\d{1,3})°\s*(?:(\d{1,2})['′]\s*)?(?:(\d{1,2}(?:\.\d+)?)["″]\s*)?([NSEW])/gi;
+
+/**
+ * Parse `lat, lon` in decimal degrees or `48°51'23.8"N 2°21'07.9"E`, the
+ * formats `copyCoordinates` produces. Returns null for anything else.
+ */
+export function parseCoordinates(text: string): { lat: number; lon: number } | null {
+  let lat: number | undefined;
+  let lon: number | undefined;
+  const decimal = DECIMAL_PAIR.exec(text);
+  if (decimal) {
+    lat = Number(decimal[1]);
+    lon = Number(decimal[2]);
+  } else {
+    for (const [, deg, min, sec, hemisphere] of text.matchAll(DMS_PART)) {
+      const value = Number(deg) + Number(min ?? 0) / 60 + Number(sec ?? 0) / 3600;
+      const h = hemisphere!.toUpperCase();
+      if (h === 'N' || h === 'S') lat = h === 'S' ? -value : value;
+      else lon = h === 'W' ? -value : value;
+    }
+  }
+  if (lat === undefined || lon === undefined) return null;
+  if (!(Math.abs(lat) <= 90 && Math.abs(lon) <= 180)) return null;
+  return { lat, lon };
+}
@@ -0,0 +1,58 @@
+import { isDesktopRuntime } from './runtime';
+import { invokeTauri } from './tauri-bridge';
+
+export type DesktopLogLevel = 'error' | 'warn' | 'info' | 'debug';
+
+let forwardingInstalled = false;
+
+function describe(value: unknown): string {
+  if (value instanceof Error) return value.stack || `${value.name}: ${value.message}`;
+  if (typeof value === 'string') return value;
+  try {
+    return JSON.stringify(value);
+  } catch {
+    return String(value);
+  }
+}
+
+/**
+ * Write a line to the desktop log (desktop.log, `frontend` target). No-op in
+ * the browser. Never throws and never logs its own failures, so it is safe to
+ * call from console hooks.
+ */
+export function logToDesktop(
+  level: DesktopLogLevel,
+  message: string,
+  context?: Record<string, unknown>,
+): void {
+  if (!isDesktopRuntime()) return;
+  void invokeTauri<void>('log_from_frontend', { level, message, context: context ?? null }).catch(() => {});
+}
+
+/**
+ * Mirror console errors/warnings and uncaught errors into the desktop log so
+ * they survive the window being closed. Rate limiting happens on the Rust side.
+ */
+export function installDesktopLogForwarding(): void {
+  if (forwardingInstalled || !isDesktopRuntime()) return;
+  forwardingInstalled = true;
+
+  for (const level of ['error', 'warn'] as const) {
+    const original = console[level].bind(console);
+    console[level] = (...args: unknown[]) => {
+      original(...args);
+      logToDesktop(level, args.map(describe).join(' '));
+    };
+  }
+
+  window.addEventListener('error', (event) => {
+    logToDesktop('error', event.message || describe(event.error), {
+      source: event.filename,
+      line: event.lineno,
+      column: event.colno,
+    });
+  });
+  window.addEventListener('unhandledrejection', (event) => {
+    logToDesktop('error', `Unhandled rejection: ${describe(event.reason)}`);
+  });
+}
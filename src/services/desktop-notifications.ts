@@ -0,0 +1,63 @@
+import { isDesktopRuntime } from './runtime';
+import { invokeTauri, listenTauri } from './tauri-bridge';
+
+export type NotificationUrgency = 'low' | 'normal' | 'critical';
+
+export interface DesktopNotification {
+  title: string;
+  body: string;
+  urgency?: NotificationUrgency;
+  /** Reported back through `onDesktopNotificationAction` when the user clicks the notification. */
+  action?: string;
+}
+
+/** Mirrors `notifications::NotificationAction` on the Rust side. */
+interface NotificationAction {
+  id: number;
+  action: string;
+}
+
+const ACTION_EVENT = 'notification://action';
+
+/** Ids of notifications this window sent with an action; clicks on others are not ours. */
+const sentIds = new Set<number>();
+const handlers = new Set<(action: string) => void>();
+let listening: Promise<void> | null = null;
+
+function ensureListening(): Promise<void> {
+  listening ??= listenTauri<NotificationAction>(ACTION_EVENT, ({ id, action }) => {
+    if (!sentIds.delete(id)) return;
+    for (const handler of handlers) handler(action);
+  })
+    .then(() => {})
+    .catch((error) => {
+      console.warn('[notifications] could not subscribe to notification clicks', error);
+    });
+  return listening;
+}
+
+/**
+ * Show a native notification through the desktop shell. Clicking it brings
+ * up this window and reports `action` to `onDesktopNotificationAction`.
+ * Resolves to the notification id, or null outside the desktop app and when
+ * the OS's Do Not Disturb held the notification back.
+ */
+export async function sendDesktopNotification(notification: DesktopNotification): Promise<number | null> {
+  if (!isDesktopRuntime()) return null;
+  if (notification.action) await ensureListening();
+  const id = await invokeTauri<number | null>('send_notification', {
+    title: notification.title,
+    body: notification.body,
+    urgency: notification.urgency ?? 'normal',
+    action: notification.action ?? null,
+  });
+  if (notification.action && id !== null) sentIds.add(id);
+  return id;
+}
+
+/** Subscribe to clicks on this window's notifications. Returns an unsubscribe function. */
+export function onDesktopNotificationAction(handler: (action: string) => void): () => void {
+  handlers.add(handler);
+  if (isDesktopRuntime()) void ensureListening();
+  return () => handlers.delete(handler);
+}
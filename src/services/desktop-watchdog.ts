@@ -0,0 +1,20 @@
+import { isDesktopRuntime } from './runtime';
+import { invokeTauri, listenTauri } from './tauri-bridge';
+
+let responderInstalled = false;
+
+/**
+ * Answer the Rust watchdog's `watchdog://ping` so it can tell a frozen main
+ * window from a busy one. The ack runs on the page's event loop, so a hung
+ * page stops acking and the desktop shell logs (and optionally reloads) it.
+ */
+export function installWatchdogResponder(): void {
+  if (responderInstalled || !isDesktopRuntime()) return;
+  responderInstalled = true;
+
+  void listenTauri<number>('watchdog://ping', (seq) => {
+    void invokeTauri<void>('watchdog_ack', { seq }).catch(() => {});
+  }).catch((error) => {
+    console.warn('[watchdog] could not subscribe to pings', error);
+  });
+}
@@ -4,6 +4,8 @@ import {
   type ListEarthquakesResponse,
 } from '@/generated/client/worldmonitor/seismology/v1/service_client';
 import { createCircuitBreaker } from '@/utils';
+import { isDesktopRuntime } from './runtime';
+import { tryInvokeTauri } from './tauri-bridge';
 
 // Re-export the proto Earthquake type as the domain's public type
 export type { Earthquake };
@@ -17,5 +19,14 @@ export async function fetchEarthquakes(): Promise<Earthquake[]> {
   const response = await breaker.execute(async () => {
     return client.listEarthquakes({ minMagnitude: 0 });
   }, emptyFallback);
-  return response.earthquakes;
+  // Offline or upstream down: fall back to the desktop app's polled copy.
+  return response.earthquakes.length > 0 ? response.earthquakes : fetchStoredEarthquakes();
+}
+
+/** The last day's quakes from the desktop app's USGS poller (`usgs::query_earthquakes`). */
+async function fetchStoredEarthquakes(): Promise<Earthquake[]> {
+  if (!isDesktopRuntime()) return [];
+  const since = Date.now() - 24 * 60 * 60 * 1000;
+  const earthquakes = await tryInvokeTauri<Earthquake[]>('query_earthquakes', { query: { since } });
+  return earthquakes ?? [];
 }
@@ -25,6 +25,8 @@ import {
 import { createCircuitBreaker } from '@/utils';
 import { getCSSColor } from '@/utils';
 import { isFeatureAvailable } from '../runtime-config';
+import { isDesktopRuntime } from '../runtime';
+import { tryInvokeTauri } from '../tauri-bridge';
 import { dataFreshness } from '../data-freshness';
 
 // ---- Client + Circuit Breakers ----
@@ -95,8 +97,12 @@ async function fetchSingleFredSeries(config: FredConfig): Promise<FredSeries | n
     return client.getFredSeries({ seriesId: config.id, limit: 120 });
   }, emptyFredFallback);
 
-  const obs = resp.series?.observations;
-  if (!obs || obs.length === 0) return null;
+  return summarizeFredSeries(config, resp.series?.observations ?? []);
+}
+
+/** Latest value and change from oldest-first observations. */
+function summarizeFredSeries(config: FredConfig, obs: { date: string; value: number }[]): FredSeries | null {
+  if (obs.length === 0) return null;
 
   if (obs.length >= 2) {
     const latest = obs[obs.length - 1]!;
@@ -135,9 +141,39 @@ async function fetchSingleFredSeries(config: FredConfig): Promise<FredSeries | n
   };
 }
 
+/** Shape of `fred::get_fred_series` in the desktop app. */
+interface NativeFredSeries {
+  id: string;
+  timestamps: number[];
+  values: number[];
+}
+
+/** Series the desktop app fetched with its stored key; empty elsewhere or before the first sync. */
+async function fetchNativeFredData(): Promise<FredSeries[]> {
+  if (!isDesktopRuntime()) return [];
+  const series = await tryInvokeTauri<NativeFredSeries[]>('get_fred_series', {
+    ids: FRED_SERIES.map((config) => config.id),
+    limit: 120,
+  });
+  if (!series) return [];
+  return FRED_SERIES.flatMap((config) => {
+    const native = series.find((s) => s.id === config.id);
+    if (!native) return [];
+    const obs = native.timestamps.map((time, i) => ({
+      date: new Date(time).toISOString().split('T')[0]!,
+      value: native.values[i]!,
+    }));
+    const summary = summarizeFredSeries(config, obs);
+    return summary ? [summary] : [];
+  });
+}
+
 export async function fetchFredData(): Promise<FredSeries[]> {
   if (!isFeatureAvailable('economicFred')) return [];
 
+  const native = await fetchNativeFredData();
+  if (native.length > 0) return native;
+
   const results = await Promise.all(FRED_SERIES.map(fetchSingleFredSeries));
   return results.filter((r): r is FredSeries => r !== null);
 }
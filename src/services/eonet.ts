@@ -1,5 +1,6 @@
 import type { NaturalEvent, NaturalEventCategory } from '@/types';
 import { fetchGDACSEvents, type GDACSEvent } from './gdacs';
+import { fetchDesktopHazards } from './hazards';
 
 interface EonetGeometry {
   magnitudeValue?: number;
@@ -40,6 +41,7 @@ const CATEGORY_ICONS: Record<NaturalEventCategory, string> = {
   severeStorms: '🌀',
   wildfires: '🔥',
   volcanoes: '🌋',
+  tsunamis: '🌊',
   earthquakes: '🔴',
   floods: '🌊',
   landslides: '⛰️',
@@ -86,17 +88,20 @@ function convertGDACSToNaturalEvent(gdacs: GDACSEvent): NaturalEvent {
 }
 
 export async function fetchNaturalEvents(days = 30): Promise<NaturalEvent[]> {
-  const [eonetEvents, gdacsEvents] = await Promise.all([
+  const [eonetEvents, gdacsEvents, desktopHazards] = await Promise.all([
     fetchEonetEvents(days),
     fetchGDACSEvents(),
+    fetchDesktopHazards(days).catch(() => []),
   ]);
 
-  console.log(`[NaturalEvents] EONET: ${eonetEvents.length}, GDACS: ${gdacsEvents.length}`);
+  console.log(`[NaturalEvents] EONET: ${eonetEvents.length}, GDACS: ${gdacsEvents.length}, desktop hazards: ${desktopHazards.length}`);
   const gdacsConverted = gdacsEvents.map(convertGDACSToNaturalEvent);
   const seenLocations = new Set<string>();
   const merged: NaturalEvent[] = [];
 
-  for (const event of gdacsConverted) {
+  // Official volcano alert levels and tsunami bulletins win over the
+  // aggregated copies of the same event.
+  for (const event of [...desktopHazards, ...gdacsConverted]) {
     const key = `${event.lat.toFixed(1)}-${event.lon.toFixed(1)}-${event.category}`;
     if (!seenLocations.has(key)) {
       seenLocations.add(key);
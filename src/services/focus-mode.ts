@@ -0,0 +1,39 @@
+import { isDesktopRuntime } from './runtime';
+import { listenTauri, tryInvokeTauri } from './tauri-bridge';
+
+/** Mirrors `focus_mode::FocusModeStatus` on the Rust side. */
+export interface FocusModeStatus {
+  active: boolean;
+  critical_breaks_through: boolean;
+}
+
+const FOCUS_MODE_EVENT = 'app://focus-mode';
+
+let status: FocusModeStatus | null = null;
+let loading: Promise<void> | null = null;
+
+/** Fetch the OS Do Not Disturb state from the desktop shell and follow its changes. */
+export function loadFocusMode(): Promise<void> {
+  if (!isDesktopRuntime()) return Promise.resolve();
+  loading ??= (async () => {
+    status = await tryInvokeTauri<FocusModeStatus>('get_focus_mode_status');
+    try {
+      await listenTauri<FocusModeStatus>(FOCUS_MODE_EVENT, (next) => {
+        status = next;
+      });
+    } catch (error) {
+      console.warn('[focus-mode] could not subscribe to focus mode changes', error);
+    }
+  })();
+  return loading;
+}
+
+/**
+ * Whether an alert may play a sound: not while the OS is in Do Not Disturb,
+ * unless it is critical and the user lets critical alerts through.
+ */
+export function alertSoundAllowed(critical = false): boolean {
+  void loadFocusMode();
+  if (!status?.active) return true;
+  return critical && status.critical_breaks_through;
+}
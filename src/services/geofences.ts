@@ -0,0 +1,93 @@
+import { isDesktopRuntime } from './runtime';
+import { invokeTauri, listenTauri, tryInvokeTauri } from './tauri-bridge';
+
+/**
+ * Mirrors `geofences::Shape`; coordinates are `[lon, lat]`, radius in
+ * metres, and `zone` a `MaritimeZone` id such as `eez:8442`.
+ */
+export type GeofenceShape =
+  | { type: 'polygon'; coordinates: [number, number][] }
+  | { type: 'circle'; center: [number, number]; radius: number }
+  | { type: 'zone'; zone: string };
+
+/** Mirrors `maritime::MaritimeZone`. */
+export interface MaritimeZone {
+  id: string;
+  kind: 'territorial' | 'eez';
+  name: string;
+  territory: string;
+  sovereign: string;
+  iso: string;
+  polType: string;
+}
+
+/** Mirrors `geofences::Geofence` on the Rust side. */
+export interface Geofence {
+  id?: string;
+  name: string;
+  enabled?: boolean;
+  shape: GeofenceShape;
+  target?: 'aircraft' | 'vessel' | 'any';
+  dwellSecs?: number;
+}
+
+/** Mirrors `geofences::GeofenceEvent`. */
+export interface GeofenceEvent {
+  fenceId: string;
+  fenceName: string;
+  transition: 'enter' | 'exit' | 'dwell';
+  target: 'aircraft' | 'vessel';
+  subject: string;
+  label: string;
+  lat: number;
+  lon: number;
+  insideSecs: number;
+  at: number;
+}
+
+const GEOFENCE_EVENT = 'geofence://event';
+
+/** Saved geofences; empty outside the desktop app. */
+export async function listGeofences(): Promise<Geofence[]> {
+  if (!isDesktopRuntime()) return [];
+  return (await tryInvokeTauri<Geofence[]>('list_geofences')) ?? [];
+}
+
+/**
+ * Save a fence drawn on the map (or replace the one with its id). Rejects
+ * with the shell's validation message, e.g. for a polygon under 3 points.
+ */
+export function saveGeofence(fence: Geofence): Promise<Geofence> {
+  return invokeTauri<Geofence>('save_geofence', { fence });
+}
+
+export async function deleteGeofence(id: string): Promise<boolean> {
+  return (await tryInvokeTauri<boolean>('delete_geofence', { id })) ?? false;
+}
+
+/** The territorial sea and EEZ at a point; empty on the high seas. */
+export async function lookupMaritimeZone(lat: number, lon: number): Promise<MaritimeZone[]> {
+  if (!isDesktopRuntime()) return [];
+  return (await tryInvokeTauri<MaritimeZone[]>('lookup_maritime_zone', { lat, lon })) ?? [];
+}
+
+/** Loaded maritime zones, to pick one for a zone geofence. */
+export async function listMaritimeZones(kind?: MaritimeZone['kind']): Promise<MaritimeZone[]> {
+  if (!isDesktopRuntime()) return [];
+  return (await tryInvokeTauri<MaritimeZone[]>('list_maritime_zones', { kind })) ?? [];
+}
+
+/**
+ * Follow enter, exit and dwell transitions, which the shell evaluates
+ * against every aircraft and vessel position. Resolves to an unsubscribe
+ * function; a no-op outside the desktop app.
+ */
+export async function onGeofenceEvents(handler: (events: GeofenceEvent[]) => void): Promise<() => void> {
+  if (!isDesktopRuntime()) return () => {};
+  try {
+    return await listenTauri<GeofenceEvent[]>(GEOFENCE_EVENT, handler);
+  } catch (error) {
+    console.warn('[geofences] could not subscribe to geofence events', error);
+    return () => {};
+  }
+}
@@ -6,6 +6,8 @@
 import type { NewsItem } from '@/types';
 import type { HappyContentCategory } from '@/services/positive-classifier';
 import { HAPPY_CATEGORY_LABELS } from '@/services/positive-classifier';
+import { copyPngImage } from '@/services/desktop-clipboard';
+import { shareNatively } from '@/services/native-share';
 
 const SIZE = 1080;
 const PAD = 80;
@@ -183,7 +185,7 @@ export async function renderHappyShareCard(item: NewsItem): Promise<HTMLCanvasEl
 
 /**
  * Generate and share a branded PNG card for a positive news item.
- * Fallback chain: Web Share API -> clipboard -> download.
+ * Fallback chain: native share UI (desktop) -> Web Share API -> clipboard -> download.
  * Follows the same pattern as StoryModal.ts lines 128-147.
  */
 export async function shareHappyCard(item: NewsItem): Promise<void> {
@@ -198,7 +200,14 @@ export async function shareHappyCard(item: NewsItem): Promise<void> {
 
   const file = new File([blob], 'happymonitor-story.png', { type: 'image/png' });
 
-  // Attempt 1: Web Share API (mobile-first)
+  // Attempt 1: Native share UI (desktop app)
+  try {
+    if (await shareNatively({ title: item.title, text: item.title, url: item.link, file })) return;
+  } catch {
+    /* share UI unavailable — fall through */
+  }
+
+  // Attempt 2: Web Share API (mobile-first)
   if (navigator.share && navigator.canShare?.({ files: [file] })) {
     try {
       await navigator.share({
@@ -211,17 +220,15 @@ export async function shareHappyCard(item: NewsItem): Promise<void> {
     }
   }
 
-  // Attempt 2: Copy image to clipboard
+  // Attempt 3: Copy image to clipboard
   try {
-    await navigator.clipboard.write([
-      new ClipboardItem({ 'image/png': blob }),
-    ]);
+    await copyPngImage(blob);
     return;
   } catch {
     /* clipboard write failed — fall through to download */
   }
 
-  // Attempt 3: Download via anchor element
+  // Attempt 4: Download via anchor element
   const url = URL.createObjectURL(blob);
   const a = document.createElement('a');
   a.href = url;
@@ -0,0 +1,65 @@
+import type { NaturalEvent } from '@/types';
+import { isDesktopRuntime } from './runtime';
+import { tryInvokeTauri } from './tauri-bridge';
+
+export type HazardKind = 'volcano' | 'tsunami';
+export type HazardLevel = 'information' | 'advisory' | 'watch' | 'warning';
+
+/** A stored row from `hazards::query_hazards`. */
+export interface DesktopHazard {
+  id: string;
+  kind: HazardKind;
+  level: HazardLevel;
+  title: string;
+  description: string;
+  /** Milliseconds since the epoch. */
+  time: number;
+  lat: number | null;
+  lon: number | null;
+  sourceName: string;
+  sourceUrl: string | null;
+  magnitude?: number | null;
+}
+
+const LEVEL_BADGES: Record<HazardLevel, string> = {
+  warning: '🔴 ',
+  watch: '🟠 ',
+  advisory: '🟡 ',
+  information: '',
+};
+
+function toNaturalEvent(hazard: DesktopHazard & { lat: number; lon: number }): NaturalEvent {
+  const volcano = hazard.kind === 'volcano';
+  return {
+    id: hazard.id,
+    title: `${LEVEL_BADGES[hazard.level] ?? ''}${hazard.title}`,
+    description: hazard.description || undefined,
+    category: volcano ? 'volcanoes' : 'tsunamis',
+    categoryTitle: `${volcano ? 'Volcano' : 'Tsunami'} ${hazard.level}`,
+    lat: hazard.lat,
+    lon: hazard.lon,
+    date: new Date(hazard.time),
+    magnitude: hazard.magnitude ?? undefined,
+    magnitudeUnit: hazard.magnitude != null ? 'M' : undefined,
+    sourceUrl: hazard.sourceUrl ?? undefined,
+    sourceName: hazard.sourceName,
+    closed: false,
+  };
+}
+
+/**
+ * Volcano notices and tsunami bulletins from the desktop app's hazard
+ * poller (`hazards::query_hazards`), as natural events for the map. Only
+ * advisories and above from the last `days` that carry a position are
+ * returned; empty off desktop.
+ */
+export async function fetchDesktopHazards(days = 30): Promise<NaturalEvent[]> {
+  if (!isDesktopRuntime()) return [];
+  const since = Date.now() - days * 24 * 60 * 60 * 1000;
+  const hazards = await tryInvokeTauri<DesktopHazard[]>('query_hazards', {
+    query: { kinds: ['volcano', 'tsunami'], minLevel: 'advisory', since },
+  });
+  return (hazards ?? [])
+    .filter((h): h is DesktopHazard & { lat: number; lon: number } => h.lat != null && h.lon != null)
+    .map(toNaturalEvent);
+}
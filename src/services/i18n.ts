@@ -1,5 +1,6 @@
 import i18next from 'i18next';
 import LanguageDetector from 'i18next-browser-languagedetector';
+import { getSystemLocale, loadSystemLocale } from './system-locale';
 
 // English is always needed as fallback — bundle it eagerly.
 import enTranslation from '../locales/en.json';
@@ -72,8 +73,17 @@ export async function initI18n(): Promise<void> {
 
   loadedLanguages.add('en');
 
+  // On desktop the OS language list beats the webview's, which is often
+  // just the engine default.
+  await loadSystemLocale();
+  const detector = new LanguageDetector();
+  detector.addDetector({
+    name: 'desktopLocale',
+    lookup: () => getSystemLocale()?.languages,
+  });
+
   await i18next
-    .use(LanguageDetector)
+    .use(detector)
     .init({
       resources: {
         en: { translation: enTranslation as TranslationDictionary },
@@ -86,7 +96,7 @@ export async function initI18n(): Promise<void> {
         escapeValue: false, // not needed for these simple strings
       },
       detection: {
-        order: ['localStorage', 'navigator'],
+        order: ['localStorage', 'desktopLocale', 'navigator'],
         caches: ['localStorage'],
       },
     });
@@ -125,6 +135,9 @@ export function isRTL(): boolean {
 
 export function getLocale(): string {
   const lang = getCurrentLanguage();
+  // Format for the OS region when it speaks the UI language (en-GB, pt-PT).
+  const system = getSystemLocale()?.locale;
+  if (system && system.split('-')[0]?.toLowerCase() === lang) return system;
   const map: Record<string, string> = { en: 'en-US', el: 'el-GR', zh: 'zh-CN', pt: 'pt-BR', ja: 'ja-JP', tr: 'tr-TR', th: 'th-TH', vi: 'vi-VN' };
   return map[lang] || lang;
 }
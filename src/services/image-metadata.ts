@@ -0,0 +1,29 @@
+import { isDesktopRuntime } from './runtime';
+import { tryInvokeTauri } from './tauri-bridge';
+
+/** Mirrors `image_metadata::ImageMetadata` on the Rust side. */
+export interface ImageMetadata {
+  format: 'jpeg' | 'png' | 'tiff' | 'webp' | 'heif' | null;
+  /** `YYYY-MM-DDTHH:MM:SS`, with the UTC offset when the camera recorded one. */
+  taken: string | null;
+  camera: string | null;
+  lens: string | null;
+  software: string | null;
+  lat: number | null;
+  lon: number | null;
+  altitude: number | null;
+  direction: number | null;
+  fields: Record<string, string>;
+  /** Path of the clean copy, when one was asked for. */
+  stripped: string | null;
+}
+
+/**
+ * Read an image's EXIF timestamp, camera and GPS position in the desktop
+ * shell. With `strip`, a copy without metadata is written next to the file.
+ * Returns null off desktop or when the file cannot be read.
+ */
+export async function readImageMetadata(path: string, strip = false): Promise<ImageMetadata | null> {
+  if (!isDesktopRuntime()) return null;
+  return tryInvokeTauri<ImageMetadata>('read_image_metadata', { path, strip });
+}
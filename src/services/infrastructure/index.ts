@@ -16,6 +16,8 @@ import {
 import type { InternetOutage } from '@/types';
 import { createCircuitBreaker } from '@/utils';
 import { isFeatureAvailable } from '../runtime-config';
+import { isDesktopRuntime } from '../runtime';
+import { listenTauri, tryInvokeTauri } from '../tauri-bridge';
 
 // ---- Client + Circuit Breakers ----
 
@@ -80,6 +82,13 @@ export async function fetchInternetOutages(): Promise<InternetOutage[]> {
     return [];
   }
 
+  // The desktop app syncs Radar itself (`cloudflare::query_internet_outages`).
+  const stored = await fetchStoredOutages();
+  if (stored.length > 0) {
+    outagesConfigured = true;
+    return stored.map(toOutage);
+  }
+
   const resp = await outageBreaker.execute(async () => {
     return client.listInternetOutages({
       country: '',
@@ -97,6 +106,27 @@ export async function fetchInternetOutages(): Promise<InternetOutage[]> {
   return resp.outages.map(toOutage);
 }
 
+/** The last week's outages and traffic anomalies from the desktop app's store. */
+async function fetchStoredOutages(): Promise<ProtoOutage[]> {
+  if (!isDesktopRuntime()) return [];
+  const startDate = new Date(Date.now() - 7 * 24 * 60 * 60 * 1000).toISOString().slice(0, 10);
+  return (await tryInvokeTauri<ProtoOutage[]>('query_internet_outages', { query: { startDate } })) ?? [];
+}
+
+/**
+ * Follow ongoing national-scale outages as the desktop app first sees them.
+ * Resolves to an unsubscribe function; a no-op outside the desktop app.
+ */
+export async function onNationalOutages(handler: (outages: InternetOutage[]) => void): Promise<() => void> {
+  if (!isDesktopRuntime()) return () => {};
+  try {
+    return await listenTauri<ProtoOutage[]>('cloudflare://outages', (outages) => handler(outages.map(toOutage)));
+  } catch (error) {
+    console.warn('[infrastructure] could not subscribe to outage events', error);
+    return () => {};
+  }
+}
+
 export function getOutagesStatus(): string {
   return outageBreaker.getStatus();
 }
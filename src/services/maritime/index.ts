@@ -8,6 +8,8 @@ import { createCircuitBreaker } from '@/utils';
 import type { AisDisruptionEvent, AisDensityZone, AisDisruptionType } from '@/types';
 import { dataFreshness } from '../data-freshness';
 import { isFeatureAvailable } from '../runtime-config';
+import { isDesktopRuntime } from '../runtime';
+import { invokeTauri, listenTauri, tryInvokeTauri } from '../tauri-bridge';
 
 // ---- Proto fallback (desktop safety when relay URL is unavailable) ----
 
@@ -145,7 +147,7 @@ const isLocalhost = isClientRuntime && window.location.hostname === 'localhost';
 // ---- Internal Helpers ----
 
 function shouldIncludeCandidates(): boolean {
-  return positionCallbacks.size > 0;
+  return positionCallbacks.size > 0 && !nativeStatus?.running;
 }
 
 function parseSnapshot(data: unknown): {
@@ -292,6 +294,46 @@ function emitCandidateReports(reports: SnapshotCandidateReport[]): void {
   pruneCallbackTimestampIndex(now);
 }
 
+// ---- Native Stream (desktop) ----
+// The desktop app reads AISstream itself and sends batched positions, so
+// candidate reports are not polled while it runs. Snapshots still supply
+// disruptions and density.
+
+/** Mirrors `ais::AisStreamStatus` on the Rust side. */
+interface NativeStreamStatus {
+  running: boolean;
+  connected: boolean;
+  messages: number;
+  dropped: number;
+}
+
+let nativeStatus: NativeStreamStatus | null = null;
+let nativeStarting = false;
+const nativeUnlisteners: Array<() => void> = [];
+
+async function startNativeStream(): Promise<void> {
+  if (nativeStarting || nativeStatus?.running || !isDesktopRuntime() || !isAisConfigured()) return;
+  nativeStarting = true;
+  try {
+    nativeUnlisteners.push(
+      await listenTauri<SnapshotCandidateReport[]>('ais://positions', emitCandidateReports),
+      await listenTauri<NativeStreamStatus>('ais://status', (status) => { nativeStatus = status; }),
+    );
+    nativeStatus = await invokeTauri<NativeStreamStatus>('start_ais_stream');
+  } catch (error) {
+    console.warn('[ais] native stream unavailable, using relay snapshots', error);
+    stopNativeStream();
+  } finally {
+    nativeStarting = false;
+  }
+}
+
+function stopNativeStream(): void {
+  nativeUnlisteners.splice(0).forEach((unlisten) => unlisten());
+  if (nativeStatus?.running) void tryInvokeTauri<void>('stop_ais_stream');
+  nativeStatus = null;
+}
+
 // ---- Polling ----
 
 async function pollSnapshot(force = false): Promise<void> {
@@ -312,6 +354,9 @@ async function pollSnapshot(force = false): Promise<void> {
     latestDensity = snapshot.density;
     latestStatus = snapshot.status;
     lastPollAt = Date.now();
+    if (nativeStatus?.running) {
+      nativeStatus = (await tryInvokeTauri<NativeStreamStatus>('get_ais_stream_status')) ?? nativeStatus;
+    }
 
     if (includeCandidates) {
       if (snapshot.sequence > lastSequence) {
@@ -379,12 +424,14 @@ if (isClientRuntime) {
 
 export function registerAisCallback(callback: AisCallback): void {
   positionCallbacks.add(callback);
+  void startNativeStream();
   startPolling();
 }
 
 export function unregisterAisCallback(callback: AisCallback): void {
   positionCallbacks.delete(callback);
   if (positionCallbacks.size === 0) {
+    stopNativeStream();
     lastCallbackTimestampByMmsi.clear();
   }
 }
@@ -401,9 +448,13 @@ export function disconnectAisStream(): void {
   isPolling = false;
   inFlight = false;
   latestStatus.connected = false;
+  stopNativeStream();
 }
 
 export function getAisStatus(): { connected: boolean; vessels: number; messages: number } {
+  if (nativeStatus?.running) {
+    return { connected: nativeStatus.connected, vessels: latestStatus.vessels, messages: nativeStatus.messages };
+  }
   const isFresh = Date.now() - lastPollAt <= SNAPSHOT_STALE_MS;
   return {
     connected: latestStatus.connected && isFresh,
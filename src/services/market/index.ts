@@ -14,6 +14,8 @@ import {
 } from '@/generated/client/worldmonitor/market/v1/service_client';
 import type { MarketData, CryptoData } from '@/types';
 import { createCircuitBreaker } from '@/utils';
+import { isDesktopRuntime } from '../runtime';
+import { tryInvokeTauri } from '../tauri-bridge';
 
 // ---- Client + Circuit Breakers ----
 
@@ -77,6 +79,14 @@ export async function fetchMultipleStocks(
   const setKey = symbolSetKey(allSymbolStrings);
   const symbolMetaMap = new Map(symbols.map((s) => [s.symbol, s]));
 
+  const stored = await fetchStoredSeries(allSymbolStrings);
+  if (stored.length === symbols.length) {
+    const data = stored.map((s) => storedToMarketData(s, symbolMetaMap.get(s.symbol)));
+    options.onBatch?.(data);
+    lastSuccessfulByKey.set(setKey, data);
+    return { data };
+  }
+
   const resp = await stockBreaker.execute(async () => {
     return client.listMarketQuotes({ symbols: allSymbolStrings });
   }, emptyStockFallback);
@@ -104,6 +114,32 @@ export async function fetchMultipleStocks(
   };
 }
 
+interface StoredMarketSeries {
+  symbol: string;
+  name: string;
+  values: number[];
+  price: number | null;
+  changePercent: number | null;
+}
+
+/** Series the desktop app polls itself; empty unless every symbol is covered. */
+async function fetchStoredSeries(symbols: string[]): Promise<StoredMarketSeries[]> {
+  if (!isDesktopRuntime() || symbols.length === 0) return [];
+  const series = await tryInvokeTauri<StoredMarketSeries[]>('get_market_series', { symbols });
+  return (series ?? []).filter((s) => s.price != null);
+}
+
+function storedToMarketData(series: StoredMarketSeries, meta?: { name?: string; display?: string }): MarketData {
+  return {
+    symbol: series.symbol,
+    name: meta?.name || series.name,
+    display: meta?.display || series.symbol,
+    price: series.price,
+    change: series.changePercent,
+    sparkline: series.values.length > 0 ? series.values : undefined,
+  };
+}
+
 export async function fetchStockQuote(
   symbol: string,
   name: string,
@@ -15,6 +15,7 @@ import {
   checkWingbitsStatus,
 } from './wingbits';
 import { isFeatureAvailable } from './runtime-config';
+import { getNativeOpenSkyStates } from './opensky-native';
 
 // OpenSky API path — route through Vercel so Railway secret never reaches the browser.
 const OPENSKY_PROXY_URL = '/api/opensky';
@@ -292,7 +293,24 @@ async function fetchQueryRegion(region: QueryRegion): Promise<RegionResult> {
 const STALE_MAX_AGE_MS = 10 * 60 * 1000;
 const regionCache = new Map<string, { flights: MilitaryFlight[]; timestamp: number }>();
 
+// One box around every query region: a single request costs the same OpenSky
+// credits as one large region.
+const NATIVE_BOUNDING_BOX = {
+  south: Math.min(...MILITARY_QUERY_REGIONS.map(r => r.lamin)),
+  west: Math.min(...MILITARY_QUERY_REGIONS.map(r => r.lomin)),
+  north: Math.max(...MILITARY_QUERY_REGIONS.map(r => r.lamax)),
+  east: Math.max(...MILITARY_QUERY_REGIONS.map(r => r.lomax)),
+};
+
 async function fetchFromOpenSky(): Promise<MilitaryFlight[]> {
+  // Desktop: the app polls OpenSky itself within its credit budget.
+  const native = await getNativeOpenSkyStates<OpenSkyStateArray>(NATIVE_BOUNDING_BOX);
+  if (native) {
+    const flights = parseOpenSkyResponse(native);
+    console.log(`[Military Flights] Found ${flights.length} military aircraft from the native OpenSky poller`);
+    return flights;
+  }
+
   const allFlights: MilitaryFlight[] = [];
   const seenHexCodes = new Set<string>();
   let allFailed = true;
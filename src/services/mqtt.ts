@@ -0,0 +1,18 @@
+import { isDesktopRuntime } from './runtime';
+import { tryInvokeTauri } from './tauri-bridge';
+
+/** Mirrors `mqtt::MqttStatus`. */
+export interface MqttStatus {
+  enabled: boolean;
+  connected: boolean;
+  published: number;
+  /** Dropped because the queue was full while disconnected. */
+  dropped: number;
+  error: string | null;
+}
+
+/** The MQTT publisher's status; null outside the desktop app. */
+export async function getMqttStatus(): Promise<MqttStatus | null> {
+  if (!isDesktopRuntime()) return null;
+  return tryInvokeTauri<MqttStatus>('get_mqtt_status');
+}
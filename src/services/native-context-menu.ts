@@ -0,0 +1,69 @@
+import { isDesktopRuntime } from './runtime';
+import { invokeTauri, listenTauri } from './tauri-bridge';
+
+/** Mirrors `context_menu::ContextMenuItem` on the Rust side. */
+export type NativeContextMenuItem =
+  | { type: 'item'; id: string; label: string; enabled?: boolean; checked?: boolean }
+  | { type: 'separator' };
+
+interface ContextMenuSelection {
+  request: number;
+  id: string;
+}
+
+let pending: { request: number; onSelect: (id: string) => void } | null = null;
+// Where the popup is modal (Windows, macOS) the selection can arrive before
+// `show_context_menu` returns its request number.
+let earlySelection: ContextMenuSelection | null = null;
+let subscribed = false;
+
+function takeEarlySelection(): ContextMenuSelection | null {
+  const selection = earlySelection;
+  earlySelection = null;
+  return selection;
+}
+
+function subscribe(): void {
+  if (subscribed) return;
+  subscribed = true;
+  void listenTauri<ContextMenuSelection>('context-menu://selected', ({ request, id }) => {
+    if (pending?.request !== request) {
+      earlySelection = { request, id };
+      return;
+    }
+    const { onSelect } = pending;
+    pending = null;
+    onSelect(id);
+  }).catch((error) => {
+    subscribed = false;
+    console.warn('[context-menu] could not subscribe to selections', error);
+  });
+}
+
+/**
+ * Show a native popup menu at the cursor. Resolves false outside the desktop
+ * app or when the shell refuses, so callers can fall back to an HTML menu.
+ * `onSelect` runs only if an item is picked; a newer menu replaces this one.
+ */
+export async function showNativeContextMenu(
+  items: NativeContextMenuItem[],
+  onSelect: (id: string) => void,
+): Promise<boolean> {
+  if (!isDesktopRuntime()) return false;
+  subscribe();
+  try {
+    takeEarlySelection();
+    const request = await invokeTauri<number>('show_context_menu', { items });
+    const early = takeEarlySelection();
+    if (early?.request === request) {
+      pending = null;
+      onSelect(early.id);
+    } else {
+      pending = { request, onSelect };
+    }
+    return true;
+  } catch (error) {
+    console.warn('[context-menu] native menu unavailable', error);
+    return false;
+  }
+}
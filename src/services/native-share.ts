@@ -0,0 +1,38 @@
+import { isDesktopRuntime } from './runtime';
+import { invokeTauri } from './tauri-bridge';
+
+export interface ShareContent {
+  title?: string;
+  text?: string;
+  /** http(s) link. */
+  url?: string;
+  /** PNG, JPEG, PDF, CSV, JSON or plain text. */
+  file?: File;
+}
+
+/** `shared`: the OS share UI opened. `copied`: there is none (Linux), so the link, text or image was copied. */
+export type ShareResult = 'shared' | 'copied';
+
+function toBase64(file: File): Promise<string> {
+  return new Promise((resolve, reject) => {
+    const reader = new FileReader();
+    reader.onload = () => resolve(String(reader.result).slice(String(reader.result).indexOf(',') + 1));
+    reader.onerror = () => reject(reader.error);
+    reader.readAsDataURL(file);
+  });
+}
+
+/**
+ * Share through the desktop shell's native share UI. Resolves to null in the
+ * browser, where callers use the Web Share API instead.
+ */
+export async function shareNatively(content: ShareContent): Promise<ShareResult | null> {
+  if (!isDesktopRuntime()) return null;
+  const file = content.file ? { name: content.file.name, data: await toBase64(content.file) } : undefined;
+  return invokeTauri<ShareResult>('share_content', {
+    title: content.title,
+    text: content.text,
+    url: content.url,
+    file,
+  });
+}
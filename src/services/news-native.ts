@@ -0,0 +1,57 @@
+import { isDesktopRuntime } from './runtime';
+import { invokeTauri } from './tauri-bridge';
+
+/** Mirrors `news::NewsItem` on the Rust side. */
+interface NativeNewsItem {
+  source: string;
+  title: string;
+  link: string;
+  pubDate: string;
+  imageUrl?: string;
+  lang?: string;
+}
+
+export interface NativeFeedEntry {
+  title: string;
+  link: string;
+  pubDate: Date;
+  imageUrl?: string;
+}
+
+const RSS_PROXY_PREFIX = '/api/rss-proxy?url=';
+
+/** The upstream feed URL behind an `/api/rss-proxy` path. */
+function upstreamUrl(url: string): string | null {
+  if (url.startsWith(RSS_PROXY_PREFIX)) return decodeURIComponent(url.slice(RSS_PROXY_PREFIX.length));
+  return /^https?:\/\//.test(url) ? url : null;
+}
+
+/**
+ * Fetch a feed through the desktop app, which then keeps it refreshed in the
+ * background. Null outside the desktop app or if the app could not fetch it,
+ * so callers fall back to fetching it themselves.
+ */
+export async function fetchNativeFeed(
+  feed: { name: string; lang?: string },
+  url: string,
+  limit = 5,
+): Promise<NativeFeedEntry[] | null> {
+  if (!isDesktopRuntime()) return null;
+  const upstream = upstreamUrl(url);
+  if (!upstream) return null;
+  try {
+    const items = await invokeTauri<NativeNewsItem[]>('fetch_news_feed', {
+      feed: { name: feed.name, url: upstream, lang: feed.lang ?? null },
+      limit,
+    });
+    return items.map((item) => ({
+      title: item.title,
+      link: item.link,
+      pubDate: new Date(item.pubDate),
+      imageUrl: item.imageUrl,
+    }));
+  } catch (error) {
+    console.warn(`[news] native fetch failed for ${feed.name}`, error);
+    return null;
+  }
+}
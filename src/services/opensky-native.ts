@@ -0,0 +1,66 @@
+import { isDesktopRuntime } from './runtime';
+import { invokeTauri, listenTauri } from './tauri-bridge';
+
+/** Mirrors `opensky::Snapshot` on the Rust side: OpenSky's own response shape. */
+export interface NativeOpenSkySnapshot<State = unknown[]> {
+  time: number;
+  states: State[];
+}
+
+/** Mirrors `opensky::StateDiff`. */
+interface StateDiff {
+  time: number;
+  updated: unknown[][];
+  removed: string[];
+}
+
+export interface NativeBoundingBox {
+  south: number;
+  west: number;
+  north: number;
+  east: number;
+}
+
+const DIFF_EVENT = 'opensky://diff';
+
+const states = new Map<string, unknown[]>();
+let time = 0;
+let started: Promise<boolean> | null = null;
+
+function applyDiff(diff: StateDiff): void {
+  for (const id of diff.removed) states.delete(id);
+  for (const state of diff.updated) states.set(String(state[0]), state);
+  time = diff.time;
+}
+
+async function start(boundingBox: NativeBoundingBox): Promise<boolean> {
+  try {
+    // Subscribe before reading the snapshot so no diff falls in between.
+    await listenTauri<StateDiff>(DIFF_EVENT, applyDiff);
+    await invokeTauri('start_opensky_poller', { boundingBox });
+    const snapshot = await invokeTauri<NativeOpenSkySnapshot>('get_opensky_snapshot');
+    if (snapshot.time >= time) {
+      states.clear();
+      for (const state of snapshot.states) states.set(String(state[0]), state);
+      time = snapshot.time;
+    }
+    return true;
+  } catch (error) {
+    console.warn('[opensky] native poller unavailable, using the relay', error);
+    return false;
+  }
+}
+
+/**
+ * Start the desktop app's OpenSky poller for `boundingBox` on first use and
+ * return its latest states, kept current from diff events. Null outside the
+ * desktop app, if the poller could not start, or before its first poll.
+ */
+export async function getNativeOpenSkyStates<State = unknown[]>(
+  boundingBox: NativeBoundingBox,
+): Promise<NativeOpenSkySnapshot<State> | null> {
+  if (!isDesktopRuntime()) return null;
+  started ??= start(boundingBox);
+  if (!(await started) || time === 0) return null;
+  return { time, states: Array.from(states.values()) as State[] };
+}
@@ -0,0 +1,77 @@
+import { isDesktopRuntime } from './runtime';
+import { invokeTauri, listenTauri, tryInvokeTauri } from './tauri-bridge';
+
+/** Mirrors `orbits::SatellitePosition`; `id` is the NORAD catalogue number. */
+export interface SatellitePosition {
+  id: number;
+  name: string;
+  group: string;
+  lat: number;
+  lon: number;
+  altKm: number;
+  speedKms: number;
+}
+
+/** Mirrors `orbits::Pass`; times are epoch milliseconds, angles degrees. */
+export interface SatellitePass {
+  id: number;
+  name: string;
+  rise: number;
+  riseAzimuth: number;
+  culmination: number;
+  maxElevation: number;
+  set: number;
+  setAzimuth: number;
+}
+
+export interface Observer {
+  lat: number;
+  lon: number;
+  altM?: number;
+}
+
+const POSITIONS_EVENT = 'orbits://positions';
+const PASSES_EVENT = 'orbits://passes';
+
+/** Every tracked satellite now (or at `time`); empty outside the desktop app. */
+export async function getSatellitePositions(time?: number): Promise<SatellitePosition[]> {
+  if (!isDesktopRuntime()) return [];
+  return (await tryInvokeTauri<SatellitePosition[]>('get_satellite_positions', { time })) ?? [];
+}
+
+/** `[lon, lat]` points a minute apart; one orbit ahead unless told otherwise. */
+export async function getGroundTrack(id: number, before?: number, after?: number): Promise<[number, number][]> {
+  if (!isDesktopRuntime()) return [];
+  return (await tryInvokeTauri<[number, number][]>('get_ground_track', { id, before, after })) ?? [];
+}
+
+/**
+ * Passes over `observer` (default: the configured one), soonest first.
+ * Rejects with the shell's message when no observer is known.
+ */
+export function predictPasses(
+  ids: number[],
+  options: { observer?: Observer; hours?: number; minElevation?: number } = {},
+): Promise<SatellitePass[]> {
+  return invokeTauri<SatellitePass[]>('predict_passes', { ids, ...options });
+}
+
+async function listen<T>(event: string, handler: (payload: T) => void): Promise<() => void> {
+  if (!isDesktopRuntime()) return () => {};
+  try {
+    return await listenTauri<T>(event, handler);
+  } catch (error) {
+    console.warn(`[orbits] could not subscribe to ${event}`, error);
+    return () => {};
+  }
+}
+
+/** Follow the position batches the shell propagates on its timer. */
+export function onSatellitePositions(handler: (positions: SatellitePosition[]) => void): Promise<() => void> {
+  return listen(POSITIONS_EVENT, handler);
+}
+
+/** Follow the hourly pass predictions for `orbits.pass_satellites`. */
+export function onSatellitePasses(handler: (passes: SatellitePass[]) => void): Promise<() => void> {
+  return listen(PASSES_EVENT, handler);
+}
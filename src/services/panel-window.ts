@@ -0,0 +1,73 @@
+import { isDesktopRuntime } from './runtime';
+import { invokeTauri } from './tauri-bridge';
+import { t } from './i18n';
+
+/** Matches the panel id rules enforced by `open_panel_window` on the Rust side. */
+const PANEL_ID_PATTERN = /^[a-z0-9-]{1,64}$/;
+
+/**
+ * Panel id when this page is a detached panel window (`panel.html?id=...`),
+ * otherwise null.
+ */
+export function getDetachedPanelId(): string | null {
+  if (!location.pathname.endsWith('/panel.html')) return null;
+  const id = new URL(location.href).searchParams.get('id');
+  return id && PANEL_ID_PATTERN.test(id) ? id : null;
+}
+
+/**
+ * Reduce the booted app to a single panel filling the window. The rest of the
+ * dashboard stays mounted underneath so the panel keeps its normal data feed.
+ */
+export function applyDetachedPanelMode(panelId: string): void {
+  document.documentElement.dataset.detachedPanel = panelId;
+  const target = panelId === 'map'
+    ? document.getElementById('mapSection')
+    : document.querySelector<HTMLElement>(`.panel[data-panel="${panelId}"]`);
+  if (!target) {
+    document.title = 'World Monitor';
+    console.warn(`[panel-window] unknown panel "${panelId}"`);
+    return;
+  }
+  target.classList.add('detached-panel-target');
+  // Let size-aware content (the map canvas, virtual lists) re-measure.
+  window.dispatchEvent(new Event('resize'));
+  const title = target.querySelector('.panel-title')?.textContent?.trim();
+  document.title = title ? `${title} - World Monitor` : 'World Monitor';
+}
+
+/**
+ * The chromeless map display (`panel.html?id=map&display=1`) has no title
+ * bar, so Escape closes it.
+ */
+export function installMapDisplayControls(): void {
+  if (new URL(location.href).searchParams.get('display') !== '1') return;
+  document.documentElement.dataset.mapDisplay = '';
+  document.addEventListener('keydown', (e) => {
+    if (e.key !== 'Escape' || e.defaultPrevented) return;
+    void invokeTauri<void>('close_map_display').catch((error) => {
+      console.warn('[panel-window] could not close map display', error);
+    });
+  });
+}
+
+/**
+ * "Open in new window" button for a panel header. Null outside the desktop
+ * app and inside panel windows themselves.
+ */
+export function createPopOutButton(panelId: string): HTMLButtonElement | null {
+  if (!isDesktopRuntime() || getDetachedPanelId() !== null) return null;
+  const button = document.createElement('button');
+  button.type = 'button';
+  button.className = 'panel-popout-btn';
+  button.title = t('components.panel.popOut');
+  button.setAttribute('aria-label', t('components.panel.popOut'));
+  button.innerHTML = '<svg width="12" height="12" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2.5" stroke-linecap="round" stroke-linejoin="round"><path d="M18 13v6a2 2 0 01-2 2H5a2 2 0 01-2-2V8a2 2 0 012-2h6"/><polyline points="15 3 21 3 21 9"/><line x1="10" y1="14" x2="21" y2="3"/></svg>';
+  button.addEventListener('click', (e) => {
+    e.stopPropagation();
+    void invokeTauri<void>('open_panel_window', { panelId }).catch((error) => {
+      console.warn('[panel-window] could not open panel window', error);
+    });
+  });
+  return button;
+}
@@ -0,0 +1,21 @@
+import { isDesktopRuntime } from './runtime';
+import { tryInvokeTauri } from './tauri-bridge';
+
+/** Mirrors `relay::RelayStatus`. */
+export interface RelayStatus {
+  running: boolean;
+  /** `host:port` the relay listens on. */
+  address: string | null;
+  /** Whether `RELAY_SHARED_SECRET` is set, so LAN clients can connect. */
+  secured: boolean;
+  clients: number;
+  maxClients: number;
+  rejected: number;
+  error: string | null;
+}
+
+/** The built-in relay's status; null outside the desktop app. */
+export async function getRelayStatus(): Promise<RelayStatus | null> {
+  if (!isDesktopRuntime()) return null;
+  return tryInvokeTauri<RelayStatus>('get_relay_status');
+}
@@ -7,6 +7,7 @@ import { getPersistentCache, setPersistentCache } from './persistent-cache';
 import { dataFreshness } from './data-freshness';
 import { ingestHeadlines } from './trending-keywords';
 import { getCurrentLanguage } from './i18n';
+import { fetchNativeFeed, type NativeFeedEntry } from './news-native';
 
 // Per-feed circuit breaker: track failures and cooldowns
 const FEED_COOLDOWN_MS = 5 * 60 * 1000; // 5 minutes after failure
@@ -254,41 +255,50 @@ export async function fetchFeed(feed: Feed): Promise<NewsItem[]> {
 
     if (!url) throw new Error(`No URL found for feed ${feed.name}`);
 
-    const response = await fetchWithProxy(url);
-    if (!response.ok) throw new Error(`HTTP ${response.status}`);
-    const text = await response.text();
-    const parser = new DOMParser();
-    const doc = parser.parseFromString(text, 'text/xml');
-
-    const parseError = doc.querySelector('parsererror');
-    if (parseError) {
-      console.warn(`Parse error for ${feed.name}`);
-      recordFeedFailure(feedScope);
-      const persistent = await loadPersistentFeed(feedScope);
-      return cached?.items || persistent || [];
-    }
+    // The desktop app fetches and parses feeds itself, then keeps them fresh.
+    let entries: NativeFeedEntry[] | null = await fetchNativeFeed(feed, url);
+    if (!entries) {
+      const response = await fetchWithProxy(url);
+      if (!response.ok) throw new Error(`HTTP ${response.status}`);
+      const text = await response.text();
+      const parser = new DOMParser();
+      const doc = parser.parseFromString(text, 'text/xml');
+
+      const parseError = doc.querySelector('parsererror');
+      if (parseError) {
+        console.warn(`Parse error for ${feed.name}`);
+        recordFeedFailure(feedScope);
+        const persistent = await loadPersistentFeed(feedScope);
+        return cached?.items || persistent || [];
+      }
 
-    let items = doc.querySelectorAll('item');
-    const isAtom = items.length === 0;
-    if (isAtom) items = doc.querySelectorAll('entry');
-
-    const parsed = Array.from(items)
-      .slice(0, 5)
-      .map((item) => {
-        const title = item.querySelector('title')?.textContent || '';
-        let link = '';
-        if (isAtom) {
-          const linkEl = item.querySelector('link[href]');
-          link = linkEl?.getAttribute('href') || '';
-        } else {
-          link = item.querySelector('link')?.textContent || '';
-        }
+      let items = doc.querySelectorAll('item');
+      const isAtom = items.length === 0;
+      if (isAtom) items = doc.querySelectorAll('entry');
+
+      entries = Array.from(items)
+        .slice(0, 5)
+        .map((item) => {
+          const title = item.querySelector('title')?.textContent || '';
+          let link = '';
+          if (isAtom) {
+            const linkEl = item.querySelector('link[href]');
+            link = linkEl?.getAttribute('href') || '';
+          } else {
+            link = item.querySelector('link')?.textContent || '';
+          }
+
+          const pubDateStr = isAtom
+            ? (item.querySelector('published')?.textContent || item.querySelector('updated')?.textContent || '')
+            : (item.querySelector('pubDate')?.textContent || '');
+          const parsedDate = pubDateStr ? new Date(pubDateStr) : new Date();
+          const pubDate = Number.isNaN(parsedDate.getTime()) ? new Date() : parsedDate;
+          return { title, link, pubDate, imageUrl: SITE_VARIANT === 'happy' ? extractImageUrl(item) : undefined };
+        });
+    }
 
-        const pubDateStr = isAtom
-          ? (item.querySelector('published')?.textContent || item.querySelector('updated')?.textContent || '')
-          : (item.querySelector('pubDate')?.textContent || '');
-        const parsedDate = pubDateStr ? new Date(pubDateStr) : new Date();
-        const pubDate = Number.isNaN(parsedDate.getTime()) ? new Date() : parsedDate;
+    const parsed = entries
+      .map(({ title, link, pubDate, imageUrl }) => {
         const threat = classifyByKeyword(title, SITE_VARIANT);
         const isAlert = threat.level === 'critical' || threat.level === 'high';
         const geoMatches = inferGeoHubsFromTitle(title);
@@ -303,7 +313,7 @@ export async function fetchFeed(feed: Feed): Promise<NewsItem[]> {
           threat,
           ...(topGeo && { lat: topGeo.hub.lat, lon: topGeo.hub.lon, locationName: topGeo.hub.name }),
           lang: feed.lang,
-          ...(SITE_VARIANT === 'happy' && { imageUrl: extractImageUrl(item) }),
+          ...(SITE_VARIANT === 'happy' && { imageUrl }),
         };
       });
 
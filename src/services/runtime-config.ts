@@ -23,7 +23,12 @@ export type RuntimeSecretKey =
   | 'OLLAMA_API_URL'
   | 'OLLAMA_MODEL'
   | 'WORLDMONITOR_API_KEY'
-  | 'WTO_API_KEY';
+  | 'WTO_API_KEY'
+  | 'FAA_NOTAM_CLIENT_ID'
+  | 'FAA_NOTAM_CLIENT_SECRET'
+  | 'RELAY_SHARED_SECRET'
+  | 'MQTT_PASSWORD'
+  | 'ALERT_WEBHOOK_SECRET';
 
 export type RuntimeFeatureId =
   | 'aiGroq'
@@ -42,7 +47,11 @@ export type RuntimeFeatureId =
   | 'nasaFirms'
   | 'aiOllama'
   | 'wtoTrade'
-  | 'supplyChain';
+  | 'supplyChain'
+  | 'faaNotams'
+  | 'localRelay'
+  | 'mqttBridge'
+  | 'signedWebhooks';
 
 export interface RuntimeFeatureDefinition {
   id: RuntimeFeatureId;
@@ -89,6 +98,10 @@ const defaultToggles: Record<RuntimeFeatureId, boolean> = {
   aiOllama: true,
   wtoTrade: true,
   supplyChain: true,
+  faaNotams: true,
+  localRelay: true,
+  mqttBridge: true,
+  signedWebhooks: true,
 };
 
 export const RUNTIME_FEATURES: RuntimeFeatureDefinition[] = [
@@ -213,6 +226,34 @@ export const RUNTIME_FEATURES: RuntimeFeatureDefinition[] = [
     requiredSecrets: ['FRED_API_KEY'],
     fallback: 'Chokepoints and minerals always available; shipping requires FRED key.',
   },
+  {
+    id: 'faaNotams',
+    name: 'FAA NOTAMs',
+    description: 'NOTAMs for configured airports and areas from the FAA NOTAM API (desktop app).',
+    requiredSecrets: ['FAA_NOTAM_CLIENT_ID', 'FAA_NOTAM_CLIENT_SECRET'],
+    fallback: 'Only the public TFR feed is shown.',
+  },
+  {
+    id: 'localRelay',
+    name: 'Built-in relay for LAN clients',
+    description: 'Shared secret LAN clients present to the desktop app\'s built-in AIS/OpenSky relay.',
+    requiredSecrets: ['RELAY_SHARED_SECRET'],
+    fallback: 'The built-in relay only accepts connections from this machine.',
+  },
+  {
+    id: 'mqttBridge',
+    name: 'MQTT publishing',
+    description: 'Password for the broker the desktop app publishes alerts, watchlist hits and metrics to.',
+    requiredSecrets: ['MQTT_PASSWORD'],
+    fallback: 'Connects to the broker with the username only, or anonymously.',
+  },
+  {
+    id: 'signedWebhooks',
+    name: 'Signed alert webhooks',
+    description: 'Signing secret for the HMAC-SHA256 signature on alert webhook requests (desktop app).',
+    requiredSecrets: ['ALERT_WEBHOOK_SECRET'],
+    fallback: 'Alert webhooks are sent unsigned.',
+  },
 ];
 
 function readEnvSecret(key: RuntimeSecretKey): string {
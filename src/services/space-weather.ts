@@ -0,0 +1,49 @@
+import { isDesktopRuntime } from './runtime';
+import { listenTauri, tryInvokeTauri } from './tauri-bridge';
+
+/** `[epoch ms, value]`, oldest first. */
+export type Reading = [number, number];
+
+/** Mirrors `swpc::SpaceWeather`; events are flares and SWPC messages, newest first. */
+export interface SpaceWeather {
+  stormLevel: number | null;
+  kp: Reading[];
+  speed: Reading[];
+  density: Reading[];
+  bz: Reading[];
+  bt: Reading[];
+  events: SpaceWeatherEvent[];
+}
+
+export type SpaceWeatherEvent =
+  | { id: string; kind: 'flare'; time: number; peak: number | null; end: number | null; class: string; flux: number }
+  | { id: string; kind: 'message'; time: number; product: string; message: string };
+
+/** Mirrors `swpc::Conditions`, the payload of `swpc://update`. */
+export interface SpaceWeatherConditions {
+  stormLevel: number | null;
+  kp: number | null;
+  speed: number | null;
+  density: number | null;
+  bz: number | null;
+  bt: number | null;
+}
+
+const UPDATE_EVENT = 'swpc://update';
+
+/** The last `hours` (72 by default) of stored space weather; null outside the desktop app. */
+export async function getSpaceWeather(hours?: number): Promise<SpaceWeather | null> {
+  if (!isDesktopRuntime()) return null;
+  return tryInvokeTauri<SpaceWeather>('get_space_weather', { hours });
+}
+
+/** Follow the conditions after each SWPC poll; resolves to an unsubscribe function. */
+export async function onSpaceWeather(handler: (conditions: SpaceWeatherConditions) => void): Promise<() => void> {
+  if (!isDesktopRuntime()) return () => {};
+  try {
+    return await listenTauri<SpaceWeatherConditions>(UPDATE_EVENT, handler);
+  } catch (error) {
+    console.warn('[space-weather] could not subscribe to updates', error);
+    return () => {};
+  }
+}
@@ -0,0 +1,33 @@
+import { isDesktopRuntime } from './runtime';
+import { hasTauriInvokeBridge, invokeTauri, tryInvokeTauri } from './tauri-bridge';
+
+function hasNativeSpeech(): boolean {
+  return isDesktopRuntime() && hasTauriInvokeBridge();
+}
+
+/**
+ * Read `text` aloud through the platform's text-to-speech. `interrupt` cuts
+ * off what is being said instead of queueing behind it. Returns false off
+ * desktop or when speech is turned off or unavailable.
+ */
+export async function speak(text: string, interrupt = false): Promise<boolean> {
+  if (!hasNativeSpeech() || !text.trim()) return false;
+  try {
+    await invokeTauri<void>('speak', { text, interrupt });
+    return true;
+  } catch (error) {
+    console.warn('[speech] speak failed', error);
+    return false;
+  }
+}
+
+export async function stopSpeaking(): Promise<void> {
+  if (!hasNativeSpeech()) return;
+  await tryInvokeTauri<void>('stop_speaking');
+}
+
+/** Read the latest notification digest aloud. Returns false off desktop or on failure. */
+export async function speakLatestDigest(): Promise<boolean> {
+  if (!hasNativeSpeech()) return false;
+  return (await tryInvokeTauri<unknown>('speak_digest')) !== null;
+}
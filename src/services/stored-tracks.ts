@@ -0,0 +1,26 @@
+import { isDesktopRuntime } from './runtime';
+import { tryInvokeTauri } from './tauri-bridge';
+
+interface TrackPoint {
+  time: number;
+  lat: number;
+  lon: number;
+}
+
+const TRAIL_WINDOW_MS = 24 * 60 * 60 * 1000;
+
+/**
+ * The last day's recorded positions of an aircraft (ICAO24) or vessel (MMSI)
+ * from the desktop app's event store (`store::get_track`), as `[lat, lon]`
+ * pairs oldest first. Null outside the desktop app or when nothing is stored.
+ */
+export async function fetchStoredTrack(
+  kind: 'flight' | 'vessel',
+  id: string,
+): Promise<[number, number][] | null> {
+  if (!isDesktopRuntime()) return null;
+  const from = Date.now() - TRAIL_WINDOW_MS;
+  const points = await tryInvokeTauri<TrackPoint[]>('get_track', { id, kind, from });
+  if (!points || points.length === 0) return null;
+  return points.map((p): [number, number] => [p.lat, p.lon]);
+}
@@ -4,7 +4,9 @@
  * Fallback: Ollama -> Groq -> OpenRouter -> Browser T5
  *
  * Uses NewsServiceClient.summarizeArticle() RPC instead of legacy
- * per-provider fetch endpoints.
+ * per-provider fetch endpoints. On desktop the provider chain runs in the
+ * shell instead (`summarize`), which streams the reply and keeps the API
+ * keys out of the webview.
  */
 
 import { mlWorker } from './ml-worker';
@@ -14,6 +16,8 @@ import { isFeatureAvailable, type RuntimeFeatureId } from './runtime-config';
 import { trackLLMUsage, trackLLMFailure } from './analytics';
 import { NewsServiceClient, type SummarizeArticleResponse } from '@/generated/client/worldmonitor/news/v1/service_client';
 import { createCircuitBreaker } from '@/utils';
+import { isDesktopRuntime } from './runtime';
+import { createTauriChannel, hasTauriInvokeBridge, tryInvokeTauri } from './tauri-bridge';
 
 export type SummarizationProvider = 'ollama' | 'groq' | 'openrouter' | 'browser' | 'cache';
 
@@ -29,6 +33,7 @@ export type ProgressCallback = (step: number, total: number, message: string) =>
 export interface SummarizeOptions {
   skipCloudProviders?: boolean;  // true = skip Ollama/Groq/OpenRouter, go straight to browser T5
   skipBrowserFallback?: boolean; // true = skip browser T5 fallback
+  onToken?: (partial: string) => void; // desktop: the reply so far, as it streams
 }
 
 // ── Sebuf client (replaces direct fetch to /api/{provider}-summarize) ──
@@ -97,6 +102,52 @@ async function tryApiProvider(
   }
 }
 
+// ── Desktop provider chain (streams over a Tauri channel) ──
+
+type NativeSummaryEvent =
+  | { kind: 'started'; provider: 'ollama' | 'groq' | 'openrouter'; model: string }
+  | { kind: 'token'; text: string }
+  | { kind: 'reset' };
+
+interface NativeSummary {
+  text: string;
+  provider: 'ollama' | 'groq' | 'openrouter';
+  model: string;
+}
+
+function hasNativeSummaries(): boolean {
+  return isDesktopRuntime() && hasTauriInvokeBridge();
+}
+
+async function tryNativeSummary(
+  headlines: string[],
+  geoContext: string | undefined,
+  lang: string | undefined,
+  onProgress: ProgressCallback | undefined,
+  step: number,
+  totalSteps: number,
+  onToken: ((partial: string) => void) | undefined,
+): Promise<SummarizationResult | null> {
+  let partial = '';
+  const channel = createTauriChannel<NativeSummaryEvent>((event) => {
+    if (event.kind === 'started') {
+      lastAttemptedProvider = event.provider;
+      const label = API_PROVIDERS.find(p => p.provider === event.provider)?.label ?? event.provider;
+      onProgress?.(step, totalSteps, `Connecting to ${label}...`);
+    } else {
+      partial = event.kind === 'token' ? partial + event.text : '';
+      onToken?.(partial);
+    }
+  });
+  const result = await tryInvokeTauri<NativeSummary>('summarize', {
+    request: { mode: 'brief', headlines, context: geoContext || '', lang: lang || 'en' },
+    onEvent: channel,
+  });
+  if (!result?.text) return null;
+  console.log(`[Summarization] ${result.provider} success (native):`, result.model);
+  return { summary: result.text, provider: result.provider, model: result.model, cached: false };
+}
+
 // ── Browser T5 provider (different interface -- no API call) ──
 
 async function tryBrowserT5(headlines: string[], modelId?: string): Promise<SummarizationResult | null> {
@@ -139,7 +190,11 @@ async function runApiChain(
   onProgress: ProgressCallback | undefined,
   stepOffset: number,
   totalSteps: number,
+  onToken?: (partial: string) => void,
 ): Promise<SummarizationResult | null> {
+  if (hasNativeSummaries()) {
+    return tryNativeSummary(headlines, geoContext, lang, onProgress, stepOffset, totalSteps, onToken);
+  }
   for (const [i, provider] of providers.entries()) {
     onProgress?.(stepOffset + i, totalSteps, `Connecting to ${provider.label}...`);
     const result = await tryApiProvider(provider, headlines, geoContext, lang);
@@ -207,7 +262,7 @@ async function generateSummaryInternal(
 
       // Warm model failed inference -- fallback through API providers
       if (!options?.skipCloudProviders) {
-        const chainResult = await runApiChain(API_PROVIDERS, headlines, geoContext, undefined, onProgress, 2, totalSteps);
+        const chainResult = await runApiChain(API_PROVIDERS, headlines, geoContext, undefined, onProgress, 2, totalSteps, options?.onToken);
         if (chainResult) return chainResult;
       }
     } else {
@@ -219,7 +274,7 @@ async function generateSummaryInternal(
 
       // API providers while model loads
       if (!options?.skipCloudProviders) {
-        const chainResult = await runApiChain(API_PROVIDERS, headlines, geoContext, undefined, onProgress, 1, totalSteps);
+        const chainResult = await runApiChain(API_PROVIDERS, headlines, geoContext, undefined, onProgress, 1, totalSteps, options?.onToken);
         if (chainResult) {
           if (chainResult.provider === 'groq') console.log('[BETA] Groq:', chainResult.summary);
           return chainResult;
@@ -245,7 +300,7 @@ async function generateSummaryInternal(
   let chainResult: SummarizationResult | null = null;
 
   if (!options?.skipCloudProviders) {
-    chainResult = await runApiChain(API_PROVIDERS, headlines, geoContext, lang, onProgress, 1, totalSteps);
+    chainResult = await runApiChain(API_PROVIDERS, headlines, geoContext, lang, onProgress, 1, totalSteps, options?.onToken);
   }
   if (chainResult) return chainResult;
 
@@ -272,6 +327,11 @@ export async function translateText(
 ): Promise<string | null> {
   if (!text) return null;
 
+  if (hasNativeSummaries()) {
+    const [translated] = (await translateTexts([text], targetLang)) ?? [];
+    return translated?.trim() || null;
+  }
+
   const totalSteps = API_PROVIDERS.length;
   for (const [i, providerDef] of API_PROVIDERS.entries()) {
     if (!isFeatureAvailable(providerDef.featureId)) continue;
@@ -299,3 +359,13 @@ export async function translateText(
 
   return null;
 }
+
+/**
+ * Translate several texts at once through the desktop `translate_text`
+ * command, which caches each translation locally. Returns null off desktop
+ * or when no provider answered; texts come back in the order given.
+ */
+export async function translateTexts(texts: string[], targetLang: string): Promise<string[] | null> {
+  if (!hasNativeSummaries() || texts.length === 0) return null;
+  return tryInvokeTauri<string[]>('translate_text', { texts, targetLang });
+}
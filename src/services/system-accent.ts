@@ -0,0 +1,34 @@
+import { isDesktopRuntime } from './runtime';
+import { listenTauri, tryInvokeTauri } from './tauri-bridge';
+import { invalidateColorCache } from '@/utils/theme-colors';
+
+/** Mirrors `accent_color::AccentColor` on the Rust side. */
+interface AccentColor {
+  /** `#rrggbb`, or null when the OS does not expose one. */
+  accent: string | null;
+}
+
+const CHANGED_EVENT = 'app://accent-color';
+const CSS_VAR = '--system-accent';
+
+function apply({ accent }: AccentColor): void {
+  const root = document.documentElement.style;
+  if (accent) root.setProperty(CSS_VAR, accent);
+  else root.removeProperty(CSS_VAR);
+  invalidateColorCache();
+  window.dispatchEvent(new CustomEvent('system-accent-changed', { detail: { accent } }));
+}
+
+/**
+ * Expose the OS accent color as the `--system-accent` CSS variable and keep
+ * it in sync when the user changes it. No-op outside the desktop app.
+ */
+export function installSystemAccentColor(): void {
+  if (!isDesktopRuntime()) return;
+  void tryInvokeTauri<AccentColor>('get_accent_color').then((color) => {
+    if (color) apply(color);
+  });
+  listenTauri<AccentColor>(CHANGED_EVENT, apply).catch((error) => {
+    console.warn('[accent] could not subscribe to accent color changes', error);
+  });
+}
@@ -0,0 +1,50 @@
+import { isDesktopRuntime } from './runtime';
+import { listenTauri, tryInvokeTauri } from './tauri-bridge';
+
+export interface SystemLocale {
+  /** BCP 47 tag for number/date formatting, e.g. `en-GB`. */
+  locale: string;
+  region: string | null;
+  /** Preferred UI languages, most preferred first. */
+  languages: string[];
+}
+
+const CHANGED_EVENT = 'app://locale-changed';
+const WINDOW_EVENT = 'system-locale-changed';
+
+let systemLocale: SystemLocale | null = null;
+let loading: Promise<SystemLocale | null> | null = null;
+
+/**
+ * Fetch the OS locale from the desktop shell and follow its changes.
+ * Resolves to null in the browser, where `navigator.languages` is all there is.
+ */
+export function loadSystemLocale(): Promise<SystemLocale | null> {
+  if (!isDesktopRuntime()) return Promise.resolve(null);
+  loading ??= (async () => {
+    systemLocale = await tryInvokeTauri<SystemLocale>('get_locale');
+    try {
+      await listenTauri<SystemLocale>(CHANGED_EVENT, (locale) => {
+        systemLocale = locale;
+        window.dispatchEvent(new CustomEvent(WINDOW_EVENT, { detail: { locale } }));
+      });
+    } catch (error) {
+      console.warn('[locale] could not subscribe to locale changes', error);
+    }
+    return systemLocale;
+  })();
+  return loading;
+}
+
+/** The OS locale once `loadSystemLocale` has resolved, otherwise null. */
+export function getSystemLocale(): SystemLocale | null {
+  return systemLocale;
+}
+
+export function subscribeSystemLocaleChange(cb: (locale: SystemLocale) => void): () => void {
+  const handler = (e: Event) => {
+    cb((e as CustomEvent).detail.locale as SystemLocale);
+  };
+  window.addEventListener(WINDOW_EVENT, handler);
+  return () => window.removeEventListener(WINDOW_EVENT, handler);
+}
@@ -1,4 +1,7 @@
-type TauriInvoke = <T>(command: string, payload?: Record<string, unknown>) => Promise<T>;
+/** Named arguments, or raw bytes that reach the command as its request body. */
+type TauriInvokePayload = Record<string, unknown> | Uint8Array;
+
+type TauriInvoke = <T>(command: string, payload?: TauriInvokePayload) => Promise<T>;
 
 function resolveInvokeBridge(): TauriInvoke | null {
   if (typeof window === 'undefined') {
@@ -23,7 +26,7 @@ export function hasTauriInvokeBridge(): boolean {
 
 export async function invokeTauri<T>(
   command: string,
-  payload?: Record<string, unknown>,
+  payload?: TauriInvokePayload,
 ): Promise<T> {
   const invoke = resolveInvokeBridge();
   if (!invoke) {
@@ -44,3 +47,62 @@ export async function tryInvokeTauri<T>(
     return null;
   }
 }
+
+type TauriTransformCallback = (callback: (event: unknown) => void, once?: boolean) => number;
+
+/**
+ * Subscribe to a Rust-emitted event. Uses the event plugin directly because
+ * the app does not bundle `@tauri-apps/api`. Resolves to an unlisten function.
+ */
+export async function listenTauri<T>(
+  event: string,
+  handler: (payload: T) => void,
+): Promise<() => void> {
+  const internals = (window as unknown as {
+    __TAURI_INTERNALS__?: { transformCallback?: TauriTransformCallback };
+  }).__TAURI_INTERNALS__;
+  if (typeof internals?.transformCallback !== 'function') {
+    throw new Error('Tauri event bridge unavailable');
+  }
+
+  const callbackId = internals.transformCallback((message) => {
+    handler((message as { payload: T }).payload);
+  });
+  const eventId = await invokeTauri<number>('plugin:event|listen', {
+    event,
+    target: { kind: 'Any' },
+    handler: callbackId,
+  });
+  return () => {
+    void invokeTauri('plugin:event|unlisten', { event, eventId }).catch(() => {});
+  };
+}
+
+/**
+ * A channel argument for commands that stream over `tauri::ipc::Channel`,
+ * handing messages to `handler` in the order they were sent. Serializes
+ * the way the command expects, without `@tauri-apps/api`.
+ */
+export function createTauriChannel<T>(handler: (message: T) => void): { toJSON(): string } {
+  const internals = (window as unknown as {
+    __TAURI_INTERNALS__?: { transformCallback?: TauriTransformCallback };
+  }).__TAURI_INTERNALS__;
+  if (typeof internals?.transformCallback !== 'function') {
+    throw new Error('Tauri channel bridge unavailable');
+  }
+
+  let next = 0;
+  const pending = new Map<number, T>();
+  const callbackId = internals.transformCallback((raw) => {
+    const { message, index, id, end } = raw as { message: T; index?: number; id?: number; end?: boolean };
+    if (end) return;
+    pending.set(index ?? id ?? next, message);
+    while (pending.has(next)) {
+      const queued = pending.get(next) as T;
+      pending.delete(next);
+      next += 1;
+      handler(queued);
+    }
+  });
+  return { toJSON: () => `__CHANNEL__:${callbackId}` };
+}
@@ -0,0 +1,46 @@
+import { isDesktopRuntime } from './runtime';
+import { tryInvokeTauri } from './tauri-bridge';
+
+/** Mirrors `tile_cache::TileCacheStatus` on the Rust side. */
+interface TileCacheStatus {
+  baseUrl: string;
+  url: string;
+  bytes: number | null;
+  maxBytes: number;
+}
+
+let tilePattern: RegExp | null = null;
+let baseUrl = '';
+let loading: Promise<void> | null = null;
+
+/** A regex matching the provider's tile URLs, capturing z, x and y. */
+function patternFor(template: string): RegExp {
+  const escaped = template.replace(/[.*+?^$()|[\]\\]/g, '\\$&');
+  const source = escaped
+    .replace('{s}', '[a-z0-9-]+')
+    .replace('{z}', '(?<z>\\d+)')
+    .replace('{x}', '(?<x>\\d+)')
+    .replace('{y}', '(?<y>\\d+)');
+  return new RegExp(`^${source}$`);
+}
+
+/**
+ * Look up which tile provider the desktop app caches. Until this resolves
+ * (and outside the desktop app) tiles load straight from the provider.
+ */
+export function initTileCache(): Promise<void> {
+  if (!isDesktopRuntime()) return Promise.resolve();
+  loading ??= tryInvokeTauri<TileCacheStatus>('get_tile_cache_status').then((status) => {
+    if (!status) return;
+    tilePattern = patternFor(status.url);
+    baseUrl = status.baseUrl;
+  });
+  return loading;
+}
+
+/** The `tiles:` URL serving `url` from the desktop tile cache, if it is one of the cached provider's tiles. */
+export function cachedTileUrl(url: string): string | null {
+  const groups = tilePattern?.exec(url)?.groups;
+  if (!groups) return null;
+  return `${baseUrl}/${groups.z}/${groups.x}/${groups.y}`;
+}
@@ -4,7 +4,7 @@ import { RuntimeConfigPanel } from '@/components/RuntimeConfigPanel';
 import { WorldMonitorTab } from '@/components/WorldMonitorTab';
 import { RUNTIME_FEATURES, loadDesktopSecrets } from '@/services/runtime-config';
 import { getApiBaseUrl, resolveLocalApiPort } from '@/services/runtime';
-import { tryInvokeTauri } from '@/services/tauri-bridge';
+import { invokeTauri, listenTauri, tryInvokeTauri } from '@/services/tauri-bridge';
 import { escapeHtml } from '@/utils/sanitize';
 import { initI18n, t } from '@/services/i18n';
 import { applyStoredTheme } from '@/utils/theme-manager';
@@ -30,34 +30,51 @@ async function invokeDesktopAction(command: string, successLabel: string): Promi
   setActionStatus(t('modals.settingsWindow.invokeFail', { command }), 'error');
 }
 
-function initTabs(): void {
-  const tabs = document.querySelectorAll<HTMLButtonElement>('.settings-tab');
-  const panels = document.querySelectorAll<HTMLElement>('.settings-tab-panel');
+/**
+ * Sections accepted by `open_settings_window_command` / `worldmonitor://settings/<section>`,
+ * mapped to their tab's `data-tab`.
+ */
+const SECTION_TABS: Record<string, string> = {
+  worldmonitor: 'worldmonitor',
+  llms: 'llms',
+  'api-keys': 'keys',
+  desktop: 'desktop',
+  debug: 'debug',
+};
+
+function activateTab(tab: HTMLButtonElement): void {
+  const target = tab.dataset.tab;
+  if (!target) return;
+
+  document.querySelectorAll<HTMLButtonElement>('.settings-tab').forEach((t) => {
+    t.classList.remove('active');
+    t.setAttribute('aria-selected', 'false');
+  });
+  document.querySelectorAll<HTMLElement>('.settings-tab-panel').forEach((p) => p.classList.remove('active'));
 
-  tabs.forEach((tab) => {
-    tab.addEventListener('click', () => {
-      const target = tab.dataset.tab;
-      if (!target) return;
+  tab.classList.add('active');
+  tab.setAttribute('aria-selected', 'true');
 
-      tabs.forEach((t) => {
-        t.classList.remove('active');
-        t.setAttribute('aria-selected', 'false');
-      });
-      panels.forEach((p) => p.classList.remove('active'));
+  const panelId = tab.getAttribute('aria-controls');
+  if (panelId) {
+    document.getElementById(panelId)?.classList.add('active');
+  }
 
-      tab.classList.add('active');
-      tab.setAttribute('aria-selected', 'true');
+  if (target === 'debug' && !diagnosticsInitialized) {
+    diagnosticsInitialized = true;
+    initDiagnostics();
+  }
+}
 
-      const panelId = tab.getAttribute('aria-controls');
-      if (panelId) {
-        document.getElementById(panelId)?.classList.add('active');
-      }
+function openSection(section: string): void {
+  const tabId = SECTION_TABS[section];
+  const tab = tabId ? document.querySelector<HTMLButtonElement>(`.settings-tab[data-tab="${tabId}"]`) : null;
+  if (tab) activateTab(tab);
+}
 
-      if (target === 'debug' && !diagnosticsInitialized) {
-        diagnosticsInitialized = true;
-        initDiagnostics();
-      }
-    });
+function initTabs(): void {
+  document.querySelectorAll<HTMLButtonElement>('.settings-tab').forEach((tab) => {
+    tab.addEventListener('click', () => activateTab(tab));
   });
 }
 
@@ -170,7 +187,342 @@ async function initSettingsWindow(): Promise<void> {
     void invokeDesktopAction('open_sidecar_log_file', t('modals.settingsWindow.openApiLog'));
   });
 
+  document.getElementById('openDesktopLogBtn')?.addEventListener('click', () => {
+    void invokeDesktopAction('open_desktop_log_file', t('modals.settingsWindow.openDesktopLog'));
+  });
+
+  document.getElementById('openLogViewerBtn')?.addEventListener('click', () => {
+    void tryInvokeTauri<void>('open_log_viewer_window_command');
+  });
+
+  document.getElementById('clearLogsBtn')?.addEventListener('click', () => {
+    if (!window.confirm(t('modals.settingsWindow.clearLogsConfirm'))) return;
+    void invokeTauri<{ reclaimed_bytes: number }>('clear_logs')
+      .then(({ reclaimed_bytes }) => {
+        const size = `${(reclaimed_bytes / (1024 * 1024)).toFixed(1)} MB`;
+        setActionStatus(t('modals.settingsWindow.logsCleared', { size }), 'ok');
+      })
+      .catch((error: unknown) => {
+        setActionStatus(t('modals.settingsWindow.clearLogsFailed', { error: String(error) }), 'error');
+      });
+  });
+
+  document.getElementById('restartAppBtn')?.addEventListener('click', () => {
+    void invokeTauri<void>('restart_app').catch((error: unknown) => {
+      setActionStatus(t('modals.settingsWindow.restartFailed', { error: String(error) }), 'error');
+    });
+  });
+
+  void initErrorReporting();
+  void initGlobalShortcut();
+  void initLaunchWindow();
+  void initLaunchAtLogin();
+  void initBatteryRefresh();
+  void initFocusMode();
+  void initAdsbReceiver();
+  void initBuildInfo();
+  void initSystemProxy();
   initTabs();
+
+  const section = new URLSearchParams(location.search).get('section');
+  if (section) openSection(section);
+  void listenTauri<string>('settings://navigate', openSection).catch((error) => {
+    console.warn('[settings] could not subscribe to section navigation', error);
+  });
+}
+
+interface ErrorReportingSettings {
+  write_to_file: boolean;
+  endpoint: string | null;
+}
+
+async function initErrorReporting(): Promise<void> {
+  const fileToggle = document.getElementById('errorReportFile') as HTMLInputElement | null;
+  const endpointInput = document.getElementById('errorReportEndpoint') as HTMLInputElement | null;
+  if (!fileToggle || !endpointInput) return;
+
+  const current = await tryInvokeTauri<ErrorReportingSettings>('get_error_reporting');
+  if (current) {
+    fileToggle.checked = current.write_to_file;
+    endpointInput.value = current.endpoint ?? '';
+  }
+
+  document.getElementById('saveErrorReportingBtn')?.addEventListener('click', () => {
+    const settings: ErrorReportingSettings = {
+      write_to_file: fileToggle.checked,
+      endpoint: endpointInput.value.trim() || null,
+    };
+    void invokeTauri<void>('set_error_reporting', { settings })
+      .then(() => setActionStatus(t('modals.settingsWindow.errorReportingSaved'), 'ok'))
+      .catch((error: unknown) => {
+        setActionStatus(t('modals.settingsWindow.errorReportingFailed', { error: String(error) }), 'error');
+      });
+  });
+}
+
+interface AboutInfo {
+  name: string;
+  version: string;
+  git_commit: string;
+  build_date: string;
+  sidecar_node_version: string | null;
+  license: string;
+}
+
+async function initBuildInfo(): Promise<void> {
+  const el = document.getElementById('aboutBuildInfo');
+  if (!el) return;
+  const info = await tryInvokeTauri<AboutInfo>('get_about_info');
+  if (!info) return;
+  el.textContent = t('modals.settingsWindow.buildInfo', {
+    name: info.name,
+    version: info.version,
+    commit: info.git_commit,
+    date: info.build_date,
+    node: info.sidecar_node_version ?? '?',
+    license: info.license,
+  });
+}
+
+/** Mirrors `proxy::SystemProxy` on the Rust side (credentials stripped). */
+interface SystemProxy {
+  source: 'none' | 'environment' | 'system';
+  http: string | null;
+  https: string | null;
+  bypass: string[];
+  pac_url: string | null;
+  auto_detect: boolean;
+}
+
+async function initSystemProxy(): Promise<void> {
+  const el = document.getElementById('systemProxyInfo');
+  if (!el) return;
+  const proxy = await tryInvokeTauri<SystemProxy>('get_system_proxy');
+  if (!proxy) return;
+  const server = proxy.https ?? proxy.http;
+  if (server) {
+    el.textContent = t('modals.settingsWindow.systemProxy', { server, source: proxy.source });
+  } else if (proxy.pac_url || proxy.auto_detect) {
+    el.textContent = t('modals.settingsWindow.systemProxyAuto', { url: proxy.pac_url ?? 'WPAD' });
+  } else {
+    el.textContent = t('modals.settingsWindow.systemProxyNone');
+  }
+}
+
+async function initGlobalShortcut(): Promise<void> {
+  const input = document.getElementById('globalShortcutInput') as HTMLInputElement | null;
+  if (!input) return;
+
+  input.value = (await tryInvokeTauri<string | null>('get_global_shortcut')) ?? '';
+
+  const apply = (shortcut: string | null): void => {
+    void invokeTauri<void>('set_global_shortcut', { shortcut })
+      .then(() => {
+        input.value = shortcut ?? '';
+        setActionStatus(
+          shortcut
+            ? t('modals.settingsWindow.globalShortcutSaved', { shortcut })
+            : t('modals.settingsWindow.globalShortcutDisabled'),
+          'ok',
+        );
+      })
+      .catch((error: unknown) => {
+        setActionStatus(t('modals.settingsWindow.globalShortcutFailed', { error: String(error) }), 'error');
+      });
+  };
+
+  document.getElementById('saveGlobalShortcutBtn')?.addEventListener('click', () => {
+    apply(input.value.trim() || null);
+  });
+  document.getElementById('disableGlobalShortcutBtn')?.addEventListener('click', () => apply(null));
+}
+
+type LaunchWindow = 'dashboard' | 'tray' | 'settings';
+
+async function initLaunchWindow(): Promise<void> {
+  const select = document.getElementById('launchWindowSelect') as HTMLSelectElement | null;
+  if (!select) return;
+
+  let current = (await tryInvokeTauri<LaunchWindow>('get_launch_window')) ?? 'dashboard';
+  select.value = current;
+
+  select.addEventListener('change', () => {
+    const choice = select.value as LaunchWindow;
+    void invokeTauri<void>('set_launch_window', { window: choice })
+      .then(() => {
+        current = choice;
+        setActionStatus(t('modals.settingsWindow.launchWindowSaved'), 'ok');
+      })
+      .catch((error: unknown) => {
+        select.value = current;
+        setActionStatus(t('modals.settingsWindow.launchWindowFailed', { error: String(error) }), 'error');
+      });
+  });
+}
+
+async function initLaunchAtLogin(): Promise<void> {
+  const toggle = document.getElementById('launchAtLoginToggle') as HTMLInputElement | null;
+  if (!toggle) return;
+
+  toggle.checked = (await tryInvokeTauri<boolean>('get_launch_at_login')) ?? false;
+
+  toggle.addEventListener('change', () => {
+    const requested = toggle.checked;
+    toggle.disabled = true;
+    void invokeTauri<boolean>('set_launch_at_login', { enabled: requested })
+      .then((enabled) => {
+        toggle.checked = enabled;
+        setActionStatus(t(enabled ? 'modals.settingsWindow.launchAtLoginOn' : 'modals.settingsWindow.launchAtLoginOff'), 'ok');
+      })
+      .catch((error: unknown) => {
+        toggle.checked = !requested;
+        setActionStatus(t('modals.settingsWindow.launchAtLoginFailed', { error: String(error) }), 'error');
+      })
+      .finally(() => {
+        toggle.disabled = false;
+      });
+  });
+}
+
+interface PowerStatus {
+  on_battery: boolean;
+  battery_percent: number | null;
+  refresh_multiplier: number;
+}
+
+async function initBatteryRefresh(): Promise<void> {
+  const select = document.getElementById('batteryRefreshSelect') as HTMLSelectElement | null;
+  const hint = document.getElementById('batteryStatusHint');
+  if (!select) return;
+
+  const status = await tryInvokeTauri<PowerStatus>('get_power_status');
+  if (hint && status?.battery_percent != null) {
+    hint.textContent = t(status.on_battery ? 'modals.settingsWindow.batteryDischarging' : 'modals.settingsWindow.batteryCharging', {
+      percent: status.battery_percent,
+    });
+  }
+
+  let current = String((await tryInvokeTauri<number>('get_battery_refresh_multiplier')) ?? 2);
+  if (!select.querySelector(`option[value="${current}"]`)) {
+    select.add(new Option(t('modals.settingsWindow.batteryRefreshCustom', { multiplier: current }), current));
+  }
+  select.value = current;
+
+  select.addEventListener('change', () => {
+    const choice = select.value;
+    void invokeTauri<number>('set_battery_refresh_multiplier', { multiplier: Number(choice) })
+      .then(() => {
+        current = choice;
+        setActionStatus(t('modals.settingsWindow.batteryRefreshSaved'), 'ok');
+      })
+      .catch((error: unknown) => {
+        select.value = current;
+        setActionStatus(t('modals.settingsWindow.batteryRefreshFailed', { error: String(error) }), 'error');
+      });
+  });
+}
+
+interface FocusModeStatus {
+  active: boolean;
+  critical_breaks_through: boolean;
+}
+
+async function initFocusMode(): Promise<void> {
+  const toggle = document.getElementById('criticalBreaksThroughToggle') as HTMLInputElement | null;
+  const hint = document.getElementById('focusModeHint');
+  if (!toggle) return;
+
+  const status = await tryInvokeTauri<FocusModeStatus>('get_focus_mode_status');
+  if (hint && status?.active) hint.textContent = t('modals.settingsWindow.focusModeActive');
+  toggle.checked = status?.critical_breaks_through ?? true;
+
+  toggle.addEventListener('change', () => {
+    const requested = toggle.checked;
+    toggle.disabled = true;
+    void invokeTauri<boolean>('set_critical_breaks_through', { enabled: requested })
+      .then((enabled) => {
+        toggle.checked = enabled;
+        setActionStatus(t('modals.settingsWindow.focusModeSaved'), 'ok');
+      })
+      .catch((error: unknown) => {
+        toggle.checked = !requested;
+        setActionStatus(t('modals.settingsWindow.focusModeFailed', { error: String(error) }), 'error');
+      })
+      .finally(() => {
+        toggle.disabled = false;
+      });
+  });
+}
+
+type AdsbProtocol = 'sbs' | 'beast';
+
+interface AdsbReceiver {
+  enabled: boolean;
+  host: string;
+  port: number | null;
+  protocol: AdsbProtocol;
+}
+
+interface AdsbStatus {
+  connected: boolean;
+  aircraft: number;
+  error: string | null;
+}
+
+const ADSB_DEFAULT_PORTS: Record<AdsbProtocol, number> = { sbs: 30003, beast: 30005 };
+
+async function initAdsbReceiver(): Promise<void> {
+  const toggle = document.getElementById('adsbEnabledToggle') as HTMLInputElement | null;
+  const host = document.getElementById('adsbHostInput') as HTMLInputElement | null;
+  const port = document.getElementById('adsbPortInput') as HTMLInputElement | null;
+  const protocol = document.getElementById('adsbProtocolSelect') as HTMLSelectElement | null;
+  const hint = document.getElementById('adsbStatusHint');
+  if (!toggle || !host || !port || !protocol) return;
+
+  const show = (receiver: AdsbReceiver): void => {
+    toggle.checked = receiver.enabled;
+    host.value = receiver.host;
+    port.value = receiver.port == null ? '' : String(receiver.port);
+    protocol.value = receiver.protocol;
+    port.placeholder = String(ADSB_DEFAULT_PORTS[receiver.protocol]);
+  };
+
+  let current = await tryInvokeTauri<AdsbReceiver>('get_adsb_receiver');
+  if (!current) return;
+  show(current);
+
+  const status = await tryInvokeTauri<AdsbStatus>('get_adsb_status');
+  if (hint && current.enabled && status) {
+    hint.textContent = status.connected
+      ? t('modals.settingsWindow.adsbConnected', { aircraft: status.aircraft })
+      : t('modals.settingsWindow.adsbDisconnected', { error: status.error ?? '' });
+  }
+
+  protocol.addEventListener('change', () => {
+    port.placeholder = String(ADSB_DEFAULT_PORTS[protocol.value as AdsbProtocol]);
+  });
+
+  const save = (): void => {
+    const receiver: AdsbReceiver = {
+      enabled: toggle.checked,
+      host: host.value.trim(),
+      port: port.value ? Number(port.value) : null,
+      protocol: protocol.value as AdsbProtocol,
+    };
+    void invokeTauri<AdsbReceiver>('set_adsb_receiver', { receiver })
+      .then((saved) => {
+        current = saved;
+        show(saved);
+        setActionStatus(t('modals.settingsWindow.adsbSaved'), 'ok');
+      })
+      .catch((error: unknown) => {
+        if (current) show(current);
+        setActionStatus(t('modals.settingsWindow.adsbFailed', { error: String(error) }), 'error');
+      });
+  };
+
+  toggle.addEventListener('change', save);
+  document.getElementById('saveAdsbBtn')?.addEventListener('click', save);
 }
 
 function getSidecarBase(): string {
@@ -0,0 +1,9 @@
+/**
+ * Entry point for the mini ticker window (Tauri desktop only, toggled from
+ * the tray menu's Ticker submenu).
+ */
+import './styles/main.css';
+import './styles/ticker.css';
+import { initTickerWindow } from '@/ticker-window';
+
+void initTickerWindow();
@@ -0,0 +1,58 @@
+/**
+ * Mini ticker strip: live alert and headline counts pushed by the desktop
+ * shell as `alerts://status`. Clicking the counts brings up the dashboard;
+ * the grip on the left drags the window, which then snaps to a corner.
+ */
+import { invokeTauri, listenTauri } from '@/services/tauri-bridge';
+
+/** Mirrors `alert_status::AlertStatus` on the Rust side. */
+interface AlertStatus {
+  alerts: number;
+  critical: number;
+  headlines: number;
+  breaking: number;
+}
+
+const STATUS_EVENT = 'alerts://status';
+
+function render(status: AlertStatus): void {
+  const set = (id: string, value: number) => {
+    const el = document.getElementById(id);
+    if (el) el.textContent = value.toLocaleString();
+  };
+  set('tickerAlerts', status.alerts);
+  set('tickerCritical', status.critical);
+  set('tickerHeadlines', status.headlines);
+  set('tickerBreaking', status.breaking);
+  document.querySelector('.ticker-shell')?.classList.toggle('ticker-hot', status.critical > 0 || status.breaking > 0);
+}
+
+export async function initTickerWindow(): Promise<void> {
+  const appEl = document.getElementById('app');
+  if (!appEl) return;
+
+  appEl.innerHTML = `
+    <div class="ticker-shell">
+      <div class="ticker-grip" data-tauri-drag-region title="Drag to move">⋮⋮</div>
+      <button type="button" class="ticker-counts" id="tickerCounts" title="Show dashboard">
+        <span class="ticker-stat ticker-stat-critical"><strong id="tickerCritical">0</strong> critical</span>
+        <span class="ticker-stat"><strong id="tickerAlerts">0</strong> alerts</span>
+        <span class="ticker-stat ticker-stat-breaking"><strong id="tickerBreaking">0</strong> breaking</span>
+        <span class="ticker-stat"><strong id="tickerHeadlines">0</strong> headlines</span>
+      </button>
+    </div>
+  `;
+
+  document.getElementById('tickerCounts')?.addEventListener('click', () => {
+    void invokeTauri<void>('activate_ticker').catch((error) => {
+      console.warn('[ticker] could not show dashboard', error);
+    });
+  });
+
+  try {
+    await listenTauri<AlertStatus>(STATUS_EVENT, render);
+    render(await invokeTauri<AlertStatus>('get_alert_status'));
+  } catch (error) {
+    console.warn('[ticker] could not load alert status', error);
+  }
+}
@@ -904,6 +904,7 @@ export type NaturalEventCategory =
   | 'severeStorms'
   | 'wildfires'
   | 'volcanoes'
+  | 'tsunamis'
   | 'earthquakes'
   | 'floods'
   | 'landslides'
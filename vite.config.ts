@@ -844,6 +844,9 @@ export default defineConfig({
         about: resolve(__dirname, 'src/about.html'),
         settings: resolve(__dirname, 'settings.html'),
         liveChannels: resolve(__dirname, 'live-channels.html'),
+        logViewer: resolve(__dirname, 'log-viewer.html'),
+        ticker: resolve(__dirname, 'ticker.html'),
+        panel: resolve(__dirname, 'panel.html'),
       },
       output: {
         manualChunks(id) {